@@ -0,0 +1,99 @@
+//! Helpers for caching the responses of the HTTP requests made while
+//! authenticating, such as to JWK URLs or auth webhooks.
+
+use std::time::Duration;
+
+use http::header::{CACHE_CONTROL, EXPIRES};
+use http::HeaderMap;
+
+/// How long a response can be cached for, as per its `Cache-Control` and
+/// `Expires` headers. `Cache-Control` takes precedence over `Expires`. Returns
+/// `None` when the response has neither.
+pub fn time_to_live(headers: &HeaderMap) -> Option<Duration> {
+    if let Some(cache_control) = headers
+        .get(CACHE_CONTROL)
+        .and_then(|value| value.to_str().ok())
+    {
+        let mut max_age = None;
+        for directive in cache_control.split(',') {
+            let directive = directive.trim();
+            if directive.eq_ignore_ascii_case("no-cache")
+                || directive.eq_ignore_ascii_case("no-store")
+            {
+                return Some(Duration::ZERO);
+            }
+            if let Some((name, value)) = directive.split_once('=') {
+                if name.trim().eq_ignore_ascii_case("max-age") {
+                    max_age = value.trim().trim_matches('"').parse().ok();
+                }
+            }
+        }
+        if let Some(max_age) = max_age {
+            return Some(Duration::from_secs(max_age));
+        }
+    }
+    headers.get(EXPIRES).map(|expires| {
+        // An invalid `Expires` value means that the response is already expired
+        expires
+            .to_str()
+            .ok()
+            .and_then(|expires| chrono::DateTime::parse_from_rfc2822(expires).ok())
+            .and_then(|expires| {
+                (expires.with_timezone(&chrono::Utc) - chrono::Utc::now())
+                    .to_std()
+                    .ok()
+            })
+            .unwrap_or(Duration::ZERO)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use http::header::{HeaderName, HeaderValue, CACHE_CONTROL, EXPIRES};
+    use http::HeaderMap;
+    use std::time::Duration;
+
+    use super::time_to_live;
+
+    fn headers(entries: &[(HeaderName, &str)]) -> HeaderMap {
+        entries
+            .iter()
+            .map(|(name, value)| (name.clone(), HeaderValue::from_str(value).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn test_time_to_live() {
+        assert_eq!(time_to_live(&HeaderMap::new()), None);
+        assert_eq!(time_to_live(&headers(&[(CACHE_CONTROL, "public")])), None);
+        assert_eq!(
+            time_to_live(&headers(&[(CACHE_CONTROL, "public, max-age=3600")])),
+            Some(Duration::from_secs(3600))
+        );
+        assert_eq!(
+            time_to_live(&headers(&[(CACHE_CONTROL, "no-store, max-age=3600")])),
+            Some(Duration::ZERO)
+        );
+        // `Cache-Control` takes precedence over `Expires`
+        assert_eq!(
+            time_to_live(&headers(&[
+                (CACHE_CONTROL, "max-age=60"),
+                (EXPIRES, "Wed, 21 Oct 2015 07:28:00 GMT")
+            ])),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(
+            time_to_live(&headers(&[(EXPIRES, "Wed, 21 Oct 2015 07:28:00 GMT")])),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(
+            time_to_live(&headers(&[(EXPIRES, "0")])),
+            Some(Duration::ZERO)
+        );
+        let expires = (chrono::Utc::now() + chrono::Duration::hours(1)).to_rfc2822();
+        let time_to_live = time_to_live(&headers(&[(EXPIRES, &expires)])).unwrap();
+        assert!(
+            time_to_live > Duration::from_secs(3500) && time_to_live <= Duration::from_secs(3600)
+        );
+    }
+}
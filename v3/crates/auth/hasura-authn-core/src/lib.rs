@@ -14,6 +14,8 @@ use std::{
     str::FromStr,
 };
 
+pub mod cache_control;
+
 /// The architecture is as follows:
 /// 1. Every authn mechanism returns an 'Identity'.
 ///    An 'Identity' lists the allowed roles and a default role
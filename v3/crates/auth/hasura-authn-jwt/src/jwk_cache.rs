@@ -0,0 +1,272 @@
+//! Process-wide cache of the JWK sets fetched from `jwkFromUrl`.
+//!
+//! JWK sets are cached per URL for as long as the `Cache-Control` (`max-age`)
+//! or `Expires` headers of the response allow, or `DEFAULT_TIME_TO_LIVE` when
+//! the response has neither. Once expired, the cached set keeps being served
+//! while it is refreshed in the background, so that an unavailable identity
+//! provider does not fail requests whose keys are already known. Refresh
+//! failures are recorded as tracing events on the refresh span.
+//!
+//! A token whose `kid` is not in the cached set triggers an immediate refetch,
+//! which picks up rotated keys. Fetches of a URL, successful or not, are at
+//! most once every `MIN_REFETCH_INTERVAL`, so that neither arbitrary tokens
+//! nor an unavailable identity provider can make every request fetch again.
+//! Concurrent requests needing to fetch the same URL wait for a single fetch.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, PoisonError};
+use std::time::{Duration, Instant};
+
+use hasura_authn_core::cache_control;
+use jsonwebtoken::jwk::{Jwk, JwkSet};
+use tracing_util::SpanVisibility;
+use url::Url;
+
+use crate::jwt::{Error, InternalError};
+
+/// How long a JWK set is cached when the response has no caching headers.
+const DEFAULT_TIME_TO_LIVE: Duration = Duration::from_secs(5 * 60);
+
+/// Minimum time between two fetches of the same URL, whether triggered by an
+/// unknown `kid`, an expired set or a failed fetch.
+const MIN_REFETCH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Timeout of the request fetching a JWK set.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(60);
+
+struct CachedJwkSet {
+    jwk_set: Arc<JwkSet>,
+    expires_at: Instant,
+}
+
+#[derive(Default)]
+struct CacheEntry {
+    /// The latest JWK set fetched, unless no fetch has succeeded yet
+    cached: Option<CachedJwkSet>,
+    /// When the URL was last fetched, whether successfully or not
+    last_fetch_attempt: Option<Instant>,
+    /// Whether a background refresh is in progress
+    refreshing: bool,
+    /// Held for the duration of a fetch of the URL
+    fetch_lock: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl CacheEntry {
+    fn fetched_since(&self, instant: Instant) -> bool {
+        self.last_fetch_attempt
+            .is_some_and(|last_fetch_attempt| last_fetch_attempt >= instant)
+    }
+
+    fn find_jwk(&self, kid: &str) -> Result<Jwk, Error> {
+        match &self.cached {
+            Some(cached) => cached.jwk_set.find(kid).cloned().ok_or_else(|| {
+                InternalError::NoMatchingJWKFound {
+                    kid: kid.to_string(),
+                }
+                .into()
+            }),
+            None => Err(InternalError::JWKSetUnavailable.into()),
+        }
+    }
+}
+
+fn lock_cache() -> MutexGuard<'static, HashMap<Url, CacheEntry>> {
+    static CACHE: OnceLock<Mutex<HashMap<Url, CacheEntry>>> = OnceLock::new();
+    CACHE
+        .get_or_init(Mutex::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Returns the JWK with the given `kid` from the JWK set at `jwk_url`, using
+/// the cached set when possible.
+pub(crate) async fn get_jwk(
+    http_client: &reqwest::Client,
+    jwk_url: &Url,
+    kid: &str,
+) -> Result<Jwk, Error> {
+    let started = Instant::now();
+    let refetchable_since = started.checked_sub(MIN_REFETCH_INTERVAL).unwrap_or(started);
+    let fetch_lock = {
+        let mut cache = lock_cache();
+        let entry = cache.entry(jwk_url.clone()).or_default();
+        if let Some(cached) = &entry.cached {
+            if started >= cached.expires_at && !entry.refreshing {
+                entry.refreshing = true;
+                spawn_refresh(http_client.clone(), jwk_url.clone(), started);
+            }
+            if let Some(jwk) = cached.jwk_set.find(kid) {
+                return Ok(jwk.clone());
+            }
+        }
+        // A fetch in progress is waited for rather than rate limited
+        let fetching = entry.fetch_lock.try_lock().is_err();
+        if !fetching && entry.fetched_since(refetchable_since) {
+            return entry.find_jwk(kid);
+        }
+        entry.fetch_lock.clone()
+    };
+    // Either the URL has never been fetched, or the key is unknown and may
+    // have been rotated in since the last fetch. Concurrent requests wait for
+    // the fetch in progress, and use its result instead of fetching again.
+    let _fetching = fetch_lock.lock().await;
+    {
+        let mut cache = lock_cache();
+        let entry = cache.entry(jwk_url.clone()).or_default();
+        if entry.fetched_since(refetchable_since) {
+            return entry.find_jwk(kid);
+        }
+    }
+    fetch_and_cache(http_client, jwk_url).await?;
+    lock_cache()
+        .entry(jwk_url.clone())
+        .or_default()
+        .find_jwk(kid)
+}
+
+/// Refreshes an expired JWK set without blocking the request that noticed it.
+fn spawn_refresh(http_client: reqwest::Client, jwk_url: Url, expired_at: Instant) {
+    let span_link = tracing_util::SpanLink::from_current_span();
+    tokio::spawn(async move {
+        let fetch_lock = lock_cache()
+            .entry(jwk_url.clone())
+            .or_default()
+            .fetch_lock
+            .clone();
+        let _fetching = fetch_lock.lock().await;
+        {
+            let mut cache = lock_cache();
+            let entry = cache.entry(jwk_url.clone()).or_default();
+            // The set has been fetched since it expired
+            if entry.fetched_since(expired_at) {
+                entry.refreshing = false;
+                return;
+            }
+        }
+        let tracer = tracing_util::global_tracer();
+        let _: Result<(), Error> = tracer
+            .new_trace_async_with_link(
+                "refresh_jwk",
+                "Refresh JWK",
+                SpanVisibility::Internal,
+                span_link,
+                || {
+                    Box::pin(async {
+                        fetch_and_cache(&http_client, &jwk_url)
+                            .await
+                            .inspect_err(|error| {
+                                tracing_util::add_event_on_active_span(format!(
+                                    "Failed to refresh the JWK set from {jwk_url}, the cached JWK set is still in use: {error}"
+                                ));
+                            })
+                    })
+                },
+            )
+            .await;
+    });
+}
+
+/// Fetches the JWK set and updates the cache with it. On failure, a cached set
+/// is kept in use. Either way, the URL is not fetched again before
+/// `MIN_REFETCH_INTERVAL`. Must be called while holding the entry's fetch lock.
+async fn fetch_and_cache(http_client: &reqwest::Client, jwk_url: &Url) -> Result<(), Error> {
+    lock_cache()
+        .entry(jwk_url.clone())
+        .or_default()
+        .last_fetch_attempt = Some(Instant::now());
+    let result = fetch_jwk_set(http_client, jwk_url).await;
+    let now = Instant::now();
+    let mut cache = lock_cache();
+    let entry = cache.entry(jwk_url.clone()).or_default();
+    entry.refreshing = false;
+    match result {
+        Ok((jwk_set, time_to_live)) => {
+            entry.cached = Some(CachedJwkSet {
+                jwk_set: Arc::new(jwk_set),
+                expires_at: now + time_to_live.max(MIN_REFETCH_INTERVAL),
+            });
+            Ok(())
+        }
+        Err(error) => {
+            if let Some(cached) = &mut entry.cached {
+                cached.expires_at = cached.expires_at.max(now + MIN_REFETCH_INTERVAL);
+            }
+            Err(error)
+        }
+    }
+}
+
+async fn fetch_jwk_set(
+    http_client: &reqwest::Client,
+    jwk_url: &Url,
+) -> Result<(JwkSet, Duration), Error> {
+    let tracer = tracing_util::global_tracer();
+    tracer
+        .in_span_async("fetch_jwk", "Fetch JWK", SpanVisibility::Internal, || {
+            Box::pin(async {
+                let jwk_request = http_client
+                    .get(jwk_url.clone())
+                    .headers(tracing_util::get_trace_headers())
+                    .timeout(FETCH_TIMEOUT)
+                    .build()
+                    .map_err(InternalError::ReqwestError)?;
+
+                let jwk_response = http_client
+                    .execute(jwk_request)
+                    .await
+                    .map_err(InternalError::ErrorFetchingJWKSet)?;
+                if jwk_response.status().is_success() {
+                    let time_to_live = cache_control::time_to_live(jwk_response.headers())
+                        .unwrap_or(DEFAULT_TIME_TO_LIVE);
+                    let jwk_set: JwkSet = jwk_response
+                        .json()
+                        .await
+                        .map_err(InternalError::ReqwestError)?;
+                    Ok((jwk_set, time_to_live))
+                } else {
+                    Err(InternalError::UnsuccessfulJWKFetch(jwk_response.status()))?
+                }
+            })
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::get_jwk;
+    use crate::jwt::{Error, InternalError};
+
+    #[tokio::test]
+    async fn test_failed_fetches_are_rate_limited_and_single_flight() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/jwk")
+            .with_status(503)
+            .expect(1)
+            .create_async()
+            .await;
+        let jwk_url = url::Url::parse(&format!("{}/jwk", server.url())).unwrap();
+        let http_client = reqwest::Client::new();
+
+        // Concurrent requests wait for a single fetch
+        let (first, second) = tokio::join!(
+            get_jwk(&http_client, &jwk_url, "kid"),
+            get_jwk(&http_client, &jwk_url, "kid")
+        );
+        assert!(matches!(
+            first,
+            Err(Error::Internal(InternalError::UnsuccessfulJWKFetch(_)))
+        ));
+        assert!(matches!(
+            second,
+            Err(Error::Internal(InternalError::JWKSetUnavailable))
+        ));
+
+        // The failed fetch is not retried right away
+        assert!(matches!(
+            get_jwk(&http_client, &jwk_url, "kid").await,
+            Err(Error::Internal(InternalError::JWKSetUnavailable))
+        ));
+        mock.assert_async().await;
+    }
+}
@@ -1,5 +1,4 @@
 use std::collections::HashMap;
-use std::time::Duration;
 
 use axum::http::{HeaderMap, HeaderValue};
 use cookie::{self, Cookie};
@@ -17,9 +16,11 @@ use schemars::JsonSchema;
 use serde::{de::Error as SerdeDeError, Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::{json, Value};
 use std::collections::HashSet;
-use tracing_util::{ErrorVisibility, SpanVisibility, TraceableError};
+use tracing_util::{ErrorVisibility, TraceableError};
 use url::Url;
 
+use crate::jwk_cache;
+
 /// Name of the key, which is by default used to lookup the Hasura claims
 /// in the claims obtained after decoding the JWT.
 pub(crate) const DEFAULT_HASURA_CLAIMS_NAMESPACE: &str = "claims.jwt.hasura.io";
@@ -88,6 +89,8 @@ pub enum InternalError {
     ErrorFetchingJWKSet(reqwest::Error),
     #[error("No matching JWK found for the given kid: {kid}")]
     NoMatchingJWKFound { kid: String },
+    #[error("The JWKSet is unavailable, as it could not be fetched recently")]
+    JWKSetUnavailable,
     #[error("Received unsuccessful response {0} status while fetching JWK ")]
     UnsuccessfulJWKFetch(StatusCode),
     #[error("Algorithm not found in the JWK")]
@@ -462,42 +465,14 @@ async fn get_decoding_key_from_jwk_url(
     jwk_url: &Url,
     jwt_authorization_header: &str,
 ) -> Result<(Vec<jwt::Algorithm>, jwt::DecodingKey), Error> {
-    let tracer = tracing_util::global_tracer();
-    tracer
-        .in_span_async("fetch_jwk", "Fetch JWK", SpanVisibility::Internal, || {
-            Box::pin(async {
-                let jwk_request = http_client
-                    .get(jwk_url.clone())
-                    .headers(tracing_util::get_trace_headers())
-                    .timeout(Duration::from_secs(60))
-                    .build()
-                    .map_err(InternalError::ReqwestError)?;
-
-                let jwk_response = http_client
-                    .execute(jwk_request)
-                    .await
-                    .map_err(InternalError::ErrorFetchingJWKSet)?;
-                if jwk_response.status().is_success() {
-                    let jwk_set: jwt::jwk::JwkSet = jwk_response
-                        .json()
-                        .await
-                        .map_err(InternalError::ReqwestError)?;
-                    let decoded_header = decode_header(jwt_authorization_header)
-                        .map_err(Error::ErrorDecodingAuthorizationHeader)?;
-                    let kid = decoded_header.kid.ok_or(Error::KidHeaderNotFound)?;
-                    let jwk = jwk_set
-                        .find(kid.as_str())
-                        .ok_or(InternalError::NoMatchingJWKFound { kid })?;
-                    let decoding_key = jwt::DecodingKey::from_jwk(jwk)
-                        .map_err(InternalError::JWTDecodingKeyError)?;
-                    let acceptable_algorithms = get_acceptable_algorithms_for_key(jwk);
-                    Ok((acceptable_algorithms, decoding_key))
-                } else {
-                    Err(InternalError::UnsuccessfulJWKFetch(jwk_response.status()))?
-                }
-            })
-        })
-        .await
+    let decoded_header =
+        decode_header(jwt_authorization_header).map_err(Error::ErrorDecodingAuthorizationHeader)?;
+    let kid = decoded_header.kid.ok_or(Error::KidHeaderNotFound)?;
+    let jwk = jwk_cache::get_jwk(http_client, jwk_url, &kid).await?;
+    let decoding_key =
+        jwt::DecodingKey::from_jwk(&jwk).map_err(InternalError::JWTDecodingKeyError)?;
+    let acceptable_algorithms = get_acceptable_algorithms_for_key(&jwk);
+    Ok((acceptable_algorithms, decoding_key))
 }
 
 fn get_acceptable_algorithms_for_key(jwk: &jwt::jwk::Jwk) -> Vec<jwt::Algorithm> {
@@ -1360,6 +1335,25 @@ mod tests {
 
         assert_eq!(hasura_claims, decoded_hasura_claims);
 
+        // The JWK set is cached, so decoding another token signed with a known key
+        // does not fetch it again
+        let authorization_token_1 = jwt::encode(
+            &jwt_header,
+            &claims,
+            &EncodingKey::from_ec_pem(test_jwk_1.key.to_pem().as_bytes())?,
+        )?;
+        let decoded_hasura_claims = decode_and_parse_hasura_claims(
+            &http_client,
+            &jwt_config,
+            authorization_token_1,
+            AudienceValidationMode::Required,
+        )
+        .await?;
+
+        mock.assert();
+
+        assert_eq!(hasura_claims, decoded_hasura_claims);
+
         let mut test_jwk_3 = jwk::JsonWebKey::new(jwk::Key::generate_p256());
         test_jwk_3.set_algorithm(ES256)?;
         test_jwk_3.key_id = Some("random_kid_3".to_string());
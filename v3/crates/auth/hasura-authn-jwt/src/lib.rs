@@ -1,2 +1,3 @@
 pub mod auth;
+mod jwk_cache;
 pub mod jwt;
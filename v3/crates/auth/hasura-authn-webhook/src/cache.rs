@@ -0,0 +1,148 @@
+//! Process-wide cache of the identities returned by auth hooks.
+//!
+//! A response is cached under the project, the URL and method of the auth hook
+//! and the headers and body it is sent that authenticate the request, so a
+//! cached identity is only reused for a request carrying the same credentials.
+//! Unless the cache config lists these headers, every header sent to the auth
+//! hook is part of the key, except for the ones identifying the request or its
+//! trace, which differ between every request of a client.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard, OnceLock, PoisonError};
+use std::time::{Duration, Instant};
+
+use engine_types::ProjectId;
+use hasura_authn_core::{Identity, Role};
+use reqwest::Url;
+
+use crate::webhook::AuthHookRequest;
+
+/// Maximum number of cached identities. When the cache is full, expired
+/// entries are evicted, and new identities are not cached if it still is.
+const MAX_ENTRIES: usize = 10_000;
+
+/// Headers which identify a request or its trace rather than authenticate it.
+const NON_AUTHENTICATION_HEADERS: [&str; 13] = [
+    "b3",
+    "baggage",
+    "traceparent",
+    "tracestate",
+    "x-amzn-trace-id",
+    "x-b3-flags",
+    "x-b3-parentspanid",
+    "x-b3-sampled",
+    "x-b3-spanid",
+    "x-b3-traceid",
+    "x-cloud-trace-context",
+    "x-correlation-id",
+    "x-request-id",
+];
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub(crate) struct CacheKey {
+    project_id: Option<String>,
+    url: Url,
+    method: reqwest::Method,
+    headers: Vec<(String, Vec<u8>)>,
+    body_headers: Vec<(String, String)>,
+    allow_role_emulation_for: Option<Role>,
+}
+
+impl CacheKey {
+    /// The key of the request to the auth hook, made of the given `key_headers`
+    /// only, if any.
+    pub(crate) fn new(
+        project_id: Option<&ProjectId>,
+        url: &Url,
+        request: &AuthHookRequest,
+        key_headers: Option<&[String]>,
+        allow_role_emulation_for: Option<&Role>,
+    ) -> Self {
+        let (method, headers, mut body_headers) = match request {
+            AuthHookRequest::Get { headers } => (reqwest::Method::GET, headers, Vec::new()),
+            AuthHookRequest::Post { headers, body } => (
+                reqwest::Method::POST,
+                headers,
+                body.headers
+                    .iter()
+                    .filter(|(name, _)| is_key_header(name, key_headers))
+                    .map(|(name, value)| (name.to_lowercase(), value.clone()))
+                    .collect(),
+            ),
+        };
+        let mut headers: Vec<_> = headers
+            .iter()
+            .filter(|(name, _)| is_key_header(name.as_str(), key_headers))
+            .map(|(name, value)| (name.as_str().to_owned(), value.as_bytes().to_vec()))
+            .collect();
+        headers.sort();
+        body_headers.sort();
+        CacheKey {
+            project_id: project_id.map(|project_id| project_id.0.clone()),
+            url: url.clone(),
+            method,
+            headers,
+            body_headers,
+            allow_role_emulation_for: allow_role_emulation_for.cloned(),
+        }
+    }
+}
+
+fn is_key_header(name: &str, key_headers: Option<&[String]>) -> bool {
+    match key_headers {
+        Some(key_headers) => key_headers
+            .iter()
+            .any(|key_header| key_header.eq_ignore_ascii_case(name)),
+        None => !NON_AUTHENTICATION_HEADERS
+            .iter()
+            .any(|header| header.eq_ignore_ascii_case(name)),
+    }
+}
+
+struct CachedIdentity {
+    identity: Identity,
+    expires_at: Instant,
+}
+
+fn lock_cache() -> MutexGuard<'static, HashMap<CacheKey, CachedIdentity>> {
+    static CACHE: OnceLock<Mutex<HashMap<CacheKey, CachedIdentity>>> = OnceLock::new();
+    CACHE
+        .get_or_init(Mutex::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Returns the cached identity for the request, if it has not expired.
+pub(crate) fn get(key: &CacheKey) -> Option<Identity> {
+    let mut cache = lock_cache();
+    match cache.get(key) {
+        Some(cached) if cached.expires_at > Instant::now() => Some(cached.identity.clone()),
+        Some(_) => {
+            cache.remove(key);
+            None
+        }
+        None => None,
+    }
+}
+
+/// Caches the identity for the request for the given duration.
+pub(crate) fn insert(key: CacheKey, identity: Identity, time_to_live: Duration) {
+    if time_to_live.is_zero() {
+        return;
+    }
+    let now = Instant::now();
+    let mut cache = lock_cache();
+    if cache.len() >= MAX_ENTRIES && !cache.contains_key(&key) {
+        cache.retain(|_, cached| cached.expires_at > now);
+        if cache.len() >= MAX_ENTRIES {
+            return;
+        }
+    }
+    cache.insert(
+        key,
+        CachedIdentity {
+            identity,
+            expires_at: now + time_to_live,
+        },
+    );
+}
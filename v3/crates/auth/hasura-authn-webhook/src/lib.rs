@@ -1 +1,2 @@
+mod cache;
 pub mod webhook;
@@ -5,6 +5,7 @@ use std::time::Duration;
 
 use auth_base::{Identity, Role, RoleAuthorization, SessionVariableName, SessionVariableValue};
 use axum::http::{HeaderMap, HeaderName, StatusCode};
+use engine_types::ProjectId;
 use reqwest::{header::ToStrError, Url};
 use serde::{de::Error as SerdeDeError, Deserialize, Deserializer, Serialize, Serializer};
 
@@ -12,7 +13,9 @@ use hasura_authn_core as auth_base;
 use open_dds::{session_variables, EnvironmentValue};
 use schemars::JsonSchema;
 use serde_json::Value;
-use tracing_util::{ErrorVisibility, SpanVisibility, TraceableError};
+use tracing_util::{AttributeVisibility, ErrorVisibility, SpanVisibility, TraceableError};
+
+use crate::cache;
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -134,8 +137,8 @@ impl AuthHookConfig {
 
 /// The body of the POST request to be made to the Auth hook.
 #[derive(Serialize, Debug)]
-struct AuthHookPostRequestBody {
-    headers: HashMap<String, String>,
+pub(crate) struct AuthHookPostRequestBody {
+    pub(crate) headers: HashMap<String, String>,
 }
 
 impl AuthHookPostRequestBody {
@@ -189,6 +192,12 @@ impl AuthHookConfigV3 {
             AuthHookConfigV3::POST(config) => &config.url.value,
         }
     }
+    pub fn get_cache_config(&self) -> Option<&AuthHookConfigV3Cache> {
+        match self {
+            AuthHookConfigV3::GET(config) => config.cache.as_ref(),
+            AuthHookConfigV3::POST(config) => config.cache.as_ref(),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, PartialEq)]
@@ -203,6 +212,9 @@ pub struct AuthHookConfigV3GET {
     #[serde(default)]
     /// The configuration for the headers to be sent to the GET auth hook.
     pub custom_headers_config: Option<AuthHookConfigV3GETHeaders>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The configuration for caching the responses of the GET auth hook.
+    pub cache: Option<AuthHookConfigV3Cache>,
 }
 
 impl AuthHookConfigV3GET {
@@ -269,6 +281,9 @@ pub struct AuthHookConfigV3POST {
     #[serde(default)]
     /// The configuration for the headers to be sent to the POST auth hook.
     pub custom_headers_config: Option<AuthHookConfigV3POSTHeaders>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The configuration for caching the responses of the POST auth hook.
+    pub cache: Option<AuthHookConfigV3Cache>,
 }
 
 impl AuthHookConfigV3POST {
@@ -367,6 +382,41 @@ impl AuthHookConfigV3Headers {
     }
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+#[schemars(title = "AuthHookConfigV3Cache")]
+#[schemars(example = "AuthHookConfigV3Cache::example")]
+/// The configuration for caching the responses of the auth hook. A response is
+/// cached for the duration given by its `Cache-Control: max-age` or `Expires`
+/// header, bounded by `maxTtlSeconds`, and reused for requests forwarding the
+/// same authenticating headers to the auth hook. Responses with neither header
+/// are not cached.
+pub struct AuthHookConfigV3Cache {
+    /// The maximum number of seconds for which a response of the auth hook is cached.
+    pub max_ttl_seconds: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The headers sent to the auth hook which authenticate a request, such as
+    /// `Authorization` or `Cookie`. A cached response is reused for requests
+    /// with the same values of these headers. If omitted, all the headers sent
+    /// to the auth hook are used, except for the ones identifying the request
+    /// or its trace, such as `X-Request-Id` or `traceparent`.
+    pub key_headers: Option<Vec<String>>,
+}
+
+impl AuthHookConfigV3Cache {
+    fn example() -> Self {
+        serde_json::from_str(
+            r#"
+            {
+                "maxTtlSeconds": 300
+            }
+        "#,
+        )
+        .unwrap()
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, PartialEq)]
 #[serde(untagged)]
 #[schemars(title = "AllOrList")]
@@ -474,6 +524,68 @@ async fn make_auth_hook_request(
     request: AuthHookRequest,
     allow_role_emulation_for: Option<&Role>,
 ) -> Result<auth_base::Identity, Error> {
+    send_auth_hook_request(
+        http_client,
+        auth_hook_url,
+        request,
+        allow_role_emulation_for,
+    )
+    .await
+    .map(|(identity, _time_to_live)| identity)
+}
+
+/// Like `make_auth_hook_request`, but reuses the identity returned by a
+/// previous identical request to the auth hook, for as long as the response
+/// allowed and the cache config permits.
+async fn make_cached_auth_hook_request(
+    http_client: &reqwest::Client,
+    auth_hook_url: &Url,
+    request: AuthHookRequest,
+    allow_role_emulation_for: Option<&Role>,
+    cache_config: &AuthHookConfigV3Cache,
+    project_id: Option<&ProjectId>,
+) -> Result<auth_base::Identity, Error> {
+    let cache_key = cache::CacheKey::new(
+        project_id,
+        auth_hook_url,
+        &request,
+        cache_config.key_headers.as_deref(),
+        allow_role_emulation_for,
+    );
+    let cached_identity = cache::get(&cache_key);
+    tracing_util::set_attribute_on_active_span(
+        AttributeVisibility::Default,
+        "auth.webhook.cache_hit",
+        cached_identity.is_some(),
+    );
+    if let Some(identity) = cached_identity {
+        return Ok(identity);
+    }
+    let (identity, time_to_live) = send_auth_hook_request(
+        http_client,
+        auth_hook_url,
+        request,
+        allow_role_emulation_for,
+    )
+    .await?;
+    if let Some(time_to_live) = time_to_live {
+        cache::insert(
+            cache_key,
+            identity.clone(),
+            time_to_live.min(Duration::from_secs(cache_config.max_ttl_seconds)),
+        );
+    }
+    Ok(identity)
+}
+
+/// Makes the HTTP request to the auth hook, returning the identity along with
+/// how long the response may be cached for, as per its caching headers.
+async fn send_auth_hook_request(
+    http_client: &reqwest::Client,
+    auth_hook_url: &Url,
+    request: AuthHookRequest,
+    allow_role_emulation_for: Option<&Role>,
+) -> Result<(auth_base::Identity, Option<Duration>), Error> {
     let tracer = tracing_util::global_tracer();
     let http_request_builder = match request {
         AuthHookRequest::Get { headers } => {
@@ -521,6 +633,7 @@ async fn make_auth_hook_request(
     match response.status() {
         reqwest::StatusCode::UNAUTHORIZED => Err(Error::AuthenticationFailed),
         reqwest::StatusCode::OK => {
+            let time_to_live = auth_base::cache_control::time_to_live(response.headers());
             let auth_hook_response: HashMap<String, serde_json::Value> =
                 response.json().await.map_err(InternalError::ReqwestError)?;
             let mut session_variables = HashMap::new();
@@ -550,7 +663,7 @@ async fn make_auth_hook_request(
             let mut allowed_roles = HashMap::new();
             allowed_roles.insert(role.clone(), role_authorization);
 
-            Ok(match allow_role_emulation_for {
+            let identity = match allow_role_emulation_for {
                 Some(emulation_role) => {
                     if role == *emulation_role {
                         Identity::RoleEmulationEnabled(role)
@@ -565,7 +678,8 @@ async fn make_auth_hook_request(
                     default_role: role,
                     allowed_roles,
                 },
-            })
+            };
+            Ok((identity, time_to_live))
         }
         status_code => Err(InternalError::AuthHookUnexpectedStatus(status_code))?,
     }
@@ -612,6 +726,7 @@ pub async fn authenticate_request_v2(
     auth_hook_config: &AuthHookConfigV3,
     client_headers: &HeaderMap,
     allow_role_emulation_for: Option<&Role>,
+    project_id: Option<&ProjectId>,
 ) -> Result<auth_base::Identity, Error> {
     let tracer = tracing_util::global_tracer();
     tracer
@@ -624,15 +739,28 @@ pub async fn authenticate_request_v2(
                     let request = get_auth_hook_request_v2(auth_hook_config, client_headers);
                     let url_str = auth_hook_config.get_url();
                     match Url::from_str(url_str) {
-                        Ok(url) => {
-                            make_auth_hook_request(
-                                http_client,
-                                &url,
-                                request,
-                                allow_role_emulation_for,
-                            )
-                            .await
-                        }
+                        Ok(url) => match auth_hook_config.get_cache_config() {
+                            Some(cache_config) => {
+                                make_cached_auth_hook_request(
+                                    http_client,
+                                    &url,
+                                    request,
+                                    allow_role_emulation_for,
+                                    cache_config,
+                                    project_id,
+                                )
+                                .await
+                            }
+                            None => {
+                                make_auth_hook_request(
+                                    http_client,
+                                    &url,
+                                    request,
+                                    allow_role_emulation_for,
+                                )
+                                .await
+                            }
+                        },
                         // This should never happen as the URL is validated beforehand.
                         Err(_) => Err(Error::Internal(InternalError::InvalidUrl(
                             url_str.to_string(),
@@ -679,7 +807,7 @@ fn ignore_header(header: &str) -> bool {
         .contains(header)
 }
 
-enum AuthHookRequest {
+pub(crate) enum AuthHookRequest {
     Get {
         headers: HeaderMap,
     },
@@ -1286,6 +1414,144 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    // This test emulates the webhook responses being cached as per their `Cache-Control` header
+    async fn test_webhook_response_caching() {
+        // Request a new server from the pool
+        let mut server = mockito::Server::new_async().await;
+
+        let url = server.url();
+
+        // Create mocks, the cached response must only be requested once
+        let cached_mock = server
+            .mock("POST", "/validate-request")
+            .match_body(r#"{"headers":{"authorization":"Bearer cached"}}"#)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_header("cache-control", "max-age=300")
+            .with_body(r#"{"x-hasura-role": "test-role"}"#)
+            .expect(1)
+            .create();
+        let uncached_mock = server
+            .mock("POST", "/validate-request")
+            .match_body(r#"{"headers":{"authorization":"Bearer uncached"}}"#)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"x-hasura-role": "test-role"}"#)
+            .expect(2)
+            .create();
+
+        let http_client = reqwest::Client::new();
+
+        let auth_hook_config: AuthHookConfigV3 = serde_json::from_value(json!({
+            "method": "POST",
+            "url": {
+                "value": url + "/validate-request"
+            },
+            "customHeadersConfig": {
+                "body": {
+                    "headers": {
+                        "forward": ["Authorization"]
+                    }
+                }
+            },
+            "cache": {
+                "maxTtlSeconds": 60
+            }
+        }))
+        .unwrap();
+
+        for token in ["Bearer cached", "Bearer uncached"] {
+            let mut client_headers = HeaderMap::new();
+            client_headers.insert("authorization", token.parse().unwrap());
+            for _ in 0..2 {
+                let auth_response = authenticate_request_v2(
+                    &http_client,
+                    &auth_hook_config,
+                    &client_headers,
+                    None,
+                    None,
+                )
+                .await
+                .unwrap();
+                assert_eq!(
+                    auth_response.get_role_authorization(None).unwrap().role,
+                    Role::new("test-role")
+                );
+            }
+        }
+
+        cached_mock.assert();
+        uncached_mock.assert();
+    }
+
+    #[tokio::test]
+    // This test emulates cached webhook responses being reused for requests which only differ by
+    // their request id and trace headers, but not across projects
+    async fn test_webhook_response_caching_key() {
+        let mut server = mockito::Server::new_async().await;
+
+        let url = server.url();
+
+        let mock = server
+            .mock("GET", "/validate-request")
+            .match_header("authorization", "Bearer token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_header("cache-control", "max-age=300")
+            .with_body(r#"{"x-hasura-role": "test-role"}"#)
+            .expect(2)
+            .create();
+
+        let http_client = reqwest::Client::new();
+
+        let auth_hook_config: AuthHookConfigV3 = serde_json::from_value(json!({
+            "method": "GET",
+            "url": {
+                "value": url + "/validate-request"
+            },
+            "customHeadersConfig": {
+                "headers": {
+                    "forward": "*"
+                }
+            },
+            "cache": {
+                "maxTtlSeconds": 60
+            }
+        }))
+        .unwrap();
+
+        for project_id in ["project_1", "project_2"] {
+            let project_id = ProjectId(project_id.to_string());
+            for request_id in ["request_1", "request_2"] {
+                let mut client_headers = HeaderMap::new();
+                client_headers.insert("authorization", "Bearer token".parse().unwrap());
+                client_headers.insert("x-request-id", request_id.parse().unwrap());
+                client_headers.insert(
+                    "traceparent",
+                    format!("00-{request_id}-00f067aa0ba902b7-01")
+                        .parse()
+                        .unwrap(),
+                );
+                let auth_response = authenticate_request_v2(
+                    &http_client,
+                    &auth_hook_config,
+                    &client_headers,
+                    None,
+                    Some(&project_id),
+                )
+                .await
+                .unwrap();
+                assert_eq!(
+                    auth_response.get_role_authorization(None).unwrap().role,
+                    Role::new("test-role")
+                );
+            }
+        }
+
+        mock.assert();
+    }
+
     #[test]
     fn test_all_or_list_serialization_all() {
         let all = AllOrList::<String>::All(All(()));
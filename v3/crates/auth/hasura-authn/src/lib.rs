@@ -1,6 +1,7 @@
 use std::{fmt::Display, str::FromStr};
 
 use axum::http::HeaderMap;
+use engine_types::ProjectId;
 use hasura_authn_core::{Identity, Role};
 use hasura_authn_jwt::{auth as jwt_auth, jwt};
 use hasura_authn_noauth as noauth;
@@ -380,11 +381,13 @@ pub enum PossibleAuthModeConfig<'a> {
     V3(&'a AuthModeConfigV3),
 }
 
-/// Authenticate the user based on the headers and the auth config
+/// Authenticate the user based on the headers and the auth config. The project
+/// scopes the identities cached while authenticating.
 pub async fn authenticate(
     headers_map: &HeaderMap,
     client: &reqwest::Client,
     resolved_auth_config: &ResolvedAuthConfig,
+    project_id: Option<&ProjectId>,
 ) -> Result<Identity, AuthError> {
     // We are still supporting AuthConfig::V1, hence we need to
     // support role emulation
@@ -419,6 +422,7 @@ pub async fn authenticate(
                 webhook_config,
                 headers_map,
                 allow_role_emulation_by,
+                project_id,
             )
             .await
             .map_err(AuthError::from)
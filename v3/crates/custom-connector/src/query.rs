@@ -7,7 +7,6 @@ use std::{
 
 use axum::{http::StatusCode, Json};
 use indexmap::IndexMap;
-use itertools::Itertools;
 use ndc_models;
 use regex::Regex;
 
@@ -165,7 +164,15 @@ fn execute_query(
     let groups = query
         .groups
         .as_ref()
-        .map(|grouping| eval_groups(grouping, &paginated))
+        .map(|grouping| {
+            eval_groups(
+                collection_relationships,
+                variables,
+                state,
+                grouping,
+                &paginated,
+            )
+        })
         .transpose()?;
 
     let rows = query
@@ -294,18 +301,33 @@ struct Chunk {
 }
 
 fn eval_groups(
+    collection_relationships: &BTreeMap<ndc_models::RelationshipName, ndc_models::Relationship>,
+    variables: &BTreeMap<ndc_models::VariableName, serde_json::Value>,
+    state: &AppState,
     grouping: &ndc_models::Grouping,
     paginated: &[Row],
 ) -> Result<Vec<ndc_models::Group>> {
-    let chunks: Vec<Chunk> = paginated
-        .iter()
-        .chunk_by(|row| eval_dimensions(row, &grouping.dimensions).expect("cannot eval dimensions"))
-        .into_iter()
-        .map(|(dimensions, rows)| Chunk {
-            dimensions,
-            rows: rows.cloned().collect(),
-        })
-        .collect();
+    // rows with the same dimensions form one group, wherever they appear in the input
+    let mut chunks: Vec<Chunk> = vec![];
+    for row in paginated {
+        let dimensions = eval_dimensions(
+            collection_relationships,
+            variables,
+            state,
+            row,
+            &grouping.dimensions,
+        )?;
+        match chunks
+            .iter_mut()
+            .find(|chunk| chunk.dimensions == dimensions)
+        {
+            Some(chunk) => chunk.rows.push(row.clone()),
+            None => chunks.push(Chunk {
+                dimensions,
+                rows: vec![row.clone()],
+            }),
+        }
+    }
 
     let sorted = group_sort(chunks, grouping.order_by.as_ref())?;
 
@@ -468,12 +490,15 @@ fn eval_group_order_by_element(
 }
 
 fn eval_dimensions(
+    collection_relationships: &BTreeMap<ndc_models::RelationshipName, ndc_models::Relationship>,
+    variables: &BTreeMap<ndc_models::VariableName, serde_json::Value>,
+    state: &AppState,
     row: &Row,
     dimensions: &[ndc_models::Dimension],
 ) -> Result<Vec<serde_json::Value>> {
     let mut values = vec![];
     for dimension in dimensions {
-        let value = eval_dimension(row, dimension)?;
+        let value = eval_dimension(collection_relationships, variables, state, row, dimension)?;
         values.push(value);
     }
     Ok(values)
@@ -505,16 +530,31 @@ fn eval_aggregate_comparison_value(
     }
 }
 
-fn eval_dimension(row: &Row, dimension: &ndc_models::Dimension) -> Result<serde_json::Value> {
+fn eval_dimension(
+    collection_relationships: &BTreeMap<ndc_models::RelationshipName, ndc_models::Relationship>,
+    variables: &BTreeMap<ndc_models::VariableName, serde_json::Value>,
+    state: &AppState,
+    row: &Row,
+    dimension: &ndc_models::Dimension,
+) -> Result<serde_json::Value> {
     match dimension {
         ndc_models::Dimension::Column {
             column_name,
             arguments: _,
             field_path,
-            path: _,
+            path,
             extraction,
         } => {
-            let value = eval_column_field_path(row, column_name, field_path.as_ref())?;
+            // dimensions across object relationships are evaluated like columns to order by
+            let value = eval_order_by_column(
+                collection_relationships,
+                variables,
+                state,
+                row,
+                path,
+                column_name,
+                field_path.as_ref(),
+            )?;
             eval_extraction(extraction.as_ref(), value)
         }
     }
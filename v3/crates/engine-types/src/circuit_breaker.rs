@@ -0,0 +1,209 @@
+//! Circuit breakers for requests to data connectors.
+//!
+//! A circuit opens once a data connector has failed a number of times in a row, after which
+//! requests to it are rejected without being sent. Once the reset timeout has passed, the circuit
+//! is half-open: a single probe request is let through, closing the circuit if it succeeds and
+//! re-opening it if it fails.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// The state of the circuit breakers of all data connectors, shared between requests
+#[derive(Debug, Clone, Default)]
+pub struct CircuitBreakers(Arc<Mutex<HashMap<CircuitKey, CircuitState>>>);
+
+/// Identifies a circuit. Data connectors of different projects have separate circuits, as do
+/// the URLs that a data connector serves queries and mutations on, as they can fail separately.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CircuitKey {
+    pub project_id: Option<String>,
+    pub data_connector: String,
+    pub url: String,
+}
+
+/// The state of the circuit of a single data connector
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests are sent
+    Closed { consecutive_failures: u32 },
+    /// Requests are rejected until the given instant
+    Open { until: Instant },
+    /// A probe request was let through at the given instant, other requests are rejected
+    HalfOpen { since: Instant },
+}
+
+impl CircuitBreakers {
+    /// Returns whether a request to the data connector may be sent
+    pub fn try_acquire(&self, key: &CircuitKey, reset_timeout: Duration) -> bool {
+        let mut circuits = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(state) = circuits.get_mut(key) else {
+            return true;
+        };
+        let now = Instant::now();
+        match *state {
+            CircuitState::Closed { .. } => true,
+            CircuitState::Open { until } if now >= until => {
+                *state = CircuitState::HalfOpen { since: now };
+                true
+            }
+            // a probe that never reported back must not keep the circuit half-open forever
+            CircuitState::HalfOpen { since } if now >= since + reset_timeout => {
+                *state = CircuitState::HalfOpen { since: now };
+                true
+            }
+            CircuitState::Open { .. } | CircuitState::HalfOpen { .. } => false,
+        }
+    }
+
+    /// Records a successful request to the data connector, closing its circuit unless it is
+    /// open: the request was then sent before the circuit opened, and the reset timeout must
+    /// still pass before a probe decides whether the data connector has recovered.
+    pub fn record_success(&self, key: &CircuitKey) {
+        let mut circuits = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        if matches!(
+            circuits.get(key),
+            Some(CircuitState::Closed { .. } | CircuitState::HalfOpen { .. })
+        ) {
+            circuits.remove(key);
+        }
+    }
+
+    /// Records a failed request to the data connector, opening its circuit once
+    /// `failure_threshold` requests have failed in a row, or if the failed request was a probe
+    pub fn record_failure(
+        &self,
+        key: &CircuitKey,
+        failure_threshold: u32,
+        reset_timeout: Duration,
+    ) {
+        let mut circuits = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        let state = circuits.entry(key.clone()).or_insert(CircuitState::Closed {
+            consecutive_failures: 0,
+        });
+        let open = CircuitState::Open {
+            until: Instant::now() + reset_timeout,
+        };
+        *state = match *state {
+            CircuitState::Closed {
+                consecutive_failures,
+            } => {
+                let consecutive_failures = consecutive_failures.saturating_add(1);
+                if consecutive_failures >= failure_threshold {
+                    open
+                } else {
+                    CircuitState::Closed {
+                        consecutive_failures,
+                    }
+                }
+            }
+            CircuitState::HalfOpen { .. } => open,
+            // failures of requests sent before the circuit opened do not extend it
+            CircuitState::Open { until } => CircuitState::Open { until },
+        };
+    }
+
+    /// The state of the circuit of the data connector
+    pub fn state(&self, key: &CircuitKey) -> CircuitState {
+        let circuits = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        circuits.get(key).copied().unwrap_or(CircuitState::Closed {
+            consecutive_failures: 0,
+        })
+    }
+}
+
+impl CircuitState {
+    /// The name of the state, as reported in traces
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Closed { .. } => "closed",
+            Self::Open { .. } => "open",
+            Self::HalfOpen { .. } => "half-open",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CircuitBreakers, CircuitKey, CircuitState};
+    use std::time::Duration;
+
+    fn circuit_key(project_id: Option<&str>, url: &str) -> CircuitKey {
+        CircuitKey {
+            project_id: project_id.map(ToString::to_string),
+            data_connector: "db".to_string(),
+            url: url.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_opens_after_consecutive_failures() {
+        let breakers = CircuitBreakers::default();
+        let key = circuit_key(None, "http://db/");
+        let reset_timeout = Duration::from_secs(60);
+        breakers.record_failure(&key, 2, reset_timeout);
+        assert!(breakers.try_acquire(&key, reset_timeout));
+        breakers.record_failure(&key, 2, reset_timeout);
+        assert!(!breakers.try_acquire(&key, reset_timeout));
+        assert_eq!(breakers.state(&key).as_str(), "open");
+    }
+
+    #[test]
+    fn test_success_resets_failures() {
+        let breakers = CircuitBreakers::default();
+        let key = circuit_key(None, "http://db/");
+        let reset_timeout = Duration::from_secs(60);
+        breakers.record_failure(&key, 2, reset_timeout);
+        breakers.record_success(&key);
+        breakers.record_failure(&key, 2, reset_timeout);
+        assert!(breakers.try_acquire(&key, reset_timeout));
+        assert_eq!(
+            breakers.state(&key),
+            CircuitState::Closed {
+                consecutive_failures: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_half_open_allows_a_single_probe() {
+        let breakers = CircuitBreakers::default();
+        let key = circuit_key(None, "http://db/");
+        let reset_timeout = Duration::ZERO;
+        breakers.record_failure(&key, 1, Duration::ZERO);
+        assert!(breakers.try_acquire(&key, Duration::from_secs(60)));
+        assert_eq!(breakers.state(&key).as_str(), "half-open");
+        assert!(!breakers.try_acquire(&key, Duration::from_secs(60)));
+
+        // a failed probe re-opens the circuit, a successful one closes it
+        breakers.record_failure(&key, 1, reset_timeout);
+        assert!(breakers.try_acquire(&key, Duration::from_secs(60)));
+        breakers.record_success(&key);
+        assert_eq!(breakers.state(&key).as_str(), "closed");
+    }
+
+    #[test]
+    fn test_success_of_request_sent_before_opening_keeps_circuit_open() {
+        let breakers = CircuitBreakers::default();
+        let key = circuit_key(None, "http://db/");
+        let reset_timeout = Duration::from_secs(60);
+        breakers.record_failure(&key, 1, reset_timeout);
+        breakers.record_success(&key);
+        assert_eq!(breakers.state(&key).as_str(), "open");
+        assert!(!breakers.try_acquire(&key, reset_timeout));
+    }
+
+    #[test]
+    fn test_circuits_are_separate_per_project_and_url() {
+        let breakers = CircuitBreakers::default();
+        let key = circuit_key(Some("project-1"), "http://db/");
+        let reset_timeout = Duration::from_secs(60);
+        breakers.record_failure(&key, 1, reset_timeout);
+        assert!(!breakers.try_acquire(&key, reset_timeout));
+        assert!(breakers.try_acquire(&circuit_key(Some("project-2"), "http://db/"), reset_timeout));
+        assert!(breakers.try_acquire(
+            &circuit_key(Some("project-1"), "http://db-write/"),
+            reset_timeout
+        ));
+    }
+}
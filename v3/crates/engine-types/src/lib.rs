@@ -1,3 +1,15 @@
+mod circuit_breaker;
+mod persisted_query_cache;
+mod remote_join_limit;
+mod replica_pool;
+mod response_cache;
+
+pub use circuit_breaker::{CircuitBreakers, CircuitKey, CircuitState};
+pub use persisted_query_cache::PersistedQueryCache;
+pub use remote_join_limit::{RemoteJoinQueryLimit, DEFAULT_MAX_CONCURRENT_REMOTE_JOIN_QUERIES};
+pub use replica_pool::{LoadBalancingStrategy, ReplicaLease, ReplicaPools};
+pub use response_cache::{InMemoryResponseCache, ResponseCache, ResponseCacheBackend};
+
 /// Context for making HTTP requests
 #[derive(Debug, Clone)]
 pub struct HttpContext {
@@ -5,6 +17,28 @@ pub struct HttpContext {
     pub client: reqwest::Client,
     /// Response size limit for NDC requests
     pub ndc_response_size_limit: Option<usize>,
+    /// Circuit breakers for requests to data connectors
+    pub ndc_circuit_breakers: CircuitBreakers,
+    /// Load balancing between the read replicas of data connectors
+    pub ndc_replica_pools: ReplicaPools,
+    /// Cache of the responses of data connectors to queries
+    pub ndc_response_cache: ResponseCache,
+    /// Limit on the number of remote join queries in flight at once for a request
+    pub remote_join_query_limit: RemoteJoinQueryLimit,
+    /// Queries registered by clients as automatic persisted queries
+    pub persisted_queries: PersistedQueryCache,
+}
+
+impl HttpContext {
+    /// The context for executing a new request, which shares everything but the limit on
+    /// concurrent remote join queries with other requests
+    #[must_use]
+    pub fn for_request(&self) -> Self {
+        Self {
+            remote_join_query_limit: self.remote_join_query_limit.for_request(),
+            ..self.clone()
+        }
+    }
 }
 
 #[derive(Clone, serde::Serialize, Debug)]
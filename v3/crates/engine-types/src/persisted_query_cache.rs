@@ -0,0 +1,169 @@
+//! Caching of the queries registered by clients as automatic persisted queries.
+//!
+//! Each project has its own in-memory LRU cache, bounded by the total size of its queries, so that
+//! the clients of one project cannot evict the queries registered by the clients of another. Once
+//! the bound is reached, the least recently used queries are evicted to make room for new ones.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::ProjectId;
+
+/// The default bound on the total size of the queries registered for a project, 10MiB
+const DEFAULT_MAX_SIZE_BYTES_PER_PROJECT: usize = 10 * 1024 * 1024;
+
+/// The queries registered by clients, keyed by project and by the hash of the query, shared
+/// between requests
+#[derive(Debug, Clone)]
+pub struct PersistedQueryCache {
+    max_size_bytes_per_project: usize,
+    projects: Arc<Mutex<HashMap<Option<String>, LruState>>>,
+}
+
+#[derive(Debug, Default)]
+struct LruState {
+    entries: HashMap<String, Entry>,
+    // the hashes of the queries, ordered from least to most recently used
+    recency: BTreeMap<u64, String>,
+    next_use: u64,
+    size_bytes: usize,
+}
+
+#[derive(Debug)]
+struct Entry {
+    query: Arc<str>,
+    last_use: u64,
+}
+
+/// The size a query takes up in the cache, including its hash
+fn entry_size(query_hash: &str, query: &str) -> usize {
+    query_hash.len() + query.len()
+}
+
+impl LruState {
+    fn touch(&mut self, query_hash: &str) {
+        let next_use = self.next_use;
+        if let Some(entry) = self.entries.get_mut(query_hash) {
+            let previous_use = std::mem::replace(&mut entry.last_use, next_use);
+            self.recency.remove(&previous_use);
+            self.recency.insert(next_use, query_hash.to_string());
+            self.next_use += 1;
+        }
+    }
+
+    fn evict_least_recently_used(&mut self) {
+        if let Some((_, query_hash)) = self.recency.pop_first() {
+            if let Some(entry) = self.entries.remove(&query_hash) {
+                self.size_bytes -= entry_size(&query_hash, &entry.query);
+            }
+        }
+    }
+}
+
+impl PersistedQueryCache {
+    pub fn new(max_size_bytes_per_project: usize) -> Self {
+        Self {
+            max_size_bytes_per_project,
+            projects: Arc::default(),
+        }
+    }
+
+    /// Returns the query registered for the project under the hash, if it has not been evicted
+    pub fn get(&self, project_id: Option<&ProjectId>, query_hash: &str) -> Option<Arc<str>> {
+        let mut projects = self.projects.lock().unwrap_or_else(PoisonError::into_inner);
+        let state = projects.get_mut(&project_id.map(|project_id| project_id.0.clone()))?;
+        let query = state.entries.get(query_hash)?.query.clone();
+        state.touch(query_hash);
+        Some(query)
+    }
+
+    /// Registers the query for the project under its hash, evicting the least recently used
+    /// queries of the project if needed
+    pub fn insert(&self, project_id: Option<&ProjectId>, query_hash: String, query: &str) {
+        let size = entry_size(&query_hash, query);
+        // queries that could never fit are not registered, rather than flushing the whole cache
+        if size > self.max_size_bytes_per_project {
+            return;
+        }
+        let mut projects = self.projects.lock().unwrap_or_else(PoisonError::into_inner);
+        let state = projects
+            .entry(project_id.map(|project_id| project_id.0.clone()))
+            .or_default();
+        if state.entries.contains_key(&query_hash) {
+            state.touch(&query_hash);
+            return;
+        }
+        while state.size_bytes + size > self.max_size_bytes_per_project {
+            state.evict_least_recently_used();
+        }
+        let last_use = state.next_use;
+        state.next_use += 1;
+        state.size_bytes += size;
+        state.recency.insert(last_use, query_hash.clone());
+        state.entries.insert(
+            query_hash,
+            Entry {
+                query: Arc::from(query),
+                last_use,
+            },
+        );
+    }
+}
+
+impl Default for PersistedQueryCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_SIZE_BYTES_PER_PROJECT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(name: &str) -> ProjectId {
+        ProjectId(name.to_string())
+    }
+
+    #[test]
+    fn test_least_recently_used_queries_are_evicted() {
+        // room for three queries of a single character under a single character hash
+        let cache = PersistedQueryCache::new(6);
+        cache.insert(None, "a".into(), "1");
+        cache.insert(None, "b".into(), "2");
+        cache.insert(None, "c".into(), "3");
+        // reading `a` makes `b` the least recently used query
+        assert!(cache.get(None, "a").is_some());
+        cache.insert(None, "d".into(), "4");
+        assert_eq!(cache.get(None, "a").as_deref(), Some("1"));
+        assert_eq!(cache.get(None, "b"), None);
+        assert_eq!(cache.get(None, "c").as_deref(), Some("3"));
+        // new queries are still registered once the cache is full
+        assert_eq!(cache.get(None, "d").as_deref(), Some("4"));
+        cache.insert(None, "e".into(), "5");
+        assert_eq!(cache.get(None, "e").as_deref(), Some("5"));
+    }
+
+    #[test]
+    fn test_queries_larger_than_the_cache_are_not_registered() {
+        let cache = PersistedQueryCache::new(4);
+        cache.insert(None, "a".into(), "1");
+        cache.insert(None, "b".into(), "1234");
+        assert!(cache.get(None, "a").is_some());
+        assert_eq!(cache.get(None, "b"), None);
+    }
+
+    #[test]
+    fn test_projects_do_not_evict_each_others_queries() {
+        let cache = PersistedQueryCache::new(4);
+        let project_1 = project("project_1");
+        let project_2 = project("project_2");
+        cache.insert(Some(&project_1), "a".into(), "1");
+        for query_hash in ["b", "c", "d"] {
+            cache.insert(Some(&project_2), query_hash.into(), "2");
+        }
+        assert_eq!(cache.get(Some(&project_1), "a").as_deref(), Some("1"));
+        assert_eq!(cache.get(Some(&project_2), "a"), None);
+        assert_eq!(cache.get(None, "a"), None);
+        assert_eq!(cache.get(Some(&project_2), "d").as_deref(), Some("2"));
+    }
+}
@@ -0,0 +1,72 @@
+//! Limiting how many queries the remote joins of a request send to data connectors at once.
+
+use std::sync::Arc;
+
+use tokio::sync::{AcquireError, Semaphore, SemaphorePermit};
+
+/// The default maximum number of remote join queries in flight at once for each request
+pub const DEFAULT_MAX_CONCURRENT_REMOTE_JOIN_QUERIES: usize = 10;
+
+/// Limits the number of queries that the remote joins of a request send to data connectors at the
+/// same time. Requests do not share a limit, so each request should get its own with
+/// [`RemoteJoinQueryLimit::for_request`].
+#[derive(Debug, Clone)]
+pub struct RemoteJoinQueryLimit {
+    max_concurrent_queries: usize,
+    semaphore: Arc<Semaphore>,
+}
+
+impl RemoteJoinQueryLimit {
+    /// A limit of at most `max_concurrent_queries` queries in flight at once, which is at least one
+    pub fn new(max_concurrent_queries: usize) -> Self {
+        let max_concurrent_queries = max_concurrent_queries.max(1);
+        Self {
+            max_concurrent_queries,
+            semaphore: Arc::new(Semaphore::new(max_concurrent_queries)),
+        }
+    }
+
+    pub fn max_concurrent_queries(&self) -> usize {
+        self.max_concurrent_queries
+    }
+
+    /// A limit with the same maximum, for a new request
+    #[must_use]
+    pub fn for_request(&self) -> Self {
+        Self::new(self.max_concurrent_queries)
+    }
+
+    /// Waits until another query can be sent. The query counts towards the limit until the
+    /// returned permit is dropped.
+    pub async fn acquire(&self) -> Result<SemaphorePermit<'_>, AcquireError> {
+        self.semaphore.acquire().await
+    }
+}
+
+impl Default for RemoteJoinQueryLimit {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_CONCURRENT_REMOTE_JOIN_QUERIES)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_requests_do_not_share_limit() {
+        let limit = RemoteJoinQueryLimit::new(1);
+        let _permit = limit.acquire().await.unwrap();
+
+        // the limit of this request is used up, but another request has its own
+        assert!(limit.semaphore.try_acquire().is_err());
+        let request_limit = limit.for_request();
+        assert_eq!(request_limit.max_concurrent_queries(), 1);
+        assert!(request_limit.semaphore.try_acquire().is_ok());
+    }
+
+    #[test]
+    fn test_limit_is_at_least_one() {
+        assert_eq!(RemoteJoinQueryLimit::new(0).max_concurrent_queries(), 1);
+    }
+}
@@ -0,0 +1,247 @@
+//! Load balancing of requests between the read replicas of data connectors.
+//!
+//! Replicas are ejected from their pool for a cooldown after a connection error, and while their
+//! health probes fail. If every replica of a pool is ejected, requests are balanced between all
+//! of them rather than failed outright.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// The state of the read replica pools of all data connectors, shared between requests
+#[derive(Debug, Clone, Default)]
+pub struct ReplicaPools(Arc<Mutex<HashMap<String, ReplicaPool>>>);
+
+/// How requests are balanced between the replicas of a pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadBalancingStrategy {
+    RoundRobin,
+    LeastOutstandingRequests,
+}
+
+#[derive(Debug)]
+struct ReplicaPool {
+    replicas: Vec<Replica>,
+    // the replica to start from when looking for the next one
+    next: usize,
+}
+
+#[derive(Debug, Default)]
+struct Replica {
+    outstanding_requests: usize,
+    ejected_until: Option<Instant>,
+    unhealthy: bool,
+}
+
+impl Replica {
+    fn is_available(&self, now: Instant) -> bool {
+        !self.unhealthy && self.ejected_until.is_none_or(|until| now >= until)
+    }
+}
+
+impl ReplicaPools {
+    // run `f` on the pool, (re)creating it if it does not have `replica_count` replicas
+    fn with_pool<T>(
+        &self,
+        pool: &str,
+        replica_count: usize,
+        f: impl FnOnce(&mut ReplicaPool) -> T,
+    ) -> T {
+        let mut pools = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        let pool = pools
+            .entry(pool.to_string())
+            .and_modify(|pool| {
+                if pool.replicas.len() != replica_count {
+                    *pool = ReplicaPool::new(replica_count);
+                }
+            })
+            .or_insert_with(|| ReplicaPool::new(replica_count));
+        f(pool)
+    }
+
+    // update a replica of the pool, unless the replicas of the pool changed in the meantime
+    fn update_replica(
+        &self,
+        pool: &str,
+        replica_count: usize,
+        replica: usize,
+        f: impl FnOnce(&mut Replica),
+    ) {
+        let mut pools = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(pool) = pools
+            .get_mut(pool)
+            .filter(|pool| pool.replicas.len() == replica_count)
+        {
+            if let Some(replica) = pool.replicas.get_mut(replica) {
+                f(replica);
+            }
+        }
+    }
+
+    /// Picks the replica of the pool to send a request to. The replica counts as having a request
+    /// in progress until the returned lease is dropped.
+    pub fn acquire(
+        &self,
+        pool: &str,
+        replica_count: usize,
+        strategy: LoadBalancingStrategy,
+    ) -> ReplicaLease {
+        let replica = self.with_pool(pool, replica_count, |pool| {
+            let replica = pool.pick(strategy, Instant::now());
+            pool.replicas[replica].outstanding_requests += 1;
+            pool.next = (replica + 1) % replica_count;
+            replica
+        });
+        ReplicaLease {
+            pools: self.clone(),
+            pool: pool.to_string(),
+            replica_count,
+            replica,
+        }
+    }
+
+    /// Records the result of a health probe of a replica. Unhealthy replicas are ejected until
+    /// a probe succeeds.
+    pub fn set_healthy(&self, pool: &str, replica_count: usize, replica: usize, healthy: bool) {
+        self.update_replica(pool, replica_count, replica, |replica| {
+            replica.unhealthy = !healthy;
+        });
+    }
+
+    /// Whether the replica can be sent requests
+    pub fn is_available(&self, pool: &str, replica_count: usize, replica: usize) -> bool {
+        self.with_pool(pool, replica_count, |pool| {
+            pool.replicas
+                .get(replica)
+                .is_some_and(|replica| replica.is_available(Instant::now()))
+        })
+    }
+}
+
+impl ReplicaPool {
+    fn new(replica_count: usize) -> Self {
+        ReplicaPool {
+            replicas: (0..replica_count).map(|_| Replica::default()).collect(),
+            next: 0,
+        }
+    }
+
+    fn pick(&self, strategy: LoadBalancingStrategy, now: Instant) -> usize {
+        let replica_count = self.replicas.len();
+        // replicas in round robin order
+        let in_turn = (0..replica_count).map(|offset| (self.next + offset) % replica_count);
+        let mut available = in_turn
+            .clone()
+            .filter(|replica| self.replicas[*replica].is_available(now))
+            .peekable();
+        // if every replica is ejected, any of them may have recovered
+        let candidates: Vec<usize> = if available.peek().is_some() {
+            available.collect()
+        } else {
+            in_turn.collect()
+        };
+        match strategy {
+            LoadBalancingStrategy::RoundRobin => candidates[0],
+            // ties are broken in round robin order, as `min_by_key` keeps the first minimum
+            LoadBalancingStrategy::LeastOutstandingRequests => candidates
+                .into_iter()
+                .min_by_key(|replica| self.replicas[*replica].outstanding_requests)
+                .unwrap_or(self.next),
+        }
+    }
+}
+
+/// A request in progress on a replica of a pool
+#[derive(Debug)]
+pub struct ReplicaLease {
+    pools: ReplicaPools,
+    pool: String,
+    replica_count: usize,
+    replica: usize,
+}
+
+impl ReplicaLease {
+    /// The index of the replica in its pool
+    pub fn replica(&self) -> usize {
+        self.replica
+    }
+
+    /// Ejects the replica from its pool for the given cooldown
+    pub fn eject(&self, cooldown: Duration) {
+        self.pools
+            .update_replica(&self.pool, self.replica_count, self.replica, |replica| {
+                replica.ejected_until = Some(Instant::now() + cooldown);
+            });
+    }
+}
+
+impl Drop for ReplicaLease {
+    fn drop(&mut self) {
+        self.pools
+            .update_replica(&self.pool, self.replica_count, self.replica, |replica| {
+                replica.outstanding_requests = replica.outstanding_requests.saturating_sub(1);
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LoadBalancingStrategy, ReplicaPools};
+    use std::time::Duration;
+
+    const POOL: &str = "db";
+
+    #[test]
+    fn test_round_robin() {
+        let pools = ReplicaPools::default();
+        let replicas: Vec<usize> = (0..4)
+            .map(|_| {
+                pools
+                    .acquire(POOL, 3, LoadBalancingStrategy::RoundRobin)
+                    .replica()
+            })
+            .collect();
+        assert_eq!(replicas, vec![0, 1, 2, 0]);
+    }
+
+    #[test]
+    fn test_least_outstanding_requests() {
+        let pools = ReplicaPools::default();
+        let strategy = LoadBalancingStrategy::LeastOutstandingRequests;
+        let first = pools.acquire(POOL, 2, strategy);
+        let second = pools.acquire(POOL, 2, strategy);
+        assert_eq!((first.replica(), second.replica()), (0, 1));
+        drop(second);
+        // the first replica still has a request in progress
+        assert_eq!(pools.acquire(POOL, 2, strategy).replica(), 1);
+        assert_eq!(pools.acquire(POOL, 2, strategy).replica(), 1);
+    }
+
+    #[test]
+    fn test_ejected_replicas_are_skipped() {
+        let pools = ReplicaPools::default();
+        let strategy = LoadBalancingStrategy::RoundRobin;
+        pools
+            .acquire(POOL, 2, strategy)
+            .eject(Duration::from_secs(60));
+        assert!(!pools.is_available(POOL, 2, 0));
+        assert_eq!(pools.acquire(POOL, 2, strategy).replica(), 1);
+        assert_eq!(pools.acquire(POOL, 2, strategy).replica(), 1);
+
+        // when every replica is ejected, all of them are used
+        pools.set_healthy(POOL, 2, 1, false);
+        assert_eq!(pools.acquire(POOL, 2, strategy).replica(), 0);
+        assert_eq!(pools.acquire(POOL, 2, strategy).replica(), 1);
+
+        pools.set_healthy(POOL, 2, 1, true);
+        assert_eq!(pools.acquire(POOL, 2, strategy).replica(), 1);
+    }
+
+    #[test]
+    fn test_ejection_cooldown_expires() {
+        let pools = ReplicaPools::default();
+        let strategy = LoadBalancingStrategy::RoundRobin;
+        pools.acquire(POOL, 2, strategy).eject(Duration::ZERO);
+        assert!(pools.is_available(POOL, 2, 0));
+    }
+}
@@ -0,0 +1,201 @@
+//! Caching of the responses of data connectors to queries.
+//!
+//! Responses are cached as serialized bytes under a key derived from the query request, so that
+//! the cache can be backed by stores outside of the engine. The default backend is an in-memory
+//! LRU cache, bounded by the total size of the cached responses.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+
+/// The default bound on the total size of the responses in the in-memory cache, 100MiB
+const DEFAULT_MAX_SIZE_BYTES: usize = 100 * 1024 * 1024;
+
+/// A store for cached data connector responses
+#[async_trait]
+pub trait ResponseCacheBackend: Debug + Send + Sync {
+    /// Returns the response cached under the key, if it has not expired
+    async fn get(&self, key: &str) -> Option<Bytes>;
+
+    /// Caches the response under the key for the given duration
+    async fn insert(&self, key: String, response: Bytes, ttl: Duration);
+}
+
+/// The cache of data connector responses, shared between requests
+#[derive(Debug, Clone)]
+pub struct ResponseCache(Arc<dyn ResponseCacheBackend>);
+
+impl ResponseCache {
+    pub fn new(backend: Arc<dyn ResponseCacheBackend>) -> Self {
+        Self(backend)
+    }
+
+    pub async fn get(&self, key: &str) -> Option<Bytes> {
+        self.0.get(key).await
+    }
+
+    pub async fn insert(&self, key: String, response: Bytes, ttl: Duration) {
+        if !ttl.is_zero() {
+            self.0.insert(key, response, ttl).await;
+        }
+    }
+}
+
+impl Default for ResponseCache {
+    fn default() -> Self {
+        Self::new(Arc::new(InMemoryResponseCache::new(DEFAULT_MAX_SIZE_BYTES)))
+    }
+}
+
+/// An in-memory cache that evicts the least recently used responses once the total size of the
+/// cached responses exceeds its bound
+#[derive(Debug)]
+pub struct InMemoryResponseCache {
+    max_size_bytes: usize,
+    state: Mutex<LruState>,
+}
+
+#[derive(Debug, Default)]
+struct LruState {
+    entries: HashMap<String, Entry>,
+    // the keys of the entries, ordered from least to most recently used
+    recency: BTreeMap<u64, String>,
+    next_use: u64,
+    size_bytes: usize,
+}
+
+#[derive(Debug)]
+struct Entry {
+    response: Bytes,
+    expires_at: Instant,
+    last_use: u64,
+}
+
+impl LruState {
+    fn touch(&mut self, key: &str) {
+        let next_use = self.next_use;
+        if let Some(entry) = self.entries.get_mut(key) {
+            let previous_use = std::mem::replace(&mut entry.last_use, next_use);
+            self.recency.remove(&previous_use);
+            self.recency.insert(next_use, key.to_string());
+            self.next_use += 1;
+        }
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.recency.remove(&entry.last_use);
+            self.size_bytes -= entry.response.len();
+        }
+    }
+
+    fn evict_least_recently_used(&mut self) {
+        if let Some((_, key)) = self.recency.pop_first() {
+            if let Some(entry) = self.entries.remove(&key) {
+                self.size_bytes -= entry.response.len();
+            }
+        }
+    }
+}
+
+impl InMemoryResponseCache {
+    pub fn new(max_size_bytes: usize) -> Self {
+        Self {
+            max_size_bytes,
+            state: Mutex::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl ResponseCacheBackend for InMemoryResponseCache {
+    async fn get(&self, key: &str) -> Option<Bytes> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        match state.entries.get(key) {
+            Some(entry) if entry.expires_at > Instant::now() => {
+                let response = entry.response.clone();
+                state.touch(key);
+                Some(response)
+            }
+            Some(_) => {
+                state.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    async fn insert(&self, key: String, response: Bytes, ttl: Duration) {
+        // responses that could never fit are not cached, rather than flushing the whole cache
+        if response.len() > self.max_size_bytes {
+            return;
+        }
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.remove(&key);
+        while state.size_bytes + response.len() > self.max_size_bytes {
+            state.evict_least_recently_used();
+        }
+        let last_use = state.next_use;
+        state.next_use += 1;
+        state.size_bytes += response.len();
+        state.recency.insert(last_use, key.clone());
+        state.entries.insert(
+            key,
+            Entry {
+                response,
+                expires_at: Instant::now() + ttl,
+                last_use,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(max_size_bytes: usize) -> ResponseCache {
+        ResponseCache::new(Arc::new(InMemoryResponseCache::new(max_size_bytes)))
+    }
+
+    #[tokio::test]
+    async fn test_responses_expire_after_their_ttl() {
+        let cache = cache(100);
+        cache
+            .insert("a".into(), Bytes::from("1"), Duration::from_millis(10))
+            .await;
+        assert_eq!(cache.get("a").await, Some(Bytes::from("1")));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(cache.get("a").await, None);
+    }
+
+    #[tokio::test]
+    async fn test_least_recently_used_responses_are_evicted() {
+        let cache = cache(3);
+        let ttl = Duration::from_secs(60);
+        cache.insert("a".into(), Bytes::from("1"), ttl).await;
+        cache.insert("b".into(), Bytes::from("2"), ttl).await;
+        cache.insert("c".into(), Bytes::from("3"), ttl).await;
+        // reading `a` makes `b` the least recently used response
+        assert!(cache.get("a").await.is_some());
+        cache.insert("d".into(), Bytes::from("4"), ttl).await;
+        assert!(cache.get("a").await.is_some());
+        assert_eq!(cache.get("b").await, None);
+        assert!(cache.get("c").await.is_some());
+        assert!(cache.get("d").await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_responses_larger_than_the_cache_are_not_cached() {
+        let cache = cache(3);
+        let ttl = Duration::from_secs(60);
+        cache.insert("a".into(), Bytes::from("1"), ttl).await;
+        cache.insert("b".into(), Bytes::from("1234"), ttl).await;
+        assert!(cache.get("a").await.is_some());
+        assert_eq!(cache.get("b").await, None);
+    }
+}
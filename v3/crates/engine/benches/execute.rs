@@ -1,8 +1,11 @@
 use core::time::Duration;
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, SamplingMode};
-use engine_types::{ExposeInternalErrors, HttpContext};
+use engine_types::{
+    CircuitBreakers, ExposeInternalErrors, HttpContext, PersistedQueryCache, RemoteJoinQueryLimit,
+    ReplicaPools, ResponseCache,
+};
 use graphql_frontend::{
-    execute_mutation_plan, execute_query_internal, execute_query_plan, generate_ir,
+    execute_mutation_plan, execute_query_internal, execute_query_plan, generate_ir, RequestMethod,
 };
 use graphql_ir::{generate_request_plan, GraphqlRequestPipeline, RequestPlan};
 use graphql_schema::GDS;
@@ -68,6 +71,11 @@ pub fn bench_execute(
     let http_context = HttpContext {
         client: reqwest::Client::new(),
         ndc_response_size_limit: None,
+        ndc_circuit_breakers: CircuitBreakers::default(),
+        ndc_replica_pools: ReplicaPools::default(),
+        ndc_response_cache: ResponseCache::default(),
+        remote_join_query_limit: RemoteJoinQueryLimit::default(),
+        persisted_queries: PersistedQueryCache::default(),
     };
     let runtime = Runtime::new().unwrap();
 
@@ -76,6 +84,7 @@ pub fn bench_execute(
         operation_name: None,
         query,
         variables: None,
+        extensions: None,
     };
 
     let request_headers = reqwest::header::HeaderMap::new();
@@ -231,6 +240,7 @@ pub fn bench_execute(
                     &session,
                     &request_headers,
                     request.clone(),
+                    RequestMethod::Post,
                     None,
                 )
                 .await
@@ -1,10 +1,10 @@
 use clap::Parser;
 use engine::{
-    get_base_routes, get_cors_layer, get_jsonapi_route, get_metadata_routes,
+    get_base_routes, get_cors_layer, get_jsonapi_route, get_metadata_routes, get_query_route,
     internal_flags::{resolve_unstable_features, UnstableFeature},
     StartupError, VERSION,
 };
-use engine_types::ExposeInternalErrors;
+use engine_types::{ExposeInternalErrors, DEFAULT_MAX_CONCURRENT_REMOTE_JOIN_QUERIES};
 use graphql_ir::GraphqlRequestPipeline;
 use serde::Serialize;
 use std::net;
@@ -39,6 +39,15 @@ struct ServerOptions {
     /// The port on which the server listens.
     #[arg(long, value_name = "PORT", env = "PORT", default_value_t = DEFAULT_PORT)]
     port: u16,
+    /// The maximum number of queries that the remote joins of a request send to data connectors
+    /// at once.
+    #[arg(
+        long,
+        value_name = "COUNT",
+        env = "MAX_CONCURRENT_REMOTE_JOIN_QUERIES",
+        default_value_t = DEFAULT_MAX_CONCURRENT_REMOTE_JOIN_QUERIES
+    )]
+    max_concurrent_remote_join_queries: usize,
     /// Enable CORS. Support preflight request and include related headers in responses.
     #[arg(long, env = "ENABLE_CORS")]
     enable_cors: bool,
@@ -153,6 +162,7 @@ async fn start_engine(server: &ServerOptions) -> Result<(), StartupError> {
         expose_internal_errors,
         auth_config,
         resolved_metadata,
+        server.max_concurrent_remote_join_queries,
     )
     .map_err(StartupError::ReadSchema)?;
 
@@ -160,6 +170,15 @@ async fn start_engine(server: &ServerOptions) -> Result<(), StartupError> {
 
     app = app.merge(get_jsonapi_route(state.clone()));
 
+    // The '/v1/query' route is experimental, and is only served if the `enable_query_endpoint`
+    // unstable feature is enabled
+    if server
+        .unstable_features
+        .contains(&UnstableFeature::EnableQueryEndpoint)
+    {
+        app = app.merge(get_query_route(state.clone()));
+    }
+
     // If `--introspection-metadata` is specified we also serve the file indicated on `/metadata`
     // and its hash on `/metadata-hash`.
     if let Some(path) = &server.introspection_metadata {
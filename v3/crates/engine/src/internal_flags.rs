@@ -19,6 +19,7 @@
 pub enum UnstableFeature {
     EnableAggregationPredicates,
     EnableOpenDdPipelineForGraphql,
+    EnableQueryEndpoint,
 }
 
 pub fn resolve_unstable_features(
@@ -31,7 +32,8 @@ pub fn resolve_unstable_features(
             UnstableFeature::EnableAggregationPredicates => {
                 features.enable_aggregation_predicates = true;
             }
-            UnstableFeature::EnableOpenDdPipelineForGraphql => {
+            UnstableFeature::EnableOpenDdPipelineForGraphql
+            | UnstableFeature::EnableQueryEndpoint => {
                 // this does not currently affect metadata resolve behaviour
             }
         }
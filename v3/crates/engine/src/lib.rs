@@ -8,10 +8,13 @@ mod state;
 mod types;
 pub use cors::build_cors_layer;
 pub use middleware::{
-    authentication_middleware, explain_request_tracing_middleware,
-    graphql_request_tracing_middleware, plugins_middleware,
+    authentication_middleware, batch_request_middleware, endpoint_request_tracing_middleware,
+    explain_request_tracing_middleware, graphql_request_tracing_middleware, plugins_middleware,
+};
+pub use routes::{
+    execute_query_request, get_base_routes, get_cors_layer, get_jsonapi_route, get_metadata_routes,
+    get_query_route, QueryError, QueryResponse,
 };
-pub use routes::{get_base_routes, get_cors_layer, get_jsonapi_route, get_metadata_routes};
 pub use state::{build_state, print_warnings, resolve_metadata};
 pub use types::{EngineState, RequestType, StartupError};
 
@@ -4,13 +4,13 @@ use crate::EngineState;
 use crate::VERSION;
 use axum::{
     extract::{ConnectInfo, State},
-    http::{HeaderMap, Request},
+    http::{header, HeaderMap, Request},
     middleware::Next,
     response::IntoResponse,
     Extension,
 };
 use axum_core::body::Body;
-use engine_types::WithMiddlewareErrorConverter;
+use engine_types::{MiddlewareError, WithMiddlewareErrorConverter};
 use hasura_authn::authenticate;
 use http_body_util::BodyExt;
 use pre_parse_plugin::execute::pre_parse_plugins_handler;
@@ -70,9 +70,20 @@ pub async fn graphql_request_tracing_middleware(
 pub async fn explain_request_tracing_middleware(
     request: Request<Body>,
     next: Next,
+) -> axum::response::Response {
+    endpoint_request_tracing_middleware("/v1/explain", request, next).await
+}
+
+/// Middleware to start tracing of a request to the endpoint at `path`, such as `/v1/query`.
+/// This middleware must be active for the entire duration
+/// of the request i.e. this middleware should be the
+/// entry point and the exit point of the request.
+pub async fn endpoint_request_tracing_middleware(
+    path: &'static str,
+    request: Request<Body>,
+    next: Next,
 ) -> axum::response::Response {
     let tracer = tracing_util::global_tracer();
-    let path = "/v1/explain";
     tracer
         .in_span_async_with_parent_context(
             path,
@@ -113,6 +124,7 @@ pub async fn authentication_middleware(
                     &headers_map,
                     &engine_state.http_context.client,
                     &engine_state.auth_config,
+                    None, // project_id is not needed for OSS v3-engine.
                 ))
             },
         )
@@ -172,6 +184,14 @@ pub async fn plugins_middleware(
             }
         }?;
 
+    // without pre-response plugins, the response is passed on as is, so that incrementally
+    // delivered responses are streamed rather than collected
+    let Some(pre_response_plugins) =
+        nonempty::NonEmpty::from_slice(&engine_state.plugin_configs.pre_response_plugins)
+    else {
+        return Ok(response);
+    };
+
     let (parts, body) = response.into_parts();
     let response_bytes = body
         .collect()
@@ -181,24 +201,153 @@ pub async fn plugins_middleware(
         })?
         .to_bytes();
 
-    if let Some(pre_response_plugins) =
-        nonempty::NonEmpty::from_slice(&engine_state.plugin_configs.pre_response_plugins)
-    {
-        pre_response_plugins_handler(
-            client_address,
-            &pre_response_plugins,
-            &engine_state.http_context.client,
-            session,
-            &raw_request,
-            &response_bytes,
-            headers_map,
-        )?;
-    }
+    pre_response_plugins_handler(
+        client_address,
+        &pre_response_plugins,
+        &engine_state.http_context.client,
+        session,
+        &raw_request,
+        &response_bytes,
+        headers_map,
+    )?;
     let recreated_response =
         axum::response::Response::from_parts(parts, axum::body::Body::from(response_bytes));
     Ok(recreated_response)
 }
 
+/// Middleware to execute batched GraphQL requests, whose body is an array of operations.
+///
+/// Each operation is passed on to the rest of the middleware and the request handler as a
+/// request of its own, sharing the session resolved for the batch, and their responses are
+/// returned as an array in the same order. The operations are executed one after the other, so
+/// that mutations run in order without parsing the operations here to tell them apart from queries.
+/// Batching is only enabled when the GraphQL configuration of the metadata sets a maximum batch size.
+pub async fn batch_request_middleware(
+    State(state): State<WithMiddlewareErrorConverter<EngineState>>,
+    request: Request<Body>,
+    next: Next,
+) -> axum::response::Result<axum::response::Response> {
+    let (parts, body) = request.into_parts();
+    let bytes = body
+        .collect()
+        .await
+        .map_err(|err| {
+            (reqwest::StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response()
+        })?
+        .to_bytes();
+
+    if !is_batch_request(&bytes) {
+        return Ok(next
+            .run(Request::from_parts(parts, Body::from(bytes)))
+            .await);
+    }
+
+    let Some(max_batch_size) = state
+        .state
+        .resolved_metadata
+        .graphql_config
+        .global
+        .max_batch_size
+    else {
+        return Err(state
+            .handle_error(bad_batch_request(
+                "batched requests are disabled".to_string(),
+            ))
+            .into());
+    };
+
+    let operations: Vec<serde_json::Value> = serde_json::from_slice(&bytes)
+        .map_err(|err| state.handle_error(bad_batch_request(format!("invalid batch: {err}"))))?;
+
+    if operations.is_empty() {
+        return Err(state
+            .handle_error(bad_batch_request(
+                "a batch must contain at least one operation".to_string(),
+            ))
+            .into());
+    }
+    if operations.len() > max_batch_size as usize {
+        return Err(state
+            .handle_error(bad_batch_request(format!(
+                "the batch contains {} operations, but at most {max_batch_size} are allowed",
+                operations.len()
+            )))
+            .into());
+    }
+
+    let mut operation_parts = parts;
+    operation_parts.headers.remove(header::CONTENT_LENGTH);
+    // the responses of the operations are combined into a single JSON response, so they are not
+    // delivered incrementally
+    operation_parts.headers.insert(
+        header::ACCEPT,
+        axum::http::HeaderValue::from_static("application/json"),
+    );
+    let mut operation_responses = Vec::with_capacity(operations.len());
+    for operation in operations {
+        operation_responses
+            .push(run_batched_operation(next.clone(), operation_parts.clone(), operation).await);
+    }
+
+    let mut headers = HeaderMap::new();
+    let mut responses = Vec::with_capacity(operation_responses.len());
+    for (operation_headers, response) in operation_responses {
+        for (name, value) in &operation_headers {
+            if name != header::CONTENT_TYPE
+                && name != header::CONTENT_LENGTH
+                && !headers
+                    .get_all(name)
+                    .iter()
+                    .any(|existing| existing == value)
+            {
+                headers.append(name.clone(), value.clone());
+            }
+        }
+        responses.push(response);
+    }
+    Ok((headers, axum::Json(responses)).into_response())
+}
+
+/// Whether the body of a GraphQL request is a batch, i.e. a JSON array of operations
+fn is_batch_request(body: &[u8]) -> bool {
+    body.iter()
+        .find(|byte| !byte.is_ascii_whitespace())
+        .is_some_and(|byte| *byte == b'[')
+}
+
+fn bad_batch_request(message: String) -> MiddlewareError {
+    MiddlewareError {
+        status: reqwest::StatusCode::BAD_REQUEST,
+        message,
+        is_internal: false,
+    }
+}
+
+/// Run a single operation of a batch, returning the headers and the JSON body of its response.
+/// Responses that are not JSON, such as those rejecting malformed operations, are turned into
+/// GraphQL errors so that every element of the batch response is a GraphQL response.
+async fn run_batched_operation(
+    next: Next,
+    parts: axum::http::request::Parts,
+    operation: serde_json::Value,
+) -> (HeaderMap, serde_json::Value) {
+    let request = Request::from_parts(parts, Body::from(operation.to_string()));
+    let (parts, body) = next.run(request).await.into_parts();
+    let response = match body.collect().await {
+        Ok(collected) => {
+            let bytes = collected.to_bytes();
+            serde_json::from_slice(&bytes)
+                .unwrap_or_else(|_| error_response(&String::from_utf8_lossy(&bytes)))
+        }
+        Err(err) => error_response(&err.to_string()),
+    };
+    (parts.headers, response)
+}
+
+fn error_response(message: &str) -> serde_json::Value {
+    serde_json::json!({ "errors": [{ "message": message }] })
+}
+
 /// Middleware to start tracing of the `/*path` request.
 /// This middleware must be active for the entire duration
 /// of the request i.e. this middleware should be the
@@ -225,3 +374,15 @@ pub async fn pre_route_request_tracing_middleware(
         .await
         .response
 }
+
+#[cfg(test)]
+mod tests {
+    use super::is_batch_request;
+
+    #[test]
+    fn test_is_batch_request() {
+        assert!(is_batch_request(b" \n[{\"query\": \"{ a }\"}]"));
+        assert!(!is_batch_request(b"{\"query\": \"{ a }\"}"));
+        assert!(!is_batch_request(b""));
+    }
+}
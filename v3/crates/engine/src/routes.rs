@@ -1,23 +1,29 @@
 mod graphql;
-pub use graphql::{handle_explain_request, handle_request, handle_websocket_request};
+pub use graphql::{
+    handle_explain_request, handle_get_request, handle_request, handle_websocket_request,
+};
 mod jsonapi;
 pub use jsonapi::create_json_api_router;
+mod query;
+pub use query::{execute_query_request, handle_query_request, QueryError, QueryResponse};
 
 use axum::{
     extract::DefaultBodyLimit,
     response::Html,
-    routing::{get, post},
+    routing::{get, post, MethodRouter},
     Router,
 };
 use base64::engine::Engine;
 use std::hash;
 use std::hash::{Hash, Hasher};
 use std::path::PathBuf;
+use tower::ServiceExt;
 use tower_http::cors::CorsLayer;
 use tower_http::trace::TraceLayer;
 
 use crate::{
-    authentication_middleware, build_cors_layer, explain_request_tracing_middleware,
+    authentication_middleware, batch_request_middleware, build_cors_layer,
+    endpoint_request_tracing_middleware, explain_request_tracing_middleware,
     graphql_request_tracing_middleware, middleware::pre_route_request_tracing_middleware,
     plugins_middleware, EngineState, StartupError,
 };
@@ -38,28 +44,24 @@ pub fn get_base_routes(state: EngineState) -> Router {
         .layer(TraceLayer::new_for_http())
         .with_state(state.clone());
 
-    let graphql_route = Router::new()
-        .route("/graphql", post(handle_request))
-        .layer(axum::middleware::from_fn_with_state(
-            graphql_frontend::build_state_with_middleware_error_converter(state.clone()),
-            plugins_middleware,
-        ))
-        .layer(axum::middleware::from_fn_with_state(
-            graphql_frontend::build_state_with_middleware_error_converter(()),
-            hasura_authn_core::resolve_session,
-        ))
-        .layer(axum::middleware::from_fn_with_state(
-            graphql_frontend::build_state_with_middleware_error_converter(state.clone()),
-            authentication_middleware,
-        ))
-        .layer(axum::middleware::from_fn(|request, next| {
-            graphql_request_tracing_middleware(RequestType::Http, request, next)
-        }))
-        // *PLEASE DO NOT ADD ANY MIDDLEWARE
-        // BEFORE THE `graphql_request_tracing_middleware`*
-        // Refer to it for more details.
-        .layer(TraceLayer::new_for_http())
-        .with_state(state.clone());
+    let graphql_route = graphql_http_route(post(handle_request), &state, true);
+
+    // GraphQL requests sent with GET share the '/graphql' route and method with websocket
+    // connections, but not their middleware, so they are dispatched to separate routers
+    let graphql_get_route = {
+        let graphql_http_get_route = graphql_http_route(get(handle_get_request), &state, false);
+        Router::new().route(
+            "/graphql",
+            get(move |request: axum::extract::Request| {
+                let router = if is_websocket_upgrade(request.headers()) {
+                    graphql_ws_route
+                } else {
+                    graphql_http_get_route
+                };
+                dispatch_request(router, request)
+            }),
+        )
+    };
 
     let explain_route = Router::new()
         .route("/v1/explain", post(handle_explain_request))
@@ -96,8 +98,8 @@ pub fn get_base_routes(state: EngineState) -> Router {
         .route("/", get(graphiql))
         // The '/graphql' route
         .merge(graphql_route)
-        // The '/graphql' route for websocket
-        .merge(graphql_ws_route)
+        // The '/graphql' route for websocket and GET requests
+        .merge(graphql_get_route)
         // The '/v1/explain' route
         .merge(explain_route)
         // The '/health' route
@@ -108,6 +110,65 @@ pub fn get_base_routes(state: EngineState) -> Router {
         .layer(DefaultBodyLimit::max(10 * MB))
 }
 
+/// The '/graphql' route for GraphQL requests sent over HTTP with the given method. Batched
+/// requests are only accepted when `accepts_batches` is set, as their body is an array of
+/// operations that cannot be sent with GET.
+fn graphql_http_route(
+    method_router: MethodRouter<EngineState>,
+    state: &EngineState,
+    accepts_batches: bool,
+) -> Router {
+    let route =
+        Router::new()
+            .route("/graphql", method_router)
+            .layer(axum::middleware::from_fn_with_state(
+                graphql_frontend::build_state_with_middleware_error_converter(state.clone()),
+                plugins_middleware,
+            ));
+    let route = if accepts_batches {
+        route.layer(axum::middleware::from_fn_with_state(
+            graphql_frontend::build_state_with_middleware_error_converter(state.clone()),
+            batch_request_middleware,
+        ))
+    } else {
+        route
+    };
+    route
+        .layer(axum::middleware::from_fn_with_state(
+            graphql_frontend::build_state_with_middleware_error_converter(()),
+            hasura_authn_core::resolve_session,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            graphql_frontend::build_state_with_middleware_error_converter(state.clone()),
+            authentication_middleware,
+        ))
+        .layer(axum::middleware::from_fn(|request, next| {
+            graphql_request_tracing_middleware(RequestType::Http, request, next)
+        }))
+        // *PLEASE DO NOT ADD ANY MIDDLEWARE
+        // BEFORE THE `graphql_request_tracing_middleware`*
+        // Refer to it for more details.
+        .layer(TraceLayer::new_for_http())
+        .with_state(state.clone())
+}
+
+fn is_websocket_upgrade(headers: &axum::http::HeaderMap) -> bool {
+    headers
+        .get(axum::http::header::UPGRADE)
+        .and_then(|upgrade| upgrade.to_str().ok())
+        .is_some_and(|upgrade| upgrade.eq_ignore_ascii_case("websocket"))
+}
+
+async fn dispatch_request(
+    router: Router,
+    request: axum::extract::Request,
+) -> axum::response::Response {
+    match router.oneshot(request).await {
+        Ok(response) => response,
+        Err(infallible) => match infallible {},
+    }
+}
+
 /// Serve the introspection metadata file and its hash at `/metadata` and `/metadata-hash` respectively.
 /// This is a temporary workaround to enable the console to interact with an engine process running locally.
 pub async fn get_metadata_routes(
@@ -130,6 +191,29 @@ pub fn get_jsonapi_route(state: EngineState) -> Router {
     create_json_api_router(state)
 }
 
+/// The experimental '/v1/query' route, executing OpenDD query requests. It is only served when
+/// the `enable_query_endpoint` unstable feature is enabled.
+pub fn get_query_route(state: EngineState) -> Router {
+    Router::new()
+        .route("/v1/query", post(handle_query_request))
+        .layer(axum::middleware::from_fn_with_state(
+            query::build_state_with_middleware_error_converter(()),
+            hasura_authn_core::resolve_session,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            query::build_state_with_middleware_error_converter(state.clone()),
+            authentication_middleware,
+        ))
+        .layer(axum::middleware::from_fn(|request, next| {
+            endpoint_request_tracing_middleware("/v1/query", request, next)
+        }))
+        // *PLEASE DO NOT ADD ANY MIDDLEWARE
+        // BEFORE THE `endpoint_request_tracing_middleware`*
+        // Refer to it for more details.
+        .layer(TraceLayer::new_for_http())
+        .with_state(state)
+}
+
 pub fn get_cors_layer(allow_origin: &[String]) -> CorsLayer {
     build_cors_layer(allow_origin)
 }
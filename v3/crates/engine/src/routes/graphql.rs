@@ -1,24 +1,182 @@
+use std::hash::{self, Hash, Hasher};
 use std::sync::Arc;
 
 use axum::{
-    extract::{ConnectInfo, State},
+    extract::{ConnectInfo, Query, State},
+    http::{header, HeaderValue, StatusCode},
     response::IntoResponse,
     Extension, Json,
 };
-use futures_util::FutureExt;
+use base64::engine::Engine;
+use futures_util::{FutureExt, StreamExt};
 
 use crate::EngineState;
+use graphql_frontend::{IncrementalPart, IncrementalSender, RequestMethod};
 use hasura_authn_core::Session;
 use lang_graphql as gql;
 use tracing_util::{set_status_on_current_span, SpanVisibility};
 
-#[allow(clippy::print_stdout)]
 pub async fn handle_request(
     headers: axum::http::header::HeaderMap,
     State(state): State<EngineState>,
     Extension(session): Extension<Session>,
     Json(request): Json<gql::http::RawRequest>,
-) -> gql::http::Response {
+) -> axum::response::Response {
+    // pre-response plugins are sent the whole response, so it is not delivered incrementally
+    // when there are any
+    if accepts_multipart_mixed(&headers) && state.plugin_configs.pre_response_plugins.is_empty() {
+        return execute_request_incrementally(state, session, headers, request).await;
+    }
+    execute_request(state, session, headers, request, RequestMethod::Post, None)
+        .await
+        .inner()
+        .into_response()
+}
+
+/// Whether the client accepts `multipart/mixed` responses, which is how responses with deferred
+/// fragments and streamed lists are delivered incrementally
+fn accepts_multipart_mixed(headers: &axum::http::header::HeaderMap) -> bool {
+    headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|media_type| media_type.trim().starts_with("multipart/mixed"))
+}
+
+/// The closing delimiter of a `multipart/mixed` response
+const MULTIPART_CLOSING_DELIMITER: &str = "\r\n-----\r\n";
+
+/// Encodes a payload of an incrementally delivered response as a part of a `multipart/mixed`
+/// response, whose boundary is `-`
+fn encode_multipart_part(payload: &serde_json::Value) -> String {
+    format!("\r\n---\r\nContent-Type: application/json; charset=utf-8\r\n\r\n{payload}")
+}
+
+/// Executes a request whose response is delivered incrementally as a `multipart/mixed` response,
+/// with a part for the initial response and for each subsequent payload. The response is a JSON
+/// response as usual when the query neither defers fragments nor streams lists.
+async fn execute_request_incrementally(
+    state: EngineState,
+    session: Session,
+    headers: axum::http::header::HeaderMap,
+    request: gql::http::RawRequest,
+) -> axum::response::Response {
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+    let mut execution = Box::pin(execute_request(
+        state,
+        session,
+        headers,
+        request,
+        RequestMethod::Post,
+        Some(sender),
+    ));
+
+    // the initial response is always the first part sent
+    let initial_part = tokio::select! {
+        biased;
+        Some(part) = receiver.recv() => part,
+        response = &mut execution => return response.inner().into_response(),
+    };
+    let mut response_headers = match &initial_part {
+        IncrementalPart::Initial { headers, .. } => headers.clone(),
+        IncrementalPart::Subsequent { .. } => axum::http::header::HeaderMap::new(),
+    };
+    response_headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("multipart/mixed; boundary=\"-\""),
+    );
+
+    // the execution sends the remaining parts, and is driven along with the body
+    let remaining_parts = futures_util::stream::select(
+        execution
+            .into_stream()
+            .filter_map(|_response| futures_util::future::ready(None)),
+        futures_util::stream::poll_fn(move |cx| receiver.poll_recv(cx)),
+    );
+    let body = futures_util::stream::once(futures_util::future::ready(initial_part))
+        .chain(remaining_parts)
+        .map(|part| encode_multipart_part(part.payload()))
+        .chain(futures_util::stream::once(futures_util::future::ready(
+            MULTIPART_CLOSING_DELIMITER.to_string(),
+        )))
+        .map(Ok::<_, std::convert::Infallible>);
+    (
+        StatusCode::OK,
+        response_headers,
+        axum::body::Body::from_stream(body),
+    )
+        .into_response()
+}
+
+/// The parameters of a GraphQL request sent with GET, which are in the query string of the URL.
+/// The variables and extensions are JSON-encoded.
+/// Ref: <https://graphql.github.io/graphql-over-http/draft/#sec-GET>
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetRequestParams {
+    query: Option<String>,
+    operation_name: Option<String>,
+    variables: Option<String>,
+    extensions: Option<String>,
+}
+
+impl GetRequestParams {
+    fn into_raw_request(self) -> Result<gql::http::RawRequest, serde_json::Error> {
+        let parse_json = |value: Option<String>| {
+            value
+                .map(|value| serde_json::from_str::<serde_json::Value>(&value))
+                .transpose()
+        };
+        serde_json::from_value(serde_json::json!({
+            "query": self.query.unwrap_or_default(),
+            "operationName": self.operation_name,
+            "variables": parse_json(self.variables)?,
+            "extensions": parse_json(self.extensions)?,
+        }))
+    }
+}
+
+pub async fn handle_get_request(
+    headers: axum::http::header::HeaderMap,
+    State(state): State<EngineState>,
+    Extension(session): Extension<Session>,
+    Query(params): Query<GetRequestParams>,
+) -> axum::response::Response {
+    let request = match params.into_raw_request() {
+        Ok(request) => request,
+        Err(err) => {
+            return gql::http::Response::error_message_with_status(
+                StatusCode::BAD_REQUEST,
+                format!("invalid GraphQL request parameters: {err}"),
+                false,
+            )
+            .into_response()
+        }
+    };
+    let response = execute_request(
+        state,
+        session,
+        headers.clone(),
+        request,
+        RequestMethod::Get,
+        None,
+    )
+    .await
+    .inner();
+    into_cacheable_response(response, &headers)
+}
+
+#[allow(clippy::print_stdout)]
+async fn execute_request(
+    state: EngineState,
+    session: Session,
+    headers: axum::http::header::HeaderMap,
+    request: gql::http::RawRequest,
+    request_method: RequestMethod,
+    incremental_sender: Option<IncrementalSender>,
+) -> graphql_frontend::GraphQLResponse {
+    let http_context = state.http_context.for_request();
     let tracer = tracing_util::global_tracer();
     let response = tracer
         .in_span_async(
@@ -29,18 +187,38 @@ pub async fn handle_request(
                 {
                     Box::pin(async move {
                         let (_operation_type, graphql_response, execution_plans_match) =
-                            graphql_frontend::execute_query(
-                                state.request_pipeline,
-                                state.expose_internal_errors,
-                                &state.http_context,
-                                &state.graphql_state,
-                                &state.resolved_metadata,
-                                &session,
-                                &headers,
-                                request,
-                                None,
-                            )
-                            .await;
+                            match &incremental_sender {
+                                Some(incremental_sender) => {
+                                    graphql_frontend::execute_query_incrementally(
+                                        state.request_pipeline,
+                                        state.expose_internal_errors,
+                                        &http_context,
+                                        &state.graphql_state,
+                                        &state.resolved_metadata,
+                                        &session,
+                                        &headers,
+                                        request,
+                                        None,
+                                        incremental_sender,
+                                    )
+                                    .await
+                                }
+                                None => {
+                                    graphql_frontend::execute_query(
+                                        state.request_pipeline,
+                                        state.expose_internal_errors,
+                                        &http_context,
+                                        &state.graphql_state,
+                                        &state.resolved_metadata,
+                                        &session,
+                                        &headers,
+                                        request,
+                                        request_method,
+                                        None,
+                                    )
+                                    .await
+                                }
+                            };
 
                         // if our execution plans do not match, emit a trace that we can track in
                         // Grafana
@@ -69,7 +247,67 @@ pub async fn handle_request(
     // The only way to determine the error is to inspect the status code from the `Response` struct.
     // In `/graphql` API, all responses are sent with `200` OK including errors, which leaves no way to deduce errors in the tracing middleware.
     set_status_on_current_span(&response);
-    response.inner()
+    response
+}
+
+/// Adds an `ETag` header to a response that can be cached, which is one with a `Cache-Control`
+/// header. When the `If-None-Match` header of the request matches it, the response is replaced
+/// with a `304 Not Modified` response without a body, which keeps the caching headers.
+fn into_cacheable_response(
+    response: gql::http::Response,
+    request_headers: &axum::http::header::HeaderMap,
+) -> axum::response::Response {
+    if !response.headers.contains_key(header::CACHE_CONTROL) {
+        return response.into_response();
+    }
+    let Ok(body) = serde_json::to_vec(&response) else {
+        return response.into_response();
+    };
+    let etag = make_etag(&body);
+
+    if etag_matches(request_headers, &etag) {
+        let mut headers = axum::http::header::HeaderMap::new();
+        for name in [header::CACHE_CONTROL, header::VARY] {
+            if let Some(value) = response.headers.get(&name) {
+                headers.insert(name, value.clone());
+            }
+        }
+        headers.insert(header::ETAG, etag);
+        return (StatusCode::NOT_MODIFIED, headers).into_response();
+    }
+
+    let mut headers = response.headers;
+    headers.insert(header::ETAG, etag);
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    (response.status_code, headers, body).into_response()
+}
+
+/// A strong entity tag for the body of a response
+fn make_etag(body: &[u8]) -> HeaderValue {
+    let mut hasher = hash::DefaultHasher::new();
+    body.hash(&mut hasher);
+    let hash =
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(hasher.finish().to_be_bytes());
+    HeaderValue::from_str(&format!("\"{hash}\"")).expect("entity tag is a valid header value")
+}
+
+/// Whether any of the entity tags in the `If-None-Match` headers of a request matches the entity
+/// tag of the response, using the weak comparison.
+/// Ref: <https://www.rfc-editor.org/rfc/rfc9110#name-if-none-match>
+fn etag_matches(request_headers: &axum::http::header::HeaderMap, etag: &HeaderValue) -> bool {
+    let Ok(etag) = etag.to_str() else {
+        return false;
+    };
+    request_headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == etag)
 }
 
 pub async fn handle_explain_request(
@@ -78,6 +316,7 @@ pub async fn handle_explain_request(
     Extension(session): Extension<Session>,
     Json(request): Json<gql::http::RawRequest>,
 ) -> graphql_frontend::ExplainResponse {
+    let http_context = state.http_context.for_request();
     let tracer = tracing_util::global_tracer();
     let response = tracer
         .in_span_async(
@@ -89,12 +328,13 @@ pub async fn handle_explain_request(
                     graphql_frontend::execute_explain(
                         state.request_pipeline,
                         state.expose_internal_errors,
-                        &state.http_context,
+                        &http_context,
                         &state.graphql_state,
                         &state.resolved_metadata,
                         &session,
                         &headers,
                         request,
+                        None, // project_id is not needed for OSS v3-engine.
                     )
                     .map(|(_operation_type, graphql_response)| graphql_response),
                 )
@@ -132,3 +372,105 @@ pub async fn handle_websocket_request(
         .graphql_websocket_server
         .upgrade_and_handle_websocket(client_address, ws, context)
 }
+
+#[cfg(test)]
+mod test {
+    use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+    use indexmap::IndexMap;
+    use lang_graphql as gql;
+    use pretty_assertions::assert_eq;
+
+    use super::{
+        accepts_multipart_mixed, encode_multipart_part, into_cacheable_response, GetRequestParams,
+    };
+
+    #[test]
+    fn test_get_request_params() {
+        let params = GetRequestParams {
+            query: Some("query Author($id: Int!) { author(id: $id) { name } }".to_string()),
+            operation_name: Some("Author".to_string()),
+            variables: Some(r#"{"id": 1}"#.to_string()),
+            extensions: None,
+        };
+        let request = params.into_raw_request().unwrap();
+        assert_eq!(
+            request.operation_name.map(|name| name.to_string()),
+            Some("Author".to_string())
+        );
+        assert_eq!(
+            serde_json::to_value(request.variables).unwrap(),
+            serde_json::json!({"id": 1})
+        );
+
+        let params = GetRequestParams {
+            query: Some("{ authors { name } }".to_string()),
+            operation_name: None,
+            variables: Some("{".to_string()),
+            extensions: None,
+        };
+        assert!(params.into_raw_request().is_err());
+    }
+
+    #[test]
+    fn test_accepts_multipart_mixed() {
+        let mut headers = HeaderMap::new();
+        assert!(!accepts_multipart_mixed(&headers));
+        headers.insert(header::ACCEPT, HeaderValue::from_static("application/json"));
+        assert!(!accepts_multipart_mixed(&headers));
+        headers.insert(
+            header::ACCEPT,
+            HeaderValue::from_static("multipart/mixed;deferSpec=20220824, application/json;q=0.9"),
+        );
+        assert!(accepts_multipart_mixed(&headers));
+    }
+
+    #[test]
+    fn test_encode_multipart_part() {
+        assert_eq!(
+            encode_multipart_part(&serde_json::json!({"data": {"foo": 1}, "hasNext": true})),
+            "\r\n---\r\nContent-Type: application/json; charset=utf-8\r\n\r\n{\"data\":{\"foo\":1},\"hasNext\":true}"
+        );
+    }
+
+    #[test]
+    fn test_cacheable_response() {
+        let make_response = || {
+            let mut response = gql::http::Response::ok(IndexMap::new());
+            response.headers.insert(
+                header::CACHE_CONTROL,
+                HeaderValue::from_static("public, max-age=60"),
+            );
+            response.headers.insert(
+                header::VARY,
+                HeaderValue::from_static("authorization, cookie, x-hasura-role"),
+            );
+            response
+        };
+
+        let response = into_cacheable_response(make_response(), &HeaderMap::new());
+        assert_eq!(response.status(), StatusCode::OK);
+        let etag = response.headers().get(header::ETAG).unwrap().clone();
+
+        let mut request_headers = HeaderMap::new();
+        request_headers.insert(header::IF_NONE_MATCH, etag.clone());
+        let response = into_cacheable_response(make_response(), &request_headers);
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers().get(header::ETAG), Some(&etag));
+        assert_eq!(
+            response.headers().get(header::CACHE_CONTROL),
+            Some(&HeaderValue::from_static("public, max-age=60"))
+        );
+        assert_eq!(
+            response.headers().get(header::VARY),
+            Some(&HeaderValue::from_static(
+                "authorization, cookie, x-hasura-role"
+            ))
+        );
+
+        // responses without a `Cache-Control` header are not given an entity tag
+        let response =
+            into_cacheable_response(gql::http::Response::ok(IndexMap::new()), &request_headers);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().get(header::ETAG).is_none());
+    }
+}
@@ -28,10 +28,15 @@ pub fn create_json_api_router(state: EngineState) -> axum::Router {
 fn build_router(state: EngineState, endpoint: jsonapi::EndPoint) -> axum::Router {
     Router::new()
         .route("/__schema", get(handle_jsonapi_schema))
-        // TODO: update method GET; for now we are only supporting queries. And
-        // in JSON:API spec, all queries have the GET method. Not even HEAD is
-        // supported. So this should be fine.
-        .route("/*path", get(handle_jsonapi_request))
+        // resources are fetched with GET, and created, updated and deleted with POST, PATCH and
+        // DELETE when their model is backed by procedures. HEAD is not supported.
+        .route(
+            "/*path",
+            get(handle_jsonapi_request)
+                .post(handle_jsonapi_request)
+                .patch(handle_jsonapi_request)
+                .delete(handle_jsonapi_request),
+        )
         .layer(axum::middleware::from_fn_with_state(
             jsonapi::build_state_with_middleware_error_converter(()),
             hasura_authn_core::resolve_session,
@@ -104,8 +109,15 @@ async fn handle_jsonapi_request(
     axum::extract::RawQuery(raw_query): axum::extract::RawQuery,
     axum::extract::State(state): axum::extract::State<EngineState>,
     Extension(session): Extension<Session>,
+    request_body: axum::body::Bytes,
 ) -> impl IntoResponse {
     let tracer = tracing_util::global_tracer();
+    // spec: <https://jsonapi.org/format/#crud>
+    let success_status = match method {
+        Method::POST => axum::http::StatusCode::CREATED,
+        Method::DELETE => axum::http::StatusCode::NO_CONTENT,
+        _ => axum::http::StatusCode::OK,
+    };
     let response = tracer
         .in_span_async(
             "handle_jsonapi_request",
@@ -114,13 +126,14 @@ async fn handle_jsonapi_request(
             || {
                 Box::pin(jsonapi::handler_internal(
                     Arc::new(request_headers),
-                    Arc::new(state.http_context.clone()),
+                    Arc::new(state.http_context.for_request()),
                     Arc::new(session),
                     &state.jsonapi_catalog,
                     state.resolved_metadata,
                     method,
                     uri,
                     jsonapi_library::query::Query::from_params(&raw_query.unwrap_or_default()),
+                    request_body,
                 ))
             },
         )
@@ -128,7 +141,10 @@ async fn handle_jsonapi_request(
 
     set_status_on_current_span(&response);
     match response {
-        Ok(r) => (axum::http::StatusCode::OK, Json(r)).into_response(),
+        Ok(_) if success_status == axum::http::StatusCode::NO_CONTENT => {
+            success_status.into_response()
+        }
+        Ok(r) => (success_status, Json(r)).into_response(),
         Err(e) => e.into_http_error().into_response(),
     }
 }
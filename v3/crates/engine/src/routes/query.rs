@@ -0,0 +1,288 @@
+use axum::{extract::State, response::IntoResponse, Extension, Json};
+use engine_types::{ExposeInternalErrors, HttpContext};
+use hasura_authn_core::Session;
+use indexmap::IndexMap;
+use metadata_resolve::Metadata;
+use open_dds::query::{Alias, Query, QueryRequest};
+use plan_types::{
+    NDCMutationExecution, NDCQueryExecution, ProcessResponseAs, FUNCTION_IR_VALUE_COLUMN_NAME,
+};
+use serde::Serialize;
+use tracing_util::{
+    set_status_on_current_span, ErrorVisibility, SpanVisibility, Traceable, TraceableError,
+};
+
+use crate::EngineState;
+
+/// Errors raised while planning or executing a `/v1/query` request. Execution errors are
+/// reported in the response next to the alias of the query, and do not fail the other queries
+/// of the request.
+#[derive(Debug, thiserror::Error)]
+pub enum QueryError {
+    #[error("{0}")]
+    Plan(#[from] plan::PlanError),
+    #[error("{0}")]
+    Execute(#[from] execute::FieldError),
+    #[error("no result was returned by the data connector")]
+    EmptyResponse,
+    #[error("could not serialize the result: {0}")]
+    Serialize(#[from] serde_json::Error),
+}
+
+impl TraceableError for QueryError {
+    fn visibility(&self) -> ErrorVisibility {
+        match self {
+            Self::Plan(error) => error.visibility(),
+            Self::Execute(error) => error.visibility(),
+            Self::EmptyResponse | Self::Serialize(_) => ErrorVisibility::Internal,
+        }
+    }
+}
+
+/// The response of a `/v1/query` request: the result or the error of each query, keyed by
+/// the alias of the query, in the order of the request. Requests are planned as a whole, so a
+/// planning error fails the entire request.
+pub struct QueryResponse {
+    results: Result<IndexMap<Alias, Result<serde_json::Value, QueryError>>, QueryError>,
+    expose_internal_errors: ExposeInternalErrors,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+enum QueryResult {
+    Result(serde_json::Value),
+    Error { message: String },
+}
+
+impl QueryResponse {
+    pub fn new(
+        results: Result<IndexMap<Alias, Result<serde_json::Value, QueryError>>, QueryError>,
+        expose_internal_errors: ExposeInternalErrors,
+    ) -> Self {
+        Self {
+            results,
+            expose_internal_errors,
+        }
+    }
+
+    fn error_message(&self, error: &QueryError) -> String {
+        match (error.visibility(), self.expose_internal_errors) {
+            (ErrorVisibility::Internal, ExposeInternalErrors::Censor) => {
+                "internal error".to_string()
+            }
+            _ => error.to_string(),
+        }
+    }
+
+    /// The status and the JSON body of the response
+    pub fn to_json(&self) -> (axum::http::StatusCode, serde_json::Value) {
+        match &self.results {
+            Ok(results) => {
+                let results = results
+                    .iter()
+                    .map(|(alias, result)| {
+                        let query_result = match result {
+                            Ok(value) => QueryResult::Result(value.clone()),
+                            Err(error) => QueryResult::Error {
+                                message: self.error_message(error),
+                            },
+                        };
+                        (alias, query_result)
+                    })
+                    .collect::<IndexMap<_, _>>();
+                // errors of the planned queries are reported per query, so the request itself
+                // succeeds
+                (axum::http::StatusCode::OK, serde_json::json!(results))
+            }
+            Err(error) => {
+                let status = match error.visibility() {
+                    ErrorVisibility::User => axum::http::StatusCode::BAD_REQUEST,
+                    ErrorVisibility::Internal => axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                };
+                (
+                    status,
+                    serde_json::json!({ "error": { "message": self.error_message(error) } }),
+                )
+            }
+        }
+    }
+}
+
+impl IntoResponse for QueryResponse {
+    fn into_response(self) -> axum::response::Response {
+        let (status, body) = self.to_json();
+        (status, Json(body)).into_response()
+    }
+}
+
+impl Traceable for QueryResponse {
+    type ErrorType<'a> = <Result<serde_json::Value, QueryError> as Traceable>::ErrorType<'a>;
+
+    fn get_error(&self) -> Option<Self::ErrorType<'_>> {
+        match &self.results {
+            Ok(results) => results.values().find_map(Traceable::get_error),
+            Err(_) => self.results.get_error(),
+        }
+    }
+}
+
+/// Build a middleware state whose errors are returned like the errors of a `/v1/query` request
+pub fn build_state_with_middleware_error_converter<S>(
+    state: S,
+) -> engine_types::WithMiddlewareErrorConverter<S> {
+    engine_types::WithMiddlewareErrorConverter::new(state, |error| {
+        let message = if error.is_internal {
+            "internal error".to_string()
+        } else {
+            error.message
+        };
+        (
+            error.status,
+            Json(serde_json::json!({ "error": { "message": message } })),
+        )
+            .into_response()
+    })
+}
+
+pub async fn handle_query_request(
+    headers: axum::http::header::HeaderMap,
+    State(state): State<EngineState>,
+    Extension(session): Extension<Session>,
+    Json(request): Json<QueryRequest>,
+) -> QueryResponse {
+    let http_context = state.http_context.for_request();
+    let tracer = tracing_util::global_tracer();
+    let response = tracer
+        .in_span_async(
+            "handle_query_request",
+            "Handle query request",
+            SpanVisibility::User,
+            || {
+                Box::pin(async {
+                    QueryResponse::new(
+                        execute_query_request(
+                            &request,
+                            &state.resolved_metadata,
+                            &session,
+                            &http_context,
+                            &headers,
+                        )
+                        .await,
+                        state.expose_internal_errors,
+                    )
+                })
+            },
+        )
+        .await;
+
+    // Like `/graphql`, responses with per-query errors are sent with `200` OK, so errors are
+    // marked on the span here
+    set_status_on_current_span(&response);
+    response
+}
+
+/// Plan the request, then execute its queries in parallel. A request is planned as a whole
+/// with `plan::plan_query_request`, which allows either queries or a single procedure.
+pub async fn execute_query_request(
+    request: &QueryRequest,
+    metadata: &Metadata,
+    session: &Session,
+    http_context: &HttpContext,
+    request_headers: &axum::http::header::HeaderMap,
+) -> Result<IndexMap<Alias, Result<serde_json::Value, QueryError>>, QueryError> {
+    let QueryRequest::V1(request_v1) = request;
+    let execution_plan = plan::plan_query_request(request, metadata, session, request_headers)?;
+
+    match execution_plan {
+        plan::ExecutionPlan::Queries(execution_trees) => {
+            let queries = execution_trees
+                .into_iter()
+                .filter_map(|(alias, execution_tree)| {
+                    let query = request_v1.queries.get(&alias)?;
+                    Some(async move {
+                        let result = execute_query(http_context, query, execution_tree).await;
+                        (alias, result)
+                    })
+                });
+            Ok(futures_util::future::join_all(queries)
+                .await
+                .into_iter()
+                .collect())
+        }
+        plan::ExecutionPlan::Mutation(execution_tree) => {
+            // a procedure is only planned for a request made of that single procedure
+            let alias = request_v1.queries.keys().next().ok_or_else(|| {
+                plan::PlanError::Internal("procedure planned for an empty request".into())
+            })?;
+            let result = execute_procedure(http_context, execution_tree).await;
+            Ok(IndexMap::from_iter([(alias.clone(), result)]))
+        }
+    }
+}
+
+// execute a model, aggregate, groups or function query, returning what the query selected
+async fn execute_query(
+    http_context: &HttpContext,
+    query: &Query,
+    execution_tree: plan_types::QueryExecutionTree,
+) -> Result<serde_json::Value, QueryError> {
+    let process_response_as = match query {
+        Query::ModelAggregate(_) => ProcessResponseAs::Aggregates,
+        Query::Command(_) => ProcessResponseAs::Object { is_nullable: true },
+        Query::ModelGroups(_) => ProcessResponseAs::Groups,
+        Query::Model(_) => ProcessResponseAs::Array { is_nullable: false },
+    };
+    let ndc_query_execution = NDCQueryExecution {
+        execution_span_attribute: "QUERY",
+        execution_tree,
+        field_span_attribute: "QUERY".into(),
+        process_response_as,
+    };
+    let rowset = execute::resolve_ndc_query_execution(http_context, ndc_query_execution, None)
+        .await?
+        .into_iter()
+        .next()
+        .ok_or(QueryError::EmptyResponse)?;
+
+    match query {
+        Query::Model(_) => Ok(serde_json::to_value(rowset.rows.unwrap_or_default())?),
+        Query::ModelAggregate(_) => {
+            Ok(serde_json::to_value(rowset.aggregates.unwrap_or_default())?)
+        }
+        Query::ModelGroups(_) => Ok(serde_json::to_value(rowset.groups.unwrap_or_default())?),
+        // functions return their result in a single row
+        Query::Command(_) => rowset
+            .rows
+            .and_then(|rows| rows.into_iter().next())
+            .and_then(|mut row| row.swap_remove(FUNCTION_IR_VALUE_COLUMN_NAME))
+            .map(|row_field_value| row_field_value.0)
+            .ok_or(QueryError::EmptyResponse),
+    }
+}
+
+// execute a procedure, returning its result
+async fn execute_procedure(
+    http_context: &HttpContext,
+    execution_tree: plan_types::MutationExecutionTree,
+) -> Result<serde_json::Value, QueryError> {
+    let data_connector = execution_tree
+        .mutation_execution_plan
+        .data_connector
+        .clone();
+    let ndc_mutation_execution = NDCMutationExecution {
+        execution_tree,
+        process_response_as: ProcessResponseAs::Object { is_nullable: true },
+        data_connector,
+        execution_span_attribute: "QUERY",
+        field_span_attribute: "QUERY".into(),
+    };
+    let mutation_response =
+        execute::resolve_ndc_mutation_execution(http_context, ndc_mutation_execution, None).await?;
+    // a single procedure is executed
+    mutation_response
+        .operation_results
+        .into_iter()
+        .next()
+        .map(|ndc_models::MutationOperationResults::Procedure { result }| result)
+        .ok_or(QueryError::EmptyResponse)
+}
@@ -1,5 +1,8 @@
 use crate::{EngineState, StartupError};
-use engine_types::{ExposeInternalErrors, HttpContext};
+use engine_types::{
+    CircuitBreakers, ExposeInternalErrors, HttpContext, PersistedQueryCache, RemoteJoinQueryLimit,
+    ReplicaPools, ResponseCache,
+};
 use graphql_ir::GraphqlRequestPipeline;
 use std::fmt::Display;
 use std::sync::Arc;
@@ -55,6 +58,7 @@ pub fn build_state(
     expose_internal_errors: ExposeInternalErrors,
     auth_config: hasura_authn::ResolvedAuthConfig,
     resolved_metadata: metadata_resolve::Metadata,
+    max_concurrent_remote_join_queries: usize,
 ) -> Result<EngineState, anyhow::Error> {
     // Metadata
     let resolved_metadata = Arc::new(resolved_metadata);
@@ -62,7 +66,14 @@ pub fn build_state(
     let http_context = HttpContext {
         client: reqwest::Client::new(),
         ndc_response_size_limit: None,
+        ndc_circuit_breakers: CircuitBreakers::default(),
+        ndc_replica_pools: ReplicaPools::default(),
+        ndc_response_cache: ResponseCache::default(),
+        remote_join_query_limit: RemoteJoinQueryLimit::new(max_concurrent_remote_join_queries),
+        persisted_queries: PersistedQueryCache::default(),
     };
+    // the health checks stop once the metadata is dropped
+    execute::ndc::start_read_replica_health_checks(&http_context, &resolved_metadata);
     let plugin_configs = resolved_metadata.plugin_configs.clone();
 
     let schema = graphql_schema::GDS {
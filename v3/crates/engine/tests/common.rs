@@ -1,6 +1,6 @@
 use anyhow::anyhow;
 use goldenfile::{differs::text_diff, Mint};
-use graphql_frontend::execute_query;
+use graphql_frontend::{execute_query, RequestMethod};
 use graphql_ir::GraphqlRequestPipeline;
 use graphql_schema::GDS;
 use hasura_authn_core::{
@@ -23,7 +23,10 @@ use std::{
 };
 extern crate json_value_merge;
 use axum::http::{HeaderMap, Method, Uri};
-use engine_types::{ExposeInternalErrors, HttpContext, ProjectId};
+use engine_types::{
+    CircuitBreakers, ExposeInternalErrors, HttpContext, PersistedQueryCache, ProjectId,
+    RemoteJoinQueryLimit, ReplicaPools, ResponseCache,
+};
 use json_value_merge::Merge;
 use jsonapi_library::query::Query;
 use serde_json::Value;
@@ -37,6 +40,11 @@ pub fn setup(test_dir: &Path) -> GoldenTestContext {
     let http_context = HttpContext {
         client: reqwest::Client::new(),
         ndc_response_size_limit: None,
+        ndc_circuit_breakers: CircuitBreakers::default(),
+        ndc_replica_pools: ReplicaPools::default(),
+        ndc_response_cache: ResponseCache::default(),
+        remote_join_query_limit: RemoteJoinQueryLimit::default(),
+        persisted_queries: PersistedQueryCache::default(),
     };
     let mint = Mint::new(test_dir);
     GoldenTestContext { http_context, mint }
@@ -142,6 +150,7 @@ pub(crate) fn test_introspection_expectation(
             operation_name: None,
             query,
             variables: None,
+            extensions: None,
         };
 
         // Execute the test
@@ -156,6 +165,7 @@ pub(crate) fn test_introspection_expectation(
                 session,
                 &request_headers,
                 raw_request.clone(),
+                RequestMethod::Post,
                 None,
             )
             .await;
@@ -171,6 +181,7 @@ pub(crate) fn test_introspection_expectation(
                 session,
                 &request_headers,
                 raw_request.clone(),
+                RequestMethod::Post,
                 None,
             )
             .await;
@@ -213,18 +224,39 @@ pub fn test_execution_expectation(
 enum JsonApiRequest {
     #[serde(rename = "GET")]
     Get(String),
+    #[serde(rename = "POST")]
+    Post(String, serde_json::Value),
+    #[serde(rename = "PATCH")]
+    Patch(String, serde_json::Value),
+    #[serde(rename = "DELETE")]
+    Delete(String),
 }
 
 impl JsonApiRequest {
     fn to_method(&self) -> Method {
         match self {
             JsonApiRequest::Get(_) => Method::GET,
+            JsonApiRequest::Post(_, _) => Method::POST,
+            JsonApiRequest::Patch(_, _) => Method::PATCH,
+            JsonApiRequest::Delete(_) => Method::DELETE,
         }
     }
 
     fn to_path(&self) -> &str {
         match self {
-            JsonApiRequest::Get(path) => path,
+            JsonApiRequest::Get(path)
+            | JsonApiRequest::Post(path, _)
+            | JsonApiRequest::Patch(path, _)
+            | JsonApiRequest::Delete(path) => path,
+        }
+    }
+
+    fn to_body(&self) -> anyhow::Result<axum::body::Bytes> {
+        match self {
+            JsonApiRequest::Get(_) | JsonApiRequest::Delete(_) => Ok(axum::body::Bytes::new()),
+            JsonApiRequest::Post(_, body) | JsonApiRequest::Patch(_, body) => {
+                Ok(serde_json::to_vec(body)?.into())
+            }
         }
     }
 }
@@ -275,6 +307,7 @@ async fn test_jsonapi(
                 request.to_method(),
                 Uri::try_from(&path).map_err(|e| anyhow::anyhow!("Invalid URI: {}", e))?,
                 Query::from_params(&query_params),
+                request.to_body()?,
             )
             .await;
 
@@ -447,6 +480,7 @@ pub fn test_execution_expectation_for_multiple_ndc_versions(
                         operation_name: None,
                         query: query.clone(),
                         variables: None,
+                        extensions: None,
                     };
                     for session in &sessions {
                         let (_, response, _) = execute_query(
@@ -458,6 +492,7 @@ pub fn test_execution_expectation_for_multiple_ndc_versions(
                             session,
                             &request_headers,
                             raw_request.clone(),
+                            RequestMethod::Post,
                             None,
                         )
                         .await;
@@ -506,6 +541,7 @@ pub fn test_execution_expectation_for_multiple_ndc_versions(
                             session,
                             &request_headers,
                             raw_request.clone(),
+                            RequestMethod::Post,
                             None,
                         )
                         .await;
@@ -533,6 +569,7 @@ pub fn test_execution_expectation_for_multiple_ndc_versions(
                             operation_name: None,
                             query: query.clone(),
                             variables: Some(variables),
+                            extensions: None,
                         };
                         // do actual test
                         let (_, response, _) = execute_query(
@@ -544,6 +581,7 @@ pub fn test_execution_expectation_for_multiple_ndc_versions(
                             session,
                             &request_headers,
                             raw_request.clone(),
+                            RequestMethod::Post,
                             None,
                         )
                         .await;
@@ -593,6 +631,7 @@ pub fn test_execution_expectation_for_multiple_ndc_versions(
                             session,
                             &request_headers,
                             raw_request.clone(),
+                            RequestMethod::Post,
                             None,
                         )
                         .await;
@@ -729,6 +768,7 @@ pub fn test_execute_explain(
             operation_name: None,
             query,
             variables: None,
+            extensions: None,
         };
         let (_, raw_response) = graphql_frontend::execute_explain(
             GraphqlRequestPipeline::Old,
@@ -739,6 +779,7 @@ pub fn test_execute_explain(
             &session,
             &request_headers,
             raw_request,
+            None,
         )
         .await;
 
@@ -761,6 +802,73 @@ pub fn test_execute_explain(
     })
 }
 
+#[allow(dead_code)]
+pub fn test_execute_query_request(
+    test_path_string: &str,
+    test_metadata_path: &str,
+    common_metadata_paths: &[&str],
+) -> anyhow::Result<()> {
+    tokio_test::block_on(async {
+        let root_test_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests");
+        let mut test_ctx = setup(&root_test_dir);
+        let test_path = root_test_dir.join(test_path_string);
+        let request_file_path = test_path.join("request.json");
+        let expected_response_file = test_path_string.to_string() + "/expected.json";
+
+        let test_metadata_path = root_test_dir.join(test_metadata_path);
+        let metadata = merge_with_common_metadata(
+            &test_metadata_path,
+            common_metadata_paths
+                .iter()
+                .map(|path| root_test_dir.join(path)),
+        )?;
+
+        let (resolved_metadata, _) = metadata_resolve::resolve(
+            open_dds::traits::OpenDd::deserialize(metadata, jsonpath::JSONPath::new())?,
+            &test_metadata_resolve_configuration(),
+        )?;
+
+        let request_headers = reqwest::header::HeaderMap::new();
+        let session = {
+            let session_variables: BTreeMap<SessionVariableName, SessionVariableValue> =
+                BTreeMap::from_iter([(
+                    SESSION_VARIABLE_ROLE.clone(),
+                    SessionVariableValue::Unparsed("admin".to_owned()),
+                )]);
+            resolve_session(session_variables)
+        }?;
+        let request: open_dds::query::QueryRequest =
+            serde_json::from_str(&read_to_string(&request_file_path)?)?;
+
+        let results = engine::execute_query_request(
+            &request,
+            &resolved_metadata,
+            &session,
+            &test_ctx.http_context,
+            &request_headers,
+        )
+        .await;
+        let (status, body) =
+            engine::QueryResponse::new(results, ExposeInternalErrors::Expose).to_json();
+        let response = serde_json::json!({ "status": status.as_u16(), "body": body });
+
+        let mut expected = test_ctx.mint.new_goldenfile_with_differ(
+            expected_response_file,
+            Box::new(|file1, file2| {
+                let json1: serde_json::Value =
+                    serde_json::from_reader(File::open(file1).unwrap()).unwrap();
+                let json2: serde_json::Value =
+                    serde_json::from_reader(File::open(file2).unwrap()).unwrap();
+                if json1 != json2 {
+                    text_diff(file1, file2);
+                }
+            }),
+        )?;
+        write!(expected, "{}", serde_json::to_string_pretty(&response)?)?;
+        Ok(())
+    })
+}
+
 // This is where we'll want to enable pre-release features in tests
 pub(crate) fn test_metadata_resolve_configuration() -> metadata_resolve::configuration::Configuration
 {
@@ -926,7 +1034,7 @@ pub fn diff_execution_plan(
     if let Ok(query) = graphql_frontend::parse_query(&raw_request.query) {
         // normalize the parsed GQL query
         if let Ok(normalized_request) =
-            graphql_frontend::normalize_request(schema, session, query, raw_request)
+            graphql_frontend::normalize_request(schema, metadata, session, query, raw_request)
         {
             // generate IR
             if let Ok(old_ir) = graphql_frontend::build_ir(
@@ -507,6 +507,14 @@ fn test_model_select_many_order_by_remote_relationship() -> anyhow::Result<()> {
     common::test_execution_expectation(test_path_string, &[common_metadata_path_string])
 }
 
+#[test]
+fn test_model_select_many_order_by_remote_relationship_top_level() -> anyhow::Result<()> {
+    let test_path_string = "execute/models/select_many/order_by/remote_relationship_top_level";
+    let common_metadata_path_string =
+        "execute/models/select_many/order_by/remote_relationship/combined_metadata.json";
+    common::test_execution_expectation(test_path_string, &[common_metadata_path_string])
+}
+
 #[test]
 fn test_model_select_many_order_by_reuse_order_by_expression() -> anyhow::Result<()> {
     common::test_execution_expectation_for_multiple_ndc_versions(
@@ -1060,6 +1068,24 @@ fn test_model_select_many_where_remote_object_relationship_nested() -> anyhow::R
     )
 }
 
+#[test]
+fn test_model_select_many_where_remote_object_relationship_concurrent() -> anyhow::Result<()> {
+    common::test_execution_expectation_for_multiple_ndc_versions(
+        "execute/models/select_many/where/remote_relationships/boolean_expression_type/object/concurrent",
+        &["execute/models/select_many/where/remote_relationships/boolean_expression_type/common_metadata.json"],
+        BTreeMap::from([
+            (
+                NdcVersion::V01,
+                vec!["execute/models/select_many/where/remote_relationships/boolean_expression_type/pg_connector_ndc_v01.json", "execute/common_metadata/postgres_connector_ndc_v01_schema.json"],
+            ),
+            (
+                NdcVersion::V02,
+                vec!["execute/models/select_many/where/remote_relationships/boolean_expression_type/pg_connector_ndc_v02.json", "execute/common_metadata/postgres_connector_ndc_v02_schema.json"],
+            ),
+        ]),
+    )
+}
+
 #[test]
 fn test_model_select_many_where_remote_object_relationship_simple_across_subgraphs(
 ) -> anyhow::Result<()> {
@@ -1940,6 +1966,33 @@ fn test_command_procedures_boolean_expression_argument() -> anyhow::Result<()> {
     )
 }
 
+// Tests a mutation with several root fields, which are sent to the data connector in a single
+// request, so that if one of them fails none of them are applied
+#[test]
+fn test_command_procedures_multiple_root_fields() -> anyhow::Result<()> {
+    common::test_execution_expectation_for_multiple_ndc_versions(
+        "execute/commands/procedures/multiple_root_fields",
+        &["execute/common_metadata/command_metadata.json"],
+        BTreeMap::from([(
+            NdcVersion::V02,
+            vec!["execute/common_metadata/custom_connector_v02_schema.json"],
+        )]),
+    )
+}
+
+// Tests a mutation with root fields using different data connectors, where only consecutive root
+// fields using the same data connector are sent to it in a single request
+#[test]
+fn test_command_procedures_multiple_data_connectors() -> anyhow::Result<()> {
+    common::test_execution_expectation(
+        "execute/commands/procedures/multiple_data_connectors",
+        &[
+            "execute/common_metadata/command_metadata.json",
+            "execute/models/select_many/order_by/remote_relationship/combined_metadata.json",
+        ],
+    )
+}
+
 // Tests a mutation command with preset arguments:
 // arguments: 2 arguments (lower_bound, upper_bound) - one provided by presets in permissions
 // output: object (commandActor) output type
@@ -2718,6 +2771,17 @@ fn test_aggregates_root_field_custom_count_return_type() -> anyhow::Result<()> {
     )
 }
 
+#[test]
+fn test_aggregates_root_field_groups() -> anyhow::Result<()> {
+    common::test_execution_expectation(
+        "execute/aggregates/root_field/groups",
+        &[
+            "execute/aggregates/common_metadata/supergraph.json",
+            "execute/common_metadata/custom_connector_v02_schema.json",
+        ],
+    )
+}
+
 #[test]
 fn test_aggregates_root_field_typename() -> anyhow::Result<()> {
     common::test_execution_expectation_for_multiple_ndc_versions(
@@ -0,0 +1,431 @@
+//! Tests of GraphQL requests sent over HTTP through the routes and middleware of the engine, for
+//! the behaviour that depends on the HTTP method and headers of a request: queries sent with GET,
+//! the caching headers of their responses, mutations sent with GET, batched requests and responses
+//! delivered incrementally as `multipart/mixed`.
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use axum::body::Body;
+use axum::extract::ConnectInfo;
+use axum::http::{header, HeaderMap, HeaderValue, Request, StatusCode};
+use axum::response::IntoResponse;
+use engine_types::{ExposeInternalErrors, DEFAULT_MAX_CONCURRENT_REMOTE_JOIN_QUERIES};
+use graphql_ir::GraphqlRequestPipeline;
+use tower::ServiceExt;
+
+#[allow(dead_code)]
+mod common;
+
+const TEST_PATH: &str = "execute/http/get_request";
+
+const BATCH_GRAPHQL_CONFIG_PATH: &str = "execute/http/batch_request/graphql_config.json";
+
+const COMMON_METADATA_PATHS: [&str; 2] = [
+    "execute/common_metadata/custom_connector_v02_schema.json",
+    "execute/common_metadata/command_metadata.json",
+];
+
+const VARY: &str = "authorization, cookie, x-hasura-role";
+
+/// The metadata of the tests, along with the given additional metadata files
+fn read_metadata(additional_metadata_paths: &[&str]) -> anyhow::Result<serde_json::Value> {
+    let root_test_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests");
+    common::merge_with_common_metadata(
+        &root_test_dir.join(TEST_PATH).join("metadata.json"),
+        COMMON_METADATA_PATHS
+            .iter()
+            .chain(additional_metadata_paths)
+            .map(|path| root_test_dir.join(path)),
+    )
+}
+
+/// The routes of an engine whose requests all have the given role
+fn build_routes(role: &str) -> anyhow::Result<axum::Router> {
+    build_routes_with_metadata(role, &read_metadata(&[])?)
+}
+
+/// The routes of an engine serving the given metadata, whose requests all have the given role
+fn build_routes_with_metadata(
+    role: &str,
+    metadata: &serde_json::Value,
+) -> anyhow::Result<axum::Router> {
+    let auth_config = serde_json::json!({
+        "version": "v1",
+        "definition": {
+            "mode": {
+                "noAuth": {
+                    "role": role,
+                    "sessionVariables": { "x-hasura-user-id": "1" }
+                }
+            }
+        }
+    });
+    let (resolved_metadata, auth_config) = engine::resolve_metadata(
+        &metadata.to_string(),
+        &auth_config.to_string(),
+        &common::test_metadata_resolve_configuration(),
+    )?;
+    let state = engine::build_state(
+        GraphqlRequestPipeline::Old,
+        ExposeInternalErrors::Expose,
+        auth_config,
+        resolved_metadata,
+        DEFAULT_MAX_CONCURRENT_REMOTE_JOIN_QUERIES,
+    )?;
+    Ok(engine::get_base_routes(state))
+}
+
+/// Send a GraphQL request with GET, returning the status, headers and body of the response
+async fn send_get_request(
+    routes: &axum::Router,
+    query: &str,
+    request_headers: HeaderMap,
+) -> anyhow::Result<(StatusCode, HeaderMap, Vec<u8>)> {
+    let url = reqwest::Url::parse_with_params("http://localhost/graphql", [("query", query)])?;
+    let mut request =
+        Request::get(format!("{}?{}", url.path(), url.query().unwrap_or(""))).body(Body::empty())?;
+    request.headers_mut().extend(request_headers);
+    request
+        .extensions_mut()
+        .insert(ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 0))));
+
+    let response = routes.clone().oneshot(request).await?;
+    let status = response.status();
+    let headers = response.headers().clone();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    Ok((status, headers, body.to_vec()))
+}
+
+#[test]
+fn test_get_request_cache_headers() -> anyhow::Result<()> {
+    tokio_test::block_on(async {
+        let routes = build_routes("admin")?;
+        let query = "query { MovieMany { movie_id title } }";
+
+        let (status, headers, body) = send_get_request(&routes, query, HeaderMap::new()).await?;
+        assert_eq!(status, StatusCode::OK);
+        let response: serde_json::Value = serde_json::from_slice(&body)?;
+        assert!(response.get("errors").is_none(), "{response}");
+        assert!(response["data"]["MovieMany"].is_array(), "{response}");
+        assert_eq!(
+            headers.get(header::CACHE_CONTROL),
+            Some(&HeaderValue::from_static("public, max-age=60"))
+        );
+        assert_eq!(
+            headers.get(header::VARY),
+            Some(&HeaderValue::from_static(VARY))
+        );
+        let etag = headers
+            .get(header::ETAG)
+            .expect("cacheable response has an entity tag")
+            .clone();
+
+        // the same response is not sent again to a client that already has it
+        let mut request_headers = HeaderMap::new();
+        request_headers.insert(header::IF_NONE_MATCH, etag.clone());
+        let (status, headers, body) = send_get_request(&routes, query, request_headers).await?;
+        assert_eq!(status, StatusCode::NOT_MODIFIED);
+        assert!(body.is_empty());
+        assert_eq!(headers.get(header::ETAG), Some(&etag));
+        assert_eq!(
+            headers.get(header::CACHE_CONTROL),
+            Some(&HeaderValue::from_static("public, max-age=60"))
+        );
+        assert_eq!(
+            headers.get(header::VARY),
+            Some(&HeaderValue::from_static(VARY))
+        );
+
+        // a stale entity tag gets the full response
+        let mut request_headers = HeaderMap::new();
+        request_headers.insert(header::IF_NONE_MATCH, HeaderValue::from_static("\"stale\""));
+        let (status, _headers, body) = send_get_request(&routes, query, request_headers).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert!(!body.is_empty());
+        Ok(())
+    })
+}
+
+#[test]
+fn test_get_request_with_permission_filter_is_private() -> anyhow::Result<()> {
+    tokio_test::block_on(async {
+        // the rows that `user1` sees depend on its session, so shared caches must not store them
+        let routes = build_routes("user1")?;
+        let query = "query { MovieMany { movie_id title } }";
+
+        let (status, headers, body) = send_get_request(&routes, query, HeaderMap::new()).await?;
+        assert_eq!(status, StatusCode::OK);
+        let response: serde_json::Value = serde_json::from_slice(&body)?;
+        assert!(response.get("errors").is_none(), "{response}");
+        assert_eq!(
+            headers.get(header::CACHE_CONTROL),
+            Some(&HeaderValue::from_static("private, max-age=60"))
+        );
+        assert_eq!(
+            headers.get(header::VARY),
+            Some(&HeaderValue::from_static(VARY))
+        );
+        Ok(())
+    })
+}
+
+#[test]
+fn test_get_request_mutation_not_allowed() -> anyhow::Result<()> {
+    tokio_test::block_on(async {
+        let routes = build_routes("admin")?;
+        let query =
+            r#"mutation { updateActorNameById(id: 1, name: "Chris Hemsworth") { actor_id } }"#;
+
+        let (status, headers, body) = send_get_request(&routes, query, HeaderMap::new()).await?;
+        assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(
+            headers.get(header::ALLOW),
+            Some(&HeaderValue::from_static("POST"))
+        );
+        assert!(headers.get(header::CACHE_CONTROL).is_none());
+        let response: serde_json::Value = serde_json::from_slice(&body)?;
+        assert!(response.get("errors").is_some(), "{response}");
+        Ok(())
+    })
+}
+
+/// Send a batch of GraphQL operations with POST, returning the status and body of the response
+async fn send_batch_request(
+    routes: &axum::Router,
+    operations: &serde_json::Value,
+) -> anyhow::Result<(StatusCode, serde_json::Value)> {
+    let mut request = Request::post("/graphql")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(operations.to_string()))?;
+    request
+        .extensions_mut()
+        .insert(ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 0))));
+
+    let response = routes.clone().oneshot(request).await?;
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    Ok((status, serde_json::from_slice(&body)?))
+}
+
+/// Start a pre-parse plugin that records the name of every operation it is called with, and
+/// answers the operation named `Intercepted` itself. Returns the URL of the plugin along with the
+/// names of the operations it has been called with.
+async fn start_pre_parse_plugin() -> anyhow::Result<(String, Arc<Mutex<Vec<String>>>)> {
+    let operation_names = Arc::new(Mutex::new(Vec::new()));
+    let recorded_operation_names = operation_names.clone();
+    let plugin = axum::Router::new().route(
+        "/",
+        axum::routing::post(move |axum::Json(body): axum::Json<serde_json::Value>| {
+            let operation_name = body["rawRequest"]["operationName"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string();
+            recorded_operation_names
+                .lock()
+                .unwrap()
+                .push(operation_name.clone());
+            async move {
+                if operation_name == "Intercepted" {
+                    (
+                        StatusCode::OK,
+                        axum::Json(serde_json::json!({ "data": { "intercepted": true } })),
+                    )
+                        .into_response()
+                } else {
+                    StatusCode::NO_CONTENT.into_response()
+                }
+            }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("http://{}/", listener.local_addr()?);
+    tokio::spawn(async move { axum::serve(listener, plugin).await });
+    Ok((url, operation_names))
+}
+
+#[test]
+fn test_batch_request_through_plugins() -> anyhow::Result<()> {
+    tokio_test::block_on(async {
+        let (plugin_url, operation_names) = start_pre_parse_plugin().await?;
+        let mut metadata = read_metadata(&[BATCH_GRAPHQL_CONFIG_PATH])?;
+        metadata["supergraph"]["objects"]
+            .as_array_mut()
+            .expect("supergraph objects")
+            .push(serde_json::json!({
+                "kind": "LifecyclePluginHook",
+                "version": "v1",
+                "definition": {
+                    "pre": "parse",
+                    "name": "record_operations",
+                    "url": { "value": plugin_url },
+                    "config": {
+                        "request": {
+                            "session": {},
+                            "rawRequest": { "query": {}, "variables": {} }
+                        }
+                    }
+                }
+            }));
+        let routes = build_routes_with_metadata("admin", &metadata)?;
+
+        let operations = serde_json::json!([
+            { "query": "query First { MovieMany { movie_id } }", "operationName": "First" },
+            { "query": "query Intercepted { MovieMany { title } }", "operationName": "Intercepted" },
+            { "query": "query Last { MovieMany { title } }", "operationName": "Last" }
+        ]);
+        let (status, response) = send_batch_request(&routes, &operations).await?;
+        assert_eq!(status, StatusCode::OK);
+        let responses = response.as_array().expect("batch response is an array");
+        assert_eq!(responses.len(), 3, "{response}");
+
+        // each operation goes through the plugin, and the responses are in the order of the batch
+        let first_movies = responses[0]["data"]["MovieMany"]
+            .as_array()
+            .expect("movies of the first operation");
+        assert!(!first_movies.is_empty(), "{response}");
+        assert!(first_movies
+            .iter()
+            .all(|movie| movie.get("movie_id").is_some() && movie.get("title").is_none()));
+        assert_eq!(
+            responses[1],
+            serde_json::json!({ "data": { "intercepted": true } })
+        );
+        let last_movies = responses[2]["data"]["MovieMany"]
+            .as_array()
+            .expect("movies of the last operation");
+        assert!(!last_movies.is_empty(), "{response}");
+        assert!(last_movies
+            .iter()
+            .all(|movie| movie.get("title").is_some() && movie.get("movie_id").is_none()));
+        assert_eq!(
+            *operation_names.lock().unwrap(),
+            vec!["First", "Intercepted", "Last"]
+        );
+
+        // batches larger than the configured maximum are rejected before any operation is run
+        let operations = serde_json::json!([
+            { "query": "query A { MovieMany { title } }", "operationName": "A" },
+            { "query": "query B { MovieMany { title } }", "operationName": "B" },
+            { "query": "query C { MovieMany { title } }", "operationName": "C" },
+            { "query": "query D { MovieMany { title } }", "operationName": "D" }
+        ]);
+        let (status, response) = send_batch_request(&routes, &operations).await?;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{response}");
+        assert_eq!(operation_names.lock().unwrap().len(), 3);
+        Ok(())
+    })
+}
+
+#[test]
+fn test_batch_request_disabled_by_default() -> anyhow::Result<()> {
+    tokio_test::block_on(async {
+        // the metadata of this engine does not configure batching
+        let routes = build_routes("admin")?;
+        let operations = serde_json::json!([{ "query": "query { MovieMany { title } }" }]);
+
+        let (status, response) = send_batch_request(&routes, &operations).await?;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{response}");
+        assert_eq!(
+            response["errors"][0]["message"],
+            "batched requests are disabled"
+        );
+        Ok(())
+    })
+}
+
+/// Split the body of a `multipart/mixed` response, whose boundary is `-`, into the JSON payloads of
+/// its parts
+fn multipart_payloads(body: &str) -> anyhow::Result<Vec<serde_json::Value>> {
+    let parts = body
+        .strip_suffix("\r\n-----\r\n")
+        .ok_or_else(|| anyhow::anyhow!("missing closing delimiter: {body}"))?;
+    parts
+        .split("\r\n---\r\n")
+        .skip(1)
+        .map(|part| {
+            let (headers, payload) = part
+                .split_once("\r\n\r\n")
+                .ok_or_else(|| anyhow::anyhow!("part without a body: {part}"))?;
+            assert_eq!(headers, "Content-Type: application/json; charset=utf-8");
+            Ok(serde_json::from_str(payload)?)
+        })
+        .collect()
+}
+
+#[test]
+fn test_multipart_mixed_response() -> anyhow::Result<()> {
+    tokio_test::block_on(async {
+        let routes = build_routes("admin")?;
+        let query = r#"query {
+            MovieMany(order_by: { movie_id: Asc }) @stream(initialCount: 1, label: "movies") {
+                movie_id
+                ... @defer(label: "title") { title }
+            }
+        }"#;
+        let mut request = Request::post("/graphql")
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(
+                header::ACCEPT,
+                "multipart/mixed;deferSpec=20220824, application/json;q=0.9",
+            )
+            .body(Body::from(
+                serde_json::json!({ "query": query }).to_string(),
+            ))?;
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 0))));
+
+        let response = routes.clone().oneshot(request).await?;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE),
+            Some(&HeaderValue::from_static("multipart/mixed; boundary=\"-\""))
+        );
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+        let payloads = multipart_payloads(std::str::from_utf8(&body)?)?;
+
+        // the initial response has the first movie, without its deferred title
+        let (initial, subsequent) = payloads.split_first().expect("an initial part");
+        assert_eq!(initial["hasNext"], true, "{initial}");
+        let initial_movies = initial["data"]["MovieMany"]
+            .as_array()
+            .expect("initial movies");
+        assert_eq!(initial_movies.len(), 1, "{initial}");
+        assert!(initial_movies[0].get("title").is_none(), "{initial}");
+
+        // the remaining movies and the titles follow, with the last part ending the response
+        let last = subsequent.last().expect("a subsequent part");
+        assert_eq!(last["hasNext"], false, "{last}");
+        let incremental: Vec<&serde_json::Value> = subsequent
+            .iter()
+            .flat_map(|payload| payload["incremental"].as_array().into_iter().flatten())
+            .collect();
+        let streamed_movies: Vec<&serde_json::Value> = incremental
+            .iter()
+            .filter(|result| result["label"] == "movies")
+            .flat_map(|result| {
+                assert_eq!(result["path"], serde_json::json!(["MovieMany", 1]));
+                result["items"].as_array().into_iter().flatten()
+            })
+            .collect();
+        assert!(!streamed_movies.is_empty(), "{payloads:?}");
+        assert!(streamed_movies
+            .iter()
+            .all(|movie| movie.get("movie_id").is_some() && movie.get("title").is_none()));
+        let title_paths: Vec<&serde_json::Value> = incremental
+            .iter()
+            .filter(|result| result["label"] == "title")
+            .map(|result| {
+                assert!(result["data"]["title"].is_string(), "{result}");
+                &result["path"]
+            })
+            .collect();
+        let expected_title_paths: Vec<serde_json::Value> = (0..=streamed_movies.len())
+            .map(|index| serde_json::json!(["MovieMany", index]))
+            .collect();
+        assert_eq!(title_paths, expected_title_paths.iter().collect::<Vec<_>>());
+        Ok(())
+    })
+}
@@ -0,0 +1,50 @@
+#[allow(dead_code)]
+mod common;
+
+#[test]
+fn test_query_models_of_different_connectors() -> anyhow::Result<()> {
+    common::test_execute_query_request(
+        "query/models_of_different_connectors",
+        "execute/multiple_root_fields/successful_execution/metadata.json",
+        &[],
+    )
+}
+
+#[test]
+fn test_query_unknown_model() -> anyhow::Result<()> {
+    common::test_execute_query_request(
+        "query/unknown_model",
+        "execute/multiple_root_fields/successful_execution/metadata.json",
+        &[],
+    )
+}
+
+#[test]
+fn test_query_group_filter() -> anyhow::Result<()> {
+    common::test_execute_query_request(
+        "query/group_filter",
+        "execute/multiple_root_fields/successful_execution/metadata.json",
+        &[],
+    )
+}
+
+#[test]
+fn test_query_group_filter_unsupported_operator() -> anyhow::Result<()> {
+    common::test_execute_query_request(
+        "query/group_filter_unsupported_operator",
+        "execute/multiple_root_fields/successful_execution/metadata.json",
+        &[],
+    )
+}
+
+#[test]
+fn test_query_group_by_relationship_dimension() -> anyhow::Result<()> {
+    common::test_execute_query_request(
+        "query/group_by_relationship_dimension",
+        "execute/remote_relationships/mutually_recursive/metadata.json",
+        &[
+            "execute/common_metadata/postgres_connector_ndc_v01_schema.json",
+            "execute/common_metadata/custom_connector_v02_schema.json",
+        ],
+    )
+}
@@ -76,6 +76,25 @@ fn test_local_relationships_model_to_model_array() -> anyhow::Result<()> {
     )
 }
 
+#[test]
+fn test_local_relationships_model_to_model_array_session_variable_value_mapping(
+) -> anyhow::Result<()> {
+    common::test_execution_expectation_for_multiple_ndc_versions(
+        "execute/relationships/array/session_variable_value_mapping",
+        &["execute/relationships/common_metadata/graphql_config.json"],
+        BTreeMap::from([
+            (
+                NdcVersion::V01,
+                vec!["execute/common_metadata/postgres_connector_ndc_v01_schema.json"],
+            ),
+            (
+                NdcVersion::V02,
+                vec!["execute/common_metadata/postgres_connector_ndc_v02_schema.json"],
+            ),
+        ]),
+    )
+}
+
 #[test]
 fn test_local_relationships_model_to_model_array_with_arguments() -> anyhow::Result<()> {
     common::test_execution_expectation_for_multiple_ndc_versions(
@@ -332,6 +351,14 @@ fn test_remote_relationships_model_to_model_array() -> anyhow::Result<()> {
     common::test_execution_expectation(test_path_string, &[common_metadata_path_string])
 }
 
+#[test]
+fn test_remote_relationships_model_to_model_array_session_variable_value_mapping(
+) -> anyhow::Result<()> {
+    let test_path_string = "execute/remote_relationships/array/session_variable_value_mapping";
+    let common_metadata_path_string = "execute/common_metadata/two_postgres_connector_schema.json";
+    common::test_execution_expectation(test_path_string, &[common_metadata_path_string])
+}
+
 #[test]
 fn test_remote_relationships_model_to_model_array_aggregate() -> anyhow::Result<()> {
     let test_path_string = "execute/remote_relationships/array/aggregate";
@@ -339,6 +366,16 @@ fn test_remote_relationships_model_to_model_array_aggregate() -> anyhow::Result<
     common::test_execution_expectation(test_path_string, &[common_metadata_path_string])
 }
 
+// The target data connector limits the number of variable sets per request, so the rows are
+// joined across several queries
+#[test]
+fn test_remote_relationships_max_variable_sets_per_request() -> anyhow::Result<()> {
+    let test_path_string = "execute/remote_relationships/max_variable_sets_per_request";
+    let common_metadata_path_string =
+        "execute/remote_relationships/max_variable_sets_per_request/combined_metadata.json";
+    common::test_execution_expectation(test_path_string, &[common_metadata_path_string])
+}
+
 // Test remote joins with a Procedure LHS. This exercises:
 //  - join to a model (object relationship), with permissions
 //  - join to a collection
@@ -568,6 +605,16 @@ fn test_relationships_nested_selection_no_nested_capability() -> anyhow::Result<
     common::test_execution_expectation(test_path_string, &[common_metadata_path_string])
 }
 
+// Relationship mapped from a field nested inside an object of the source, executed locally
+// 1. The relationship is selected inside the nested object in the NDC query
+// 2. Source rows whose nested object is null have no related object
+#[test]
+fn test_relationships_nested_from_nested_source_field() -> anyhow::Result<()> {
+    let test_path_string = "execute/relationships/nested/from_nested_source_field";
+    let common_metadata_path_string = "execute/common_metadata/custom_connector_v02_schema.json";
+    common::test_execution_expectation(test_path_string, &[common_metadata_path_string])
+}
+
 // Miscellaneous tests
 
 // What is being tested?
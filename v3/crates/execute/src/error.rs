@@ -1,6 +1,8 @@
 use engine_types::ExposeInternalErrors;
 use gql::{ast::common as ast, http::GraphQLError};
 use lang_graphql as gql;
+use metadata_resolve::Qualified;
+use open_dds::models::ModelName;
 use open_dds::relationships::RelationshipName;
 use plan_types::RemotePredicateKey;
 use reqwest::StatusCode;
@@ -28,6 +30,12 @@ pub enum FieldError {
         connector_error: ndc_client::ConnectorError,
     },
 
+    #[error("request to data connector timed out after {} ms", timeout.as_millis())]
+    NDCTimeout { timeout: std::time::Duration },
+
+    #[error("data connector is unavailable: requests are rejected after repeated failures")]
+    NDCCircuitOpen,
+
     #[error("field '{field_name:} not found in _Service")]
     FieldNotFoundInService { field_name: String },
 
@@ -37,6 +45,18 @@ pub enum FieldError {
     #[error("Relationship '{name}' is either remote or not having 'relation_comparisons' NDC capability; not supported for filtering")]
     RelationshipPredicatesNotSupported { name: RelationshipName },
 
+    #[error("ordering by fields of remote relationships would require sorting more than {row_limit} rows, which is the most that can be sorted for this model")]
+    RemoteOrderByRowLimitExceeded { row_limit: u32 },
+
+    #[error("filtering by the remote relationship to model {target_model_name} matched more than {max_keys} distinct values of the related fields, which is the most a filter can compare with")]
+    RemotePredicateKeyLimitExceeded {
+        target_model_name: Qualified<ModelName>,
+        max_keys: u32,
+    },
+
+    #[error("the mutation request to the data connector failed, see the error for the mutation field '{field_name}'")]
+    BatchedMutationFailed { field_name: String },
+
     #[error("internal error: {0}")]
     InternalError(#[from] FieldInternalError),
 }
@@ -48,9 +68,14 @@ impl FieldError {
                 Some(connector_error.error_response.details().clone())
             }
             Self::InternalError(internal) => internal.get_details(),
-            Self::FieldNotFoundInService { .. }
+            Self::NDCTimeout { .. }
+            | Self::NDCCircuitOpen
+            | Self::FieldNotFoundInService { .. }
             | Self::SubscriptionsNotSupported
-            | Self::RelationshipPredicatesNotSupported { .. } => None,
+            | Self::RelationshipPredicatesNotSupported { .. }
+            | Self::RemoteOrderByRowLimitExceeded { .. }
+            | Self::RemotePredicateKeyLimitExceeded { .. }
+            | Self::BatchedMutationFailed { .. } => None,
         }
     }
 
@@ -83,8 +108,13 @@ impl TraceableError for FieldError {
     fn visibility(&self) -> ErrorVisibility {
         match self {
             Self::NDCExpected { .. }
+            | Self::NDCTimeout { .. }
+            | Self::NDCCircuitOpen
             | Self::FieldNotFoundInService { .. }
             | Self::RelationshipPredicatesNotSupported { .. }
+            | Self::RemoteOrderByRowLimitExceeded { .. }
+            | Self::RemotePredicateKeyLimitExceeded { .. }
+            | Self::BatchedMutationFailed { .. }
             | Self::SubscriptionsNotSupported => ErrorVisibility::User,
             Self::InternalError(internal_error) => internal_error.visibility(),
         }
@@ -195,6 +225,11 @@ impl NDCUnexpectedError {
 // Convert NDC errors
 impl From<ndc_client::Error> for FieldError {
     fn from(ndc_client_error: ndc_client::Error) -> FieldError {
+        match ndc_client_error {
+            ndc_client::Error::Timeout(timeout) => return FieldError::NDCTimeout { timeout },
+            ndc_client::Error::CircuitOpen => return FieldError::NDCCircuitOpen,
+            _ => {}
+        }
         if let ndc_client::Error::Connector(err) = &ndc_client_error {
             if matches!(
                 err.status,
@@ -3,22 +3,29 @@
 use crate::error;
 use std::sync::Arc;
 mod ndc_request;
+mod nested_relationships;
 mod remote_joins;
+mod remote_order_by;
 mod remote_predicates;
-use crate::error::FieldError;
+use crate::error::{FieldError, NDCUnexpectedError};
 use crate::ndc;
 use crate::FieldInternalError;
 use async_recursion::async_recursion;
 use engine_types::{HttpContext, ProjectId};
-use indexmap::IndexMap;
+use futures_util::future::try_join_all;
+use indexmap::{IndexMap, IndexSet};
 pub use ndc_request::{
     make_ndc_mutation_request, make_ndc_query_request, v01::NdcV01CompatibilityError,
 };
+pub use nested_relationships::lift_nested_relationships;
+use nonempty::NonEmpty;
 use plan_types::{
     JoinLocations, NDCMutationExecution, NDCQueryExecution, NDCSubscriptionExecution,
-    PredicateQueryTrees, ProcessResponseAs, QueryExecutionPlan, QueryExecutionTree,
-    RemotePredicateKey, ResolvedFilterExpression, FUNCTION_IR_VALUE_COLUMN_NAME,
+    PredicateQueryTree, PredicateQueryTrees, ProcessResponseAs, QueryExecutionPlan,
+    QueryExecutionTree, RemotePredicateKey, ResolvedFilterExpression,
+    FUNCTION_IR_VALUE_COLUMN_NAME,
 };
+pub use remote_joins::execute_join_locations;
 pub use remote_predicates::replace_predicates_in_query_execution_plan;
 use std::collections::BTreeMap;
 
@@ -48,39 +55,92 @@ pub async fn resolve_ndc_query_execution(
 }
 
 // run a PredicateQueryTree, turning it into a `ResolvedFilterExpression`
+// the predicates filter queries to the `source_data_connector`
 #[async_recursion]
 pub async fn execute_remote_predicates(
     remote_predicates: &PredicateQueryTrees,
+    source_data_connector: &metadata_resolve::DataConnectorLink,
     http_context: &HttpContext,
     field_span_attribute: &str,
     execution_span_attribute: &'static str,
     process_response_as: &ProcessResponseAs,
     project_id: Option<&ProjectId>,
 ) -> Result<BTreeMap<RemotePredicateKey, ResolvedFilterExpression>, FieldError> {
-    // resolve all our filter expressions into here, ready to && them in
-    // at the appropriate moment
-    let mut filter_expressions = BTreeMap::new();
-
-    for (uuid, remote_predicate) in &remote_predicates.0 {
-        // don't bother recursing if it's empty
-        if !remote_predicate.children.0.is_empty() {
-            let child_filter_expressions = execute_remote_predicates(
-                &remote_predicate.children,
+    let max_keys = source_data_connector
+        .request_policy
+        .as_ref()
+        .and_then(|request_policy| request_policy.max_remote_filter_values);
+
+    // the predicates are independent of each other, so are executed concurrently
+    let resolved_predicates =
+        try_join_all(remote_predicates.0.iter().map(|(uuid, remote_predicate)| {
+            execute_remote_predicate(
+                *uuid,
+                remote_predicate,
+                max_keys,
                 http_context,
                 field_span_attribute,
                 execution_span_attribute,
                 process_response_as,
                 project_id,
             )
-            .await?;
-            filter_expressions.extend(child_filter_expressions);
-        }
+        }))
+        .await?;
 
-        // execute our remote predicate, including everything we have learned
-        // from the child predicates
+    // resolve all our filter expressions into here, ready to && them in
+    // at the appropriate moment
+    Ok(resolved_predicates.into_iter().flatten().collect())
+}
+
+// run a single PredicateQueryTree, returning the `ResolvedFilterExpression` for it, along with
+// those of its children
+#[allow(clippy::too_many_arguments)]
+async fn execute_remote_predicate(
+    uuid: RemotePredicateKey,
+    remote_predicate: &PredicateQueryTree,
+    max_keys: Option<u32>,
+    http_context: &HttpContext,
+    field_span_attribute: &str,
+    execution_span_attribute: &'static str,
+    process_response_as: &ProcessResponseAs,
+    project_id: Option<&ProjectId>,
+) -> Result<BTreeMap<RemotePredicateKey, ResolvedFilterExpression>, FieldError> {
+    // don't bother recursing if it's empty
+    let mut filter_expressions = if remote_predicate.children.0.is_empty() {
+        BTreeMap::new()
+    } else {
+        execute_remote_predicates(
+            &remote_predicate.children,
+            &remote_predicate.query.query_execution_plan.data_connector,
+            http_context,
+            field_span_attribute,
+            execution_span_attribute,
+            process_response_as,
+            project_id,
+        )
+        .await?
+    };
+
+    // execute our remote predicate, including everything we have learned
+    // from the child predicates. If the number of values is limited, the rows are fetched a page
+    // at a time, so that no more than the limit (and one more, to tell it was exceeded) are
+    // fetched at once.
+    let page_size = max_keys.map(|max_keys| max_keys.saturating_add(1));
+    let mut distinct_keys = IndexSet::new();
+    let mut offset = 0;
+    loop {
+        let mut query = remote_predicate.query.clone();
+        if let Some(page_size) = page_size {
+            remote_predicates::page_remote_predicate_query(
+                &mut query.query_execution_plan.query_node,
+                &remote_predicate.ndc_column_mapping,
+                page_size,
+                offset,
+            );
+        }
         let result_row_set = execute_query_execution_tree(
             http_context,
-            remote_predicate.query.clone(),
+            query,
             field_span_attribute,
             execution_span_attribute,
             process_response_as,
@@ -90,17 +150,32 @@ pub async fn execute_remote_predicates(
         .await?;
 
         // Assume a single row set is returned
-        let single_rowset = get_single_rowset(result_row_set)?;
-
-        // Turn the results into a `ResolvedFilterExpression`
-        let column_comparison = remote_predicates::build_source_column_comparisons(
-            single_rowset.rows.unwrap_or_default(),
+        let rows = get_single_rowset(result_row_set)?.rows.unwrap_or_default();
+        let row_count = rows.len();
+        remote_predicates::collect_distinct_keys(
+            rows,
             &remote_predicate.ndc_column_mapping,
+            &remote_predicate.target_model_name,
+            max_keys,
+            &mut distinct_keys,
         )?;
 
-        filter_expressions.insert(*uuid, column_comparison);
+        // a full page means there may be more rows
+        match page_size {
+            Some(page_size) if row_count >= page_size as usize => {
+                offset = offset.saturating_add(page_size);
+            }
+            _ => break,
+        }
     }
 
+    // Turn the results into a `ResolvedFilterExpression`
+    let column_comparison = remote_predicates::build_source_column_comparisons(
+        distinct_keys,
+        &remote_predicate.ndc_column_mapping,
+    );
+
+    filter_expressions.insert(uuid, column_comparison);
     Ok(filter_expressions)
 }
 
@@ -129,11 +204,13 @@ async fn execute_query_execution_tree<'s>(
     // 1) run remote predicates
     // 2) update predicates in `QueryExecutionPlan` with results of remote predicates
     // 3) run `QueryExecutionPlan` query
-    // 4) run any remote joins
+    // 4) order the rows by fields of remote relationships, if needed
+    // 5) run any remote joins
 
     // resolve all our filter expressions
     let mut filter_expressions = execute_remote_predicates(
         &execution_tree.remote_predicates,
+        &execution_tree.query_execution_plan.data_connector,
         http_context,
         field_span_attribute,
         execution_span_attribute,
@@ -151,8 +228,16 @@ async fn execute_query_execution_tree<'s>(
         &filter_expressions,
     )?;
 
+    // when only the page of rows to be ordered by the engine is fetched, keep the query in case
+    // the page turns out to need every row
+    let page_query_execution_plan = execution_tree
+        .remote_order_by
+        .as_ref()
+        .filter(|remote_order_by| remote_order_by.fetches_page_only())
+        .map(|_| query_execution_plan_with_predicates.clone());
+
     // create our `main` NDC request
-    let response_rowsets = execute_ndc_query(
+    let mut response_rowsets = execute_ndc_query(
         http_context,
         query_execution_plan_with_predicates,
         field_span_attribute,
@@ -161,6 +246,34 @@ async fn execute_query_execution_tree<'s>(
     )
     .await?;
 
+    // if ordering by fields of remote relationships, sort and paginate the rows ourselves
+    if let Some(remote_order_by) = &execution_tree.remote_order_by {
+        if let Some(mut query_execution_plan) = page_query_execution_plan {
+            if remote_order_by::page_is_incomplete(remote_order_by, &response_rowsets) {
+                // fetch one more row than can be sorted, so that we can tell when there are too many
+                query_execution_plan.query_node.limit =
+                    Some(remote_order_by.row_limit.saturating_add(1));
+                response_rowsets = execute_ndc_query(
+                    http_context,
+                    query_execution_plan,
+                    field_span_attribute,
+                    execution_span_attribute,
+                    project_id,
+                )
+                .await?;
+            }
+        }
+        remote_order_by::execute_remote_order_by(
+            http_context,
+            remote_order_by,
+            execution_span_attribute,
+            process_response_as,
+            project_id,
+            &mut response_rowsets,
+        )
+        .await?;
+    }
+
     // run any remote joins for the main request, combining
     // the results with the original rowsets
     run_remote_joins(
@@ -183,6 +296,10 @@ async fn execute_ndc_query(
     project_id: Option<&ProjectId>,
 ) -> Result<Vec<ndc_models::RowSet>, FieldError> {
     let data_connector = query_execution_plan.data_connector.clone();
+    let response_cache = query_execution_plan.response_cache.clone();
+    let nested_relationships_query_node =
+        nested_relationships::has_nested_relationships(&query_execution_plan.query_node)
+            .then(|| query_execution_plan.query_node.clone());
 
     let query_request = ndc_request::make_ndc_query_request(query_execution_plan)?;
 
@@ -190,13 +307,18 @@ async fn execute_ndc_query(
         http_context,
         &query_request,
         &data_connector,
+        response_cache.as_ref(),
         execution_span_attribute,
         field_span_attribute.to_owned(),
         project_id,
     )
     .await?;
 
-    Ok(response.as_latest_rowsets())
+    let mut rowsets = response.as_latest_rowsets();
+    if let Some(query_node) = nested_relationships_query_node {
+        nested_relationships::lift_nested_relationships(&query_node, &mut rowsets)?;
+    }
+    Ok(rowsets)
 }
 
 // given results of ndc query, do any joins, and process result
@@ -230,16 +352,65 @@ pub async fn resolve_ndc_mutation_execution(
     ndc_mutation_execution: NDCMutationExecution,
     project_id: Option<&ProjectId>,
 ) -> Result<ndc_models::MutationResponse, FieldError> {
-    let NDCMutationExecution {
-        execution_tree,
-        data_connector,
-        execution_span_attribute,
-        field_span_attribute,
-        process_response_as,
-    } = ndc_mutation_execution;
+    resolve_ndc_mutation_executions(
+        http_context,
+        NonEmpty::new(ndc_mutation_execution),
+        project_id,
+    )
+    .await?
+    .into_iter()
+    .next()
+    .ok_or_else(|| {
+        FieldError::from(NDCUnexpectedError::BadNDCResponse {
+            summary: "missing mutation response".to_string(),
+        })
+    })?
+}
 
-    let mutation_request =
-        ndc_request::make_ndc_mutation_request(execution_tree.mutation_execution_plan)?;
+/// Run several mutations against the same data connector as a single NDC mutation request, with
+/// an operation for each mutation, so that a data connector that supports transactions applies
+/// them atomically. The response is split back up, and the remote joins of each mutation are run
+/// against its own operation result. Returns a result per mutation, in the same order, as the
+/// remote joins of one mutation can fail without affecting the others.
+pub async fn resolve_ndc_mutation_executions(
+    http_context: &HttpContext,
+    ndc_mutation_executions: NonEmpty<NDCMutationExecution>,
+    project_id: Option<&ProjectId>,
+) -> Result<Vec<Result<ndc_models::MutationResponse, FieldError>>, FieldError> {
+    let data_connector = ndc_mutation_executions.head.data_connector.clone();
+    let execution_span_attribute = ndc_mutation_executions.head.execution_span_attribute;
+    let field_span_attribute = ndc_mutation_executions
+        .iter()
+        .map(|ndc_mutation_execution| ndc_mutation_execution.field_span_attribute.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let mut remote_joins = Vec::with_capacity(ndc_mutation_executions.len());
+    let mutation_execution_plans = ndc_mutation_executions.map(|ndc_mutation_execution| {
+        let NDCMutationExecution {
+            execution_tree,
+            data_connector: _,
+            execution_span_attribute: _,
+            field_span_attribute: _,
+            process_response_as,
+        } = ndc_mutation_execution;
+        let nested_relationships_procedure_fields = execution_tree
+            .mutation_execution_plan
+            .procedure_fields
+            .as_ref()
+            .filter(|procedure_fields| {
+                nested_relationships::nested_field_has_nested_relationships(procedure_fields)
+            })
+            .cloned();
+        remote_joins.push((
+            execution_tree.remote_join_executions,
+            process_response_as,
+            nested_relationships_procedure_fields,
+        ));
+        execution_tree.mutation_execution_plan
+    });
+
+    let mutation_request = ndc_request::make_ndc_mutation_request(mutation_execution_plans)?;
 
     let mutation_response = ndc::execute_ndc_mutation(
         http_context,
@@ -252,21 +423,60 @@ pub async fn resolve_ndc_mutation_execution(
     .await?
     .as_latest();
 
-    let mutation_response_as_query_response =
-        mutation_response_to_query_response(mutation_response);
-    let response_rowsets = run_remote_joins(
-        http_context,
-        execution_tree.remote_join_executions,
-        execution_span_attribute,
-        &process_response_as,
-        project_id,
-        mutation_response_as_query_response.0,
-    )
-    .await?;
-    let mutation_response =
-        query_response_back_to_mutation_response(ndc_models::QueryResponse(response_rowsets))?;
+    if mutation_response.operation_results.len() != remote_joins.len() {
+        return Err(NDCUnexpectedError::BadNDCResponse {
+            summary: format!(
+                "expected {} operation results, received {}",
+                remote_joins.len(),
+                mutation_response.operation_results.len()
+            ),
+        }
+        .into());
+    }
+
+    let mut mutation_responses = Vec::with_capacity(remote_joins.len());
+    for (
+        operation_result,
+        (remote_join_executions, process_response_as, nested_relationships_procedure_fields),
+    ) in mutation_response
+        .operation_results
+        .into_iter()
+        .zip(remote_joins)
+    {
+        let operation_result = match nested_relationships_procedure_fields {
+            None => operation_result,
+            Some(procedure_fields) => {
+                let ndc_models::MutationOperationResults::Procedure { mut result } =
+                    operation_result;
+                if let Err(err) =
+                    nested_relationships::lift_in_nested_value(&procedure_fields, &mut result)
+                {
+                    mutation_responses.push(Err(err));
+                    continue;
+                }
+                ndc_models::MutationOperationResults::Procedure { result }
+            }
+        };
+        let mutation_response_as_query_response =
+            mutation_response_to_query_response(ndc_models::MutationResponse {
+                operation_results: vec![operation_result],
+            });
+        let mutation_response = run_remote_joins(
+            http_context,
+            remote_join_executions,
+            execution_span_attribute,
+            &process_response_as,
+            project_id,
+            mutation_response_as_query_response.0,
+        )
+        .await
+        .and_then(|response_rowsets| {
+            query_response_back_to_mutation_response(ndc_models::QueryResponse(response_rowsets))
+        });
+        mutation_responses.push(mutation_response);
+    }
 
-    Ok(mutation_response)
+    Ok(mutation_responses)
 }
 
 /// Kludge-ily make a `MutationResponse` look like a `QueryResponse` so we can pass it to the
@@ -348,6 +558,9 @@ pub struct NDCSubscriptionQuery {
     pub data_connector: Arc<metadata_resolve::DataConnectorLink>,
     pub process_response_as: ProcessResponseAs,
     pub polling_interval_ms: u64,
+    /// The query, if it selects relationships inside nested objects, whose rowsets must be lifted
+    /// out of the response with `lift_nested_relationships`
+    pub nested_relationships_query_node: Option<plan_types::QueryNode>,
 }
 
 /// Resolve a subscription execution plan to a NDC query.
@@ -364,11 +577,15 @@ pub async fn resolve_ndc_subscription_execution(
     // Remote relationships and relationships without NDC comparison capability are not allowed in predicates for subscriptions.
     // Only allow local relationships and fields that can be pushed down to NDC.
     let data_connector = query_execution_plan.data_connector.clone();
+    let nested_relationships_query_node =
+        nested_relationships::has_nested_relationships(&query_execution_plan.query_node)
+            .then(|| query_execution_plan.query_node.clone());
     let query_request = make_ndc_query_request(query_execution_plan)?;
     Ok(NDCSubscriptionQuery {
         query_request,
         data_connector,
         process_response_as,
         polling_interval_ms,
+        nested_relationships_query_node,
     })
 }
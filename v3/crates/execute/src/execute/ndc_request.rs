@@ -3,6 +3,7 @@ pub mod v02;
 
 use crate::{error, ndc};
 use metadata_resolve::data_connectors::NdcVersion;
+use nonempty::NonEmpty;
 use plan_types::{MutationExecutionPlan, QueryExecutionPlan};
 
 pub fn make_ndc_query_request(
@@ -22,19 +23,22 @@ pub fn make_ndc_query_request(
     }
 }
 
+/// Make a single mutation request with an operation for each of the mutation execution plans,
+/// which must all be for the same data connector
 pub fn make_ndc_mutation_request(
-    mutation_execution_plan: MutationExecutionPlan,
+    mutation_execution_plans: NonEmpty<MutationExecutionPlan>,
 ) -> Result<ndc::NdcMutationRequest, error::FieldError> {
-    match mutation_execution_plan
+    match mutation_execution_plans
+        .head
         .data_connector
         .capabilities
         .supported_ndc_version
     {
         NdcVersion::V01 => Ok(ndc::NdcMutationRequest::V01(v01::make_mutation_request(
-            mutation_execution_plan,
+            mutation_execution_plans,
         )?)),
         NdcVersion::V02 => Ok(ndc::NdcMutationRequest::V02(v02::make_mutation_request(
-            mutation_execution_plan,
+            mutation_execution_plans,
         )?)),
     }
 }
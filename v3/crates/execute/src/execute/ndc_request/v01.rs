@@ -1,6 +1,7 @@
 use std::collections::BTreeMap;
 
 use indexmap::IndexMap;
+use nonempty::NonEmpty;
 use open_dds::data_connector::DataConnectorColumnName;
 use open_dds::types::DataConnectorArgumentName;
 
@@ -9,7 +10,7 @@ use plan_types::{
     AggregateFieldSelection, AggregateSelectionSet, Argument, Field, MutationArgument,
     MutationExecutionPlan, NestedArray, NestedField, NestedObject, OrderByDirection,
     OrderByElement, OrderByTarget, QueryExecutionPlan, QueryNode, Relationship,
-    RelationshipArgument, ResolvedFilterExpression, VariableName,
+    RelationshipArgument, ResolvedFilterExpression, VariableName, NESTED_RELATIONSHIP_FIELD_ALIAS,
 };
 
 #[derive(Debug, thiserror::Error)]
@@ -26,6 +27,9 @@ pub enum NdcV01CompatibilityError {
 
     #[error("Groupings are not supported in NDC v0.1.x")]
     GroupByNotSupported,
+
+    #[error("Relationships mapped to nested fields are not supported in NDC v0.1.x")]
+    NestedRelationshipColumnMappingsNotSupported,
 }
 
 pub fn make_query_request(
@@ -37,31 +41,36 @@ pub fn make_query_request(
         arguments: make_arguments(query_execution_plan.arguments)?,
         collection_relationships: make_collection_relationships(
             query_execution_plan.collection_relationships,
-        ),
+        )?,
         variables: make_variables(query_execution_plan.variables),
     };
     Ok(query_request)
 }
 
 pub fn make_mutation_request(
-    mutation_execution_plan: MutationExecutionPlan,
+    mutation_execution_plans: NonEmpty<MutationExecutionPlan>,
 ) -> Result<ndc_models_v01::MutationRequest, FieldError> {
-    let mutation_operation = ndc_models_v01::MutationOperation::Procedure {
-        name: ndc_models_v01::ProcedureName::new(
-            mutation_execution_plan.procedure_name.into_inner(),
-        ),
-        arguments: make_mutation_arguments(mutation_execution_plan.procedure_arguments)?,
-        fields: mutation_execution_plan
-            .procedure_fields
-            .map(make_nested_field)
-            .transpose()?,
-    };
+    let mut operations = Vec::with_capacity(mutation_execution_plans.len());
+    // relationships are shared by all the operations of the request
+    let mut collection_relationships = BTreeMap::new();
+
+    for mutation_execution_plan in mutation_execution_plans {
+        operations.push(ndc_models_v01::MutationOperation::Procedure {
+            name: ndc_models_v01::ProcedureName::new(
+                mutation_execution_plan.procedure_name.into_inner(),
+            ),
+            arguments: make_mutation_arguments(mutation_execution_plan.procedure_arguments)?,
+            fields: mutation_execution_plan
+                .procedure_fields
+                .map(make_nested_field)
+                .transpose()?,
+        });
+        collection_relationships.extend(mutation_execution_plan.collection_relationships);
+    }
 
     let mutation_request = ndc_models_v01::MutationRequest {
-        operations: vec![mutation_operation],
-        collection_relationships: make_collection_relationships(
-            mutation_execution_plan.collection_relationships,
-        ),
+        operations,
+        collection_relationships: make_collection_relationships(collection_relationships)?,
     };
 
     Ok(mutation_request)
@@ -375,13 +384,35 @@ fn make_field(field: Field) -> Result<ndc_models_v01::Field, FieldError> {
             query_node,
             relationship,
             arguments,
+            source_field_path,
         } => {
             let query = make_query(*query_node)?;
-            Ok(ndc_models_v01::Field::Relationship {
+            let relationship_field = ndc_models_v01::Field::Relationship {
                 query: Box::new(query),
                 relationship: ndc_models_v01::RelationshipName::from(relationship.as_str()),
                 arguments: make_relationship_arguments_from_arguments(arguments)?,
-            })
+            };
+            // relationships from nested source fields are selected inside the nested objects
+            // that they are followed from
+            Ok(source_field_path
+                .into_iter()
+                .rev()
+                .fold(relationship_field, |field, column| {
+                    ndc_models_v01::Field::Column {
+                        column: ndc_models_v01::FieldName::new(column.into_inner()),
+                        fields: Some(ndc_models_v01::NestedField::Object(
+                            ndc_models_v01::NestedObject {
+                                fields: IndexMap::from([(
+                                    ndc_models_v01::FieldName::from(
+                                        NESTED_RELATIONSHIP_FIELD_ALIAS,
+                                    ),
+                                    field,
+                                )]),
+                            },
+                        )),
+                        arguments: BTreeMap::new(),
+                    }
+                }))
         }
     }
 }
@@ -422,30 +453,38 @@ fn make_nested_array(nested_field: NestedArray) -> Result<ndc_models_v01::Nested
 
 fn make_collection_relationships(
     collection_relationships: BTreeMap<plan_types::NdcRelationshipName, Relationship>,
-) -> BTreeMap<ndc_models_v01::RelationshipName, ndc_models_v01::Relationship> {
+) -> Result<BTreeMap<ndc_models_v01::RelationshipName, ndc_models_v01::Relationship>, FieldError> {
     collection_relationships
         .into_iter()
         .map(|(name, relationship)| {
-            (
+            Ok((
                 ndc_models_v01::RelationshipName::from(name.as_str()),
-                make_relationship(relationship),
-            )
+                make_relationship(relationship)?,
+            ))
         })
-        .collect::<BTreeMap<_, _>>()
+        .collect::<Result<BTreeMap<_, _>, FieldError>>()
 }
 
-fn make_relationship(relationship: Relationship) -> ndc_models_v01::Relationship {
-    ndc_models_v01::Relationship {
+fn make_relationship(
+    relationship: Relationship,
+) -> Result<ndc_models_v01::Relationship, FieldError> {
+    Ok(ndc_models_v01::Relationship {
         column_mapping: relationship
             .column_mapping
             .into_iter()
             .map(|(s, t)| {
-                (
+                // NDC v0.1.x can only map to columns, not to fields nested inside them
+                let [t]: [DataConnectorColumnName; 1] = t.try_into().map_err(|_| {
+                    FieldError::InternalError(FieldInternalError::NdcV01CompatibilityError(
+                        NdcV01CompatibilityError::NestedRelationshipColumnMappingsNotSupported,
+                    ))
+                })?;
+                Ok((
                     ndc_models_v01::FieldName::new(s.into_inner()),
                     ndc_models_v01::FieldName::new(t.into_inner()),
-                )
+                ))
             })
-            .collect(),
+            .collect::<Result<_, FieldError>>()?,
         relationship_type: match relationship.relationship_type {
             open_dds::relationships::RelationshipType::Object => {
                 ndc_models_v01::RelationshipType::Object
@@ -458,7 +497,7 @@ fn make_relationship(relationship: Relationship) -> ndc_models_v01::Relationship
             relationship.target_collection.into_inner(),
         ),
         arguments: make_relationship_arguments(relationship.arguments),
-    }
+    })
 }
 
 fn make_order_by(
@@ -3,6 +3,7 @@ use std::collections::BTreeMap;
 
 use indexmap::IndexMap;
 use ndc_models as ndc_models_v02;
+use nonempty::NonEmpty;
 use open_dds::data_connector::DataConnectorColumnName;
 use open_dds::types::DataConnectorArgumentName;
 
@@ -11,7 +12,8 @@ use plan_types::{
     AggregateFieldSelection, AggregateSelectionSet, Argument, Field, MutationArgument,
     MutationExecutionPlan, NestedArray, NestedField, NestedObject, OrderByDirection,
     OrderByElement, OrderByTarget, QueryExecutionPlan, QueryNode, Relationship,
-    RelationshipArgument, ResolvedFilterExpression, VariableName,
+    RelationshipArgument, RelationshipPathElement, ResolvedFilterExpression, VariableName,
+    NESTED_RELATIONSHIP_FIELD_ALIAS,
 };
 
 pub fn make_query_request(
@@ -30,24 +32,29 @@ pub fn make_query_request(
 }
 
 pub fn make_mutation_request(
-    mutation_execution_plan: MutationExecutionPlan,
+    mutation_execution_plans: NonEmpty<MutationExecutionPlan>,
 ) -> Result<ndc_models_v02::MutationRequest, FieldError> {
-    let mutation_operation = ndc_models_v02::MutationOperation::Procedure {
-        name: ndc_models_v02::ProcedureName::new(
-            mutation_execution_plan.procedure_name.into_inner(),
-        ),
-        arguments: make_mutation_arguments(mutation_execution_plan.procedure_arguments)?,
-        fields: mutation_execution_plan
-            .procedure_fields
-            .map(make_nested_field)
-            .transpose()?,
-    };
+    let mut operations = Vec::with_capacity(mutation_execution_plans.len());
+    // relationships are shared by all the operations of the request
+    let mut collection_relationships = BTreeMap::new();
+
+    for mutation_execution_plan in mutation_execution_plans {
+        operations.push(ndc_models_v02::MutationOperation::Procedure {
+            name: ndc_models_v02::ProcedureName::new(
+                mutation_execution_plan.procedure_name.into_inner(),
+            ),
+            arguments: make_mutation_arguments(mutation_execution_plan.procedure_arguments)?,
+            fields: mutation_execution_plan
+                .procedure_fields
+                .map(make_nested_field)
+                .transpose()?,
+        });
+        collection_relationships.extend(mutation_execution_plan.collection_relationships);
+    }
 
     let mutation_request = ndc_models_v02::MutationRequest {
-        operations: vec![mutation_operation],
-        collection_relationships: make_collection_relationships(
-            mutation_execution_plan.collection_relationships,
-        ),
+        operations,
+        collection_relationships: make_collection_relationships(collection_relationships),
     };
 
     Ok(mutation_request)
@@ -97,7 +104,7 @@ fn make_query(query_node: QueryNode) -> Result<ndc_models_v02::Query, FieldError
         predicate: ndc_predicate,
         aggregates: query_node.aggregates.map(make_aggregates),
         fields: ndc_fields,
-        groups: query_node.group_by.map(make_group_by),
+        groups: query_node.group_by.map(make_group_by).transpose()?,
     })
 }
 
@@ -373,13 +380,35 @@ fn make_field(field: Field) -> Result<ndc_models_v02::Field, FieldError> {
             query_node,
             relationship,
             arguments,
+            source_field_path,
         } => {
             let query = make_query(*query_node)?;
-            Ok(ndc_models_v02::Field::Relationship {
+            let relationship_field = ndc_models_v02::Field::Relationship {
                 query: Box::new(query),
                 relationship: ndc_models_v02::RelationshipName::from(relationship.as_str()),
                 arguments: make_relationship_arguments_from_arguments(arguments)?,
-            })
+            };
+            // relationships from nested source fields are selected inside the nested objects
+            // that they are followed from
+            Ok(source_field_path
+                .into_iter()
+                .rev()
+                .fold(relationship_field, |field, column| {
+                    ndc_models_v02::Field::Column {
+                        column: ndc_models_v02::FieldName::new(column.into_inner()),
+                        fields: Some(ndc_models_v02::NestedField::Object(
+                            ndc_models_v02::NestedObject {
+                                fields: IndexMap::from([(
+                                    ndc_models_v02::FieldName::from(
+                                        NESTED_RELATIONSHIP_FIELD_ALIAS,
+                                    ),
+                                    field,
+                                )]),
+                            },
+                        )),
+                        arguments: BTreeMap::new(),
+                    }
+                }))
         }
     }
 }
@@ -440,7 +469,9 @@ fn make_relationship(relationship: Relationship) -> ndc_models_v02::Relationship
             .map(|(s, t)| {
                 (
                     ndc_models_v02::FieldName::new(s.into_inner()),
-                    vec![ndc_models_v02::FieldName::new(t.into_inner())],
+                    t.into_iter()
+                        .map(|t| ndc_models_v02::FieldName::new(t.into_inner()))
+                        .collect(),
                 )
             })
             .collect(),
@@ -459,7 +490,7 @@ fn make_relationship(relationship: Relationship) -> ndc_models_v02::Relationship
     }
 }
 
-fn make_group_by(grouping: plan_types::Grouping) -> ndc_models_v02::Grouping {
+fn make_group_by(grouping: plan_types::Grouping) -> Result<ndc_models_v02::Grouping, FieldError> {
     let aggregates = make_aggregates(plan_types::AggregateSelectionSet {
         fields: grouping.aggregates,
     });
@@ -468,6 +499,7 @@ fn make_group_by(grouping: plan_types::Grouping) -> ndc_models_v02::Grouping {
         .into_iter()
         .map(|(_dim_name, dimension)| match dimension {
             plan_types::Dimension::Column {
+                relationship_path,
                 column_path,
                 extraction,
             } => {
@@ -483,24 +515,88 @@ fn make_group_by(grouping: plan_types::Grouping) -> ndc_models_v02::Grouping {
                             .collect(),
                     )
                 };
-                ndc_models_v02::Dimension::Column {
+                Ok(ndc_models_v02::Dimension::Column {
                     arguments: BTreeMap::new(),
                     column_name,
-                    path: vec![],
+                    path: relationship_path
+                        .into_iter()
+                        .map(make_path_element)
+                        .collect::<Result<_, _>>()?,
                     field_path,
                     extraction: extraction
                         .map(|e| ndc_models_v02::ExtractionFunctionName::from(e.as_str())),
-                }
+                })
             }
         })
-        .collect();
-    ndc_models_v02::Grouping {
+        .collect::<Result<_, FieldError>>()?;
+    Ok(ndc_models_v02::Grouping {
         aggregates,
         dimensions,
-        limit: None,
-        offset: None,
-        order_by: None,
-        predicate: None,
+        limit: grouping.limit,
+        offset: grouping.offset,
+        order_by: grouping.order_by.map(make_group_order_by),
+        predicate: grouping.predicate.map(make_group_expression),
+    })
+}
+
+fn make_group_expression(
+    expression: plan_types::GroupExpression,
+) -> ndc_models_v02::GroupExpression {
+    match expression {
+        plan_types::GroupExpression::And { expressions } => ndc_models_v02::GroupExpression::And {
+            expressions: expressions.into_iter().map(make_group_expression).collect(),
+        },
+        plan_types::GroupExpression::Or { expressions } => ndc_models_v02::GroupExpression::Or {
+            expressions: expressions.into_iter().map(make_group_expression).collect(),
+        },
+        plan_types::GroupExpression::Not { expression } => ndc_models_v02::GroupExpression::Not {
+            expression: Box::new(make_group_expression(*expression)),
+        },
+        plan_types::GroupExpression::IsNull { aggregate } => {
+            ndc_models_v02::GroupExpression::UnaryComparisonOperator {
+                target: ndc_models_v02::GroupComparisonTarget::Aggregate {
+                    aggregate: make_aggregate(aggregate),
+                },
+                operator: ndc_models_v02::UnaryComparisonOperator::IsNull,
+            }
+        }
+        plan_types::GroupExpression::BinaryComparison {
+            aggregate,
+            operator,
+            value,
+        } => ndc_models_v02::GroupExpression::BinaryComparisonOperator {
+            target: ndc_models_v02::GroupComparisonTarget::Aggregate {
+                aggregate: make_aggregate(aggregate),
+            },
+            operator: ndc_models_v02::ComparisonOperatorName::new(operator.into_inner()),
+            value: ndc_models_v02::GroupComparisonValue::Scalar { value },
+        },
+    }
+}
+
+fn make_group_order_by(
+    order_by_elements: Vec<plan_types::GroupOrderByElement>,
+) -> ndc_models_v02::GroupOrderBy {
+    ndc_models_v02::GroupOrderBy {
+        elements: order_by_elements
+            .into_iter()
+            .map(|element| ndc_models_v02::GroupOrderByElement {
+                order_direction: match element.order_direction {
+                    OrderByDirection::Asc => ndc_models_v02::OrderDirection::Asc,
+                    OrderByDirection::Desc => ndc_models_v02::OrderDirection::Desc,
+                },
+                target: match element.target {
+                    plan_types::GroupOrderByTarget::Dimension { index } => {
+                        ndc_models_v02::GroupOrderByTarget::Dimension { index }
+                    }
+                    plan_types::GroupOrderByTarget::Aggregate { aggregate } => {
+                        ndc_models_v02::GroupOrderByTarget::Aggregate {
+                            aggregate: make_aggregate(aggregate),
+                        }
+                    }
+                },
+            })
+            .collect(),
     }
 }
 
@@ -542,28 +638,7 @@ fn make_order_by_target(
             // called `text`, you'll have to provide the following paths to access the `text` column:
             // ["UserPosts", "PostsComments"]
             for path_element in relationship_path {
-                order_by_element_path.push(ndc_models_v02::PathElement {
-                    field_path: if path_element.field_path.is_empty() {
-                        None
-                    } else {
-                        Some(
-                            path_element
-                                .field_path
-                                .iter()
-                                .map(|name| ndc_models_v02::FieldName::from(name.as_str()))
-                                .collect(),
-                        )
-                    },
-                    relationship: ndc_models_v02::RelationshipName::from(
-                        path_element.relationship_name.as_str(),
-                    ),
-                    arguments: BTreeMap::new(),
-                    predicate: path_element
-                        .filter_predicate
-                        .map(make_expression)
-                        .transpose()?
-                        .map(Box::new),
-                });
+                order_by_element_path.push(make_path_element(path_element)?);
             }
 
             Ok(ndc_models_v02::OrderByTarget::Column {
@@ -585,6 +660,33 @@ fn make_order_by_target(
     }
 }
 
+fn make_path_element(
+    path_element: RelationshipPathElement<ResolvedFilterExpression>,
+) -> Result<ndc_models_v02::PathElement, FieldError> {
+    Ok(ndc_models_v02::PathElement {
+        field_path: if path_element.field_path.is_empty() {
+            None
+        } else {
+            Some(
+                path_element
+                    .field_path
+                    .iter()
+                    .map(|name| ndc_models_v02::FieldName::from(name.as_str()))
+                    .collect(),
+            )
+        },
+        relationship: ndc_models_v02::RelationshipName::from(
+            path_element.relationship_name.as_str(),
+        ),
+        arguments: BTreeMap::new(),
+        predicate: path_element
+            .filter_predicate
+            .map(make_expression)
+            .transpose()?
+            .map(Box::new),
+    })
+}
+
 /// Translates the internal IR 'AggregateSelectionSet' into an NDC query aggregates selection
 fn make_aggregates(
     aggregate_selection_set: AggregateSelectionSet,
@@ -593,49 +695,48 @@ fn make_aggregates(
         .fields
         .into_iter()
         .map(|(field_name, aggregate_selection)| {
-            let aggregate = match aggregate_selection {
-                AggregateFieldSelection::Count { column_path, .. } => {
-                    make_count_aggregate(column_path, false)
-                }
-                AggregateFieldSelection::CountDistinct { column_path, .. } => {
-                    make_count_aggregate(column_path, true)
-                }
-                AggregateFieldSelection::AggregationFunction {
-                    function_name,
-                    column_path,
-                } => {
-                    let nonempty::NonEmpty {
-                        head: column,
-                        tail: field_path,
-                    } = column_path;
-                    let nested_field_path = field_path
-                        .into_iter()
-                        .map(|column_name| {
-                            ndc_models_v02::FieldName::from(column_name.into_inner())
-                        })
-                        .collect::<Vec<_>>();
-                    ndc_models_v02::Aggregate::SingleColumn {
-                        column: ndc_models_v02::FieldName::from(column.into_inner()),
-                        arguments: BTreeMap::new(),
-                        field_path: if nested_field_path.is_empty() {
-                            None
-                        } else {
-                            Some(nested_field_path)
-                        },
-                        function: ndc_models_v02::AggregateFunctionName::from(
-                            function_name.as_str(),
-                        ),
-                    }
-                }
-            };
             (
                 ndc_models_v02::FieldName::from(field_name.as_str()),
-                aggregate,
+                make_aggregate(aggregate_selection),
             )
         })
         .collect()
 }
 
+fn make_aggregate(aggregate_selection: AggregateFieldSelection) -> ndc_models_v02::Aggregate {
+    match aggregate_selection {
+        AggregateFieldSelection::Count { column_path, .. } => {
+            make_count_aggregate(column_path, false)
+        }
+        AggregateFieldSelection::CountDistinct { column_path, .. } => {
+            make_count_aggregate(column_path, true)
+        }
+        AggregateFieldSelection::AggregationFunction {
+            function_name,
+            column_path,
+        } => {
+            let nonempty::NonEmpty {
+                head: column,
+                tail: field_path,
+            } = column_path;
+            let nested_field_path = field_path
+                .into_iter()
+                .map(|column_name| ndc_models_v02::FieldName::from(column_name.into_inner()))
+                .collect::<Vec<_>>();
+            ndc_models_v02::Aggregate::SingleColumn {
+                column: ndc_models_v02::FieldName::from(column.into_inner()),
+                arguments: BTreeMap::new(),
+                field_path: if nested_field_path.is_empty() {
+                    None
+                } else {
+                    Some(nested_field_path)
+                },
+                function: ndc_models_v02::AggregateFunctionName::from(function_name.as_str()),
+            }
+        }
+    }
+}
+
 /// Creates the appropriate NDC count aggregation based on whether we're selecting
 /// a column (nested or otherwise) or not
 fn make_count_aggregate(
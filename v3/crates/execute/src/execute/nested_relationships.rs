@@ -0,0 +1,194 @@
+//! Relationships whose source fields are nested inside an object are selected inside that object
+//! in NDC queries. Once a data connector responds, the rowsets of such relationships are moved
+//! back out to the fields of the relationships, so that the rest of execution sees the same
+//! response shape as for any other local relationship.
+
+use indexmap::IndexMap;
+use serde_json as json;
+
+use crate::error::{FieldError, FieldInternalError};
+use plan_types::{
+    AggregateFieldSelection, Field, NdcFieldAlias, NestedField, QueryNode,
+    NESTED_RELATIONSHIP_FIELD_ALIAS,
+};
+
+type Row = IndexMap<ndc_models::FieldName, ndc_models::RowFieldValue>;
+
+/// Whether a query selects any relationships inside nested objects, whose rowsets need lifting
+pub(crate) fn has_nested_relationships(query_node: &QueryNode) -> bool {
+    query_node
+        .fields
+        .as_ref()
+        .is_some_and(|fields| fields_have_nested_relationships(&fields.fields))
+}
+
+/// Move the rowsets of relationships selected inside nested objects to the fields of the
+/// relationships, in the rowsets returned for a query
+pub fn lift_nested_relationships(
+    query_node: &QueryNode,
+    rowsets: &mut [ndc_models::RowSet],
+) -> Result<(), FieldError> {
+    let Some(fields) = &query_node.fields else {
+        return Ok(());
+    };
+    if !fields_have_nested_relationships(&fields.fields) {
+        return Ok(());
+    }
+    for rowset in rowsets {
+        for row in rowset.rows.iter_mut().flatten() {
+            lift_in_row(&fields.fields, row)?;
+        }
+    }
+    Ok(())
+}
+
+fn fields_have_nested_relationships(fields: &IndexMap<NdcFieldAlias, Field>) -> bool {
+    fields.values().any(|field| match field {
+        Field::Column { fields, .. } => fields
+            .as_ref()
+            .is_some_and(nested_field_has_nested_relationships),
+        Field::Relationship {
+            query_node,
+            source_field_path,
+            ..
+        } => !source_field_path.is_empty() || has_nested_relationships(query_node),
+    })
+}
+
+/// Whether a nested field selects any relationships inside nested objects
+pub(crate) fn nested_field_has_nested_relationships(nested_field: &NestedField) -> bool {
+    match nested_field {
+        NestedField::Object(nested_object) => {
+            fields_have_nested_relationships(&nested_object.fields)
+        }
+        NestedField::Array(nested_array) => {
+            nested_field_has_nested_relationships(&nested_array.fields)
+        }
+    }
+}
+
+fn lift_in_row(fields: &IndexMap<NdcFieldAlias, Field>, row: &mut Row) -> Result<(), FieldError> {
+    for (alias, field) in fields {
+        match field {
+            Field::Column { fields: None, .. } => {}
+            Field::Column {
+                fields: Some(nested_field),
+                ..
+            } => {
+                if nested_field_has_nested_relationships(nested_field) {
+                    if let Some(value) = row.get_mut(alias.as_str()) {
+                        lift_in_nested_value(nested_field, &mut value.0)?;
+                    }
+                }
+            }
+            Field::Relationship {
+                query_node,
+                source_field_path,
+                ..
+            } => {
+                let has_nested_relationships = has_nested_relationships(query_node);
+                if source_field_path.is_empty() && !has_nested_relationships {
+                    continue;
+                }
+                let value = row.get_mut(alias.as_str()).ok_or_else(|| {
+                    FieldInternalError::InternalGeneric {
+                        description: format!(
+                            "invalid NDC response; could not find relationship field {alias} in response"
+                        ),
+                    }
+                })?;
+                if !source_field_path.is_empty() {
+                    value.0 = take_nested_relationship_rowset(
+                        value.0.take(),
+                        source_field_path.len(),
+                        query_node,
+                    )?;
+                }
+                if has_nested_relationships {
+                    let mut rowset: ndc_models::RowSet = json::from_value(value.0.take())?;
+                    lift_nested_relationships(query_node, std::slice::from_mut(&mut rowset))?;
+                    value.0 = json::to_value(rowset)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Move the rowsets of relationships selected inside nested objects to the fields of the
+/// relationships, in the value of a nested field
+pub(crate) fn lift_in_nested_value(
+    nested_field: &NestedField,
+    value: &mut json::Value,
+) -> Result<(), FieldError> {
+    match nested_field {
+        _ if value.is_null() => Ok(()),
+        NestedField::Object(nested_object) if value.is_object() => {
+            let mut row: Row = json::from_value(value.take())?;
+            lift_in_row(&nested_object.fields, &mut row)?;
+            *value = json::to_value(row)?;
+            Ok(())
+        }
+        NestedField::Array(nested_array) if value.is_array() => {
+            for value in value.as_array_mut().into_iter().flatten() {
+                lift_in_nested_value(&nested_array.fields, value)?;
+            }
+            Ok(())
+        }
+        _ => Err(FieldInternalError::InternalGeneric {
+            description: format!(
+                "invalid NDC response; unexpected value {value} for a nested field"
+            ),
+        }
+        .into()),
+    }
+}
+
+/// Descend through the nested objects that a relationship was selected inside of to its rowset.
+/// If any of the objects is null, there are no rows to follow the relationship from.
+fn take_nested_relationship_rowset(
+    mut value: json::Value,
+    depth: usize,
+    query_node: &QueryNode,
+) -> Result<json::Value, FieldError> {
+    for _ in 0..depth {
+        value = match value {
+            json::Value::Null => return Ok(json::to_value(empty_rowset(query_node))?),
+            json::Value::Object(mut object) => object
+                .remove(NESTED_RELATIONSHIP_FIELD_ALIAS)
+                .ok_or_else(|| FieldInternalError::InternalGeneric {
+                    description: format!(
+                        "invalid NDC response; could not find {NESTED_RELATIONSHIP_FIELD_ALIAS} in nested object"
+                    ),
+                })?,
+            value => Err(FieldInternalError::InternalGeneric {
+                description: format!(
+                    "invalid NDC response; expected a nested object, found {value}"
+                ),
+            })?,
+        };
+    }
+    Ok(value)
+}
+
+/// The rowset a data connector returns for a query that matches no rows
+fn empty_rowset(query_node: &QueryNode) -> ndc_models::RowSet {
+    ndc_models::RowSet {
+        aggregates: query_node.aggregates.as_ref().map(|aggregates| {
+            aggregates
+                .fields
+                .iter()
+                .map(|(alias, aggregate)| {
+                    let value = match aggregate {
+                        AggregateFieldSelection::Count { .. }
+                        | AggregateFieldSelection::CountDistinct { .. } => json::Value::from(0),
+                        AggregateFieldSelection::AggregationFunction { .. } => json::Value::Null,
+                    };
+                    (ndc_models::FieldName::from(alias.as_str()), value)
+                })
+                .collect()
+        }),
+        rows: query_node.fields.as_ref().map(|_| vec![]),
+        groups: query_node.group_by.as_ref().map(|_| vec![]),
+    }
+}
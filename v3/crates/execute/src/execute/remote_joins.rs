@@ -58,31 +58,47 @@
 //! 3. Get the NDC query from the remote join node, and attach the values in the
 //!    above step as variables in the NDC query. This NDC query already has a
 //!    "where" filter clause with a variable on the join mapping field. Make the
-//!    NDC query, and call the response as RHS response.
+//!    NDC query, and call the response as RHS response. If the data connector
+//!    limits the number of variable sets per request, the values are split
+//!    across several NDC queries, whose responses are merged back in order.
 //!
 //! 4. If there is a sub-tree from this remote join node, recursively perform
 //!    this algorithm.
 //!
 //! 5. Perform join on LHS response and RHS response
+//!
+//! The remote joins at the same level of the join tree are independent of each
+//! other, so steps 2 to 4 are performed for all of them concurrently, with a
+//! limit on how many NDC queries of the request are in flight at once, set by
+//! the `remote_join_query_limit` of the `HttpContext`.
+use futures_util::future::try_join_all;
 use metadata_resolve::{Qualified, QualifiedTypeName, QualifiedTypeReference};
 use open_dds::types::CustomTypeName;
 use serde_json as json;
 use std::collections::{BTreeMap, HashMap};
 use tracing_util::SpanVisibility;
 
-use plan_types::{ProcessResponseAs, RemoteJoinObjectFieldMapping};
+use plan_types::{ProcessResponseAs, RemoteJoin, RemoteJoinObjectFieldMapping};
 
 use crate::error;
 use crate::ndc::execute_ndc_query;
 use engine_types::{HttpContext, ProjectId};
 
-use collect::ExecutableJoinNode;
+use collect::{ExecutableJoinNode, LocationInfo};
 use plan_types::{JoinLocations, RemoteJoinVariableSet};
 mod collect;
 mod join;
 
 use async_recursion::async_recursion;
 
+/// The response of the remote query of a join node, ready to be joined with the LHS response
+struct FetchedJoinNode {
+    join_node: RemoteJoin,
+    remote_alias: String,
+    location_path: Vec<LocationInfo>,
+    rhs_response: HashMap<RemoteJoinVariableSet, ndc_models::RowSet>,
+}
+
 /// Execute remote joins. As an entry-point it assumes the response is available
 /// for the top-level query, and executes further remote joins recursively.
 #[async_recursion]
@@ -112,86 +128,31 @@ pub async fn execute_join_locations(
         },
     )?;
 
-    for executable_join_node in next_join_nodes {
-        let ExecutableJoinNode {
-            variable_sets,
-            location_path,
-            mut join_node,
-            sub_tree,
-            remote_alias,
-        } = executable_join_node;
-
-        // if we do not get any join arguments back, we have nothing on the RHS
-        // to execute. Skip execution.
-        if variable_sets.is_empty() {
-            continue;
-        }
-        // patch the target/RHS IR with variable values
-        let foreach_variables: Vec<BTreeMap<plan_types::VariableName, json::Value>> = variable_sets
-            .iter()
-            .map(|variable_set| {
-                variable_set
-                    .iter()
-                    .map(|(variable_name, variable)| {
-                        let mapped_variable_value =
-                            map_remote_join_variable_value_to_target_connector(
-                                &variable.value,
-                                &variable.variable_type,
-                                &join_node.object_type_field_mappings,
-                            )?;
-                        Ok((variable_name.clone(), mapped_variable_value))
-                    })
-                    .collect::<Result<_, _>>()
-            })
-            .collect::<Result<_, error::FieldError>>()?;
-
-        join_node.target_ndc_execution.variables = Some(foreach_variables);
-
-        let ndc_query =
-            super::ndc_request::make_ndc_query_request(join_node.target_ndc_execution.clone())?;
-
-        // execute the remote query
-        let mut target_response = tracer
-            .in_span_async(
-                "execute_remote_join_query",
-                "Execute remote query for join",
-                SpanVisibility::Internal,
-                || {
-                    Box::pin(execute_ndc_query(
-                        http_context,
-                        &ndc_query,
-                        &join_node.target_data_connector,
-                        execution_span_attribute,
-                        remote_alias.clone(),
-                        project_id,
-                    ))
-                },
-            )
-            .await?
-            .as_latest_rowsets();
-
-        // if the sub-tree is not empty, recursively process the sub-tree; which
-        // will modify the `target_response` with all joins down the tree
-        if !sub_tree.locations.is_empty() {
-            execute_join_locations(
+    // the join nodes are independent of each other, so their remote queries are executed
+    // concurrently
+    let fetched_join_nodes =
+        try_join_all(next_join_nodes.into_iter().map(|executable_join_node| {
+            fetch_join_node(
                 http_context,
                 execution_span_attribute,
-                &mut target_response,
-                &join_node.process_response_as,
-                &sub_tree,
+                executable_join_node,
                 project_id,
             )
-            .await?;
-        }
+        }))
+        .await?;
+
+    for FetchedJoinNode {
+        join_node,
+        remote_alias,
+        location_path,
+        rhs_response,
+    } in fetched_join_nodes.into_iter().flatten()
+    {
         tracer.in_span(
             "response_join",
             "Join responses for remote query",
             SpanVisibility::Internal,
             || {
-                // from `Vec<RowSet>` create `HashMap<Argument, RowSet>`
-                let rhs_response: HashMap<RemoteJoinVariableSet, ndc_models::RowSet> =
-                    variable_sets.into_iter().zip(target_response).collect();
-
                 join::join_responses(
                     &location_path,
                     &join_node,
@@ -205,6 +166,174 @@ pub async fn execute_join_locations(
     Ok(())
 }
 
+/// Execute the remote query of a join node, along with any joins in its sub-tree. Returns `None`
+/// when there are no rows on the LHS to join with.
+async fn fetch_join_node(
+    http_context: &HttpContext,
+    execution_span_attribute: &'static str,
+    executable_join_node: ExecutableJoinNode,
+    project_id: Option<&ProjectId>,
+) -> Result<Option<FetchedJoinNode>, error::FieldError> {
+    let ExecutableJoinNode {
+        variable_sets,
+        location_path,
+        join_node,
+        sub_tree,
+        remote_alias,
+    } = executable_join_node;
+
+    // if we do not get any join arguments back, we have nothing on the RHS
+    // to execute. Skip execution.
+    if variable_sets.is_empty() {
+        return Ok(None);
+    }
+    let variable_sets = variable_sets.into_iter().collect::<Vec<_>>();
+
+    // patch the target/RHS IR with variable values
+    let foreach_variables: Vec<BTreeMap<plan_types::VariableName, json::Value>> = variable_sets
+        .iter()
+        .map(|variable_set| {
+            variable_set
+                .iter()
+                .map(|(variable_name, variable)| {
+                    let mapped_variable_value = map_remote_join_variable_value_to_target_connector(
+                        &variable.value,
+                        &variable.variable_type,
+                        &join_node.object_type_field_mappings,
+                    )?;
+                    Ok((variable_name.clone(), mapped_variable_value))
+                })
+                .collect::<Result<_, _>>()
+        })
+        .collect::<Result<_, error::FieldError>>()?;
+
+    // the variable sets are split across several queries if the data connector limits how many
+    // can be sent at once
+    let max_variable_sets_per_request = join_node
+        .target_data_connector
+        .request_policy
+        .as_ref()
+        .and_then(|request_policy| request_policy.max_variable_sets_per_request);
+
+    let chunk_responses = try_join_all(
+        chunk_variable_sets(&foreach_variables, max_variable_sets_per_request).map(|variables| {
+            execute_remote_join_query(
+                http_context,
+                execution_span_attribute,
+                &join_node,
+                variables.to_vec(),
+                &remote_alias,
+                project_id,
+            )
+        }),
+    )
+    .await?;
+
+    let mut target_response = merge_chunk_responses(chunk_responses, variable_sets.len())?;
+
+    // if the sub-tree is not empty, recursively process the sub-tree; which
+    // will modify the `target_response` with all joins down the tree
+    if !sub_tree.locations.is_empty() {
+        execute_join_locations(
+            http_context,
+            execution_span_attribute,
+            &mut target_response,
+            &join_node.process_response_as,
+            &sub_tree,
+            project_id,
+        )
+        .await?;
+    }
+
+    // from `Vec<RowSet>` create `HashMap<Argument, RowSet>`
+    let rhs_response = variable_sets.into_iter().zip(target_response).collect();
+
+    Ok(Some(FetchedJoinNode {
+        join_node,
+        remote_alias,
+        location_path,
+        rhs_response,
+    }))
+}
+
+/// Split the variable sets of a remote join into the chunks sent in each query, of at most
+/// `max_variable_sets_per_request` variable sets
+fn chunk_variable_sets<T>(
+    variable_sets: &[T],
+    max_variable_sets_per_request: Option<u32>,
+) -> std::slice::Chunks<'_, T> {
+    let chunk_size = max_variable_sets_per_request.map_or(variable_sets.len(), |max| max as usize);
+    variable_sets.chunks(chunk_size.max(1))
+}
+
+/// Merge the responses of the chunks of variable sets back into one rowset per variable set, in
+/// the order of the variable sets
+fn merge_chunk_responses(
+    chunk_responses: Vec<Vec<ndc_models::RowSet>>,
+    variable_set_count: usize,
+) -> Result<Vec<ndc_models::RowSet>, error::FieldError> {
+    let rowsets = chunk_responses.into_iter().flatten().collect::<Vec<_>>();
+    if rowsets.len() != variable_set_count {
+        return Err(error::NDCUnexpectedError::BadNDCResponse {
+            summary: format!(
+                "expected {variable_set_count} rowsets for the variable sets of a remote join, but got {}",
+                rowsets.len()
+            ),
+        }
+        .into());
+    }
+    Ok(rowsets)
+}
+
+/// Execute the remote query of a join node for some of its variable sets
+async fn execute_remote_join_query(
+    http_context: &HttpContext,
+    execution_span_attribute: &'static str,
+    join_node: &RemoteJoin,
+    foreach_variables: Vec<BTreeMap<plan_types::VariableName, json::Value>>,
+    remote_alias: &str,
+    project_id: Option<&ProjectId>,
+) -> Result<Vec<ndc_models::RowSet>, error::FieldError> {
+    let mut target_ndc_execution = join_node.target_ndc_execution.clone();
+    target_ndc_execution.variables = Some(foreach_variables);
+    let ndc_query = super::ndc_request::make_ndc_query_request(target_ndc_execution)?;
+
+    // wait for our turn, so that only so many queries of the request are in flight at once
+    let _permit = http_context
+        .remote_join_query_limit
+        .acquire()
+        .await
+        .map_err(|_| error::FieldInternalError::InternalGeneric {
+            description: "remote join concurrency limit was closed".to_string(),
+        })?;
+
+    let tracer = tracing_util::global_tracer();
+    let mut target_response = tracer
+        .in_span_async(
+            "execute_remote_join_query",
+            "Execute remote query for join",
+            SpanVisibility::Internal,
+            || {
+                Box::pin(execute_ndc_query(
+                    http_context,
+                    &ndc_query,
+                    &join_node.target_data_connector,
+                    join_node.target_ndc_execution.response_cache.as_ref(),
+                    execution_span_attribute,
+                    remote_alias.to_owned(),
+                    project_id,
+                ))
+            },
+        )
+        .await?
+        .as_latest_rowsets();
+    super::nested_relationships::lift_nested_relationships(
+        &join_node.target_ndc_execution.query_node,
+        &mut target_response,
+    )?;
+    Ok(target_response)
+}
+
 fn map_remote_join_variable_value_to_target_connector(
     value: &serde_json::Value,
     value_type: &QualifiedTypeReference,
@@ -290,3 +419,54 @@ fn get_json_value_type(value: &serde_json::Value) -> &'static str {
         serde_json::Value::Object(_) => "object",
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{chunk_variable_sets, merge_chunk_responses};
+    use serde_json as json;
+
+    fn rowset(id: u32) -> ndc_models::RowSet {
+        ndc_models::RowSet {
+            aggregates: None,
+            rows: Some(vec![[(
+                ndc_models::FieldName::from("id"),
+                ndc_models::RowFieldValue(json::json!(id)),
+            )]
+            .into_iter()
+            .collect()]),
+            groups: None,
+        }
+    }
+
+    #[test]
+    fn test_chunk_variable_sets() {
+        let variable_sets = [1, 2, 3, 4, 5];
+        let chunks = |max| chunk_variable_sets(&variable_sets, max).collect::<Vec<_>>();
+
+        assert_eq!(chunks(None), vec![&[1, 2, 3, 4, 5][..]]);
+        assert_eq!(chunks(Some(2)), vec![&[1, 2][..], &[3, 4], &[5]]);
+        assert_eq!(chunks(Some(5)), vec![&[1, 2, 3, 4, 5][..]]);
+        assert_eq!(chunks(Some(10)), vec![&[1, 2, 3, 4, 5][..]]);
+        assert_eq!(chunks(Some(1)).len(), 5);
+        assert_eq!(chunk_variable_sets::<u32>(&[], None).count(), 0);
+    }
+
+    #[test]
+    fn test_merge_chunk_responses_keeps_variable_set_order() {
+        let merged = merge_chunk_responses(
+            vec![
+                vec![rowset(1), rowset(2)],
+                vec![rowset(3), rowset(4)],
+                vec![rowset(5)],
+            ],
+            5,
+        )
+        .unwrap();
+        assert_eq!(merged, (1..=5).map(rowset).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_merge_chunk_responses_rejects_missing_rowsets() {
+        assert!(merge_chunk_responses(vec![vec![rowset(1)], vec![]], 2).is_err());
+    }
+}
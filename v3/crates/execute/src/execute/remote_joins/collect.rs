@@ -7,6 +7,7 @@
 use indexmap::IndexMap;
 use metadata_resolve::QualifiedTypeReference;
 use nonempty::NonEmpty;
+use open_dds::data_connector::DataConnectorColumnName;
 use serde_json as json;
 use std::collections::{BTreeMap, HashSet};
 
@@ -111,6 +112,13 @@ fn collect_argument_from_rows(
                         }
                         .into())
                     }
+                    ProcessResponseAs::Groups => {
+                        return Err(error::FieldInternalError::InternalGeneric {
+                            description: "Unexpected groups response on the LHS of a remote join"
+                                .to_owned(),
+                        }
+                        .into())
+                    }
                     ProcessResponseAs::CommandResponse {
                         command_name: _,
                         is_nullable,
@@ -144,7 +152,7 @@ fn collect_argument_from_row(
 ) -> Result<(), error::FieldError> {
     match NonEmpty::from_slice(path) {
         None => {
-            let argument = extract_variable_set(join_fields, row);
+            let argument = extract_variable_set(join_fields, row)?;
             // de-duplicate arguments
             arguments.insert(argument);
         }
@@ -179,6 +187,7 @@ pub(crate) fn get_join_fields(join_node: &RemoteJoin) -> Vec<JoinField<'_>> {
     for mapping in join_node.join_mapping.values() {
         join_fields.push(JoinField {
             source_field_alias: &mapping.source_field_alias,
+            source_field_path: &mapping.source_field_path,
             field_type: &mapping.source_field_type,
             variable_name: mapping.target_field.make_variable_name(),
         });
@@ -190,6 +199,9 @@ pub struct JoinField<'a> {
     /// The name of the field to read the value from
     pub source_field_alias: &'a SourceFieldAlias,
 
+    /// The NDC fields to descend into through nested objects in the field's value
+    pub source_field_path: &'a [DataConnectorColumnName],
+
     /// The type of the field
     pub field_type: &'a QualifiedTypeReference,
 
@@ -202,15 +214,16 @@ pub struct JoinField<'a> {
 pub(crate) fn extract_variable_set(
     join_fields: &Vec<JoinField<'_>>,
     row: &IndexMap<ndc_models::FieldName, ndc_models::RowFieldValue>,
-) -> RemoteJoinVariableSet {
+) -> Result<RemoteJoinVariableSet, error::FieldError> {
     let mut variable_set = BTreeMap::new();
     for JoinField {
         source_field_alias,
+        source_field_path,
         field_type,
         variable_name,
     } in join_fields
     {
-        let val = get_value(source_field_alias, row);
+        let val = get_nested_value(get_value(source_field_alias, row), source_field_path)?;
         variable_set.insert(
             variable_name.clone(),
             RemoteJoinVariable {
@@ -219,7 +232,7 @@ pub(crate) fn extract_variable_set(
             },
         );
     }
-    variable_set
+    Ok(variable_set)
 }
 
 pub(crate) fn get_value<'n>(
@@ -232,6 +245,33 @@ pub(crate) fn get_value<'n>(
     }
 }
 
+/// Descend into a value through nested objects. A null object along the way makes the value
+/// null, but a field missing from an object means the response does not match the query.
+fn get_nested_value<'n>(
+    value: &'n json::Value,
+    field_path: &[DataConnectorColumnName],
+) -> Result<&'n json::Value, error::FieldError> {
+    let mut value = value;
+    for field_name in field_path {
+        value = match value {
+            json::Value::Null => return Ok(&json::Value::Null),
+            json::Value::Object(object) => object.get(field_name.as_str()).ok_or_else(|| {
+                error::FieldInternalError::InternalGeneric {
+                    description: format!(
+                        "invalid NDC response; could not find nested field {field_name} in response"
+                    ),
+                }
+            })?,
+            _ => Err(error::FieldInternalError::InternalGeneric {
+                description: format!(
+                    "invalid NDC response; expected an object containing the nested field {field_name}"
+                ),
+            })?,
+        };
+    }
+    Ok(value)
+}
+
 fn rows_from_row_field_value(
     location_kind: LocationKind,
     nested_val: &ndc_models::RowFieldValue,
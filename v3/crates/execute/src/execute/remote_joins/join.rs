@@ -133,7 +133,7 @@ fn insert_value_into_row(
         // no location path; so remote join available at this level
         None => {
             let join_fields = collect::get_join_fields(join_node);
-            let argument = collect::extract_variable_set(&join_fields, row);
+            let argument = collect::extract_variable_set(&join_fields, row)?;
             let rhs_value = json::to_value(rhs_response.get(&argument))?;
             row.insert(remote_alias, ndc_models::RowFieldValue(rhs_value));
             Ok(())
@@ -0,0 +1,520 @@
+//! Ordering of the rows of a query by the engine, for queries that order by fields of remote
+//! relationships. The rows have been fetched without any pagination, ordered only by the elements
+//! before the first remote relationship, so here we join the fields of the remote relationships
+//! onto each row, sort the rows, and then apply the limit and offset of the query.
+use std::cmp::Ordering;
+
+use indexmap::IndexMap;
+use serde_json as json;
+
+use super::remote_joins;
+use crate::error::FieldError;
+use engine_types::{HttpContext, ProjectId};
+use plan_types::{
+    NdcFieldAlias, OrderByDirection, ProcessResponseAs, RemoteOrderBy, RemoteOrderByElement,
+    RemoteOrderByTarget,
+};
+
+pub(crate) async fn execute_remote_order_by(
+    http_context: &HttpContext,
+    remote_order_by: &RemoteOrderBy,
+    execution_span_attribute: &'static str,
+    process_response_as: &ProcessResponseAs,
+    project_id: Option<&ProjectId>,
+    response_rowsets: &mut Vec<ndc_models::RowSet>,
+) -> Result<(), FieldError> {
+    // one more row than the limit is fetched, so we can tell when there are too many to sort
+    let row_limit = remote_order_by.row_limit;
+    if response_rowsets.iter().any(|row_set| {
+        row_set
+            .rows
+            .as_ref()
+            .is_some_and(|rows| rows.len() > row_limit as usize)
+    }) {
+        return Err(FieldError::RemoteOrderByRowLimitExceeded { row_limit });
+    }
+
+    // fetch the fields of the remote relationships being ordered by
+    remote_joins::execute_join_locations(
+        http_context,
+        execution_span_attribute,
+        response_rowsets,
+        process_response_as,
+        &remote_order_by.remote_join_executions,
+        project_id,
+    )
+    .await?;
+
+    for row_set in response_rowsets.iter_mut() {
+        if let Some(rows) = row_set.rows.take() {
+            row_set.rows = Some(order_rows(rows, remote_order_by));
+        }
+    }
+    Ok(())
+}
+
+/// Whether the rows fetched for only the page of a query might be missing some rows that belong
+/// on it. This is the case when the rows that are equal to the last row of the page, in the
+/// elements that the data connector orders by, continue past the end of the page, as the engine
+/// orders those rows by the remaining elements, and so every row is needed.
+pub(crate) fn page_is_incomplete(
+    remote_order_by: &RemoteOrderBy,
+    response_rowsets: &[ndc_models::RowSet],
+) -> bool {
+    if !remote_order_by.fetches_page_only() {
+        return false;
+    }
+    let connector_ordered_elements =
+        &remote_order_by.elements[..remote_order_by.connector_ordered_elements];
+    // one row past the end of the page is fetched
+    let page_end = remote_order_by.fetch_limit as usize - 1;
+    response_rowsets.iter().any(|row_set| {
+        row_set.rows.as_ref().is_some_and(|rows| {
+            page_end > 0
+                && rows.len() > page_end
+                && connector_ordered_elements.iter().all(|element| {
+                    compare_values(
+                        &get_sort_value(&rows[page_end - 1], &element.target),
+                        &get_sort_value(&rows[page_end], &element.target),
+                    )
+                    .is_eq()
+                })
+        })
+    })
+}
+
+fn order_rows(
+    rows: Vec<IndexMap<ndc_models::FieldName, ndc_models::RowFieldValue>>,
+    remote_order_by: &RemoteOrderBy,
+) -> Vec<IndexMap<ndc_models::FieldName, ndc_models::RowFieldValue>> {
+    let mut keyed_rows = rows
+        .into_iter()
+        .map(|row| {
+            let sort_key = remote_order_by
+                .elements
+                .iter()
+                .map(|element| get_sort_value(&row, &element.target))
+                .collect::<Vec<_>>();
+            (sort_key, row)
+        })
+        .collect::<Vec<_>>();
+
+    // The data connector has already ordered the rows by the leading elements, so we only order
+    // runs of rows that are equal in those elements by the remaining ones. The sort is stable, so
+    // rows that compare equal keep the order the data connector returned them in.
+    let connector_ordered_elements = remote_order_by.connector_ordered_elements;
+    let engine_ordered_elements = &remote_order_by.elements[connector_ordered_elements..];
+    for run in keyed_rows.chunk_by_mut(|(left_key, _), (right_key, _)| {
+        left_key[..connector_ordered_elements]
+            .iter()
+            .zip(&right_key[..connector_ordered_elements])
+            .all(|(left, right)| compare_values(left, right).is_eq())
+    }) {
+        run.sort_by(|(left_key, _), (right_key, _)| {
+            compare_sort_keys(
+                &left_key[connector_ordered_elements..],
+                &right_key[connector_ordered_elements..],
+                engine_ordered_elements,
+            )
+        });
+    }
+
+    let offset = remote_order_by.offset.map_or(0, |offset| offset as usize);
+    let limit = remote_order_by
+        .limit
+        .map_or(usize::MAX, |limit| limit as usize);
+
+    keyed_rows
+        .into_iter()
+        .skip(offset)
+        .take(limit)
+        .map(|(_, mut row)| {
+            // the fields only used for ordering are not part of the response
+            for element in &remote_order_by.elements {
+                if let Some(alias) = element.target.row_field_alias() {
+                    row.shift_remove(alias.as_str());
+                }
+            }
+            row
+        })
+        .collect()
+}
+
+fn get_sort_value(
+    row: &IndexMap<ndc_models::FieldName, ndc_models::RowFieldValue>,
+    target: &RemoteOrderByTarget,
+) -> json::Value {
+    match target {
+        RemoteOrderByTarget::Field { field_path } => match field_path.split_first() {
+            Some((field_alias, rest)) => row
+                .get(field_alias.as_str())
+                .map_or(json::Value::Null, |value| get_nested_value(&value.0, rest)),
+            None => json::Value::Null,
+        },
+        RemoteOrderByTarget::RemoteRelationship {
+            join_alias,
+            field_path,
+        } => {
+            // the remote join inserts the row set of the related object, which is null or
+            // empty when there is no related object
+            let related_object = row
+                .get(join_alias.as_str())
+                .and_then(|value| value.0.get("rows"))
+                .and_then(|rows| rows.get(0));
+            related_object.map_or(json::Value::Null, |object| {
+                get_nested_value(object, field_path)
+            })
+        }
+    }
+}
+
+fn get_nested_value(value: &json::Value, field_path: &[NdcFieldAlias]) -> json::Value {
+    field_path
+        .iter()
+        .try_fold(value, |value, field_alias| value.get(field_alias.as_str()))
+        .cloned()
+        .unwrap_or(json::Value::Null)
+}
+
+fn compare_sort_keys(
+    left_key: &[json::Value],
+    right_key: &[json::Value],
+    elements: &[RemoteOrderByElement],
+) -> Ordering {
+    left_key
+        .iter()
+        .zip(right_key)
+        .zip(elements)
+        .map(|((left, right), element)| match element.order_direction {
+            OrderByDirection::Asc => compare_values(left, right),
+            OrderByDirection::Desc => compare_values(left, right).reverse(),
+        })
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Compares two values to order by. This is a total order, so that sorting is deterministic
+/// whatever values the data connectors return. Nulls sort after all other values, so they come
+/// last in ascending order and first in descending order. Values of different types are ordered
+/// by type: booleans, then numbers, then strings, then arrays, then objects.
+fn compare_values(left: &json::Value, right: &json::Value) -> Ordering {
+    match (left, right) {
+        (json::Value::Bool(left), json::Value::Bool(right)) => left.cmp(right),
+        (json::Value::Number(left), json::Value::Number(right)) => compare_numbers(left, right),
+        (json::Value::String(left), json::Value::String(right)) => compare_strings(left, right),
+        (json::Value::Array(left), json::Value::Array(right)) => left
+            .iter()
+            .zip(right)
+            .map(|(left, right)| compare_values(left, right))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or_else(|| left.len().cmp(&right.len())),
+        (json::Value::Object(left), json::Value::Object(right)) => {
+            // objects are compared field by field, in order of field name
+            let mut left = left.iter().collect::<Vec<_>>();
+            let mut right = right.iter().collect::<Vec<_>>();
+            left.sort_by_key(|(name, _)| *name);
+            right.sort_by_key(|(name, _)| *name);
+            left.iter()
+                .zip(&right)
+                .map(|((left_name, left), (right_name, right))| {
+                    left_name
+                        .cmp(right_name)
+                        .then_with(|| compare_values(left, right))
+                })
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or_else(|| left.len().cmp(&right.len()))
+        }
+        _ => type_rank(left).cmp(&type_rank(right)),
+    }
+}
+
+fn type_rank(value: &json::Value) -> u8 {
+    match value {
+        json::Value::Bool(_) => 0,
+        json::Value::Number(_) => 1,
+        json::Value::String(_) => 2,
+        json::Value::Array(_) => 3,
+        json::Value::Object(_) => 4,
+        json::Value::Null => 5,
+    }
+}
+
+/// Integers are compared exactly, and any other numbers as floats
+fn compare_numbers(left: &json::Number, right: &json::Number) -> Ordering {
+    if let (Some(left), Some(right)) = (left.as_i64(), right.as_i64()) {
+        return left.cmp(&right);
+    }
+    if let (Some(left), Some(right)) = (left.as_u64(), right.as_u64()) {
+        return left.cmp(&right);
+    }
+    match (left.as_f64(), right.as_f64()) {
+        (Some(left), Some(right)) => left.total_cmp(&right),
+        // only arbitrary precision numbers cannot be represented as floats
+        _ => left.to_string().cmp(&right.to_string()),
+    }
+}
+
+/// Strings are compared case insensitively, so that for example "apple" comes before "Banana",
+/// falling back to comparing their code points to order strings that only differ in case
+fn compare_strings(left: &str, right: &str) -> Ordering {
+    left.chars()
+        .flat_map(char::to_lowercase)
+        .cmp(right.chars().flat_map(char::to_lowercase))
+        .then_with(|| left.cmp(right))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_row(
+        values: &[(&str, json::Value)],
+    ) -> IndexMap<ndc_models::FieldName, ndc_models::RowFieldValue> {
+        values
+            .iter()
+            .map(|(name, value)| {
+                (
+                    ndc_models::FieldName::from(*name),
+                    ndc_models::RowFieldValue(value.clone()),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_compare_values_nulls_last() {
+        assert_eq!(
+            compare_values(&json::json!(1), &json::Value::Null),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_values(&json::json!(2), &json::json!(10)),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_values(&json::json!(1.5), &json::json!(1)),
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare_values(&json::json!("b"), &json::json!("a")),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn test_compare_values_of_different_types() {
+        let values = [
+            json::json!({ "a": 1 }),
+            json::json!([1, 2]),
+            json::json!("a"),
+            json::Value::Null,
+            json::json!(1),
+            json::json!(true),
+        ];
+        let mut sorted_values = values.to_vec();
+        sorted_values.sort_by(compare_values);
+        assert_eq!(
+            sorted_values,
+            vec![
+                json::json!(true),
+                json::json!(1),
+                json::json!("a"),
+                json::json!([1, 2]),
+                json::json!({ "a": 1 }),
+                json::Value::Null,
+            ]
+        );
+        // the order is total, so it does not depend on the order of the values being sorted
+        let mut reversed_values = values.into_iter().rev().collect::<Vec<_>>();
+        reversed_values.sort_by(compare_values);
+        assert_eq!(reversed_values, sorted_values);
+    }
+
+    #[test]
+    fn test_compare_values_strings_case_insensitively() {
+        assert_eq!(
+            compare_values(&json::json!("apple"), &json::json!("Banana")),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_values(&json::json!("Apple"), &json::json!("apple")),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_values(&json::json!("apple"), &json::json!("apple")),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn test_compare_values_large_numbers() {
+        assert_eq!(
+            compare_values(&json::json!(u64::MAX), &json::json!(-1)),
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare_values(&json::json!(u64::MAX - 1), &json::json!(u64::MAX)),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn test_order_rows_by_remote_relationship() {
+        let remote_order_by = RemoteOrderBy {
+            elements: vec![
+                RemoteOrderByElement {
+                    order_direction: OrderByDirection::Desc,
+                    target: RemoteOrderByTarget::RemoteRelationship {
+                        join_alias: NdcFieldAlias::from("__hasura_order_by__0"),
+                        field_path: vec![NdcFieldAlias::from("__hasura_order_by__0")],
+                    },
+                },
+                RemoteOrderByElement {
+                    order_direction: OrderByDirection::Asc,
+                    target: RemoteOrderByTarget::Field {
+                        field_path: vec![NdcFieldAlias::from("__hasura_order_by__1")],
+                    },
+                },
+            ],
+            connector_ordered_elements: 0,
+            remote_join_executions: plan_types::JoinLocations::new(),
+            limit: Some(2),
+            offset: Some(1),
+            row_limit: 10,
+            fetch_limit: 11,
+        };
+        let related =
+            |value: json::Value| json::json!({ "rows": [{ "__hasura_order_by__0": value }] });
+        let rows = vec![
+            make_row(&[
+                ("id", json::json!(1)),
+                ("__hasura_order_by__0", related(json::json!("a"))),
+                ("__hasura_order_by__1", json::json!(1)),
+            ]),
+            make_row(&[
+                ("id", json::json!(2)),
+                ("__hasura_order_by__0", json::json!({ "rows": [] })),
+                ("__hasura_order_by__1", json::json!(2)),
+            ]),
+            make_row(&[
+                ("id", json::json!(3)),
+                ("__hasura_order_by__0", related(json::json!("b"))),
+                ("__hasura_order_by__1", json::json!(3)),
+            ]),
+            make_row(&[
+                ("id", json::json!(4)),
+                ("__hasura_order_by__0", related(json::json!("a"))),
+                ("__hasura_order_by__1", json::json!(0)),
+            ]),
+        ];
+
+        let ordered_rows = order_rows(rows, &remote_order_by);
+
+        // nulls come first when descending, then "b", then the two "a" rows by ascending field
+        let ordered_ids = ordered_rows
+            .iter()
+            .map(|row| row.get("id").map(|value| value.0.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ordered_ids,
+            vec![Some(json::json!(3)), Some(json::json!(4))]
+        );
+
+        // the fields only used for ordering are removed
+        assert!(ordered_rows.iter().all(|row| row.len() == 1));
+    }
+
+    fn order_by_field_then_remote_relationship(limit: u32, fetch_limit: u32) -> RemoteOrderBy {
+        RemoteOrderBy {
+            elements: vec![
+                RemoteOrderByElement {
+                    order_direction: OrderByDirection::Asc,
+                    target: RemoteOrderByTarget::Field {
+                        field_path: vec![NdcFieldAlias::from("__hasura_order_by__0")],
+                    },
+                },
+                RemoteOrderByElement {
+                    order_direction: OrderByDirection::Asc,
+                    target: RemoteOrderByTarget::RemoteRelationship {
+                        join_alias: NdcFieldAlias::from("__hasura_order_by__1"),
+                        field_path: vec![NdcFieldAlias::from("__hasura_order_by__0")],
+                    },
+                },
+            ],
+            connector_ordered_elements: 1,
+            remote_join_executions: plan_types::JoinLocations::new(),
+            limit: Some(limit),
+            offset: None,
+            row_limit: 10,
+            fetch_limit,
+        }
+    }
+
+    fn make_field_and_related_row(
+        id: i64,
+        field: json::Value,
+        related: json::Value,
+    ) -> IndexMap<ndc_models::FieldName, ndc_models::RowFieldValue> {
+        make_row(&[
+            ("id", json::json!(id)),
+            ("__hasura_order_by__0", field),
+            (
+                "__hasura_order_by__1",
+                json::json!({ "rows": [{ "__hasura_order_by__0": related }] }),
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_order_rows_ordered_by_connector() {
+        let remote_order_by = order_by_field_then_remote_relationship(10, 11);
+        // the data connector orders "b" before "a", which the engine keeps, only ordering the
+        // rows with equal fields by the remote relationship
+        let rows = vec![
+            make_field_and_related_row(1, json::json!("b"), json::json!(2)),
+            make_field_and_related_row(2, json::json!("b"), json::json!(1)),
+            make_field_and_related_row(3, json::json!("a"), json::json!(2)),
+            make_field_and_related_row(4, json::json!("a"), json::json!(1)),
+        ];
+
+        let ordered_ids = order_rows(rows, &remote_order_by)
+            .iter()
+            .map(|row| row.get("id").map(|value| value.0.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ordered_ids,
+            vec![
+                Some(json::json!(2)),
+                Some(json::json!(1)),
+                Some(json::json!(4)),
+                Some(json::json!(3)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_page_is_incomplete() {
+        // a page of two rows, with one more row fetched past it
+        let remote_order_by = order_by_field_then_remote_relationship(2, 3);
+        let row_set = |fields: [i64; 3]| ndc_models::RowSet {
+            aggregates: None,
+            rows: Some(
+                fields
+                    .iter()
+                    .map(|field| make_row(&[("__hasura_order_by__0", json::json!(field))]))
+                    .collect(),
+            ),
+            groups: None,
+        };
+
+        // the row past the page differs from the last row of the page, so no rows are missing
+        assert!(!page_is_incomplete(&remote_order_by, &[row_set([1, 2, 3])]));
+        // rows equal to the last row of the page continue past it, and might belong on it
+        assert!(page_is_incomplete(&remote_order_by, &[row_set([1, 2, 2])]));
+        // when every row is fetched, no rows are missing
+        assert!(!page_is_incomplete(
+            &order_by_field_then_remote_relationship(2, 11),
+            &[row_set([1, 2, 2])]
+        ));
+    }
+}
@@ -1,5 +1,7 @@
 use crate::error::{FieldError, FieldInternalError, FilterPredicateError};
 use indexmap::{IndexMap, IndexSet};
+use metadata_resolve::Qualified;
+use open_dds::models::ModelName;
 use plan_types::{
     Argument, Field, FieldsSelection, NestedArray, NestedField, NestedObject, OrderByElement,
     OrderByTarget, QueryExecutionPlan, QueryNode, RemotePredicateKey, ResolvedFilterExpression,
@@ -29,6 +31,7 @@ pub fn replace_predicates_in_query_execution_plan(
         collection_relationships: query_execution_plan.collection_relationships,
         data_connector: query_execution_plan.data_connector,
         variables: query_execution_plan.variables,
+        response_cache: query_execution_plan.response_cache,
     })
 }
 
@@ -88,7 +91,10 @@ fn replace_predicates_in_query_node(
             .predicate
             .map(|predicate| replace_predicates_in_filter_expression(predicate, predicates))
             .transpose()?,
-        group_by: query_node.group_by,
+        group_by: query_node
+            .group_by
+            .map(|grouping| replace_predicates_in_grouping(grouping, predicates))
+            .transpose()?,
     })
 }
 
@@ -102,25 +108,66 @@ fn replace_predicates_in_order_by_target(
             name,
             field_path,
         } => Ok(OrderByTarget::Column {
-            relationship_path: relationship_path
-                .into_iter()
-                .map(|relationship_path_element| {
-                    Ok(plan_types::RelationshipPathElement {
-                        field_path: relationship_path_element.field_path,
-                        relationship_name: relationship_path_element.relationship_name,
-                        filter_predicate: relationship_path_element
-                            .filter_predicate
-                            .map(|pred| replace_predicates_in_filter_expression(pred, predicates))
-                            .transpose()?,
-                    })
-                })
-                .collect::<Result<Vec<_>, FilterPredicateError>>()?,
+            relationship_path: replace_predicates_in_relationship_path(
+                relationship_path,
+                predicates,
+            )?,
             name,
             field_path,
         }),
     }
 }
 
+fn replace_predicates_in_grouping(
+    grouping: plan_types::Grouping,
+    predicates: &BTreeMap<RemotePredicateKey, ResolvedFilterExpression>,
+) -> Result<plan_types::Grouping, FilterPredicateError> {
+    Ok(plan_types::Grouping {
+        dimensions: grouping
+            .dimensions
+            .into_iter()
+            .map(|(alias, dimension)| {
+                let dimension = match dimension {
+                    plan_types::Dimension::Column {
+                        relationship_path,
+                        column_path,
+                        extraction,
+                    } => plan_types::Dimension::Column {
+                        relationship_path: replace_predicates_in_relationship_path(
+                            relationship_path,
+                            predicates,
+                        )?,
+                        column_path,
+                        extraction,
+                    },
+                };
+                Ok((alias, dimension))
+            })
+            .collect::<Result<IndexMap<_, _>, FilterPredicateError>>()?,
+        ..grouping
+    })
+}
+
+fn replace_predicates_in_relationship_path(
+    relationship_path: Vec<plan_types::RelationshipPathElement<ResolvedFilterExpression>>,
+    predicates: &BTreeMap<RemotePredicateKey, ResolvedFilterExpression>,
+) -> Result<Vec<plan_types::RelationshipPathElement<ResolvedFilterExpression>>, FilterPredicateError>
+{
+    relationship_path
+        .into_iter()
+        .map(|relationship_path_element| {
+            Ok(plan_types::RelationshipPathElement {
+                field_path: relationship_path_element.field_path,
+                relationship_name: relationship_path_element.relationship_name,
+                filter_predicate: relationship_path_element
+                    .filter_predicate
+                    .map(|pred| replace_predicates_in_filter_expression(pred, predicates))
+                    .transpose()?,
+            })
+        })
+        .collect()
+}
+
 fn replace_predicates_in_nested_field(
     nested_field: NestedField,
     predicates: &BTreeMap<RemotePredicateKey, ResolvedFilterExpression>,
@@ -172,8 +219,10 @@ fn replace_predicates_in_field(
             query_node,
             relationship,
             arguments,
+            source_field_path,
         } => Field::Relationship {
             relationship,
+            source_field_path,
             query_node: Box::new(replace_predicates_in_query_node(*query_node, predicates)?),
             arguments: arguments
                 .into_iter()
@@ -274,85 +323,222 @@ fn replace_predicates_in_filter_expression(
     })
 }
 
-/// Utility to store distinct comparisons to avoid duplicate comparison predicates
-/// in the remote relationship comparison expression.
-struct DistinctComparisons {
-    comparisons: IndexSet<ResolvedFilterExpression>,
+/// Order a remote predicate query by the target columns and fetch a page of `page_size` rows from
+/// `offset`, so that the values of the target columns are fetched a bounded number at a time.
+/// Rows with the same values are next to each other, so the distinct values across the pages do
+/// not depend on how the data connector orders them.
+pub fn page_remote_predicate_query(
+    query_node: &mut QueryNode,
+    ndc_column_mapping: &[plan_types::RelationshipColumnMapping],
+    page_size: u32,
+    offset: u32,
+) {
+    query_node.order_by = Some(
+        ndc_column_mapping
+            .iter()
+            .map(|column_mapping| OrderByElement {
+                order_direction: plan_types::OrderByDirection::Asc,
+                target: OrderByTarget::Column {
+                    relationship_path: vec![],
+                    name: column_mapping.target_ndc_column.clone(),
+                    field_path: vec![],
+                },
+            })
+            .collect(),
+    );
+    query_node.limit = Some(page_size);
+    query_node.offset = Some(offset);
 }
 
-impl DistinctComparisons {
-    fn new() -> Self {
-        DistinctComparisons {
-            comparisons: IndexSet::new(),
+/// Collect the distinct values of the target columns in the NDC response rows of a remote
+/// predicate query, in the order they were returned. There can be no more than `max_keys`
+/// distinct values, to bound the size of the filter.
+pub fn collect_distinct_keys(
+    rows: Vec<IndexMap<ndc_models::FieldName, ndc_models::RowFieldValue>>,
+    ndc_column_mapping: &[plan_types::RelationshipColumnMapping],
+    target_model_name: &Qualified<ModelName>,
+    max_keys: Option<u32>,
+    distinct_keys: &mut IndexSet<Vec<serde_json::Value>>,
+) -> Result<(), FieldError> {
+    for mut row in rows {
+        let key = ndc_column_mapping
+            .iter()
+            .map(|column_mapping| {
+                let target_column_field =
+                    ndc_models::FieldName::from(column_mapping.target_ndc_column.as_str());
+                // Fetch RHS (target) column value from the row
+                row.swap_remove(&target_column_field)
+                    .map(|target_value| target_value.0)
+                    .ok_or_else(|| FieldInternalError::InternalGeneric {
+                        description: format!(
+                            "Unable to build remote predicate local comparison. Target field from NDC response not found: {target_column_field}"
+                        ),
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        distinct_keys.insert(key);
+        if let Some(max_keys) = max_keys {
+            if distinct_keys.len() > max_keys as usize {
+                return Err(FieldError::RemotePredicateKeyLimitExceeded {
+                    target_model_name: target_model_name.clone(),
+                    max_keys,
+                });
+            }
         }
     }
-
-    fn push(&mut self, expression: ResolvedFilterExpression) {
-        self.comparisons.insert(expression);
-    }
-
-    fn into_vec(self) -> Vec<ResolvedFilterExpression> {
-        self.comparisons.into_iter().collect()
-    }
+    Ok(())
 }
 
-/// Build the column comparison expressions using the equal operator NDC response rows.
+/// Build the column comparison expressions using the equal operator from the distinct values of
+/// the target columns.
 ///
 /// [[(a, a_value_1, b, b_value_1), (a, a_value_2, b, b_value_2)]] --->
 /// WHERE (a = a_value_1 AND b = b_value_1) OR (a = a_value_2 AND b = b_value_2)
 /// The above filter is semantically equivalent to
 /// WHERE (a, b) IN ((a_value_1, b_value_1), (a_value_2, b_value_2))
 pub fn build_source_column_comparisons(
-    mut rows: Vec<IndexMap<ndc_models::FieldName, ndc_models::RowFieldValue>>,
+    distinct_keys: IndexSet<Vec<serde_json::Value>>,
     ndc_column_mapping: &[plan_types::RelationshipColumnMapping],
-) -> Result<ResolvedFilterExpression, FieldError> {
-    let mut expressions = DistinctComparisons::new();
-    for row in &mut rows {
-        let mut column_comparisons = Vec::new();
-        for column_mapping in ndc_column_mapping {
-            let target_column_field =
-                ndc_models::FieldName::from(column_mapping.target_ndc_column.as_str());
-            // Fetch RHS (target) column value from the row
-            let target_value = row.swap_remove(&target_column_field).ok_or_else(
-                || FieldInternalError::InternalGeneric{
-                        description: format!(
-                            "Unable to build remote predicate local comparison. Target field from NDC response not found: {target_column_field}"
-                        ),
-                }
-            )?;
-
-            let plan_types::SourceNdcColumn {
-                column: source_column,
-                field_path,
-                eq_operator,
-            } = &column_mapping.source_ndc_column;
-            // Generate LHS (source) column comparison with target column value
-            column_comparisons.push(ResolvedFilterExpression::LocalFieldComparison(
-                plan_types::LocalFieldComparison::BinaryComparison {
-                    column: plan_types::ComparisonTarget::Column {
-                        // The column name is the root column
-                        name: field_path.first().unwrap_or(source_column).clone(),
-                        // The field path is the nesting path inside the root column, if any
-                        field_path: field_path
-                            .iter()
-                            .chain([source_column])
-                            .skip(1)
-                            .cloned()
-                            .collect(),
-                    },
-                    operator: eq_operator.clone(),
-                    value: plan_types::ComparisonValue::Scalar {
-                        value: target_value.0,
-                    },
-                },
-            ));
-        }
-        // combine column comparisons from each row with AND
-        // Ex. (source_column_a = target_column_value) AND (source_column_b = target_column_value)
-        expressions.push(ResolvedFilterExpression::mk_and(column_comparisons));
-    }
+) -> ResolvedFilterExpression {
+    let expressions = distinct_keys
+        .into_iter()
+        .map(|key| {
+            let column_comparisons = ndc_column_mapping
+                .iter()
+                .zip(key)
+                .map(|(column_mapping, target_value)| {
+                    let plan_types::SourceNdcColumn {
+                        column: source_column,
+                        field_path,
+                        eq_operator,
+                    } = &column_mapping.source_ndc_column;
+                    // Generate LHS (source) column comparison with target column value
+                    ResolvedFilterExpression::LocalFieldComparison(
+                        plan_types::LocalFieldComparison::BinaryComparison {
+                            column: plan_types::ComparisonTarget::Column {
+                                // The column name is the root column
+                                name: field_path.first().unwrap_or(source_column).clone(),
+                                // The field path is the nesting path inside the root column, if any
+                                field_path: field_path
+                                    .iter()
+                                    .chain([source_column])
+                                    .skip(1)
+                                    .cloned()
+                                    .collect(),
+                            },
+                            operator: eq_operator.clone(),
+                            value: plan_types::ComparisonValue::Scalar {
+                                value: target_value,
+                            },
+                        },
+                    )
+                })
+                .collect();
+            // combine column comparisons from each row with AND
+            // Ex. (source_column_a = target_column_value) AND (source_column_b = target_column_value)
+            ResolvedFilterExpression::mk_and(column_comparisons)
+        })
+        .collect();
     // combine all row comparisons with OR
     // Ex. (source_column_a = target_column_value) AND (source_column_b = target_column_value)
     //     OR (source_column_a = target_column_value) AND (source_column_b = target_column_value)
-    Ok(ResolvedFilterExpression::mk_or(expressions.into_vec()))
+    ResolvedFilterExpression::mk_or(expressions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use open_dds::data_connector::{DataConnectorColumnName, DataConnectorOperatorName};
+    use open_dds::{identifier, subgraph_identifier};
+
+    fn make_rows(ids: &[i64]) -> Vec<IndexMap<ndc_models::FieldName, ndc_models::RowFieldValue>> {
+        ids.iter()
+            .map(|id| {
+                IndexMap::from_iter([(
+                    ndc_models::FieldName::from("author_id"),
+                    ndc_models::RowFieldValue(serde_json::json!(id)),
+                )])
+            })
+            .collect()
+    }
+
+    fn make_column_mapping() -> Vec<plan_types::RelationshipColumnMapping> {
+        vec![plan_types::RelationshipColumnMapping {
+            source_ndc_column: plan_types::SourceNdcColumn {
+                column: DataConnectorColumnName::from("id"),
+                field_path: vec![],
+                eq_operator: DataConnectorOperatorName::from("_eq"),
+            },
+            target_ndc_column: DataConnectorColumnName::from("author_id"),
+        }]
+    }
+
+    fn make_model_name() -> Qualified<ModelName> {
+        Qualified::new(
+            subgraph_identifier!("default"),
+            ModelName::new(identifier!("Articles")),
+        )
+    }
+
+    fn collect_keys(
+        ids: &[i64],
+        max_keys: Option<u32>,
+    ) -> Result<IndexSet<Vec<serde_json::Value>>, FieldError> {
+        let mut distinct_keys = IndexSet::new();
+        collect_distinct_keys(
+            make_rows(ids),
+            &make_column_mapping(),
+            &make_model_name(),
+            max_keys,
+            &mut distinct_keys,
+        )?;
+        Ok(distinct_keys)
+    }
+
+    #[test]
+    fn test_build_source_column_comparisons_deduplicates_keys() {
+        let distinct_keys = collect_keys(&[1, 2, 1, 2, 1], Some(2)).unwrap();
+        let expression = build_source_column_comparisons(distinct_keys, &make_column_mapping());
+
+        let ResolvedFilterExpression::Or { expressions } = expression else {
+            panic!("expected an or expression, got {expression:?}");
+        };
+        assert_eq!(expressions.len(), 2);
+    }
+
+    #[test]
+    fn test_collect_distinct_keys_key_limit() {
+        assert!(matches!(
+            collect_keys(&[1, 2, 3], Some(2)),
+            Err(FieldError::RemotePredicateKeyLimitExceeded { max_keys: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_collect_distinct_keys_without_key_limit() {
+        assert_eq!(collect_keys(&[3, 1, 3, 2], None).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_collect_distinct_keys_across_pages() {
+        let mut distinct_keys = IndexSet::new();
+        for page in [&[1, 1, 2][..], &[2, 3, 3]] {
+            collect_distinct_keys(
+                make_rows(page),
+                &make_column_mapping(),
+                &make_model_name(),
+                Some(3),
+                &mut distinct_keys,
+            )
+            .unwrap();
+        }
+        assert_eq!(
+            distinct_keys.into_iter().collect::<Vec<_>>(),
+            vec![
+                vec![serde_json::json!(1)],
+                vec![serde_json::json!(2)],
+                vec![serde_json::json!(3)]
+            ]
+        );
+    }
 }
@@ -1,24 +1,31 @@
 pub mod client;
 pub mod migration;
+mod replicas;
+mod response_cache;
 pub mod types;
+pub use replicas::start_read_replica_health_checks;
 pub use types::*;
 
 use std::borrow::Cow;
+use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
 
 use axum::http::HeaderMap;
+use rand::Rng;
 
 use lang_graphql::ast::common as ast;
 use tracing_util::{set_attribute_on_active_span, AttributeVisibility, SpanVisibility};
 
 use crate::error;
-use engine_types::{HttpContext, ProjectId};
+use engine_types::{CircuitBreakers, CircuitKey, HttpContext, ProjectId};
 
-/// Executes a NDC operation
+/// Executes a NDC operation, reusing a cached response if the model or command caches them
 pub async fn execute_ndc_query(
     http_context: &HttpContext,
     query: &NdcQueryRequest,
     data_connector: &metadata_resolve::DataConnectorLink,
+    response_cache: Option<&plan_types::ResponseCache>,
     execution_span_attribute: &'static str,
     field_span_attribute: String,
     project_id: Option<&ProjectId>,
@@ -44,9 +51,28 @@ pub async fn execute_ndc_query(
                         "field",
                         field_span_attribute,
                     );
+                    let cache_key = response_cache.and_then(|response_cache| {
+                        response_cache::cache_key(query, data_connector, response_cache, project_id)
+                    });
+                    if let Some(cache_key) = &cache_key {
+                        if let Some(cached_response) =
+                            response_cache::get(http_context, cache_key, query).await
+                        {
+                            return Ok(cached_response);
+                        }
+                    }
                     let connector_response =
                         fetch_from_data_connector(http_context, query, data_connector, project_id)
                             .await?;
+                    if let (Some(cache_key), Some(response_cache)) = (cache_key, response_cache) {
+                        response_cache::insert(
+                            http_context,
+                            cache_key,
+                            &connector_response,
+                            response_cache,
+                        )
+                        .await;
+                    }
                     Ok(connector_response)
                 })
             },
@@ -70,14 +96,15 @@ pub async fn fetch_from_data_connector(
                 Box::pin(async {
                     let headers =
                         append_project_id_to_headers(&data_connector.headers.0, project_id)?;
-                    let ndc_config = client::Configuration {
-                        base_path: data_connector.url.get_url(ast::OperationType::Query),
-                        // This is isn't expensive, reqwest::Client is behind an Arc
-                        client: http_context.client.clone(),
-                        headers,
-                        response_size_limit: http_context.ndc_response_size_limit,
-                    };
-                    client::query_post(ndc_config, query_request).await
+                    // queries are idempotent, so they can be retried
+                    send_with_request_policy(
+                        &http_context.ndc_circuit_breakers,
+                        data_connector.request_policy.as_ref(),
+                        circuit_key(data_connector, ast::OperationType::Query, project_id),
+                        true,
+                        || post_query(http_context, data_connector, &headers, query_request),
+                    )
+                    .await
                     // .map_err(error::RequestError::from) // error::Error -> InternalError -> Error
                 })
             },
@@ -85,6 +112,154 @@ pub async fn fetch_from_data_connector(
         .await
 }
 
+// Send a query to the data connector. Each retry of the query picks a read replica again.
+async fn post_query(
+    http_context: &HttpContext,
+    data_connector: &metadata_resolve::DataConnectorLink,
+    headers: &HeaderMap,
+    query_request: &NdcQueryRequest,
+) -> Result<NdcQueryResponse, client::Error> {
+    send_to_query_url(http_context, data_connector, |base_path| {
+        let ndc_config = client::Configuration {
+            base_path,
+            // This is isn't expensive, reqwest::Client is behind an Arc
+            client: http_context.client.clone(),
+            headers: Cow::Borrowed(headers),
+            response_size_limit: http_context.ndc_response_size_limit,
+            timeout: request_timeout(data_connector),
+        };
+        client::query_post(ndc_config, query_request)
+    })
+    .await
+}
+
+/// Sends a request to the URL that the data connector serves queries on, balancing it between
+/// the read replicas of the data connector if it has any
+pub async fn send_to_query_url<'a, T, F, Fut>(
+    http_context: &HttpContext,
+    data_connector: &'a metadata_resolve::DataConnectorLink,
+    send: F,
+) -> Result<T, client::Error>
+where
+    F: FnOnce(&'a reqwest::Url) -> Fut,
+    Fut: Future<Output = Result<T, client::Error>>,
+{
+    let read_replica = replicas::acquire_read_replica(http_context, data_connector);
+    let base_path = read_replica.as_ref().map_or_else(
+        || data_connector.url.get_url(ast::OperationType::Query),
+        |read_replica| read_replica.url,
+    );
+    let result = send(base_path).await;
+    if let (Some(read_replica), Err(error)) = (&read_replica, &result) {
+        read_replica.report_error(error);
+    }
+    result
+}
+
+fn request_timeout(data_connector: &metadata_resolve::DataConnectorLink) -> Option<Duration> {
+    data_connector
+        .request_policy
+        .as_ref()
+        .and_then(|request_policy| request_policy.timeout)
+}
+
+// The circuit of the data connector for the requests of the given operation type
+fn circuit_key(
+    data_connector: &metadata_resolve::DataConnectorLink,
+    operation_type: ast::OperationType,
+    project_id: Option<&ProjectId>,
+) -> CircuitKey {
+    CircuitKey {
+        project_id: project_id.map(|project_id| project_id.0.clone()),
+        data_connector: data_connector.name.to_string(),
+        url: data_connector.url.get_url(operation_type).to_string(),
+    }
+}
+
+/// Sends a request to the data connector using its request policy. Requests are rejected while
+/// the circuit of the data connector is open, and transient failures of `retryable` requests
+/// are retried with a jittered exponential backoff.
+async fn send_with_request_policy<T, F, Fut>(
+    circuit_breakers: &CircuitBreakers,
+    request_policy: Option<&metadata_resolve::data_connectors::RequestPolicy>,
+    circuit_key: CircuitKey,
+    retryable: bool,
+    send: F,
+) -> Result<T, client::Error>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<T, client::Error>>,
+{
+    let Some(request_policy) = request_policy else {
+        return send().await;
+    };
+    let retry_policy = request_policy.retry.as_ref().filter(|_| retryable);
+
+    let mut retries = 0;
+    loop {
+        if let Some(circuit_breaker) = &request_policy.circuit_breaker {
+            let acquired =
+                circuit_breakers.try_acquire(&circuit_key, circuit_breaker.reset_timeout);
+            set_attribute_on_active_span(
+                AttributeVisibility::Default,
+                "circuit_breaker.state",
+                circuit_breakers.state(&circuit_key).as_str(),
+            );
+            if !acquired {
+                return Err(client::Error::CircuitOpen);
+            }
+        }
+
+        let result = send()
+            .await
+            .map_err(|error| match (error, request_policy.timeout) {
+                (client::Error::Reqwest(error), Some(timeout)) if error.is_timeout() => {
+                    client::Error::Timeout(timeout)
+                }
+                (error, _) => error,
+            });
+
+        let transient_failure = result.as_ref().is_err_and(client::Error::is_transient);
+        if let Some(circuit_breaker) = &request_policy.circuit_breaker {
+            if transient_failure {
+                circuit_breakers.record_failure(
+                    &circuit_key,
+                    circuit_breaker.failure_threshold,
+                    circuit_breaker.reset_timeout,
+                );
+            } else {
+                circuit_breakers.record_success(&circuit_key);
+            }
+        }
+
+        match retry_policy {
+            Some(retry_policy) if transient_failure && retries < retry_policy.max_retries => {
+                tokio::time::sleep(retry_backoff(retry_policy, retries)).await;
+                retries += 1;
+                set_attribute_on_active_span(
+                    AttributeVisibility::Default,
+                    "retries",
+                    i64::from(retries),
+                );
+            }
+            _ => return result,
+        }
+    }
+}
+
+/// The delay before a retry: the initial backoff doubled for every previous retry, up to the
+/// maximum backoff, and randomly reduced by up to half so that concurrent retries are spread out
+fn retry_backoff(
+    retry_policy: &metadata_resolve::data_connectors::RetryPolicy,
+    retries: u32,
+) -> Duration {
+    let backoff = retry_policy
+        .initial_backoff
+        .saturating_mul(2_u32.saturating_pow(retries))
+        .min(retry_policy.max_backoff);
+    backoff.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+}
+
 // This function appends project-id (if present) to the HeaderMap defined by the data_connector object
 pub fn append_project_id_to_headers<'a>(
     headers: &'a HeaderMap,
@@ -169,10 +344,202 @@ pub async fn fetch_from_data_connector_mutation(
                         client: http_context.client.clone(),
                         headers,
                         response_size_limit: http_context.ndc_response_size_limit,
+                        timeout: request_timeout(data_connector),
                     };
-                    client::mutation_post(ndc_config, query_request).await
+                    // mutations are never retried, as they may have been applied already
+                    send_with_request_policy(
+                        &http_context.ndc_circuit_breakers,
+                        data_connector.request_policy.as_ref(),
+                        circuit_key(data_connector, ast::OperationType::Mutation, project_id),
+                        false,
+                        || client::mutation_post(ndc_config.clone(), query_request),
+                    )
+                    .await
                 })
             },
         )
         .await
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+
+    use engine_types::{CircuitBreakers, CircuitKey};
+    use metadata_resolve::data_connectors::{CircuitBreakerPolicy, RequestPolicy, RetryPolicy};
+    use pretty_assertions::assert_eq;
+
+    use super::{client, send_with_request_policy};
+
+    fn request_policy() -> RequestPolicy {
+        RequestPolicy {
+            timeout: Some(Duration::from_millis(50)),
+            retry: Some(RetryPolicy {
+                max_retries: 2,
+                initial_backoff: Duration::from_millis(1),
+                max_backoff: Duration::from_millis(2),
+            }),
+            circuit_breaker: None,
+            max_variable_sets_per_request: None,
+            max_remote_filter_values: None,
+        }
+    }
+
+    fn circuit_key() -> CircuitKey {
+        CircuitKey {
+            project_id: None,
+            data_connector: "db".to_string(),
+            url: "http://db/".to_string(),
+        }
+    }
+
+    fn connector_error(status: reqwest::StatusCode) -> client::Error {
+        client::Error::InvalidConnector(client::InvalidConnectorError {
+            status,
+            content: serde_json::Value::Null,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_transient_failures_are_retried() {
+        let attempts = AtomicU32::new(0);
+        let result = send_with_request_policy(
+            &CircuitBreakers::default(),
+            Some(&request_policy()),
+            circuit_key(),
+            true,
+            || async {
+                // the last retry succeeds
+                if attempts.fetch_add(1, Ordering::SeqCst) < 2 {
+                    Err(connector_error(reqwest::StatusCode::SERVICE_UNAVAILABLE))
+                } else {
+                    Ok(())
+                }
+            },
+        )
+        .await;
+        assert!(result.is_ok());
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retries_are_bounded() {
+        let attempts = AtomicU32::new(0);
+        let result = send_with_request_policy(
+            &CircuitBreakers::default(),
+            Some(&request_policy()),
+            circuit_key(),
+            true,
+            || async {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err::<(), _>(connector_error(reqwest::StatusCode::BAD_GATEWAY))
+            },
+        )
+        .await;
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_non_transient_failures_and_mutations_are_not_retried() {
+        let attempts = AtomicU32::new(0);
+        let result = send_with_request_policy(
+            &CircuitBreakers::default(),
+            Some(&request_policy()),
+            circuit_key(),
+            true,
+            || async {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err::<(), _>(connector_error(reqwest::StatusCode::BAD_REQUEST))
+            },
+        )
+        .await;
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+
+        let attempts = AtomicU32::new(0);
+        let result = send_with_request_policy(
+            &CircuitBreakers::default(),
+            Some(&request_policy()),
+            circuit_key(),
+            false,
+            || async {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err::<(), _>(connector_error(reqwest::StatusCode::SERVICE_UNAVAILABLE))
+            },
+        )
+        .await;
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        // a data connector that accepts connections but never responds
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/query", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            // keep the connections open
+            let mut connections = Vec::new();
+            for connection in listener.incoming() {
+                connections.push(connection);
+            }
+        });
+
+        let request_policy = RequestPolicy {
+            retry: None,
+            ..request_policy()
+        };
+        let client = reqwest::Client::new();
+        let result = send_with_request_policy(
+            &CircuitBreakers::default(),
+            Some(&request_policy),
+            circuit_key(),
+            true,
+            || async {
+                client
+                    .post(&url)
+                    .timeout(Duration::from_millis(50))
+                    .send()
+                    .await
+                    .map(|_| ())
+                    .map_err(client::Error::from)
+            },
+        )
+        .await;
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "request to connector timed out after 50 ms"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_open_circuit_rejects_requests() {
+        let circuit_breakers = CircuitBreakers::default();
+        let request_policy = RequestPolicy {
+            retry: None,
+            circuit_breaker: Some(CircuitBreakerPolicy {
+                failure_threshold: 1,
+                reset_timeout: Duration::from_secs(60),
+            }),
+            ..request_policy()
+        };
+        let attempts = AtomicU32::new(0);
+        for _ in 0..2 {
+            let _ = send_with_request_policy(
+                &circuit_breakers,
+                Some(&request_policy),
+                circuit_key(),
+                true,
+                || async {
+                    attempts.fetch_add(1, Ordering::SeqCst);
+                    Err::<(), _>(connector_error(reqwest::StatusCode::SERVICE_UNAVAILABLE))
+                },
+            )
+            .await;
+        }
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+        assert_eq!(circuit_breakers.state(&circuit_key()).as_str(), "open");
+    }
+}
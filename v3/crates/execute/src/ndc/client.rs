@@ -1,4 +1,5 @@
 use std::borrow::Cow;
+use std::time::Duration;
 
 use metadata_resolve::data_connectors::NdcVersion;
 use reqwest::header::{HeaderMap, HeaderValue};
@@ -41,6 +42,46 @@ pub enum Error {
 
     #[error("invalid connector error: {0}")]
     InvalidConnector(InvalidConnectorError),
+
+    #[error("request to connector timed out after {} ms", .0.as_millis())]
+    Timeout(Duration),
+
+    #[error("requests to connector are rejected because it failed repeatedly")]
+    CircuitOpen,
+}
+
+impl Error {
+    /// Whether the error is caused by the connector being unreachable or unavailable, rather
+    /// than by the request. Only these failures are retried and counted by circuit breakers.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Timeout(_) => true,
+            Self::Reqwest(error) => {
+                error.is_timeout()
+                    || error.is_connect()
+                    || error.status().is_some_and(is_transient_status)
+            }
+            Self::Connector(ConnectorError { status, .. })
+            | Self::InvalidConnector(InvalidConnectorError { status, .. }) => {
+                is_transient_status(*status)
+            }
+            Self::Serde(_)
+            | Self::Io(_)
+            | Self::InvalidBaseURL
+            | Self::ProjectIdHeaderValueConversion(_)
+            | Self::ResponseTooLarge(_)
+            | Self::CircuitOpen => false,
+        }
+    }
+}
+
+fn is_transient_status(status: reqwest::StatusCode) -> bool {
+    matches!(
+        status,
+        reqwest::StatusCode::BAD_GATEWAY
+            | reqwest::StatusCode::SERVICE_UNAVAILABLE
+            | reqwest::StatusCode::GATEWAY_TIMEOUT
+    )
 }
 
 impl tracing_util::TraceableError for Error {
@@ -71,6 +112,7 @@ pub struct Configuration<'s> {
     pub client: reqwest::Client,
     pub headers: Cow<'s, HeaderMap<HeaderValue>>,
     pub response_size_limit: Option<usize>,
+    pub timeout: Option<Duration>,
 }
 
 /// POST on /query/explain endpoint
@@ -305,6 +347,41 @@ pub async fn query_post(
         .await
 }
 
+/// GET on /health endpoint
+///
+/// <https://hasura.github.io/ndc-spec/specification/health.html>
+pub async fn health_get(
+    configuration: Configuration<'_>,
+    ndc_version: NdcVersion,
+) -> Result<(), Error> {
+    let tracer = tracing_util::global_tracer();
+    tracer
+        .in_span_async(
+            "health_get",
+            "Get health of connector",
+            SpanVisibility::Internal,
+            || {
+                Box::pin(async {
+                    let url = append_path(configuration.base_path, &["health"])?;
+                    let request = construct_request(
+                        configuration,
+                        ndc_version,
+                        reqwest::Method::GET,
+                        url,
+                        |r| r,
+                    );
+                    request
+                        .headers(tracing_util::get_trace_headers())
+                        .send()
+                        .await?
+                        .error_for_status()?;
+                    Ok(())
+                })
+            },
+        )
+        .await
+}
+
 #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct QueryRelRequest {
     pub rel: plan_pushdown_types::Rel,
@@ -373,6 +450,10 @@ fn construct_request(
                 let mut request_builder = configuration.client.request(method, url);
                 // Apply customizations
                 request_builder = modify(request_builder);
+                // Bound the whole request, including reading the response
+                if let Some(timeout) = configuration.timeout {
+                    request_builder = request_builder.timeout(timeout);
+                }
                 // Set headers from configuration
                 request_builder = request_builder.headers(configuration.headers.into_owned());
                 // Set X-Hasura-NDC-Version
@@ -0,0 +1,155 @@
+//! Balancing of queries between the read replicas of a data connector
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use engine_types::{HttpContext, LoadBalancingStrategy, ReplicaLease};
+use metadata_resolve::data_connectors::ResolvedReadReplicaUrls;
+use metadata_resolve::Metadata;
+use open_dds::data_connector::ReplicaLoadBalancing;
+use tracing_util::{set_attribute_on_active_span, AttributeVisibility};
+
+use super::client;
+
+/// A read replica that a query is sent to
+pub(super) struct ReadReplica<'a> {
+    pub url: &'a reqwest::Url,
+    lease: ReplicaLease,
+    ejection_cooldown: Duration,
+}
+
+impl ReadReplica<'_> {
+    /// Ejects the replica from its pool if the query could not connect to it
+    pub fn report_error(&self, error: &client::Error) {
+        if let client::Error::Reqwest(error) = error {
+            if error.is_connect() {
+                self.lease.eject(self.ejection_cooldown);
+            }
+        }
+    }
+}
+
+/// Picks the read replica to send a query to, if the data connector has read replicas
+pub(super) fn acquire_read_replica<'a>(
+    http_context: &HttpContext,
+    data_connector: &'a metadata_resolve::DataConnectorLink,
+) -> Option<ReadReplica<'a>> {
+    let read_replicas = data_connector.url.read_replicas()?;
+    let strategy = match read_replicas.load_balancing {
+        ReplicaLoadBalancing::RoundRobin => LoadBalancingStrategy::RoundRobin,
+        ReplicaLoadBalancing::LeastOutstandingRequests => {
+            LoadBalancingStrategy::LeastOutstandingRequests
+        }
+    };
+    let lease = http_context.ndc_replica_pools.acquire(
+        &data_connector.name.to_string(),
+        read_replicas.read.len(),
+        strategy,
+    );
+    set_attribute_on_active_span(
+        AttributeVisibility::Default,
+        "read_replica",
+        i64::try_from(lease.replica()).unwrap_or(i64::MAX),
+    );
+    Some(ReadReplica {
+        url: &read_replicas.read[lease.replica()].0,
+        lease,
+        ejection_cooldown: read_replicas.ejection_cooldown,
+    })
+}
+
+/// Starts probing the `/health` endpoint of the read replicas of every data connector of the
+/// metadata that has health checks. The probes of a data connector stop once the metadata is
+/// dropped, so that the probes of replaced metadata do not outlive it.
+pub fn start_read_replica_health_checks(http_context: &HttpContext, metadata: &Arc<Metadata>) {
+    let model_data_connectors = metadata
+        .models
+        .values()
+        .filter_map(|model| model.model.source.as_ref())
+        .map(|model_source| &model_source.data_connector);
+    let command_data_connectors = metadata
+        .commands
+        .values()
+        .filter_map(|command| command.command.source.as_ref())
+        .map(|command_source| &command_source.data_connector);
+    // every model and command source has its own copy of the link of its data connector
+    let mut data_connectors = HashMap::new();
+    for data_connector in model_data_connectors.chain(command_data_connectors) {
+        data_connectors
+            .entry(&data_connector.name)
+            .or_insert(data_connector);
+    }
+
+    for data_connector in data_connectors.into_values() {
+        if let Some(interval) = data_connector
+            .url
+            .read_replicas()
+            .and_then(|read_replicas| read_replicas.health_check_interval)
+        {
+            tokio::spawn(probe_read_replicas(
+                http_context.clone(),
+                Arc::downgrade(metadata),
+                data_connector.clone(),
+                interval,
+            ));
+        }
+    }
+}
+
+// Probe the `/health` endpoint of every read replica of the data connector, every `interval`,
+// for as long as the metadata of the data connector is alive
+async fn probe_read_replicas(
+    http_context: HttpContext,
+    metadata: Weak<Metadata>,
+    data_connector: Arc<metadata_resolve::DataConnectorLink>,
+    interval: Duration,
+) {
+    let Some(read_replicas) = data_connector.url.read_replicas() else {
+        return;
+    };
+    let pool = data_connector.name.to_string();
+    while metadata.strong_count() > 0 {
+        for replica in 0..read_replicas.read.len() {
+            let healthy = probe_read_replica(
+                &http_context,
+                &data_connector,
+                read_replicas,
+                replica,
+                interval,
+            )
+            .await;
+            http_context.ndc_replica_pools.set_healthy(
+                &pool,
+                read_replicas.read.len(),
+                replica,
+                healthy,
+            );
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+async fn probe_read_replica(
+    http_context: &HttpContext,
+    data_connector: &metadata_resolve::DataConnectorLink,
+    read_replicas: &ResolvedReadReplicaUrls,
+    replica: usize,
+    interval: Duration,
+) -> bool {
+    let ndc_config = client::Configuration {
+        base_path: &read_replicas.read[replica].0,
+        client: http_context.client.clone(),
+        headers: Cow::Borrowed(&data_connector.headers.0),
+        response_size_limit: http_context.ndc_response_size_limit,
+        // a probe must not outlive the next one
+        timeout: Some(interval),
+    };
+    client::health_get(
+        ndc_config,
+        data_connector.capabilities.supported_ndc_version,
+    )
+    .await
+    .is_ok()
+}
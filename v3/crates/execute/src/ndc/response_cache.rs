@@ -0,0 +1,282 @@
+//! Caching of the responses of data connectors to queries of models and commands that opt into
+//! it in their metadata
+
+use bytes::Bytes;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use engine_types::{HttpContext, ProjectId};
+use hasura_authn_core::Session;
+use lang_graphql::ast::common as ast;
+use open_dds::data_connector::DataConnectorName;
+use tracing_util::{set_attribute_on_active_span, AttributeVisibility};
+
+use super::{NdcQueryRequest, NdcQueryResponse};
+
+/// Everything that identifies the response of a data connector to a query. The permission
+/// predicates and argument presets of the request are already part of the query request.
+#[derive(Serialize)]
+struct CacheKey<'a> {
+    project_id: Option<&'a ProjectId>,
+    data_connector: &'a metadata_resolve::Qualified<DataConnectorName>,
+    url: &'a str,
+    query_request: &'a NdcQueryRequest,
+    session: Option<&'a Session>,
+}
+
+/// The key that the response to the query is cached under, if the query request can be serialized
+pub(super) fn cache_key(
+    query_request: &NdcQueryRequest,
+    data_connector: &metadata_resolve::DataConnectorLink,
+    response_cache: &plan_types::ResponseCache,
+    project_id: Option<&ProjectId>,
+) -> Option<String> {
+    let key = CacheKey {
+        project_id,
+        data_connector: &data_connector.name,
+        url: data_connector
+            .url
+            .get_url(ast::OperationType::Query)
+            .as_str(),
+        query_request,
+        session: response_cache.session.as_ref(),
+    };
+    let key = serde_json::to_vec(&key).ok()?;
+    Some(format!("{:x}", Sha256::digest(key)))
+}
+
+/// Returns the cached response to the query, recording whether it was a hit on the active span
+pub(super) async fn get(
+    http_context: &HttpContext,
+    key: &str,
+    query_request: &NdcQueryRequest,
+) -> Option<NdcQueryResponse> {
+    let response = http_context
+        .ndc_response_cache
+        .get(key)
+        .await
+        .and_then(|response| deserialize_response(query_request, &response));
+    set_attribute_on_active_span(
+        AttributeVisibility::Default,
+        "response_cache",
+        if response.is_some() { "hit" } else { "miss" },
+    );
+    response
+}
+
+/// Caches the response to the query for the TTL of the model or command
+pub(super) async fn insert(
+    http_context: &HttpContext,
+    key: String,
+    response: &NdcQueryResponse,
+    response_cache: &plan_types::ResponseCache,
+) {
+    if let Some(response) = serialize_response(response) {
+        http_context
+            .ndc_response_cache
+            .insert(key, response, response_cache.ttl)
+            .await;
+    }
+}
+
+fn serialize_response(response: &NdcQueryResponse) -> Option<Bytes> {
+    match response {
+        NdcQueryResponse::V01(response) => serde_json::to_vec(response),
+        NdcQueryResponse::V02(response) => serde_json::to_vec(response),
+    }
+    .ok()
+    .map(Bytes::from)
+}
+
+// responses are cached without their version, which is the version of the query request
+fn deserialize_response(
+    query_request: &NdcQueryRequest,
+    response: &[u8],
+) -> Option<NdcQueryResponse> {
+    match query_request {
+        NdcQueryRequest::V01(_) => serde_json::from_slice(response)
+            .ok()
+            .map(NdcQueryResponse::V01),
+        NdcQueryRequest::V02(_) => serde_json::from_slice(response)
+            .ok()
+            .map(NdcQueryResponse::V02),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::time::Duration;
+
+    use engine_types::{
+        CircuitBreakers, HttpContext, PersistedQueryCache, ProjectId, RemoteJoinQueryLimit,
+        ReplicaPools, ResponseCache,
+    };
+    use hasura_authn_core::{Role, RoleAuthorization, Session, SessionVariableList};
+
+    use super::super::{execute_ndc_query, NdcQueryRequest};
+
+    fn http_context() -> HttpContext {
+        HttpContext {
+            client: reqwest::Client::new(),
+            ndc_response_size_limit: None,
+            ndc_circuit_breakers: CircuitBreakers::default(),
+            ndc_replica_pools: ReplicaPools::default(),
+            ndc_response_cache: ResponseCache::default(),
+            remote_join_query_limit: RemoteJoinQueryLimit::default(),
+            persisted_queries: PersistedQueryCache::default(),
+        }
+    }
+
+    fn data_connector(url: &str) -> metadata_resolve::DataConnectorLink {
+        serde_json::from_value(serde_json::json!({
+            "name": { "name": "db" },
+            "url": { "singleUrl": url },
+            "headers": {},
+            "capabilities": { "supported_ndc_version": "V01" }
+        }))
+        .unwrap()
+    }
+
+    fn query_request(collection: &str) -> NdcQueryRequest {
+        NdcQueryRequest::V01(
+            serde_json::from_value(serde_json::json!({
+                "collection": collection,
+                "query": { "fields": {} },
+                "arguments": {},
+                "collection_relationships": {}
+            }))
+            .unwrap(),
+        )
+    }
+
+    fn session(role: &str) -> Session {
+        RoleAuthorization {
+            role: Role::new(role),
+            session_variables: std::collections::HashMap::new(),
+            allowed_session_variables_from_request: SessionVariableList::All,
+        }
+        .build_session(BTreeMap::new())
+    }
+
+    fn response_cache(session: Option<Session>) -> plan_types::ResponseCache {
+        plan_types::ResponseCache {
+            ttl: Duration::from_secs(60),
+            session,
+        }
+    }
+
+    async fn execute(
+        http_context: &HttpContext,
+        data_connector: &metadata_resolve::DataConnectorLink,
+        query_request: &NdcQueryRequest,
+        response_cache: &plan_types::ResponseCache,
+        project_id: Option<&ProjectId>,
+    ) {
+        execute_ndc_query(
+            http_context,
+            query_request,
+            data_connector,
+            Some(response_cache),
+            "test",
+            "test".to_string(),
+            project_id,
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_identical_queries_are_served_from_the_cache() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/query")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"[{"rows":[]}]"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let http_context = http_context();
+        let data_connector = data_connector(&server.url());
+        let query_request = query_request("authors");
+        for _ in 0..2 {
+            execute(
+                &http_context,
+                &data_connector,
+                &query_request,
+                &response_cache(None),
+                None,
+            )
+            .await;
+        }
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_different_queries_projects_and_sessions_miss_the_cache() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/query")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"[{"rows":[]}]"#)
+            .expect(5)
+            .create_async()
+            .await;
+
+        let http_context = http_context();
+        let data_connector = data_connector(&server.url());
+        let authors = query_request("authors");
+        let articles = query_request("articles");
+        let project_1 = ProjectId("project_1".to_string());
+        let project_2 = ProjectId("project_2".to_string());
+
+        // cached without a session
+        execute(
+            &http_context,
+            &data_connector,
+            &authors,
+            &response_cache(None),
+            None,
+        )
+        .await;
+        // another query
+        execute(
+            &http_context,
+            &data_connector,
+            &articles,
+            &response_cache(None),
+            None,
+        )
+        .await;
+        // another project
+        execute(
+            &http_context,
+            &data_connector,
+            &authors,
+            &response_cache(None),
+            Some(&project_1),
+        )
+        .await;
+        execute(
+            &http_context,
+            &data_connector,
+            &authors,
+            &response_cache(None),
+            Some(&project_2),
+        )
+        .await;
+        // another session, when the cache is per session
+        execute(
+            &http_context,
+            &data_connector,
+            &authors,
+            &response_cache(Some(session("user"))),
+            None,
+        )
+        .await;
+        mock.assert_async().await;
+    }
+}
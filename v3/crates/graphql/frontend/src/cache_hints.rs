@@ -0,0 +1,119 @@
+//! Cache hints for the responses of GraphQL queries sent with GET.
+//!
+//! A response can only be cached when every model that the query selects from, including through
+//! relationships, has a cache hint. It is then cached for the shortest of their max ages, and
+//! only by the client unless every model allows shared caches to store it, and the role of the
+//! request sees every row of every model. Cached responses vary by the headers that the session
+//! is resolved from.
+
+use axum::http::{header, HeaderName, HeaderValue};
+use hasura_authn_core::Role;
+use metadata_resolve::{FilterPermission, ModelCacheHint};
+use open_dds::models::CacheScope;
+
+/// The request headers that a cacheable response varies by: the headers credentials are usually
+/// sent in, and the header that selects the role of the request
+pub const VARY_HEADERS: [HeaderName; 3] = [
+    header::AUTHORIZATION,
+    header::COOKIE,
+    HeaderName::from_static("x-hasura-role"),
+];
+
+/// Combines the cache hints of the models that a query selects from, returning `None` when the
+/// response to the query cannot be cached
+pub fn resolve_cache_hint(
+    metadata: &metadata_resolve::Metadata,
+    role: &Role,
+    ir: &graphql_ir::IR<'_, '_>,
+) -> Option<ModelCacheHint> {
+    let graphql_ir::IR::Query(_) = ir else {
+        return None;
+    };
+    let usage_counts = graphql_ir::get_all_usage_counts_in_query(ir);
+
+    // commands are not cached, as we know nothing about how often their results change
+    if !usage_counts.commands_used.is_empty() {
+        return None;
+    }
+
+    let mut combined_cache_hint: Option<ModelCacheHint> = None;
+    for model_count in &usage_counts.models_used {
+        let model = metadata.models.get(&model_count.model)?;
+        let mut cache_hint = model.graphql_api.cache_hint?;
+        // the rows that a role with a permission filter sees can depend on the session, so its
+        // responses must not be shared with other sessions
+        let has_permission_filter = model
+            .select_permissions
+            .get(role)
+            .is_none_or(|permission| permission.filter != FilterPermission::AllowAll);
+        if has_permission_filter {
+            cache_hint.scope = CacheScope::Private;
+        }
+        combined_cache_hint = Some(match combined_cache_hint {
+            None => cache_hint,
+            Some(combined_cache_hint) => combine_cache_hints(combined_cache_hint, cache_hint),
+        });
+    }
+    combined_cache_hint
+}
+
+fn combine_cache_hints(left: ModelCacheHint, right: ModelCacheHint) -> ModelCacheHint {
+    ModelCacheHint {
+        max_age: left.max_age.min(right.max_age),
+        scope: match (left.scope, right.scope) {
+            (CacheScope::Public, CacheScope::Public) => CacheScope::Public,
+            _ => CacheScope::Private,
+        },
+    }
+}
+
+/// The value of the `Cache-Control` header for a response with the given cache hint
+pub fn cache_control_header_value(cache_hint: &ModelCacheHint) -> HeaderValue {
+    let scope = match cache_hint.scope {
+        CacheScope::Private => "private",
+        CacheScope::Public => "public",
+    };
+    HeaderValue::from_str(&format!("{scope}, max-age={}", cache_hint.max_age))
+        .expect("cache control header value is valid")
+}
+
+/// The value of the `Vary` header for a response that can be cached
+pub fn vary_header_value() -> HeaderValue {
+    let vary = VARY_HEADERS
+        .iter()
+        .map(HeaderName::as_str)
+        .collect::<Vec<_>>()
+        .join(", ");
+    HeaderValue::from_str(&vary).expect("vary header value is valid")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_combine_cache_hints() {
+        let public = ModelCacheHint {
+            max_age: 60,
+            scope: CacheScope::Public,
+        };
+        let private = ModelCacheHint {
+            max_age: 30,
+            scope: CacheScope::Private,
+        };
+
+        assert_eq!(combine_cache_hints(public, public), public);
+        assert_eq!(
+            combine_cache_hints(public, private),
+            ModelCacheHint {
+                max_age: 30,
+                scope: CacheScope::Private,
+            }
+        );
+        assert_eq!(
+            cache_control_header_value(&combine_cache_hints(public, private)),
+            "private, max-age=30"
+        );
+        assert_eq!(vary_header_value(), "authorization, cookie, x-hasura-role");
+    }
+}
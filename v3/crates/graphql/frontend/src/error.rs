@@ -8,11 +8,17 @@ use tracing_util::{ErrorVisibility, TraceableError};
 /// Ref: <https://spec.graphql.org/October2021/#sec-Errors.Request-errors>
 #[derive(Debug, thiserror::Error)]
 pub enum RequestError {
+    #[error("{0}")]
+    PersistedQueryError(#[from] crate::PersistedQueryError),
+
     #[error("parsing failed: {0}")]
     ParseFailure(#[from] gql::ast::spanning::Positioned<gql::parser::Error>),
 
     #[error("validation failed: {0}")]
-    ValidationFailed(#[from] gql::validation::Error),
+    ValidationFailed(gql::validation::Error),
+
+    #[error("{0}")]
+    QueryLimitExceeded(#[from] crate::QueryLimitError),
 
     #[error("{0}")]
     IRConversionError(#[from] graphql_ir::Error),
@@ -22,6 +28,19 @@ pub enum RequestError {
 
     #[error("explain error: {0}")]
     ExplainError(String),
+
+    #[error("mutations cannot be sent with GET requests, use POST instead")]
+    MutationWithGetRequest,
+}
+
+impl From<gql::validation::Error> for RequestError {
+    fn from(error: gql::validation::Error) -> Self {
+        // limits enforced during validation are reported like the other query limits
+        match crate::QueryLimitError::from_validation_error(&error) {
+            Some(query_limit_error) => Self::QueryLimitExceeded(query_limit_error),
+            None => Self::ValidationFailed(error),
+        }
+    }
 }
 
 impl RequestError {
@@ -37,10 +56,16 @@ impl RequestError {
         // We are using the visibility of the error to determine if it is an internal error or not. We are assuming that
         // if we are showing the error message to the user, it is something that they can fix on their end.
         let is_internal = self.visibility() == ErrorVisibility::Internal;
+        let extensions = match self {
+            Self::QueryLimitExceeded(error) => Some(gql::http::Extensions {
+                details: error.details(),
+            }),
+            _ => None,
+        };
         GraphQLError {
             message,
             path: None,
-            extensions: None,
+            extensions,
             is_internal,
         }
     }
@@ -52,9 +77,12 @@ impl TraceableError for RequestError {
             Self::IRConversionError(ir_error) => ir_error.visibility(),
             Self::GraphQlPlanError(plan_error) => plan_error.visibility(),
             // Rest all errors are visible to users via traces
-            Self::ParseFailure(_) | Self::ValidationFailed(_) | Self::ExplainError(_) => {
-                ErrorVisibility::User
-            }
+            Self::PersistedQueryError(_)
+            | Self::ParseFailure(_)
+            | Self::ValidationFailed(_)
+            | Self::QueryLimitExceeded(_)
+            | Self::ExplainError(_)
+            | Self::MutationWithGetRequest => ErrorVisibility::User,
         }
     }
 }
@@ -3,17 +3,18 @@ mod types;
 use crate::process_response::{process_mutation_response, process_response, ProcessedResponse};
 use engine_types::{HttpContext, ProjectId};
 use execute::FieldError;
-use execute::{resolve_ndc_mutation_execution, resolve_ndc_query_execution};
+use execute::{resolve_ndc_mutation_executions, resolve_ndc_query_execution};
 use gql::normalized_ast;
 use gql::schema::NamespacedGetter;
-use graphql_ir::MutationPlan;
 use graphql_ir::{ApolloFederationSelect, NodeQueryPlan, QueryPlan};
+use graphql_ir::{MutationPlan, MutationSelect};
 use graphql_schema::GDSRoleNamespaceGetter;
 use graphql_schema::GDS;
 use indexmap::IndexMap;
 use lang_graphql as gql;
 use lang_graphql::ast::common as ast;
-use plan_types::{NDCMutationExecution, NDCQueryExecution};
+use nonempty::NonEmpty;
+use plan_types::NDCQueryExecution;
 use tracing_util::{set_attribute_on_active_span, AttributeVisibility};
 pub use types::{ExecuteQueryResult, RootFieldResult};
 
@@ -44,7 +45,7 @@ pub async fn execute_query_plan(
 }
 
 /// Execute a single root field's query plan to produce a result.
-async fn execute_query_field_plan(
+pub(crate) async fn execute_query_field_plan(
     field_alias: &ast::Alias,
     http_context: &HttpContext,
     query_plan: NodeQueryPlan<'_, '_, '_>,
@@ -201,46 +202,109 @@ fn resolve_type_name(type_name: ast::TypeName) -> Result<serde_json::Value, Fiel
     Ok(serde_json::to_value(type_name)?)
 }
 
-/// Execute a single root field's mutation plan to produce a result.
-async fn execute_mutation_field_plan(
+/// Execute the root fields of a mutation that use the same data connector, as a single mutation
+/// request, to produce a result for each of them.
+async fn execute_mutation_group_plan(
     http_context: &HttpContext,
-    mutation_plan: NDCMutationExecution,
-    selection_set: &normalized_ast::SelectionSet<'_, GDS>,
+    mutation_group: IndexMap<ast::Alias, MutationSelect<'_, '_>>,
     project_id: Option<&ProjectId>,
-) -> RootFieldResult {
+) -> Vec<(ast::Alias, RootFieldResult)> {
     let tracer = tracing_util::global_tracer();
     tracer
         .in_span_async(
-            "execute_mutation_field_plan",
-            "Execute request plan for mutation field",
+            "execute_mutation_group_plan",
+            "Execute request plan for mutation fields",
             tracing_util::SpanVisibility::User,
             || {
                 Box::pin(async {
-                    let process_response_as = &mutation_plan.process_response_as.clone();
-                    let processed_response =
-                        resolve_ndc_mutation_execution(http_context, mutation_plan, project_id)
-                            .await
-                            .and_then(|mutation_response| {
-                                process_mutation_response(
-                                    selection_set,
-                                    mutation_response,
-                                    process_response_as,
-                                )
-                            });
+                    let mut fields = Vec::new();
+                    let mut mutation_executions = Vec::new();
+                    for (alias, field_plan) in mutation_group {
+                        fields.push((
+                            alias,
+                            field_plan.selection_set,
+                            field_plan.mutation_execution.process_response_as.clone(),
+                        ));
+                        mutation_executions.push(field_plan.mutation_execution);
+                    }
+                    let Some(mutation_executions) = NonEmpty::from_vec(mutation_executions) else {
+                        return Vec::new();
+                    };
 
-                    RootFieldResult::from_processed_response(
-                        process_response_as.is_nullable(),
-                        processed_response,
+                    match resolve_ndc_mutation_executions(
+                        http_context,
+                        mutation_executions,
+                        project_id,
                     )
+                    .await
+                    {
+                        Ok(mutation_responses) => fields
+                            .into_iter()
+                            .zip(mutation_responses)
+                            .map(
+                                |(
+                                    (alias, selection_set, process_response_as),
+                                    mutation_response,
+                                )| {
+                                    let processed_response =
+                                        mutation_response.and_then(|mutation_response| {
+                                            process_mutation_response(
+                                                selection_set,
+                                                mutation_response,
+                                                &process_response_as,
+                                            )
+                                        });
+                                    (
+                                        alias,
+                                        RootFieldResult::from_processed_response(
+                                            process_response_as.is_nullable(),
+                                            processed_response,
+                                        ),
+                                    )
+                                },
+                            )
+                            .collect(),
+                        // the whole request failed, so the error is reported against the first
+                        // field, and the rest of the fields refer to it
+                        Err(error) => {
+                            let mut fields = fields.into_iter();
+                            let mut results = Vec::new();
+                            if let Some((alias, _selection_set, process_response_as)) =
+                                fields.next()
+                            {
+                                let failed_field_name = alias.to_string();
+                                results.push((
+                                    alias,
+                                    RootFieldResult::new(
+                                        process_response_as.is_nullable(),
+                                        Err(error),
+                                    ),
+                                ));
+                                for (alias, _selection_set, process_response_as) in fields {
+                                    results.push((
+                                        alias,
+                                        RootFieldResult::new(
+                                            process_response_as.is_nullable(),
+                                            Err(FieldError::BatchedMutationFailed {
+                                                field_name: failed_field_name.clone(),
+                                            }),
+                                        ),
+                                    ));
+                                }
+                            }
+                            results
+                        }
+                    }
                 })
             },
         )
         .await
 }
 
-/// Given an entire plan for a mutation, produce a result. We do this by executing the singular
-/// root fields of the mutation sequentially rather than concurrently, in the order defined by the
-/// `IndexMap`'s keys.
+/// Given an entire plan for a mutation, produce a result. We do this by executing the root fields
+/// of the mutation sequentially rather than concurrently, in the order they were requested. Each
+/// run of consecutive root fields using the same data connector is executed together, as a single
+/// mutation request.
 pub async fn execute_mutation_plan(
     http_context: &HttpContext,
     mutation_plan: MutationPlan<'_, '_>,
@@ -262,18 +326,8 @@ pub async fn execute_mutation_plan(
     }
 
     for (_, mutation_group) in mutation_plan.nodes {
-        for (alias, field_plan) in mutation_group {
-            executed_root_fields.push((
-                alias,
-                execute_mutation_field_plan(
-                    http_context,
-                    field_plan.mutation_execution,
-                    field_plan.selection_set,
-                    project_id,
-                )
-                .await,
-            ));
-        }
+        executed_root_fields
+            .extend(execute_mutation_group_plan(http_context, mutation_group, project_id).await);
     }
 
     for (alias, root_field) in executed_root_fields {
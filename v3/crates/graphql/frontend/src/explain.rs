@@ -6,7 +6,7 @@ use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use async_recursion::async_recursion;
-use engine_types::{ExposeInternalErrors, HttpContext};
+use engine_types::{ExposeInternalErrors, HttpContext, ProjectId};
 use execute::ndc::client as ndc_client;
 use graphql_ir::{
     ApolloFederationSelect, GraphqlRequestPipeline, MutationPlan, NodeQueryPlan, QueryPlan,
@@ -34,6 +34,7 @@ pub async fn execute_explain(
     session: &Session,
     request_headers: &reqwest::header::HeaderMap,
     request: RawRequest,
+    project_id: Option<&ProjectId>,
 ) -> (Option<ast::OperationType>, types::ExplainResponse) {
     explain_query_internal(
         request_pipeline,
@@ -44,6 +45,7 @@ pub async fn execute_explain(
         session,
         request_headers,
         request,
+        project_id,
     )
     .await
     .map_or_else(
@@ -67,6 +69,7 @@ async fn explain_query_internal(
     session: &Session,
     request_headers: &reqwest::header::HeaderMap,
     raw_request: gql::http::RawRequest,
+    project_id: Option<&ProjectId>,
 ) -> Result<(ast::OperationType, types::ExplainResponse), crate::RequestError> {
     let tracer = tracing_util::global_tracer();
     tracer
@@ -86,12 +89,21 @@ async fn explain_query_internal(
                     raw_request.query.to_string(),
                 );
                 Box::pin(async {
+                    // resolve the query, which may be a persisted query, and check the allowlist
+                    let query = steps::resolve_query(
+                        metadata,
+                        &http_context.persisted_queries,
+                        session,
+                        &raw_request,
+                        project_id,
+                    )?;
+
                     // parse the raw request into a GQL query
-                    let query = steps::parse_query(&raw_request.query)?;
+                    let query = steps::parse_query(&query)?;
 
                     // normalize the parsed GQL query
                     let normalized_request =
-                        steps::normalize_request(schema, session, query, &raw_request)?;
+                        steps::normalize_request(schema, metadata, session, query, &raw_request)?;
 
                     // generate IR
                     let ir = steps::build_ir(
@@ -300,40 +312,52 @@ pub(crate) async fn explain_mutation_plan(
     }
 
     for (_, mutation_group) in mutation_plan.nodes {
-        for (alias, ndc_mutation_execution) in mutation_group {
-            // we don't have remote predicates on mutations
-            // so there won't be any steps here
-            let predicate_explain_steps = vec![];
+        // the fields using the same data connector are sent to it in a single request
+        let mut aliases = Vec::new();
+        let mut mutation_executions = Vec::new();
+        for (alias, mutation_select) in mutation_group {
+            aliases.push(alias.to_string());
+            mutation_executions.push(mutation_select.mutation_execution);
+        }
+        let Some(mutation_executions) = NonEmpty::from_vec(mutation_executions) else {
+            continue;
+        };
 
-            let resolved_execution_plan = ndc_mutation_execution
-                .mutation_execution
-                .execution_tree
-                .mutation_execution_plan;
+        let process_response_as = mutation_executions.head.process_response_as.clone();
+        let data_connector = mutation_executions.head.data_connector.clone();
+        let mut join_locations = Vec::new();
+        let mutation_execution_plans = mutation_executions.map(|mutation_execution| {
+            join_locations.push(mutation_execution.execution_tree.remote_join_executions);
+            mutation_execution.execution_tree.mutation_execution_plan
+        });
 
-            let mutation_request = execute::make_ndc_mutation_request(resolved_execution_plan)
-                .map_err(|e| crate::RequestError::ExplainError(e.to_string()))?;
+        let mutation_request = execute::make_ndc_mutation_request(mutation_execution_plans)
+            .map_err(|e| crate::RequestError::ExplainError(e.to_string()))?;
 
-            let sequence_steps = get_execution_steps(
-                expose_internal_errors,
-                http_context,
-                alias.to_string(),
-                &ndc_mutation_execution
-                    .mutation_execution
-                    .process_response_as,
-                ndc_mutation_execution
-                    .mutation_execution
-                    .execution_tree
-                    .remote_join_executions,
-                types::NDCRequest::Mutation(mutation_request),
-                &ndc_mutation_execution.mutation_execution.data_connector,
-            )
-            .await?;
-            let field_steps = prepend_vec_to_nonempty(
-                predicate_explain_steps.into_iter().map(Box::new).collect(),
-                sequence_steps,
-            );
-            root_steps.push(Box::new(types::Step::Sequence(field_steps)));
+        // we don't have remote predicates on mutations, so the steps start with the mutation
+        // itself, followed by the remote joins of the first field
+        let mut join_locations = join_locations.into_iter();
+        let mut sequence_steps = get_execution_steps(
+            expose_internal_errors,
+            http_context,
+            aliases.join(", "),
+            &process_response_as,
+            join_locations.next().unwrap_or_else(JoinLocations::new),
+            types::NDCRequest::Mutation(mutation_request),
+            &data_connector,
+        )
+        .await?;
+
+        // and then the remote joins of the rest of the fields
+        for join_locations in join_locations {
+            if let Some(join_steps) =
+                get_join_steps(expose_internal_errors, join_locations, http_context).await?
+            {
+                sequence_steps.push(Box::new(types::Step::Sequence(join_steps)));
+                sequence_steps.push(Box::new(types::Step::HashJoin));
+            }
         }
+        root_steps.push(Box::new(types::Step::Sequence(sequence_steps)));
     }
 
     // simplify the steps
@@ -378,7 +402,8 @@ async fn get_execution_steps(
         }
         ProcessResponseAs::Array { .. }
         | ProcessResponseAs::Object { .. }
-        | ProcessResponseAs::Aggregates { .. } => {
+        | ProcessResponseAs::Aggregates { .. }
+        | ProcessResponseAs::Groups => {
             // A model execution node
             let data_connector_explain = fetch_explain_from_data_connector(
                 expose_internal_errors,
@@ -481,6 +506,7 @@ async fn construct_ndc_query(
     // in the following queries
     let filter_expressions = execute::execute_remote_predicates(
         &remote_predicates,
+        &query_execution_plan.data_connector,
         http_context,
         "execute_remote_predicate",
         "execute_remote_predicate",
@@ -624,30 +650,49 @@ pub(crate) async fn fetch_explain_from_data_connector(
             SpanVisibility::Internal,
             || {
                 Box::pin(async {
-                    let ndc_config = ndc_client::Configuration {
-                        base_path: data_connector.url.get_url(ast::OperationType::Query),
+                    let ndc_config = |base_path| ndc_client::Configuration {
+                        base_path,
                         // This is isn't expensive, reqwest::Client is behind an Arc
                         client: http_context.client.clone(),
                         headers: Cow::Borrowed(&data_connector.headers.0),
                         response_size_limit: http_context.ndc_response_size_limit,
+                        timeout: data_connector
+                            .request_policy
+                            .as_ref()
+                            .and_then(|request_policy| request_policy.timeout),
                     };
                     match ndc_request {
                         types::NDCRequest::Query(query_request) => {
                             if data_connector.capabilities.supports_explaining_queries {
-                                ndc_client::explain_query_post(ndc_config, query_request)
-                                    .await
-                                    .map(Some)
-                                    .map_err(execute::FieldError::from)
+                                // queries are explained by the read replica they would be sent to
+                                execute::ndc::send_to_query_url(
+                                    http_context,
+                                    data_connector,
+                                    |base_path| {
+                                        ndc_client::explain_query_post(
+                                            ndc_config(base_path),
+                                            query_request,
+                                        )
+                                    },
+                                )
+                                .await
+                                .map(Some)
+                                .map_err(execute::FieldError::from)
                             } else {
                                 Ok(None)
                             }
                         }
                         types::NDCRequest::Mutation(mutation_request) => {
                             if data_connector.capabilities.supports_explaining_mutations {
-                                ndc_client::explain_mutation_post(ndc_config, mutation_request)
-                                    .await
-                                    .map(Some)
-                                    .map_err(execute::FieldError::from)
+                                ndc_client::explain_mutation_post(
+                                    ndc_config(
+                                        data_connector.url.get_url(ast::OperationType::Mutation),
+                                    ),
+                                    mutation_request,
+                                )
+                                .await
+                                .map(Some)
+                                .map_err(execute::FieldError::from)
                             } else {
                                 Ok(None)
                             }
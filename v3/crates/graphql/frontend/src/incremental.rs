@@ -0,0 +1,789 @@
+//! Incremental delivery of query responses, as per the `@defer` and `@stream` directives of the
+//! incremental delivery RFC: <https://github.com/graphql/graphql-spec/pull/742>
+//!
+//! The initial response leaves out the fields of deferred fragments and the items of streamed
+//! lists after their initial count, which follow it in subsequent payloads. Deferred root fields
+//! and the remote joins that only deferred fragments select from are executed concurrently with
+//! the rest of the query, and are delivered as soon as they complete.
+//!
+//! Streamed lists are not fetched incrementally from the data connector: the whole list is fetched
+//! for the initial response, which then leaves out the items after the initial count, and those
+//! are delivered right after it. `@stream` therefore makes the initial response smaller, but does
+//! not make it arrive sooner.
+
+use engine_types::{ExposeInternalErrors, HttpContext, ProjectId};
+use futures_util::future::{BoxFuture, Either};
+use futures_util::stream::{FuturesUnordered, StreamExt};
+use gql::http::{GraphQLError, PathSegment};
+use gql::normalized_ast;
+use graphql_ir::{NodeQueryPlan, QueryPlan};
+use graphql_schema::GDS;
+use indexmap::IndexMap;
+use lang_graphql as gql;
+use lang_graphql::ast::common as ast;
+use plan_types::{JoinLocations, JoinNode, Location};
+use serde::Serialize;
+use serde_json as json;
+
+use crate::execute::{execute_query_field_plan, ExecuteQueryResult, RootFieldResult};
+use crate::process_response::process_response;
+use crate::types::GraphQLResponse;
+
+/// The sending half of the channel that the parts of an incrementally delivered response are
+/// sent to, in order
+pub type IncrementalSender = tokio::sync::mpsc::UnboundedSender<IncrementalPart>;
+
+/// A part of an incrementally delivered response
+#[derive(Debug)]
+pub enum IncrementalPart {
+    /// The initial response, along with its headers
+    Initial {
+        headers: axum::http::HeaderMap,
+        payload: json::Value,
+    },
+    /// A payload with the results of deferred fragments and streamed lists
+    Subsequent { payload: json::Value },
+}
+
+impl IncrementalPart {
+    pub fn payload(&self) -> &json::Value {
+        match self {
+            Self::Initial { payload, .. } | Self::Subsequent { payload } => payload,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct SubsequentPayload {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    incremental: Vec<IncrementalResult>,
+    has_next: bool,
+}
+
+/// The result of a deferred fragment or of the remaining items of a streamed list
+#[derive(Serialize, Debug)]
+#[serde(untagged)]
+enum IncrementalResult {
+    Defer {
+        data: json::Value,
+        path: Vec<PathSegment>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        label: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        errors: Option<Vec<GraphQLError>>,
+    },
+    Stream {
+        items: Vec<json::Value>,
+        path: Vec<PathSegment>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        label: Option<String>,
+    },
+}
+
+impl IncrementalResult {
+    fn is_defer(&self) -> bool {
+        matches!(self, Self::Defer { .. })
+    }
+}
+
+/// Whether any field of the selection set, or of its nested selection sets, is deferred or
+/// streamed
+pub fn has_incremental_fields(selection_set: &normalized_ast::SelectionSet<'_, GDS>) -> bool {
+    selection_set.fields.values().any(|field| {
+        field.deferred.is_some()
+            || field.stream.is_some()
+            || has_incremental_fields(&field.selection_set)
+    })
+}
+
+/// A root field executed for the initial response
+struct IncrementalRootField<'a> {
+    result: RootFieldResult,
+    /// Results to be delivered right after the initial response
+    results: Vec<IncrementalResult>,
+    /// Results to be delivered once the remote joins of its deferred fragments are executed
+    pending: Option<BoxFuture<'a, Vec<IncrementalResult>>>,
+}
+
+/// Executes the plan of a query with deferred fragments or streamed lists, sending the parts of
+/// its response as they become available. The initial response is returned once every part has
+/// been sent.
+pub async fn execute_query_plan_incrementally<'a, 's>(
+    http_context: &'a HttpContext,
+    query_plan: QueryPlan<'a, 's, 'a>,
+    selection_set: &'a normalized_ast::SelectionSet<'s, GDS>,
+    expose_internal_errors: ExposeInternalErrors,
+    project_id: Option<&'a ProjectId>,
+    sender: &IncrementalSender,
+) -> GraphQLResponse {
+    let mut eager_root_fields = Vec::new();
+    let mut deferred_root_fields: IndexMap<Option<String>, Vec<_>> = IndexMap::new();
+    for (alias, field_plan) in query_plan {
+        let field = selection_set.fields.get(&alias);
+        match field.and_then(|field| field.deferred.as_ref()) {
+            Some(deferred) => deferred_root_fields
+                .entry(deferred.label.clone())
+                .or_default()
+                .push((alias, field_plan)),
+            None => eager_root_fields.push((alias, field, field_plan)),
+        }
+    }
+
+    // deferred root fields are executed alongside the rest of the query
+    let mut pending: FuturesUnordered<BoxFuture<'a, Vec<IncrementalResult>>> = deferred_root_fields
+        .into_iter()
+        .map(|(label, field_plans)| {
+            Box::pin(execute_deferred_root_fields(
+                http_context,
+                label,
+                field_plans,
+                expose_internal_errors,
+                project_id,
+            )) as BoxFuture<'a, _>
+        })
+        .collect();
+
+    let mut initial_execution = std::pin::pin!(futures_ext::execute_concurrently(
+        eager_root_fields.into_iter(),
+        |(alias, field, field_plan)| async move {
+            let root_field = execute_root_field_incrementally(
+                http_context,
+                alias.clone(),
+                field,
+                field_plan,
+                expose_internal_errors,
+                project_id,
+            )
+            .await;
+            (alias, root_field)
+        },
+    ));
+    // keep the pending results progressing while the initial response is executed
+    let mut results = Vec::new();
+    let executed_root_fields = loop {
+        if pending.is_empty() {
+            break initial_execution.await;
+        }
+        match futures_util::future::select(initial_execution.as_mut(), pending.next()).await {
+            Either::Left((executed_root_fields, _)) => break executed_root_fields,
+            Either::Right((pending_results, _)) => {
+                results.extend(pending_results.into_iter().flatten())
+            }
+        }
+    };
+
+    let mut root_fields = IndexMap::new();
+    for (alias, root_field) in executed_root_fields {
+        root_fields.insert(alias, root_field.result);
+        results.extend(root_field.results);
+        pending.extend(root_field.pending);
+    }
+    let response = ExecuteQueryResult { root_fields }.to_graphql_response(expose_internal_errors);
+
+    // nothing more is delivered when the initial response has no data
+    let has_next = response.data.is_some() && !(results.is_empty() && pending.is_empty());
+    let mut payload = json::to_value(&response).unwrap_or(json::Value::Null);
+    if let json::Value::Object(payload) = &mut payload {
+        payload.insert("hasNext".to_string(), json::Value::Bool(has_next));
+    }
+    let initial_part = IncrementalPart::Initial {
+        headers: response.headers.clone(),
+        payload,
+    };
+    if sender.send(initial_part).is_err() || !has_next {
+        return GraphQLResponse::from_response(response);
+    }
+
+    if !results.is_empty() {
+        let has_next = !pending.is_empty();
+        if !send_subsequent_payload(sender, results, has_next) {
+            return GraphQLResponse::from_response(response);
+        }
+    }
+    while let Some(results) = pending.next().await {
+        let has_next = !pending.is_empty();
+        if !send_subsequent_payload(sender, results, has_next) {
+            break;
+        }
+    }
+    GraphQLResponse::from_response(response)
+}
+
+/// Sends a subsequent payload, returning whether it could be sent
+fn send_subsequent_payload(
+    sender: &IncrementalSender,
+    incremental: Vec<IncrementalResult>,
+    has_next: bool,
+) -> bool {
+    let payload = SubsequentPayload {
+        incremental,
+        has_next,
+    };
+    let payload = json::to_value(payload).unwrap_or(json::Value::Null);
+    sender.send(IncrementalPart::Subsequent { payload }).is_ok()
+}
+
+/// Executes the root fields of a deferred fragment. Their response headers are ignored, as the
+/// headers of the response have already been sent with the initial response by then.
+async fn execute_deferred_root_fields(
+    http_context: &HttpContext,
+    label: Option<String>,
+    field_plans: Vec<(ast::Alias, NodeQueryPlan<'_, '_, '_>)>,
+    expose_internal_errors: ExposeInternalErrors,
+    project_id: Option<&ProjectId>,
+) -> Vec<IncrementalResult> {
+    let executed_root_fields =
+        futures_ext::execute_concurrently(field_plans.into_iter(), |(alias, field_plan)| async {
+            let root_field =
+                execute_query_field_plan(&alias, http_context, field_plan, project_id).await;
+            (alias, root_field)
+        })
+        .await;
+    let response = ExecuteQueryResult {
+        root_fields: executed_root_fields.into_iter().collect(),
+    }
+    .to_graphql_response(expose_internal_errors);
+    let data = response.data.map_or(json::Value::Null, |data| {
+        json::Value::Object(
+            data.into_iter()
+                .map(|(alias, value)| (alias.to_string(), value))
+                .collect(),
+        )
+    });
+    vec![IncrementalResult::Defer {
+        data,
+        path: Vec::new(),
+        label,
+        errors: response.errors.map(Vec::from),
+    }]
+}
+
+/// Executes a root field for the initial response, splitting its deferred fragments and streamed
+/// lists out of it. The remote joins that only its deferred fragments select from are left to be
+/// executed after the initial response.
+async fn execute_root_field_incrementally<'a, 's>(
+    http_context: &'a HttpContext,
+    alias: ast::Alias,
+    field: Option<&'a normalized_ast::Field<'s, GDS>>,
+    field_plan: NodeQueryPlan<'a, 's, 'a>,
+    expose_internal_errors: ExposeInternalErrors,
+    project_id: Option<&'a ProjectId>,
+) -> IncrementalRootField<'a> {
+    let Some(field) = field else {
+        let result = execute_query_field_plan(&alias, http_context, field_plan, project_id).await;
+        return IncrementalRootField {
+            result,
+            results: Vec::new(),
+            pending: None,
+        };
+    };
+    let field_path = vec![PathSegment::field(alias.0.clone())];
+
+    let field_plan = match field_plan {
+        NodeQueryPlan::NDCQueryExecution {
+            query_execution: mut ndc_query,
+            selection_set,
+        } => {
+            let (eager_join_locations, deferred_join_locations, pruned_selection_set) =
+                split_join_locations(
+                    selection_set,
+                    std::mem::take(&mut ndc_query.execution_tree.remote_join_executions),
+                );
+            ndc_query.execution_tree.remote_join_executions = eager_join_locations;
+            if deferred_join_locations.is_empty() {
+                NodeQueryPlan::NDCQueryExecution {
+                    query_execution: ndc_query,
+                    selection_set,
+                }
+            } else {
+                let process_response_as = ndc_query.process_response_as.clone();
+                let execution_span_attribute = ndc_query.execution_span_attribute;
+                let mut rowsets =
+                    match execute::resolve_ndc_query_execution(http_context, ndc_query, project_id)
+                        .await
+                    {
+                        Ok(rowsets) => rowsets,
+                        Err(error) => {
+                            return IncrementalRootField {
+                                result: RootFieldResult::new(
+                                    process_response_as.is_nullable(),
+                                    Err(error),
+                                ),
+                                results: Vec::new(),
+                                pending: None,
+                            }
+                        }
+                    };
+                let processed_response =
+                    process_response(&pruned_selection_set, rowsets.clone(), &process_response_as);
+                let mut result = RootFieldResult::from_processed_response(
+                    process_response_as.is_nullable(),
+                    processed_response,
+                );
+                // the deferred fragments are delivered once the remote joins are executed, as
+                // they cannot be told apart from the fragments that select from them here
+                let mut results = Vec::new();
+                if let Ok(value) = &mut result.result {
+                    split_field_value(field, value, &mut field_path.clone(), &mut results);
+                    results.retain(|result| !result.is_defer());
+                }
+
+                let pending: BoxFuture<'a, Vec<IncrementalResult>> = Box::pin(async move {
+                    let processed_response = execute::execute_join_locations(
+                        http_context,
+                        execution_span_attribute,
+                        &mut rowsets,
+                        &process_response_as,
+                        &deferred_join_locations,
+                        project_id,
+                    )
+                    .await
+                    .and_then(|()| process_response(selection_set, rowsets, &process_response_as));
+                    match processed_response {
+                        Ok(processed_response) => {
+                            let mut value = processed_response.response;
+                            let mut results = Vec::new();
+                            split_field_value(
+                                field,
+                                &mut value,
+                                &mut field_path.clone(),
+                                &mut results,
+                            );
+                            results.retain(IncrementalResult::is_defer);
+                            results
+                        }
+                        Err(error) => vec![IncrementalResult::Defer {
+                            data: json::Value::Null,
+                            errors: Some(vec![error.to_graphql_error(
+                                expose_internal_errors,
+                                Some(field_path.clone()),
+                            )]),
+                            path: field_path,
+                            label: None,
+                        }],
+                    }
+                });
+                return IncrementalRootField {
+                    result,
+                    results,
+                    pending: Some(pending),
+                };
+            }
+        }
+        field_plan => field_plan,
+    };
+
+    let mut result = execute_query_field_plan(&alias, http_context, field_plan, project_id).await;
+    let mut results = Vec::new();
+    if let Ok(value) = &mut result.result {
+        split_field_value(field, value, &mut field_path.clone(), &mut results);
+    }
+    IncrementalRootField {
+        result,
+        results,
+        pending: None,
+    }
+}
+
+/// Splits the remote joins of a selection set into those needed for the initial response and
+/// those that only deferred fragments select from, returning the selection set without the fields
+/// of the latter, which cannot be processed until their remote joins are executed.
+fn split_join_locations<'s>(
+    selection_set: &normalized_ast::SelectionSet<'s, GDS>,
+    join_locations: JoinLocations,
+) -> (
+    JoinLocations,
+    JoinLocations,
+    normalized_ast::SelectionSet<'s, GDS>,
+) {
+    let find_field = |alias: &str| {
+        selection_set
+            .fields
+            .values()
+            .find(|field| field.alias.0.as_str() == alias)
+    };
+    // leaving out every field would leave an empty selection set, which selects the whole row, so
+    // then the remote joins are executed for the initial response instead
+    let deferred_fields_count = join_locations
+        .locations
+        .keys()
+        .filter(|alias| find_field(alias).is_some_and(|field| field.deferred.is_some()))
+        .count();
+    let can_defer = selection_set.fields.len() > deferred_fields_count;
+
+    let mut eager_join_locations = JoinLocations::new();
+    let mut deferred_join_locations = JoinLocations::new();
+    let mut pruned_selection_set = selection_set.clone();
+    for (alias, location) in join_locations.locations {
+        let Some(field) = find_field(&alias) else {
+            eager_join_locations.locations.insert(alias, location);
+            continue;
+        };
+        if can_defer && field.deferred.is_some() {
+            pruned_selection_set.fields.shift_remove(&field.alias);
+            deferred_join_locations.locations.insert(alias, location);
+            continue;
+        }
+        match location.join_node {
+            JoinNode::Remote(remote_join) => {
+                eager_join_locations.locations.insert(
+                    alias,
+                    Location {
+                        join_node: JoinNode::Remote(remote_join),
+                        rest: location.rest,
+                    },
+                );
+            }
+            JoinNode::Local(location_kind) => {
+                let (eager_rest, deferred_rest, pruned_field_selection_set) =
+                    split_join_locations(&field.selection_set, location.rest);
+                if let Some(pruned_field) = pruned_selection_set.fields.get_mut(&field.alias) {
+                    pruned_field.selection_set = pruned_field_selection_set;
+                }
+                if !eager_rest.is_empty() {
+                    eager_join_locations.locations.insert(
+                        alias.clone(),
+                        Location {
+                            join_node: JoinNode::Local(location_kind),
+                            rest: eager_rest,
+                        },
+                    );
+                }
+                if !deferred_rest.is_empty() {
+                    deferred_join_locations.locations.insert(
+                        alias,
+                        Location {
+                            join_node: JoinNode::Local(location_kind),
+                            rest: deferred_rest,
+                        },
+                    );
+                }
+            }
+        }
+    }
+    (
+        eager_join_locations,
+        deferred_join_locations,
+        pruned_selection_set,
+    )
+}
+
+/// Removes the items of a streamed list after its initial count from the value of a field, along
+/// with the deferred fragments of its selection set, collecting them as incremental results in
+/// the order they are to be delivered. The value holds the whole list, as it has already been
+/// fetched.
+fn split_field_value(
+    field: &normalized_ast::Field<'_, GDS>,
+    value: &mut json::Value,
+    path: &mut Vec<PathSegment>,
+    results: &mut Vec<IncrementalResult>,
+) {
+    let first_result = results.len();
+    split_selection_set_value(&field.selection_set, value, path, results);
+    if let (Some(stream), json::Value::Array(items)) = (&field.stream, value) {
+        if items.len() > stream.initial_count {
+            let mut stream_path = path.clone();
+            stream_path.push(PathSegment::index(stream.initial_count));
+            // the streamed items are delivered before the deferred fragments within them
+            results.insert(
+                first_result,
+                IncrementalResult::Stream {
+                    items: items.split_off(stream.initial_count),
+                    path: stream_path,
+                    label: stream.label.clone(),
+                },
+            );
+        }
+    }
+}
+
+fn split_selection_set_value(
+    selection_set: &normalized_ast::SelectionSet<'_, GDS>,
+    value: &mut json::Value,
+    path: &mut Vec<PathSegment>,
+    results: &mut Vec<IncrementalResult>,
+) {
+    match value {
+        json::Value::Array(items) => {
+            for (index, item) in items.iter_mut().enumerate() {
+                path.push(PathSegment::index(index));
+                split_selection_set_value(selection_set, item, path, results);
+                path.pop();
+            }
+        }
+        json::Value::Object(object) => {
+            // the fields of a deferred fragment are delivered together, along with any fragments
+            // deferred within them
+            let mut deferred_data: IndexMap<Option<String>, json::Map<String, json::Value>> =
+                IndexMap::new();
+            for (alias, field) in &selection_set.fields {
+                match &field.deferred {
+                    Some(deferred) => {
+                        if let Some(field_value) = object.shift_remove(alias.0.as_str()) {
+                            deferred_data
+                                .entry(deferred.label.clone())
+                                .or_default()
+                                .insert(alias.to_string(), field_value);
+                        }
+                    }
+                    None => {
+                        if let Some(field_value) = object.get_mut(alias.0.as_str()) {
+                            path.push(PathSegment::field(alias.0.clone()));
+                            split_field_value(field, field_value, path, results);
+                            path.pop();
+                        }
+                    }
+                }
+            }
+            for (label, data) in deferred_data {
+                results.push(IncrementalResult::Defer {
+                    data: json::Value::Object(data),
+                    path: path.clone(),
+                    label,
+                    errors: None,
+                });
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use graphql_schema::{GDSRoleNamespaceGetter, GDS};
+    use hasura_authn_core::Role;
+    use lang_graphql::http::{PathSegment, Request};
+    use lang_graphql::normalized_ast;
+    use lang_graphql::schema::Schema;
+    use lang_graphql::{parser::Parser, validation::normalize_request};
+    use open_dds::data_connector::CollectionName;
+    use plan_types::{
+        JoinLocations, JoinNode, Location, LocationKind, ProcessResponseAs, QueryExecutionPlan,
+        QueryNode, RemoteJoin, RemoteJoinType,
+    };
+    use serde_json::json;
+
+    use super::{has_incremental_fields, split_field_value, split_join_locations};
+
+    fn build_schema() -> Result<Schema<GDS>, Box<dyn std::error::Error>> {
+        let test_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests");
+        let schema = fs::read_to_string(test_dir.join("schema.json"))?;
+        let gds = GDS::new_with_default_flags(open_dds::Metadata::from_json_str(&schema)?)?;
+        Ok(GDS::build_schema(&gds)?)
+    }
+
+    fn normalize<'s>(
+        schema: &'s Schema<GDS>,
+        query: &str,
+    ) -> Result<normalized_ast::Operation<'s, GDS>, Box<dyn std::error::Error>> {
+        let request = Request {
+            operation_name: None,
+            query: Parser::new(query).parse_executable_document()?,
+            variables: BTreeMap::new(),
+        };
+        Ok(normalize_request(
+            &GDSRoleNamespaceGetter {
+                scope: Role::new("admin"),
+            },
+            schema,
+            &request,
+        )?)
+    }
+
+    /// The only root field of an operation
+    fn root_field<'a, 's>(
+        operation: &'a normalized_ast::Operation<'s, GDS>,
+    ) -> Result<&'a normalized_ast::Field<'s, GDS>, Box<dyn std::error::Error>> {
+        Ok(operation
+            .selection_set
+            .fields
+            .values()
+            .next()
+            .ok_or("no root field")?)
+    }
+
+    fn remote_join_location() -> Location {
+        let data_connector: Arc<metadata_resolve::DataConnectorLink> = Arc::new(
+            serde_json::from_value(json!({
+                "name": { "name": "db" },
+                "url": { "singleUrl": "http://localhost:8080" },
+                "headers": {},
+                "capabilities": { "supported_ndc_version": "V01" }
+            }))
+            .unwrap(),
+        );
+        Location {
+            join_node: JoinNode::Remote(RemoteJoin {
+                target_data_connector: data_connector.clone(),
+                target_ndc_execution: QueryExecutionPlan {
+                    query_node: QueryNode {
+                        limit: None,
+                        offset: None,
+                        order_by: None,
+                        predicate: None,
+                        aggregates: None,
+                        fields: None,
+                        group_by: None,
+                    },
+                    collection: CollectionName::from("author"),
+                    arguments: BTreeMap::new(),
+                    collection_relationships: BTreeMap::new(),
+                    variables: None,
+                    data_connector,
+                    response_cache: None,
+                },
+                join_mapping: BTreeMap::new(),
+                object_type_field_mappings: BTreeMap::new(),
+                process_response_as: ProcessResponseAs::Object { is_nullable: true },
+                remote_join_type: RemoteJoinType::ToModel,
+            }),
+            rest: JoinLocations::new(),
+        }
+    }
+
+    fn location_aliases(join_locations: &JoinLocations) -> Vec<&str> {
+        join_locations
+            .locations
+            .keys()
+            .map(String::as_str)
+            .collect()
+    }
+
+    fn field_aliases<'a>(selection_set: &'a normalized_ast::SelectionSet<'_, GDS>) -> Vec<&'a str> {
+        selection_set
+            .fields
+            .keys()
+            .map(|alias| alias.0.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_split_field_value() -> Result<(), Box<dyn std::error::Error>> {
+        let schema = build_schema()?;
+        let normalized_request = normalize(
+            &schema,
+            r#"{ ArticleMany @stream(initialCount: 1, label: "articles") { id ... @defer(label: "title") { title } } }"#,
+        )?;
+        assert!(has_incremental_fields(&normalized_request.selection_set));
+        let field = root_field(&normalized_request)?;
+
+        let mut value = json!([{"id": 1, "title": "one"}, {"id": 2, "title": "two"}]);
+        let mut results = Vec::new();
+        split_field_value(
+            field,
+            &mut value,
+            &mut vec![PathSegment::field(field.alias.0.clone())],
+            &mut results,
+        );
+        assert_eq!(value, json!([{"id": 1}]));
+        // the streamed items are delivered before the fragments deferred within them
+        assert_eq!(
+            serde_json::to_value(results)?,
+            json!([
+                {"items": [{"id": 2}], "path": ["ArticleMany", 1], "label": "articles"},
+                {"data": {"title": "one"}, "path": ["ArticleMany", 0], "label": "title"},
+                {"data": {"title": "two"}, "path": ["ArticleMany", 1], "label": "title"},
+            ])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_join_locations() -> Result<(), Box<dyn std::error::Error>> {
+        let schema = build_schema()?;
+        let normalized_request = normalize(
+            &schema,
+            r#"{ ArticleMany {
+                title
+                eager_author: Author { last_name }
+                Author { first_name ... @defer(label: "articles") { Articles { title } } }
+                ... @defer(label: "author") { deferred_author: Author { last_name } }
+            } }"#,
+        )?;
+        let field = root_field(&normalized_request)?;
+        // `Author` is a local relationship here, while the other relationships are remote
+        let join_locations = JoinLocations {
+            locations: [
+                ("eager_author".to_string(), remote_join_location()),
+                (
+                    "Author".to_string(),
+                    Location {
+                        join_node: JoinNode::Local(LocationKind::LocalRelationship),
+                        rest: JoinLocations {
+                            locations: [("Articles".to_string(), remote_join_location())]
+                                .into_iter()
+                                .collect(),
+                        },
+                    },
+                ),
+                ("deferred_author".to_string(), remote_join_location()),
+            ]
+            .into_iter()
+            .collect(),
+        };
+
+        let (eager_join_locations, deferred_join_locations, pruned_selection_set) =
+            split_join_locations(&field.selection_set, join_locations);
+        assert_eq!(
+            location_aliases(&eager_join_locations),
+            vec!["eager_author"]
+        );
+        // the remote joins deferred within a local relationship stay under its location
+        assert_eq!(
+            location_aliases(&deferred_join_locations),
+            vec!["Author", "deferred_author"]
+        );
+        let author_location = &deferred_join_locations.locations["Author"];
+        assert_eq!(
+            author_location.join_node,
+            JoinNode::Local(LocationKind::LocalRelationship)
+        );
+        assert_eq!(location_aliases(&author_location.rest), vec!["Articles"]);
+        // the fields of the deferred remote joins are left out until they are executed
+        assert_eq!(
+            field_aliases(&pruned_selection_set),
+            vec!["title", "eager_author", "Author"]
+        );
+        let pruned_author = pruned_selection_set
+            .fields
+            .values()
+            .find(|field| field.alias.0.as_str() == "Author")
+            .ok_or("no Author field")?;
+        assert_eq!(
+            field_aliases(&pruned_author.selection_set),
+            vec!["first_name"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_join_locations_of_only_deferred_fields() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let schema = build_schema()?;
+        let normalized_request = normalize(
+            &schema,
+            r"{ ArticleMany { ... @defer { Author { first_name } } } }",
+        )?;
+        let field = root_field(&normalized_request)?;
+        let join_locations = JoinLocations {
+            locations: [("Author".to_string(), remote_join_location())]
+                .into_iter()
+                .collect(),
+        };
+
+        // leaving out every field would select the whole row, so the remote joins are executed
+        // for the initial response instead
+        let (eager_join_locations, deferred_join_locations, pruned_selection_set) =
+            split_join_locations(&field.selection_set, join_locations);
+        assert_eq!(location_aliases(&eager_join_locations), vec!["Author"]);
+        assert!(deferred_join_locations.is_empty());
+        assert_eq!(field_aliases(&pruned_selection_set), vec!["Author"]);
+        Ok(())
+    }
+}
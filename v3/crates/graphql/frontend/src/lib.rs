@@ -1,8 +1,12 @@
+mod cache_hints;
 mod error;
 mod execute;
 mod explain;
+mod incremental;
+mod persisted_queries;
 mod process_response;
 mod query;
+mod query_limits;
 mod query_usage;
 mod steps;
 mod types;
@@ -11,12 +15,20 @@ pub use error::{build_state_with_middleware_error_converter, RequestError};
 pub use execute::{execute_mutation_plan, execute_query_plan, ExecuteQueryResult, RootFieldResult};
 pub use explain::execute_explain;
 pub use explain::types::{redact_ndc_explain, ExplainResponse};
+pub use incremental::{
+    execute_query_plan_incrementally, has_incremental_fields, IncrementalPart, IncrementalSender,
+};
+pub use persisted_queries::PersistedQueryError;
 pub use process_response::process_response;
 pub use query::{
-    execute_query, execute_query_internal, set_request_metadata_attributes, set_usage_attributes,
+    execute_query, execute_query_incrementally, execute_query_internal,
+    set_request_metadata_attributes, set_usage_attributes,
+};
+pub use query_limits::QueryLimitError;
+pub use steps::{
+    build_ir, build_request_plan, generate_ir, normalize_request, parse_query, resolve_query,
 };
-pub use steps::{build_ir, build_request_plan, generate_ir, normalize_request, parse_query};
-pub use types::{GraphQLErrors, GraphQLResponse};
+pub use types::{GraphQLErrors, GraphQLResponse, RequestMethod};
 
 #[cfg(test)]
 mod tests {
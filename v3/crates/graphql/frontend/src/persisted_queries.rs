@@ -0,0 +1,449 @@
+//! Persisted queries and the operation allowlist.
+//!
+//! A request can refer to a query by the SHA-256 hash of its text in the
+//! `extensions.persistedQuery.sha256Hash` field, following the automatic
+//! persisted queries (APQ) protocol. The hash is looked up in the operation
+//! collections of the metadata first, then, if no operation collection is
+//! defined, in the queries previously registered by clients of the same
+//! project. Registered queries are kept in a per-project LRU cache bounded by
+//! the total size of the queries, so the least recently used queries are
+//! evicted to make room for new ones. An unknown hash is reported as
+//! `PersistedQueryNotFound`, upon which clients resend the request with both
+//! the hash and the query.
+//!
+//! When operation collections are defined, they act as an allowlist: only
+//! their operations can be run, and only by the roles they are permitted to,
+//! and clients can no longer register queries of their own. Introspection
+//! queries, and the roles that the collections allow to run any operation, are
+//! exempt from the allowlist.
+
+use std::borrow::Cow;
+
+use engine_types::{PersistedQueryCache, ProjectId};
+use hasura_authn_core::{Role, Session};
+use lang_graphql::ast::executable::{ExecutableDefinition, Selection, SelectionSet};
+use lang_graphql::http::RawRequest;
+use metadata_resolve::OperationCollections;
+use tracing_util::{ErrorVisibility, TraceableError};
+
+/// Maximum length of a query registered by a client.
+const MAX_REGISTERED_QUERY_LENGTH: usize = 100_000;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PersistedQueryError {
+    // Clients implementing automatic persisted queries match on this exact message
+    #[error("PersistedQueryNotFound")]
+    NotFound,
+
+    #[error("the provided sha256Hash does not match the query")]
+    HashMismatch,
+
+    #[error("the operation is not allowed for role {role}")]
+    OperationNotAllowed { role: Role },
+}
+
+impl TraceableError for PersistedQueryError {
+    fn visibility(&self) -> ErrorVisibility {
+        ErrorVisibility::User
+    }
+}
+
+/// Returns the query of a request, looking it up when the request refers to
+/// a persisted query, and checks that it is allowed for the role of the
+/// session.
+pub fn resolve_persisted_query<'r>(
+    operation_collections: &OperationCollections,
+    registered_queries: &PersistedQueryCache,
+    session: &Session,
+    raw_request: &'r RawRequest,
+    project_id: Option<&ProjectId>,
+) -> Result<Cow<'r, str>, PersistedQueryError> {
+    let query_hash = raw_request
+        .extensions
+        .as_ref()
+        .and_then(|extensions| extensions.persisted_query.as_ref())
+        .map(|persisted_query| persisted_query.sha256_hash.to_ascii_lowercase());
+
+    let query = match query_hash {
+        None => Cow::Borrowed(raw_request.query.as_str()),
+        Some(query_hash) if raw_request.query.is_empty() => Cow::Owned(
+            lookup_query(
+                operation_collections,
+                registered_queries,
+                project_id,
+                &query_hash,
+            )
+            .ok_or(PersistedQueryError::NotFound)?,
+        ),
+        Some(query_hash) => {
+            if metadata_resolve::hash_query(&raw_request.query) != query_hash {
+                return Err(PersistedQueryError::HashMismatch);
+            }
+            if !operation_collections.allowlist_enabled
+                && raw_request.query.len() <= MAX_REGISTERED_QUERY_LENGTH
+            {
+                registered_queries.insert(project_id, query_hash, &raw_request.query);
+            }
+            Cow::Borrowed(raw_request.query.as_str())
+        }
+    };
+
+    if operation_collections.is_allowed(&query, &session.role) || is_introspection_query(&query) {
+        Ok(query)
+    } else {
+        Err(PersistedQueryError::OperationNotAllowed {
+            role: session.role.clone(),
+        })
+    }
+}
+
+fn lookup_query(
+    operation_collections: &OperationCollections,
+    registered_queries: &PersistedQueryCache,
+    project_id: Option<&ProjectId>,
+    query_hash: &str,
+) -> Option<String> {
+    if let Some(operation) = operation_collections.get_by_hash(query_hash) {
+        return Some(operation.query.clone());
+    }
+    if operation_collections.allowlist_enabled {
+        return None;
+    }
+    registered_queries
+        .get(project_id, query_hash)
+        .map(|query| query.to_string())
+}
+
+/// Whether every operation of the query only selects introspection fields,
+/// i.e. `__schema`, `__type` or `__typename`. A query which cannot be parsed
+/// is not an introspection query.
+fn is_introspection_query(query: &str) -> bool {
+    let Ok(document) = lang_graphql::parser::Parser::new(query).parse_executable_document() else {
+        return false;
+    };
+    document
+        .items
+        .iter()
+        .all(|definition| match &definition.item {
+            ExecutableDefinition::Operation(operation) => {
+                selects_introspection_fields_only(&operation.selection_set.item)
+            }
+            ExecutableDefinition::Fragment(_) => true,
+        })
+}
+
+fn selects_introspection_fields_only(selection_set: &SelectionSet) -> bool {
+    selection_set
+        .items
+        .iter()
+        .all(|selection| match &selection.item {
+            Selection::Field(field) => field.name.item.as_str().starts_with("__"),
+            Selection::InlineFragment(fragment) => {
+                selects_introspection_fields_only(&fragment.selection_set.item)
+            }
+            // Fragments spread at the root of an operation are not used by
+            // introspection queries
+            Selection::FragmentSpread(_) => false,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use engine_types::{PersistedQueryCache, ProjectId};
+    use hasura_authn_core::{Identity, Role, Session};
+    use lang_graphql::http::{PersistedQueryExtension, RawRequest, RequestExtensions};
+    use metadata_resolve::{hash_query, OperationCollections, PersistedOperation};
+
+    use super::{resolve_persisted_query, PersistedQueryError};
+
+    fn session(role: &str) -> Session {
+        Identity::admin(Role::new(role))
+            .get_role_authorization(None)
+            .unwrap()
+            .build_session(BTreeMap::new())
+    }
+
+    fn raw_request(query: &str, sha256_hash: Option<String>) -> RawRequest {
+        RawRequest {
+            operation_name: None,
+            query: query.to_string(),
+            variables: None,
+            extensions: sha256_hash.map(|sha256_hash| RequestExtensions {
+                persisted_query: Some(PersistedQueryExtension {
+                    version: Some(1),
+                    sha256_hash,
+                }),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_automatic_persisted_queries() {
+        let operation_collections = OperationCollections::default();
+        let session = session("user");
+        let registered_queries = PersistedQueryCache::default();
+        let query = "query { test_automatic_persisted_queries }";
+
+        let request = raw_request("", Some(hash_query(query)));
+        assert!(matches!(
+            resolve_persisted_query(
+                &operation_collections,
+                &registered_queries,
+                &session,
+                &request,
+                None
+            ),
+            Err(PersistedQueryError::NotFound)
+        ));
+
+        let request = raw_request("query { other }", Some(hash_query(query)));
+        assert!(matches!(
+            resolve_persisted_query(
+                &operation_collections,
+                &registered_queries,
+                &session,
+                &request,
+                None
+            ),
+            Err(PersistedQueryError::HashMismatch)
+        ));
+
+        let request = raw_request(query, Some(hash_query(query)));
+        resolve_persisted_query(
+            &operation_collections,
+            &registered_queries,
+            &session,
+            &request,
+            None,
+        )
+        .unwrap();
+
+        let request = raw_request("", Some(hash_query(query).to_ascii_uppercase()));
+        assert_eq!(
+            resolve_persisted_query(
+                &operation_collections,
+                &registered_queries,
+                &session,
+                &request,
+                None
+            )
+            .unwrap(),
+            query
+        );
+    }
+
+    #[test]
+    fn test_operation_allowlist() {
+        let query = "query { test_operation_allowlist }";
+        let registered_queries = PersistedQueryCache::default();
+        let operation_collections = OperationCollections {
+            allowlist_enabled: true,
+            operations: BTreeMap::from_iter([(
+                hash_query(query),
+                PersistedOperation {
+                    query: query.to_string(),
+                    allowed_roles: BTreeSet::from_iter([Role::new("user")]),
+                },
+            )]),
+            unrestricted_roles: BTreeSet::from_iter([Role::new("support")]),
+        };
+
+        let request = raw_request("", Some(hash_query(query)));
+        assert_eq!(
+            resolve_persisted_query(
+                &operation_collections,
+                &registered_queries,
+                &session("user"),
+                &request,
+                None
+            )
+            .unwrap(),
+            query
+        );
+
+        let request = raw_request(query, None);
+        assert!(matches!(
+            resolve_persisted_query(
+                &operation_collections,
+                &registered_queries,
+                &session("guest"),
+                &request,
+                None
+            ),
+            Err(PersistedQueryError::OperationNotAllowed { .. })
+        ));
+
+        // Clients cannot register queries when the allowlist is enabled
+        let other_query = "query { other }";
+        let request = raw_request(other_query, Some(hash_query(other_query)));
+        assert!(matches!(
+            resolve_persisted_query(
+                &operation_collections,
+                &registered_queries,
+                &session("user"),
+                &request,
+                None
+            ),
+            Err(PersistedQueryError::OperationNotAllowed { .. })
+        ));
+        let request = raw_request("", Some(hash_query(other_query)));
+        assert!(matches!(
+            resolve_persisted_query(
+                &operation_collections,
+                &registered_queries,
+                &session("user"),
+                &request,
+                None
+            ),
+            Err(PersistedQueryError::NotFound)
+        ));
+
+        // Roles allowed to run any operation and introspection queries are exempt from the
+        // allowlist, while a role named `admin` is not exempt unless it is allowed to be
+        let request = raw_request(other_query, None);
+        resolve_persisted_query(
+            &operation_collections,
+            &registered_queries,
+            &session("support"),
+            &request,
+            None,
+        )
+        .unwrap();
+        assert!(matches!(
+            resolve_persisted_query(
+                &operation_collections,
+                &registered_queries,
+                &session("admin"),
+                &request,
+                None
+            ),
+            Err(PersistedQueryError::OperationNotAllowed { .. })
+        ));
+        let introspection_query =
+            "query { __schema { types { name } } ... on query_root { __typename } }";
+        let request = raw_request(introspection_query, None);
+        resolve_persisted_query(
+            &operation_collections,
+            &registered_queries,
+            &session("guest"),
+            &request,
+            None,
+        )
+        .unwrap();
+        let request = raw_request("query { __typename other }", None);
+        assert!(matches!(
+            resolve_persisted_query(
+                &operation_collections,
+                &registered_queries,
+                &session("guest"),
+                &request,
+                None
+            ),
+            Err(PersistedQueryError::OperationNotAllowed { .. })
+        ));
+    }
+
+    #[test]
+    fn test_registered_queries_are_scoped_to_projects() {
+        let operation_collections = OperationCollections::default();
+        let session = session("user");
+        let registered_queries = PersistedQueryCache::default();
+        let query = "query { test_registered_queries_are_scoped_to_projects }";
+        let project_1 = ProjectId("project_1".to_string());
+        let project_2 = ProjectId("project_2".to_string());
+
+        let request = raw_request(query, Some(hash_query(query)));
+        resolve_persisted_query(
+            &operation_collections,
+            &registered_queries,
+            &session,
+            &request,
+            Some(&project_1),
+        )
+        .unwrap();
+
+        let request = raw_request("", Some(hash_query(query)));
+        assert_eq!(
+            resolve_persisted_query(
+                &operation_collections,
+                &registered_queries,
+                &session,
+                &request,
+                Some(&project_1)
+            )
+            .unwrap(),
+            query
+        );
+        assert!(matches!(
+            resolve_persisted_query(
+                &operation_collections,
+                &registered_queries,
+                &session,
+                &request,
+                Some(&project_2)
+            ),
+            Err(PersistedQueryError::NotFound)
+        ));
+        assert!(matches!(
+            resolve_persisted_query(
+                &operation_collections,
+                &registered_queries,
+                &session,
+                &request,
+                None
+            ),
+            Err(PersistedQueryError::NotFound)
+        ));
+    }
+
+    #[test]
+    fn test_queries_still_register_after_eviction() {
+        let operation_collections = OperationCollections::default();
+        let session = session("user");
+        let queries = ["query { a }", "query { b }", "query { c }"];
+        // room for two of the queries along with their hashes
+        let registered_queries =
+            PersistedQueryCache::new(2 * (queries[0].len() + hash_query(queries[0]).len()));
+
+        for query in queries {
+            let request = raw_request(query, Some(hash_query(query)));
+            resolve_persisted_query(
+                &operation_collections,
+                &registered_queries,
+                &session,
+                &request,
+                None,
+            )
+            .unwrap();
+        }
+
+        // the first query was evicted to make room for the third one
+        let request = raw_request("", Some(hash_query(queries[0])));
+        assert!(matches!(
+            resolve_persisted_query(
+                &operation_collections,
+                &registered_queries,
+                &session,
+                &request,
+                None
+            ),
+            Err(PersistedQueryError::NotFound)
+        ));
+        for query in &queries[1..] {
+            let request = raw_request("", Some(hash_query(query)));
+            assert_eq!(
+                resolve_persisted_query(
+                    &operation_collections,
+                    &registered_queries,
+                    &session,
+                    &request,
+                    None
+                )
+                .unwrap(),
+                *query
+            );
+        }
+    }
+}
@@ -15,7 +15,9 @@ use open_dds::commands::CommandName;
 use open_dds::types::FieldName;
 
 use graphql_ir::{global_id_col_format, GLOBAL_ID_VERSION};
-use graphql_schema::{AggregateOutputAnnotation, Annotation, GlobalID, OutputAnnotation, GDS};
+use graphql_schema::{
+    AggregateOutputAnnotation, Annotation, GlobalID, GroupsOutputAnnotation, OutputAnnotation, GDS,
+};
 use metadata_resolve::data_connectors;
 use metadata_resolve::Qualified;
 use plan_types::FUNCTION_IR_VALUE_COLUMN_NAME;
@@ -191,6 +193,21 @@ where
                                 ),
                             }
                         }
+                        OutputAnnotation::RelationshipToModelGroups { .. } => {
+                            let field_json_value_result = row
+                                .remove(field.alias.0.as_str())
+                                .ok_or_else(|| execute::NDCUnexpectedError::BadNDCResponse {
+                                    summary: format!("missing field: {}", field.alias.clone()),
+                                })?;
+                            match serde_json::from_value(field_json_value_result) {
+                                Err(err) => Err(execute::NDCUnexpectedError::BadNDCResponse {
+                                    summary: format!("Unable to parse RowSet: {err}"),
+                                })?,
+                                Ok(row_set) => {
+                                    process_groups_requested_fields(row_set, &field.selection_set)
+                                }
+                            }
+                        }
                         OutputAnnotation::RelationshipToCommand(
                             command_relationship_annotation,
                         ) => {
@@ -511,6 +528,94 @@ fn reshape_aggregate_fields(
     Ok(response)
 }
 
+fn process_groups_requested_fields(
+    row_set: ndc_models::RowSet,
+    groups_output_selection_set: &normalized_ast::SelectionSet<'_, GDS>,
+) -> Result<json::Value, execute::FieldError> {
+    let groups = row_set.groups
+        .ok_or_else(|| execute::NDCUnexpectedError::BadNDCResponse {
+            summary:
+                "Unable to parse response from NDC, RowSet groups property was null when it was expected to be an array"
+                    .to_owned(),
+        })?;
+
+    // the values of the dimensions of each group are returned in the order the dimensions
+    // were requested in
+    let dimension_aliases = graphql_ir::groups_dimension_aliases(groups_output_selection_set);
+
+    let result = groups
+        .into_iter()
+        .map(|group| reshape_group_fields(group, &dimension_aliases, groups_output_selection_set))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(json::Value::Array(result))
+}
+
+fn reshape_group_fields(
+    group: ndc_models::Group,
+    dimension_aliases: &[plan_types::NdcFieldAlias],
+    group_output_selection_set: &normalized_ast::SelectionSet<'_, GDS>,
+) -> Result<json::Value, execute::FieldError> {
+    if group.dimensions.len() != dimension_aliases.len() {
+        Err(execute::NDCUnexpectedError::BadNDCResponse {
+            summary: format!(
+                "expected {} dimensions in a group, found {}",
+                dimension_aliases.len(),
+                group.dimensions.len()
+            ),
+        })?;
+    }
+    let mut dimensions = dimension_aliases
+        .iter()
+        .map(plan_types::NdcFieldAlias::as_str)
+        .zip(group.dimensions)
+        .collect::<IndexMap<&str, json::Value>>();
+    let mut aggregates = group.aggregates;
+
+    let result =
+        group_output_selection_set.as_object_selection_set(|_type_name, field, field_call| {
+            match field_call.info.generic {
+                Annotation::Output(OutputAnnotation::Groups(GroupsOutputAnnotation::GroupKey)) => {
+                    let group_key = field.selection_set.as_object_selection_set(
+                        |_type_name, key_field, key_field_call| match key_field_call.info.generic {
+                            Annotation::Output(OutputAnnotation::Groups(
+                                GroupsOutputAnnotation::GroupKeyField { .. }
+                                | GroupsOutputAnnotation::GroupKeyExtractionField { .. },
+                            )) => {
+                                let dimension_alias =
+                                    graphql_ir::mk_alias_from_graphql_field_path(&[
+                                        &field.alias,
+                                        &key_field.alias,
+                                    ]);
+                                let dimension_value = dimensions
+                                    .swap_remove(dimension_alias.as_str())
+                                    .ok_or_else(|| execute::NDCUnexpectedError::BadNDCResponse {
+                                        summary: format!("missing dimension: {dimension_alias}"),
+                                    })?;
+                                Ok::<_, execute::FieldError>(dimension_value)
+                            }
+                            annotation => Err(execute::FieldInternalError::UnexpectedAnnotation {
+                                annotation: annotation.clone(),
+                            })?,
+                        },
+                    )?;
+                    Ok::<_, execute::FieldError>(
+                        json::to_value(group_key).map_err(execute::FieldError::from)?,
+                    )
+                }
+                Annotation::Output(OutputAnnotation::Groups(
+                    GroupsOutputAnnotation::GroupAggregate,
+                )) => {
+                    reshape_aggregate_fields(&mut aggregates, &[&field.alias], &field.selection_set)
+                }
+                annotation => Err(execute::FieldInternalError::UnexpectedAnnotation {
+                    annotation: annotation.clone(),
+                })?,
+            }
+        })?;
+    let response = json::to_value(result).map_err(execute::FieldError::from)?;
+    Ok(response)
+}
+
 pub fn process_response(
     selection_set: &normalized_ast::SelectionSet<'_, GDS>,
     rows_sets: Vec<ndc_models::RowSet>,
@@ -572,6 +677,13 @@ pub fn process_response(
                         response: result,
                     })
                 }
+                ProcessResponseAs::Groups => {
+                    let result = process_groups_requested_fields(row_set, selection_set)?;
+                    Ok(ProcessedResponse {
+                        response_headers: None,
+                        response: result,
+                    })
+                }
             }
         },
     )
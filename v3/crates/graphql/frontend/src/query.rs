@@ -1,10 +1,12 @@
 use super::steps;
 use indexmap::IndexMap;
 
-use super::types::GraphQLResponse;
+use super::types::{GraphQLResponse, RequestMethod};
+use crate::cache_hints;
 use crate::execute::{
     execute_mutation_plan, execute_query_plan, ExecuteQueryResult, RootFieldResult,
 };
+use crate::incremental::{self, IncrementalSender};
 use engine_types::{ExposeInternalErrors, HttpContext, ProjectId};
 use graphql_ir::GraphqlRequestPipeline;
 use graphql_schema::GDS;
@@ -24,6 +26,7 @@ pub async fn execute_query(
     session: &Session,
     request_headers: &reqwest::header::HeaderMap,
     request: RawRequest,
+    request_method: RequestMethod,
     project_id: Option<&ProjectId>,
 ) -> (Option<ast::OperationType>, GraphQLResponse, bool) {
     execute_query_internal(
@@ -35,6 +38,7 @@ pub async fn execute_query(
         session,
         request_headers,
         request,
+        request_method,
         project_id,
     )
     .await
@@ -50,6 +54,47 @@ pub async fn execute_query(
     )
 }
 
+/// Executes a GraphQL query, sending the parts of its response to the given sender as they become
+/// available when the query defers fragments or streams lists. Nothing is sent otherwise, and the
+/// returned response is to be sent instead.
+pub async fn execute_query_incrementally(
+    request_pipeline: GraphqlRequestPipeline,
+    expose_internal_errors: ExposeInternalErrors,
+    http_context: &HttpContext,
+    schema: &Schema<GDS>,
+    metadata: &Arc<metadata_resolve::Metadata>,
+    session: &Session,
+    request_headers: &reqwest::header::HeaderMap,
+    request: RawRequest,
+    project_id: Option<&ProjectId>,
+    incremental_sender: &IncrementalSender,
+) -> (Option<ast::OperationType>, GraphQLResponse, bool) {
+    execute_query_with_incremental_sender(
+        request_pipeline,
+        expose_internal_errors,
+        http_context,
+        schema,
+        metadata,
+        session,
+        request_headers,
+        request,
+        RequestMethod::Post,
+        project_id,
+        Some(incremental_sender),
+    )
+    .await
+    .map_or_else(
+        |e| {
+            (
+                None,
+                GraphQLResponse::from_error(&e, expose_internal_errors),
+                true,
+            )
+        },
+        |(op_type, response, plan_matches)| (Some(op_type), response, plan_matches),
+    )
+}
+
 /// Executes a GraphQL query using new pipeline
 pub async fn execute_query_internal(
     request_pipeline: GraphqlRequestPipeline,
@@ -60,7 +105,37 @@ pub async fn execute_query_internal(
     session: &Session,
     request_headers: &reqwest::header::HeaderMap,
     raw_request: gql::http::RawRequest,
+    request_method: RequestMethod,
     project_id: Option<&ProjectId>,
+) -> Result<(ast::OperationType, GraphQLResponse, bool), crate::RequestError> {
+    execute_query_with_incremental_sender(
+        request_pipeline,
+        expose_internal_errors,
+        http_context,
+        schema,
+        metadata,
+        session,
+        request_headers,
+        raw_request,
+        request_method,
+        project_id,
+        None,
+    )
+    .await
+}
+
+async fn execute_query_with_incremental_sender(
+    request_pipeline: GraphqlRequestPipeline,
+    expose_internal_errors: ExposeInternalErrors,
+    http_context: &HttpContext,
+    schema: &gql::schema::Schema<GDS>,
+    metadata: &Arc<metadata_resolve::Metadata>,
+    session: &Session,
+    request_headers: &reqwest::header::HeaderMap,
+    raw_request: gql::http::RawRequest,
+    request_method: RequestMethod,
+    project_id: Option<&ProjectId>,
+    incremental_sender: Option<&IncrementalSender>,
 ) -> Result<(ast::OperationType, GraphQLResponse, bool), crate::RequestError> {
     let tracer = tracing_util::global_tracer();
     tracer
@@ -72,12 +147,28 @@ pub async fn execute_query_internal(
                 // Set GraphQL request metadata attributes on the current span
                 set_request_metadata_attributes(&raw_request, session);
                 Box::pin(async {
+                    // resolve the query, which may be a persisted query, and check the allowlist
+                    let query = steps::resolve_query(
+                        metadata,
+                        &http_context.persisted_queries,
+                        session,
+                        &raw_request,
+                        project_id,
+                    )?;
+
                     // parse the raw request into a GQL query
-                    let query = steps::parse_query(&raw_request.query)?;
+                    let query = steps::parse_query(&query)?;
 
                     // normalize the parsed GQL query
                     let normalized_request =
-                        steps::normalize_request(schema, session, query, &raw_request)?;
+                        steps::normalize_request(schema, metadata, session, query, &raw_request)?;
+
+                    // mutations have side effects, so they cannot be sent with GET requests
+                    if request_method == RequestMethod::Get
+                        && normalized_request.ty == ast::OperationType::Mutation
+                    {
+                        return Err(crate::RequestError::MutationWithGetRequest);
+                    }
 
                     // generate IR
                     let ir = steps::build_ir(
@@ -109,6 +200,12 @@ pub async fn execute_query_internal(
 
                     let matching_execution_plans = request_plan == new_request_plan;
 
+                    // only the responses of GET requests are cached by HTTP caches
+                    let cache_hint = match request_method {
+                        RequestMethod::Get => cache_hints::resolve_cache_hint(metadata, &session.role, &ir),
+                        RequestMethod::Post => None,
+                    };
+
                     let display_name = match normalized_request.name {
                         Some(ref name) => std::borrow::Cow::Owned(format!("Execute {name}")),
                         None => std::borrow::Cow::Borrowed("Execute request plan"),
@@ -131,8 +228,33 @@ pub async fn execute_query_internal(
                                         .await
                                     }
                                     graphql_ir::RequestPlan::QueryPlan(query_plan) => {
-                                        execute_query_plan(http_context, query_plan, project_id)
-                                            .await
+                                        match incremental_sender {
+                                            // only queries that defer fragments or stream lists
+                                            // are delivered incrementally
+                                            Some(incremental_sender)
+                                                if incremental::has_incremental_fields(
+                                                    &normalized_request.selection_set,
+                                                ) =>
+                                            {
+                                                return incremental::execute_query_plan_incrementally(
+                                                    http_context,
+                                                    query_plan,
+                                                    &normalized_request.selection_set,
+                                                    expose_internal_errors,
+                                                    project_id,
+                                                    incremental_sender,
+                                                )
+                                                .await;
+                                            }
+                                            _ => {
+                                                execute_query_plan(
+                                                    http_context,
+                                                    query_plan,
+                                                    project_id,
+                                                )
+                                                .await
+                                            }
+                                        }
                                     }
                                     graphql_ir::RequestPlan::SubscriptionPlan(
                                         alias,
@@ -158,10 +280,14 @@ pub async fn execute_query_internal(
                                     }
                                 };
 
-                                GraphQLResponse::from_result(
+                                let mut response = GraphQLResponse::from_result(
                                     execute_query_result,
                                     expose_internal_errors,
-                                )
+                                );
+                                if let Some(cache_hint) = &cache_hint {
+                                    response.set_cache_hint(cache_hint);
+                                }
+                                response
                             })
                         })
                         .await;
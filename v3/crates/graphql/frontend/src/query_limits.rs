@@ -0,0 +1,303 @@
+//! Limits on the size and estimated cost of GraphQL operations.
+//!
+//! The depth, field and alias limits are enforced while the operation is
+//! normalized, which stops as soon as one of them is exceeded. The cost is
+//! estimated from the normalized operation, so fragments are already inlined
+//! and variables already substituted. Either way, operations exceeding the
+//! limits are rejected before any request is made to a data connector.
+
+use graphql_schema::{
+    Annotation, InputAnnotation, ModelInputAnnotation, OutputAnnotation, RootFieldAnnotation,
+    RootFieldKind, GDS,
+};
+use lang_graphql::normalized_ast::{self, Operation};
+use lang_graphql::validation::{SelectionLimit, SelectionLimits};
+use metadata_resolve::QueryLimits;
+use open_dds::relationships::RelationshipType;
+use serde_json::json;
+use tracing_util::{ErrorVisibility, TraceableError};
+
+/// The number of rows that a field returning a list of objects is estimated to
+/// return when it has no `limit` argument.
+const DEFAULT_LIST_SIZE: u64 = 100;
+
+#[derive(Debug, thiserror::Error)]
+pub enum QueryLimitError {
+    #[error("the operation exceeds the depth limit of {max_depth}")]
+    DepthLimitExceeded { max_depth: u32 },
+
+    #[error("the operation selects more fields than the limit of {max_fields}")]
+    FieldLimitExceeded { max_fields: u32 },
+
+    #[error("the operation has more aliases than the limit of {max_aliases}")]
+    AliasLimitExceeded { max_aliases: u32 },
+
+    #[error(
+        "the operation has an estimated cost of {cost}, which exceeds the limit of {max_cost}"
+    )]
+    CostLimitExceeded { cost: u64, max_cost: u64 },
+}
+
+impl QueryLimitError {
+    /// The limit error raised while normalizing an operation, if any
+    pub fn from_validation_error(error: &lang_graphql::validation::Error) -> Option<Self> {
+        match error {
+            lang_graphql::validation::Error::SelectionLimitExceeded { limit, max } => {
+                Some(match limit {
+                    SelectionLimit::Depth => Self::DepthLimitExceeded { max_depth: *max },
+                    SelectionLimit::Fields => Self::FieldLimitExceeded { max_fields: *max },
+                    SelectionLimit::Aliases => Self::AliasLimitExceeded { max_aliases: *max },
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Details of the error, returned in the extensions of the GraphQL error
+    pub fn details(&self) -> serde_json::Value {
+        match self {
+            Self::DepthLimitExceeded { max_depth } => json!({
+                "code": "query-limit-exceeded",
+                "limit": "maxDepth",
+                "max": max_depth,
+            }),
+            Self::FieldLimitExceeded { max_fields } => json!({
+                "code": "query-limit-exceeded",
+                "limit": "maxFields",
+                "max": max_fields,
+            }),
+            Self::AliasLimitExceeded { max_aliases } => json!({
+                "code": "query-limit-exceeded",
+                "limit": "maxAliases",
+                "max": max_aliases,
+            }),
+            Self::CostLimitExceeded { cost, max_cost } => json!({
+                "code": "query-limit-exceeded",
+                "limit": "maxCost",
+                "value": cost,
+                "max": max_cost,
+            }),
+        }
+    }
+}
+
+impl TraceableError for QueryLimitError {
+    fn visibility(&self) -> ErrorVisibility {
+        ErrorVisibility::User
+    }
+}
+
+/// The limits enforced while normalizing an operation
+pub fn selection_limits(query_limits: &QueryLimits) -> SelectionLimits {
+    SelectionLimits {
+        max_depth: query_limits.max_depth,
+        max_fields: query_limits.max_fields,
+        max_aliases: query_limits.max_aliases,
+    }
+}
+
+/// Checks the estimated cost of the normalized operation against the given
+/// limits
+pub fn check_cost_limit(
+    query_limits: &QueryLimits,
+    normalized_request: &Operation<'_, GDS>,
+) -> Result<(), QueryLimitError> {
+    if let Some(max_cost) = query_limits.max_cost {
+        let cost = estimate_selection_set_cost(&normalized_request.selection_set);
+        if cost > max_cost {
+            return Err(QueryLimitError::CostLimitExceeded { cost, max_cost });
+        }
+    }
+    Ok(())
+}
+
+fn estimate_selection_set_cost(selection_set: &normalized_ast::SelectionSet<'_, GDS>) -> u64 {
+    selection_set
+        .fields
+        .values()
+        .map(estimate_field_cost)
+        .fold(0, u64::saturating_add)
+}
+
+fn estimate_field_cost(field: &normalized_ast::Field<'_, GDS>) -> u64 {
+    let selection_set_cost = estimate_selection_set_cost(&field.selection_set);
+    // The selection set of a field selecting many rows of a model, such as an
+    // array relationship, is fetched once per row
+    let row_count = if selects_many_rows(field) {
+        estimated_row_count(field)
+    } else {
+        1
+    };
+    row_count
+        .saturating_mul(selection_set_cost)
+        .saturating_add(1)
+}
+
+/// Whether the field selects many rows of a model, as per its root field kind
+/// or relationship type in the metadata
+fn selects_many_rows(field: &normalized_ast::Field<'_, GDS>) -> bool {
+    field.field_call().is_ok_and(|field_call| {
+        matches!(
+            field_call.info.generic,
+            Annotation::Output(
+                OutputAnnotation::RootField(
+                    RootFieldAnnotation::Model {
+                        kind: RootFieldKind::SelectMany | RootFieldKind::SelectGroups,
+                        ..
+                    } | RootFieldAnnotation::ModelSubscription {
+                        kind: RootFieldKind::SelectMany | RootFieldKind::SelectGroups,
+                        ..
+                    }
+                ) | OutputAnnotation::RelationshipToModelGroups(_)
+            )
+        ) || matches!(
+            field_call.info.generic,
+            Annotation::Output(OutputAnnotation::RelationshipToModel(relationship))
+                if relationship.relationship_type == RelationshipType::Array
+        )
+    })
+}
+
+/// The `limit` argument of the field if it has one, `DEFAULT_LIST_SIZE` otherwise
+fn estimated_row_count(field: &normalized_ast::Field<'_, GDS>) -> u64 {
+    field
+        .field_call()
+        .ok()
+        .and_then(|field_call| {
+            field_call
+                .arguments
+                .values()
+                .find_map(|argument| match argument.info.generic {
+                    Annotation::Input(InputAnnotation::Model(
+                        ModelInputAnnotation::ModelLimitArgument,
+                    )) => argument
+                        .value
+                        .as_nullable(normalized_ast::Value::as_int_u32)
+                        .ok()
+                        .flatten(),
+                    _ => None,
+                })
+        })
+        .map_or(DEFAULT_LIST_SIZE, u64::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::fs;
+    use std::path::PathBuf;
+
+    use graphql_schema::GDS;
+    use hasura_authn_core::Role;
+    use lang_graphql::http::Request;
+    use lang_graphql::validation::{normalize_request_with_limits, SelectionLimits};
+    use lang_graphql::{parser::Parser, validation::normalize_request};
+    use metadata_resolve::QueryLimits;
+
+    use super::{check_cost_limit, estimate_selection_set_cost, QueryLimitError};
+
+    #[test]
+    fn test_query_limits() -> Result<(), Box<dyn std::error::Error>> {
+        let test_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests");
+        let schema = fs::read_to_string(test_dir.join("schema.json"))?;
+        let gds = GDS::new_with_default_flags(open_dds::Metadata::from_json_str(&schema)?)?;
+        let schema = GDS::build_schema(&gds)?;
+        let namespaced_getter = graphql_schema::GDSRoleNamespaceGetter {
+            scope: Role::new("admin"),
+        };
+
+        let query = Parser::new(
+            r#"query {
+              articles: ArticleMany(limit: 1, where: { title: { _like: "random" } }) {
+                id
+                title
+              }
+              AuthorMany(where: { first_name: { _is_null: false } }) {
+                author_id
+                first_name
+                Articles(limit: 2) {
+                  title
+                  Author {
+                    first_name
+                  }
+                }
+              }
+            }"#,
+        )
+        .parse_executable_document()?;
+        let request = Request {
+            operation_name: None,
+            query,
+            variables: BTreeMap::new(),
+        };
+        let normalized_request = normalize_request(&namespaced_getter, &schema, &request)?;
+
+        // `ArticleMany` costs 1 + 1 * 2 thanks to its limit. `AuthorMany`
+        // costs 1 + 100 * (2 + 1 + 2 * (1 + (1 + 1))), as the `Articles` array
+        // relationship costs its limit per row while the `Author` object
+        // relationship costs a single row.
+        assert_eq!(
+            estimate_selection_set_cost(&normalized_request.selection_set),
+            904
+        );
+
+        // The operation has a depth of 4, 10 fields and 1 alias
+        let within_limits = SelectionLimits {
+            max_depth: Some(4),
+            max_fields: Some(10),
+            max_aliases: Some(1),
+        };
+        normalize_request_with_limits(&namespaced_getter, &schema, &request, &within_limits)?;
+        for (limits, expected_error) in [
+            (
+                SelectionLimits {
+                    max_depth: Some(3),
+                    ..within_limits
+                },
+                "the operation exceeds the depth limit of 3",
+            ),
+            (
+                SelectionLimits {
+                    max_fields: Some(9),
+                    ..within_limits
+                },
+                "the operation selects more fields than the limit of 9",
+            ),
+            (
+                SelectionLimits {
+                    max_aliases: Some(0),
+                    ..within_limits
+                },
+                "the operation has more aliases than the limit of 0",
+            ),
+        ] {
+            let error =
+                normalize_request_with_limits(&namespaced_getter, &schema, &request, &limits)
+                    .expect_err("the operation should exceed the limits");
+            let query_limit_error = QueryLimitError::from_validation_error(&error)
+                .expect("the error should be a query limit error");
+            assert_eq!(query_limit_error.to_string(), expected_error);
+        }
+
+        let within_cost_limit = QueryLimits {
+            max_cost: Some(904),
+            ..QueryLimits::default()
+        };
+        check_cost_limit(&within_cost_limit, &normalized_request)?;
+        check_cost_limit(&QueryLimits::default(), &normalized_request)?;
+        assert!(matches!(
+            check_cost_limit(
+                &QueryLimits {
+                    max_cost: Some(903),
+                    ..within_cost_limit
+                },
+                &normalized_request
+            ),
+            Err(QueryLimitError::CostLimitExceeded {
+                cost: 904,
+                max_cost: 903
+            })
+        ));
+        Ok(())
+    }
+}
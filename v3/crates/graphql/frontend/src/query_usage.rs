@@ -214,6 +214,11 @@ fn analyze_model_input_annotation(
             parent_type,
             deprecated,
             ..
+        }
+        | graphql_schema::ModelInputAnnotation::ModelGroupsOrderByKeyField {
+            field_name,
+            parent_type,
+            deprecated,
         } => {
             let DeprecatedDetails {
                 is_deprecated,
@@ -254,7 +259,15 @@ fn analyze_model_input_annotation(
         | graphql_schema::ModelInputAnnotation::ModelLimitArgument
         | graphql_schema::ModelInputAnnotation::ModelOffsetArgument
         | graphql_schema::ModelInputAnnotation::ModelUniqueIdentifierArgument { .. }
-        | graphql_schema::ModelInputAnnotation::ModelFilterInputArgument => {}
+        | graphql_schema::ModelInputAnnotation::ModelFilterInputArgument
+        | graphql_schema::ModelInputAnnotation::ModelGroupKeyExtractionArgument
+        | graphql_schema::ModelInputAnnotation::ModelGroupKeyExtractionFunction { .. }
+        | graphql_schema::ModelInputAnnotation::ModelGroupsFilterArgument
+        | graphql_schema::ModelInputAnnotation::ModelGroupsFilterLogicalOperator(_)
+        | graphql_schema::ModelInputAnnotation::ModelGroupsFilterCount
+        | graphql_schema::ModelInputAnnotation::ModelGroupsCountComparisonOperator { .. }
+        | graphql_schema::ModelInputAnnotation::ModelGroupsOrderByArgument
+        | graphql_schema::ModelInputAnnotation::ModelGroupsOrderByCount => {}
     }
     result
 }
@@ -352,10 +365,55 @@ fn analyze_output_annotation(annotation: &graphql_schema::OutputAnnotation) -> V
                 deprecated_reason: reason,
             }));
         }
+        graphql_schema::OutputAnnotation::RelationshipToModelGroups(relationship) => {
+            let DeprecatedDetails {
+                is_deprecated,
+                reason,
+            } = get_deprecated_details(relationship.deprecated.as_ref());
+            result.push(OpenddObject::Relationship(RelationshipUsage {
+                name: relationship.relationship_name.clone(),
+                source: relationship.source_type.clone(),
+                target: RelationshipTarget::Model {
+                    model_name: relationship.target_model_name.clone(),
+                    relationship_type: RelationshipType::Array,
+                    opendd_type: relationship.target_type.clone(),
+                    mapping: get_relationship_model_mappings(&relationship.mappings),
+                },
+                deprecated: is_deprecated,
+                deprecated_reason: reason,
+            }));
+        }
+        graphql_schema::OutputAnnotation::Groups(
+            graphql_schema::GroupsOutputAnnotation::GroupKeyField {
+                field_name,
+                parent_type,
+                deprecated,
+            }
+            | graphql_schema::GroupsOutputAnnotation::GroupKeyExtractionField {
+                field_name,
+                parent_type,
+                deprecated,
+            },
+        ) => {
+            let DeprecatedDetails {
+                is_deprecated,
+                reason,
+            } = get_deprecated_details(deprecated.as_ref());
+            result.push(OpenddObject::Field(FieldUsage {
+                name: field_name.to_owned(),
+                opendd_type: parent_type.to_owned(),
+                deprecated: is_deprecated,
+                deprecated_reason: reason,
+            }));
+        }
         graphql_schema::OutputAnnotation::GlobalIDField { .. }
         | graphql_schema::OutputAnnotation::RelayNodeInterfaceID { .. }
         | graphql_schema::OutputAnnotation::SDL
-        | graphql_schema::OutputAnnotation::Aggregate(_) => {}
+        | graphql_schema::OutputAnnotation::Aggregate(_)
+        | graphql_schema::OutputAnnotation::Groups(
+            graphql_schema::GroupsOutputAnnotation::GroupKey
+            | graphql_schema::GroupsOutputAnnotation::GroupAggregate,
+        ) => {}
     }
     result
 }
@@ -1,7 +1,12 @@
+use std::borrow::Cow;
 use std::collections::BTreeMap;
 
 use super::types::{GraphQlParseError, GraphQlValidationError};
+use crate::error::RequestError;
+use crate::persisted_queries::{self, PersistedQueryError};
+use crate::query_limits;
 use crate::query_usage;
+use engine_types::{PersistedQueryCache, ProjectId};
 use gql::normalized_ast::Operation;
 use graphql_schema::{GDSRoleNamespaceGetter, GDS};
 use hasura_authn_core::Session;
@@ -10,6 +15,32 @@ use lang_graphql::ast::common as ast;
 use std::sync::Arc;
 use tracing_util::{set_attribute_on_active_span, AttributeVisibility, SpanVisibility};
 
+/// Resolves the query of a raw GraphQL request, which may refer to a persisted
+/// query, and checks it against the operation allowlist
+pub fn resolve_query<'r>(
+    metadata: &metadata_resolve::Metadata,
+    registered_queries: &PersistedQueryCache,
+    session: &Session,
+    raw_request: &'r gql::http::RawRequest,
+    project_id: Option<&ProjectId>,
+) -> Result<Cow<'r, str>, PersistedQueryError> {
+    let tracer = tracing_util::global_tracer();
+    tracer.in_span(
+        "resolve_query",
+        "Resolve the persisted query and check the operation allowlist",
+        SpanVisibility::Internal,
+        || {
+            persisted_queries::resolve_persisted_query(
+                &metadata.operation_collections,
+                registered_queries,
+                session,
+                raw_request,
+                project_id,
+            )
+        },
+    )
+}
+
 /// Parses a raw GraphQL request into a GQL query AST
 pub fn parse_query(
     query: &str,
@@ -33,13 +64,21 @@ pub fn parse_query(
     Ok(query)
 }
 
-/// Normalize the parsed GQL query
+/// Normalize the parsed GQL query, checking it against the query limits of the
+/// role. The depth, field and alias limits are enforced during normalization,
+/// and the estimated cost of the normalized query afterwards.
 pub fn normalize_request<'s>(
     schema: &'s gql::schema::Schema<GDS>,
+    metadata: &metadata_resolve::Metadata,
     session: &Session,
     query: gql::ast::executable::ExecutableDocument,
     raw_request: &gql::http::RawRequest,
-) -> Result<Operation<'s, GDS>, gql::validation::Error> {
+) -> Result<Operation<'s, GDS>, RequestError> {
+    let role_limits = metadata
+        .graphql_config
+        .query_limits
+        .as_ref()
+        .map(|query_limits| query_limits.for_role(&session.role));
     let tracer = tracing_util::global_tracer();
     let normalized_request = tracer
         .in_span(
@@ -64,17 +103,28 @@ pub fn normalize_request<'s>(
                         .as_ref()
                         .map_or_else(BTreeMap::default, Clone::clone),
                 };
-                gql::validation::normalize_request(
+                gql::validation::normalize_request_with_limits(
                     &GDSRoleNamespaceGetter {
                         scope: session.role.clone(),
                     },
                     schema,
                     &request,
+                    &role_limits
+                        .map(query_limits::selection_limits)
+                        .unwrap_or_default(),
                 )
                 .map_err(GraphQlValidationError)
             },
         )
         .map_err(|e| e.0)?;
+    if let Some(role_limits) = role_limits {
+        tracer.in_span(
+            "check_query_cost",
+            "Check the estimated cost of the request against the query limits",
+            SpanVisibility::Internal,
+            || query_limits::check_cost_limit(role_limits, &normalized_request),
+        )?;
+    }
     Ok(normalized_request)
 }
 
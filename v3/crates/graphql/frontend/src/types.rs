@@ -1,3 +1,4 @@
+use crate::cache_hints;
 use crate::execute::ExecuteQueryResult;
 use crate::RequestError;
 use engine_types::ExposeInternalErrors;
@@ -28,6 +29,14 @@ impl TraceableError for GraphQLErrors<'_> {
     }
 }
 
+/// The HTTP method that a GraphQL request is sent with. Only queries can be sent with GET, and
+/// their responses may be cached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestMethod {
+    Get,
+    Post,
+}
+
 /// A simple wrapper around a GraphQL HTTP response
 pub struct GraphQLResponse(gql::http::Response);
 
@@ -40,10 +49,19 @@ impl GraphQLResponse {
     }
 
     pub fn from_error(err: &RequestError, expose_internal_errors: ExposeInternalErrors) -> Self {
-        Self(Response::error(
+        let mut response = Response::error(
             err.to_graphql_error(expose_internal_errors),
             axum::http::HeaderMap::default(),
-        ))
+        );
+        // Ref: <https://graphql.github.io/graphql-over-http/draft/#sec-GET>
+        if let RequestError::MutationWithGetRequest = err {
+            response.status_code = axum::http::StatusCode::METHOD_NOT_ALLOWED;
+            response.headers.insert(
+                axum::http::header::ALLOW,
+                axum::http::HeaderValue::from_static("POST"),
+            );
+        }
+        Self(response)
     }
 
     pub fn from_response(response: gql::http::Response) -> Self {
@@ -66,6 +84,20 @@ impl GraphQLResponse {
         }
     }
 
+    /// Sets the `Cache-Control` and `Vary` headers of the response from the given cache hint,
+    /// unless the response contains errors, which are never cached
+    pub fn set_cache_hint(&mut self, cache_hint: &metadata_resolve::ModelCacheHint) {
+        if !self.does_contain_error() {
+            self.0.headers.insert(
+                axum::http::header::CACHE_CONTROL,
+                cache_hints::cache_control_header_value(cache_hint),
+            );
+            self.0
+                .headers
+                .insert(axum::http::header::VARY, cache_hints::vary_header_value());
+        }
+    }
+
     pub fn inner(self) -> gql::http::Response {
         self.0
     }
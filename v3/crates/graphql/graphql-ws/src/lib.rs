@@ -1,9 +1,11 @@
 pub(crate) mod metrics;
+pub(crate) mod multiplexer;
 pub(crate) mod poller;
 pub(crate) mod protocol;
 pub(crate) mod websocket;
 
 pub use metrics::{NoOpWebSocketMetrics, WebSocketMetrics};
+pub use multiplexer::SubscriptionMultiplexer;
 pub use protocol::types::OperationId;
 pub use websocket::{
     types::{ConnectionExpiry, Context, WebSocketId},
@@ -0,0 +1,271 @@
+//! Multiplexing of subscription polling.
+//!
+//! Subscriptions which resolve to the same NDC query request are grouped in a
+//! cohort. As the request is built after the role's permissions and the
+//! session variables they refer to have been applied, two subscriptions end up
+//! in the same cohort only if they are for the same normalized query, with the
+//! same permissions and the same relevant session variables. A cohort polls
+//! the data connector once per polling interval and publishes the response to
+//! all its subscribers, each of which processes it against its own selection
+//! set. New responses are only published when they differ from the previous
+//! one.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use blake2::{Blake2b, Digest};
+use engine_types::{HttpContext, ProjectId};
+use execute::ndc::{NdcQueryRequest, NdcQueryResponse};
+use tokio::sync::watch;
+
+/// The result of polling a cohort's query, shared by all its subscribers.
+pub type CohortResult = Arc<Result<NdcQueryResponse, execute::FieldError>>;
+
+/// A subscriber's handle to a cohort. The cohort is stopped once all the
+/// handles to it have been dropped.
+pub type CohortReceiver = watch::Receiver<Option<CohortResult>>;
+
+/// Identifies the subscriptions that can share a single poller.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CohortKey {
+    project_id: Option<String>,
+    data_connector: String,
+    query_request_hash: [u8; 64],
+    polling_interval_ms: u64,
+}
+
+impl CohortKey {
+    fn new(
+        project_id: Option<&ProjectId>,
+        data_connector: &metadata_resolve::DataConnectorLink,
+        query_request: &NdcQueryRequest,
+        polling_interval_ms: u64,
+    ) -> Result<Self, serde_json::Error> {
+        let serialized = serde_json::to_vec(query_request)?;
+        Ok(Self {
+            project_id: project_id.map(|project_id| project_id.0.clone()),
+            data_connector: data_connector.name.to_string(),
+            query_request_hash: hash(&serialized),
+            polling_interval_ms,
+        })
+    }
+}
+
+fn hash(bytes: &[u8]) -> [u8; 64] {
+    let mut hasher = Blake2b::new();
+    hasher.update(bytes);
+    hasher.finalize().into()
+}
+
+struct Cohort {
+    /// Distinguishes a cohort from one which later replaces it under the same key
+    id: uuid::Uuid,
+    sender: watch::Sender<Option<CohortResult>>,
+}
+
+/// Groups subscription pollers executing identical queries. Cheap to clone, all
+/// clones share the same cohorts.
+#[derive(Clone, Default)]
+pub struct SubscriptionMultiplexer {
+    cohorts: Arc<Mutex<HashMap<CohortKey, Cohort>>>,
+}
+
+impl SubscriptionMultiplexer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of cohorts that are currently polling.
+    pub fn cohort_count(&self) -> usize {
+        self.cohorts.lock().map_or(0, |cohorts| cohorts.len())
+    }
+
+    /// Joins the cohort polling the given query request, starting a new one if
+    /// there is none. If the cohort has already polled, the latest result is
+    /// immediately available to the new subscriber.
+    ///
+    /// A query request that cannot be serialized cannot be compared with any
+    /// other, so it is polled by a cohort of its own which no other
+    /// subscription can join.
+    pub(crate) fn subscribe(
+        &self,
+        http_context: &HttpContext,
+        project_id: Option<&ProjectId>,
+        query_request: &NdcQueryRequest,
+        data_connector: &Arc<metadata_resolve::DataConnectorLink>,
+        polling_interval_ms: u64,
+    ) -> CohortReceiver {
+        let Ok(key) = CohortKey::new(
+            project_id,
+            data_connector,
+            query_request,
+            polling_interval_ms,
+        ) else {
+            let (sender, receiver) = watch::channel(None);
+            let poll = CohortPoller {
+                multiplexer: self.clone(),
+                key: None,
+                polling_interval_ms,
+                cohort_id: uuid::Uuid::new_v4(),
+                sender,
+                http_context: http_context.clone(),
+                project_id: project_id.cloned(),
+                query_request: query_request.clone(),
+                data_connector: data_connector.clone(),
+                span_link: tracing_util::SpanLink::from_current_span(),
+            };
+            tokio::spawn(poll.run());
+            return receiver;
+        };
+        let mut cohorts = self
+            .cohorts
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Some(cohort) = cohorts.get(&key) {
+            let mut receiver = cohort.sender.subscribe();
+            receiver.mark_changed();
+            return receiver;
+        }
+        let (sender, receiver) = watch::channel(None);
+        let cohort_id = uuid::Uuid::new_v4();
+        cohorts.insert(
+            key.clone(),
+            Cohort {
+                id: cohort_id,
+                sender: sender.clone(),
+            },
+        );
+        drop(cohorts);
+
+        let poll = CohortPoller {
+            multiplexer: self.clone(),
+            key: Some(key),
+            polling_interval_ms,
+            cohort_id,
+            sender,
+            http_context: http_context.clone(),
+            project_id: project_id.cloned(),
+            query_request: query_request.clone(),
+            data_connector: data_connector.clone(),
+            span_link: tracing_util::SpanLink::from_current_span(),
+        };
+        tokio::spawn(poll.run());
+        receiver
+    }
+
+    /// Removes the cohort if it has no subscribers left, or unconditionally if
+    /// `force` is set. Returns whether the cohort was removed.
+    fn remove_cohort(&self, key: &CohortKey, cohort_id: uuid::Uuid, force: bool) -> bool {
+        let mut cohorts = self
+            .cohorts
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        match cohorts.get(key) {
+            Some(cohort) if cohort.id == cohort_id => {
+                if force || cohort.sender.receiver_count() == 0 {
+                    cohorts.remove(key);
+                    true
+                } else {
+                    false
+                }
+            }
+            // the cohort has already been replaced
+            _ => true,
+        }
+    }
+}
+
+/// The background task polling on behalf of a cohort.
+struct CohortPoller {
+    multiplexer: SubscriptionMultiplexer,
+    /// The key the cohort is registered under, unless it cannot be joined
+    key: Option<CohortKey>,
+    polling_interval_ms: u64,
+    cohort_id: uuid::Uuid,
+    sender: watch::Sender<Option<CohortResult>>,
+    http_context: HttpContext,
+    project_id: Option<ProjectId>,
+    query_request: NdcQueryRequest,
+    data_connector: Arc<metadata_resolve::DataConnectorLink>,
+    span_link: tracing_util::SpanLink,
+}
+
+impl CohortPoller {
+    async fn run(self) {
+        let polling_interval = tokio::time::Duration::from_millis(self.polling_interval_ms);
+        let tracer = tracing_util::global_tracer();
+        let mut response_hash = None;
+        loop {
+            let result: Result<NdcQueryResponse, execute::FieldError> = tracer
+                .new_trace_async_with_link(
+                    "websocket_poll_subscription_cohort",
+                    "Polling a subscription cohort",
+                    tracing_util::SpanVisibility::User,
+                    self.span_link.clone(),
+                    || {
+                        tracing_util::set_attribute_on_active_span(
+                            tracing_util::AttributeVisibility::Default,
+                            "graphql.subscription.cohort_id",
+                            self.cohort_id.to_string(),
+                        );
+                        tracing_util::set_attribute_on_active_span(
+                            tracing_util::AttributeVisibility::Default,
+                            "graphql.subscription.cohort_size",
+                            i64::try_from(self.sender.receiver_count()).unwrap_or(i64::MAX),
+                        );
+                        Box::pin(async {
+                            let response = execute::fetch_from_data_connector(
+                                &self.http_context,
+                                &self.query_request,
+                                &self.data_connector,
+                                self.project_id.as_ref(),
+                            )
+                            .await?;
+                            Ok(response)
+                        })
+                    },
+                )
+                .await;
+
+            match result {
+                Ok(response) => {
+                    // A response that cannot be serialized cannot be
+                    // compared with the previous one, so it is always published
+                    let new_hash = serde_json::to_vec(&response)
+                        .ok()
+                        .map(|serialized| hash(&serialized));
+                    // Only wake up the subscribers if the response has changed
+                    if new_hash.is_none() || response_hash != new_hash {
+                        response_hash = new_hash;
+                        self.sender.send_replace(Some(Arc::new(Ok(response))));
+                    }
+                }
+                Err(error) => {
+                    // Subscribers stop on errors, so does the cohort. A
+                    // subsequent subscription will start a new one.
+                    self.remove_cohort(true);
+                    self.sender.send_replace(Some(Arc::new(Err(error))));
+                    break;
+                }
+            }
+
+            // Wait for the polling interval, or until every subscriber has left
+            tokio::select! {
+                () = tokio::time::sleep(polling_interval) => {}
+                () = self.sender.closed() => {}
+            }
+            if self.remove_cohort(false) {
+                break;
+            }
+        }
+    }
+
+    /// Removes the cohort from the multiplexer, see `SubscriptionMultiplexer::remove_cohort`.
+    /// A cohort which is not registered is removed once it has no subscribers left.
+    fn remove_cohort(&self, force: bool) -> bool {
+        match &self.key {
+            Some(key) => self.multiplexer.remove_cohort(key, self.cohort_id, force),
+            None => force || self.sender.receiver_count() == 0,
+        }
+    }
+}
@@ -5,10 +5,12 @@ use crate::websocket::types as ws;
 use axum::http;
 use blake2::{Blake2b, Digest};
 use engine_types::ExposeInternalErrors;
-use graphql_frontend::{process_response, ExecuteQueryResult, RootFieldResult};
-use graphql_ir::RequestPlan;
+use graphql_frontend::{process_response, ExecuteQueryResult, IncrementalPart, RootFieldResult};
+use graphql_ir::{QueryPlan, RequestPlan};
+use graphql_schema::GDS;
 use hasura_authn_core::Session;
 use indexmap::IndexMap;
+use lang_graphql::normalized_ast;
 use nonempty::NonEmpty;
 use pre_parse_plugin::execute as pre_parse_plugin;
 use pre_response_plugin::execute as pre_response_plugin;
@@ -267,15 +269,23 @@ pub async fn execute_query_internal<M: WebSocketMetrics>(
     session: Session,
     headers: http::HeaderMap,
     connection: &ws::Connection<M>,
-    raw_request: lang_graphql::http::RawRequest,
+    mut raw_request: lang_graphql::http::RawRequest,
 ) -> Result<(), graphql_frontend::RequestError> {
     let schema = &connection.context.schema;
     let metadata = &connection.context.metadata;
+    // Resolve the query, which may be a persisted query, and check the allowlist.
+    raw_request.query = graphql_frontend::resolve_query(
+        metadata,
+        &session,
+        &raw_request,
+        connection.context.project_id.as_ref(),
+    )?
+    .into_owned();
     // Parse the raw GraphQL request.
     let query = graphql_frontend::parse_query(&raw_request.query)?;
-    // Normalize the parsed GraphQL query.
+    // Normalize the parsed GraphQL query, checking it against the query limits.
     let normalized_request =
-        graphql_frontend::normalize_request(schema, &session, query, &raw_request)?;
+        graphql_frontend::normalize_request(schema, metadata, &session, query, &raw_request)?;
 
     // Generate Intermediate Representation (IR) from the query.
     let ir = graphql_frontend::build_ir(
@@ -315,6 +325,7 @@ pub async fn execute_query_internal<M: WebSocketMetrics>(
                         session,
                         headers,
                         raw_request,
+                        &normalized_request.selection_set,
                         request_plan,
                     )
                     .await;
@@ -334,10 +345,11 @@ async fn execute<M: WebSocketMetrics>(
     session: Session,
     headers: http::HeaderMap,
     raw_request: lang_graphql::http::RawRequest,
+    selection_set: &normalized_ast::SelectionSet<'_, GDS>,
     request_plan: RequestPlan<'_, '_, '_>,
 ) {
     let project_id = connection.context.project_id.as_ref();
-    let http_context = &connection.context.http_context;
+    let http_context = &connection.context.http_context.for_request();
     let expose_internal_errors = connection.context.expose_internal_errors;
     match request_plan {
         // Handle mutations.
@@ -357,6 +369,24 @@ async fn execute<M: WebSocketMetrics>(
             )
             .await;
         }
+        // Handle queries that defer fragments or stream lists, unless there are pre-response
+        // plugins, which are sent the whole response.
+        RequestPlan::QueryPlan(query_plan)
+            if graphql_frontend::has_incremental_fields(selection_set)
+                && connection
+                    .context
+                    .plugin_configs
+                    .pre_response_plugins
+                    .is_empty() =>
+        {
+            send_incremental_operation_response(
+                operation_id,
+                query_plan,
+                selection_set,
+                connection,
+            )
+            .await;
+        }
         // Handle queries.
         RequestPlan::QueryPlan(query_plan) => {
             let execute_query_result =
@@ -373,17 +403,24 @@ async fn execute<M: WebSocketMetrics>(
             )
             .await;
         }
-        // Handle subscriptions by starting a polling loop to repeatedly fetch data.
+        // Handle subscriptions by joining the cohort polling the same query, and
+        // processing every new result it publishes.
         RequestPlan::SubscriptionPlan(alias, plan) => {
             match execute::resolve_ndc_subscription_execution(plan.subscription_execution).await {
                 Ok(ndc_subscription) => {
-                    let query_request = ndc_subscription.query_request;
-                    let data_connector = ndc_subscription.data_connector;
                     let selection_set = plan.selection_set;
                     let process_response_as = ndc_subscription.process_response_as;
+                    let nested_relationships_query_node =
+                        ndc_subscription.nested_relationships_query_node;
                     let is_nullable = process_response_as.is_nullable();
-                    let polling_interval_duration =
-                        tokio::time::Duration::from_millis(ndc_subscription.polling_interval_ms);
+
+                    let mut cohort = connection.subscription_multiplexer.subscribe(
+                        http_context,
+                        project_id,
+                        &ndc_subscription.query_request,
+                        &ndc_subscription.data_connector,
+                        ndc_subscription.polling_interval_ms,
+                    );
 
                     // Initialize a response hash to track changes in the response.
                     let mut response_hash = ResponseHash::new();
@@ -391,80 +428,89 @@ async fn execute<M: WebSocketMetrics>(
                     let tracer = tracing_util::global_tracer();
                     let this_span_link = tracing_util::SpanLink::from_current_span();
 
-                    // A loop to periodically wait for the polling interval, then fetch data from NDC.
-                    loop {
-                        let result: Result<_, execute::FieldError> = tracer
-                            .new_trace_async_with_link(
-                                "websocket_poll_subscription",
-                                "Polling a subscription query",
-                                tracing_util::SpanVisibility::User,
-                                this_span_link.clone(),
-                                || {
-                                    tracing_util::set_attribute_on_active_span(
-                                        tracing_util::AttributeVisibility::Default,
-                                        "graphql.operation.id",
-                                        operation_id.0.clone(),
-                                    );
-                                    Box::pin(async {
-                                        // Fetch response from the connector
-                                        let response = execute::fetch_from_data_connector(
-                                            http_context,
-                                            &query_request,
-                                            &data_connector,
-                                            None,
-                                        )
-                                        .await?;
-                                        // Process response
-                                        let response_rowsets = response.as_latest_rowsets();
-                                        let processed_response = process_response(
-                                            selection_set,
-                                            response_rowsets,
-                                            &process_response_as,
-                                        );
-                                        let root_fields = IndexMap::from([(
-                                            alias.clone(),
-                                            RootFieldResult::from_processed_response(
-                                                is_nullable,
-                                                processed_response,
-                                            ),
-                                        )]);
-                                        // Generate a single root field query response
-                                        let query_result = ExecuteQueryResult { root_fields };
+                    // A loop to wait for the cohort to publish a new result, then process it.
+                    // The loop ends when the cohort stops.
+                    while cohort.changed().await.is_ok() {
+                        let Some(cohort_result) = cohort.borrow_and_update().clone() else {
+                            continue;
+                        };
+                        match cohort_result.as_ref() {
+                            Ok(response) => {
+                                let stop_subscription = tracer
+                                    .new_trace_async_with_link(
+                                        "websocket_poll_subscription",
+                                        "Processing a subscription poll result",
+                                        tracing_util::SpanVisibility::User,
+                                        this_span_link.clone(),
+                                        || {
+                                            tracing_util::set_attribute_on_active_span(
+                                                tracing_util::AttributeVisibility::Default,
+                                                "graphql.operation.id",
+                                                operation_id.0.clone(),
+                                            );
+                                            Box::pin(async {
+                                                // Process response
+                                                let mut response_rowsets =
+                                                    response.clone().as_latest_rowsets();
+                                                let processed_response =
+                                                    nested_relationships_query_node
+                                                        .as_ref()
+                                                        .map_or(Ok(()), |query_node| {
+                                                            execute::lift_nested_relationships(
+                                                                query_node,
+                                                                &mut response_rowsets,
+                                                            )
+                                                        })
+                                                        .and_then(|()| {
+                                                            process_response(
+                                                                selection_set,
+                                                                response_rowsets,
+                                                                &process_response_as,
+                                                            )
+                                                        });
+                                                let root_fields = IndexMap::from([(
+                                                    alias.clone(),
+                                                    RootFieldResult::from_processed_response(
+                                                        is_nullable,
+                                                        processed_response,
+                                                    ),
+                                                )]);
+                                                // Generate a single root field query response
+                                                let query_result =
+                                                    ExecuteQueryResult { root_fields };
 
-                                        let graphql_response =
-                                            graphql_frontend::GraphQLResponse::from_result(
-                                                query_result,
-                                                expose_internal_errors,
-                                            )
-                                            .inner();
-                                        // Send the response
-                                        let stop_subscription =
-                                            send_subscription_operation_response(
-                                                client_address,
-                                                &mut response_hash,
-                                                operation_id.clone(),
-                                                &raw_request,
-                                                &session,
-                                                &headers,
-                                                graphql_response,
-                                                connection,
-                                            )
-                                            .await;
-                                        Ok(stop_subscription)
-                                    })
-                                },
-                            )
-                            .await;
-
-                        match result {
-                            Ok(stop_subscription) => {
+                                                let graphql_response =
+                                                    graphql_frontend::GraphQLResponse::from_result(
+                                                        query_result,
+                                                        expose_internal_errors,
+                                                    )
+                                                    .inner();
+                                                // Send the response
+                                                let stop_subscription =
+                                                    send_subscription_operation_response(
+                                                        client_address,
+                                                        &mut response_hash,
+                                                        operation_id.clone(),
+                                                        &raw_request,
+                                                        &session,
+                                                        &headers,
+                                                        graphql_response,
+                                                        connection,
+                                                    )
+                                                    .await;
+                                                tracing_util::Successful::new(stop_subscription)
+                                            })
+                                        },
+                                    )
+                                    .await
+                                    .into_inner();
                                 // Stop the subscription, if only errors sent in the current response
                                 if stop_subscription {
                                     break;
                                 }
                             }
                             Err(err) => {
-                                // Send the exception as a GraphQL error and stop polling
+                                // Send the exception as a GraphQL error and stop the subscription
                                 let graphql_error =
                                     err.to_graphql_error(expose_internal_errors, None);
                                 send_graphql_errors(
@@ -476,8 +522,6 @@ async fn execute<M: WebSocketMetrics>(
                                 break;
                             }
                         }
-                        // Wait for the polling interval
-                        tokio::time::sleep(polling_interval_duration).await;
                     }
                 }
                 // Send an error message if the subscription fails to resolve.
@@ -613,6 +657,56 @@ async fn send_single_result_operation_response<M: WebSocketMetrics>(
     }
 }
 
+/// Executes a query that defers fragments or streams lists, sending a `next` message for each part
+/// of its response as it becomes available, followed by a complete message.
+async fn send_incremental_operation_response<M: WebSocketMetrics>(
+    operation_id: OperationId,
+    query_plan: QueryPlan<'_, '_, '_>,
+    selection_set: &normalized_ast::SelectionSet<'_, GDS>,
+    connection: &ws::Connection<M>,
+) {
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+    let execution = async {
+        // the sender is dropped once the execution completes, which ends the forwarding
+        let sender = sender;
+        graphql_frontend::execute_query_plan_incrementally(
+            &connection.context.http_context.for_request(),
+            query_plan,
+            selection_set,
+            connection.context.expose_internal_errors,
+            connection.context.project_id.as_ref(),
+            &sender,
+        )
+        .await
+    };
+    let forwarding = async {
+        while let Some(part) = receiver.recv().await {
+            // a response without data is sent as an error message instead
+            if let IncrementalPart::Initial { payload, .. } = &part {
+                if payload.get("data").map_or(true, serde_json::Value::is_null) {
+                    continue;
+                }
+            }
+            connection
+                .send(ws::Message::Protocol(Box::new(
+                    ServerMessage::NextIncremental {
+                        id: operation_id.clone(),
+                        payload: part.payload().clone(),
+                    },
+                )))
+                .await;
+        }
+    };
+    let (response, ()) = futures_util::future::join(execution, forwarding).await;
+    match GraphQLResponse::new(response.inner()) {
+        GraphQLResponse::Ok(_) => send_complete(operation_id, connection).await,
+        // No need to send a complete message after sending errors.
+        GraphQLResponse::Error(errors) => {
+            send_graphql_errors(operation_id, errors, connection).await;
+        }
+    }
+}
+
 /// Sends a subscription operation response.
 async fn send_subscription_operation_response<M: WebSocketMetrics>(
     client_address: std::net::SocketAddr,
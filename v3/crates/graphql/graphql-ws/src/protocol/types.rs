@@ -82,6 +82,15 @@ pub enum ServerMessage {
         payload: lang_graphql::http::Response,
     },
 
+    /// Server sends a part of the result of a query that defers fragments or streams lists,
+    /// which is delivered incrementally.
+    /// ref: <https://github.com/graphql/graphql-spec/pull/742>
+    #[serde(rename = "next")]
+    NextIncremental {
+        id: OperationId,
+        payload: serde_json::Value,
+    },
+
     /// Server sends errors resulting from a requested operation.
     /// ref: <https://github.com/enisdenjo/graphql-ws/blob/master/PROTOCOL.md#error>
     #[serde(rename = "error")]
@@ -12,6 +12,7 @@ use axum::{
 use futures_util::StreamExt;
 
 use crate::metrics::WebSocketMetrics;
+use crate::multiplexer::SubscriptionMultiplexer;
 use crate::protocol;
 
 pub static SEC_WEBSOCKET_PROTOCOL: &str = "Sec-WebSocket-Protocol";
@@ -21,6 +22,8 @@ static WEBSOCKET_CHANNEL_SIZE: usize = 50;
 /// GraphQL WebSocket server implementation.
 pub struct WebSocketServer<M> {
     pub connections: types::Connections<M>,
+    /// Shared by the subscriptions of all connections
+    pub subscription_multiplexer: SubscriptionMultiplexer,
 }
 
 impl<M> WebSocketServer<M> {
@@ -28,6 +31,7 @@ impl<M> WebSocketServer<M> {
     pub fn new() -> Self {
         Self {
             connections: types::Connections::new(), // Initialize an empty map of active connections
+            subscription_multiplexer: SubscriptionMultiplexer::new(),
         }
     }
 
@@ -78,6 +82,7 @@ impl<M> WebSocketServer<M> {
                     // Check if headers contain graphql-transport-ws protocol
                     check_protocol_in_headers(&context.handshake_headers)?;
                     let connections = self.connections.clone();
+                    let subscription_multiplexer = self.subscription_multiplexer.clone();
                     // Upgrade the WebSocket connection and handle it
                     let span_link = tracing_util::SpanLink::from_current_span();
                     // // Clone the websocket_id to move it into the closure
@@ -91,6 +96,7 @@ impl<M> WebSocketServer<M> {
                                 websocket_id_cloned,
                                 context,
                                 connections,
+                                subscription_multiplexer,
                                 span_link,
                             )
                         });
@@ -161,6 +167,7 @@ async fn start_websocket_session<M: WebSocketMetrics>(
     websocket_id: types::WebSocketId,
     context: types::Context<M>,
     connections: types::Connections<M>,
+    subscription_multiplexer: SubscriptionMultiplexer,
     parent_span_link: tracing_util::SpanLink,
 ) {
     let tracer = tracing_util::global_tracer();
@@ -182,7 +189,12 @@ async fn start_websocket_session<M: WebSocketMetrics>(
 
                     // Create a new WebSocket connection instance
                     let connection = connections
-                        .new_connection(websocket_id, context, channel_sender)
+                        .new_connection(
+                            websocket_id,
+                            context,
+                            channel_sender,
+                            subscription_multiplexer,
+                        )
                         .await;
 
                     let this_span_link = tracing_util::SpanLink::from_current_span();
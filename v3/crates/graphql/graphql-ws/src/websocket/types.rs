@@ -12,6 +12,7 @@ use std::sync::Arc;
 use tokio::sync::{mpsc::Sender, RwLock};
 
 use crate::metrics::WebSocketMetrics;
+use crate::multiplexer::SubscriptionMultiplexer;
 use crate::poller;
 use crate::protocol::types as protocol;
 
@@ -74,13 +75,14 @@ impl<M> Connections<M> {
         id: WebSocketId,
         context: Context<M>,
         channel: Sender<Message>,
+        subscription_multiplexer: SubscriptionMultiplexer,
     ) -> Connection<M>
     where
         M: WebSocketMetrics,
     {
         // Record this new connection in metrics
         context.metrics.record_connection_init();
-        let new_connection = Connection::new(id, context, channel);
+        let new_connection = Connection::new(id, context, channel, subscription_multiplexer);
         let mut map = self.0.write().await;
         map.insert(new_connection.id.clone(), new_connection.clone());
         new_connection
@@ -124,12 +126,19 @@ pub struct Connection<M> {
     pub send_channel: Sender<Message>,
     // Active pollers associated with operations. A web socket connection can have multiple active subscriptions.
     pub pollers: Arc<RwLock<HashMap<protocol::OperationId, poller::Poller>>>,
+    // Groups identical subscriptions across connections so that they are polled only once
+    pub subscription_multiplexer: SubscriptionMultiplexer,
 }
 
 impl<M> Connection<M> {
     /// Creates a new WebSocket connection with the given context and message sender channel.
     /// To actually create a WebSocket connection, use the `Connections::new_connection` method.
-    pub fn new(id: WebSocketId, context: Context<M>, channel: Sender<Message>) -> Self {
+    pub fn new(
+        id: WebSocketId,
+        context: Context<M>,
+        channel: Sender<Message>,
+        subscription_multiplexer: SubscriptionMultiplexer,
+    ) -> Self {
         Self {
             id,
             protocol_init_state: Arc::new(RwLock::new(
@@ -138,6 +147,7 @@ impl<M> Connection<M> {
             context,                                        // Shared connection context
             send_channel: channel, // Channel for sending messages over the WebSocket
             pollers: Arc::new(RwLock::new(HashMap::new())), // A map of active pollers
+            subscription_multiplexer,
         }
     }
 
@@ -1,6 +1,9 @@
 use axum::http::HeaderMap;
 use axum::{extract::State, response::IntoResponse, routing::get};
-use engine_types::{ExposeInternalErrors, HttpContext};
+use engine_types::{
+    CircuitBreakers, ExposeInternalErrors, HttpContext, PersistedQueryCache, RemoteJoinQueryLimit,
+    ReplicaPools, ResponseCache,
+};
 use futures_util::{SinkExt, StreamExt};
 use graphql_ir::GraphqlRequestPipeline;
 use graphql_ws::Context;
@@ -98,6 +101,11 @@ async fn start_websocket_server_inner(
     let http_context = HttpContext {
         client: reqwest::Client::new(),
         ndc_response_size_limit: None,
+        ndc_circuit_breakers: CircuitBreakers::default(),
+        ndc_replica_pools: ReplicaPools::default(),
+        ndc_response_cache: ResponseCache::default(),
+        remote_join_query_limit: RemoteJoinQueryLimit::default(),
+        persisted_queries: PersistedQueryCache::default(),
     };
     let plugin_configs = metadata_resolve::LifecyclePluginConfigs {
         pre_parse_plugins: Vec::new(),
@@ -118,10 +126,8 @@ async fn start_websocket_server_inner(
         handshake_headers: Arc::new(HeaderMap::new()), // Will be populated in "ws_handler"
     };
 
-    let connections = graphql_ws::Connections::new();
-    let ws_server = graphql_ws::WebSocketServer {
-        connections: connections.clone(),
-    };
+    let ws_server = graphql_ws::WebSocketServer::new();
+    let connections = ws_server.connections.clone();
     // Spawn a server
     let state = ServerState { ws_server, context };
     let server_handle = tokio::spawn(async move {
@@ -300,3 +306,11 @@ pub(crate) async fn check_operation_id<M>(
     let (_, connection) = connections.iter().next().unwrap();
     assert!(connection.pollers.read().await.contains_key(&operation_id));
 }
+
+#[allow(dead_code)]
+pub(crate) async fn cohort_count<M>(connections: &graphql_ws::Connections<M>) -> usize {
+    // One connection should be present in an active test
+    let connections = connections.0.read().await;
+    let (_, connection) = connections.iter().next().unwrap();
+    connection.subscription_multiplexer.cohort_count()
+}
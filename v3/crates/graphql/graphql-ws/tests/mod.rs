@@ -244,6 +244,74 @@ async fn test_graphql_ws_subscribe_admin() {
     server_handle.abort();
 }
 
+#[tokio::test]
+async fn test_graphql_ws_subscribe_identical_queries_share_a_cohort() {
+    let TestServer {
+        connections,
+        mut socket,
+        server_handle,
+    } = start_websocket_server().await;
+    // Send connection_init and check ack
+    assert_graphql_ws_connection_init(&mut socket, connection_init_admin()).await;
+
+    // Send the same subscription twice, with different operation ids
+    for operation_id in ["operation-1", "operation-2"] {
+        let json_message = serde_json::to_string(&subscribe_article_by_id(operation_id)).unwrap();
+        socket
+            .send(tungstenite::Message::Text(json_message))
+            .await
+            .unwrap();
+    }
+
+    // Both subscribers receive the result
+    let mut operation_ids = Vec::new();
+    for _ in 0..2 {
+        let message = expect_text_message(&mut socket).await;
+        let message_json: serde_json::Value =
+            serde_json::from_str(message.as_str()).expect("Expected a valid JSON");
+        assert_eq!(message_json["type"], "next");
+        assert_eq!(
+            message_json["payload"]["data"]["ArticleByID"]["title"],
+            "The Next 700 Programming Languages"
+        );
+        operation_ids.push(message_json["id"].as_str().unwrap().to_string());
+    }
+    operation_ids.sort();
+    assert_eq!(operation_ids, vec!["operation-1", "operation-2"]);
+
+    // Only one cohort polls on behalf of both subscribers
+    assert_eq!(cohort_count(&connections).await, 1);
+
+    // Complete both subscriptions
+    for operation_id in ["operation-1", "operation-2"] {
+        let json_message = serde_json::to_string(&serde_json::json!({
+            "type": "complete",
+            "id": operation_id
+        }))
+        .unwrap();
+        socket
+            .send(tungstenite::Message::Text(json_message))
+            .await
+            .unwrap();
+    }
+    assert_zero_operations_timeout(&connections).await;
+
+    // The cohort stops once it has no subscribers left
+    let result = tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
+        while cohort_count(&connections).await != 0 {
+            tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+        }
+    })
+    .await;
+    assert!(result.is_ok(), "Cohort is not stopped");
+
+    // Close the connection
+    socket.close(None).await.unwrap();
+    // Assert zero connections
+    assert_zero_connections_timeout(connections).await;
+    server_handle.abort();
+}
+
 #[tokio::test]
 async fn test_graphql_ws_subscribe_user_1() {
     let TestServer {
@@ -457,3 +525,110 @@ async fn test_graphql_ws_keepalive() {
     assert_zero_connections_timeout(connections).await;
     server_handle.abort();
 }
+
+#[tokio::test]
+async fn test_graphql_ws_query_with_deferred_fragment() {
+    let TestServer {
+        connections,
+        mut socket,
+        server_handle,
+    } = start_websocket_server().await;
+    // Send connection_init and check ack
+    assert_graphql_ws_connection_init(&mut socket, connection_init_admin()).await;
+
+    // Send a query deferring a fragment
+    let operation_id = "some-operation-id";
+    let query = r#"
+          query {
+            ArticleByID(article_id: 1) {
+              article_id
+              title
+              ... @defer(label: "author") {
+                Author {
+                  author_id
+                  first_name
+                }
+              }
+            }
+          }
+    "#;
+    let subscribe_message = serde_json::json!({
+        "type": "subscribe",
+        "id": operation_id,
+        "payload": {
+            "query": query,
+            "variables": {}
+        }
+    });
+    socket
+        .send(tungstenite::Message::Text(
+            serde_json::to_string(&subscribe_message).unwrap(),
+        ))
+        .await
+        .unwrap();
+
+    // The initial response is sent without the deferred fragment
+    let message = expect_text_message(&mut socket).await;
+    let message_json: serde_json::Value =
+        serde_json::from_str(message.as_str()).expect("Expected a valid JSON");
+    let expected = serde_json::json!({
+        "type": "next",
+        "id": operation_id,
+        "payload": {
+            "data": {
+                "ArticleByID": {
+                    "article_id": 1,
+                    "title": "The Next 700 Programming Languages"
+                }
+            },
+            "hasNext": true
+        }
+    });
+    assert_eq!(message_json, expected);
+
+    // The deferred fragment follows in a subsequent payload
+    let message = expect_text_message(&mut socket).await;
+    let message_json: serde_json::Value =
+        serde_json::from_str(message.as_str()).expect("Expected a valid JSON");
+    let expected = serde_json::json!({
+        "type": "next",
+        "id": operation_id,
+        "payload": {
+            "incremental": [
+                {
+                    "data": {
+                        "Author": {
+                            "author_id": 1,
+                            "first_name": "Peter"
+                        }
+                    },
+                    "path": ["ArticleByID"],
+                    "label": "author"
+                }
+            ],
+            "hasNext": false
+        }
+    });
+    assert_eq!(message_json, expected);
+
+    // The operation completes once every payload is sent
+    let message = expect_text_message(&mut socket).await;
+    let message_json: serde_json::Value =
+        serde_json::from_str(message.as_str()).expect("Expected a valid JSON");
+    assert_eq!(
+        message_json,
+        serde_json::json!({
+            "type": "complete",
+            "id": operation_id
+        })
+    );
+
+    // Send close frame from client
+    socket
+        .send(tungstenite::Message::Close(None))
+        .await
+        .unwrap();
+    // Assert zero connections
+    assert_zero_connections_timeout(connections).await;
+    server_handle.abort();
+}
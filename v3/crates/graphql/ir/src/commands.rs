@@ -67,6 +67,8 @@ pub struct CommandInfo<'s> {
 
     // All the models/commands used in the 'command' operation.
     pub usage_counts: UsagesCounts,
+    /// Caching of the data connector's responses to this command
+    pub response_cache: Option<&'s metadata_resolve::ResponseCachePolicy>,
 }
 
 /// IR for the 'function based command' operations
@@ -178,6 +180,7 @@ pub fn generate_command_info<'n, 's>(
         selection,
         type_container: field.type_container.clone(),
         usage_counts,
+        response_cache: command_source.response_cache.as_ref(),
     })
 }
 
@@ -248,6 +251,7 @@ pub fn generate_command_info_open_dd<'n, 's>(
         selection,
         type_container: field.type_container.clone(),
         usage_counts,
+        response_cache: command_source.response_cache.as_ref(),
     })
 }
 
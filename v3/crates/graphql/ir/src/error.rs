@@ -64,6 +64,9 @@ pub enum Error {
     #[error("field '{field_name:} not found in entity representation")]
     FieldNotFoundInEntityRepresentation { field_name: FieldName },
 
+    #[error("groups can only be ordered by the field {field_name} of their key if it is selected from the key")]
+    GroupsOrderByFieldNotSelected { field_name: FieldName },
+
     #[error("order_by expects a list of input objects with exactly one key-value pair per input object. Please split the input object with multiple key-value pairs into a list of single key-value pair objects.")]
     OrderByObjectShouldExactlyHaveOneKeyValuePair,
 
@@ -93,6 +96,12 @@ pub enum Error {
         type_name: Qualified<CustomTypeName>,
     },
 
+    #[error("grouping the objects of the relationship {relationship_name} is only supported by the OpenDD request pipeline")]
+    RelationshipGroupsNotSupported { relationship_name: RelationshipName },
+
+    #[error("ordering by the remote relationship {relationship_name} is only supported when selecting many rows of a model at the root of a query")]
+    RemoteRelationshipOrderByNotSupported { relationship_name: RelationshipName },
+
     #[error("{0}")]
     PlanError(#[from] plan::PlanError),
 
@@ -41,7 +41,7 @@ pub struct FilterExpression<'s> {
 pub struct QueryFilter<'s> {
     /// Filter derived from `where` clause.
     pub where_clause: Option<Expression<'s>>,
-    /// Relay global ID or unique field comparisons
+    /// Relay global ID, unique field comparisons or relationship value mappings
     pub additional_filter: Option<Expression<'s>>,
 }
 
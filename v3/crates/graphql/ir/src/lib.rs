@@ -30,8 +30,8 @@ pub use model_tracking::get_all_usage_counts_in_query;
 pub use mutation_root::generate_ir as generate_mutation_ir;
 pub use order_by::OrderBy;
 pub use plan::{
-    generate_request_plan, ApolloFederationSelect, Error as PlanError, MutationPlan, NodeQueryPlan,
-    QueryPlan, RequestPlan,
+    generate_request_plan, ApolloFederationSelect, Error as PlanError, MutationPlan,
+    MutationSelect, NodeQueryPlan, QueryPlan, RequestPlan,
 };
 pub use query_root::generate_ir as generate_query_ir;
 pub use relationship::{build_remote_command_relationship, build_remote_relationship};
@@ -39,7 +39,8 @@ pub use root_field::{
     ApolloFederationRootFields, MutationRootField, QueryRootField, SubscriptionRootField,
 };
 pub use selection_set::{
-    generate_selection_set_ir, FieldSelection, NestedSelection, ResultSelectionSet,
+    generate_selection_set_ir, groups_dimension_aliases, FieldSelection, NestedSelection,
+    ResultSelectionSet,
 };
 pub use subscription_root::generate_ir as generate_subscription_ir;
 
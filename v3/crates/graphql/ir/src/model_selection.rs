@@ -54,6 +54,8 @@ pub struct ModelSelection<'s> {
 
     /// Variable arguments to be used for remote joins
     pub variable_arguments: BTreeMap<DataConnectorArgumentName, VariableName>,
+    // Caching of the data connector's responses to queries of this model
+    pub response_cache: Option<&'s metadata_resolve::ResponseCachePolicy>,
 }
 
 struct ModelSelectAggregateArguments<'s> {
@@ -216,6 +218,7 @@ pub fn model_selection_ir<'s>(
         selection: Some(selection),
         aggregate_selection: None,
         variable_arguments: BTreeMap::new(),
+        response_cache: model_source.response_cache.as_ref(),
     })
 }
 
@@ -531,6 +534,7 @@ fn model_aggregate_selection_ir<'s>(
         selection: None,
         aggregate_selection: Some(aggregate_selection),
         variable_arguments: BTreeMap::new(),
+        response_cache: model_source.response_cache.as_ref(),
     })
 }
 
@@ -67,6 +67,29 @@ pub fn build_ndc_order_by<'s>(
     }
 }
 
+/// Whether an order_by argument orders by fields of remote relationships, which the data connector
+/// of the model cannot order by, so that the engine has to sort the rows itself
+pub fn uses_remote_relationships(
+    value: &Value<'_, GDS>,
+    data_connector_link: &metadata_resolve::DataConnectorLink,
+) -> Result<bool, error::Error> {
+    for argument in value.as_list()? {
+        for object_field in argument.as_object()?.values() {
+            if let Annotation::Input(InputAnnotation::Model(
+                ModelInputAnnotation::ModelOrderByRelationshipArgument(
+                    OrderByRelationshipAnnotation { target_source, .. },
+                ),
+            )) = object_field.info.generic
+            {
+                if target_source.model.data_connector.name != data_connector_link.name {
+                    return Ok(true);
+                }
+            }
+        }
+    }
+    Ok(false)
+}
+
 // Build the NDC OrderByElement by traversing the relationships when present
 // For eg: If we have the following order_by query:
 //      Track(order_by: {Album: {Artist: {ArtistId: Asc}, AlbumId: Asc}}, limit: 15)
@@ -223,6 +246,14 @@ pub fn build_ndc_order_by_element<'s>(
                 )
                 .map_err(crate::InternalEngineError::OrderableRelationshipError)?;
 
+                // Remote relationships are sorted by the engine, which plans such root fields as
+                // OpenDD queries, see `uses_remote_relationships`
+                if data_connector_link.name != target_source.model.data_connector.name {
+                    return Err(error::Error::RemoteRelationshipOrderByNotSupported {
+                        relationship_name: relationship_name.clone(),
+                    });
+                }
+
                 let ndc_relationship_name =
                     NdcRelationshipName::new(source_type, relationship_name);
                 relationships.insert(
@@ -60,7 +60,7 @@ pub fn generate_request_plan<'n, 's, 'ir>(
         }
         IR::Mutation(ir) => {
             let mut mutation_plan = MutationPlan {
-                nodes: IndexMap::new(),
+                nodes: Vec::new(),
                 type_names: IndexMap::new(),
             };
             for (alias, field) in ir {
@@ -79,11 +79,18 @@ pub fn generate_request_plan<'n, 's, 'ir>(
                             request_headers,
                             &mut unique_number,
                         )?;
-                        mutation_plan
-                            .nodes
-                            .entry(plan.mutation_execution.data_connector.clone())
-                            .or_default()
-                            .insert(alias.clone(), plan);
+                        // consecutive fields using the same data connector are grouped together
+                        match mutation_plan.nodes.last_mut() {
+                            Some((data_connector, mutation_group))
+                                if *data_connector == plan.mutation_execution.data_connector =>
+                            {
+                                mutation_group.insert(alias.clone(), plan);
+                            }
+                            _ => mutation_plan.nodes.push((
+                                plan.mutation_execution.data_connector.clone(),
+                                IndexMap::from([(alias.clone(), plan)]),
+                            )),
+                        }
                     }
                 };
             }
@@ -293,7 +300,8 @@ fn plan_subscription<'s, 'ir>(
 }
 
 fn reject_remote_joins(tree: QueryExecutionTree) -> Result<QueryExecutionPlan, error::Error> {
-    if !tree.remote_join_executions.is_empty() {
+    // ordering by remote relationships needs remote joins to fetch the fields being ordered by
+    if !tree.remote_join_executions.is_empty() || tree.remote_order_by.is_some() {
         return Err(error::Error::RemoteJoinsAreNotSupportedSubscriptions);
     }
     Ok(tree.query_execution_plan)
@@ -464,6 +472,33 @@ fn plan_query<'n, 's, 'ir>(
                 selection_set,
             }
         }
+        QueryRootField::ModelSelectGroups { ir, selection_set } => {
+            // TODO: expose more specific function in `plan` for just model selections
+            let single_node_execution_plan = plan::query_to_plan(
+                &open_dds::query::Query::ModelGroups(ir.model_selection.clone()),
+                metadata,
+                session,
+                request_headers,
+                unique_number,
+            )?;
+            let execution_tree = match single_node_execution_plan {
+                plan::SingleNodeExecutionPlan::Query(execution_tree) => Ok(execution_tree),
+                plan::SingleNodeExecutionPlan::Mutation(_) => {
+                    // we should use a more specific planning function to avoid
+                    // this as it _should not_ happen
+                    Err(error::Error::PlanExpectedQueryGotMutation)
+                }
+            }?;
+            NodeQueryPlan::NDCQueryExecution {
+                query_execution: NDCQueryExecution {
+                    execution_tree,
+                    execution_span_attribute: "execute_model_select_groups",
+                    field_span_attribute: ir.field_name.to_string(),
+                    process_response_as: ProcessResponseAs::Groups,
+                },
+                selection_set,
+            }
+        }
         QueryRootField::NodeSelect(optional_ir) => match optional_ir {
             Some(ir) => {
                 let execution_tree = match ir.model_selection {
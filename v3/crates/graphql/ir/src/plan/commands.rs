@@ -144,6 +144,13 @@ pub(crate) fn plan_query_execution(
                 query_node,
                 collection: CollectionName::from(ir.function_name.as_str()),
                 arguments: arguments.clone(),
+                response_cache: plan::plan_response_cache(
+                    metadata,
+                    session,
+                    &ir.command_info.data_connector,
+                    ir.command_info.response_cache,
+                    &collection_relationships,
+                ),
                 collection_relationships,
                 variables: None,
                 data_connector: ir.command_info.data_connector.clone(),
@@ -152,6 +159,7 @@ pub(crate) fn plan_query_execution(
                 query_execution_plan,
                 remote_join_executions,
                 remote_predicates,
+                remote_order_by: None,
             })
         }
     }
@@ -135,6 +135,13 @@ pub(crate) fn plan_query_execution(
         query_node: query,
         collection: ir.collection.clone(),
         arguments,
+        response_cache: plan::plan_response_cache(
+            metadata,
+            session,
+            &ir.data_connector,
+            ir.response_cache,
+            &collection_relationships,
+        ),
         collection_relationships,
         variables: None,
         data_connector: ir.data_connector.clone(),
@@ -143,5 +150,6 @@ pub(crate) fn plan_query_execution(
         query_execution_plan,
         remote_join_executions,
         remote_predicates,
+        remote_order_by: None,
     })
 }
@@ -10,7 +10,10 @@ use indexmap::IndexMap;
 use metadata_resolve::data_connectors::NdcVersion;
 use metadata_resolve::{FieldMapping, Metadata};
 use open_dds::data_connector::DataConnectorColumnName;
-use plan::{process_command_relationship_definition, process_model_relationship_definition};
+use plan::{
+    process_command_relationship_definition_with_source_path,
+    process_model_relationship_definition_with_source_path,
+};
 use plan_types::RemoteJoinFieldMapping;
 use plan_types::{
     CommandReturnKind, Field, JoinLocations, JoinNode, Location, LocationKind, NestedArray,
@@ -150,16 +153,17 @@ pub(crate) fn plan_selection_set(
                 relationship_info,
             } => {
                 // collect local model relationship
-                relationships.insert(
-                    name.clone(),
-                    process_model_relationship_definition(relationship_info).map_err(
-                        |plan_error| {
-                            error::Error::Internal(error::InternalError::InternalGeneric {
-                                description: plan_error.to_string(),
-                            })
-                        },
-                    )?,
-                );
+                let (source_field_path, relationship) =
+                    process_model_relationship_definition_with_source_path(
+                        relationship_info,
+                        &metadata.object_types,
+                    )
+                    .map_err(|plan_error| {
+                        error::Error::Internal(error::InternalError::InternalGeneric {
+                            description: plan_error.to_string(),
+                        })
+                    })?;
+                relationships.insert(name.clone(), relationship);
                 let Plan {
                     inner: relationship_query,
                     join_locations: jl,
@@ -179,6 +183,7 @@ pub(crate) fn plan_selection_set(
                     query_node: Box::new(relationship_query),
                     relationship: name.clone(),
                     arguments: BTreeMap::new(),
+                    source_field_path,
                 };
                 if !jl.locations.is_empty() {
                     join_locations.locations.insert(
@@ -197,16 +202,17 @@ pub(crate) fn plan_selection_set(
                 relationship_info,
             } => {
                 // collect local command relationship
-                relationships.insert(
-                    name.clone(),
-                    process_command_relationship_definition(relationship_info).map_err(
-                        |plan_error| {
-                            error::Error::Internal(error::InternalError::InternalGeneric {
-                                description: plan_error.to_string(),
-                            })
-                        },
-                    )?,
-                );
+                let (source_field_path, relationship) =
+                    process_command_relationship_definition_with_source_path(
+                        relationship_info,
+                        &metadata.object_types,
+                    )
+                    .map_err(|plan_error| {
+                        error::Error::Internal(error::InternalError::InternalGeneric {
+                            description: plan_error.to_string(),
+                        })
+                    })?;
+                relationships.insert(name.clone(), relationship);
                 let Plan {
                     inner: relationship_query,
                     join_locations: jl,
@@ -240,6 +246,7 @@ pub(crate) fn plan_selection_set(
                     query_node: Box::new(relationship_query),
                     relationship: name.clone(),
                     arguments: relationship_arguments,
+                    source_field_path,
                 };
 
                 if !jl.locations.is_empty() {
@@ -258,7 +265,7 @@ pub(crate) fn plan_selection_set(
                 relationship_info,
             } => {
                 let mut join_mapping = BTreeMap::new();
-                for ((src_field_alias, src_field_type, src_field), target_field) in
+                for ((src_field_access, src_field_type, src_field, src_field_path), target_field) in
                     &relationship_info.join_mapping
                 {
                     let ndc_field_alias = process_remote_relationship_field_mapping(
@@ -267,9 +274,10 @@ pub(crate) fn plan_selection_set(
                         &mut fields,
                     );
                     join_mapping.insert(
-                        src_field_alias.clone(),
+                        src_field_access.clone(),
                         RemoteJoinFieldMapping {
                             source_field_alias: ndc_field_alias.clone(),
+                            source_field_path: src_field_path.clone(),
                             source_field_type: src_field_type.clone(),
                             target_field: target_field.clone(),
                         },
@@ -280,6 +288,7 @@ pub(crate) fn plan_selection_set(
                     query_execution_plan: query_execution,
                     remote_join_executions: sub_join_locations,
                     remote_predicates: model_remote_predicates,
+                    remote_order_by: _,
                 } = model_selection::plan_query_execution(
                     ir,
                     metadata,
@@ -315,7 +324,7 @@ pub(crate) fn plan_selection_set(
             } => {
                 let mut join_mapping = BTreeMap::new();
 
-                for ((src_field_alias, src_field_type, src_field), target_field) in
+                for ((src_field_access, src_field_type, src_field, src_field_path), target_field) in
                     &relationship_info.join_mapping
                 {
                     let ndc_field_alias = process_remote_relationship_field_mapping(
@@ -324,9 +333,10 @@ pub(crate) fn plan_selection_set(
                         &mut fields,
                     );
                     join_mapping.insert(
-                        src_field_alias.clone(),
+                        src_field_access.clone(),
                         RemoteJoinFieldMapping {
                             source_field_alias: ndc_field_alias.clone(),
+                            source_field_path: src_field_path.clone(),
                             source_field_type: src_field_type.clone(),
                             target_field: TargetField::Argument(target_field.clone()),
                         },
@@ -337,6 +347,7 @@ pub(crate) fn plan_selection_set(
                     query_execution_plan: ndc_ir,
                     remote_join_executions: sub_join_locations,
                     remote_predicates: command_remote_predicates,
+                    remote_order_by: _,
                 } = commands::plan_query_execution(
                     ir,
                     metadata,
@@ -20,15 +20,16 @@ pub type QueryPlan<'n, 's, 'ir> = IndexMap<ast::Alias, NodeQueryPlan<'n, 's, 'ir
 /// Unlike a query, the root nodes of a mutation aren't necessarily independent. Specifically, the
 /// GraphQL specification says that each root mutation must be executed sequentially. Moreover, if
 /// we want to, say, insert a parent _and_ children in one query, we want the ability to make
-/// transactional requests. In a mutation plan, we group nodes by connector, allowing us to issue
-/// transactional commands to connectors whose capabilities allow for transactional mutations.
-/// Otherwise, we can just send them one-by-one (though still sequentially).
+/// transactional requests. In a mutation plan, we group consecutive nodes that use the same
+/// connector, allowing us to issue transactional commands to connectors whose capabilities allow
+/// for transactional mutations, while still executing the root fields in the order they were
+/// requested. Otherwise, we can just send them one-by-one (though still sequentially).
 #[derive(Debug, PartialEq)]
 pub struct MutationPlan<'n, 's> {
-    pub nodes: IndexMap<
+    pub nodes: Vec<(
         Arc<metadata_resolve::DataConnectorLink>,
         IndexMap<ast::Alias, MutationSelect<'n, 's>>,
-    >,
+    )>,
     pub type_names: IndexMap<ast::Alias, ast::TypeName>,
 }
 
@@ -25,6 +25,7 @@ use graphql_schema::{Annotation, NodeFieldTypeNameMapping, OutputAnnotation, Roo
 pub mod apollo_federation;
 pub mod node_field;
 pub mod select_aggregate;
+pub mod select_groups;
 pub mod select_many;
 pub mod select_one;
 
@@ -281,6 +282,10 @@ pub fn generate_model_rootfield_ir<'n, 's>(
                 model_name,
             )?,
         },
+        RootFieldKind::SelectGroups => root_field::QueryRootField::ModelSelectGroups {
+            selection_set: &field.selection_set,
+            ir: select_groups::select_groups_generate_ir(field, field_call, source, model_name)?,
+        },
     };
     Ok(ir)
 }
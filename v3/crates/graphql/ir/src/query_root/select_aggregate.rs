@@ -126,6 +126,22 @@ pub fn aggregate_query(
     model_name: &Qualified<open_dds::models::ModelName>,
     model_source: &metadata_resolve::ModelSource,
     usage_counts: &mut UsagesCounts,
+) -> Result<AggregateQuery, error::Error> {
+    aggregate_query_from_arguments(
+        field_call.arguments.values(),
+        model_name,
+        model_source,
+        usage_counts,
+    )
+}
+
+/// Builds an aggregate query from the arguments of an aggregate field, which may be a subset
+/// of the arguments of a field that takes further arguments of its own
+pub(crate) fn aggregate_query_from_arguments<'a, 's: 'a>(
+    arguments: impl IntoIterator<Item = &'a normalized_ast::InputField<'s, GDS>>,
+    model_name: &Qualified<open_dds::models::ModelName>,
+    model_source: &metadata_resolve::ModelSource,
+    usage_counts: &mut UsagesCounts,
 ) -> Result<AggregateQuery, error::Error> {
     let mut limit = None;
     let mut offset = None;
@@ -136,7 +152,7 @@ pub fn aggregate_query(
     // Add the name of the root model
     count_model(model_name, usage_counts);
 
-    for field_call_argument in field_call.arguments.values() {
+    for field_call_argument in arguments {
         match field_call_argument.info.generic {
             // Model arguments
             Annotation::Input(InputAnnotation::Model(
@@ -0,0 +1,382 @@
+//! IR for 'select_groups' operation
+//!
+//! A 'select_groups' operation groups the rows of a model and fetches a set of aggregates
+//! over the rows of each group
+
+use graphql_schema::{
+    Annotation, InputAnnotation, LogicalOperatorField, ModelInputAnnotation, ModelOrderByDirection,
+    GDS,
+};
+use indexmap::IndexMap;
+use lang_graphql::ast::common as ast;
+use lang_graphql::normalized_ast;
+use metadata_resolve::Qualified;
+use open_dds::types::FieldName;
+use plan_types::UsagesCounts;
+use serde::Serialize;
+
+use super::select_aggregate::{aggregate_query_from_arguments, AggregateQuery};
+use crate::error;
+use crate::selection_set;
+
+/// IR for the 'select_groups' operation on a model
+#[derive(Debug, Serialize)]
+pub struct ModelSelectGroups<'n> {
+    // The name of the field as published in the schema
+    pub field_name: ast::Name,
+
+    // Groups are only planned via OpenDD, whichever request pipeline is in use
+    pub model_selection: open_dds::query::ModelGroupsSelection,
+
+    // The Graphql output type of the operation
+    pub(crate) type_container: &'n ast::TypeContainer<ast::TypeName>,
+
+    // All the models/commands used in this operation. This includes the models/commands
+    // used via relationships. And in future, the models/commands used in the filter clause
+    pub(crate) usage_counts: UsagesCounts,
+}
+
+/// Generates the IR for a 'select_groups' operation
+pub(crate) fn select_groups_generate_ir<'n, 's>(
+    field: &'n normalized_ast::Field<'s, GDS>,
+    field_call: &'n normalized_ast::FieldCall<'s, GDS>,
+    model_source: &'s metadata_resolve::ModelSource,
+    model_name: &'s Qualified<open_dds::models::ModelName>,
+) -> Result<ModelSelectGroups<'n>, error::Error> {
+    let mut usage_counts = UsagesCounts::new();
+
+    let GroupsQuery {
+        aggregate_query:
+            AggregateQuery {
+                limit,
+                offset,
+                where_clause,
+                model_arguments,
+                order_by: objects_order_by,
+            },
+        groups_filter,
+        groups_order_by,
+        groups_limit,
+        groups_offset,
+    } = groups_query(field_call, model_name, model_source, &mut usage_counts)?;
+
+    let (dimensions, selection) =
+        selection_set::generate_groups_selection_set_open_dd_ir(&field.selection_set)?;
+    let order_by = resolve_groups_order_by(groups_order_by, &dimensions)?;
+
+    let target = open_dds::query::ModelTarget {
+        subgraph: model_name.subgraph.clone(),
+        model_name: model_name.name.clone(),
+        offset,
+        order_by: objects_order_by,
+        arguments: model_arguments.unwrap_or_default(), // Permission presets are handled during planning
+        filter: where_clause,
+        limit,
+    };
+
+    Ok(ModelSelectGroups {
+        field_name: field_call.name.clone(),
+        model_selection: open_dds::query::ModelGroupsSelection {
+            target,
+            selection,
+            dimensions: open_dds::query::ModelDimensions {
+                dimensions,
+                filter: groups_filter,
+                order_by,
+                limit: groups_limit,
+                offset: groups_offset,
+            },
+        },
+        type_container: &field.type_container,
+        usage_counts,
+    })
+}
+
+pub struct GroupsQuery {
+    /// The query over the rows that are grouped
+    pub aggregate_query: AggregateQuery,
+    pub groups_filter: Option<open_dds::query::GroupBooleanExpression>,
+    pub groups_order_by: Vec<GroupsOrderByElement>,
+    pub groups_limit: Option<usize>,
+    pub groups_offset: Option<usize>,
+}
+
+/// An ordering of groups, which refers to the fields of the group key by their name until the
+/// dimensions selected for them are known
+pub struct GroupsOrderByElement {
+    pub target: GroupsOrderByTarget,
+    pub direction: open_dds::models::OrderByDirection,
+}
+
+pub enum GroupsOrderByTarget {
+    KeyField(FieldName),
+    Count,
+}
+
+/// Builds a groups query from the arguments of a groups field. The filter, ordering, limit and
+/// offset of the groups are arguments of the field itself, while the rest of the arguments are
+/// those of an aggregate field and select the rows that are grouped.
+pub fn groups_query(
+    field_call: &normalized_ast::FieldCall<'_, GDS>,
+    model_name: &Qualified<open_dds::models::ModelName>,
+    model_source: &metadata_resolve::ModelSource,
+    usage_counts: &mut UsagesCounts,
+) -> Result<GroupsQuery, error::Error> {
+    let mut groups_filter = None;
+    let mut groups_order_by = Vec::new();
+    let mut groups_limit = None;
+    let mut groups_offset = None;
+    let mut aggregate_arguments = Vec::new();
+
+    for field_call_argument in field_call.arguments.values() {
+        match field_call_argument.info.generic {
+            // Filter of the groups
+            Annotation::Input(InputAnnotation::Model(
+                ModelInputAnnotation::ModelGroupsFilterArgument,
+            )) => {
+                groups_filter = groups_filter_expression(&field_call_argument.value)?;
+            }
+            // Ordering of the groups
+            Annotation::Input(InputAnnotation::Model(
+                ModelInputAnnotation::ModelGroupsOrderByArgument,
+            )) => {
+                groups_order_by = groups_order_by_elements(&field_call_argument.value)?;
+            }
+            // Limit of the groups
+            Annotation::Input(InputAnnotation::Model(ModelInputAnnotation::ModelLimitArgument)) => {
+                groups_limit = field_call_argument
+                    .value
+                    .as_nullable(normalized_ast::Value::as_int_u32)
+                    .map_err(error::Error::map_unexpected_value_to_external_error)?;
+            }
+            // Offset of the groups
+            Annotation::Input(InputAnnotation::Model(
+                ModelInputAnnotation::ModelOffsetArgument,
+            )) => {
+                groups_offset = field_call_argument
+                    .value
+                    .as_nullable(normalized_ast::Value::as_int_u32)
+                    .map_err(error::Error::map_unexpected_value_to_external_error)?;
+            }
+            _ => aggregate_arguments.push(field_call_argument),
+        }
+    }
+
+    let aggregate_query = aggregate_query_from_arguments(
+        aggregate_arguments,
+        model_name,
+        model_source,
+        usage_counts,
+    )?;
+
+    let groups_limit: Option<usize> = groups_limit
+        .map(|limit| {
+            usize::try_from(limit).map_err(|_| error::Error::InvalidLimitValue { value: limit })
+        })
+        .transpose()?;
+
+    let groups_offset: Option<usize> = groups_offset
+        .map(|offset| {
+            usize::try_from(offset).map_err(|_| error::Error::InvalidOffsetValue { value: offset })
+        })
+        .transpose()?;
+
+    Ok(GroupsQuery {
+        aggregate_query,
+        groups_filter,
+        groups_order_by,
+        groups_limit,
+        groups_offset,
+    })
+}
+
+/// The number of objects in a group, which groups are filtered and ordered by
+fn count_aggregate() -> open_dds::query::Aggregate {
+    open_dds::query::Aggregate {
+        function: open_dds::query::AggregationFunction::Count {},
+        operand: None,
+    }
+}
+
+/// Builds the boolean expression that groups are filtered with. The fields of an object are
+/// combined with a conjunction, and null values are ignored.
+fn groups_filter_expression(
+    value: &normalized_ast::Value<'_, GDS>,
+) -> Result<Option<open_dds::query::GroupBooleanExpression>, error::Error> {
+    let Some(object) = value
+        .as_nullable(normalized_ast::Value::as_object)
+        .map_err(error::Error::map_unexpected_value_to_external_error)?
+    else {
+        return Ok(None);
+    };
+
+    let mut expressions = Vec::new();
+    for field in object.values() {
+        match field.info.generic {
+            Annotation::Input(InputAnnotation::Model(
+                ModelInputAnnotation::ModelGroupsFilterLogicalOperator(operator),
+            )) => match operator {
+                LogicalOperatorField::AndOp | LogicalOperatorField::OrOp => {
+                    let Some(values) = field
+                        .value
+                        .as_nullable(normalized_ast::Value::as_list)
+                        .map_err(error::Error::map_unexpected_value_to_external_error)?
+                    else {
+                        continue;
+                    };
+                    let operands = values
+                        .iter()
+                        .map(groups_filter_expression)
+                        .filter_map(Result::transpose)
+                        .collect::<Result<Vec<_>, _>>()?;
+                    expressions.push(match operator {
+                        LogicalOperatorField::AndOp => {
+                            open_dds::query::GroupBooleanExpression::And(operands)
+                        }
+                        _ => open_dds::query::GroupBooleanExpression::Or(operands),
+                    });
+                }
+                LogicalOperatorField::NotOp => {
+                    if let Some(operand) = groups_filter_expression(&field.value)? {
+                        expressions.push(open_dds::query::GroupBooleanExpression::Not(Box::new(
+                            operand,
+                        )));
+                    }
+                }
+            },
+            Annotation::Input(InputAnnotation::Model(
+                ModelInputAnnotation::ModelGroupsFilterCount,
+            )) => {
+                let Some(comparisons) =
+                    field
+                        .value
+                        .as_nullable(normalized_ast::Value::as_object)
+                        .map_err(error::Error::map_unexpected_value_to_external_error)?
+                else {
+                    continue;
+                };
+                for comparison in comparisons.values() {
+                    let operator = match comparison.info.generic {
+                        Annotation::Input(InputAnnotation::Model(
+                            ModelInputAnnotation::ModelGroupsCountComparisonOperator { operator },
+                        )) => operator,
+                        annotation => Err(error::InternalEngineError::UnexpectedAnnotation {
+                            annotation: annotation.clone(),
+                        })?,
+                    };
+                    if comparison.value.is_null() {
+                        continue;
+                    }
+                    expressions.push(open_dds::query::GroupBooleanExpression::Comparison {
+                        operand: count_aggregate(),
+                        operator: operator.clone(),
+                        argument: Box::new(open_dds::query::Value::Literal(
+                            comparison.value.as_json(),
+                        )),
+                    });
+                }
+            }
+            annotation => Err(error::InternalEngineError::UnexpectedAnnotation {
+                annotation: annotation.clone(),
+            })?,
+        }
+    }
+
+    Ok(Some(if expressions.len() == 1 {
+        expressions.remove(0)
+    } else {
+        open_dds::query::GroupBooleanExpression::And(expressions)
+    }))
+}
+
+/// Builds the ordering of groups, which is a list of objects with one field each as the order
+/// of the fields of an object is undefined in GraphQL
+fn groups_order_by_elements(
+    value: &normalized_ast::Value<'_, GDS>,
+) -> Result<Vec<GroupsOrderByElement>, error::Error> {
+    let Some(values) = value
+        .as_nullable(normalized_ast::Value::as_list)
+        .map_err(error::Error::map_unexpected_value_to_external_error)?
+    else {
+        return Ok(vec![]);
+    };
+
+    let mut elements = Vec::new();
+    for value in values {
+        let object = value.as_object()?;
+        if object.len() > 1 {
+            return Err(error::Error::OrderByObjectShouldExactlyHaveOneKeyValuePair);
+        }
+        for field in object.values() {
+            let target = match field.info.generic {
+                Annotation::Input(InputAnnotation::Model(
+                    ModelInputAnnotation::ModelGroupsOrderByKeyField { field_name, .. },
+                )) => GroupsOrderByTarget::KeyField(field_name.clone()),
+                Annotation::Input(InputAnnotation::Model(
+                    ModelInputAnnotation::ModelGroupsOrderByCount,
+                )) => GroupsOrderByTarget::Count,
+                annotation => Err(error::InternalEngineError::UnexpectedAnnotation {
+                    annotation: annotation.clone(),
+                })?,
+            };
+            let Some(direction) = field
+                .value
+                .as_nullable(normalized_ast::Value::as_enum)
+                .map_err(error::Error::map_unexpected_value_to_external_error)?
+            else {
+                continue;
+            };
+            let direction = match direction.info.generic {
+                Annotation::Input(InputAnnotation::Model(
+                    ModelInputAnnotation::ModelOrderByDirection { direction },
+                )) => match direction {
+                    ModelOrderByDirection::Asc => open_dds::models::OrderByDirection::Asc,
+                    ModelOrderByDirection::Desc => open_dds::models::OrderByDirection::Desc,
+                },
+                annotation => Err(error::InternalEngineError::UnexpectedAnnotation {
+                    annotation: annotation.clone(),
+                })?,
+            };
+            elements.push(GroupsOrderByElement { target, direction });
+        }
+    }
+    Ok(elements)
+}
+
+/// Resolves the fields of the group key that groups are ordered by to the dimensions selected for
+/// them, so groups can only be ordered by the fields selected from their key
+pub fn resolve_groups_order_by(
+    order_by: Vec<GroupsOrderByElement>,
+    dimensions: &IndexMap<open_dds::query::Name, open_dds::query::Dimension>,
+) -> Result<Vec<open_dds::query::GroupOrderByElement>, error::Error> {
+    order_by
+        .into_iter()
+        .map(|GroupsOrderByElement { target, direction }| {
+            let operand = match target {
+                GroupsOrderByTarget::KeyField(field_name) => {
+                    let dimension_name = dimensions
+                        .iter()
+                        .find_map(|(dimension_name, dimension)| {
+                            let open_dds::query::Dimension::Field {
+                                column: open_dds::query::Operand::Field(operand),
+                                extraction: None,
+                            } = dimension
+                            else {
+                                return None;
+                            };
+                            (operand.target.field_name == field_name && operand.nested.is_none())
+                                .then_some(dimension_name)
+                        })
+                        .ok_or_else(|| error::Error::GroupsOrderByFieldNotSelected {
+                            field_name: field_name.clone(),
+                        })?;
+                    open_dds::query::GroupOrderByOperand::Dimension(dimension_name.clone())
+                }
+                GroupsOrderByTarget::Count => {
+                    open_dds::query::GroupOrderByOperand::Aggregate(count_aggregate())
+                }
+            };
+            Ok(open_dds::query::GroupOrderByElement { operand, direction })
+        })
+        .collect()
+}
@@ -16,7 +16,7 @@ use crate::arguments;
 use crate::error;
 use crate::filter;
 use crate::model_selection;
-use crate::order_by::{build_ndc_order_by, build_order_by_open_dd_ir};
+use crate::order_by::{build_ndc_order_by, build_order_by_open_dd_ir, uses_remote_relationships};
 use crate::permissions;
 use crate::GraphqlRequestPipeline;
 use graphql_schema::GDS;
@@ -82,6 +82,7 @@ pub fn select_many_generate_ir<'n, 's>(
     // For opendd execution pipeline
     let mut model_arguments_input = None;
     let mut order_by_input = None;
+    let mut uses_remote_order_by = false;
 
     // Add the name of the root model
     let mut usage_counts = UsagesCounts::new();
@@ -128,17 +129,26 @@ pub fn select_many_generate_ir<'n, 's>(
                     })?,
                 },
                 ModelInputAnnotation::ModelOrderByExpression => {
+                    // ordering by remote relationships is planned as an OpenDD query
+                    uses_remote_order_by = argument
+                        .value
+                        .as_nullable(|v| {
+                            uses_remote_relationships(v, &model_source.data_connector)
+                        })?
+                        .unwrap_or(false);
                     // order by is optional
-                    order_by = argument.value.as_nullable(|v| {
-                        build_ndc_order_by(
-                            v,
-                            &session.variables,
-                            &mut usage_counts,
-                            &model_source.type_mappings,
-                            object_types,
-                            &model_source.data_connector,
-                        )
-                    })?;
+                    if !uses_remote_order_by {
+                        order_by = argument.value.as_nullable(|v| {
+                            build_ndc_order_by(
+                                v,
+                                &session.variables,
+                                &mut usage_counts,
+                                &model_source.type_mappings,
+                                object_types,
+                                &model_source.data_connector,
+                            )
+                        })?;
+                    }
                     // For opendd execution pipeline
                     // Assign the order_by_input only if it is not null
                     if !argument.value.is_null() {
@@ -179,6 +189,13 @@ pub fn select_many_generate_ir<'n, 's>(
         &mut usage_counts,
     )?;
 
+    // the engine orders rows by remote relationships when executing OpenDD query plans
+    let request_pipeline = if uses_remote_order_by {
+        GraphqlRequestPipeline::OpenDd
+    } else {
+        request_pipeline
+    };
+
     let model_selection = match request_pipeline {
         GraphqlRequestPipeline::OpenDd => {
             let where_clause = match where_input {
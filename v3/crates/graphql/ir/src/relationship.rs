@@ -4,16 +4,17 @@ use lang_graphql::normalized_ast::{self, Field};
 use open_dds::{
     arguments::ArgumentName,
     commands::FunctionName,
+    data_connector::DataConnectorColumnName,
     models::ModelName,
     relationships::{RelationshipName, RelationshipType},
-    types::{CustomTypeName, DataConnectorArgumentName, FieldName},
+    types::{CustomTypeName, DataConnectorArgumentName},
 };
 use std::collections::BTreeMap;
 
 use serde::Serialize;
 
 use super::{
-    commands::generate_function_based_command,
+    commands::{generate_function_based_command, CommandSelection, FunctionBasedCommand},
     filter,
     model_selection::{self, model_selection_ir},
     order_by::build_ndc_order_by,
@@ -24,10 +25,12 @@ use crate::order_by;
 use crate::{
     error,
     query_root::select_aggregate::{aggregate_query, AggregateQuery},
+    query_root::select_groups::{groups_query, resolve_groups_order_by, GroupsQuery},
 };
 use graphql_schema::{
     Annotation, BooleanExpressionAnnotation, CommandRelationshipAnnotation, InputAnnotation,
-    ModelAggregateRelationshipAnnotation, ModelInputAnnotation, ModelRelationshipAnnotation, GDS,
+    ModelAggregateRelationshipAnnotation, ModelGroupsRelationshipAnnotation, ModelInputAnnotation,
+    ModelRelationshipAnnotation, GDS,
 };
 use metadata_resolve::{
     self, CommandSource, ObjectTypeWithRelationships, Qualified, QualifiedTypeReference,
@@ -63,10 +66,13 @@ pub struct RemoteCommandRelationshipInfo<'s> {
         BTreeMap<Qualified<CustomTypeName>, RemoteJoinObjectFieldMapping>,
 }
 
+/// The source field of a join mapping, the type of the value joined on, the field mapping of the
+/// root source field and the NDC fields descended into through its nested objects
 pub type SourceField = (
-    FieldName,
+    metadata_resolve::RelationshipFieldAccess,
     QualifiedTypeReference,
     metadata_resolve::FieldMapping,
+    Vec<DataConnectorColumnName>,
 );
 
 pub fn generate_model_relationship_open_dd_ir<'s>(
@@ -354,7 +360,7 @@ pub fn generate_model_relationship_ir<'s>(
         additional_filter: None,
     };
 
-    let selection_ir = model_selection_ir(
+    let mut selection_ir = model_selection_ir(
         &field.selection_set,
         &relationship_annotation.target_type,
         target_source,
@@ -372,6 +378,17 @@ pub fn generate_model_relationship_ir<'s>(
         usage_counts,
     )?;
 
+    apply_model_relationship_value_mappings(
+        &mut selection_ir,
+        &relationship_annotation.relationship_name,
+        &relationship_annotation.source_type,
+        &relationship_annotation.target_model_name,
+        target_source,
+        &relationship_annotation.value_mappings,
+        object_types,
+        &session.variables,
+    )?;
+
     match metadata_resolve::field_selection_relationship_execution_category(
         relationship_field_nestedness,
         source_data_connector,
@@ -467,6 +484,84 @@ pub fn generate_model_aggregate_relationship_open_dd_ir<'s>(
     Ok(open_dds::query::RelationshipAggregateSelection { selection, target })
 }
 
+pub fn generate_model_groups_relationship_open_dd_ir<'s>(
+    field: &Field<'s, GDS>,
+    relationship_annotation: &'s ModelGroupsRelationshipAnnotation,
+    models: &'s IndexMap<
+        Qualified<open_dds::models::ModelName>,
+        metadata_resolve::ModelWithPermissions,
+    >,
+    usage_counts: &mut UsagesCounts,
+) -> Result<open_dds::query::RelationshipGroupsSelection, error::Error> {
+    // Add the target model being used in the usage counts
+    count_model(&relationship_annotation.target_model_name, usage_counts);
+
+    let (dimensions, selection) =
+        selection_set::generate_groups_selection_set_open_dd_ir(&field.selection_set)?;
+
+    let field_call = field.field_call()?;
+
+    let model_source = models
+        .get(&relationship_annotation.target_model_name)
+        .ok_or_else(
+            || error::InternalDeveloperError::TargetModelNotFoundForRelationship {
+                model_name: relationship_annotation.target_model_name.clone(),
+                relationship_name: relationship_annotation.relationship_name.clone(),
+            },
+        )?
+        .model
+        .source
+        .as_ref()
+        .ok_or_else(|| error::InternalEngineError::InternalGeneric {
+            description: format!(
+                "Model {} has no source",
+                relationship_annotation.target_model_name
+            ),
+        })?;
+
+    let GroupsQuery {
+        aggregate_query:
+            AggregateQuery {
+                limit,
+                offset,
+                where_clause,
+                model_arguments,
+                order_by: objects_order_by,
+            },
+        groups_filter,
+        groups_order_by,
+        groups_limit,
+        groups_offset,
+    } = groups_query(
+        field_call,
+        &relationship_annotation.target_model_name,
+        model_source,
+        usage_counts,
+    )?;
+    let order_by = resolve_groups_order_by(groups_order_by, &dimensions)?;
+
+    let target = open_dds::query::RelationshipTarget {
+        relationship_name: relationship_annotation.relationship_name.clone(),
+        arguments: model_arguments.unwrap_or_default(),
+        filter: where_clause,
+        limit,
+        offset,
+        order_by: objects_order_by,
+    };
+
+    Ok(open_dds::query::RelationshipGroupsSelection {
+        target,
+        selection,
+        dimensions: open_dds::query::ModelDimensions {
+            dimensions,
+            filter: groups_filter,
+            order_by,
+            limit: groups_limit,
+            offset: groups_offset,
+        },
+    })
+}
+
 pub fn generate_model_aggregate_relationship_ir<'s>(
     field: &Field<'s, GDS>,
     relationship_annotation: &'s ModelAggregateRelationshipAnnotation,
@@ -516,7 +611,7 @@ pub fn generate_model_aggregate_relationship_ir<'s>(
             None => error::Error::from(normalized_ast::Error::NoTypenameFound),
         })?;
 
-    let selection_ir = model_selection::generate_aggregate_model_selection_ir(
+    let mut selection_ir = model_selection::generate_aggregate_model_selection_ir(
         field,
         field_call,
         &relationship_annotation.target_type,
@@ -529,6 +624,17 @@ pub fn generate_model_aggregate_relationship_ir<'s>(
         usage_counts,
     )?;
 
+    apply_model_relationship_value_mappings(
+        &mut selection_ir,
+        &relationship_annotation.relationship_name,
+        &relationship_annotation.source_type,
+        &relationship_annotation.target_model_name,
+        target_source,
+        &relationship_annotation.value_mappings,
+        object_types,
+        &session.variables,
+    )?;
+
     match metadata_resolve::field_selection_relationship_execution_category(
         relationship_field_nestedness,
         source_data_connector,
@@ -766,7 +872,7 @@ pub fn build_local_command_relationship<'s>(
     request_headers: &reqwest::header::HeaderMap,
     usage_counts: &mut UsagesCounts,
 ) -> Result<FieldSelection<'s>, error::Error> {
-    let relationships_ir = generate_function_based_command(
+    let mut relationships_ir = generate_function_based_command(
         &annotation.command_name,
         target_function_name,
         field,
@@ -783,6 +889,14 @@ pub fn build_local_command_relationship<'s>(
         usage_counts,
     )?;
 
+    apply_command_relationship_value_mappings(
+        &mut relationships_ir,
+        annotation,
+        target_source,
+        object_types,
+        &session.variables,
+    )?;
+
     let rel_info = LocalCommandRelationshipInfo {
         relationship_name: &annotation.relationship_name,
         source_type: &annotation.source_type,
@@ -845,8 +959,18 @@ pub fn build_remote_relationship<'s>(
             })?
             .field_type;
 
+        let (source_field_path, source_field_type) =
+            plan::get_relationship_source_nested_field_path(
+                source_field,
+                source_field_type,
+                relationship_name,
+                source_type_mappings,
+                object_types,
+            )
+            .map_err(|err| error::Error::PlanError(plan::PlanError::Relationship(err)))?;
+
         collect_remote_join_object_type_field_mappings(
-            source_field_type,
+            &source_field_type,
             &mut object_type_field_mappings,
             relationship_name,
             source_type_mappings,
@@ -866,9 +990,10 @@ pub fn build_remote_relationship<'s>(
         })?;
 
         let source_field = (
-            source_field.field_name.clone(),
+            source_field.clone(),
             source_field_type.clone(),
             source_column,
+            source_field_path,
         );
         match target {
             metadata_resolve::RelationshipModelMappingTarget::ModelField(
@@ -894,7 +1019,7 @@ pub fn build_remote_relationship<'s>(
                 let comparison_exp = LocalFieldComparison::BinaryComparison {
                     column: ComparisonTarget::Column {
                         name: target_column.column.clone(),
-                        field_path: vec![],
+                        field_path: target_column.field_path.clone(),
                     },
                     operator: target_column.equal_operator.clone(),
                     value: ComparisonValue::Variable {
@@ -986,8 +1111,18 @@ pub fn build_remote_command_relationship<'n, 's>(
             })?
             .field_type;
 
+        let (source_field_path, source_field_type) =
+            plan::get_relationship_source_nested_field_path(
+                source_field,
+                source_field_type,
+                &annotation.relationship_name,
+                type_mappings,
+                object_types,
+            )
+            .map_err(|err| error::Error::PlanError(plan::PlanError::Relationship(err)))?;
+
         collect_remote_join_object_type_field_mappings(
-            source_field_type,
+            &source_field_type,
             &mut object_type_field_mappings,
             &annotation.relationship_name,
             type_mappings,
@@ -1007,9 +1142,10 @@ pub fn build_remote_command_relationship<'n, 's>(
         })?;
 
         let source_field = (
-            source_field.field_name.clone(),
+            source_field.clone(),
             source_field_type.clone(),
             source_column,
+            source_field_path,
         );
         join_mapping.push((source_field, target_argument_name.clone()));
     }
@@ -1030,6 +1166,14 @@ pub fn build_remote_command_relationship<'n, 's>(
         usage_counts,
     )?;
 
+    apply_command_relationship_value_mappings(
+        &mut remote_relationships_ir,
+        annotation,
+        target_source,
+        object_types,
+        &session.variables,
+    )?;
+
     // Add the arguments on which the join is done to the command arguments
     let mut variable_arguments = BTreeMap::new();
     for (_source, target_argument_name) in &join_mapping {
@@ -1058,3 +1202,67 @@ pub fn build_remote_command_relationship<'n, 's>(
         relationship_info: rel_info,
     })
 }
+
+/// Constrains the target model selection of a relationship by the values that the
+/// relationship's mappings bind to the target's arguments and fields
+fn apply_model_relationship_value_mappings(
+    selection_ir: &mut model_selection::ModelSelection<'_>,
+    relationship_name: &RelationshipName,
+    source_type: &Qualified<CustomTypeName>,
+    target_model_name: &Qualified<ModelName>,
+    target_source: &metadata_resolve::ModelSource,
+    value_mappings: &[metadata_resolve::RelationshipModelValueMapping],
+    object_types: &BTreeMap<Qualified<CustomTypeName>, ObjectTypeWithRelationships>,
+    session_variables: &SessionVariables,
+) -> Result<(), error::Error> {
+    let plan::ModelRelationshipValueConstraints {
+        arguments,
+        predicate,
+    } = plan::process_model_relationship_value_mappings(
+        relationship_name,
+        source_type,
+        target_model_name,
+        target_source,
+        value_mappings,
+        object_types,
+        session_variables,
+    )?;
+
+    selection_ir.arguments.extend(arguments);
+
+    if let Some(predicate) = predicate {
+        let query_filter = &mut selection_ir.filter_clause.query_filter;
+        query_filter.additional_filter = Some(match query_filter.additional_filter.take() {
+            Some(additional_filter) => Expression::mk_and(vec![additional_filter, predicate]),
+            None => predicate,
+        });
+    }
+
+    Ok(())
+}
+
+/// Binds the arguments of the target command of a relationship to the values that the
+/// relationship's mappings bind to them
+fn apply_command_relationship_value_mappings(
+    command_ir: &mut FunctionBasedCommand<'_>,
+    annotation: &CommandRelationshipAnnotation,
+    target_source: &CommandSource,
+    object_types: &BTreeMap<Qualified<CustomTypeName>, ObjectTypeWithRelationships>,
+    session_variables: &SessionVariables,
+) -> Result<(), error::Error> {
+    let value_arguments = plan::process_command_relationship_value_mappings(
+        &annotation.relationship_name,
+        &annotation.source_type,
+        &annotation.command_name,
+        target_source,
+        &annotation.value_mappings,
+        object_types,
+        session_variables,
+    )?;
+
+    if let CommandSelection::Ir { arguments, .. } = &mut command_ir.command_info.selection {
+        arguments.extend(value_arguments);
+    }
+
+    Ok(())
+}
@@ -6,7 +6,9 @@ use serde::Serialize;
 
 use super::{
     commands,
-    query_root::{apollo_federation, node_field, select_aggregate, select_many, select_one},
+    query_root::{
+        apollo_federation, node_field, select_aggregate, select_groups, select_many, select_one,
+    },
 };
 use graphql_schema::GDS;
 
@@ -45,6 +47,11 @@ pub enum QueryRootField<'n, 's> {
         selection_set: &'n gql::normalized_ast::SelectionSet<'s, GDS>,
         ir: select_aggregate::ModelSelectAggregate<'n, 's>,
     },
+    // Operation that selects aggregates over groups of rows from a model
+    ModelSelectGroups {
+        selection_set: &'n gql::normalized_ast::SelectionSet<'s, GDS>,
+        ir: select_groups::ModelSelectGroups<'n>,
+    },
     // Operation that selects a single row from the model corresponding
     // to the Global Id input.
     NodeSelect(Option<node_field::NodeSelect<'n, 's>>),
@@ -17,7 +17,8 @@ use crate::aggregates::mk_alias_from_graphql_field_path;
 use crate::error;
 use crate::global_id;
 use graphql_schema::{
-    AggregateOutputAnnotation, AggregationFunctionAnnotation, InputAnnotation, TypeKind,
+    AggregateOutputAnnotation, AggregationFunctionAnnotation, GroupsOutputAnnotation,
+    InputAnnotation, ModelInputAnnotation, TypeKind,
 };
 use graphql_schema::{Annotation, OutputAnnotation, RootFieldAnnotation, GDS};
 use metadata_resolve::{ObjectTypeWithRelationships, Qualified};
@@ -485,6 +486,19 @@ pub fn generate_selection_set_open_dd_ir(
                             ),
                         );
                     }
+                    OutputAnnotation::RelationshipToModelGroups(relationship_annotation) => {
+                        fields.insert(
+                            make_field_alias(field.alias.0.as_str())?,
+                            open_dds::query::ObjectSubSelection::RelationshipGroups(
+                                relationship::generate_model_groups_relationship_open_dd_ir(
+                                    field,
+                                    relationship_annotation,
+                                    models,
+                                    usage_counts,
+                                )?,
+                            ),
+                        );
+                    }
                     OutputAnnotation::RelationshipToCommand(relationship_annotation) => {
                         fields.insert(
                             make_field_alias(field.alias.0.as_str())?,
@@ -525,6 +539,150 @@ pub fn generate_aggregate_selection_set_open_dd_ir(
     Ok(fields)
 }
 
+/// Builds the OpenDD dimensions and aggregates selected by a groups selection set. The fields
+/// selected from a group key become dimensions, and the aggregates selected from a group
+/// aggregate are collected as they would be for a model aggregate. Both are named after the
+/// path of aliases leading to them, so that the response can be reshaped to match the request.
+pub fn generate_groups_selection_set_open_dd_ir(
+    selection_set: &normalized_ast::SelectionSet<'_, GDS>,
+) -> Result<
+    (
+        IndexMap<Name, open_dds::query::Dimension>,
+        IndexMap<Name, open_dds::query::Aggregate>,
+    ),
+    error::Error,
+> {
+    let mut dimensions = IndexMap::new();
+    let mut aggregates = IndexMap::new();
+    for field in selection_set.fields.values() {
+        let field_call = field.field_call()?;
+        match field_call.info.generic {
+            Annotation::Output(OutputAnnotation::Groups(GroupsOutputAnnotation::GroupKey)) => {
+                for key_field in field.selection_set.fields.values() {
+                    let key_field_call = key_field.field_call()?;
+                    match key_field_call.info.generic {
+                        Annotation::Output(OutputAnnotation::Groups(
+                            annotation @ (GroupsOutputAnnotation::GroupKeyField {
+                                field_name, ..
+                            }
+                            | GroupsOutputAnnotation::GroupKeyExtractionField {
+                                field_name,
+                                ..
+                            }),
+                        )) => {
+                            let extraction = match annotation {
+                                GroupsOutputAnnotation::GroupKeyExtractionField { .. } => {
+                                    Some(group_key_extraction_function(key_field_call)?)
+                                }
+                                _ => None,
+                            };
+                            let column = open_dds::query::Operand::Field(ObjectFieldOperand {
+                                target: Box::new(open_dds::query::ObjectFieldTarget {
+                                    field_name: field_name.clone(),
+                                    arguments: IndexMap::new(),
+                                }),
+                                nested: None,
+                            });
+                            let dimension_alias = make_field_alias(
+                                mk_alias_from_graphql_field_path(&[&field.alias, &key_field.alias])
+                                    .as_str(),
+                            )?;
+                            dimensions.insert(
+                                Name::from(dimension_alias.as_str().to_owned()),
+                                open_dds::query::Dimension::Field { column, extraction },
+                            );
+                        }
+                        // Skip introspection fields such as __typename, as they will be processed during response handling.
+                        Annotation::Output(OutputAnnotation::RootField(
+                            RootFieldAnnotation::Introspection,
+                        )) => {}
+                        annotation => Err(error::InternalEngineError::UnexpectedAnnotation {
+                            annotation: annotation.clone(),
+                        })?,
+                    }
+                }
+            }
+            Annotation::Output(OutputAnnotation::Groups(
+                GroupsOutputAnnotation::GroupAggregate,
+            )) => {
+                collect_aggregate_fields(
+                    &mut aggregates,
+                    &[],
+                    &[&field.alias],
+                    &field.selection_set,
+                )?;
+            }
+            // Skip introspection fields such as __typename, as they will be processed during response handling.
+            Annotation::Output(OutputAnnotation::RootField(RootFieldAnnotation::Introspection)) => {
+            }
+            annotation => Err(error::InternalEngineError::UnexpectedAnnotation {
+                annotation: annotation.clone(),
+            })?,
+        }
+    }
+    Ok((dimensions, aggregates))
+}
+
+/// The aliases of the dimensions selected by a groups selection set, in the order in which
+/// they are sent to the data connector and so in which their values are returned for each group.
+pub fn groups_dimension_aliases(
+    selection_set: &normalized_ast::SelectionSet<'_, GDS>,
+) -> Vec<NdcFieldAlias> {
+    let mut dimension_aliases = Vec::new();
+    for field in selection_set.fields.values() {
+        let is_group_key = field.field_call().is_ok_and(|field_call| {
+            matches!(
+                field_call.info.generic,
+                Annotation::Output(OutputAnnotation::Groups(GroupsOutputAnnotation::GroupKey))
+            )
+        });
+        if !is_group_key {
+            continue;
+        }
+        for key_field in field.selection_set.fields.values() {
+            let is_group_key_field = key_field.field_call().is_ok_and(|key_field_call| {
+                matches!(
+                    key_field_call.info.generic,
+                    Annotation::Output(OutputAnnotation::Groups(
+                        GroupsOutputAnnotation::GroupKeyField { .. }
+                            | GroupsOutputAnnotation::GroupKeyExtractionField { .. }
+                    ))
+                )
+            });
+            if is_group_key_field {
+                dimension_aliases.push(mk_alias_from_graphql_field_path(&[
+                    &field.alias,
+                    &key_field.alias,
+                ]));
+            }
+        }
+    }
+    dimension_aliases
+}
+
+/// The extraction function chosen by the `extraction` argument of a group key field, whose enum
+/// values are the extraction functions that the data connector supports for the field
+fn group_key_extraction_function(
+    key_field_call: &normalized_ast::FieldCall<'_, GDS>,
+) -> Result<open_dds::query::ExtractionFunction, error::Error> {
+    let argument = key_field_call.arguments.values().next().ok_or_else(|| {
+        error::InternalEngineError::InternalGeneric {
+            description: format!(
+                "missing extraction argument for group key field {}",
+                key_field_call.name
+            ),
+        }
+    })?;
+    match argument.value.as_enum()?.info.generic {
+        Annotation::Input(InputAnnotation::Model(
+            ModelInputAnnotation::ModelGroupKeyExtractionFunction { function },
+        )) => Ok(function.clone()),
+        annotation => Err(error::InternalEngineError::UnexpectedAnnotation {
+            annotation: annotation.clone(),
+        })?,
+    }
+}
+
 fn collect_aggregate_fields(
     aggregate_fields: &mut IndexMap<Name, open_dds::query::Aggregate>,
     field_path: &[&open_dds::query::ObjectFieldTarget],
@@ -797,6 +955,11 @@ pub fn generate_selection_set_ir<'s>(
                         )?,
                     );
                 }
+                OutputAnnotation::RelationshipToModelGroups(relationship_annotation) => {
+                    Err(error::Error::RelationshipGroupsNotSupported {
+                        relationship_name: relationship_annotation.relationship_name.clone(),
+                    })?;
+                }
                 OutputAnnotation::RelationshipToCommand(relationship_annotation) => {
                     fields.insert(
                         NdcFieldAlias::from(field.alias.0.as_str()),
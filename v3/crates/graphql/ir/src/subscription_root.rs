@@ -178,6 +178,10 @@ fn generate_model_rootfield_ir<'n, 's>(
             )?,
             polling_interval_ms: *polling_interval_ms,
         },
+        // groups are not exposed in the subscription root
+        RootFieldKind::SelectGroups => Err(error::InternalEngineError::UnexpectedAnnotation {
+            annotation: field_call.info.generic.clone(),
+        })?,
     };
     Ok(ir)
 }
@@ -1,3 +1,5 @@
+use std::fmt;
+
 use super::common::*;
 use super::spanning::*;
 use super::value::*;
@@ -292,3 +294,29 @@ pub enum DirectiveLocation {
     /// An [variable definition](struct.VariableDefinition.html).
     VariableDefinition,
 }
+
+impl fmt::Display for DirectiveLocation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Query => "QUERY",
+            Self::Mutation => "MUTATION",
+            Self::Subscription => "SUBSCRIPTION",
+            Self::Field => "FIELD",
+            Self::FragmentDefinition => "FRAGMENT_DEFINITION",
+            Self::FragmentSpread => "FRAGMENT_SPREAD",
+            Self::InlineFragment => "INLINE_FRAGMENT",
+            Self::Schema => "SCHEMA",
+            Self::Scalar => "SCALAR",
+            Self::Object => "OBJECT",
+            Self::FieldDefinition => "FIELD_DEFINITION",
+            Self::ArgumentDefinition => "ARGUMENT_DEFINITION",
+            Self::Interface => "INTERFACE",
+            Self::Union => "UNION",
+            Self::Enum => "ENUM",
+            Self::EnumValue => "ENUM_VALUE",
+            Self::InputObject => "INPUT_OBJECT",
+            Self::InputFieldDefinition => "INPUT_FIELD_DEFINITION",
+            Self::VariableDefinition => "VARIABLE_DEFINITION",
+        })
+    }
+}
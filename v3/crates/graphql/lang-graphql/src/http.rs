@@ -13,8 +13,32 @@ use crate::ast::executable;
 #[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
 pub struct RawRequest {
     pub operation_name: Option<ast::Name>,
+    /// The query string, which may be omitted when the request refers to a
+    /// persisted query instead
+    #[serde(default)]
     pub query: String,
     pub variables: Option<BTreeMap<ast::Name, serde_json::Value>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extensions: Option<RequestExtensions>,
+}
+
+/// The `extensions` of a request
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
+pub struct RequestExtensions {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub persisted_query: Option<PersistedQueryExtension>,
+}
+
+/// A reference to a persisted query, as sent by clients using automatic
+/// persisted queries
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
+pub struct PersistedQueryExtension {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<u32>,
+    /// The hex-encoded SHA-256 hash of the query
+    pub sha256_hash: String,
 }
 
 pub struct Request {
@@ -32,7 +56,7 @@ pub type Path = Vec<PathSegment>;
 
 /// A path segment is either a field name or an index into a list.
 /// <https://spec.graphql.org/October2021/#sel-HAPHRPJABABEyoB>
-#[derive(Serialize, Debug, PartialEq)]
+#[derive(Serialize, Debug, Clone, PartialEq)]
 #[serde(untagged)]
 pub enum PathSegment {
     /// Path segment that represent a field.
@@ -260,6 +260,26 @@ pub struct Field<'s, S: SchemaContext> {
     pub field_calls: FieldCalls<'s, S>,
     pub selection_set: SelectionSet<'s, S>,
     pub type_container: TypeContainer<TypeName>,
+    /// Set when every selection of the field is in a fragment with `@defer`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deferred: Option<Deferred>,
+    /// Set when the field is a list with `@stream`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream: Option<Stream>,
+}
+
+/// A fragment whose fields are delivered after the initial response, as per `@defer`
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Deferred {
+    pub label: Option<String>,
+}
+
+/// A list field whose items after the first `initial_count` are delivered after the initial
+/// response, as per `@stream`
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Stream {
+    pub label: Option<String>,
+    pub initial_count: usize,
 }
 
 impl<'s, S: SchemaContext> Field<'s, S> {
@@ -329,6 +349,8 @@ impl<'s, S: SchemaContext> SelectionSet<'s, S> {
                         .selection_set
                         .filter_field_calls_by_typename(type_name.clone()),
                     type_container: field.type_container.clone(),
+                    deferred: field.deferred.clone(),
+                    stream: field.stream.clone(),
                 };
                 filtered_selection_set_fields.insert(alias.clone(), new_field);
             }
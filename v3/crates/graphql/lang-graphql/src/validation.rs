@@ -3,23 +3,47 @@ use std::collections::HashSet;
 
 use crate::ast::common as ast;
 use crate::ast::executable;
+use crate::ast::schema::DirectiveLocation;
 use crate::http::VariableValues;
 use crate::normalized_ast as normalized;
 use crate::{http, schema};
 
 mod collect;
+mod directives;
 mod error;
 pub mod input;
+mod limits;
 pub mod selection_set;
 
 pub use error::*;
 use indexmap::IndexMap;
 use indexmap::IndexSet;
+pub use limits::SelectionLimits;
 
 pub fn normalize_request<'s, S: schema::SchemaContext, NSGet: schema::NamespacedGetter<S>>(
     namespaced_getter: &NSGet,
     schema: &'s schema::Schema<S>,
     request: &http::Request,
+) -> Result<normalized::Operation<'s, S>> {
+    normalize_request_with_limits(
+        namespaced_getter,
+        schema,
+        request,
+        &SelectionLimits::default(),
+    )
+}
+
+/// Like `normalize_request`, but stops with an error as soon as the operation
+/// exceeds one of the given limits
+pub fn normalize_request_with_limits<
+    's,
+    S: schema::SchemaContext,
+    NSGet: schema::NamespacedGetter<S>,
+>(
+    namespaced_getter: &NSGet,
+    schema: &'s schema::Schema<S>,
+    request: &http::Request,
+    limits: &SelectionLimits,
 ) -> Result<normalized::Operation<'s, S>> {
     let mut fragments = HashMap::new();
     let mut operations = HashMap::new();
@@ -72,6 +96,7 @@ pub fn normalize_request<'s, S: schema::SchemaContext, NSGet: schema::Namespaced
             &fragments,
             operation,
             &request.variables,
+            limits,
         )
     } else if let Some(operation_name) = operation_name {
         Err(Error::OperationNotFound {
@@ -84,6 +109,7 @@ pub fn normalize_request<'s, S: schema::SchemaContext, NSGet: schema::Namespaced
             &fragments,
             operations.values().next().unwrap(),
             &request.variables,
+            limits,
         )
     } else {
         Err(Error::AnonymousOperationNotFound)
@@ -145,6 +171,7 @@ pub fn normalize_operation<'q, 's, S: schema::SchemaContext, NSGet: schema::Name
     fragments: &HashMap<&'q ast::Name, &'q executable::FragmentDefinition>,
     operation: &'q executable::OperationDefinition,
     variable_values: &'q VariableValues,
+    limits: &SelectionLimits,
 ) -> Result<normalized::Operation<'s, S>> {
     let mut variables = HashMap::new();
     if let Some(variable_definitions) = &operation.variable_definitions {
@@ -175,6 +202,15 @@ pub fn normalize_operation<'q, 's, S: schema::SchemaContext, NSGet: schema::Name
         values: variable_values,
     };
 
+    directives::validate_non_conditional_directives(
+        match operation.ty {
+            ast::OperationType::Query => DirectiveLocation::Query,
+            ast::OperationType::Mutation => DirectiveLocation::Mutation,
+            ast::OperationType::Subscription => DirectiveLocation::Subscription,
+        },
+        &operation.directives,
+    )?;
+
     let selection_set_type_name = match operation.ty {
         ast::OperationType::Query => &schema.query_type,
         ast::OperationType::Mutation => schema
@@ -202,6 +238,7 @@ pub fn normalize_operation<'q, 's, S: schema::SchemaContext, NSGet: schema::Name
         &variables_context,
         &selection_set_type_info,
         &operation.selection_set.item,
+        &limits::SelectionCounter::new(limits),
     )?;
     Ok(normalized::Operation {
         ty: operation.ty,
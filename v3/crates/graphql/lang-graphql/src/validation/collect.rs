@@ -3,10 +3,14 @@ use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 
+use super::directives;
 use super::error::*;
+use super::input;
 use crate::ast::common as ast;
 use crate::ast::executable;
+use crate::ast::schema::DirectiveLocation;
 use crate::ast::spanning;
+use crate::normalized_ast as normalized;
 use crate::schema;
 
 // { # vec (typename, field)
@@ -122,6 +126,9 @@ pub(super) struct CollectedField<'q, 's, S: schema::SchemaContext> {
     pub field_path: Vec<&'s ast::TypeName>,
     pub reachable: bool,
     pub field: &'q executable::Field,
+    /// The `@defer` on the outermost deferred fragment the field was selected in
+    pub deferred: Option<normalized::Deferred>,
+    pub stream: Option<normalized::Stream>,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -134,6 +141,7 @@ fn collect_fields_from_fragment<
     namespaced_getter: &NSGet,
     schema: &'s schema::Schema<S>,
     fragments: &HashMap<&'q ast::Name, &'q executable::FragmentDefinition>,
+    variables: &input::value::Variables<'q, 's, S>,
 
     field_path: &Vec<&'s ast::TypeName>,
     selection_type: &SelectableType<'s, S>,
@@ -141,8 +149,12 @@ fn collect_fields_from_fragment<
     selection_set_reachability: &HashSet<&'s ast::TypeName>,
     fragment_selection_type: &SelectableType<'s, S>,
     fragment_selection_set: &'q executable::SelectionSet,
+    deferred: Option<&normalized::Deferred>,
     fields: &mut Vec<CollectedField<'q, 's, S>>,
-) -> Result<()> {
+) -> Result<()>
+where
+    's: 'q,
+{
     let common_types: HashSet<&ast::TypeName> = selection_type
         .possible_types
         .intersection(&fragment_selection_type.possible_types)
@@ -200,31 +212,32 @@ fn collect_fields_from_fragment<
         namespaced_getter,
         schema,
         fragments,
+        variables,
         &fragment_field_path,
         fragment_selection_type,
         &fragment_reachability,
         fragment_to_be_coerced_as,
         &fragment_selection_set.items,
+        deferred,
         fields,
     )?;
     Ok(())
 }
 
 #[allow(clippy::too_many_arguments)]
-pub(super) fn collect_fields<
-    'q,
-    's,
-    S: schema::SchemaContext,
-    NSGet: schema::NamespacedGetter<S>,
->(
+pub(super) fn collect_fields<'q, 's, S: schema::SchemaContext, NSGet: schema::NamespacedGetter<S>>(
     namespaced_getter: &NSGet,
     schema: &'s schema::Schema<S>,
     fragments: &HashMap<&'q ast::Name, &'q executable::FragmentDefinition>,
+    variables: &input::value::Variables<'q, 's, S>,
     field_path: &Vec<&'s ast::TypeName>,
     selection_type: &SelectableType<'s, S>,
     selection_set: &'q [spanning::Spanning<executable::Selection>],
     fields: &mut Vec<CollectedField<'q, 's, S>>,
-) -> Result<()> {
+) -> Result<()>
+where
+    's: 'q,
+{
     // let selection_set_field_path = SelectionSetfield_path::Unconditional {
     //     root_type: selection_type,
     //     reachable_types: selection_type.possible_types.clone(),
@@ -233,11 +246,13 @@ pub(super) fn collect_fields<
         namespaced_getter,
         schema,
         fragments,
+        variables,
         field_path,
         selection_type,
         &selection_type.possible_types,
         None,
         selection_set,
+        None,
         fields,
     )
 }
@@ -247,13 +262,18 @@ fn collect_fields_internal<'q, 's, S: schema::SchemaContext, NSGet: schema::Name
     namespaced_getter: &NSGet,
     schema: &'s schema::Schema<S>,
     fragments: &HashMap<&'q ast::Name, &'q executable::FragmentDefinition>,
+    variables: &input::value::Variables<'q, 's, S>,
     field_path: &Vec<&'s ast::TypeName>,
     selection_type: &SelectableType<'s, S>,
     selection_set_reachability: &HashSet<&'s ast::TypeName>,
     selection_sub_type: Option<&SelectableType<'s, S>>,
     selection_set: &'q [spanning::Spanning<executable::Selection>],
+    deferred: Option<&normalized::Deferred>,
     fields: &mut Vec<CollectedField<'q, 's, S>>,
-) -> Result<()> {
+) -> Result<()>
+where
+    's: 'q,
+{
     for selection in selection_set {
         match &selection.item {
             executable::Selection::Field(field) => {
@@ -279,12 +299,32 @@ fn collect_fields_internal<'q, 's, S: schema::SchemaContext, NSGet: schema::Name
                     field_info
                 };
 
+                let field_directives = directives::evaluate_selection_directives(
+                    namespaced_getter,
+                    schema,
+                    variables,
+                    DirectiveLocation::Field,
+                    &field.directives,
+                )?;
+                if !field_directives.include {
+                    continue;
+                }
+                let field_type = &refined_field_info.generic.field_type;
+                if field_directives.stream.is_some() && !field_type.is_list() {
+                    return Err(Error::StreamOnNonListField {
+                        field_name: field.name.item.clone(),
+                        field_type: field_type.clone(),
+                    });
+                }
+
                 fields.push(CollectedField {
                     alias,
                     field_path: field_path.clone(),
                     info: refined_field_info,
                     field,
                     reachable: !selection_set_reachability.is_empty(),
+                    deferred: deferred.cloned(),
+                    stream: field_directives.stream,
                 });
             }
             executable::Selection::FragmentSpread(spread) => {
@@ -301,16 +341,33 @@ fn collect_fields_internal<'q, 's, S: schema::SchemaContext, NSGet: schema::Name
                             type_name: selection_type.type_name.clone(),
                         }
                     })?;
+                directives::validate_non_conditional_directives(
+                    DirectiveLocation::FragmentDefinition,
+                    &fragment_definition.directives,
+                )?;
+                let fragment_directives = directives::evaluate_selection_directives(
+                    namespaced_getter,
+                    schema,
+                    variables,
+                    DirectiveLocation::FragmentSpread,
+                    &spread.directives,
+                )?;
+                if !fragment_directives.include {
+                    continue;
+                }
                 collect_fields_from_fragment(
                     namespaced_getter,
                     schema,
                     fragments,
+                    variables,
                     field_path,
                     selection_type,
                     selection_sub_type,
                     selection_set_reachability,
                     &fragment_selection_type,
                     &fragment_definition.selection_set.item,
+                    // a fragment nested in a deferred fragment is delivered with it
+                    deferred.or(fragment_directives.deferred.as_ref()),
                     fields,
                 )?;
             }
@@ -328,16 +385,28 @@ fn collect_fields_internal<'q, 's, S: schema::SchemaContext, NSGet: schema::Name
                     }
                     None => Ok(None),
                 }?;
+                let fragment_directives = directives::evaluate_selection_directives(
+                    namespaced_getter,
+                    schema,
+                    variables,
+                    DirectiveLocation::InlineFragment,
+                    &spread.directives,
+                )?;
+                if !fragment_directives.include {
+                    continue;
+                }
                 collect_fields_from_fragment(
                     namespaced_getter,
                     schema,
                     fragments,
+                    variables,
                     field_path,
                     selection_type,
                     selection_sub_type,
                     selection_set_reachability,
                     fragment_selection_type.as_ref().unwrap_or(selection_type),
                     &spread.selection_set.item,
+                    deferred.or(fragment_directives.deferred.as_ref()),
                     fields,
                 )?;
             }
@@ -0,0 +1,515 @@
+//! Validation and evaluation of the executable directives found in a request.
+//!
+//! The built-in `@skip` and `@include` directives are supported, along with
+//! `@defer` and `@stream` from the incremental delivery RFC. They are evaluated
+//! against the request's variables while fields are being collected, so that
+//! excluded selections never make it into the normalized AST and deferred or
+//! streamed selections are marked on the fields they apply to.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+
+use super::error::*;
+use super::input;
+use super::input::source::{LocationType, ValueSource};
+use crate::ast::common as ast;
+use crate::ast::executable;
+use crate::ast::schema::DirectiveLocation;
+use crate::ast::spanning::Spanning;
+use crate::ast::value as gql;
+use crate::mk_name;
+use crate::normalized_ast as normalized;
+use crate::schema;
+
+/// The executable directives understood by the engine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum ExecutableDirective {
+    /// <https://spec.graphql.org/October2021/#sec--skip>
+    Skip,
+    /// <https://spec.graphql.org/October2021/#sec--include>
+    Include,
+    /// <https://github.com/graphql/graphql-spec/pull/742>
+    Defer,
+    /// <https://github.com/graphql/graphql-spec/pull/742>
+    Stream,
+}
+
+impl ExecutableDirective {
+    fn from_name(name: &ast::Name) -> Option<Self> {
+        match name.as_str() {
+            "skip" => Some(Self::Skip),
+            "include" => Some(Self::Include),
+            "defer" => Some(Self::Defer),
+            "stream" => Some(Self::Stream),
+            _ => None,
+        }
+    }
+
+    fn locations(self) -> &'static [DirectiveLocation] {
+        match self {
+            Self::Skip | Self::Include => &[
+                DirectiveLocation::Field,
+                DirectiveLocation::FragmentSpread,
+                DirectiveLocation::InlineFragment,
+            ],
+            Self::Defer => &[
+                DirectiveLocation::FragmentSpread,
+                DirectiveLocation::InlineFragment,
+            ],
+            Self::Stream => &[DirectiveLocation::Field],
+        }
+    }
+
+    fn argument_names(self) -> &'static [&'static str] {
+        match self {
+            Self::Skip | Self::Include => &["if"],
+            Self::Defer => &["if", "label"],
+            Self::Stream => &["if", "label", "initialCount"],
+        }
+    }
+}
+
+/// The type of the `if` argument of every supported directive
+fn if_argument_type() -> &'static ast::Type {
+    static CELL: OnceLock<ast::Type> = OnceLock::new();
+    CELL.get_or_init(|| ast::Type {
+        base: ast::BaseType::Named(ast::TypeName(mk_name!("Boolean"))),
+        nullable: false,
+    })
+}
+
+/// The type of the `label` argument of `@defer` and `@stream`
+fn label_argument_type() -> &'static ast::Type {
+    static CELL: OnceLock<ast::Type> = OnceLock::new();
+    CELL.get_or_init(|| ast::Type {
+        base: ast::BaseType::Named(ast::TypeName(mk_name!("String"))),
+        nullable: true,
+    })
+}
+
+/// The type of the `initialCount` argument of `@stream`
+fn initial_count_argument_type() -> &'static ast::Type {
+    static CELL: OnceLock<ast::Type> = OnceLock::new();
+    CELL.get_or_init(|| ast::Type {
+        base: ast::BaseType::Named(ast::TypeName(mk_name!("Int"))),
+        nullable: false,
+    })
+}
+
+/// Checks that every directive is known, allowed at the given location and
+/// specified only once, returning the recognised directives.
+fn validate_directives<'q>(
+    location: DirectiveLocation,
+    directives: &'q [Spanning<executable::Directive>],
+) -> Result<Vec<(ExecutableDirective, &'q executable::Directive)>> {
+    let mut seen_directives = HashSet::new();
+    let mut validated_directives = Vec::with_capacity(directives.len());
+    for directive in directives {
+        let directive_name = &directive.item.name.item;
+        let executable_directive =
+            ExecutableDirective::from_name(directive_name).ok_or_else(|| {
+                Error::UnknownDirective {
+                    directive_name: directive_name.clone(),
+                }
+            })?;
+        if !executable_directive.locations().contains(&location) {
+            return Err(Error::DirectiveNotAllowedAtLocation {
+                directive_name: directive_name.clone(),
+                location,
+            });
+        }
+        if !seen_directives.insert(executable_directive) {
+            return Err(Error::DuplicateDirectives {
+                directive_name: directive_name.clone(),
+                location,
+            });
+        }
+        validated_directives.push((executable_directive, &directive.item));
+    }
+    Ok(validated_directives)
+}
+
+/// Validates directives at locations where none of the supported directives
+/// have any effect, such as operations and fragment definitions.
+pub(super) fn validate_non_conditional_directives(
+    location: DirectiveLocation,
+    directives: &[Spanning<executable::Directive>],
+) -> Result<()> {
+    validate_directives(location, directives).map(|_| ())
+}
+
+/// The effect of the directives on a selection.
+pub(super) struct SelectionDirectives {
+    /// Whether the selection is included in the response as per `@skip` and
+    /// `@include`
+    pub include: bool,
+    /// Set when the selection is a fragment with an enabled `@defer`
+    pub deferred: Option<normalized::Deferred>,
+    /// Set when the selection is a field with an enabled `@stream`
+    pub stream: Option<normalized::Stream>,
+}
+
+/// Validates and evaluates the directives on a selection.
+pub(super) fn evaluate_selection_directives<
+    'q,
+    's,
+    S: schema::SchemaContext,
+    NSGet: schema::NamespacedGetter<S>,
+>(
+    namespaced_getter: &NSGet,
+    schema: &'s schema::Schema<S>,
+    variables: &input::value::Variables<'q, 's, S>,
+    location: DirectiveLocation,
+    directives: &'q [Spanning<executable::Directive>],
+) -> Result<SelectionDirectives>
+where
+    's: 'q,
+{
+    let mut selection_directives = SelectionDirectives {
+        include: true,
+        deferred: None,
+        stream: None,
+    };
+    for (executable_directive, directive) in validate_directives(location, directives)? {
+        let arguments = collect_arguments(executable_directive, directive)?;
+        let argument_location = |type_: &'static ast::Type| LocationType::Argument {
+            type_,
+            default_value: None,
+        };
+        // `if` is required on `@skip` and `@include`, but defaults to `true`
+        // on `@defer` and `@stream`
+        let condition = match arguments.get("if") {
+            Some(value) => {
+                match value.get_boolean(
+                    schema,
+                    namespaced_getter,
+                    variables,
+                    &argument_location(if_argument_type()),
+                )? {
+                    normalized::Value::SimpleValue(normalized::SimpleValue::Boolean(condition)) => {
+                        condition
+                    }
+                    _ => {
+                        return Err(Error::UnexpectedNull {
+                            expected_type: if_argument_type().clone(),
+                        })
+                    }
+                }
+            }
+            None => match executable_directive {
+                ExecutableDirective::Skip | ExecutableDirective::Include => {
+                    return Err(Error::RequiredDirectiveArgumentNotFound {
+                        directive_name: directive.name.item.clone(),
+                        argument_name: mk_name!("if"),
+                    })
+                }
+                ExecutableDirective::Defer | ExecutableDirective::Stream => true,
+            },
+        };
+        let label = match arguments.get("label") {
+            Some(value) => match value.get_string(
+                schema,
+                namespaced_getter,
+                variables,
+                &argument_location(label_argument_type()),
+            )? {
+                normalized::Value::SimpleValue(normalized::SimpleValue::String(label)) => {
+                    Some(label)
+                }
+                _ => None,
+            },
+            None => None,
+        };
+        match executable_directive {
+            // when both are present, a selection is included only if it is not
+            // skipped and is included
+            ExecutableDirective::Skip => selection_directives.include &= !condition,
+            ExecutableDirective::Include => selection_directives.include &= condition,
+            ExecutableDirective::Defer => {
+                if condition {
+                    selection_directives.deferred = Some(normalized::Deferred { label });
+                }
+            }
+            ExecutableDirective::Stream => {
+                let initial_count = match arguments.get("initialCount") {
+                    Some(value) => match value.get_integer(
+                        schema,
+                        namespaced_getter,
+                        variables,
+                        &argument_location(initial_count_argument_type()),
+                    )? {
+                        normalized::Value::SimpleValue(normalized::SimpleValue::Integer(
+                            initial_count,
+                        )) => usize::try_from(initial_count)
+                            .map_err(|_| Error::NegativeStreamInitialCount)?,
+                        _ => {
+                            return Err(Error::UnexpectedNull {
+                                expected_type: initial_count_argument_type().clone(),
+                            })
+                        }
+                    },
+                    None => 0,
+                };
+                if condition {
+                    selection_directives.stream = Some(normalized::Stream {
+                        label,
+                        initial_count,
+                    });
+                }
+            }
+        }
+    }
+    Ok(selection_directives)
+}
+
+/// Collects the arguments of a directive, rejecting duplicate and unknown
+/// arguments.
+fn collect_arguments<'q>(
+    executable_directive: ExecutableDirective,
+    directive: &'q executable::Directive,
+) -> Result<HashMap<&'static str, &'q gql::Value>> {
+    let directive_name = &directive.name.item;
+    let mut argument_values = HashMap::new();
+    let mut unexpected_arguments = Vec::new();
+    if let Some(arguments) = &directive.arguments {
+        for argument in &arguments.item {
+            let argument_name = &argument.item.key.item;
+            match executable_directive
+                .argument_names()
+                .iter()
+                .find(|name| **name == argument_name.as_str())
+            {
+                Some(name) => {
+                    if argument_values
+                        .insert(*name, &argument.item.value.item)
+                        .is_some()
+                    {
+                        return Err(Error::DuplicateDirectiveArguments {
+                            directive_name: directive_name.clone(),
+                            argument_name: argument_name.clone(),
+                        });
+                    }
+                }
+                None => unexpected_arguments.push(argument_name.clone()),
+            }
+        }
+    }
+    if !unexpected_arguments.is_empty() {
+        return Err(Error::DirectiveArgumentsNotFound {
+            directive_name: directive_name.clone(),
+            argument_names: unexpected_arguments,
+        });
+    }
+    Ok(argument_values)
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use crate::ast::common as ast;
+    use crate::http;
+    use crate::mk_name;
+    use crate::normalized_ast::{Deferred, Stream};
+    use crate::parser::Parser;
+    use crate::schema::sdl;
+    use crate::validation::{normalize_request, Error};
+
+    fn fake_schema() -> crate::schema::Schema<sdl::SDL> {
+        sdl::SDL::new("type Query { foo: Int bar: Int baz: Query list: [Int] }")
+            .and_then(|v| v.build_schema())
+            .unwrap()
+    }
+
+    fn normalized_aliases(
+        query: &str,
+        variables: BTreeMap<ast::Name, serde_json::Value>,
+    ) -> Result<Vec<String>, Error> {
+        let schema = fake_schema();
+        let request = http::Request {
+            operation_name: None,
+            query: Parser::new(query).parse_executable_document().unwrap(),
+            variables,
+        };
+        let operation = normalize_request(&sdl::SDLNamespacedGetter(), &schema, &request)?;
+        Ok(operation
+            .selection_set
+            .fields
+            .keys()
+            .map(ToString::to_string)
+            .collect())
+    }
+
+    #[test]
+    fn test_skip_and_include_on_fields() {
+        let aliases = normalized_aliases(
+            "{ foo @skip(if: true) bar @include(if: true) baz @include(if: false) { foo } }",
+            BTreeMap::new(),
+        )
+        .unwrap();
+        assert_eq!(aliases, vec!["bar"]);
+    }
+
+    #[test]
+    fn test_skip_and_include_with_variables() {
+        let aliases = normalized_aliases(
+            "query ($skip: Boolean!, $include: Boolean = false) { foo @skip(if: $skip) bar @include(if: $include) baz { foo } }",
+            BTreeMap::from([(mk_name!("skip"), serde_json::Value::Bool(true))]),
+        )
+        .unwrap();
+        assert_eq!(aliases, vec!["baz"]);
+    }
+
+    #[test]
+    fn test_skip_and_include_on_fragments() {
+        let aliases = normalized_aliases(
+            "{ ...Foo @skip(if: true) ... @include(if: true) { bar } ... on Query @include(if: false) { baz { foo } } } fragment Foo on Query { foo }",
+            BTreeMap::new(),
+        )
+        .unwrap();
+        assert_eq!(aliases, vec!["bar"]);
+    }
+
+    #[test]
+    fn test_skip_takes_precedence_over_include() {
+        let aliases = normalized_aliases(
+            "{ foo @skip(if: true) @include(if: true) bar }",
+            BTreeMap::new(),
+        )
+        .unwrap();
+        assert_eq!(aliases, vec!["bar"]);
+    }
+
+    fn normalized_incremental_directives(
+        query: &str,
+    ) -> Result<Vec<(String, Option<Deferred>, Option<Stream>)>, Error> {
+        let schema = fake_schema();
+        let request = http::Request {
+            operation_name: None,
+            query: Parser::new(query).parse_executable_document().unwrap(),
+            variables: BTreeMap::new(),
+        };
+        let operation = normalize_request(&sdl::SDLNamespacedGetter(), &schema, &request)?;
+        Ok(operation
+            .selection_set
+            .fields
+            .into_iter()
+            .map(|(alias, field)| (alias.to_string(), field.deferred, field.stream))
+            .collect())
+    }
+
+    #[test]
+    fn test_defer_on_fragments() {
+        let directives = normalized_incremental_directives(
+            r#"{ foo ... @defer(label: "later") { bar ... @defer { list } } ...Baz @defer(if: false) } fragment Baz on Query { baz { foo } }"#,
+        )
+        .unwrap();
+        let later = Some(Deferred {
+            label: Some("later".to_string()),
+        });
+        assert_eq!(
+            directives,
+            vec![
+                ("foo".to_string(), None, None),
+                ("bar".to_string(), later.clone(), None),
+                // nested deferred fragments are delivered with the outer one
+                ("list".to_string(), later, None),
+                ("baz".to_string(), None, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_field_is_deferred_only_if_every_selection_is() {
+        let directives =
+            normalized_incremental_directives("{ foo ... @defer { foo bar } }").unwrap();
+        assert_eq!(
+            directives,
+            vec![
+                ("foo".to_string(), None, None),
+                ("bar".to_string(), Some(Deferred { label: None }), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_field_deferred_with_different_labels() {
+        // the field is delivered with only one of the fragments, so they must share a label
+        assert!(matches!(
+            normalized_incremental_directives(
+                r#"{ foo ... @defer(label: "a") { bar } ... @defer(label: "b") { bar } }"#
+            ),
+            Err(Error::FieldsConflictDifferingDeferLabels { .. })
+        ));
+        // unless the field is needed for the initial response anyway
+        let directives = normalized_incremental_directives(
+            r#"{ bar ... @defer(label: "a") { bar } ... @defer(label: "b") { bar } }"#,
+        )
+        .unwrap();
+        assert_eq!(directives, vec![("bar".to_string(), None, None)]);
+        let directives = normalized_incremental_directives(
+            r#"{ foo ... @defer(label: "a") { bar } ... @defer(label: "a") { bar } }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            directives,
+            vec![
+                ("foo".to_string(), None, None),
+                (
+                    "bar".to_string(),
+                    Some(Deferred {
+                        label: Some("a".to_string()),
+                    }),
+                    None
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stream_on_list_fields() {
+        let directives = normalized_incremental_directives(
+            r#"{ list @stream(initialCount: 2, label: "items") other: list @stream(if: false) }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            directives,
+            vec![
+                (
+                    "list".to_string(),
+                    None,
+                    Some(Stream {
+                        label: Some("items".to_string()),
+                        initial_count: 2,
+                    })
+                ),
+                ("other".to_string(), None, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_directives() {
+        for query in [
+            "{ foo @unknown bar }",
+            "{ foo @skip(if: true) @skip(if: false) bar }",
+            "{ foo @skip bar }",
+            "{ foo @skip(if: true, unless: false) bar }",
+            "{ foo @skip(if: 1) bar }",
+            "query @skip(if: true) { foo }",
+            "{ ...Foo } fragment Foo on Query @include(if: true) { foo }",
+            "query ($skip: Boolean) { foo @skip(if: $skip) bar }",
+            "{ foo @defer }",
+            "{ ... @stream { foo } }",
+            "{ foo @stream }",
+            "{ list @stream(initialCount: -1) }",
+            "{ list @stream(first: 1) }",
+            "{ list @stream list }",
+        ] {
+            assert!(
+                normalized_aliases(query, BTreeMap::new()).is_err(),
+                "expected query to fail validation: {query}"
+            );
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use thiserror::Error;
 
-use crate::ast::{common as ast, spanning};
+use crate::ast::{common as ast, schema as sdl, spanning};
 
 pub type Result<T> = core::result::Result<T, Error>;
 
@@ -66,6 +66,10 @@ pub enum Error {
         location1: Option<spanning::SourcePosition>,
         location2: Option<spanning::SourcePosition>,
     },
+    #[error("cannot merge fields with different @stream directives on the same alias: {alias}")]
+    FieldsConflictDifferingStreams { alias: ast::Alias },
+    #[error("cannot merge fields deferred with different labels on the same alias: {alias}")]
+    FieldsConflictDifferingDeferLabels { alias: ast::Alias },
     #[error("the string {str} in the provided json value is not a valid GraphQL name")]
     NotAValidName { str: String },
     #[error("expected a value of type {expected_type} but found a value of type {actual_type}")]
@@ -161,4 +165,58 @@ pub enum Error {
     },
     #[error("no fields are selected")]
     FieldSelectionSetIsEmpty,
+    #[error("unknown directive: @{directive_name}")]
+    UnknownDirective { directive_name: ast::Name },
+    #[error("directive @{directive_name} is not allowed on {location}")]
+    DirectiveNotAllowedAtLocation {
+        directive_name: ast::Name,
+        location: sdl::DirectiveLocation,
+    },
+    #[error("directive @{directive_name} is specified more than once on {location}")]
+    DuplicateDirectives {
+        directive_name: ast::Name,
+        location: sdl::DirectiveLocation,
+    },
+    #[error("argument {argument_name} on directive @{directive_name} is defined more than once")]
+    DuplicateDirectiveArguments {
+        directive_name: ast::Name,
+        argument_name: ast::Name,
+    },
+    #[error("required argument {argument_name} not found on directive @{directive_name}")]
+    RequiredDirectiveArgumentNotFound {
+        directive_name: ast::Name,
+        argument_name: ast::Name,
+    },
+    #[error("unexpected arguments '{}' on directive @{directive_name}", argument_names.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+    DirectiveArgumentsNotFound {
+        directive_name: ast::Name,
+        argument_names: Vec<ast::Name>,
+    },
+    #[error("directive @stream is only allowed on fields of list type, but field {field_name} is of type {field_type}")]
+    StreamOnNonListField {
+        field_name: ast::Name,
+        field_type: ast::Type,
+    },
+    #[error("argument initialCount on directive @stream must not be negative")]
+    NegativeStreamInitialCount,
+    #[error("the operation exceeds the {limit} limit of {max}")]
+    SelectionLimitExceeded { limit: SelectionLimit, max: u32 },
+}
+
+/// A limit on the size of an operation, see `SelectionLimits`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionLimit {
+    Depth,
+    Fields,
+    Aliases,
+}
+
+impl std::fmt::Display for SelectionLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Depth => write!(f, "depth"),
+            Self::Fields => write!(f, "field"),
+            Self::Aliases => write!(f, "alias"),
+        }
+    }
 }
@@ -0,0 +1,54 @@
+use std::cell::Cell;
+
+use super::error::{Error, Result, SelectionLimit};
+
+/// Limits on the size of an operation. They are enforced while the operation
+/// is normalized, which stops as soon as one is exceeded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SelectionLimits {
+    /// The maximum depth of the selection sets
+    pub max_depth: Option<u32>,
+    /// The maximum number of fields selected, across all the selection sets
+    pub max_fields: Option<u32>,
+    /// The maximum number of aliased fields, across all the selection sets
+    pub max_aliases: Option<u32>,
+}
+
+/// Counts the fields and aliases of an operation as it is normalized
+pub(super) struct SelectionCounter<'l> {
+    limits: &'l SelectionLimits,
+    fields: Cell<u32>,
+    aliases: Cell<u32>,
+}
+
+impl<'l> SelectionCounter<'l> {
+    pub(super) fn new(limits: &'l SelectionLimits) -> Self {
+        SelectionCounter {
+            limits,
+            fields: Cell::new(0),
+            aliases: Cell::new(0),
+        }
+    }
+
+    /// Counts a field selected at the given depth, the fields of the root
+    /// selection set being at depth 1
+    pub(super) fn count_field(&self, depth: u32, is_aliased: bool) -> Result<()> {
+        check_limit(SelectionLimit::Depth, depth, self.limits.max_depth)?;
+        let fields = self.fields.get().saturating_add(1);
+        self.fields.set(fields);
+        check_limit(SelectionLimit::Fields, fields, self.limits.max_fields)?;
+        if is_aliased {
+            let aliases = self.aliases.get().saturating_add(1);
+            self.aliases.set(aliases);
+            check_limit(SelectionLimit::Aliases, aliases, self.limits.max_aliases)?;
+        }
+        Ok(())
+    }
+}
+
+fn check_limit(limit: SelectionLimit, value: u32, max: Option<u32>) -> Result<()> {
+    match max {
+        Some(max) if value > max => Err(Error::SelectionLimitExceeded { limit, max }),
+        _ => Ok(()),
+    }
+}
@@ -6,6 +6,7 @@ use std::collections::HashMap;
 use super::collect;
 use super::error::*;
 use super::input;
+use super::limits::SelectionCounter;
 use crate::ast::common as ast;
 use crate::ast::executable;
 use crate::ast::spanning;
@@ -26,6 +27,7 @@ pub fn normalize_selection_set<
 
     selection_type: &collect::SelectableType<'s, S>,
     selection_set: &'q executable::SelectionSet,
+    counter: &SelectionCounter,
 ) -> Result<normalized::SelectionSet<'s, S>>
 where
     's: 'q,
@@ -38,10 +40,12 @@ where
         variables,
         selection_type,
         Vec::from([(&reachability, Vec::from([&selection_set.items]))]),
+        counter,
+        1,
     )
 }
 
-#[allow(clippy::type_complexity)]
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
 fn normalize_selection_sets<'q, 's, S: schema::SchemaContext, NSGet: schema::NamespacedGetter<S>>(
     namespaced_getter: &NSGet,
     schema: &'s schema::Schema<S>,
@@ -55,6 +59,9 @@ fn normalize_selection_sets<'q, 's, S: schema::SchemaContext, NSGet: schema::Nam
         &Vec<&'s ast::TypeName>,
         Vec<&'q Vec<spanning::Spanning<executable::Selection>>>,
     )>,
+    counter: &SelectionCounter,
+    // the depth of the fields of the selection sets
+    depth: u32,
 ) -> Result<normalized::SelectionSet<'s, S>>
 where
     's: 'q,
@@ -66,6 +73,7 @@ where
                 namespaced_getter,
                 schema,
                 fragments,
+                variables,
                 path,
                 selection_type,
                 selection_set,
@@ -97,6 +105,14 @@ where
     });
     let mut normalized_fields = IndexMap::new();
     for (alias, (alias_type, typed_fields)) in field_map {
+        let is_aliased = typed_fields
+            .values()
+            .any(|fields| fields.head.field.name.item != *alias);
+        counter.count_field(depth, is_aliased)?;
+        let (deferred, stream) = merge_incremental_directives(
+            alias,
+            fields.iter().filter(|field| field.alias == alias),
+        )?;
         let alias = ast::Alias(alias.clone());
         let (field_calls, selection_set) = merge_fields(
             namespaced_getter,
@@ -107,6 +123,8 @@ where
             &alias,
             alias_type,
             typed_fields,
+            counter,
+            depth,
         )?;
         // if let normalized::FieldCalls::Conditional(conditional) = &field_calls {
         if !field_calls.is_empty() {
@@ -115,6 +133,8 @@ where
                 field_calls,
                 selection_set,
                 type_container: alias_type.clone(),
+                deferred,
+                stream,
             };
             normalized_fields.insert(alias, normalized_field);
         }
@@ -141,6 +161,42 @@ where
     })
 }
 
+/// Merges the `@defer` and `@stream` of the selections of an alias. The field is
+/// deferred only if every selection of it is in a deferred fragment, as it is
+/// needed for the initial response otherwise, in which case the fragments must
+/// share a label, as the field is delivered with only one of them. The
+/// selections must also agree on how it is streamed.
+fn merge_incremental_directives<'a, 'q: 'a, 's: 'a, S: schema::SchemaContext + 'a>(
+    alias: &ast::Name,
+    mut fields: impl Iterator<Item = &'a collect::CollectedField<'q, 's, S>>,
+) -> Result<(Option<normalized::Deferred>, Option<normalized::Stream>)> {
+    let Some(first_field) = fields.next() else {
+        return Ok((None, None));
+    };
+    let mut deferred = first_field.deferred.clone();
+    let mut differing_defer_labels = false;
+    for field in fields {
+        match (&first_field.deferred, &field.deferred) {
+            (_, None) => deferred = None,
+            (Some(first_deferred), Some(field_deferred)) => {
+                differing_defer_labels |= first_deferred.label != field_deferred.label;
+            }
+            (None, Some(_)) => {}
+        }
+        if field.stream != first_field.stream {
+            return Err(Error::FieldsConflictDifferingStreams {
+                alias: ast::Alias(alias.clone()),
+            });
+        }
+    }
+    if deferred.is_some() && differing_defer_labels {
+        return Err(Error::FieldsConflictDifferingDeferLabels {
+            alias: ast::Alias(alias.clone()),
+        });
+    }
+    Ok((deferred, first_field.stream.clone()))
+}
+
 #[allow(clippy::too_many_arguments)]
 fn merge_fields<'q, 's, S: schema::SchemaContext, NSGet: schema::NamespacedGetter<S>>(
     namespaced_getter: &NSGet,
@@ -151,6 +207,9 @@ fn merge_fields<'q, 's, S: schema::SchemaContext, NSGet: schema::NamespacedGette
     alias: &ast::Alias,
     alias_type: &ast::Type,
     typed_fields: HashMap<&Vec<&'s ast::TypeName>, NonEmpty<&collect::CollectedField<'q, 's, S>>>,
+    counter: &SelectionCounter,
+    // the depth of the field being merged
+    depth: u32,
 ) -> Result<(
     normalized::FieldCalls<'s, S>,
     normalized::SelectionSet<'s, S>,
@@ -199,8 +258,6 @@ where
                     field2: field.field.name.item.clone(),
                 });
             }
-            // TODO deal with directives
-            // let this_directives = normalize_directives( schema, &field.field.directives)?;
             let this_arguments = normalize_arguments(
                 namespaced_getter,
                 schema,
@@ -245,6 +302,8 @@ where
             variables,
             &selection_type,
             alias_selection_sets,
+            counter,
+            depth.saturating_add(1),
         )?,
         None => normalized::SelectionSet {
             fields: IndexMap::new(),
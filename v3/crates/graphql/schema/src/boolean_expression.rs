@@ -324,7 +324,9 @@ fn build_new_comparable_relationships_schema(
                 relationship_type,
                 target_typename: _,
                 mappings,
+                value_mappings: _,
                 relationship_aggregate: _,
+                groups_field_name: _,
             } = model_relationship_target.as_ref();
             // lookup target model for relationship
             let target_model = gds.metadata.models.get(model_name).ok_or_else(|| {
@@ -0,0 +1,586 @@
+//! Schema for the groups of a model
+//!
+//! Each group has a key, holding the values of the fields the group was formed by, and
+//! the aggregates computed across the objects of the group.
+use hasura_authn_core::Role;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use strum_macros::Display;
+
+use lang_graphql::{
+    ast::common::{self as ast, TypeContainer},
+    mk_name, schema as gql_schema,
+};
+use metadata_resolve::{Qualified, QualifiedBaseType, QualifiedTypeReference};
+use open_dds::{
+    models::ModelName,
+    types::{CustomTypeName, Deprecated, FieldName},
+};
+
+use crate::{
+    aggregates::get_aggregate_select_output_type,
+    mk_deprecation_status,
+    types::{self, input_type, output_type, TypeId, TypeKind},
+    Annotation, Error, ModelInputAnnotation, NamespaceAnnotation, GDS,
+};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Display)]
+pub enum GroupsOutputAnnotation {
+    /// The field holding the values the group was formed by
+    GroupKey,
+    /// The field holding the aggregates computed across the objects of the group
+    GroupAggregate,
+    /// A field of the model's object type that a group can be formed by
+    GroupKeyField {
+        field_name: FieldName,
+        /// The parent type is required to report field usage while analyzing query usage.
+        parent_type: Qualified<CustomTypeName>,
+        /// To mark a field as deprecated in the field usage while reporting query usage analytics.
+        deprecated: Option<Deprecated>,
+    },
+    /// A field holding a part extracted from a field of the model's object type, such as
+    /// the year of a date, that a group can be formed by
+    GroupKeyExtractionField {
+        field_name: FieldName,
+        /// The parent type is required to report field usage while analyzing query usage.
+        parent_type: Qualified<CustomTypeName>,
+        /// To mark a field as deprecated in the field usage while reporting query usage analytics.
+        deprecated: Option<Deprecated>,
+    },
+}
+
+pub fn get_groups_output_type(
+    builder: &mut gql_schema::Builder<GDS>,
+    model: &metadata_resolve::ModelWithPermissions,
+    select_groups: &metadata_resolve::SelectGroupsGraphQlDefinition,
+) -> gql_schema::RegisteredTypeName {
+    builder.register_type(TypeId::ModelGroupsOutputType {
+        model_name: model.model.name.clone(),
+        graphql_type_name: select_groups.group_type_name.clone(),
+    })
+}
+
+fn get_select_groups<'s>(
+    gds: &'s GDS,
+    model_name: &Qualified<ModelName>,
+) -> Result<
+    (
+        &'s metadata_resolve::ModelWithPermissions,
+        &'s metadata_resolve::SelectGroupsGraphQlDefinition,
+    ),
+    Error,
+> {
+    let model =
+        gds.metadata
+            .models
+            .get(model_name)
+            .ok_or_else(|| Error::InternalModelNotFound {
+                model_name: model_name.clone(),
+            })?;
+    let select_groups = model.graphql_api.select_groups.as_ref().ok_or_else(|| {
+        Error::InternalModelGroupsGraphQlApiNotFound {
+            model_name: model_name.clone(),
+        }
+    })?;
+    Ok((model, select_groups))
+}
+
+pub fn build_model_groups_output_type(
+    gds: &GDS,
+    builder: &mut gql_schema::Builder<GDS>,
+    model_name: &Qualified<ModelName>,
+    graphql_type_name: &ast::TypeName,
+) -> Result<gql_schema::TypeInfo<GDS>, Error> {
+    let (model, select_groups) = get_select_groups(gds, model_name)?;
+
+    let aggregate_expression = get_aggregate_expression(gds, select_groups)?;
+
+    let mut fields = BTreeMap::new();
+
+    let group_key_field_name = select_groups.group_key_field_name.clone();
+    let group_key_field = gql_schema::Field::<GDS>::new(
+        group_key_field_name.clone(),
+        None,
+        Annotation::Output(types::OutputAnnotation::Groups(
+            GroupsOutputAnnotation::GroupKey,
+        )),
+        TypeContainer::named_non_null(builder.register_type(TypeId::ModelGroupKeyOutputType {
+            model_name: model.model.name.clone(),
+            graphql_type_name: select_groups.group_key_type_name.clone(),
+        })),
+        BTreeMap::new(), // Arguments
+        mk_deprecation_status(None),
+    );
+    fields.insert(
+        group_key_field_name,
+        builder.allow_all_namespaced(group_key_field),
+    );
+
+    let group_aggregate_field_name = select_groups.group_aggregate_field_name.clone();
+    let group_aggregate_field = gql_schema::Field::<GDS>::new(
+        group_aggregate_field_name.clone(),
+        None,
+        Annotation::Output(types::OutputAnnotation::Groups(
+            GroupsOutputAnnotation::GroupAggregate,
+        )),
+        TypeContainer::named_non_null(get_aggregate_select_output_type(
+            builder,
+            aggregate_expression,
+        )?),
+        BTreeMap::new(), // Arguments
+        mk_deprecation_status(None),
+    );
+    fields.insert(
+        group_aggregate_field_name,
+        builder.allow_all_namespaced(group_aggregate_field),
+    );
+
+    Ok(gql_schema::TypeInfo::Object(gql_schema::Object::new(
+        builder,
+        graphql_type_name.clone(),
+        None,
+        fields,
+        BTreeMap::new(), // Interfaces
+        vec![],          // Directives
+    )))
+}
+
+pub fn build_model_group_key_output_type(
+    gds: &GDS,
+    builder: &mut gql_schema::Builder<GDS>,
+    model_name: &Qualified<ModelName>,
+    graphql_type_name: &ast::TypeName,
+) -> Result<gql_schema::TypeInfo<GDS>, Error> {
+    let (model, select_groups) = get_select_groups(gds, model_name)?;
+    let object_type = output_type::get_object_type_representation(gds, &model.model.data_type)?;
+
+    let mut fields = BTreeMap::new();
+    for (field_name, field_definition) in groupable_fields(gds, object_type)? {
+        let graphql_field_name = metadata_resolve::mk_name(field_name.as_str())
+            .map_err(metadata_resolve::Error::from)
+            .map_err(metadata_resolve::WithContext::from)?;
+
+        let field = gql_schema::Field::<GDS>::new(
+            graphql_field_name.clone(),
+            field_definition.description.clone(),
+            Annotation::Output(types::OutputAnnotation::Groups(
+                GroupsOutputAnnotation::GroupKeyField {
+                    field_name: field_name.clone(),
+                    parent_type: model.model.data_type.clone(),
+                    deprecated: field_definition.deprecated.clone(),
+                },
+            )),
+            output_type::get_output_type(gds, builder, &field_definition.field_type)?,
+            BTreeMap::new(), // Arguments
+            mk_deprecation_status(field_definition.deprecated.as_ref()),
+        );
+
+        fields.insert(
+            graphql_field_name,
+            builder.conditional_namespaced(field, field_allowed_roles(object_type, field_name)),
+        );
+    }
+
+    for (graphql_field_name, extraction) in &select_groups.extractions {
+        let field_definition = object_type
+            .object_type
+            .fields
+            .get(&extraction.field_name)
+            .ok_or_else(|| Error::InternalObjectTypeFieldNotFound {
+                field_name: extraction.field_name.clone(),
+                type_name: model.model.data_type.clone(),
+            })?;
+
+        let extraction_argument_name = mk_name!("extraction");
+        let extraction_argument = gql_schema::InputField::new(
+            extraction_argument_name.clone(),
+            Some(
+                "The part of the field's value, such as its year or month, to group by".to_string(),
+            ),
+            Annotation::Input(types::InputAnnotation::Model(
+                ModelInputAnnotation::ModelGroupKeyExtractionArgument,
+            )),
+            TypeContainer::named_non_null(builder.register_type(
+                TypeId::ModelGroupKeyExtractionEnumType {
+                    model_name: model.model.name.clone(),
+                    graphql_type_name: extraction.enum_type_name.clone(),
+                },
+            )),
+            None,
+            gql_schema::DeprecationStatus::NotDeprecated,
+        );
+
+        let field = gql_schema::Field::<GDS>::new(
+            graphql_field_name.clone(),
+            field_definition.description.clone(),
+            Annotation::Output(types::OutputAnnotation::Groups(
+                GroupsOutputAnnotation::GroupKeyExtractionField {
+                    field_name: extraction.field_name.clone(),
+                    parent_type: model.model.data_type.clone(),
+                    deprecated: field_definition.deprecated.clone(),
+                },
+            )),
+            output_type::get_output_type(gds, builder, &extraction.result_type)?,
+            BTreeMap::from([(
+                extraction_argument_name,
+                builder.allow_all_namespaced(extraction_argument),
+            )]),
+            mk_deprecation_status(field_definition.deprecated.as_ref()),
+        );
+
+        let field = builder.conditional_namespaced(
+            field,
+            field_allowed_roles(object_type, &extraction.field_name),
+        );
+        if fields.insert(graphql_field_name.clone(), field).is_some() {
+            return Err(Error::GroupKeyFieldNameConflict {
+                model_name: model.model.name.clone(),
+                field_name: graphql_field_name.clone(),
+            });
+        }
+    }
+
+    Ok(gql_schema::TypeInfo::Object(gql_schema::Object::new(
+        builder,
+        graphql_type_name.clone(),
+        None,
+        fields,
+        BTreeMap::new(), // Interfaces
+        vec![],          // Directives
+    )))
+}
+
+/// The parts of a field that a group key field can hold, one enum value per extraction function
+/// that the data connector supports for the field
+pub fn build_model_group_key_extraction_enum_type(
+    gds: &GDS,
+    builder: &mut gql_schema::Builder<GDS>,
+    model_name: &Qualified<ModelName>,
+    graphql_type_name: &ast::TypeName,
+) -> Result<gql_schema::TypeInfo<GDS>, Error> {
+    let (_model, select_groups) = get_select_groups(gds, model_name)?;
+    let extraction = select_groups
+        .extractions
+        .values()
+        .find(|extraction| &extraction.enum_type_name == graphql_type_name)
+        .ok_or_else(|| Error::InternalModelGroupsTypeNotFound {
+            model_name: model_name.clone(),
+            type_name: graphql_type_name.clone(),
+        })?;
+
+    let values = extraction
+        .functions
+        .iter()
+        .map(|(value, function)| {
+            let enum_value = builder.allow_all_namespaced(gql_schema::EnumValue {
+                value: value.clone(),
+                description: None,
+                deprecation_status: gql_schema::DeprecationStatus::NotDeprecated,
+                info: Annotation::Input(types::InputAnnotation::Model(
+                    ModelInputAnnotation::ModelGroupKeyExtractionFunction {
+                        function: function.clone(),
+                    },
+                )),
+            });
+            (value.clone(), enum_value)
+        })
+        .collect();
+
+    Ok(gql_schema::TypeInfo::Enum(gql_schema::Enum {
+        name: graphql_type_name.clone(),
+        description: None,
+        values,
+        directives: Vec::new(),
+    }))
+}
+
+/// The boolean expression that groups are filtered with, which compares the number of objects
+/// in each group
+pub fn build_model_groups_filter_input_type(
+    gds: &GDS,
+    builder: &mut gql_schema::Builder<GDS>,
+    model_name: &Qualified<ModelName>,
+    graphql_type_name: &ast::TypeName,
+) -> Result<gql_schema::TypeInfo<GDS>, Error> {
+    let (model, select_groups) = get_select_groups(gds, model_name)?;
+    let filter =
+        select_groups
+            .filter
+            .as_ref()
+            .ok_or_else(|| Error::InternalModelGroupsTypeNotFound {
+                model_name: model_name.clone(),
+                type_name: graphql_type_name.clone(),
+            })?;
+
+    let mut fields = BTreeMap::new();
+
+    if let Some(logical_operators) = &filter.logical_operators {
+        let logical_operator_fields = [
+            (
+                &logical_operators.and_operator_name,
+                types::LogicalOperatorField::AndOp,
+                TypeContainer::list_null(TypeContainer::named_non_null(
+                    gql_schema::RegisteredTypeName::new(graphql_type_name.0.clone()),
+                )),
+            ),
+            (
+                &logical_operators.or_operator_name,
+                types::LogicalOperatorField::OrOp,
+                TypeContainer::list_null(TypeContainer::named_non_null(
+                    gql_schema::RegisteredTypeName::new(graphql_type_name.0.clone()),
+                )),
+            ),
+            (
+                &logical_operators.not_operator_name,
+                types::LogicalOperatorField::NotOp,
+                TypeContainer::named_null(gql_schema::RegisteredTypeName::new(
+                    graphql_type_name.0.clone(),
+                )),
+            ),
+        ];
+        for (field_name, operator, field_type) in logical_operator_fields {
+            fields.insert(
+                field_name.clone(),
+                builder.allow_all_namespaced(gql_schema::InputField::<GDS>::new(
+                    field_name.clone(),
+                    None,
+                    Annotation::Input(types::InputAnnotation::Model(
+                        ModelInputAnnotation::ModelGroupsFilterLogicalOperator(operator),
+                    )),
+                    field_type,
+                    None,
+                    gql_schema::DeprecationStatus::NotDeprecated,
+                )),
+            );
+        }
+    }
+
+    if let Some(count_field_name) = count_field_name(gds, select_groups)? {
+        fields.insert(
+            count_field_name.clone(),
+            builder.allow_all_namespaced(gql_schema::InputField::<GDS>::new(
+                count_field_name.clone(),
+                None,
+                Annotation::Input(types::InputAnnotation::Model(
+                    ModelInputAnnotation::ModelGroupsFilterCount,
+                )),
+                TypeContainer::named_null(builder.register_type(
+                    TypeId::ModelGroupsCountComparisonInputType {
+                        model_name: model.model.name.clone(),
+                        graphql_type_name: filter.count_comparison_type_name.clone(),
+                    },
+                )),
+                None,
+                gql_schema::DeprecationStatus::NotDeprecated,
+            )),
+        );
+    }
+
+    Ok(gql_schema::TypeInfo::InputObject(
+        gql_schema::InputObject::new(
+            graphql_type_name.clone(),
+            None,
+            fields,
+            vec![], // Directives
+        ),
+    ))
+}
+
+/// The comparisons of the number of objects in a group that groups are filtered with
+pub fn build_model_groups_count_comparison_input_type(
+    gds: &GDS,
+    builder: &mut gql_schema::Builder<GDS>,
+    model_name: &Qualified<ModelName>,
+    graphql_type_name: &ast::TypeName,
+) -> Result<gql_schema::TypeInfo<GDS>, Error> {
+    let (_model, select_groups) = get_select_groups(gds, model_name)?;
+    let filter =
+        select_groups
+            .filter
+            .as_ref()
+            .ok_or_else(|| Error::InternalModelGroupsTypeNotFound {
+                model_name: model_name.clone(),
+                type_name: graphql_type_name.clone(),
+            })?;
+    let aggregate_expression = get_aggregate_expression(gds, select_groups)?;
+
+    let mut fields = BTreeMap::new();
+    for (field_name, operator) in &filter.count_comparison_operators {
+        let field_type = input_type::get_input_type(
+            gds,
+            builder,
+            &QualifiedTypeReference {
+                underlying_type: QualifiedBaseType::Named(
+                    aggregate_expression.count.result_type.clone(),
+                ),
+                nullable: true,
+            },
+        )?;
+        fields.insert(
+            field_name.clone(),
+            builder.allow_all_namespaced(gql_schema::InputField::<GDS>::new(
+                field_name.clone(),
+                None,
+                Annotation::Input(types::InputAnnotation::Model(
+                    ModelInputAnnotation::ModelGroupsCountComparisonOperator {
+                        operator: operator.clone(),
+                    },
+                )),
+                field_type,
+                None,
+                gql_schema::DeprecationStatus::NotDeprecated,
+            )),
+        );
+    }
+
+    Ok(gql_schema::TypeInfo::InputObject(
+        gql_schema::InputObject::new(
+            graphql_type_name.clone(),
+            None,
+            fields,
+            vec![], // Directives
+        ),
+    ))
+}
+
+/// The ordering of groups, by the fields of their key or the number of objects in them
+pub fn build_model_groups_order_by_input_type(
+    gds: &GDS,
+    builder: &mut gql_schema::Builder<GDS>,
+    model_name: &Qualified<ModelName>,
+    graphql_type_name: &ast::TypeName,
+) -> Result<gql_schema::TypeInfo<GDS>, Error> {
+    let (model, select_groups) = get_select_groups(gds, model_name)?;
+    let object_type = output_type::get_object_type_representation(gds, &model.model.data_type)?;
+    let order_by_input_config = gds
+        .metadata
+        .graphql_config
+        .order_by_input
+        .as_ref()
+        .ok_or_else(|| Error::InternalNoOrderByGraphqlConfigOrderByEnumType {
+            type_name: graphql_type_name.clone(),
+        })?;
+    let order_by_enum_type = || TypeId::OrderByEnumType {
+        graphql_type_name: order_by_input_config.enum_type_name.clone(),
+    };
+
+    let mut fields = BTreeMap::new();
+    for (field_name, field_definition) in groupable_fields(gds, object_type)? {
+        let graphql_field_name = metadata_resolve::mk_name(field_name.as_str())
+            .map_err(metadata_resolve::Error::from)
+            .map_err(metadata_resolve::WithContext::from)?;
+        let input_field = gql_schema::InputField::<GDS>::new(
+            graphql_field_name.clone(),
+            None,
+            Annotation::Input(types::InputAnnotation::Model(
+                ModelInputAnnotation::ModelGroupsOrderByKeyField {
+                    field_name: field_name.clone(),
+                    parent_type: model.model.data_type.clone(),
+                    deprecated: field_definition.deprecated.clone(),
+                },
+            )),
+            TypeContainer::named_null(builder.register_type(order_by_enum_type())),
+            None,
+            mk_deprecation_status(field_definition.deprecated.as_ref()),
+        );
+        fields.insert(
+            graphql_field_name,
+            builder
+                .conditional_namespaced(input_field, field_allowed_roles(object_type, field_name)),
+        );
+    }
+
+    if let Some(count_field_name) = count_field_name(gds, select_groups)? {
+        let input_field = gql_schema::InputField::<GDS>::new(
+            count_field_name.clone(),
+            None,
+            Annotation::Input(types::InputAnnotation::Model(
+                ModelInputAnnotation::ModelGroupsOrderByCount,
+            )),
+            TypeContainer::named_null(builder.register_type(order_by_enum_type())),
+            None,
+            gql_schema::DeprecationStatus::NotDeprecated,
+        );
+        if fields
+            .insert(
+                count_field_name.clone(),
+                builder.allow_all_namespaced(input_field),
+            )
+            .is_some()
+        {
+            return Err(Error::GroupKeyFieldNameConflict {
+                model_name: model.model.name.clone(),
+                field_name: count_field_name.clone(),
+            });
+        }
+    }
+
+    Ok(gql_schema::TypeInfo::InputObject(
+        gql_schema::InputObject::new(
+            graphql_type_name.clone(),
+            None,
+            fields,
+            vec![], // Directives
+        ),
+    ))
+}
+
+/// The fields of the model's object type that groups can be formed by, which are the
+/// scalar fields that do not take arguments
+fn groupable_fields<'a>(
+    gds: &GDS,
+    object_type: &'a metadata_resolve::ObjectTypeWithRelationships,
+) -> Result<Vec<(&'a FieldName, &'a metadata_resolve::FieldDefinition)>, Error> {
+    let mut fields = Vec::new();
+    for (field_name, field_definition) in &object_type.object_type.fields {
+        if field_definition.field_arguments.is_empty()
+            && !matches!(
+                field_definition.field_type.underlying_type,
+                QualifiedBaseType::List(_)
+            )
+            && output_type::get_type_kind(gds, &field_definition.field_type)? == TypeKind::Scalar
+        {
+            fields.push((field_name, field_definition));
+        }
+    }
+    Ok(fields)
+}
+
+/// Only allow grouping by a field if the type permissions allow it
+fn field_allowed_roles(
+    object_type: &metadata_resolve::ObjectTypeWithRelationships,
+    field_name: &FieldName,
+) -> HashMap<Role, Option<NamespaceAnnotation>> {
+    object_type
+        .type_output_permissions
+        .iter()
+        .filter(|(_role, perms)| perms.allowed_fields.contains(field_name))
+        .map(|(role, _perms)| (role.clone(), None))
+        .collect()
+}
+
+fn get_aggregate_expression<'s>(
+    gds: &'s GDS,
+    select_groups: &metadata_resolve::SelectGroupsGraphQlDefinition,
+) -> Result<&'s metadata_resolve::AggregateExpression, Error> {
+    gds.metadata
+        .aggregate_expressions
+        .get(&select_groups.aggregate_expression_name)
+        .ok_or_else(|| Error::InternalAggregateExpressionNotFound {
+            aggregate_expression: select_groups.aggregate_expression_name.clone(),
+        })
+}
+
+/// The name of the field that the number of objects in a group is filtered and ordered by, which
+/// is the name of the count aggregate in the group's aggregates
+fn count_field_name<'s>(
+    gds: &'s GDS,
+    select_groups: &metadata_resolve::SelectGroupsGraphQlDefinition,
+) -> Result<Option<&'s ast::Name>, Error> {
+    let aggregate_expression = get_aggregate_expression(gds, select_groups)?;
+    Ok(aggregate_expression
+        .graphql
+        .as_ref()
+        .filter(|_| aggregate_expression.count.enable)
+        .map(|graphql| &graphql.count_field_name))
+}
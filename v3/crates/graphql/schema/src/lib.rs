@@ -4,6 +4,7 @@ mod apollo_federation;
 mod boolean_expression;
 mod commands;
 mod field_arguments;
+mod groups;
 mod model_arguments;
 mod model_filter;
 mod model_filter_input;
@@ -33,10 +34,11 @@ use open_dds::{
 };
 
 pub use aggregates::{AggregateOutputAnnotation, AggregationFunctionAnnotation};
+pub use groups::GroupsOutputAnnotation;
 pub use types::output_type::relationship::{
     CommandRelationshipAnnotation, CommandTargetSource, FilterRelationshipAnnotation,
-    ModelAggregateRelationshipAnnotation, ModelRelationshipAnnotation,
-    OrderByRelationshipAnnotation,
+    ModelAggregateRelationshipAnnotation, ModelGroupsRelationshipAnnotation,
+    ModelRelationshipAnnotation, OrderByRelationshipAnnotation,
 };
 pub use types::{
     Annotation, ApolloFederationRootFields, BooleanExpressionAnnotation,
@@ -253,6 +255,57 @@ impl gql_schema::SchemaContext for GDS {
                 model_name,
                 graphql_type_name,
             ),
+            types::TypeId::ModelGroupsOutputType {
+                model_name,
+                graphql_type_name,
+            } => {
+                groups::build_model_groups_output_type(self, builder, model_name, graphql_type_name)
+            }
+            types::TypeId::ModelGroupKeyOutputType {
+                model_name,
+                graphql_type_name,
+            } => groups::build_model_group_key_output_type(
+                self,
+                builder,
+                model_name,
+                graphql_type_name,
+            ),
+            types::TypeId::ModelGroupKeyExtractionEnumType {
+                model_name,
+                graphql_type_name,
+            } => groups::build_model_group_key_extraction_enum_type(
+                self,
+                builder,
+                model_name,
+                graphql_type_name,
+            ),
+            types::TypeId::ModelGroupsFilterInputType {
+                model_name,
+                graphql_type_name,
+            } => groups::build_model_groups_filter_input_type(
+                self,
+                builder,
+                model_name,
+                graphql_type_name,
+            ),
+            types::TypeId::ModelGroupsCountComparisonInputType {
+                model_name,
+                graphql_type_name,
+            } => groups::build_model_groups_count_comparison_input_type(
+                self,
+                builder,
+                model_name,
+                graphql_type_name,
+            ),
+            types::TypeId::ModelGroupsOrderByInputType {
+                model_name,
+                graphql_type_name,
+            } => groups::build_model_groups_order_by_input_type(
+                self,
+                builder,
+                model_name,
+                graphql_type_name,
+            ),
         }
     }
 
@@ -333,6 +386,11 @@ pub enum Error {
         aggregate_expression: Qualified<AggregateExpressionName>,
         field_name: ast::Name,
     },
+    #[error("the group key field {field_name} of model {model_name} conflicts with another field of its group key. Rename the field holding the extracted parts")]
+    GroupKeyFieldNameConflict {
+        model_name: Qualified<ModelName>,
+        field_name: ast::Name,
+    },
     #[error("internal error: duplicate aggregatable field {field_name} in the aggregate expression {aggregate_expression} is named {field_name}")]
     InternalDuplicateAggregatableField {
         aggregate_expression: Qualified<AggregateExpressionName>,
@@ -348,6 +406,13 @@ pub enum Error {
     InternalErrorDuplicateEntitySourceFound { type_name: ast::TypeName },
     #[error("internal error while building schema, model not found: {model_name}")]
     InternalModelNotFound { model_name: Qualified<ModelName> },
+    #[error("internal error while building schema, groups graphql api not found for model: {model_name}")]
+    InternalModelGroupsGraphQlApiNotFound { model_name: Qualified<ModelName> },
+    #[error("internal error while building schema, groups graphql type {type_name} not found for model: {model_name}")]
+    InternalModelGroupsTypeNotFound {
+        model_name: Qualified<ModelName>,
+        type_name: ast::TypeName,
+    },
     #[error("internal error while building schema, order by expression not found: {order_by_expression_identifier}")]
     InternalOrderByExpressionNotFound {
         order_by_expression_identifier: Qualified<OrderByExpressionIdentifier>,
@@ -299,7 +299,9 @@ fn build_orderable_relationships(
                 relationship_type,
                 target_typename,
                 mappings,
+                value_mappings: _,
                 relationship_aggregate: _,
+                groups_field_name: _,
             } = model_relationship_target.as_ref();
             let target_model = gds.metadata.models.get(model_name).ok_or_else(|| {
                 crate::Error::InternalModelNotFound {
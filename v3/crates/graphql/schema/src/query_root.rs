@@ -14,6 +14,7 @@ use self::node_field::RelayNodeFieldOutput;
 pub mod apollo_federation;
 pub mod node_field;
 pub mod select_aggregate;
+pub mod select_groups;
 pub mod select_many;
 pub mod select_one;
 
@@ -55,6 +56,15 @@ pub fn query_root_schema(
             )?;
             fields.insert(field_name, field);
         }
+        if let Some(select_groups) = &model.graphql_api.select_groups {
+            let (field_name, field) = select_groups::select_groups_field(
+                builder,
+                model,
+                select_groups,
+                query_root_type_name,
+            )?;
+            fields.insert(field_name, field);
+        }
     }
 
     // Add node field for only the commands which have a query root field
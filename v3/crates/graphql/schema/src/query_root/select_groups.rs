@@ -0,0 +1,127 @@
+//! Schema for 'select_groups' operation
+//!
+//! A 'select_groups' operation groups the model's data and fetches aggregations over each group
+//!
+use std::collections::BTreeMap;
+
+use lang_graphql::{ast::common as ast, schema as gql_schema};
+
+use crate::groups::get_groups_output_type;
+use crate::model_filter_input::{add_limit_input_field, add_offset_input_field};
+use crate::query_root::select_aggregate::generate_select_aggregate_arguments;
+use crate::types::{self, Annotation};
+use crate::{mk_deprecation_status, permissions, Error, RootFieldAnnotation, RootFieldKind, GDS};
+use metadata_resolve;
+
+pub(crate) fn select_groups_field(
+    builder: &mut gql_schema::Builder<GDS>,
+    model: &metadata_resolve::ModelWithPermissions,
+    select_groups: &metadata_resolve::SelectGroupsGraphQlDefinition,
+    parent_type: &ast::TypeName,
+) -> Result<
+    (
+        ast::Name,
+        gql_schema::Namespaced<GDS, gql_schema::Field<GDS>>,
+    ),
+    Error,
+> {
+    let root_field_name = select_groups.query_root_field.clone();
+
+    let arguments = generate_select_groups_arguments(
+        builder,
+        model,
+        select_groups,
+        &select_groups.query_root_field,
+        parent_type,
+    )?;
+
+    let field_permissions = permissions::get_select_permissions_namespace_annotations(model);
+
+    let output_typename = get_groups_output_type(builder, model, select_groups);
+
+    let field = builder.conditional_namespaced(
+        gql_schema::Field::new(
+            root_field_name.clone(),
+            select_groups.description.clone(),
+            Annotation::Output(types::OutputAnnotation::RootField(
+                RootFieldAnnotation::Model {
+                    data_type: model.model.data_type.clone(),
+                    kind: RootFieldKind::SelectGroups,
+                    name: model.model.name.clone(),
+                },
+            )),
+            ast::TypeContainer::list_non_null(ast::TypeContainer::named_non_null(output_typename)),
+            arguments,
+            mk_deprecation_status(select_groups.deprecated.as_ref()),
+        ),
+        field_permissions,
+    );
+    Ok((root_field_name, field))
+}
+
+/// Generates the arguments of a 'select_groups' operation, which are those of a
+/// 'select_aggregate' operation along with the filter, ordering, limit and offset of the groups.
+pub fn generate_select_groups_arguments(
+    builder: &mut gql_schema::Builder<GDS>,
+    model: &metadata_resolve::ModelWithPermissions,
+    select_groups: &metadata_resolve::SelectGroupsGraphQlDefinition,
+    parent_field_name: &ast::Name,
+    parent_type: &ast::TypeName,
+) -> Result<BTreeMap<ast::Name, gql_schema::Namespaced<GDS, gql_schema::InputField<GDS>>>, Error> {
+    let mut arguments = generate_select_aggregate_arguments(
+        builder,
+        model,
+        &select_groups.filter_input_field_name,
+        parent_field_name,
+        parent_type,
+    )?;
+
+    if let Some(filter) = &select_groups.filter {
+        let filter_argument = gql_schema::InputField::new(
+            filter.argument_name.clone(),
+            Some("Filters the groups".to_string()),
+            Annotation::Input(types::InputAnnotation::Model(
+                types::ModelInputAnnotation::ModelGroupsFilterArgument,
+            )),
+            ast::TypeContainer::named_null(builder.register_type(
+                types::TypeId::ModelGroupsFilterInputType {
+                    model_name: model.model.name.clone(),
+                    graphql_type_name: filter.input_type_name.clone(),
+                },
+            )),
+            None,
+            gql_schema::DeprecationStatus::NotDeprecated,
+        );
+        arguments.insert(
+            filter.argument_name.clone(),
+            builder.allow_all_namespaced(filter_argument),
+        );
+    }
+
+    if let Some(order_by) = &select_groups.order_by {
+        let order_by_argument = gql_schema::InputField::new(
+            order_by.argument_name.clone(),
+            Some("Orders the groups".to_string()),
+            Annotation::Input(types::InputAnnotation::Model(
+                types::ModelInputAnnotation::ModelGroupsOrderByArgument,
+            )),
+            ast::TypeContainer::list_null(ast::TypeContainer::named_non_null(
+                builder.register_type(types::TypeId::ModelGroupsOrderByInputType {
+                    model_name: model.model.name.clone(),
+                    graphql_type_name: order_by.input_type_name.clone(),
+                }),
+            )),
+            None,
+            gql_schema::DeprecationStatus::NotDeprecated,
+        );
+        arguments.insert(
+            order_by.argument_name.clone(),
+            builder.allow_all_namespaced(order_by_argument),
+        );
+    }
+
+    add_limit_input_field(&mut arguments, builder, model)?;
+    add_offset_input_field(&mut arguments, builder, model)?;
+
+    Ok(arguments)
+}
@@ -78,6 +78,7 @@ pub enum RootFieldKind {
     SelectOne,
     SelectMany,
     SelectAggregate,
+    SelectGroups,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -178,12 +179,14 @@ pub enum OutputAnnotation {
     },
     RelationshipToModel(output_type::relationship::ModelRelationshipAnnotation),
     RelationshipToModelAggregate(output_type::relationship::ModelAggregateRelationshipAnnotation),
+    RelationshipToModelGroups(output_type::relationship::ModelGroupsRelationshipAnnotation),
     RelationshipToCommand(output_type::relationship::CommandRelationshipAnnotation),
     RelayNodeInterfaceID {
         typename_mappings: HashMap<ast::TypeName, Vec<types::FieldName>>,
     },
     SDL,
     Aggregate(crate::aggregates::AggregateOutputAnnotation),
+    Groups(crate::groups::GroupsOutputAnnotation),
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Display)]
@@ -224,6 +227,25 @@ pub enum ModelInputAnnotation {
         ndc_column: Option<NdcColumnForComparison>,
     },
     ModelFilterInputArgument,
+    ModelGroupKeyExtractionArgument,
+    ModelGroupKeyExtractionFunction {
+        function: open_dds::query::ExtractionFunction,
+    },
+    ModelGroupsFilterArgument,
+    ModelGroupsFilterLogicalOperator(LogicalOperatorField),
+    ModelGroupsFilterCount,
+    ModelGroupsCountComparisonOperator {
+        operator: open_dds::query::ComparisonOperator,
+    },
+    ModelGroupsOrderByArgument,
+    ModelGroupsOrderByKeyField {
+        field_name: types::FieldName,
+        /// The parent type is required to report field usage while analyzing query usage.
+        parent_type: Qualified<types::CustomTypeName>,
+        /// To mark a field as deprecated in the field usage while reporting query usage analytics.
+        deprecated: Option<Deprecated>,
+    },
+    ModelGroupsOrderByCount,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Display)]
@@ -433,6 +455,30 @@ pub enum TypeId {
         model_name: Qualified<models::ModelName>,
         graphql_type_name: ast::TypeName,
     },
+    ModelGroupsOutputType {
+        model_name: Qualified<models::ModelName>,
+        graphql_type_name: ast::TypeName,
+    },
+    ModelGroupKeyOutputType {
+        model_name: Qualified<models::ModelName>,
+        graphql_type_name: ast::TypeName,
+    },
+    ModelGroupKeyExtractionEnumType {
+        model_name: Qualified<models::ModelName>,
+        graphql_type_name: ast::TypeName,
+    },
+    ModelGroupsFilterInputType {
+        model_name: Qualified<models::ModelName>,
+        graphql_type_name: ast::TypeName,
+    },
+    ModelGroupsCountComparisonInputType {
+        model_name: Qualified<models::ModelName>,
+        graphql_type_name: ast::TypeName,
+    },
+    ModelGroupsOrderByInputType {
+        model_name: Qualified<models::ModelName>,
+        graphql_type_name: ast::TypeName,
+    },
 }
 
 #[derive(Serialize, Clone, Debug, Hash, PartialEq, Eq)]
@@ -481,6 +527,24 @@ impl TypeId {
             }
             | TypeId::ModelFilterInputType {
                 graphql_type_name, ..
+            }
+            | TypeId::ModelGroupsOutputType {
+                graphql_type_name, ..
+            }
+            | TypeId::ModelGroupKeyOutputType {
+                graphql_type_name, ..
+            }
+            | TypeId::ModelGroupKeyExtractionEnumType {
+                graphql_type_name, ..
+            }
+            | TypeId::ModelGroupsFilterInputType {
+                graphql_type_name, ..
+            }
+            | TypeId::ModelGroupsCountComparisonInputType {
+                graphql_type_name, ..
+            }
+            | TypeId::ModelGroupsOrderByInputType {
+                graphql_type_name, ..
             } => graphql_type_name.clone(),
             TypeId::NodeRoot => ast::TypeName(mk_name!("Node")),
             TypeId::ModelArgumentsInput { type_name, .. } => type_name.clone(),
@@ -6,7 +6,7 @@ use open_dds::{
     relationships,
     types::{CustomTypeName, FieldName, InbuiltType},
 };
-use relationship::ModelAggregateRelationshipAnnotation;
+use relationship::{ModelAggregateRelationshipAnnotation, ModelGroupsRelationshipAnnotation};
 use std::collections::{BTreeMap, HashMap, HashSet};
 
 use self::relationship::{
@@ -17,8 +17,9 @@ use super::{Annotation, PossibleApolloFederationTypes, TypeId};
 use crate::commands::generate_command_argument;
 use crate::field_arguments::generate_field_argument;
 use crate::query_root::select_aggregate;
+use crate::query_root::select_groups;
 use crate::query_root::select_many::generate_select_many_arguments;
-use crate::{aggregates, mk_deprecation_status, permissions};
+use crate::{aggregates, groups, mk_deprecation_status, permissions};
 use crate::{Role, GDS};
 use metadata_resolve::{self, mk_name};
 use metadata_resolve::{get_type_representation, TypeRepresentation};
@@ -284,6 +285,7 @@ fn add_relationship_fields(
                 let ModelRelationshipFields {
                     field,
                     aggregate_field,
+                    groups_field,
                 } = model_relationship_fields(
                     model_relationship_target,
                     builder,
@@ -296,6 +298,9 @@ fn add_relationship_fields(
                 if let Some(aggregate_field) = aggregate_field {
                     relationship_fields.push(aggregate_field);
                 }
+                if let Some(groups_field) = groups_field {
+                    relationship_fields.push(groups_field);
+                }
             }
         }
         for relationship_field in relationship_fields {
@@ -338,6 +343,12 @@ fn command_relationship_field(
         .mappings
         .iter()
         .map(|mapping| &mapping.argument_name)
+        .chain(
+            command_relationship_target
+                .value_mappings
+                .iter()
+                .map(|value_mapping| &value_mapping.argument_name),
+        )
         .collect::<HashSet<_>>();
     let arguments = command
         .command
@@ -365,6 +376,7 @@ fn command_relationship_field(
                         &command_relationship_target.target_type,
                     )?,
                     mappings: command_relationship_target.mappings.clone(),
+                    value_mappings: command_relationship_target.value_mappings.clone(),
                     deprecated: relationship.deprecated.clone(),
                 },
             )),
@@ -386,6 +398,8 @@ struct ModelRelationshipFields {
     field: gql_schema::Namespaced<GDS, gql_schema::Field<GDS>>,
     // aggregate relationship field applicable only for an array relationship
     aggregate_field: Option<gql_schema::Namespaced<GDS, gql_schema::Field<GDS>>>,
+    // groups relationship field applicable only for an array relationship
+    groups_field: Option<gql_schema::Namespaced<GDS, gql_schema::Field<GDS>>>,
 }
 
 /// Create a model relationship field
@@ -439,6 +453,7 @@ fn model_relationship_fields(
                     target_type: model_relationship_target.target_typename.clone(),
                     relationship_type: model_relationship_target.relationship_type.clone(),
                     mappings: model_relationship_target.mappings.clone(),
+                    value_mappings: model_relationship_target.value_mappings.clone(),
                     deprecated: relationship.deprecated.clone(),
                 },
             )),
@@ -465,6 +480,28 @@ fn model_relationship_fields(
                 &aggregate.field_name,
                 &model_relationship_target.target_typename,
                 &model_relationship_target.mappings,
+                &model_relationship_target.value_mappings,
+                relationship,
+                object_type_representation,
+                parent_graphql_type_name,
+            )
+        })
+        .transpose()?;
+    // the groups field reuses the types of the target model's groups API, so
+    // it is only available if the target model has one
+    let groups_field = model_relationship_target
+        .groups_field_name
+        .as_ref()
+        .zip(model.graphql_api.select_groups.as_ref())
+        .map(|(groups_field_name, select_groups)| {
+            model_groups_relationship_field(
+                select_groups,
+                builder,
+                gds,
+                model,
+                groups_field_name,
+                &model_relationship_target.target_typename,
+                &model_relationship_target.mappings,
                 relationship,
                 object_type_representation,
                 parent_graphql_type_name,
@@ -474,6 +511,7 @@ fn model_relationship_fields(
     Ok(ModelRelationshipFields {
         field,
         aggregate_field,
+        groups_field,
     })
 }
 
@@ -486,6 +524,7 @@ fn model_aggregate_relationship_field(
     aggregate_field_name: &ast::Name,
     target_typename: &Qualified<CustomTypeName>,
     mappings: &[metadata_resolve::RelationshipModelMapping],
+    value_mappings: &[metadata_resolve::RelationshipModelValueMapping],
     relationship: &metadata_resolve::RelationshipField,
     object_type_representation: &metadata_resolve::ObjectTypeWithRelationships,
     parent_graphql_type_name: &ast::TypeName,
@@ -525,6 +564,7 @@ fn model_aggregate_relationship_field(
                     target_capabilities: relationship.target_capabilities.clone(),
                     target_type: target_typename.clone(),
                     mappings: mappings.to_vec(),
+                    value_mappings: value_mappings.to_vec(),
                     deprecated: relationship.deprecated.clone(),
                 },
             )),
@@ -542,6 +582,63 @@ fn model_aggregate_relationship_field(
     Ok(field)
 }
 
+/// Create a model groups relationship field
+fn model_groups_relationship_field(
+    select_groups: &metadata_resolve::SelectGroupsGraphQlDefinition,
+    builder: &mut gql_schema::Builder<GDS>,
+    gds: &GDS,
+    target_model: &metadata_resolve::ModelWithPermissions,
+    groups_field_name: &ast::Name,
+    target_typename: &Qualified<CustomTypeName>,
+    mappings: &[metadata_resolve::RelationshipModelMapping],
+    relationship: &metadata_resolve::RelationshipField,
+    object_type_representation: &metadata_resolve::ObjectTypeWithRelationships,
+    parent_graphql_type_name: &ast::TypeName,
+) -> Result<gql_schema::Namespaced<GDS, gql_schema::Field<GDS>>, Error> {
+    let groups_output_type = groups::get_groups_output_type(builder, target_model, select_groups);
+
+    let arguments = select_groups::generate_select_groups_arguments(
+        builder,
+        target_model,
+        select_groups,
+        groups_field_name,
+        parent_graphql_type_name,
+    )?;
+
+    let target_object_type_representation =
+        get_object_type_representation(gds, &target_model.model.data_type)?;
+
+    let field = builder.conditional_namespaced(
+        gql_schema::Field::<GDS>::new(
+            groups_field_name.clone(),
+            relationship.description.clone(),
+            Annotation::Output(super::OutputAnnotation::RelationshipToModelGroups(
+                ModelGroupsRelationshipAnnotation {
+                    source_type: relationship.source.clone(),
+                    relationship_name: relationship.relationship_name.clone(),
+                    target_model_name: target_model.model.name.clone(),
+                    target_capabilities: relationship.target_capabilities.clone(),
+                    target_type: target_typename.clone(),
+                    mappings: mappings.to_vec(),
+                    deprecated: relationship.deprecated.clone(),
+                },
+            )),
+            ast::TypeContainer::list_non_null(ast::TypeContainer::named_non_null(
+                groups_output_type,
+            )),
+            arguments,
+            mk_deprecation_status(relationship.deprecated.as_ref()),
+        ),
+        permissions::get_model_relationship_namespace_annotations(
+            target_model,
+            object_type_representation,
+            target_object_type_representation,
+            mappings,
+        ),
+    );
+    Ok(field)
+}
+
 fn generate_apollo_federation_directives(
     apollo_federation_config: &metadata_resolve::ResolvedObjectApolloFederationConfig,
 ) -> Vec<Directive> {
@@ -20,11 +20,24 @@ pub struct ModelRelationshipAnnotation {
     pub target_type: Qualified<CustomTypeName>,
     pub relationship_type: RelationshipType,
     pub mappings: Vec<metadata_resolve::RelationshipModelMapping>,
+    pub value_mappings: Vec<metadata_resolve::RelationshipModelValueMapping>,
     pub deprecated: Option<Deprecated>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct ModelAggregateRelationshipAnnotation {
+    pub source_type: Qualified<CustomTypeName>,
+    pub relationship_name: RelationshipName,
+    pub target_model_name: Qualified<ModelName>,
+    pub target_capabilities: Option<RelationshipCapabilities>,
+    pub target_type: Qualified<CustomTypeName>,
+    pub mappings: Vec<metadata_resolve::RelationshipModelMapping>,
+    pub value_mappings: Vec<metadata_resolve::RelationshipModelValueMapping>,
+    pub deprecated: Option<Deprecated>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ModelGroupsRelationshipAnnotation {
     pub source_type: Qualified<CustomTypeName>,
     pub relationship_name: RelationshipName,
     pub target_model_name: Qualified<ModelName>,
@@ -69,6 +82,7 @@ pub struct CommandRelationshipAnnotation {
     pub target_type: QualifiedTypeReference,
     pub target_base_type_kind: TypeKind,
     pub mappings: Vec<metadata_resolve::RelationshipCommandMapping>,
+    pub value_mappings: Vec<metadata_resolve::RelationshipCommandValueMapping>,
     pub deprecated: Option<Deprecated>,
 }
 
@@ -1,8 +1,12 @@
 use super::types::Model;
 use crate::types::ModelWarning;
 use hasura_authn_core::Role;
-use metadata_resolve::{ModelWithPermissions, ObjectTypeWithRelationships, Qualified};
-use open_dds::types::CustomTypeName;
+use indexmap::IndexMap;
+use metadata_resolve::{
+    CommandWithPermissions, ModelMutationProcedure, ModelMutations, ModelWithPermissions,
+    ObjectTypeWithRelationships, Qualified,
+};
+use open_dds::{commands::CommandName, types::CustomTypeName};
 use std::collections::BTreeMap;
 
 // look at permissions and work out which fields we're allowed to see
@@ -11,6 +15,8 @@ pub fn build_model(
     model: &ModelWithPermissions,
     role: &Role,
     object_types: &BTreeMap<Qualified<CustomTypeName>, ObjectTypeWithRelationships>,
+    model_mutations: Option<&ModelMutations>,
+    commands: &IndexMap<Qualified<CommandName>, CommandWithPermissions>,
 ) -> Result<Model, ModelWarning> {
     // if we have no select permission for the model, ignore it
     if !model.select_permissions.contains_key(role) {
@@ -30,11 +36,58 @@ pub fn build_model(
 
     let data_connector_name = model_source.data_connector.name.clone();
 
+    let unique_identifier = model
+        .graphql_api
+        .select_uniques
+        .iter()
+        .find_map(|select_unique| {
+            let mut field_names = select_unique.unique_identifier.keys();
+            match (field_names.next(), field_names.next()) {
+                (Some(field_name), None) => Some(field_name.clone()),
+                _ => None,
+            }
+        });
+
+    let supports_aggregates = model_source
+        .data_connector
+        .capabilities
+        .supports_aggregates
+        .is_some();
+
     Ok(Model {
         name: model.model.name.clone(),
         description: model.model.raw.description.clone(),
         data_type: model.model.data_type.clone(),
         data_connector_name,
         filter_expression_type: model.filter_expression_type.clone(),
+        unique_identifier,
+        supports_aggregates,
+        mutations: model_mutations.map_or_else(ModelMutations::default, |model_mutations| {
+            build_model_mutations(model_mutations, role, commands)
+        }),
     })
 }
+
+// only keep the procedures that the role is allowed to execute
+fn build_model_mutations(
+    model_mutations: &ModelMutations,
+    role: &Role,
+    commands: &IndexMap<Qualified<CommandName>, CommandWithPermissions>,
+) -> ModelMutations {
+    let allowed_procedure = |procedure: &Option<ModelMutationProcedure>| {
+        procedure
+            .as_ref()
+            .filter(|procedure| {
+                commands
+                    .get(&procedure.command_name)
+                    .and_then(|command| command.permissions.get(role))
+                    .is_some_and(|permission| permission.allow_execution)
+            })
+            .cloned()
+    };
+    ModelMutations {
+        insert: allowed_procedure(&model_mutations.insert),
+        update: allowed_procedure(&model_mutations.update),
+        delete: allowed_procedure(&model_mutations.delete),
+    }
+}
@@ -52,6 +52,7 @@ pub fn build_object_type(
             metadata_resolve::RelationshipTarget::Model(model) => {
                 if object_type_permission_access(role, &model.target_typename, object_types) {
                     target = Some(RelationshipTarget::Model {
+                        model_name: model.model_name.clone(),
                         object_type: model.target_typename.clone(),
                         relationship_type: model.relationship_type.clone(),
                     });
@@ -4,7 +4,7 @@ use crate::types::{RoleWarning, Warning};
 use hasura_authn_core::Role;
 use indexmap::IndexMap;
 use metadata_resolve::{
-    deserialize_qualified_btreemap, serialize_qualified_btreemap, Qualified,
+    deserialize_qualified_btreemap, serialize_qualified_btreemap, ModelMutations, Qualified,
     QualifiedTypeReference, ResolvedObjectBooleanExpressionType,
 };
 use open_dds::{
@@ -50,6 +50,14 @@ pub struct State {
         deserialize_with = "deserialize_qualified_btreemap"
     )]
     pub object_types: BTreeMap<Qualified<CustomTypeName>, ObjectType>,
+    /// the object boolean expression types over the object types the role can see, describing
+    /// the `filter` of models, their nested objects and their relationships
+    #[serde(
+        serialize_with = "serialize_qualified_btreemap",
+        deserialize_with = "deserialize_qualified_btreemap"
+    )]
+    pub boolean_expression_types:
+        BTreeMap<Qualified<CustomTypeName>, ResolvedObjectBooleanExpressionType>,
 }
 
 #[derive(Debug, Deserialize, Serialize, PartialEq)]
@@ -61,6 +69,7 @@ pub struct ObjectType {
 #[derive(Debug, Deserialize, Serialize, PartialEq)]
 pub enum RelationshipTarget {
     Model {
+        model_name: Qualified<ModelName>,
         object_type: Qualified<CustomTypeName>,
         relationship_type: RelationshipType,
     },
@@ -77,7 +86,13 @@ impl State {
             .models
             .iter()
             .filter_map(|(model_name, model)| {
-                match build_model(model, role, &metadata.object_types) {
+                match build_model(
+                    model,
+                    role,
+                    &metadata.object_types,
+                    metadata.model_mutations.get(model_name),
+                    &metadata.commands,
+                ) {
                     Ok(jsonapi_model) => Some((
                         format!("/{}/{}", model_name.subgraph, model_name.name),
                         jsonapi_model,
@@ -119,10 +134,26 @@ impl State {
                 }
             })
             .collect::<BTreeMap<_, _>>();
+
+        let boolean_expression_types = metadata
+            .boolean_expression_types
+            .objects
+            .iter()
+            .filter(|(_, boolean_expression_type)| {
+                object_types.contains_key(&boolean_expression_type.object_type)
+            })
+            .map(|(boolean_expression_type_name, boolean_expression_type)| {
+                (
+                    boolean_expression_type_name.clone(),
+                    boolean_expression_type.clone(),
+                )
+            })
+            .collect();
         (
             Self {
                 routes,
                 object_types,
+                boolean_expression_types,
             },
             warnings,
         )
@@ -163,4 +194,12 @@ pub struct Model {
     pub data_type: Qualified<CustomTypeName>,
     pub data_connector_name: Qualified<DataConnectorName>,
     pub filter_expression_type: Option<ResolvedObjectBooleanExpressionType>,
+    /// the field identifying a single resource of the model in `/<subgraph>/<Model>/<id>` routes,
+    /// taken from the first unique identifier of the model made of a single field
+    pub unique_identifier: Option<FieldName>,
+    /// whether the data connector can count the rows of the model, for `meta.total`
+    pub supports_aggregates: bool,
+    /// the procedures backing `POST`, `PATCH` and `DELETE` requests, limited to those the role
+    /// is allowed to execute
+    pub mutations: ModelMutations,
 }
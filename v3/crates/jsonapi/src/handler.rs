@@ -2,16 +2,17 @@ use std::sync::Arc;
 
 use super::parse;
 use super::process_response;
-use super::types::{RelationshipTree, RequestError};
+use super::types::{InternalError, RelationshipTree, RequestError};
 use crate::catalog::{Catalog, Model, State};
+use axum::body::Bytes;
 use axum::http::{HeaderMap, Method, Uri};
 use engine_types::HttpContext;
 use hasura_authn_core::Session;
-use metadata_resolve::Metadata;
-use plan_types::{NDCQueryExecution, ProcessResponseAs};
+use metadata_resolve::{Metadata, Qualified};
+use open_dds::commands::CommandName;
+use plan_types::{CommandReturnKind, NDCMutationExecution, NDCQueryExecution, ProcessResponseAs};
 use tracing_util::SpanVisibility;
 
-#[allow(clippy::unused_async)]
 pub async fn handler_internal(
     request_headers: Arc<HeaderMap>,
     http_context: Arc<HttpContext>,
@@ -21,114 +22,285 @@ pub async fn handler_internal(
     http_method: Method,
     uri: Uri,
     query_string: jsonapi_library::query::Query,
+    request_body: Bytes,
 ) -> Result<jsonapi_library::api::DocumentData, RequestError> {
-    let tracer = tracing_util::global_tracer();
-
     let state = catalog
         .state_per_role
         .get(&session.role)
         .ok_or_else(|| RequestError::NotFound)?;
 
-    // relationship tree for processing the response
-    let mut relationship_tree = RelationshipTree::default();
-
     // route matching/validation
     match validate_route(state, &uri) {
         None => Err(RequestError::NotFound),
-        Some(model) => {
-            // create the query IR
-            let query_ir = tracer.in_span(
-                "create_query_ir",
-                "Create query IR",
-                SpanVisibility::User,
-                || {
-                    parse::create_query_ir(
-                        model,
-                        &state.object_types,
-                        &http_method,
-                        &uri,
-                        &mut relationship_tree,
-                        &query_string,
-                    )
-                },
-            )?;
-
-            // execute the query with the query-engine
-            let rowsets = tracer
-                .in_span_async(
-                    "query_engine_execute",
-                    "Execute query",
-                    SpanVisibility::User,
-                    || {
-                        Box::pin(query_engine_execute(
-                            &query_ir.query_request,
-                            &metadata,
-                            &session,
-                            &http_context,
-                            &request_headers,
-                        ))
-                    },
+        Some(model) => match http_method {
+            Method::GET => {
+                handle_query(
+                    &request_headers,
+                    &http_context,
+                    &session,
+                    state,
+                    model,
+                    &metadata,
+                    &http_method,
+                    &uri,
+                    &query_string,
                 )
-                .await?;
-
-            // process result to JSON:API compliant response
-            tracer.in_span(
-                "process_response",
-                "Process response",
-                SpanVisibility::User,
-                || {
-                    process_response::process_result(
-                        rowsets,
-                        &query_ir.root_type_name,
-                        &relationship_tree,
-                        &query_string,
-                        &state.object_types,
-                    )
-                },
-            )
-        }
+                .await
+            }
+            Method::POST | Method::PATCH | Method::DELETE => {
+                handle_mutation(
+                    &request_headers,
+                    &http_context,
+                    &session,
+                    state,
+                    model,
+                    &metadata,
+                    &http_method,
+                    &uri,
+                    &query_string,
+                    &request_body,
+                )
+                .await
+            }
+            _ => Err(RequestError::MethodNotAllowed),
+        },
     }
 }
 
+async fn handle_query(
+    request_headers: &HeaderMap,
+    http_context: &Arc<HttpContext>,
+    session: &Session,
+    state: &State,
+    model: &Model,
+    metadata: &Metadata,
+    http_method: &Method,
+    uri: &Uri,
+    query_string: &jsonapi_library::query::Query,
+) -> Result<jsonapi_library::api::DocumentData, RequestError> {
+    let tracer = tracing_util::global_tracer();
+
+    // relationship tree for processing the response
+    let mut relationship_tree = RelationshipTree::default();
+
+    // create the query IR
+    let query_ir = tracer.in_span(
+        "create_query_ir",
+        "Create query IR",
+        SpanVisibility::User,
+        || {
+            parse::create_query_ir(
+                model,
+                &state.routes,
+                &state.object_types,
+                metadata,
+                http_method,
+                uri,
+                &mut relationship_tree,
+                query_string,
+            )
+        },
+    )?;
+
+    // execute the queries with the query-engine
+    let query_results = tracer
+        .in_span_async(
+            "query_engine_execute",
+            "Execute query",
+            SpanVisibility::User,
+            || {
+                Box::pin(query_engine_execute(
+                    &query_ir.query_request,
+                    metadata,
+                    session,
+                    http_context,
+                    request_headers,
+                ))
+            },
+        )
+        .await?;
+
+    // process result to JSON:API compliant response
+    tracer.in_span(
+        "process_response",
+        "Process response",
+        SpanVisibility::User,
+        || {
+            process_response::process_result(
+                query_results,
+                &query_ir.root_type_name,
+                &query_ir.requested_data,
+                &relationship_tree,
+                uri,
+                query_string,
+                &state.object_types,
+            )
+        },
+    )
+}
+
+async fn handle_mutation(
+    request_headers: &HeaderMap,
+    http_context: &Arc<HttpContext>,
+    session: &Session,
+    state: &State,
+    model: &Model,
+    metadata: &Metadata,
+    http_method: &Method,
+    uri: &Uri,
+    query_string: &jsonapi_library::query::Query,
+    request_body: &[u8],
+) -> Result<jsonapi_library::api::DocumentData, RequestError> {
+    let tracer = tracing_util::global_tracer();
+
+    // relationship tree for processing the response
+    let mut relationship_tree = RelationshipTree::default();
+
+    // create the mutation IR, calling the procedure of the model
+    let mutation_ir = tracer.in_span(
+        "create_mutation_ir",
+        "Create mutation IR",
+        SpanVisibility::User,
+        || {
+            parse::create_mutation_ir(
+                model,
+                &state.routes,
+                &state.object_types,
+                http_method,
+                uri,
+                &mut relationship_tree,
+                query_string,
+                request_body,
+            )
+        },
+    )?;
+
+    // execute the procedure with the query-engine
+    let result = tracer
+        .in_span_async(
+            "mutation_engine_execute",
+            "Execute mutation",
+            SpanVisibility::User,
+            || {
+                Box::pin(mutation_engine_execute(
+                    &mutation_ir.query_request,
+                    &mutation_ir.command_name,
+                    metadata,
+                    session,
+                    http_context,
+                    request_headers,
+                ))
+            },
+        )
+        .await?;
+
+    // process result to JSON:API compliant response
+    tracer.in_span(
+        "process_response",
+        "Process response",
+        SpanVisibility::User,
+        || {
+            process_response::process_mutation_result(
+                result,
+                mutation_ir.kind,
+                &mutation_ir.root_type_name,
+                &relationship_tree,
+                uri,
+                query_string,
+                &state.object_types,
+            )
+        },
+    )
+}
+
 fn validate_route<'a>(state: &'a State, uri: &'a Uri) -> Option<&'a Model> {
-    // TODO: to_string() maybe not optimal. Optimize later
-    let uri_s = uri.to_string();
+    // the model route may be followed by the id of a resource and a relationship
+    let path = uri.path();
     for (route, model) in &state.routes {
-        if uri_s.starts_with(route) {
-            return Some(model);
+        if let Some(rest) = path.strip_prefix(route.as_str()) {
+            if rest.is_empty() || rest.starts_with('/') {
+                return Some(model);
+            }
         }
     }
     None
 }
 
+// execute the queries of the request in order, returning the rowsets of each
 async fn query_engine_execute(
     query_ir: &open_dds::query::QueryRequest,
     metadata: &Metadata,
     session: &Session,
     http_context: &Arc<HttpContext>,
     request_headers: &HeaderMap,
-) -> Result<Vec<ndc_models::RowSet>, RequestError> {
+) -> Result<Vec<Vec<ndc_models::RowSet>>, RequestError> {
     let execution_plan = plan::plan_query_request(query_ir, metadata, session, request_headers)
         .map_err(RequestError::PlanError)?;
     match execution_plan {
-        plan::ExecutionPlan::Queries(queries) => match queries.first() {
-            Some((_alias, execution_tree)) => {
+        plan::ExecutionPlan::Queries(queries) => {
+            let mut query_results = Vec::with_capacity(queries.len());
+            for (_alias, execution_tree) in queries {
                 let ndc_query_execution = NDCQueryExecution {
                     execution_span_attribute: "REST",
-                    execution_tree: execution_tree.clone(),
+                    execution_tree,
                     field_span_attribute: "REST".into(),
                     process_response_as: ProcessResponseAs::Array { is_nullable: false },
                 };
-                Ok(
+                query_results.push(
                     execute::resolve_ndc_query_execution(http_context, ndc_query_execution, None)
                         .await
                         .map_err(RequestError::ExecuteError)?,
-                )
+                );
             }
-            None => todo!("handle empty query result in JSONAPI"),
-        },
-        plan::ExecutionPlan::Mutation(_) => {
-            todo!("Executing mutations not implemented in JSONAPI yet")
+            Ok(query_results)
         }
+        plan::ExecutionPlan::Mutation(_) => Err(RequestError::InternalError(
+            InternalError::UnexpectedExecutionPlan,
+        )),
     }
 }
+
+// execute the procedure of a mutation, returning its result
+async fn mutation_engine_execute(
+    query_ir: &open_dds::query::QueryRequest,
+    command_name: &Qualified<CommandName>,
+    metadata: &Metadata,
+    session: &Session,
+    http_context: &Arc<HttpContext>,
+    request_headers: &HeaderMap,
+) -> Result<serde_json::Value, RequestError> {
+    let execution_plan = plan::plan_query_request(query_ir, metadata, session, request_headers)
+        .map_err(RequestError::PlanError)?;
+    let plan::ExecutionPlan::Mutation(execution_tree) = execution_plan else {
+        return Err(RequestError::InternalError(
+            InternalError::UnexpectedExecutionPlan,
+        ));
+    };
+    let data_connector = execution_tree
+        .mutation_execution_plan
+        .data_connector
+        .clone();
+    let ndc_mutation_execution = NDCMutationExecution {
+        execution_tree,
+        process_response_as: ProcessResponseAs::CommandResponse {
+            command_name: Arc::new(command_name.clone()),
+            is_nullable: true,
+            return_kind: CommandReturnKind::Object,
+            response_config: data_connector.response_config.clone(),
+        },
+        data_connector,
+        execution_span_attribute: "REST",
+        field_span_attribute: "REST".into(),
+    };
+    let mutation_response =
+        execute::resolve_ndc_mutation_execution(http_context, ndc_mutation_execution, None)
+            .await
+            .map_err(RequestError::ExecuteError)?;
+    // a single procedure is executed
+    mutation_response
+        .operation_results
+        .into_iter()
+        .next()
+        .map(|ndc_models::MutationOperationResults::Procedure { result }| result)
+        .ok_or(RequestError::InternalError(InternalError::EmptyQuerySet))
+}
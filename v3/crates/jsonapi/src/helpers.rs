@@ -13,6 +13,11 @@ pub fn get_object_type<'a>(
         .ok_or_else(|| ParseError::CannotFindObjectType(object_type_name.clone()))
 }
 
+/// The type of the resources of an object type, as rendered in JSON:API documents.
+pub(crate) fn render_type_name(type_name: &Qualified<CustomTypeName>) -> String {
+    format!("{}_{}", type_name.subgraph, type_name.name)
+}
+
 /// Helper function to convert a type reference to a relationship type.
 pub(crate) fn type_reference_to_relationship_type(
     type_reference: &QualifiedTypeReference,
@@ -1,4 +1,6 @@
-use super::types::{ModelInfo, RelationshipNode, RelationshipTree, RequestError};
+use super::types::{
+    ModelInfo, Pagination, RelationshipNode, RelationshipTree, RequestError, RequestedData,
+};
 use axum::http::{Method, Uri};
 use indexmap::IndexMap;
 use open_dds::{
@@ -15,9 +17,11 @@ use open_dds::{
 use serde::{Deserialize, Serialize};
 mod filter;
 mod include;
+mod mutation;
 use super::helpers::get_object_type;
 use crate::catalog::{Model, ObjectType, RelationshipTarget, Type};
-use metadata_resolve::{unwrap_custom_type_name, Qualified};
+use metadata_resolve::{unwrap_custom_type_name, Metadata, Qualified};
+pub use mutation::{create_mutation_ir, MutationIR};
 use std::collections::BTreeMap;
 
 #[derive(Debug, derive_more::Display, Serialize, Deserialize)]
@@ -33,11 +37,14 @@ pub enum ParseError {
 pub struct QueryIR {
     pub query_request: open_dds::query::QueryRequest,
     pub root_type_name: Qualified<CustomTypeName>,
+    pub requested_data: RequestedData,
 }
 
 pub fn create_query_ir(
     model: &Model,
+    models: &BTreeMap<String, Model>,
     object_types: &BTreeMap<Qualified<CustomTypeName>, ObjectType>,
+    metadata: &Metadata,
     _http_method: &Method,
     uri: &Uri,
     relationship_tree: &mut RelationshipTree,
@@ -47,32 +54,151 @@ pub fn create_query_ir(
     let ModelInfo {
         subgraph,
         name: model_name,
-        unique_identifier: _,
-        relationship: _,
+        unique_identifier,
+        relationship,
     } = parse_url(uri).map_err(RequestError::ParseError)?;
 
     // validate the sparse fields in the query string
     validate_sparse_fields(object_types, query_string)?;
 
+    // the resources of the model are identified by its unique identifier
+    relationship_tree
+        .identifier_field
+        .clone_from(&model.unique_identifier);
+
     // Parse the include relationships
     let include_relationships = query_string
         .include
         .as_ref()
         .map(|include| include::IncludeRelationships::parse(include));
 
+    let Some(unique_identifier) = unique_identifier else {
+        return create_collection_query_ir(
+            model,
+            models,
+            object_types,
+            metadata,
+            subgraph,
+            model_name,
+            relationship_tree,
+            query_string,
+            include_relationships.as_ref(),
+        );
+    };
+
+    // fetch the one resource of the model with the unique identifier
+    let filter = unique_identifier_filter(model, object_types, &unique_identifier)?;
+
+    let (field_selection, requested_data) = match relationship.as_slice() {
+        [] => (
+            resolve_field_selection(
+                object_types,
+                models,
+                &model.data_type,
+                relationship_tree,
+                query_string,
+                include_relationships.as_ref(),
+            )?,
+            RequestedData::Resource {
+                id: unique_identifier,
+            },
+        ),
+        [relationships, relationship] if relationships == "relationships" => {
+            let object_type = get_object_type(object_types, &model.data_type)
+                .map_err(RequestError::ParseError)?;
+            let (field_selection, identifier_field) = resolve_relationship_linkage_selection(
+                object_type,
+                models,
+                relationship_tree,
+                relationship,
+            )?;
+            (
+                field_selection,
+                RequestedData::RelationshipLinkage {
+                    relationship: relationship.clone(),
+                    identifier_field,
+                },
+            )
+        }
+        [relationship] => {
+            let object_type = get_object_type(object_types, &model.data_type)
+                .map_err(RequestError::ParseError)?;
+            // the related resources are selected like included resources, and any
+            // `include` applies to them rather than to the resource of the model
+            let related_include = include::IncludeRelationships {
+                include: BTreeMap::from([(relationship.clone(), include_relationships)]),
+            };
+            (
+                resolve_include_relationships(
+                    object_type,
+                    object_types,
+                    models,
+                    relationship_tree,
+                    query_string,
+                    Some(&related_include),
+                )?,
+                RequestedData::RelatedResources {
+                    relationship: relationship.clone(),
+                },
+            )
+        }
+        _ => return Err(RequestError::NotFound),
+    };
+
+    let model_selection = open_dds::query::ModelSelection {
+        selection: field_selection,
+        target: open_dds::query::ModelTarget {
+            arguments: IndexMap::new(),
+            filter: Some(filter),
+            order_by: vec![],
+            limit: None,
+            offset: None,
+            model_name,
+            subgraph,
+        },
+    };
+
+    let queries = IndexMap::from_iter([(
+        open_dds::query::Alias::new(identifier!("jsonapi_model_query")),
+        open_dds::query::Query::Model(model_selection),
+    )]);
+    Ok(QueryIR {
+        query_request: open_dds::query::QueryRequest::V1(open_dds::query::QueryRequestV1 {
+            queries,
+        }),
+        root_type_name: model.data_type.clone(),
+        requested_data,
+    })
+}
+
+fn create_collection_query_ir(
+    model: &Model,
+    models: &BTreeMap<String, Model>,
+    object_types: &BTreeMap<Qualified<CustomTypeName>, ObjectType>,
+    metadata: &Metadata,
+    subgraph: SubgraphName,
+    model_name: ModelName,
+    relationship_tree: &mut RelationshipTree,
+    query_string: &jsonapi_library::query::Query,
+    include_relationships: Option<&include::IncludeRelationships>,
+) -> Result<QueryIR, RequestError> {
     let field_selection = resolve_field_selection(
         object_types,
+        models,
         &model.data_type,
         relationship_tree,
         query_string,
-        include_relationships.as_ref(),
+        include_relationships,
     )?;
 
     // create filters
     let filter_query = match &query_string.filter {
         Some(filter) => {
-            let boolean_expression = filter::build_boolean_expression(model, filter)
-                .map_err(|parse_error| RequestError::ParseError(ParseError::Filter(parse_error)))?;
+            let boolean_expression =
+                filter::build_boolean_expression(model, object_types, models, metadata, filter)
+                    .map_err(|parse_error| {
+                        RequestError::ParseError(ParseError::Filter(parse_error))
+                    })?;
             Ok(Some(boolean_expression))
         }
         None => Ok(None),
@@ -101,6 +227,28 @@ pub fn create_query_ir(
         .and_then(|page| usize::try_from(page.offset).ok())
         .filter(|page| *page > 0);
 
+    // count the resources matching the filters for `meta.total`, if the data connector can
+    let count_query = model.supports_aggregates.then(|| {
+        open_dds::query::Query::ModelAggregate(open_dds::query::ModelAggregateSelection {
+            target: open_dds::query::ModelTarget {
+                arguments: IndexMap::new(),
+                filter: filter_query.clone(),
+                order_by: vec![],
+                limit: None,
+                offset: None,
+                model_name: model_name.clone(),
+                subgraph: subgraph.clone(),
+            },
+            selection: IndexMap::from_iter([(
+                open_dds::query::Name::new("total".into()),
+                open_dds::query::Aggregate {
+                    function: open_dds::query::AggregationFunction::Count {},
+                    operand: None,
+                },
+            )]),
+        })
+    });
+
     // form the model selection
     let model_selection = open_dds::query::ModelSelection {
         selection: field_selection,
@@ -115,32 +263,202 @@ pub fn create_query_ir(
         },
     };
 
-    let queries = IndexMap::from_iter([(
+    let mut queries = IndexMap::from_iter([(
         open_dds::query::Alias::new(identifier!("jsonapi_model_query")),
         open_dds::query::Query::Model(model_selection),
     )]);
+    if let Some(count_query) = count_query {
+        queries.insert(
+            open_dds::query::Alias::new(identifier!("jsonapi_model_count")),
+            count_query,
+        );
+    }
     Ok(QueryIR {
         query_request: open_dds::query::QueryRequest::V1(open_dds::query::QueryRequestV1 {
             queries,
         }),
         root_type_name: model.data_type.clone(),
+        requested_data: RequestedData::Collection(Pagination { limit, offset }),
+    })
+}
+
+// filter selecting the resource whose unique identifier is the `id` of the route
+fn unique_identifier_filter(
+    model: &Model,
+    object_types: &BTreeMap<Qualified<CustomTypeName>, ObjectType>,
+    id: &str,
+) -> Result<open_dds::query::BooleanExpression, RequestError> {
+    let field_name = model.unique_identifier.as_ref().ok_or_else(|| {
+        RequestError::BadRequest(format!(
+            "Model {} has no unique identifier made of a single field",
+            model.name.name
+        ))
+    })?;
+    let object_type =
+        get_object_type(object_types, &model.data_type).map_err(RequestError::ParseError)?;
+    let field_type = object_type.type_fields.get(field_name).ok_or_else(|| {
+        RequestError::BadRequest(format!(
+            "Unique identifier {field_name} of model {} is not accessible",
+            model.name.name
+        ))
+    })?;
+
+    Ok(open_dds::query::BooleanExpression::Comparison {
+        operand: open_dds::query::Operand::Field(open_dds::query::ObjectFieldOperand {
+            target: Box::new(open_dds::query::ObjectFieldTarget {
+                field_name: field_name.clone(),
+                arguments: IndexMap::new(),
+            }),
+            nested: None,
+        }),
+        operator: open_dds::query::ComparisonOperator::Equals,
+        argument: Box::new(open_dds::query::Value::Literal(unique_identifier_value(
+            field_type, id,
+        ))),
     })
 }
 
+// ids in routes are strings, so we parse them according to the representation of the field
+fn unique_identifier_value(field_type: &Type, id: &str) -> serde_json::Value {
+    let is_parsed = |type_representation: &ndc_models::TypeRepresentation| {
+        matches!(
+            type_representation,
+            ndc_models::TypeRepresentation::Boolean
+                | ndc_models::TypeRepresentation::Int8
+                | ndc_models::TypeRepresentation::Int16
+                | ndc_models::TypeRepresentation::Int32
+                | ndc_models::TypeRepresentation::Int64
+                | ndc_models::TypeRepresentation::Float32
+                | ndc_models::TypeRepresentation::Float64
+        )
+    };
+    let parse = match field_type {
+        Type::Scalar(type_representation) => is_parsed(type_representation),
+        Type::ScalarForDataConnector(scalar_type) => {
+            scalar_type.type_representations.iter().any(is_parsed)
+        }
+        Type::List(_) | Type::Object(_) => false,
+    };
+    if parse {
+        if let Ok(value @ (serde_json::Value::Bool(_) | serde_json::Value::Number(_))) =
+            serde_json::from_str(id)
+        {
+            return value;
+        }
+    }
+    serde_json::Value::String(id.to_string())
+}
+
+// select the unique identifier of the resources related through a model relationship
+fn resolve_relationship_linkage_selection(
+    object_type: &ObjectType,
+    models: &BTreeMap<String, Model>,
+    relationship_tree: &mut RelationshipTree,
+    relationship: &str,
+) -> Result<(IndexMap<Alias, ObjectSubSelection>, FieldName), RequestError> {
+    let Some((relationship_name, target)) = object_type
+        .type_relationships
+        .iter()
+        .find(|&(relationship_name, _)| relationship_name.as_str() == relationship)
+    else {
+        return Err(RequestError::BadRequest(format!(
+            "Relationship {relationship} not found"
+        )));
+    };
+    let RelationshipTarget::Model {
+        model_name,
+        object_type: target_type,
+        relationship_type,
+    } = target
+    else {
+        return Err(RequestError::BadRequest(format!(
+            "Relationship {relationship} does not target a model"
+        )));
+    };
+    let identifier_field = model_unique_identifier(models, model_name).ok_or_else(|| {
+        RequestError::BadRequest(format!(
+            "Model {} has no unique identifier made of a single field",
+            model_name.name
+        ))
+    })?;
+
+    let field_name_ident = Identifier::new(identifier_field.as_str())
+        .map_err(|e| RequestError::BadRequest(e.into()))?;
+    let selection = IndexMap::from_iter([(
+        Alias::new(field_name_ident),
+        ObjectSubSelection::Field(open_dds::query::ObjectFieldSelection {
+            target: open_dds::query::ObjectFieldTarget {
+                arguments: IndexMap::new(),
+                field_name: identifier_field.clone(),
+            },
+            selection: None,
+        }),
+    )]);
+
+    relationship_tree.relationships.insert(
+        relationship.to_string(),
+        RelationshipNode {
+            object_type: target_type.clone(),
+            relationship_type: relationship_type.clone(),
+            is_command_relationship: false,
+            nested: RelationshipTree {
+                identifier_field: Some(identifier_field.clone()),
+                ..RelationshipTree::default()
+            },
+        },
+    );
+    let field_alias = Identifier::new(relationship)
+        .map_err(|e| RequestError::BadRequest(format!("Invalid relationship name: {e}")))?;
+    let field_selection = IndexMap::from_iter([(
+        Alias::new(field_alias),
+        ObjectSubSelection::Relationship(RelationshipSelection {
+            target: build_relationship_target(relationship_name.clone()),
+            selection: Some(selection),
+        }),
+    )]);
+    Ok((field_selection, identifier_field))
+}
+
+// The fields of the resources of the relationship tree, including their identifier
 fn resolve_field_selection(
     object_types: &BTreeMap<Qualified<CustomTypeName>, ObjectType>,
+    models: &BTreeMap<String, Model>,
     object_type_name: &Qualified<CustomTypeName>,
     relationship_tree: &mut RelationshipTree,
     query_string: &jsonapi_library::query::Query,
     include_relationships: Option<&include::IncludeRelationships>,
+) -> Result<IndexMap<Alias, ObjectSubSelection>, RequestError> {
+    let identifier_field = relationship_tree.identifier_field.clone();
+    resolve_object_field_selection(
+        object_types,
+        models,
+        object_type_name,
+        identifier_field.as_ref(),
+        relationship_tree,
+        query_string,
+        include_relationships,
+    )
+}
+
+fn resolve_object_field_selection(
+    object_types: &BTreeMap<Qualified<CustomTypeName>, ObjectType>,
+    models: &BTreeMap<String, Model>,
+    object_type_name: &Qualified<CustomTypeName>,
+    identifier_field: Option<&FieldName>,
+    relationship_tree: &mut RelationshipTree,
+    query_string: &jsonapi_library::query::Query,
+    include_relationships: Option<&include::IncludeRelationships>,
 ) -> Result<IndexMap<Alias, ObjectSubSelection>, RequestError> {
     let object_type =
         get_object_type(object_types, object_type_name).map_err(RequestError::ParseError)?;
 
-    // create the selection fields; include all fields of the model output type
+    // create the selection fields; include all fields of the model output type, and the
+    // identifier of the resources even when it is left out of the sparse fields
     let mut selection = IndexMap::new();
     for (field_name, field_type) in &object_type.type_fields {
-        if include_field(query_string, field_name, &object_type_name.name) {
+        if include_field(query_string, field_name, &object_type_name.name)
+            || identifier_field == Some(field_name)
+        {
             let field_name_ident = Identifier::new(field_name.as_str())
                 .map_err(|e| RequestError::BadRequest(e.into()))?;
 
@@ -154,6 +472,7 @@ fn resolve_field_selection(
                     },
                     selection: resolve_nested_field_selection(
                         object_types,
+                        models,
                         relationship_tree,
                         query_string,
                         include_relationships,
@@ -167,6 +486,7 @@ fn resolve_field_selection(
     let mut relationship_fields = resolve_include_relationships(
         object_type,
         object_types,
+        models,
         relationship_tree,
         query_string,
         include_relationships,
@@ -177,6 +497,7 @@ fn resolve_field_selection(
 
 fn resolve_nested_field_selection(
     object_types: &BTreeMap<Qualified<CustomTypeName>, ObjectType>,
+    models: &BTreeMap<String, Model>,
     relationship_tree: &mut RelationshipTree,
     query_string: &jsonapi_library::query::Query,
     include_relationships: Option<&include::IncludeRelationships>,
@@ -186,15 +507,19 @@ fn resolve_nested_field_selection(
         Type::Scalar(_) | Type::ScalarForDataConnector(_) => None,
         Type::List(inner) => resolve_nested_field_selection(
             object_types,
+            models,
             relationship_tree,
             query_string,
             include_relationships,
             inner.as_ref(),
         )?,
         Type::Object(type_name) => {
-            let object_field_selection = resolve_field_selection(
+            // nested objects are attributes of the resource, so have no identifier
+            let object_field_selection = resolve_object_field_selection(
                 object_types,
+                models,
                 type_name,
+                None,
                 relationship_tree,
                 query_string,
                 include_relationships,
@@ -241,6 +566,7 @@ fn validate_sparse_fields(
 fn resolve_include_relationships(
     object_type: &ObjectType,
     object_types: &BTreeMap<Qualified<CustomTypeName>, ObjectType>,
+    models: &BTreeMap<String, Model>,
     relationship_tree: &mut RelationshipTree,
     query_string: &jsonapi_library::query::Query,
     include_relationships: Option<&include::IncludeRelationships>,
@@ -262,17 +588,23 @@ fn resolve_include_relationships(
                 .map_err(|e| RequestError::BadRequest(format!("Invalid relationship name: {e}")))?;
 
             let mut is_command_relationship = false;
-            let (target_type, relationship_type) = match &target {
+            let (target_type, relationship_type, identifier_field) = match &target {
                 RelationshipTarget::Model {
+                    model_name,
                     object_type,
                     relationship_type,
-                } => (object_type, relationship_type.clone()),
+                } => (
+                    object_type,
+                    relationship_type.clone(),
+                    model_unique_identifier(models, model_name),
+                ),
                 RelationshipTarget::Command { type_reference } => {
                     is_command_relationship = true;
                     match unwrap_custom_type_name(type_reference) {
                         Some(object_type) => (
                             object_type,
                             crate::helpers::type_reference_to_relationship_type(type_reference),
+                            None,
                         ),
                         None => {
                             return Err(RequestError::BadRequest(
@@ -283,9 +615,13 @@ fn resolve_include_relationships(
                     }
                 }
             };
-            let mut nested_relationships = RelationshipTree::default();
+            let mut nested_relationships = RelationshipTree {
+                identifier_field,
+                ..RelationshipTree::default()
+            };
             let selection = resolve_field_selection(
                 object_types,
+                models,
                 target_type,
                 &mut nested_relationships,
                 query_string,
@@ -311,6 +647,17 @@ fn resolve_include_relationships(
     Ok(fields)
 }
 
+// the unique identifier of a model, if it is made of a single field
+fn model_unique_identifier(
+    models: &BTreeMap<String, Model>,
+    model_name: &Qualified<ModelName>,
+) -> Option<FieldName> {
+    models
+        .values()
+        .find(|model| &model.name == model_name)
+        .and_then(|model| model.unique_identifier.clone())
+}
+
 fn build_relationship_target(relationship_name: RelationshipName) -> OpenDdRelationshipTarget {
     OpenDdRelationshipTarget {
         relationship_name,
@@ -333,7 +680,7 @@ fn build_relationship_target(relationship_name: RelationshipName) -> OpenDdRelat
 // like we do in GraphQL
 //
 // fields[subgraphAuthors]=author_id,firstName&fields[otherAuthors]=author_id,last_name
-fn include_field(
+pub(crate) fn include_field(
     query_string: &jsonapi_library::query::Query,
     field_name: &FieldName,
     object_type_name: &CustomTypeName,
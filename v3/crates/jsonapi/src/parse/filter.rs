@@ -1,9 +1,18 @@
-use crate::catalog::Model;
+use crate::catalog::{Model, ObjectType, RelationshipTarget, Type};
 use indexmap::IndexMap;
-use metadata_resolve::Qualified;
+use metadata_resolve::{
+    ComparisonExpressionInfo, IncludeLogicalOperators, IsNullOperator, Metadata, ModelSource,
+    ObjectComparisonKind, Qualified, ResolvedObjectBooleanExpressionType, TypeMapping,
+};
 use open_dds::query::{BooleanExpression, ObjectFieldOperand, ObjectFieldTarget, Operand, Value};
-use open_dds::{identifier::Identifier, models::ModelName, types::FieldName};
+use open_dds::{
+    identifier::Identifier,
+    models::ModelName,
+    relationships::RelationshipName,
+    types::{CustomTypeName, FieldName, OperatorName},
+};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum JsonApiFilter {
@@ -13,6 +22,9 @@ enum JsonApiFilter {
     Or {
         or: Vec<JsonApiFilter>,
     },
+    Not {
+        not: Box<JsonApiFilter>,
+    },
     Nested {
         field_name: String,
         rest: Box<JsonApiFilter>,
@@ -26,135 +38,469 @@ enum JsonApiFilter {
 
 #[derive(Debug, derive_more::Display, Serialize, Deserialize)]
 pub enum FilterError {
+    #[display("Model {_0} does not support filtering")]
     NoBooleanExpressionDefined(Qualified<ModelName>),
+    #[display("Model {_0} cannot be filtered as it has no source")]
+    NoModelSource(Qualified<ModelName>),
+    #[display("Invalid filter: {_0}")]
+    InvalidFilter(ParseFilterExpressionError),
+    #[display("Cannot filter on field {field_name} of type {object_type}")]
+    UnknownField {
+        field_name: String,
+        object_type: Qualified<CustomTypeName>,
+    },
+    #[display("Unknown operator ${operator} for field {field_name}")]
+    UnknownOperator {
+        field_name: FieldName,
+        operator: String,
+    },
+    #[display("Field {_0} must be compared with an operator")]
+    ExpectedComparison(FieldName),
+    #[display("Field {_0} must be filtered with a nested filter")]
+    ExpectedNestedFilter(FieldName),
+    #[display("The value of $is_null must be a boolean")]
+    ExpectedBooleanForIsNull,
+    #[display("Logical operators cannot be used in filters on type {_0}")]
+    LogicalOperatorsNotAllowed(Qualified<CustomTypeName>),
+    #[display("Boolean expression type {_0} could not be found")]
+    BooleanExpressionTypeNotFound(Qualified<CustomTypeName>),
+    #[display(
+        "The elements of array field {_0} must be filtered with a single comparison, or $or of them"
+    )]
+    CombinedFilterOnArrayElements(FieldName),
 }
 
 pub fn build_boolean_expression(
     model: &Model,
+    object_types: &BTreeMap<Qualified<CustomTypeName>, ObjectType>,
+    models: &BTreeMap<String, Model>,
+    metadata: &Metadata,
     filter: &serde_json::Value,
 ) -> Result<open_dds::query::BooleanExpression, FilterError> {
     // filters in jsonapi-rust work like
     // `filter={"name":{"$eq":"Horse"},"age":{"$gt":100}}`
     // with multiple expressions that we should && together
 
-    if model.filter_expression_type.is_some() {
-        // only include a filter if the model has a `BooleanExpressionType`
-        let parsed_filter = parse_filter_value(filter).unwrap();
+    // only include a filter if the model has a `BooleanExpressionType`
+    let boolean_expression_type = model
+        .filter_expression_type
+        .as_ref()
+        .ok_or_else(|| FilterError::NoBooleanExpressionDefined(model.name.clone()))?;
+    let model_source = metadata
+        .models
+        .get(&model.name)
+        .and_then(|model| model.model.source.as_deref())
+        .ok_or_else(|| FilterError::NoModelSource(model.name.clone()))?;
+
+    let parsed_filter = parse_filter_value(filter).map_err(FilterError::InvalidFilter)?;
 
-        Ok(expression_from_jsonapi_filter(&parsed_filter).unwrap())
-    } else {
-        Err(FilterError::NoBooleanExpressionDefined(model.name.clone()))
+    FilterContext {
+        object_types,
+        models,
+        metadata,
+        model_source,
+        boolean_expression_type,
+        array_field: None,
     }
+    .expression(&parsed_filter, &[])
 }
 
-// not sure if we should be validating boolean expression against the BooleanExpressionType here
-// or whether we expect that to happen in planning
-fn expression_from_jsonapi_filter(
-    filter: &JsonApiFilter,
-) -> Result<open_dds::query::BooleanExpression, ()> {
-    match filter {
-        JsonApiFilter::Comparison {
-            field_name,
-            comparison_value,
-            operator,
-        } => {
-            let field_name = FieldName::new(Identifier::new(field_name).unwrap());
-
-            let field_operand = Operand::Field(ObjectFieldOperand {
-                target: Box::new(ObjectFieldTarget {
-                    field_name,
-                    arguments: IndexMap::new(),
-                }),
-                nested: None,
-            });
+// we validate the filter against the `BooleanExpressionType`s here, rather than leaving it
+// to planning, so that invalid filters are reported to the user as bad requests
+#[derive(Clone, Copy)]
+struct FilterContext<'a> {
+    object_types: &'a BTreeMap<Qualified<CustomTypeName>, ObjectType>,
+    models: &'a BTreeMap<String, Model>,
+    metadata: &'a Metadata,
+    /// the source of the model we are filtering, which changes when following a relationship
+    model_source: &'a ModelSource,
+    /// the boolean expression type of the object we are filtering, which changes when filtering
+    /// on a nested object or following a relationship
+    boolean_expression_type: &'a ResolvedObjectBooleanExpressionType,
+    /// the array field whose elements we are filtering, if any. Each comparison on the elements
+    /// of an array holds if it holds for any element, so `$and` and `$not` would not apply to the
+    /// same element, and are rejected.
+    array_field: Option<&'a FieldName>,
+}
 
-            let operator = open_dds::types::OperatorName::new(operator.into());
+impl FilterContext<'_> {
+    // `path` is the nested object fields leading from the object of the model to the object
+    // we are filtering
+    fn expression(
+        &self,
+        filter: &JsonApiFilter,
+        path: &[FieldName],
+    ) -> Result<BooleanExpression, FilterError> {
+        match filter {
+            // the fields of a filter are always combined with `$and`, so we allow it even when
+            // logical operators are not
+            JsonApiFilter::And { and } if and.len() > 1 => {
+                self.check_not_array_elements()?;
+                self.check_array_field_comparisons(and)?;
+                Ok(BooleanExpression::And(
+                    and.iter()
+                        .map(|filter| self.expression(filter, path))
+                        .collect::<Result<_, _>>()?,
+                ))
+            }
+            JsonApiFilter::And { and } => Ok(BooleanExpression::And(
+                and.iter()
+                    .map(|filter| self.expression(filter, path))
+                    .collect::<Result<_, _>>()?,
+            )),
+            JsonApiFilter::Or { or } => {
+                self.check_logical_operators()?;
+                Ok(BooleanExpression::Or(
+                    or.iter()
+                        .map(|filter| self.expression(filter, path))
+                        .collect::<Result<_, _>>()?,
+                ))
+            }
+            JsonApiFilter::Not { not } => {
+                self.check_logical_operators()?;
+                self.check_not_array_elements()?;
+                Ok(BooleanExpression::Not(Box::new(
+                    self.expression(not, path)?,
+                )))
+            }
+            JsonApiFilter::Nested { field_name, rest } => {
+                self.nested_expression(field_name, rest, path)
+            }
+            JsonApiFilter::Comparison {
+                field_name,
+                operator,
+                comparison_value,
+            } => self.comparison_expression(field_name, operator, comparison_value, path),
+        }
+    }
 
-            let comparison = BooleanExpression::Comparison {
-                operand: field_operand,
-                argument: Box::new(Value::Literal(comparison_value.clone())),
-                operator: open_dds::query::ComparisonOperator::Custom(operator),
+    fn check_logical_operators(&self) -> Result<(), FilterError> {
+        match self.boolean_expression_type.include_logical_operators {
+            IncludeLogicalOperators::Yes => Ok(()),
+            IncludeLogicalOperators::No => Err(FilterError::LogicalOperatorsNotAllowed(
+                self.boolean_expression_type.object_type.clone(),
+            )),
+        }
+    }
+
+    fn check_not_array_elements(&self) -> Result<(), FilterError> {
+        match self.array_field {
+            Some(array_field) => Err(FilterError::CombinedFilterOnArrayElements(
+                array_field.clone(),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    // several operators on the same array field would each hold for any of its elements, rather
+    // than all hold for the same element
+    fn check_array_field_comparisons(&self, filters: &[JsonApiFilter]) -> Result<(), FilterError> {
+        let mut compared_fields = std::collections::BTreeSet::new();
+        for filter in filters {
+            if let JsonApiFilter::Comparison { field_name, .. } = filter {
+                if !compared_fields.insert(field_name.as_str()) {
+                    let field_name = self.field_name(field_name)?;
+                    let is_array = self.object_type().is_some_and(|object_type| {
+                        matches!(
+                            object_type.type_fields.get(&field_name),
+                            Some(Type::List(_))
+                        )
+                    });
+                    if is_array {
+                        return Err(FilterError::CombinedFilterOnArrayElements(field_name));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn unknown_field(&self, field_name: &str) -> FilterError {
+        FilterError::UnknownField {
+            field_name: field_name.to_string(),
+            object_type: self.boolean_expression_type.object_type.clone(),
+        }
+    }
+
+    // the object type of the object we are filtering, as seen by the role
+    fn object_type(&self) -> Option<&ObjectType> {
+        self.object_types
+            .get(&self.boolean_expression_type.object_type)
+    }
+
+    // the comparable field of the boolean expression type, if the role can see it
+    fn field_name(&self, field_name: &str) -> Result<FieldName, FilterError> {
+        Identifier::new(field_name)
+            .map(FieldName::new)
+            .ok()
+            .filter(|field_name| {
+                self.object_type()
+                    .is_some_and(|object_type| object_type.type_fields.contains_key(field_name))
+            })
+            .ok_or_else(|| self.unknown_field(field_name))
+    }
+
+    fn object_boolean_expression_type(
+        &self,
+        boolean_expression_type_name: &Qualified<CustomTypeName>,
+    ) -> Result<&ResolvedObjectBooleanExpressionType, FilterError> {
+        self.metadata
+            .boolean_expression_types
+            .objects
+            .get(boolean_expression_type_name)
+            .ok_or_else(|| {
+                FilterError::BooleanExpressionTypeNotFound(boolean_expression_type_name.clone())
+            })
+    }
+
+    fn nested_expression(
+        &self,
+        field_name: &str,
+        rest: &JsonApiFilter,
+        path: &[FieldName],
+    ) -> Result<BooleanExpression, FilterError> {
+        let fields = &self.boolean_expression_type.fields;
+
+        // relationships are not fields of the object type, so we look for them first
+        if let Some(relationship_field) = Identifier::new(field_name)
+            .ok()
+            .and_then(|identifier| fields.relationship_fields.get(&FieldName::new(identifier)))
+        {
+            // the role must be able to see the relationship and the model it targets
+            let Some(RelationshipTarget::Model { model_name, .. }) =
+                self.object_type().and_then(|object_type| {
+                    object_type
+                        .type_relationships
+                        .get(&relationship_field.relationship_name)
+                })
+            else {
+                return Err(self.unknown_field(field_name));
+            };
+            if !self.models.values().any(|model| &model.name == model_name) {
+                return Err(self.unknown_field(field_name));
+            }
+            let model_source = self
+                .metadata
+                .models
+                .get(model_name)
+                .and_then(|model| model.model.source.as_deref())
+                .ok_or_else(|| self.unknown_field(field_name))?;
+
+            let target_context = FilterContext {
+                model_source,
+                boolean_expression_type: self
+                    .object_boolean_expression_type(&relationship_field.boolean_expression_type)?,
+                // the predicate on the related objects is a single comparison of the object
+                array_field: None,
+                ..*self
             };
+            let relationship_name = Identifier::new(field_name)
+                .map(RelationshipName::new)
+                .map_err(|_| self.unknown_field(field_name))?;
 
-            Ok(comparison)
+            return Ok(BooleanExpression::Relationship {
+                operand: path_operand(path),
+                relationship_name,
+                predicate: Box::new(target_context.expression(rest, &[])?),
+            });
         }
-        JsonApiFilter::Or { or } => {
-            let mut expressions = vec![];
-            for item in or {
-                let expression = expression_from_jsonapi_filter(item)?;
-                expressions.push(expression);
+
+        let field_name = self.field_name(field_name)?;
+        match fields.object_fields.get_key_value(&field_name) {
+            Some((object_field_name, object_field)) => {
+                let nested_context = FilterContext {
+                    boolean_expression_type: self.object_boolean_expression_type(
+                        &object_field.boolean_expression_type_name,
+                    )?,
+                    array_field: match object_field.field_kind {
+                        ObjectComparisonKind::ObjectArray => Some(object_field_name),
+                        ObjectComparisonKind::Object => self.array_field,
+                    },
+                    ..*self
+                };
+                let mut nested_path = path.to_vec();
+                nested_path.push(field_name);
+                nested_context.expression(rest, &nested_path)
             }
-            Ok(open_dds::query::BooleanExpression::Or(expressions))
+            None if fields.scalar_fields.contains_key(&field_name) => {
+                Err(FilterError::ExpectedComparison(field_name))
+            }
+            None => Err(self.unknown_field(field_name.as_str())),
         }
-        JsonApiFilter::And { and } => {
-            let mut expressions = vec![];
-            for item in and {
-                let expression = expression_from_jsonapi_filter(item)?;
-                expressions.push(expression);
+    }
+
+    fn comparison_expression(
+        &self,
+        field_name: &str,
+        operator: &str,
+        comparison_value: &serde_json::Value,
+        path: &[FieldName],
+    ) -> Result<BooleanExpression, FilterError> {
+        let fields = &self.boolean_expression_type.fields;
+        let field_name = self.field_name(field_name)?;
+        let Some(comparison_info) = fields.scalar_fields.get(&field_name) else {
+            return Err(if fields.object_fields.contains_key(&field_name) {
+                FilterError::ExpectedNestedFilter(field_name)
+            } else {
+                self.unknown_field(field_name.as_str())
+            });
+        };
+        let unknown_operator = || FilterError::UnknownOperator {
+            field_name: field_name.clone(),
+            operator: operator.to_string(),
+        };
+        let operand = field_operand(path, &field_name);
+
+        if operator == "is_null" {
+            if !self.allows_is_null(comparison_info) {
+                return Err(unknown_operator());
             }
-            Ok(open_dds::query::BooleanExpression::And(expressions))
+            let is_null = comparison_value
+                .as_bool()
+                .ok_or(FilterError::ExpectedBooleanForIsNull)?;
+            let expression = BooleanExpression::IsNull(operand);
+            return Ok(if is_null {
+                expression
+            } else {
+                BooleanExpression::Not(Box::new(expression))
+            });
         }
-        JsonApiFilter::Nested { .. } => todo!("Nested fields not supported yet"),
+
+        // operators of the scalar `BooleanExpressionType` are used as they are, and built-in
+        // operators are replaced by the operator the data connector implements them with
+        let (operator_name, negated) = comparison_info
+            .operators
+            .get_key_value(operator)
+            .map(|(operator_name, _)| (operator_name, false))
+            .or_else(|| self.built_in_operator(&field_name, operator, comparison_info))
+            .ok_or_else(unknown_operator)?;
+        let comparison = BooleanExpression::Comparison {
+            operand,
+            operator: open_dds::query::ComparisonOperator::Custom(operator_name.clone()),
+            argument: Box::new(Value::Literal(comparison_value.clone())),
+        };
+        Ok(if negated {
+            BooleanExpression::Not(Box::new(comparison))
+        } else {
+            comparison
+        })
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use serde_json::json;
-    #[test]
-    fn test_parse_filter_value() {
-        let tests = vec![
-            (
-                json!({"name":{"$eq":"Horse"}}),
-                JsonApiFilter::Comparison {
-                    comparison_value: serde_json::Value::from("Horse"),
-                    field_name: "name".to_string(),
-                    operator: "eq".to_string(),
-                },
+    fn allows_is_null(&self, comparison_info: &ComparisonExpressionInfo) -> bool {
+        self.metadata
+            .boolean_expression_types
+            .scalars
+            .get(&comparison_info.boolean_expression_type_name)
+            .is_some_and(|scalar_boolean_expression_type| {
+                matches!(
+                    scalar_boolean_expression_type.is_null_operator,
+                    IsNullOperator::Include { .. }
+                )
+            })
+    }
+
+    // the operator of the scalar `BooleanExpressionType` that maps to the data connector operator
+    // implementing a built-in operator, and whether its result must be negated
+    fn built_in_operator<'b>(
+        &self,
+        field_name: &FieldName,
+        operator: &str,
+        comparison_info: &'b ComparisonExpressionInfo,
+    ) -> Option<(&'b OperatorName, bool)> {
+        let TypeMapping::Object { field_mappings, .. } = self
+            .model_source
+            .type_mappings
+            .get(&self.boolean_expression_type.object_type)?;
+        let comparison_operators = field_mappings
+            .get(field_name)?
+            .comparison_operators
+            .as_ref()?;
+        let ndc_version = self
+            .model_source
+            .data_connector
+            .capabilities
+            .supported_ndc_version;
+
+        let (data_connector_operator, negated) = match operator {
+            "eq" => (comparison_operators.get_eq_operator(ndc_version)?, false),
+            "neq" => (comparison_operators.get_eq_operator(ndc_version)?, true),
+            "lt" => (comparison_operators.get_lt_operator(ndc_version)?, false),
+            "lte" => (comparison_operators.get_lte_operator(ndc_version)?, false),
+            "gt" => (comparison_operators.get_gt_operator(ndc_version)?, false),
+            "gte" => (comparison_operators.get_gte_operator(ndc_version)?, false),
+            "contains" => (
+                comparison_operators.get_contains_operator(ndc_version)?,
+                false,
             ),
-            (
-                json!({"name":{"$eq":"Horse"},"age":{"$eq":1}}),
-                JsonApiFilter::And {
-                    and: vec![
-                        JsonApiFilter::Comparison {
-                            comparison_value: serde_json::Value::from("Horse"),
-                            field_name: "name".to_string(),
-                            operator: "eq".to_string(),
-                        },
-                        JsonApiFilter::Comparison {
-                            comparison_value: serde_json::Value::from(1),
-                            field_name: "age".to_string(),
-                            operator: "eq".to_string(),
-                        },
-                    ],
-                },
+            "icontains" => (
+                comparison_operators.get_icontains_operator(ndc_version)?,
+                false,
             ),
-            (
-                json!({"user":{"name":{"$eq":1}}}),
-                JsonApiFilter::Nested {
-                    field_name: "user".to_string(),
-                    rest: Box::new(JsonApiFilter::Comparison {
-                        comparison_value: serde_json::Value::from(1),
-                        field_name: "name".to_string(),
-                        operator: "eq".to_string(),
-                    }),
-                },
+            "starts_with" => (
+                comparison_operators.get_starts_with_operator(ndc_version)?,
+                false,
             ),
-        ];
+            "istarts_with" => (
+                comparison_operators.get_istarts_with_operator(ndc_version)?,
+                false,
+            ),
+            "ends_with" => (
+                comparison_operators.get_ends_with_operator(ndc_version)?,
+                false,
+            ),
+            "iends_with" => (
+                comparison_operators.get_iends_with_operator(ndc_version)?,
+                false,
+            ),
+            _ => return None,
+        };
 
-        for (input, expected) in tests {
-            assert_eq!(parse_filter_value(&input), Ok(expected));
-        }
+        let operator_mapping = comparison_info
+            .operator_mapping
+            .get(&self.model_source.data_connector.name)?;
+        comparison_info
+            .operators
+            .keys()
+            .find(|operator_name| operator_mapping.get(operator_name) == data_connector_operator)
+            .map(|operator_name| (operator_name, negated))
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
-enum ParseFilterExpressionError {
+// the operand for a field of the object at the end of `path`
+fn field_operand(path: &[FieldName], field_name: &FieldName) -> Operand {
+    path.iter().rev().fold(
+        object_field_operand(field_name, None),
+        |nested, field_name| object_field_operand(field_name, Some(nested)),
+    )
+}
+
+// the operand for the object at the end of `path`, if it is nested in the object of the model
+fn path_operand(path: &[FieldName]) -> Option<Operand> {
+    let (field_name, path) = path.split_last()?;
+    Some(field_operand(path, field_name))
+}
+
+fn object_field_operand(field_name: &FieldName, nested: Option<Operand>) -> Operand {
+    Operand::Field(ObjectFieldOperand {
+        target: Box::new(ObjectFieldTarget {
+            field_name: field_name.clone(),
+            arguments: IndexMap::new(),
+        }),
+        nested: nested.map(Box::new),
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, derive_more::Display, Serialize, Deserialize)]
+pub enum ParseFilterExpressionError {
+    #[display("a filter must be a JSON object")]
     FilterExpressionIsNotObject,
-    MultipleComparisonsInOneObject,
+    #[display("the filter on a field must be a non-empty object")]
     ExpectedNonEmptyObjectForFieldComparison,
+    #[display("operator {_0} must be applied to a field")]
+    UnexpectedOperator(String),
+    #[display("$and must be an array of filters")]
     ExpectedArrayForAnd,
+    #[display("$or must be an array of filters")]
     ExpectedArrayForOr,
 }
 
@@ -203,10 +549,9 @@ fn parse_filter_value(
     match value.as_object() {
         Some(items) => {
             let mut jsonapi_filters = vec![];
-            // assume only one item for now
             for (key, value) in items {
                 let jsonapi_filter = match key.as_str() {
-                    // `$and` and `$or` will need to be configurable in future
+                    // `$and`, `$or` and `$not` will need to be configurable in future
                     "$and" => {
                         // fail if items aren't an array
                         let item_array = value
@@ -233,43 +578,21 @@ fn parse_filter_value(
                             .collect::<Result<Vec<_>, _>>()?;
                         Ok(JsonApiFilter::Or { or: filter_array })
                     }
-                    field_name => {
-                        // if it's not a known operator, assume it's a field name
-                        let item_object = value.as_object().ok_or({
-                            ParseFilterExpressionError::ExpectedNonEmptyObjectForFieldComparison
-                        })?;
-
-                        if item_object.len() > 1 {
-                            return Err(ParseFilterExpressionError::MultipleComparisonsInOneObject);
-                        }
-                        if let Some((operator, comparison_value)) = item_object.iter().next() {
-                            match parse_operator(operator.clone()) {
-                                OperatorOrNested::Operator(parsed_operator) => {
-                                    Ok(JsonApiFilter::Comparison {
-                                        field_name: field_name.to_string(),
-                                        operator: parsed_operator,
-                                        comparison_value: comparison_value.clone(),
-                                    })
-                                }
-                                OperatorOrNested::Nested(_nested_field_name) => {
-                                    Ok(JsonApiFilter::Nested {
-                                        field_name: field_name.to_string(),
-                                        rest: Box::new(parse_filter_value(value)?),
-                                    })
-                                }
-                            }
-                        } else {
-                            Err(ParseFilterExpressionError::MultipleComparisonsInOneObject)
-                        }
-                    }
+                    "$not" => Ok(JsonApiFilter::Not {
+                        not: Box::new(parse_filter_value(value)?),
+                    }),
+                    operator if parse_operator(operator).is_some() => Err(
+                        ParseFilterExpressionError::UnexpectedOperator(operator.to_string()),
+                    ),
+                    field_name => parse_field_filter(field_name, value),
                 }?;
                 jsonapi_filters.push(jsonapi_filter);
             }
 
             // if there is only one filter, skip the AND wrapper
             if jsonapi_filters.len() == 1 {
-                if let Some(jsonapi_filter) = jsonapi_filters.first() {
-                    return Ok(jsonapi_filter.clone());
+                if let Some(jsonapi_filter) = jsonapi_filters.pop() {
+                    return Ok(jsonapi_filter);
                 }
             }
             Ok(JsonApiFilter::And {
@@ -280,16 +603,177 @@ fn parse_filter_value(
     }
 }
 
-enum OperatorOrNested {
-    Operator(String),
-    Nested(String),
+// the filter on a field is either an object of operators, ie `{"$gt": 1, "$lt": 10}`, or a
+// nested filter on the fields of an object or relationship, ie `{"city": {"$eq": "London"}}`
+fn parse_field_filter(
+    field_name: &str,
+    value: &serde_json::Value,
+) -> Result<JsonApiFilter, ParseFilterExpressionError> {
+    let item_object = value
+        .as_object()
+        .filter(|item_object| !item_object.is_empty())
+        .ok_or(ParseFilterExpressionError::ExpectedNonEmptyObjectForFieldComparison)?;
+
+    let is_comparison = item_object.keys().all(|key| {
+        parse_operator(key).is_some() && !matches!(key.as_str(), "$and" | "$or" | "$not")
+    });
+    if !is_comparison {
+        return Ok(JsonApiFilter::Nested {
+            field_name: field_name.to_string(),
+            rest: Box::new(parse_filter_value(value)?),
+        });
+    }
+
+    let mut comparisons = item_object
+        .iter()
+        .filter_map(|(operator, comparison_value)| {
+            parse_operator(operator).map(|operator| JsonApiFilter::Comparison {
+                field_name: field_name.to_string(),
+                operator: operator.to_string(),
+                comparison_value: comparison_value.clone(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    // several operators on the same field must all match
+    if comparisons.len() == 1 {
+        if let Some(comparison) = comparisons.pop() {
+            return Ok(comparison);
+        }
+    }
+    Ok(JsonApiFilter::And { and: comparisons })
 }
 
-// we're either looking at an operator, ie '$eq`, or the name of another nested field
+// we're either looking at an operator, ie '$eq`, or the name of a field
 // only return if it's an operator, and get rid of the `$`
-fn parse_operator(operator: String) -> OperatorOrNested {
-    match operator.strip_prefix("$") {
-        Some(op) => OperatorOrNested::Operator(op.to_string()),
-        None => OperatorOrNested::Nested(operator),
+fn parse_operator(operator: &str) -> Option<&str> {
+    operator.strip_prefix('$')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    #[test]
+    fn test_parse_filter_value() {
+        let tests = vec![
+            (
+                json!({"name":{"$eq":"Horse"}}),
+                JsonApiFilter::Comparison {
+                    comparison_value: serde_json::Value::from("Horse"),
+                    field_name: "name".to_string(),
+                    operator: "eq".to_string(),
+                },
+            ),
+            (
+                json!({"name":{"$eq":"Horse"},"age":{"$eq":1}}),
+                JsonApiFilter::And {
+                    and: vec![
+                        JsonApiFilter::Comparison {
+                            comparison_value: serde_json::Value::from("Horse"),
+                            field_name: "name".to_string(),
+                            operator: "eq".to_string(),
+                        },
+                        JsonApiFilter::Comparison {
+                            comparison_value: serde_json::Value::from(1),
+                            field_name: "age".to_string(),
+                            operator: "eq".to_string(),
+                        },
+                    ],
+                },
+            ),
+            (
+                json!({"user":{"name":{"$eq":1}}}),
+                JsonApiFilter::Nested {
+                    field_name: "user".to_string(),
+                    rest: Box::new(JsonApiFilter::Comparison {
+                        comparison_value: serde_json::Value::from(1),
+                        field_name: "name".to_string(),
+                        operator: "eq".to_string(),
+                    }),
+                },
+            ),
+            (
+                json!({"age":{"$gt":1,"$lt":10}}),
+                JsonApiFilter::And {
+                    and: vec![
+                        JsonApiFilter::Comparison {
+                            comparison_value: serde_json::Value::from(1),
+                            field_name: "age".to_string(),
+                            operator: "gt".to_string(),
+                        },
+                        JsonApiFilter::Comparison {
+                            comparison_value: serde_json::Value::from(10),
+                            field_name: "age".to_string(),
+                            operator: "lt".to_string(),
+                        },
+                    ],
+                },
+            ),
+            (
+                json!({"location":{"city":{"$eq":"London"},"country":{"$eq":"UK"}}}),
+                JsonApiFilter::Nested {
+                    field_name: "location".to_string(),
+                    rest: Box::new(JsonApiFilter::And {
+                        and: vec![
+                            JsonApiFilter::Comparison {
+                                comparison_value: serde_json::Value::from("London"),
+                                field_name: "city".to_string(),
+                                operator: "eq".to_string(),
+                            },
+                            JsonApiFilter::Comparison {
+                                comparison_value: serde_json::Value::from("UK"),
+                                field_name: "country".to_string(),
+                                operator: "eq".to_string(),
+                            },
+                        ],
+                    }),
+                },
+            ),
+            (
+                json!({"$not":{"name":{"$is_null":true}}}),
+                JsonApiFilter::Not {
+                    not: Box::new(JsonApiFilter::Comparison {
+                        comparison_value: serde_json::Value::from(true),
+                        field_name: "name".to_string(),
+                        operator: "is_null".to_string(),
+                    }),
+                },
+            ),
+        ];
+
+        for (input, expected) in tests {
+            assert_eq!(parse_filter_value(&input), Ok(expected));
+        }
+    }
+
+    #[test]
+    fn test_parse_invalid_filter_value() {
+        let tests = vec![
+            (
+                json!([{"name":{"$eq":"Horse"}}]),
+                ParseFilterExpressionError::FilterExpressionIsNotObject,
+            ),
+            (
+                json!({"name":"Horse"}),
+                ParseFilterExpressionError::ExpectedNonEmptyObjectForFieldComparison,
+            ),
+            (
+                json!({"name":{}}),
+                ParseFilterExpressionError::ExpectedNonEmptyObjectForFieldComparison,
+            ),
+            (
+                json!({"$eq":"Horse"}),
+                ParseFilterExpressionError::UnexpectedOperator("$eq".to_string()),
+            ),
+            (
+                json!({"$or":{"name":{"$eq":"Horse"}}}),
+                ParseFilterExpressionError::ExpectedArrayForOr,
+            ),
+        ];
+
+        for (input, expected) in tests {
+            assert_eq!(parse_filter_value(&input), Err(expected));
+        }
     }
 }
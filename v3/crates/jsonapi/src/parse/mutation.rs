@@ -0,0 +1,228 @@
+use super::{parse_url, resolve_field_selection, unique_identifier_value, validate_sparse_fields};
+use crate::catalog::{Model, ObjectType};
+use crate::helpers::{get_object_type, render_type_name};
+use crate::types::{ModelInfo, RelationshipTree, RequestError};
+use axum::http::{Method, Uri};
+use indexmap::IndexMap;
+use metadata_resolve::{ModelMutationKind, ModelMutationProcedure, Qualified};
+use open_dds::{
+    arguments::ArgumentName,
+    commands::CommandName,
+    identifier,
+    identifier::Identifier,
+    types::{CustomTypeName, FieldName},
+};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+pub struct MutationIR {
+    pub query_request: open_dds::query::QueryRequest,
+    pub root_type_name: Qualified<CustomTypeName>,
+    pub kind: ModelMutationKind,
+    pub command_name: Qualified<CommandName>,
+}
+
+// spec: <https://jsonapi.org/format/#crud>
+#[derive(Deserialize)]
+struct RequestDocument {
+    data: RequestResource,
+}
+
+#[derive(Deserialize)]
+struct RequestResource {
+    #[serde(rename = "type")]
+    resource_type: String,
+    id: Option<String>,
+    #[serde(default)]
+    attributes: serde_json::Map<String, serde_json::Value>,
+    relationships: Option<serde_json::Value>,
+}
+
+pub fn create_mutation_ir(
+    model: &Model,
+    models: &BTreeMap<String, Model>,
+    object_types: &BTreeMap<Qualified<CustomTypeName>, ObjectType>,
+    http_method: &Method,
+    uri: &Uri,
+    relationship_tree: &mut RelationshipTree,
+    query_string: &jsonapi_library::query::Query,
+    request_body: &[u8],
+) -> Result<MutationIR, RequestError> {
+    let ModelInfo {
+        subgraph,
+        name: _,
+        unique_identifier,
+        relationship,
+    } = parse_url(uri).map_err(RequestError::ParseError)?;
+
+    // resources are created in their collection, and updated or deleted at their own route
+    if !relationship.is_empty() {
+        return Err(RequestError::NotFound);
+    }
+    let (kind, procedure) = match (http_method, &unique_identifier) {
+        (&Method::POST, None) => (ModelMutationKind::Insert, &model.mutations.insert),
+        (&Method::PATCH, Some(_)) => (ModelMutationKind::Update, &model.mutations.update),
+        (&Method::DELETE, Some(_)) => (ModelMutationKind::Delete, &model.mutations.delete),
+        _ => return Err(RequestError::MethodNotAllowed),
+    };
+    let ModelMutationProcedure {
+        command_name,
+        key_argument,
+        object_argument,
+    } = procedure.as_ref().ok_or(RequestError::MethodNotAllowed)?;
+
+    validate_sparse_fields(object_types, query_string)?;
+
+    let mut arguments = IndexMap::new();
+
+    if let (Some(key_argument), Some(id)) = (key_argument, &unique_identifier) {
+        arguments.insert(
+            key_argument.clone(),
+            open_dds::query::Value::Literal(key_argument_value(model, object_types, id)?),
+        );
+    }
+
+    if kind != ModelMutationKind::Delete {
+        let attributes = parse_request_document(
+            model,
+            object_types,
+            request_body,
+            unique_identifier.as_deref(),
+        )?;
+        match object_argument {
+            Some(object_argument) => {
+                arguments.insert(
+                    object_argument.clone(),
+                    open_dds::query::Value::Literal(serde_json::Value::Object(attributes)),
+                );
+            }
+            // without an object argument, each attribute is the argument of the same name
+            None => {
+                for (attribute, value) in attributes {
+                    let argument_name = Identifier::new(attribute.as_str())
+                        .map(ArgumentName::new)
+                        .map_err(|_| {
+                            RequestError::BadRequest(format!("Invalid attribute name: {attribute}"))
+                        })?;
+                    arguments.insert(argument_name, open_dds::query::Value::Literal(value));
+                }
+            }
+        }
+    }
+
+    // select the mutated resource like a resource of the model
+    relationship_tree
+        .identifier_field
+        .clone_from(&model.unique_identifier);
+    let selection = resolve_field_selection(
+        object_types,
+        models,
+        &model.data_type,
+        relationship_tree,
+        query_string,
+        None,
+    )?;
+
+    let command_selection = open_dds::query::CommandSelection {
+        target: open_dds::query::CommandTarget {
+            subgraph,
+            command_name: command_name.name.clone(),
+            arguments,
+        },
+        selection: Some(selection),
+    };
+
+    let queries = IndexMap::from_iter([(
+        open_dds::query::Alias::new(identifier!("jsonapi_model_mutation")),
+        open_dds::query::Query::Command(command_selection),
+    )]);
+    Ok(MutationIR {
+        query_request: open_dds::query::QueryRequest::V1(open_dds::query::QueryRequestV1 {
+            queries,
+        }),
+        root_type_name: model.data_type.clone(),
+        kind,
+        command_name: command_name.clone(),
+    })
+}
+
+// the value of the key argument, parsed from the `id` of the route
+fn key_argument_value(
+    model: &Model,
+    object_types: &BTreeMap<Qualified<CustomTypeName>, ObjectType>,
+    id: &str,
+) -> Result<serde_json::Value, RequestError> {
+    let field_name = model.unique_identifier.as_ref().ok_or_else(|| {
+        RequestError::BadRequest(format!(
+            "Model {} has no unique identifier made of a single field",
+            model.name.name
+        ))
+    })?;
+    let object_type =
+        get_object_type(object_types, &model.data_type).map_err(RequestError::ParseError)?;
+    let field_type = object_type.type_fields.get(field_name).ok_or_else(|| {
+        RequestError::BadRequest(format!(
+            "Unique identifier {field_name} of model {} is not accessible",
+            model.name.name
+        ))
+    })?;
+    Ok(unique_identifier_value(field_type, id))
+}
+
+// validate the resource document of a `POST` or `PATCH` request, returning its attributes
+fn parse_request_document(
+    model: &Model,
+    object_types: &BTreeMap<Qualified<CustomTypeName>, ObjectType>,
+    request_body: &[u8],
+    unique_identifier: Option<&str>,
+) -> Result<serde_json::Map<String, serde_json::Value>, RequestError> {
+    let RequestDocument { data: resource } = serde_json::from_slice(request_body)
+        .map_err(|e| RequestError::BadRequest(format!("Invalid request document: {e}")))?;
+
+    let expected_type = render_type_name(&model.data_type);
+    if resource.resource_type != expected_type {
+        return Err(RequestError::Conflict(format!(
+            "Resource type {} does not match the type {expected_type} of the collection",
+            resource.resource_type
+        )));
+    }
+    match (unique_identifier, &resource.id) {
+        (None, Some(_)) => {
+            return Err(RequestError::Forbidden(
+                "Client-generated ids are not supported".to_string(),
+            ));
+        }
+        (Some(route_id), Some(id)) if id != route_id => {
+            return Err(RequestError::Conflict(format!(
+                "Resource id {id} does not match the id {route_id} of the route"
+            )));
+        }
+        (Some(_), None) => {
+            return Err(RequestError::BadRequest(
+                "The resource to update must have an id".to_string(),
+            ));
+        }
+        _ => {}
+    }
+    if resource.relationships.is_some() {
+        return Err(RequestError::Forbidden(
+            "Updating relationships is not supported".to_string(),
+        ));
+    }
+
+    let object_type =
+        get_object_type(object_types, &model.data_type).map_err(RequestError::ParseError)?;
+    for attribute in resource.attributes.keys() {
+        let is_known_field = Identifier::new(attribute.as_str()).is_ok_and(|identifier| {
+            object_type
+                .type_fields
+                .contains_key(&FieldName::new(identifier))
+        });
+        if !is_known_field {
+            return Err(RequestError::BadRequest(format!(
+                "Unknown attribute {attribute} for type {expected_type}"
+            )));
+        }
+    }
+    Ok(resource.attributes)
+}
@@ -1,14 +1,19 @@
-use super::helpers::get_object_type;
-use super::types::{RelationshipNode, RelationshipTree};
+use super::helpers::{get_object_type, render_type_name};
+use super::types::{Pagination, RelationshipNode, RelationshipTree, RequestedData};
 use crate::catalog::ObjectType;
-use crate::RequestError;
+use crate::parse::include_field;
+use crate::{InternalError, RequestError};
+use axum::http::Uri;
 use jsonapi_library::query::Query;
-use metadata_resolve::Qualified;
-use open_dds::{relationships::RelationshipType, types::CustomTypeName};
-use std::collections::BTreeMap;
+use metadata_resolve::{ModelMutationKind, Qualified};
+use open_dds::{
+    relationships::RelationshipType,
+    types::{CustomTypeName, FieldName},
+};
+use std::collections::{BTreeMap, HashSet};
 
-// a cheap way to get a unique id for each resource. we should probably get this from the
-// underlying data instead.
+// Resources without a unique identifier, like the results of commands, are given an id unique
+// within the document.
 //
 // https://jsonapi.org/format/#document-resource-object-identification
 //
@@ -23,6 +28,30 @@ fn fresh_id(unique_id: &mut i32) -> i32 {
     id
 }
 
+// the id of the resource of a row, the value of the unique identifier of its model if it has one
+fn resource_id(
+    unique_id: &mut i32,
+    identifier_field: Option<&FieldName>,
+    row: &serde_json::Map<String, serde_json::Value>,
+) -> Result<String, RequestError> {
+    match identifier_field {
+        None => Ok(fresh_id(unique_id).to_string()),
+        Some(field_name) => identifier_value(row.get(field_name.as_str())).ok_or_else(|| {
+            RequestError::InternalError(InternalError::MissingResourceIdentifier(
+                field_name.clone(),
+            ))
+        }),
+    }
+}
+
+fn identifier_value(value: Option<&serde_json::Value>) -> Option<String> {
+    match value? {
+        serde_json::Value::String(id) => Some(id.clone()),
+        serde_json::Value::Null => None,
+        id => Some(id.to_string()),
+    }
+}
+
 fn to_resource(
     unique_id: &mut i32,
     rowset: ndc_models::RowSet,
@@ -30,21 +59,19 @@ fn to_resource(
     relationship_tree: &RelationshipTree,
     query: &Query,
     object_types: &BTreeMap<Qualified<CustomTypeName>, ObjectType>,
-    collect_relationships: &mut Vec<jsonapi_library::model::Resource>,
+    collect_relationships: &mut IncludedResources,
 ) -> Result<Vec<jsonapi_library::model::Resource>, RequestError> {
     let mut resources = vec![];
     if let Some(rows) = rowset.rows {
         for row in rows {
-            let resource_id = fresh_id(unique_id);
             let resource = row_to_resource(
                 unique_id,
                 relationship_tree,
                 collect_relationships,
-                resource_id,
                 type_name,
                 query,
                 object_types,
-                row.into_iter().map(|(k, v)| (k.to_string(), v.0)),
+                row.into_iter().map(|(k, v)| (k.to_string(), v.0)).collect(),
             )?;
             resources.push(resource);
         }
@@ -52,20 +79,16 @@ fn to_resource(
     Ok(resources)
 }
 
-fn render_type_name(type_name: &Qualified<CustomTypeName>) -> String {
-    format!("{}_{}", type_name.subgraph, type_name.name)
-}
-
 fn row_to_resource(
     unique_id: &mut i32,
     relationship_tree: &RelationshipTree,
-    collect_relationships: &mut Vec<jsonapi_library::model::Resource>,
-    resource_id: i32,
+    collect_relationships: &mut IncludedResources,
     row_type: &Qualified<CustomTypeName>,
     query: &Query,
     object_types: &BTreeMap<Qualified<CustomTypeName>, ObjectType>,
-    row: impl Iterator<Item = (String, serde_json::Value)>,
+    row: serde_json::Map<String, serde_json::Value>,
 ) -> Result<jsonapi_library::model::Resource, RequestError> {
+    let id = resource_id(unique_id, relationship_tree.identifier_field.as_ref(), &row)?;
     let mut attributes = BTreeMap::new();
     let mut relationships = BTreeMap::new();
 
@@ -78,63 +101,48 @@ fn row_to_resource(
                 object_type,
                 is_command_relationship,
             } = relationship_node;
-            let relationship_type_name = render_type_name(object_type);
-            let relationship_identifier_data = match value
-                .get_mut("rows")
-                .and_then(|rows| rows.as_array_mut())
-            {
-                None => jsonapi_library::model::IdentifierData::None,
-                Some(relationship_rows) => match relationship_type {
-                    RelationshipType::Object => {
-                        if let Some(object_row_value) = relationship_rows.pop() {
-                            let relationship_id = fresh_id(unique_id);
-                            let resource_identifier = jsonapi_library::model::ResourceIdentifier {
-                                _type: relationship_type_name,
-                                id: relationship_id.to_string(),
-                            };
-                            // collect this relationship value
-                            collect_relationship_value(
-                                unique_id,
-                                nested,
-                                collect_relationships,
-                                relationship_id,
-                                object_type,
-                                *is_command_relationship,
-                                query,
-                                object_types,
-                                object_row_value,
-                            )?;
-                            jsonapi_library::model::IdentifierData::Single(resource_identifier)
-                        } else {
-                            jsonapi_library::model::IdentifierData::None
+            let relationship_identifier_data =
+                match value.get_mut("rows").and_then(|rows| rows.as_array_mut()) {
+                    None => jsonapi_library::model::IdentifierData::None,
+                    Some(relationship_rows) => match relationship_type {
+                        RelationshipType::Object => {
+                            if let Some(object_row_value) = relationship_rows.pop() {
+                                // collect this relationship value
+                                let resource_identifier = collect_relationship_value(
+                                    unique_id,
+                                    nested,
+                                    collect_relationships,
+                                    object_type,
+                                    *is_command_relationship,
+                                    query,
+                                    object_types,
+                                    object_row_value,
+                                )?;
+                                jsonapi_library::model::IdentifierData::Single(resource_identifier)
+                            } else {
+                                jsonapi_library::model::IdentifierData::None
+                            }
                         }
-                    }
-                    RelationshipType::Array => {
-                        let mut resource_identifiers = vec![];
-                        for object_row_value in relationship_rows.iter_mut() {
-                            let relationship_id = fresh_id(unique_id);
-                            let resource_identifier = jsonapi_library::model::ResourceIdentifier {
-                                _type: relationship_type_name.clone(),
-                                id: relationship_id.to_string(),
-                            };
-                            // collect this relationship value
-                            collect_relationship_value(
-                                unique_id,
-                                nested,
-                                collect_relationships,
-                                relationship_id,
-                                object_type,
-                                *is_command_relationship,
-                                query,
-                                object_types,
-                                object_row_value.take(),
-                            )?;
-                            resource_identifiers.push(resource_identifier);
+                        RelationshipType::Array => {
+                            let mut resource_identifiers = vec![];
+                            for object_row_value in relationship_rows.iter_mut() {
+                                // collect this relationship value
+                                let resource_identifier = collect_relationship_value(
+                                    unique_id,
+                                    nested,
+                                    collect_relationships,
+                                    object_type,
+                                    *is_command_relationship,
+                                    query,
+                                    object_types,
+                                    object_row_value.take(),
+                                )?;
+                                resource_identifiers.push(resource_identifier);
+                            }
+                            jsonapi_library::model::IdentifierData::Multiple(resource_identifiers)
                         }
-                        jsonapi_library::model::IdentifierData::Multiple(resource_identifiers)
-                    }
-                },
-            };
+                    },
+                };
             let relationship = jsonapi_library::model::Relationship {
                 data: Some(relationship_identifier_data),
                 links: None,
@@ -146,7 +154,11 @@ fn row_to_resource(
             let identifier = open_dds::identifier::Identifier::new(key.as_str()).unwrap();
             let field_name = open_dds::types::FieldName::new(identifier);
 
-            if object_type.type_fields.contains_key(&field_name) {
+            // the unique identifier is selected for the id of the resource, but is only an
+            // attribute when requested
+            if object_type.type_fields.contains_key(&field_name)
+                && include_field(query, &field_name, &row_type.name)
+            {
                 attributes.insert(key.to_string(), value);
             }
         }
@@ -155,7 +167,7 @@ fn row_to_resource(
     let rendered_type_name = render_type_name(row_type);
     Ok(jsonapi_library::api::Resource {
         _type: rendered_type_name,
-        id: resource_id.to_string(),
+        id,
         attributes,
         links: None,
         meta: None,
@@ -167,18 +179,48 @@ fn row_to_resource(
     })
 }
 
+// collects the related resource of a relationship value, returning its identifier
 fn collect_relationship_value(
     unique_id: &mut i32,
     relationship_tree: &RelationshipTree,
-    collect_relationships: &mut Vec<jsonapi_library::model::Resource>,
-    resource_id: i32,
+    collect_relationships: &mut IncludedResources,
+    row_type: &Qualified<CustomTypeName>,
+    is_command_relationship: bool,
+    query: &Query,
+    object_types: &BTreeMap<Qualified<CustomTypeName>, ObjectType>,
+
+    value: serde_json::Value,
+) -> Result<jsonapi_library::model::ResourceIdentifier, RequestError> {
+    let relationship_resource = relationship_value_to_resource(
+        unique_id,
+        relationship_tree,
+        collect_relationships,
+        row_type,
+        is_command_relationship,
+        query,
+        object_types,
+        value,
+    )?;
+    let resource_identifier = jsonapi_library::model::ResourceIdentifier {
+        _type: relationship_resource._type.clone(),
+        id: relationship_resource.id.clone(),
+    };
+    collect_relationships.insert(relationship_resource);
+
+    Ok(resource_identifier)
+}
+
+fn relationship_value_to_resource(
+    unique_id: &mut i32,
+    relationship_tree: &RelationshipTree,
+    collect_relationships: &mut IncludedResources,
     row_type: &Qualified<CustomTypeName>,
     is_command_relationship: bool,
     query: &Query,
     object_types: &BTreeMap<Qualified<CustomTypeName>, ObjectType>,
 
     mut value: serde_json::Value,
-) -> Result<(), RequestError> {
+) -> Result<jsonapi_library::model::Resource, RequestError> {
     if is_command_relationship {
         // If this is a command relationship, we need to extract the value from the 'FUNCTION_IR_VALUE_COLUMN_NAME' key
         // We are ignoring the other keys
@@ -191,56 +233,383 @@ fn collect_relationship_value(
     if let serde_json::Value::Object(object) = value {
         row_object = object;
     }
-    let relationship_resource = row_to_resource(
+    row_to_resource(
         unique_id,
         relationship_tree,
         collect_relationships,
-        resource_id,
         row_type,
         query,
         object_types,
-        row_object.into_iter(),
-    )?;
-    // collect this relationship resource
-    collect_relationships.push(relationship_resource);
+        row_object,
+    )
+}
+
+// the related resources of a document, in the order they were first collected, along with their
+// type and id pairs to collect each of them only once
+#[derive(Default)]
+struct IncludedResources {
+    resources: Vec<jsonapi_library::model::Resource>,
+    seen: HashSet<(String, String)>,
+}
 
-    Ok(())
+impl IncludedResources {
+    // collects the resource, once however many resources it is related to
+    fn insert(&mut self, resource: jsonapi_library::model::Resource) {
+        if self
+            .seen
+            .insert((resource._type.clone(), resource.id.clone()))
+        {
+            self.resources.push(resource);
+        }
+    }
+}
+
+// spec: <https://jsonapi.org/format/#document-compound-documents>
+// "A compound document MUST NOT include more than one resource object for each type and id
+// pair."
+fn included_resources(
+    data: &jsonapi_library::api::PrimaryData,
+    collect_relationships: IncludedResources,
+) -> Option<Vec<jsonapi_library::model::Resource>> {
+    let primary_resources: HashSet<(&str, &str)> = match data {
+        jsonapi_library::api::PrimaryData::Single(resource) => {
+            HashSet::from([(resource._type.as_str(), resource.id.as_str())])
+        }
+        jsonapi_library::api::PrimaryData::Multiple(resources) => resources
+            .iter()
+            .map(|resource| (resource._type.as_str(), resource.id.as_str()))
+            .collect(),
+        jsonapi_library::api::PrimaryData::None => HashSet::new(),
+    };
+    let mut included = collect_relationships.resources;
+    included.retain(|resource| {
+        !primary_resources.contains(&(resource._type.as_str(), resource.id.as_str()))
+    });
+    if included.is_empty() {
+        None
+    } else {
+        Some(included)
+    }
 }
 
 pub fn process_result(
-    rowsets: Vec<ndc_models::RowSet>,
+    query_results: Vec<Vec<ndc_models::RowSet>>,
     root_type_name: &Qualified<CustomTypeName>,
+    requested_data: &RequestedData,
     relationship_tree: &RelationshipTree,
+    uri: &Uri,
     query: &Query,
     object_types: &BTreeMap<Qualified<CustomTypeName>, ObjectType>,
 ) -> Result<jsonapi_library::api::DocumentData, RequestError> {
     let mut unique_id = 1;
 
-    let mut resources = vec![];
-    let mut collect_relationships = vec![];
-    if let Some(first_rowset) = rowsets.into_iter().next() {
-        resources.extend(to_resource(
-            &mut unique_id,
-            first_rowset,
-            root_type_name,
-            relationship_tree,
-            query,
-            object_types,
-            &mut collect_relationships,
-        )?);
+    // the rowsets of the model query, followed by those of the count query, if any
+    let mut query_results = query_results.into_iter();
+    let rowsets = query_results
+        .next()
+        .ok_or(RequestError::InternalError(InternalError::EmptyQuerySet))?;
+    let total = query_results.next().and_then(total_from_rowsets);
+
+    let mut collect_relationships = IncludedResources::default();
+    let (data, links, meta) = match requested_data {
+        RequestedData::Collection(pagination) => {
+            let mut resources = vec![];
+            if let Some(first_rowset) = rowsets.into_iter().next() {
+                resources.extend(to_resource(
+                    &mut unique_id,
+                    first_rowset,
+                    root_type_name,
+                    relationship_tree,
+                    query,
+                    object_types,
+                    &mut collect_relationships,
+                )?);
+            }
+            let links = pagination_links(uri, *pagination, resources.len(), total);
+            let meta = total.map(|total| {
+                [("total".to_string(), serde_json::Value::from(total))]
+                    .into_iter()
+                    .collect()
+            });
+            (
+                jsonapi_library::api::PrimaryData::Multiple(resources),
+                links,
+                meta,
+            )
+        }
+        RequestedData::Resource { id } => {
+            let row = first_row(rowsets).ok_or(RequestError::ResourceNotFound)?;
+            let mut resource = row_to_resource(
+                &mut unique_id,
+                relationship_tree,
+                &mut collect_relationships,
+                root_type_name,
+                query,
+                object_types,
+                row,
+            )?;
+            // the resource is identified by the id of the route
+            resource.id.clone_from(id);
+            (
+                jsonapi_library::api::PrimaryData::Single(Box::new(resource)),
+                self_link(uri),
+                None,
+            )
+        }
+        RequestedData::RelatedResources { relationship } => {
+            let mut row = first_row(rowsets).ok_or(RequestError::ResourceNotFound)?;
+            let relationship_node = relationship_tree
+                .relationships
+                .get(relationship)
+                .ok_or_else(|| {
+                    RequestError::BadRequest(format!("Relationship {relationship} not found"))
+                })?;
+            let mut resources = vec![];
+            for value in relationship_rows(row.remove(relationship.as_str())) {
+                resources.push(relationship_value_to_resource(
+                    &mut unique_id,
+                    &relationship_node.nested,
+                    &mut collect_relationships,
+                    &relationship_node.object_type,
+                    relationship_node.is_command_relationship,
+                    query,
+                    object_types,
+                    value,
+                )?);
+            }
+            (
+                primary_data(&relationship_node.relationship_type, resources),
+                self_link(uri),
+                None,
+            )
+        }
+        RequestedData::RelationshipLinkage {
+            relationship,
+            identifier_field,
+        } => {
+            let mut row = first_row(rowsets).ok_or(RequestError::ResourceNotFound)?;
+            let relationship_node = relationship_tree
+                .relationships
+                .get(relationship)
+                .ok_or_else(|| {
+                    RequestError::BadRequest(format!("Relationship {relationship} not found"))
+                })?;
+            let rendered_type_name = render_type_name(&relationship_node.object_type);
+            // resource identifiers are represented as resources without attributes
+            let resources = relationship_rows(row.remove(relationship.as_str()))
+                .into_iter()
+                .map(|value| {
+                    let id = identifier_value(value.get(identifier_field.as_str())).ok_or_else(
+                        || {
+                            RequestError::InternalError(InternalError::MissingResourceIdentifier(
+                                identifier_field.clone(),
+                            ))
+                        },
+                    )?;
+                    Ok(jsonapi_library::api::Resource {
+                        _type: rendered_type_name.clone(),
+                        id,
+                        attributes: BTreeMap::new(),
+                        links: None,
+                        meta: None,
+                        relationships: None,
+                    })
+                })
+                .collect::<Result<_, RequestError>>()?;
+            (
+                primary_data(&relationship_node.relationship_type, resources),
+                self_link(uri),
+                None,
+            )
+        }
+    };
+
+    let included = included_resources(&data, collect_relationships);
+
+    Ok(jsonapi_library::api::DocumentData {
+        data: Some(data),
+        included,
+        links: Some(links),
+        meta,
+        jsonapi: None,
+    })
+}
+
+/// Turns the result of the procedure of a `POST`, `PATCH` or `DELETE` request into a document.
+/// Deleted resources are not returned.
+pub fn process_mutation_result(
+    result: serde_json::Value,
+    kind: ModelMutationKind,
+    root_type_name: &Qualified<CustomTypeName>,
+    relationship_tree: &RelationshipTree,
+    uri: &Uri,
+    query: &Query,
+    object_types: &BTreeMap<Qualified<CustomTypeName>, ObjectType>,
+) -> Result<jsonapi_library::api::DocumentData, RequestError> {
+    // the procedures of updates and deletes return nothing when there is no resource to mutate,
+    // while those of inserts must return the created resource
+    let serde_json::Value::Object(row) = result else {
+        return match kind {
+            ModelMutationKind::Insert => Err(RequestError::InternalError(
+                InternalError::MissingInsertedResource,
+            )),
+            ModelMutationKind::Update | ModelMutationKind::Delete => {
+                Err(RequestError::ResourceNotFound)
+            }
+        };
+    };
+    if kind == ModelMutationKind::Delete {
+        return Ok(jsonapi_library::api::DocumentData::default());
     }
 
-    let included = if collect_relationships.is_empty() {
-        None
+    let mut unique_id = 1;
+    let mut collect_relationships = IncludedResources::default();
+    let resource = row_to_resource(
+        &mut unique_id,
+        relationship_tree,
+        &mut collect_relationships,
+        root_type_name,
+        query,
+        object_types,
+        row,
+    )?;
+    // the created resource is linked to from its collection, the updated one is at the route
+    let links = if kind == ModelMutationKind::Insert && relationship_tree.identifier_field.is_some()
+    {
+        [(
+            "self".to_string(),
+            serde_json::Value::String(format!("{}/{}", last_path_segment(uri), resource.id)),
+        )]
+        .into_iter()
+        .collect()
     } else {
-        Some(collect_relationships)
+        self_link(uri)
     };
+    let data = jsonapi_library::api::PrimaryData::Single(Box::new(resource));
 
     Ok(jsonapi_library::api::DocumentData {
-        data: Some(jsonapi_library::api::PrimaryData::Multiple(resources)),
-        included,
-        links: None,
+        included: included_resources(&data, collect_relationships),
+        data: Some(data),
+        links: Some(links),
         meta: None,
         jsonapi: None,
     })
 }
+
+// the first row of the first rowset, that is the resource fetched by its unique identifier
+fn first_row(
+    rowsets: Vec<ndc_models::RowSet>,
+) -> Option<serde_json::Map<String, serde_json::Value>> {
+    let row = rowsets.into_iter().next()?.rows?.into_iter().next()?;
+    Some(row.into_iter().map(|(k, v)| (k.to_string(), v.0)).collect())
+}
+
+// the rows of a relationship field of a row
+fn relationship_rows(value: Option<serde_json::Value>) -> Vec<serde_json::Value> {
+    match value {
+        Some(serde_json::Value::Object(mut object)) => match object.remove("rows") {
+            Some(serde_json::Value::Array(rows)) => rows,
+            _ => vec![],
+        },
+        _ => vec![],
+    }
+}
+
+fn primary_data(
+    relationship_type: &RelationshipType,
+    mut resources: Vec<jsonapi_library::model::Resource>,
+) -> jsonapi_library::api::PrimaryData {
+    match relationship_type {
+        RelationshipType::Object => match resources.pop() {
+            Some(resource) => jsonapi_library::api::PrimaryData::Single(Box::new(resource)),
+            None => jsonapi_library::api::PrimaryData::None,
+        },
+        RelationshipType::Array => jsonapi_library::api::PrimaryData::Multiple(resources),
+    }
+}
+
+// the single aggregate of the count query
+fn total_from_rowsets(rowsets: Vec<ndc_models::RowSet>) -> Option<u64> {
+    rowsets
+        .into_iter()
+        .next()?
+        .aggregates?
+        .into_values()
+        .next()?
+        .as_u64()
+}
+
+// Links are references relative to the last segment of the request path, like `Artist?page[limit]=10`,
+// so that they resolve to the right URL whatever the path the JSON:API is served under.
+fn last_path_segment(uri: &Uri) -> &str {
+    uri.path()
+        .rsplit('/')
+        .find(|segment| !segment.is_empty())
+        .unwrap_or_default()
+}
+
+fn self_link(uri: &Uri) -> jsonapi_library::api::Links {
+    let link = match uri.query() {
+        Some(query) if !query.is_empty() => format!("{}?{query}", last_path_segment(uri)),
+        _ => last_path_segment(uri).to_string(),
+    };
+    [("self".to_string(), serde_json::Value::String(link))]
+        .into_iter()
+        .collect()
+}
+
+// spec: <https://jsonapi.org/format/#fetching-pagination>
+fn pagination_links(
+    uri: &Uri,
+    pagination: Pagination,
+    page_size: usize,
+    total: Option<u64>,
+) -> jsonapi_library::api::Links {
+    let mut links = self_link(uri);
+    if let Some(limit) = pagination.limit {
+        let offset = pagination.offset.unwrap_or_default();
+        if offset > 0 {
+            links.insert(
+                "prev".to_string(),
+                serde_json::Value::String(page_link(uri, offset.saturating_sub(limit), limit)),
+            );
+        }
+        // without a total, a full page suggests there may be another one
+        let has_next = match total {
+            Some(total) => u64::try_from(offset.saturating_add(limit)).is_ok_and(|end| end < total),
+            None => page_size == limit,
+        };
+        if has_next {
+            links.insert(
+                "next".to_string(),
+                serde_json::Value::String(page_link(uri, offset.saturating_add(limit), limit)),
+            );
+        }
+    }
+    links
+}
+
+// the request with its page parameters replaced
+fn page_link(uri: &Uri, offset: usize, limit: usize) -> String {
+    let page_params = format!("page[offset]={offset}&page[limit]={limit}");
+    let params = uri
+        .query()
+        .unwrap_or_default()
+        .split('&')
+        .filter(|param| !param.is_empty() && !is_page_param(param))
+        .chain([page_params.as_str()])
+        .collect::<Vec<_>>();
+    format!("{}?{}", last_path_segment(uri), params.join("&"))
+}
+
+fn is_page_param(param: &str) -> bool {
+    let name = param.split('=').next().unwrap_or_default();
+    [
+        "page[limit]",
+        "page[offset]",
+        "page%5Blimit%5D",
+        "page%5Boffset%5D",
+    ]
+    .iter()
+    .any(|page_param| name.eq_ignore_ascii_case(page_param))
+}
@@ -1,5 +1,6 @@
 use crate::catalog::{Model, ObjectType, State};
 use std::collections::BTreeMap;
+mod input;
 mod output;
 mod parameters;
 mod shared;
@@ -53,12 +54,8 @@ fn get_response(
 fn get_route_for_model(
     model: &Model,
     object_type: &ObjectType,
-    object_types: &BTreeMap<Qualified<CustomTypeName>, ObjectType>,
+    state: &State,
     schemas: &mut BTreeMap<String, oas3::spec::ObjectOrReference<oas3::spec::ObjectSchema>>,
-    filter_boolean_expression_types: &BTreeMap<
-        String,
-        metadata_resolve::ResolvedObjectBooleanExpressionType,
-    >,
 ) -> oas3::spec::Operation {
     let mut parameters = vec![
         oas3::spec::ObjectOrReference::Object(parameters::page_limit_parameter()),
@@ -66,14 +63,18 @@ fn get_route_for_model(
         oas3::spec::ObjectOrReference::Object(parameters::ordering_parameter(model, object_type)),
         oas3::spec::ObjectOrReference::Object(parameters::include_parameter(model, object_type)),
     ];
-    if let Some(filter_parameter) =
-        parameters::filter_parameters(model, object_type, schemas, filter_boolean_expression_types)
-    {
+    if let Some(filter_parameter) = parameters::filter_parameters(
+        model,
+        &state.routes,
+        &state.object_types,
+        &state.boolean_expression_types,
+        schemas,
+    ) {
         parameters.push(oas3::spec::ObjectOrReference::Object(filter_parameter));
     }
 
     let fields_parameters =
-        parameters::fields_parameters(&model.data_type, object_type, object_types)
+        parameters::fields_parameters(&model.data_type, object_type, &state.object_types)
             .into_iter()
             .map(oas3::spec::ObjectOrReference::Object)
             .collect::<Vec<_>>();
@@ -82,7 +83,11 @@ fn get_route_for_model(
     let mut responses = BTreeMap::new();
     responses.insert(
         "200".into(),
-        oas3::spec::ObjectOrReference::Object(get_response(model, object_type, object_types)),
+        oas3::spec::ObjectOrReference::Object(get_response(
+            model,
+            object_type,
+            &state.object_types,
+        )),
     );
 
     oas3::spec::Operation {
@@ -101,6 +106,139 @@ fn get_route_for_model(
     }
 }
 
+fn json_content(schema: oas3::spec::ObjectSchema) -> BTreeMap<String, oas3::spec::MediaType> {
+    let media_type = oas3::spec::MediaType {
+        encoding: BTreeMap::new(),
+        examples: None,
+        schema: Some(oas3::spec::ObjectOrReference::Object(schema)),
+    };
+    BTreeMap::from_iter([(JSONAPI_MEDIA_TYPE.into(), media_type)])
+}
+
+fn mutation_response(
+    description: String,
+    content: BTreeMap<String, oas3::spec::MediaType>,
+) -> oas3::spec::ObjectOrReference<oas3::spec::Response> {
+    oas3::spec::ObjectOrReference::Object(oas3::spec::Response {
+        description: Some(description),
+        extensions: BTreeMap::new(),
+        headers: BTreeMap::new(),
+        links: BTreeMap::new(),
+        content,
+    })
+}
+
+fn mutation_operation(
+    summary: String,
+    request_body: Option<oas3::spec::RequestBody>,
+    parameters: Vec<oas3::spec::ObjectOrReference<oas3::spec::Parameter>>,
+    responses: BTreeMap<String, oas3::spec::ObjectOrReference<oas3::spec::Response>>,
+) -> oas3::spec::Operation {
+    oas3::spec::Operation {
+        callbacks: BTreeMap::new(),
+        deprecated: None,
+        description: None,
+        extensions: BTreeMap::new(),
+        external_docs: None,
+        operation_id: None,
+        parameters,
+        request_body: request_body.map(oas3::spec::ObjectOrReference::Object),
+        responses: Some(responses),
+        servers: vec![],
+        summary: Some(summary),
+        tags: vec![],
+    }
+}
+
+// the `id` of the resource in `/<subgraph>/<Model>/{id}` routes
+fn id_parameter() -> oas3::spec::ObjectOrReference<oas3::spec::Parameter> {
+    oas3::spec::ObjectOrReference::Object(oas3::spec::Parameter {
+        name: "id".into(),
+        allow_empty_value: None,
+        allow_reserved: None,
+        content: None,
+        deprecated: None,
+        description: Some("Unique identifier of the resource".into()),
+        example: None,
+        explode: None,
+        examples: BTreeMap::new(),
+        extensions: BTreeMap::new(),
+        location: oas3::spec::ParameterIn::Path,
+        schema: Some(oas3::spec::ObjectOrReference::Object(string_schema(None))),
+        style: None,
+        required: Some(true),
+    })
+}
+
+fn post_route_for_model(model: &Model, object_type: &ObjectType) -> oas3::spec::Operation {
+    let request_body = oas3::spec::RequestBody {
+        description: Some(format!("The {} resource to create", model.data_type.name)),
+        content: json_content(input::jsonapi_request_document_schema(
+            model,
+            object_type,
+            false,
+        )),
+        required: Some(true),
+    };
+    let responses = BTreeMap::from_iter([(
+        "201".into(),
+        mutation_response(
+            format!("Created {} resource", model.data_type.name),
+            json_content(output::jsonapi_resource_document_schema(model, object_type)),
+        ),
+    )]);
+    mutation_operation(
+        format!("Create a {} value", model.data_type.name),
+        Some(request_body),
+        vec![],
+        responses,
+    )
+}
+
+fn patch_route_for_model(model: &Model, object_type: &ObjectType) -> oas3::spec::Operation {
+    let request_body = oas3::spec::RequestBody {
+        description: Some(format!(
+            "The attributes of the {} resource to update",
+            model.data_type.name
+        )),
+        content: json_content(input::jsonapi_request_document_schema(
+            model,
+            object_type,
+            true,
+        )),
+        required: Some(true),
+    };
+    let responses = BTreeMap::from_iter([(
+        "200".into(),
+        mutation_response(
+            format!("Updated {} resource", model.data_type.name),
+            json_content(output::jsonapi_resource_document_schema(model, object_type)),
+        ),
+    )]);
+    mutation_operation(
+        format!("Update a {} value", model.data_type.name),
+        Some(request_body),
+        vec![id_parameter()],
+        responses,
+    )
+}
+
+fn delete_route_for_model(model: &Model) -> oas3::spec::Operation {
+    let responses = BTreeMap::from_iter([(
+        "204".into(),
+        mutation_response(
+            format!("Deleted {} resource", model.data_type.name),
+            BTreeMap::new(),
+        ),
+    )]);
+    mutation_operation(
+        format!("Delete a {} value", model.data_type.name),
+        None,
+        vec![id_parameter()],
+        responses,
+    )
+}
+
 // output when we make a request for an unknown role
 pub fn empty_schema() -> oas3::Spec {
     let info = oas3::spec::Info {
@@ -143,30 +281,13 @@ pub fn openapi_schema(state: &State) -> Result<oas3::Spec, SchemaError> {
     let mut paths = BTreeMap::new();
     let mut schemas = BTreeMap::new();
 
-    // get all the filter boolean expression types that we need to include in the schema
-    let mut filter_boolean_expression_types = BTreeMap::new();
-    for model in state.routes.values() {
-        if let Some(boolean_expression_type) = &model.filter_expression_type {
-            filter_boolean_expression_types.insert(
-                pretty_typename(&boolean_expression_type.name),
-                boolean_expression_type.clone(),
-            );
-        }
-    }
-
     for (route_name, model) in &state.routes {
         let object_type = state
             .object_types
             .get(&model.data_type)
             .ok_or_else(|| SchemaError::ObjectNotFound(model.data_type.clone()))?;
 
-        let get = get_route_for_model(
-            model,
-            object_type,
-            &state.object_types,
-            &mut schemas,
-            &filter_boolean_expression_types,
-        );
+        let get = get_route_for_model(model, object_type, state, &mut schemas);
 
         let full_route_path = format!("/v1/rest{route_name}");
 
@@ -179,7 +300,11 @@ pub fn openapi_schema(state: &State) -> Result<oas3::Spec, SchemaError> {
             options: None,
             parameters: vec![],
             patch: None,
-            post: None,
+            post: model
+                .mutations
+                .insert
+                .as_ref()
+                .map(|_| post_route_for_model(model, object_type)),
             put: None,
             reference: None,
             servers: vec![],
@@ -187,6 +312,37 @@ pub fn openapi_schema(state: &State) -> Result<oas3::Spec, SchemaError> {
             trace: None,
         };
 
+        // resources are updated and deleted at their own route
+        let patch = model
+            .mutations
+            .update
+            .as_ref()
+            .map(|_| patch_route_for_model(model, object_type));
+        let delete = model
+            .mutations
+            .delete
+            .as_ref()
+            .map(|_| delete_route_for_model(model));
+        if patch.is_some() || delete.is_some() {
+            let resource_path_item = oas3::spec::PathItem {
+                delete,
+                description: None,
+                extensions: BTreeMap::new(),
+                get: None,
+                head: None,
+                options: None,
+                parameters: vec![],
+                patch,
+                post: None,
+                put: None,
+                reference: None,
+                servers: vec![],
+                summary: None,
+                trace: None,
+            };
+            paths.insert(format!("{full_route_path}/{{id}}"), resource_path_item);
+        }
+
         paths.insert(full_route_path, path_item);
     }
 
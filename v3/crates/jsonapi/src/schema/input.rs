@@ -0,0 +1,49 @@
+use super::output::type_schema;
+use super::shared::{enum_schema, object_schema, pretty_typename, string_schema};
+use crate::catalog::{Model, ObjectType};
+use oas3::spec::{ObjectOrReference, ObjectSchema};
+use std::collections::BTreeMap;
+
+// the document sent to create or update a resource
+// {"data":
+//  {"type":"default_Actor",
+//   "id":"1",
+//   "attributes":{"name":"Actor 1"}
+//  }
+// }
+pub fn jsonapi_request_document_schema(
+    model: &Model,
+    object_type: &ObjectType,
+    with_id: bool,
+) -> ObjectSchema {
+    let mut attributes = BTreeMap::new();
+    for (field_name, field_type) in &object_type.type_fields {
+        attributes.insert(field_name.to_string(), type_schema(field_type));
+    }
+
+    let mut properties = BTreeMap::new();
+    let mut required = vec!["type".into(), "attributes".into()];
+
+    // the resources to update are identified by their id, the ones to create are given one
+    if with_id {
+        properties.insert("id".into(), ObjectOrReference::Object(string_schema(None)));
+        required.push("id".into());
+    }
+
+    properties.insert(
+        "type".into(),
+        ObjectOrReference::Object(enum_schema(vec![pretty_typename(&model.data_type)])),
+    );
+
+    properties.insert(
+        "attributes".into(),
+        ObjectOrReference::Object(object_schema(attributes, vec![])),
+    );
+
+    let mut document_properties = BTreeMap::new();
+    document_properties.insert(
+        "data".into(),
+        ObjectOrReference::Object(object_schema(properties, required)),
+    );
+    object_schema(document_properties, vec!["data".into()])
+}
@@ -23,7 +23,7 @@ use std::collections::BTreeMap;
 // ]}%
 
 // an OpenDD type represented in OpenAPI
-fn type_schema(ty: &Type) -> ObjectOrReference<ObjectSchema> {
+pub(super) fn type_schema(ty: &Type) -> ObjectOrReference<ObjectSchema> {
     match ty {
         Type::ScalarForDataConnector(set_of_types) => {
             // if there is only one, use it, otherwise, JSON
@@ -95,6 +95,7 @@ fn jsonapi_relationships_property(object_type: &ObjectType) -> Option<ObjectSche
     for (relationship_name, relationship_target) in &object_type.type_relationships {
         let (type_name, relationship_type) = match relationship_target {
             RelationshipTarget::Model {
+                model_name: _,
                 object_type,
                 relationship_type,
             } => (object_type, relationship_type),
@@ -202,6 +203,7 @@ fn jsonapi_included_schema(
     for (_relationship_name, relationship_target) in &object_type.type_relationships {
         let type_name = match relationship_target {
             RelationshipTarget::Model {
+                model_name: _,
                 object_type,
                 relationship_type: _,
             } => object_type,
@@ -248,3 +250,15 @@ pub fn jsonapi_document_schema(
 
     object_schema(properties, vec!["data".into()])
 }
+
+// top level jsonapi document of a single resource, as returned by mutations
+pub fn jsonapi_resource_document_schema(model: &Model, object_type: &ObjectType) -> ObjectSchema {
+    let mut properties = BTreeMap::new();
+
+    properties.insert(
+        "data".into(),
+        ObjectOrReference::Object(jsonapi_data_schema(&model.data_type, object_type)),
+    );
+
+    object_schema(properties, vec!["data".into()])
+}
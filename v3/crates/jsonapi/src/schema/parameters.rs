@@ -2,8 +2,7 @@ use metadata_resolve::{Qualified, QualifiedBaseType, QualifiedTypeName, Qualifie
 use open_dds::types::CustomTypeName;
 
 use super::shared::{array_schema, enum_schema, int_schema, pretty_typename, string_schema};
-use crate::catalog::{Model, ObjectType, Type};
-use crate::schema::shared::json_schema;
+use crate::catalog::{Model, ObjectType, RelationshipTarget, Type};
 use std::collections::BTreeMap;
 use std::string::ToString;
 
@@ -235,184 +234,218 @@ pub fn include_parameter(model: &Model, object_type: &ObjectType) -> oas3::spec:
     }
 }
 
-// Generate "filter" parameter for the given model with a given object type
+// How filters are written, for the description of the "filter" parameter
+const FILTER_GRAMMAR: &str = "A filter is a JSON object whose entries must all match. \
+    An entry either compares a field with operators, ie `{\"name\":{\"$eq\":\"Queen\"}}`, \
+    filters a nested object or a relationship with another filter, \
+    ie `{\"artist\":{\"name\":{\"$eq\":\"Queen\"}}}`, or combines filters with `$and` or `$or`, \
+    taking an array of filters, or `$not`, taking a filter. \
+    Fields can be compared with the operators of their boolean expression type, with `$is_null`, \
+    and with the built-in operators `$eq`, `$neq`, `$lt`, `$lte`, `$gt`, `$gte`, `$contains`, \
+    `$icontains`, `$starts_with`, `$istarts_with`, `$ends_with` and `$iends_with` \
+    when their data connector supports them.";
+
+// Generate "filter" parameter for the given model
 pub fn filter_parameters(
     model: &Model,
-    // We don't need this right away, this will be used once we start supporting nested filters
-    _object_type: &ObjectType,
-    schemas: &mut BTreeMap<String, oas3::spec::ObjectOrReference<oas3::spec::ObjectSchema>>,
-    // We don't need this right away, this will be used once we start supporting nested filters
-    _filter_boolean_expression_types: &BTreeMap<
-        String,
+    models: &BTreeMap<String, Model>,
+    object_types: &BTreeMap<Qualified<CustomTypeName>, ObjectType>,
+    boolean_expression_types: &BTreeMap<
+        Qualified<CustomTypeName>,
         metadata_resolve::ResolvedObjectBooleanExpressionType,
     >,
+    schemas: &mut BTreeMap<String, oas3::spec::ObjectOrReference<oas3::spec::ObjectSchema>>,
 ) -> Option<oas3::spec::Parameter> {
     // only include a filter if the model has a `BooleanExpressionType`
-    match &model.filter_expression_type {
-        Some(boolean_expression_type) => {
-            if schemas
-                .get(&pretty_typename(&boolean_expression_type.name))
-                .is_none()
-            {
-                // Add basic information about the filter
-                let mut filter_schema = oas3::spec::ObjectSchema {
-                    title: Some(pretty_typename(&boolean_expression_type.name)),
-                    description: Some(format!("Filter expression for {}", model.name.name)),
-                    schema_type: Some(oas3::spec::SchemaTypeSet::Single(
-                        oas3::spec::SchemaType::Object,
-                    )),
-                    ..Default::default()
-                };
-
-                // Keep track of the keys we add to the schema so we can add the `oneOf`
-                let mut filter_schema_keys = Vec::new();
-
-                // Add the filter schema for each scalar field
-                for (field_name, field_comparison) in &boolean_expression_type.fields.scalar_fields
-                {
-                    // Add basic information about the filter field
-                    let mut field_schema = oas3::spec::ObjectSchema {
-                        title: Some(field_name.to_string()),
-                        description: Some(format!(
-                            "Filter expression for filtering on {field_name}"
-                        )),
-                        schema_type: Some(oas3::spec::SchemaTypeSet::Single(
-                            oas3::spec::SchemaType::Object,
-                        )),
-                        ..Default::default()
-                    };
-
-                    // Add the operators
-                    for (operator_name, operator_type) in &field_comparison.operators {
-                        field_schema.properties.insert(
-                            format!("${operator_name}"),
-                            oas3::spec::ObjectOrReference::Object(type_schema(operator_type)),
-                        );
-                    }
-                    filter_schema.properties.insert(
-                        field_name.to_string(),
-                        oas3::spec::ObjectOrReference::Object(field_schema),
-                    );
-                    filter_schema_keys.push(field_name.to_string());
-                }
+    let boolean_expression_type = model.filter_expression_type.as_ref()?;
 
-                // Add the filter schema for each object field
-                for field_name in boolean_expression_type.fields.object_fields.keys() {
-                    filter_schema.properties.insert(
-                        field_name.to_string(),
-                        // TODO: Get the valid filter schema for the object type
-                        oas3::spec::ObjectOrReference::Object(json_schema()),
-                    );
-                }
+    add_filter_schema(
+        boolean_expression_type,
+        models,
+        object_types,
+        boolean_expression_types,
+        schemas,
+    );
+
+    // Note: We are using the content field here because the filter is a JSON object. We cannot use schema here.
+    let mut content = BTreeMap::new();
+    content.insert(
+        // This is how we tell OpenAPI that this is a JSON media object
+        "application/json".into(),
+        oas3::spec::MediaType {
+            encoding: BTreeMap::new(),
+            examples: None,
+            // This is the schema for the filter
+            schema: Some(filter_schema_reference(&boolean_expression_type.name)),
+        },
+    );
+    Some(oas3::spec::Parameter {
+        name: "filter".into(),
+        allow_empty_value: None,
+        allow_reserved: None,
+        content: Some(content),
+        deprecated: None,
+        description: Some(format!(
+            "Filter expression for {}. {FILTER_GRAMMAR}",
+            model.name.name
+        )),
+        example: None,
+        explode: None,
+        // TODO: add examples
+        examples: BTreeMap::new(),
+        extensions: BTreeMap::new(),
+        location: oas3::spec::ParameterIn::Query,
+        schema: None,
+        style: None,
+        required: None,
+    })
+}
+
+fn filter_schema_reference(
+    boolean_expression_type_name: &Qualified<CustomTypeName>,
+) -> oas3::spec::ObjectOrReference<oas3::spec::ObjectSchema> {
+    oas3::spec::ObjectOrReference::Ref {
+        ref_path: format!(
+            "#/components/schemas/{}",
+            pretty_typename(boolean_expression_type_name)
+        ),
+    }
+}
+
+// Add the schema of filters using the given boolean expression type, and of the filters on its
+// nested objects and relationships, leaving out what the role cannot see
+fn add_filter_schema(
+    boolean_expression_type: &metadata_resolve::ResolvedObjectBooleanExpressionType,
+    models: &BTreeMap<String, Model>,
+    object_types: &BTreeMap<Qualified<CustomTypeName>, ObjectType>,
+    boolean_expression_types: &BTreeMap<
+        Qualified<CustomTypeName>,
+        metadata_resolve::ResolvedObjectBooleanExpressionType,
+    >,
+    schemas: &mut BTreeMap<String, oas3::spec::ObjectOrReference<oas3::spec::ObjectSchema>>,
+) {
+    let schema_name = pretty_typename(&boolean_expression_type.name);
+    if schemas.contains_key(&schema_name) {
+        return;
+    }
+    let Some(object_type) = object_types.get(&boolean_expression_type.object_type) else {
+        return;
+    };
+
+    // Add basic information about the filter
+    let mut filter_schema = oas3::spec::ObjectSchema {
+        title: Some(schema_name.clone()),
+        description: Some(format!(
+            "Filter expression for {}",
+            boolean_expression_type.object_type.name
+        )),
+        schema_type: Some(oas3::spec::SchemaTypeSet::Single(
+            oas3::spec::SchemaType::Object,
+        )),
+        ..Default::default()
+    };
+
+    // the boolean expression types of nested objects and relationships, whose schemas we add next
+    let mut nested_boolean_expression_types = Vec::new();
+
+    // Add the filter schema for each scalar field
+    for (field_name, field_comparison) in &boolean_expression_type.fields.scalar_fields {
+        if !object_type.type_fields.contains_key(field_name) {
+            continue;
+        }
+        // Add basic information about the filter field
+        let mut field_schema = oas3::spec::ObjectSchema {
+            title: Some(field_name.to_string()),
+            description: Some(format!("Filter expression for filtering on {field_name}")),
+            schema_type: Some(oas3::spec::SchemaTypeSet::Single(
+                oas3::spec::SchemaType::Object,
+            )),
+            ..Default::default()
+        };
+
+        // Add the operators
+        for (operator_name, operator_type) in &field_comparison.operators {
+            field_schema.properties.insert(
+                format!("${operator_name}"),
+                oas3::spec::ObjectOrReference::Object(type_schema(operator_type)),
+            );
+        }
+        filter_schema.properties.insert(
+            field_name.to_string(),
+            oas3::spec::ObjectOrReference::Object(field_schema),
+        );
+    }
+
+    // Add the filter schema for each object field
+    for (field_name, object_field) in &boolean_expression_type.fields.object_fields {
+        if !object_type.type_fields.contains_key(field_name) {
+            continue;
+        }
+        if let Some(nested_boolean_expression_type) =
+            boolean_expression_types.get(&object_field.boolean_expression_type_name)
+        {
+            filter_schema.properties.insert(
+                field_name.to_string(),
+                filter_schema_reference(&nested_boolean_expression_type.name),
+            );
+            nested_boolean_expression_types.push(nested_boolean_expression_type);
+        }
+    }
 
-                // UNCOMMENT this once we start supporting nested filters. This will add the filter schema for each
-                // relationship field
-                //
-                // for (field_name, field_comparison) in
-                //     &boolean_expression_type.fields.relationship_fields
-                // {
-                //     // only include the filter if the object type has the relationship
-                //     if object_type
-                //         .type_relationships
-                //         .contains_key(&field_comparison.relationship_name)
-                //     {
-                //         if let Some(filter_boolean_expression_type) =
-                //             filter_boolean_expression_types
-                //                 .get(&pretty_typename(&field_comparison.boolean_expression_type))
-                //         {
-                //             filter_schema.properties.insert(
-                //                 field_name.to_string(),
-                //                 oas3::spec::ObjectOrReference::Ref {
-                //                     ref_path: format!(
-                //                         "#/components/schemas/{}",
-                //                         pretty_typename(&filter_boolean_expression_type.name)
-                //                     ),
-                //                 },
-                //             );
-                //         }
-                //     }
-                // }
-
-                // Add the filter schema for $and
-                filter_schema.properties.insert(
-                    "$and".into(),
-                    oas3::spec::ObjectOrReference::Object(array_schema(
-                        oas3::spec::ObjectOrReference::Ref {
-                            ref_path: format!(
-                                "#/components/schemas/{}",
-                                pretty_typename(&boolean_expression_type.name)
-                            ),
-                        },
-                    )),
-                );
-                filter_schema_keys.push("$and".into());
-
-                // Add the filter schema for $or
-                filter_schema.properties.insert(
-                    "$or".into(),
-                    oas3::spec::ObjectOrReference::Object(array_schema(
-                        oas3::spec::ObjectOrReference::Ref {
-                            ref_path: format!(
-                                "#/components/schemas/{}",
-                                pretty_typename(&boolean_expression_type.name)
-                            ),
-                        },
-                    )),
-                );
-                filter_schema_keys.push("$or".into());
-
-                // Add the oneOf
-                filter_schema.one_of = filter_schema_keys
-                    .into_iter()
-                    .map(|key| {
-                        oas3::spec::ObjectOrReference::Object(oas3::spec::ObjectSchema {
-                            required: vec![key],
-                            ..oas3::spec::ObjectSchema::default()
-                        })
-                    })
-                    .collect();
-                schemas.insert(
-                    pretty_typename(&boolean_expression_type.name),
-                    oas3::spec::ObjectOrReference::Object(filter_schema.clone()),
-                );
-            };
-
-            // Note: We are using the content field here because the filter is a JSON object. We cannot use schema here.
-            let mut content = BTreeMap::new();
-            content.insert(
-                // This is how we tell OpenAPI that this is a JSON media object
-                "application/json".into(),
-                oas3::spec::MediaType {
-                    encoding: BTreeMap::new(),
-                    examples: None,
-                    // This is the schema for the filter
-                    schema: Some(oas3::spec::ObjectOrReference::Ref {
-                        ref_path: format!(
-                            "#/components/schemas/{}",
-                            pretty_typename(&boolean_expression_type.name)
-                        ),
-                    }),
-                },
+    // Add the filter schema for each relationship field, if the role can see the relationship
+    // and the model it targets
+    for (field_name, relationship_field) in &boolean_expression_type.fields.relationship_fields {
+        let Some(RelationshipTarget::Model { model_name, .. }) = object_type
+            .type_relationships
+            .get(&relationship_field.relationship_name)
+        else {
+            continue;
+        };
+        if !models.values().any(|model| &model.name == model_name) {
+            continue;
+        }
+        if let Some(target_boolean_expression_type) =
+            boolean_expression_types.get(&relationship_field.boolean_expression_type)
+        {
+            filter_schema.properties.insert(
+                field_name.to_string(),
+                filter_schema_reference(&target_boolean_expression_type.name),
             );
-            Some(oas3::spec::Parameter {
-                name: "filter".into(),
-                allow_empty_value: None,
-                allow_reserved: None,
-                content: Some(content),
-                deprecated: None,
-                description: Some(format!("Filter expression for {}", model.name.name)),
-                example: None,
-                explode: None,
-                // TODO: add examples
-                examples: BTreeMap::new(),
-                extensions: BTreeMap::new(),
-                location: oas3::spec::ParameterIn::Query,
-                schema: None,
-                style: None,
-                required: None,
-            })
+            nested_boolean_expression_types.push(target_boolean_expression_type);
         }
-        None => None,
+    }
+
+    // Add the filter schema for $and, $or and $not
+    if boolean_expression_type.include_logical_operators
+        == metadata_resolve::IncludeLogicalOperators::Yes
+    {
+        for logical_operator in ["$and", "$or"] {
+            filter_schema.properties.insert(
+                logical_operator.into(),
+                oas3::spec::ObjectOrReference::Object(array_schema(filter_schema_reference(
+                    &boolean_expression_type.name,
+                ))),
+            );
+        }
+        filter_schema.properties.insert(
+            "$not".into(),
+            filter_schema_reference(&boolean_expression_type.name),
+        );
+    }
+
+    schemas.insert(
+        schema_name,
+        oas3::spec::ObjectOrReference::Object(filter_schema),
+    );
+
+    for nested_boolean_expression_type in nested_boolean_expression_types {
+        add_filter_schema(
+            nested_boolean_expression_type,
+            models,
+            object_types,
+            boolean_expression_types,
+            schemas,
+        );
     }
 }
 
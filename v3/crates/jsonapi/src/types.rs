@@ -2,8 +2,10 @@ use crate::parse;
 use hasura_authn_core::Role;
 use metadata_resolve::Qualified;
 use open_dds::{
-    identifier::SubgraphName, models::ModelName, relationships::RelationshipType,
-    types::CustomTypeName,
+    identifier::SubgraphName,
+    models::ModelName,
+    relationships::RelationshipType,
+    types::{CustomTypeName, FieldName},
 };
 use std::collections::BTreeMap;
 use tracing_util::{ErrorVisibility, TraceableError};
@@ -49,7 +51,11 @@ pub enum ModelWarning {
 #[derive(Debug, derive_more::Display)]
 pub enum RequestError {
     NotFound,
+    ResourceNotFound,
+    MethodNotAllowed,
     BadRequest(String),
+    Forbidden(String),
+    Conflict(String),
     InternalError(InternalError),
     PlanError(plan::PlanError),
     ExecuteError(execute::FieldError),
@@ -65,13 +71,31 @@ impl RequestError {
                 axum::http::StatusCode::NOT_FOUND,
                 "invalid route or path".to_string(),
             ),
+            RequestError::ResourceNotFound => (
+                axum::http::StatusCode::NOT_FOUND,
+                "resource not found".to_string(),
+            ),
+            RequestError::MethodNotAllowed => (
+                axum::http::StatusCode::METHOD_NOT_ALLOWED,
+                "method not allowed".to_string(),
+            ),
+            RequestError::Forbidden(err) => (axum::http::StatusCode::FORBIDDEN, err),
+            RequestError::Conflict(err) => (axum::http::StatusCode::CONFLICT, err),
+            RequestError::PlanError(plan::PlanError::GroupBy(err)) => {
+                (axum::http::StatusCode::BAD_REQUEST, err.to_string())
+            }
             RequestError::PlanError(plan::PlanError::Permission(_msg)) => (
                 axum::http::StatusCode::FORBIDDEN,
                 "Access forbidden".to_string(), // need to decide how much
                                                 // we tell the user, for
                                                 // now default to nothing
             ),
-            RequestError::InternalError(InternalError::EmptyQuerySet)
+            RequestError::InternalError(
+                InternalError::EmptyQuerySet
+                | InternalError::UnexpectedExecutionPlan
+                | InternalError::MissingResourceIdentifier(_)
+                | InternalError::MissingInsertedResource,
+            )
             | RequestError::PlanError(
                 plan::PlanError::Internal(_)
                 | plan::PlanError::InternalError(_)
@@ -98,6 +122,11 @@ impl RequestError {
 #[derive(Debug, derive_more::Display)]
 pub enum InternalError {
     EmptyQuerySet,
+    UnexpectedExecutionPlan,
+    /// A resource was returned without a value for the unique identifier of its model
+    MissingResourceIdentifier(FieldName),
+    /// The procedure inserting a resource did not return it
+    MissingInsertedResource,
 }
 
 impl TraceableError for RequestError {
@@ -149,7 +178,6 @@ impl axum::response::IntoResponse for JsonApiHttpError {
 }
 
 /// Model related info derived from URI path
-#[allow(dead_code)]
 pub struct ModelInfo {
     pub subgraph: SubgraphName,
     pub name: ModelName,
@@ -160,10 +188,36 @@ pub struct ModelInfo {
     pub relationship: Vec<String>,
 }
 
+/// The primary data requested, as determined by the route
+pub enum RequestedData {
+    /// `/<subgraph>/<Model>`: the resources of the model
+    Collection(Pagination),
+    /// `/<subgraph>/<Model>/<id>`: a single resource of the model
+    Resource { id: String },
+    /// `/<subgraph>/<Model>/<id>/<relationship>`: the resources related to a resource
+    RelatedResources { relationship: String },
+    /// `/<subgraph>/<Model>/<id>/relationships/<relationship>`: the identifiers of the
+    /// resources related to a resource, identified by the unique identifier of their model
+    RelationshipLinkage {
+        relationship: String,
+        identifier_field: FieldName,
+    },
+}
+
+/// Pagination of a collection, from the `page[limit]` and `page[offset]` query parameters
+#[derive(Clone, Copy, Default)]
+pub struct Pagination {
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
 /// A tree of relationships, used in processing of relationships in the JSON:API response creation
 #[derive(Default)]
 pub struct RelationshipTree {
     pub relationships: BTreeMap<String, RelationshipNode>,
+    /// The field whose value is the `id` of the resources, the unique identifier of their
+    /// model. Resources without one, like the results of commands, are given generated ids.
+    pub identifier_field: Option<FieldName>,
 }
 
 pub struct RelationshipNode {
@@ -1,6 +1,9 @@
 //! Tests that run JSONAPI to see if it works
 
-use engine_types::HttpContext;
+use engine_types::{
+    CircuitBreakers, HttpContext, PersistedQueryCache, RemoteJoinQueryLimit, ReplicaPools,
+    ResponseCache,
+};
 use hasura_authn_core::{Identity, Role};
 use jsonapi_library::api::{DocumentData, IdentifierData, PrimaryData};
 use reqwest::header::HeaderMap;
@@ -37,6 +40,11 @@ fn test_get_succeeding_requests() {
                 let http_context = HttpContext {
                     client: reqwest::Client::new(),
                     ndc_response_size_limit: None,
+                    ndc_circuit_breakers: CircuitBreakers::default(),
+                    ndc_replica_pools: ReplicaPools::default(),
+                    ndc_response_cache: ResponseCache::default(),
+                    remote_join_query_limit: RemoteJoinQueryLimit::default(),
+                    persisted_queries: PersistedQueryCache::default(),
                 };
 
                 let session = create_default_session();
@@ -50,6 +58,7 @@ fn test_get_succeeding_requests() {
                     axum::http::method::Method::GET,
                     axum::http::uri::Uri::from_str(&request_path).unwrap(),
                     query,
+                    axum::body::Bytes::new(),
                 )
                     .await;
 
@@ -100,6 +109,11 @@ fn test_get_failing_requests() {
                 let http_context = HttpContext {
                     client: reqwest::Client::new(),
                     ndc_response_size_limit: None,
+                    ndc_circuit_breakers: CircuitBreakers::default(),
+                    ndc_replica_pools: ReplicaPools::default(),
+                    ndc_response_cache: ResponseCache::default(),
+                    remote_join_query_limit: RemoteJoinQueryLimit::default(),
+                    persisted_queries: PersistedQueryCache::default(),
                 };
 
                 let session = create_default_session();
@@ -113,6 +127,7 @@ fn test_get_failing_requests() {
                     axum::http::method::Method::GET,
                     axum::http::uri::Uri::from_str(&request_path).unwrap(),
                     query,
+                    axum::body::Bytes::new(),
                 )
                     .await;
 
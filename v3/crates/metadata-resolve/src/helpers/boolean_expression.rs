@@ -298,6 +298,28 @@ fn validate_data_connector_with_comparable_relationship(
     if let object_relationships::RelationshipTarget::Model(relationship_target_model) =
         &relationship.target
     {
+        // The values a relationship binds on its target are not applied when comparing across it
+        if !relationship_target_model.value_mappings.is_empty() {
+            return Err(TypePredicateError::UnsupportedFeature {
+                message: format!(
+                    "The relationship {} maps values to its target, so it cannot be used in boolean expression type {}",
+                    comparable_relationship.relationship_name, object_boolean_expression_type.name
+                ),
+            }
+            .into());
+        }
+
+        // Comparisons across relationships can only use top-level fields on either side
+        if relationship_target_model.has_nested_field_mappings() {
+            return Err(TypePredicateError::UnsupportedFeature {
+                message: format!(
+                    "The relationship {} maps nested fields, so it cannot be used in boolean expression type {}",
+                    comparable_relationship.relationship_name, object_boolean_expression_type.name
+                ),
+            }
+            .into());
+        }
+
         let target_model = models
             .get(&relationship_target_model.model_name)
             .ok_or_else(
@@ -19,6 +19,11 @@ use std::str::FromStr;
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct NdcColumnForComparison {
     pub column: DataConnectorColumnName,
+    /// The fields of the nested objects inside `column` that lead to the compared field, if it
+    /// is not the column itself
+    #[serde(default = "serde_ext::ser_default")]
+    #[serde(skip_serializing_if = "serde_ext::is_ser_default")]
+    pub field_path: Vec<DataConnectorColumnName>,
     pub equal_operator: DataConnectorOperatorName,
 }
 
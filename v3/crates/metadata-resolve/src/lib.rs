@@ -32,28 +32,41 @@ pub use stages::boolean_expressions::{
     ScalarComparisonKind,
 };
 pub use stages::data_connectors::{
-    ArgumentPresetValue, DataConnectorLink, HttpHeadersPreset, NdcVersion,
+    ArgumentPresetValue, DataConnectorGroupingCapabilities, DataConnectorLink, HttpHeadersPreset,
+    NdcVersion, ResponseCachePolicy,
+};
+pub use stages::graphql_config::{
+    GlobalGraphqlConfig, MultipleOrderByInputObjectFields, QueryLimits, QueryLimitsConfig,
+};
+pub use stages::model_mutations::{
+    ModelMutationError, ModelMutationKind, ModelMutationProcedure, ModelMutations,
 };
-pub use stages::graphql_config::{GlobalGraphqlConfig, MultipleOrderByInputObjectFields};
 pub use stages::model_permissions::{
     FilterPermission, ModelPredicate, ModelTargetSource, ModelWithPermissions, SelectPermission,
     UnaryComparisonOperator,
 };
 pub use stages::models::{Model, ModelSource, ModelsError};
 pub use stages::models_graphql::{
-    ModelOrderByExpression, SelectAggregateGraphQlDefinition, SelectManyGraphQlDefinition,
+    GroupKeyExtractionGraphQlDefinition, GroupsFilterGraphQlDefinition,
+    GroupsOrderByGraphQlDefinition, ModelCacheHint, ModelOrderByExpression,
+    SelectAggregateGraphQlDefinition, SelectGroupsGraphQlDefinition, SelectManyGraphQlDefinition,
     SelectUniqueGraphQlDefinition, SubscriptionGraphQlDefinition, UniqueIdentifierField,
 };
 pub use stages::object_relationships::{
     field_selection_relationship_execution_category, AggregateRelationship,
     CommandRelationshipTarget, FieldNestedness, ModelRelationshipTarget,
     ObjectTypeWithRelationships, RelationshipCapabilities, RelationshipCommandMapping,
-    RelationshipExecutionCategory, RelationshipField, RelationshipModelMapping,
-    RelationshipModelMappingFieldTarget, RelationshipModelMappingTarget, RelationshipTarget,
+    RelationshipCommandValueMapping, RelationshipExecutionCategory, RelationshipField,
+    RelationshipFieldAccess, RelationshipModelMapping, RelationshipModelMappingFieldTarget,
+    RelationshipModelMappingTarget, RelationshipModelValueMapping, RelationshipTarget,
 };
 pub use stages::object_types::{
-    AggregateFunctions, ComparisonOperators, ExtractionFunctions, FieldArgumentInfo, FieldMapping,
-    ObjectTypeRepresentation, ResolvedObjectApolloFederationConfig, TypeMapping,
+    AggregateFunctions, ComparisonOperators, ExtractionFunctions, FieldArgumentInfo,
+    FieldDefinition, FieldMapping, ObjectTypeRepresentation, ResolvedObjectApolloFederationConfig,
+    TypeMapping,
+};
+pub use stages::operation_collections::{
+    hash_query, OperationCollectionError, OperationCollections, PersistedOperation,
 };
 pub use stages::order_by_expressions::{
     validate_orderable_relationship, ObjectOrderByExpression, OrderByExpressionGraphqlConfig,
@@ -62,7 +75,8 @@ pub use stages::order_by_expressions::{
 };
 pub use stages::plugins::LifecyclePluginConfigs;
 pub use stages::scalar_boolean_expressions::{
-    LogicalOperators, LogicalOperatorsGraphqlConfig, ResolvedScalarBooleanExpressionType,
+    IsNullOperator, LogicalOperators, LogicalOperatorsGraphqlConfig,
+    ResolvedScalarBooleanExpressionType,
 };
 pub use stages::scalar_type_representations::ScalarTypeRepresentation;
 pub use stages::type_permissions::{FieldPresetInfo, TypeInputPermission};
@@ -851,6 +851,43 @@ fn resolve_comparable_relationship(
         }
     }
 
+    // Check that the relationship doesn't map any values to its target, as those can't be applied here
+    if relationship.mapping.iter().any(|mapping| {
+        matches!(
+            mapping.source,
+            open_dds::relationships::RelationshipMappingSource::Value(_)
+        )
+    }) {
+        return Err(
+            AggregateBooleanExpressionError::ComparableRelationshipValueMappingsNotSupported {
+                operand_type: operand_type_name.clone(),
+                relationship_name: comparable_relationship.relationship_name.clone(),
+            },
+        );
+    }
+
+    // Check that the relationship only maps top-level fields, as nested ones can't be compared here
+    if relationship.mapping.iter().any(|mapping| {
+        let nested_source = matches!(
+            &mapping.source,
+            open_dds::relationships::RelationshipMappingSource::FieldPath(field_path)
+                if field_path.len() > 1
+        );
+        let nested_target = matches!(
+            &mapping.target,
+            open_dds::relationships::RelationshipMappingTarget::ModelField(field_path)
+                if field_path.len() > 1
+        );
+        nested_source || nested_target
+    }) {
+        return Err(
+            AggregateBooleanExpressionError::ComparableRelationshipNestedFieldMappingsNotSupported {
+                operand_type: operand_type_name.clone(),
+                relationship_name: comparable_relationship.relationship_name.clone(),
+            },
+        );
+    }
+
     // Get the relationship's targeted model's object type
     // We have to use unresolved models here because models are resolved after boolean expressions
     let model_target_subgraph = model_target.subgraph(); // Not inlined because lifetime
@@ -292,6 +292,18 @@ pub enum AggregateBooleanExpressionError {
         relationship_name: RelationshipName,
     },
 
+    #[error("the comparable relationship '{relationship_name}' for the operand type '{operand_type}' maps values to its target. This is not supported")]
+    ComparableRelationshipValueMappingsNotSupported {
+        operand_type: Qualified<CustomTypeName>,
+        relationship_name: RelationshipName,
+    },
+
+    #[error("the comparable relationship '{relationship_name}' for the operand type '{operand_type}' maps nested fields. This is not supported")]
+    ComparableRelationshipNestedFieldMappingsNotSupported {
+        operand_type: Qualified<CustomTypeName>,
+        relationship_name: RelationshipName,
+    },
+
     #[error("the comparable relationship '{relationship_name}' for the operand type '{operand_type}' targets a model than cannot be found: '{target_model_name}'")]
     ComparableRelationshipTargetModelNotFound {
         operand_type: Qualified<CustomTypeName>,
@@ -125,6 +125,15 @@ pub enum CommandsError {
         procedure_name: ProcedureName,
         error: ArgumentMappingError,
     },
+    #[error("the command {command_name:} is backed by the procedure {procedure:}, so its responses cannot be cached")]
+    ResponseCacheOnProcedure {
+        command_name: Qualified<CommandName>,
+        procedure: ProcedureName,
+    },
+    #[error("the response cache of command {command_name:} must have a ttlSeconds greater than 0")]
+    ZeroResponseCacheTtl {
+        command_name: Qualified<CommandName>,
+    },
     #[error("{error:} in command {command_name:}")]
     CommandTypeMappingCollectionError {
         command_name: Qualified<CommandName>,
@@ -58,6 +58,21 @@ pub fn resolve_command_source(
             data_connector: qualified_data_connector_name.clone(),
         })?;
 
+    // only the responses of functions can be cached, as procedures have side effects
+    if let Some(response_cache) = &command_source.response_cache {
+        if let DataConnectorCommand::Procedure(procedure) = &command_source.data_connector_command {
+            return Err(CommandsError::ResponseCacheOnProcedure {
+                command_name: command.name.clone(),
+                procedure: procedure.clone(),
+            });
+        }
+        if response_cache.ttl_seconds == 0 {
+            return Err(CommandsError::ZeroResponseCacheTtl {
+                command_name: command.name.clone(),
+            });
+        }
+    }
+
     // Get the result type and arguments of the function or procedure used as the ndc source for commands
     // object type
     let command_source_response = match &command_source.data_connector_command {
@@ -258,6 +273,10 @@ pub fn resolve_command_source(
         type_mappings,
         argument_mappings,
         data_connector_link_argument_presets,
+        response_cache: command_source
+            .response_cache
+            .as_ref()
+            .map(data_connectors::ResponseCachePolicy::new),
         source_arguments: command_source_response.arguments,
     };
 
@@ -50,6 +50,7 @@ pub struct CommandSource {
     pub argument_mappings: BTreeMap<ArgumentName, DataConnectorArgumentName>,
     pub data_connector_link_argument_presets:
         BTreeMap<DataConnectorArgumentName, ArgumentPresetValue>,
+    pub response_cache: Option<data_connectors::ResponseCachePolicy>,
     pub source_arguments: BTreeMap<DataConnectorArgumentName, ndc_models::Type>,
 }
 
@@ -23,6 +23,8 @@ pub enum DataConnectorError {
     DuplicateDataConnectorDefinition,
     #[error("The url for the data connector is invalid: {error}")]
     InvalidDataConnectorUrl { error: url::ParseError },
+    #[error("At least one read replica URL must be specified")]
+    EmptyReadReplicas,
     #[error("Invalid header name {header_name} specified")]
     InvalidHeaderName { header_name: String },
     #[error("Invalid value specified for header {header_name}")]
@@ -41,6 +43,17 @@ pub enum DataConnectorError {
         version: String,
         requirement: semver::VersionReq,
     },
+    #[error("The initial backoff ({initial_backoff_ms} ms) of the retry policy must not be greater than its maximum backoff ({max_backoff_ms} ms)")]
+    InvalidRetryBackoff {
+        initial_backoff_ms: u64,
+        max_backoff_ms: u64,
+    },
+    #[error("The failure threshold of the circuit breaker must be greater than zero")]
+    InvalidCircuitBreakerFailureThreshold,
+    #[error("The maximum number of variable sets per request must be greater than zero")]
+    InvalidMaxVariableSetsPerRequest,
+    #[error("The maximum number of remote filter values must be greater than zero")]
+    InvalidMaxRemoteFilterValues,
 }
 
 #[derive(Debug, thiserror::Error)]
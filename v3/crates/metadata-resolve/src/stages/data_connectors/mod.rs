@@ -8,11 +8,13 @@ pub use error::{
 use open_dds::{data_connector::DataConnectorName, identifier::SubgraphName};
 use std::collections::BTreeMap;
 pub use types::{
-    ArgumentPreset, ArgumentPresetValue, CommandsResponseConfig,
+    ArgumentPreset, ArgumentPresetValue, CircuitBreakerPolicy, CommandsResponseConfig,
     DataConnectorAggregateCapabilities, DataConnectorCapabilities, DataConnectorContext,
-    DataConnectorLink, DataConnectorNestedRelationshipCapabilities,
-    DataConnectorRelationshipCapabilities, DataConnectorSchema, DataConnectors,
-    DataConnectorsOutput, HttpHeadersPreset, NdcVersion,
+    DataConnectorGroupingCapabilities, DataConnectorLink,
+    DataConnectorNestedRelationshipCapabilities, DataConnectorRelationshipCapabilities,
+    DataConnectorSchema, DataConnectors, DataConnectorsOutput, HttpHeadersPreset, NdcVersion,
+    RequestPolicy, ResolvedDataConnectorUrl, ResolvedReadReplicaUrls, ResponseCachePolicy,
+    RetryPolicy,
 };
 
 /// Resolve data connectors.
@@ -57,7 +59,7 @@ fn resolve_data_connector<'s>(
     let qualified_data_connector_name =
         Qualified::new(subgraph.clone(), data_connector.name.clone());
 
-    let (data_connector_context, connector_issues) =
+    let (mut data_connector_context, connector_issues) =
         types::DataConnectorContext::new(metadata_accessor, data_connector).map_err(|error| {
             NamedDataConnectorError {
                 data_connector_name: qualified_data_connector_name.clone(),
@@ -65,6 +67,18 @@ fn resolve_data_connector<'s>(
             }
         })?;
 
+    if let Some(aggregate_capabilities) =
+        &mut data_connector_context.capabilities.supports_aggregates
+    {
+        if let Some(grouping_capabilities) = &mut aggregate_capabilities.supports_grouping {
+            grouping_capabilities.resolve_comparison_operators(
+                &qualified_data_connector_name,
+                &data_connector_context.schema,
+                aggregate_capabilities.aggregate_count_scalar_type.as_ref(),
+            );
+        }
+    }
+
     issues.extend(
         connector_issues
             .into_iter()
@@ -4,25 +4,29 @@ use super::error::{
 use crate::helpers::http::{
     HeaderError, SerializableHeaderMap, SerializableHeaderName, SerializableUrl,
 };
-use crate::helpers::ndc_validation::validate_ndc_argument_presets;
+use crate::helpers::ndc_validation::{unwrap_nullable_type, validate_ndc_argument_presets};
 use crate::ndc_migration;
+use crate::stages::object_types::{get_comparison_operators, ComparisonOperators};
 use crate::types::permission::ValueExpression;
 use crate::types::subgraph::Qualified;
 use indexmap::IndexMap;
 use lang_graphql::ast::common::OperationType;
 use ndc_models;
 use open_dds::accessor::MetadataAccessor;
+use open_dds::aggregates::DataConnectorAggregationFunctionName;
 use open_dds::data_connector::{DataConnectorColumnName, DataConnectorScalarType};
 use open_dds::types::DataConnectorArgumentName;
 use open_dds::{
-    commands::{FunctionName, ProcedureName},
+    commands::{FunctionName, ProcedureName, ResponseCache},
     data_connector::{
-        self, DataConnectorName, DataConnectorUrl, ReadWriteUrls, VersionedSchemaAndCapabilities,
+        self, DataConnectorName, DataConnectorUrl, ReadReplicaUrls, ReadWriteUrls,
+        ReplicaLoadBalancing, VersionedSchemaAndCapabilities,
     },
 };
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::sync::Arc;
+use std::time::Duration;
 use strum_macros::EnumIter;
 
 pub struct DataConnectorsOutput<'a> {
@@ -58,6 +62,7 @@ pub struct DataConnectorContext<'a> {
     pub capabilities: DataConnectorCapabilities,
     pub argument_presets: Vec<ArgumentPreset>,
     pub response_headers: Option<CommandsResponseConfig>,
+    pub request_policy: Option<RequestPolicy>,
 }
 
 impl<'a> DataConnectorContext<'a> {
@@ -118,6 +123,12 @@ impl<'a> DataConnectorContext<'a> {
             None
         };
 
+        let request_policy = data_connector
+            .request_policy
+            .as_ref()
+            .map(RequestPolicy::new)
+            .transpose()?;
+
         let context = DataConnectorContext {
             url: &data_connector.url,
             headers: data_connector
@@ -129,6 +140,7 @@ impl<'a> DataConnectorContext<'a> {
             capabilities,
             argument_presets,
             response_headers,
+            request_policy,
         };
 
         Ok((context, issues))
@@ -238,6 +250,10 @@ pub struct DataConnectorLink {
     pub url: ResolvedDataConnectorUrl,
     /// These are headers used in the protocol level
     pub headers: SerializableHeaderMap,
+    /// Timeouts, retries, circuit breaking, batching and size limits of the requests made to the
+    /// data connector
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub request_policy: Option<RequestPolicy>,
     /// HTTP response headers configuration that is forwarded from a NDC
     /// function/procedure to the client.
     #[serde(skip_serializing_if = "Option::is_none", default)]
@@ -282,6 +298,41 @@ impl DataConnectorLink {
                     })?,
                 })
             }
+            DataConnectorUrl::ReadReplicas(ReadReplicaUrls {
+                read,
+                write,
+                load_balancing,
+                ejection_cooldown_ms,
+                health_check,
+            }) => {
+                let read = read
+                    .iter()
+                    .map(|read| SerializableUrl::new(&read.value))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|error| NamedDataConnectorError {
+                        data_connector_name: name.clone(),
+                        error: DataConnectorError::InvalidDataConnectorUrl { error },
+                    })?;
+                ResolvedDataConnectorUrl::ReadReplicas(ResolvedReadReplicaUrls {
+                    read: nonempty::NonEmpty::from_vec(read).ok_or_else(|| {
+                        NamedDataConnectorError {
+                            data_connector_name: name.clone(),
+                            error: DataConnectorError::EmptyReadReplicas,
+                        }
+                    })?,
+                    write: SerializableUrl::new(&write.value).map_err(|error| {
+                        NamedDataConnectorError {
+                            data_connector_name: name.clone(),
+                            error: DataConnectorError::InvalidDataConnectorUrl { error },
+                        }
+                    })?,
+                    load_balancing: load_balancing.unwrap_or(ReplicaLoadBalancing::RoundRobin),
+                    ejection_cooldown: Duration::from_millis(*ejection_cooldown_ms),
+                    health_check_interval: health_check
+                        .as_ref()
+                        .map(|health_check| Duration::from_millis(health_check.interval_ms)),
+                })
+            }
         };
         let headers =
             SerializableHeaderMap::new(&context.headers).map_err(|e| NamedDataConnectorError {
@@ -292,12 +343,109 @@ impl DataConnectorLink {
             name,
             url,
             headers,
+            request_policy: context.request_policy.clone(),
             capabilities: context.capabilities.clone(),
             response_config: context.response_headers.clone().map(Arc::new),
         })
     }
 }
 
+/// Timeouts, retries, circuit breaking, batching and size limits of the requests made to a data
+/// connector
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RequestPolicy {
+    /// requests fail after this duration
+    pub timeout: Option<Duration>,
+    /// how queries are retried when the data connector is unavailable
+    pub retry: Option<RetryPolicy>,
+    /// when to stop sending requests to the data connector
+    pub circuit_breaker: Option<CircuitBreakerPolicy>,
+    /// the most variable sets sent in a single query request
+    pub max_variable_sets_per_request: Option<u32>,
+    /// the most distinct values a query can be compared with when filtered by a remote relationship
+    pub max_remote_filter_values: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CircuitBreakerPolicy {
+    pub failure_threshold: u32,
+    pub reset_timeout: Duration,
+}
+
+/// Caching of the responses of a data connector to the queries of a model or command
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ResponseCachePolicy {
+    pub ttl: Duration,
+    /// whether cached responses are only reused for requests with the same session
+    pub include_session_variables: bool,
+}
+
+impl ResponseCachePolicy {
+    pub fn new(response_cache: &ResponseCache) -> Self {
+        Self {
+            ttl: Duration::from_secs(response_cache.ttl_seconds),
+            include_session_variables: response_cache.include_session_variables,
+        }
+    }
+}
+
+impl RequestPolicy {
+    fn new(
+        request_policy: &data_connector::DataConnectorRequestPolicy,
+    ) -> Result<Self, DataConnectorError> {
+        let retry = request_policy
+            .retry
+            .as_ref()
+            .map(|retry| {
+                if retry.initial_backoff_ms > retry.max_backoff_ms {
+                    return Err(DataConnectorError::InvalidRetryBackoff {
+                        initial_backoff_ms: retry.initial_backoff_ms,
+                        max_backoff_ms: retry.max_backoff_ms,
+                    });
+                }
+                Ok(RetryPolicy {
+                    max_retries: retry.max_retries,
+                    initial_backoff: Duration::from_millis(retry.initial_backoff_ms),
+                    max_backoff: Duration::from_millis(retry.max_backoff_ms),
+                })
+            })
+            .transpose()?;
+        let circuit_breaker = request_policy
+            .circuit_breaker
+            .as_ref()
+            .map(|circuit_breaker| {
+                if circuit_breaker.failure_threshold == 0 {
+                    return Err(DataConnectorError::InvalidCircuitBreakerFailureThreshold);
+                }
+                Ok(CircuitBreakerPolicy {
+                    failure_threshold: circuit_breaker.failure_threshold,
+                    reset_timeout: Duration::from_millis(circuit_breaker.reset_timeout_ms),
+                })
+            })
+            .transpose()?;
+        if request_policy.max_variable_sets_per_request == Some(0) {
+            return Err(DataConnectorError::InvalidMaxVariableSetsPerRequest);
+        }
+        if request_policy.max_remote_filter_values == Some(0) {
+            return Err(DataConnectorError::InvalidMaxRemoteFilterValues);
+        }
+        Ok(Self {
+            timeout: request_policy.timeout_ms.map(Duration::from_millis),
+            retry,
+            circuit_breaker,
+            max_variable_sets_per_request: request_policy.max_variable_sets_per_request,
+            max_remote_filter_values: request_policy.max_remote_filter_values,
+        })
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct ResolvedReadWriteUrls {
@@ -305,14 +453,31 @@ pub struct ResolvedReadWriteUrls {
     pub write: SerializableUrl,
 }
 
+/// A pool of read replicas, balanced at execution time, and a write URL
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedReadReplicaUrls {
+    pub read: nonempty::NonEmpty<SerializableUrl>,
+    pub write: SerializableUrl,
+    pub load_balancing: ReplicaLoadBalancing,
+    /// how long a replica that cannot be connected to is taken out of the pool
+    pub ejection_cooldown: Duration,
+    /// how often the `/health` endpoint of each replica is probed, if at all
+    pub health_check_interval: Option<Duration>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub enum ResolvedDataConnectorUrl {
     SingleUrl(SerializableUrl),
     ReadWriteUrls(ResolvedReadWriteUrls),
+    ReadReplicas(ResolvedReadReplicaUrls),
 }
 
 impl ResolvedDataConnectorUrl {
+    /// The URL to send an operation to. For read replicas, this returns the first replica:
+    /// queries must instead be balanced between the replicas with
+    /// `execute::ndc::send_to_query_url`.
     pub fn get_url(&self, operation: OperationType) -> &reqwest::Url {
         match self {
             ResolvedDataConnectorUrl::SingleUrl(url) => &url.0,
@@ -322,6 +487,22 @@ impl ResolvedDataConnectorUrl {
                     OperationType::Mutation => &write.0,
                 }
             }
+            ResolvedDataConnectorUrl::ReadReplicas(ResolvedReadReplicaUrls {
+                read, write, ..
+            }) => match operation {
+                OperationType::Query | OperationType::Subscription => &read.head.0,
+                OperationType::Mutation => &write.0,
+            },
+        }
+    }
+
+    /// The read replicas to balance queries between, if any
+    pub fn read_replicas(&self) -> Option<&ResolvedReadReplicaUrls> {
+        match self {
+            ResolvedDataConnectorUrl::ReadReplicas(read_replicas) => Some(read_replicas),
+            ResolvedDataConnectorUrl::SingleUrl(_) | ResolvedDataConnectorUrl::ReadWriteUrls(_) => {
+                None
+            }
         }
     }
 }
@@ -509,6 +690,107 @@ pub struct DataConnectorGroupingCapabilities {
     #[serde(default = "serde_ext::ser_default")]
     #[serde(skip_serializing_if = "serde_ext::is_ser_default")]
     pub supports_pagination: bool,
+
+    /// Whether or not filtering groups by their aggregates is supported
+    #[serde(default = "serde_ext::ser_default")]
+    #[serde(skip_serializing_if = "serde_ext::is_ser_default")]
+    pub supports_filtering: bool,
+
+    /// Whether or not ordering groups is supported
+    #[serde(default = "serde_ext::ser_default")]
+    #[serde(skip_serializing_if = "serde_ext::is_ser_default")]
+    pub supports_ordering: bool,
+
+    /// The comparison operators of the results of the aggregate functions of each scalar type,
+    /// which groups are filtered with
+    #[serde(default = "serde_ext::ser_default")]
+    #[serde(skip_serializing_if = "serde_ext::is_ser_default")]
+    pub aggregate_function_comparison_operators: BTreeMap<
+        DataConnectorScalarType,
+        BTreeMap<DataConnectorAggregationFunctionName, ComparisonOperators>,
+    >,
+
+    /// The comparison operators of the results of count aggregates
+    #[serde(default = "serde_ext::ser_default")]
+    #[serde(skip_serializing_if = "serde_ext::is_ser_default")]
+    pub count_comparison_operators: Option<ComparisonOperators>,
+}
+
+impl DataConnectorGroupingCapabilities {
+    /// The comparison operators of the result of an aggregate function on a column of the type
+    pub fn get_aggregate_function_comparison_operators(
+        &self,
+        column_type: &ndc_models::Type,
+        function_name: &DataConnectorAggregationFunctionName,
+    ) -> Option<&ComparisonOperators> {
+        let ndc_models::Type::Named { name } = unwrap_nullable_type(column_type) else {
+            return None;
+        };
+        self.aggregate_function_comparison_operators
+            .get(&DataConnectorScalarType::from(name.as_str()))?
+            .get(function_name)
+    }
+
+    /// Resolves the comparison operators of the results of aggregates from the scalar types of
+    /// the data connector, if groups can be filtered
+    pub(crate) fn resolve_comparison_operators(
+        &mut self,
+        data_connector_name: &Qualified<DataConnectorName>,
+        schema: &DataConnectorSchema,
+        count_scalar_type: Option<&DataConnectorScalarType>,
+    ) {
+        if !self.supports_filtering {
+            return;
+        }
+        // duplicate operators are reported when resolving the object types using the scalar types
+        let comparison_operators = |scalar_type_name: &ndc_models::ScalarTypeName| {
+            schema
+                .scalar_types
+                .get(scalar_type_name)
+                .map(|scalar_type| {
+                    get_comparison_operators(scalar_type_name, scalar_type, data_connector_name).0
+                })
+        };
+        for (scalar_type_name, scalar_type) in &schema.scalar_types {
+            let function_comparison_operators = scalar_type
+                .aggregate_functions
+                .iter()
+                .filter_map(|(function_name, definition)| {
+                    let result_type = match definition {
+                        ndc_models::AggregateFunctionDefinition::Min
+                        | ndc_models::AggregateFunctionDefinition::Max => scalar_type_name.clone(),
+                        ndc_models::AggregateFunctionDefinition::Sum { result_type }
+                        | ndc_models::AggregateFunctionDefinition::Average { result_type } => {
+                            result_type.clone()
+                        }
+                        ndc_models::AggregateFunctionDefinition::Custom { result_type } => {
+                            match unwrap_nullable_type(result_type) {
+                                ndc_models::Type::Named { name } => {
+                                    ndc_models::ScalarTypeName::new(name.inner().clone())
+                                }
+                                _ => return None,
+                            }
+                        }
+                    };
+                    Some((
+                        DataConnectorAggregationFunctionName::new(function_name.inner().clone()),
+                        comparison_operators(&result_type)?,
+                    ))
+                })
+                .collect::<BTreeMap<_, _>>();
+            if !function_comparison_operators.is_empty() {
+                self.aggregate_function_comparison_operators.insert(
+                    DataConnectorScalarType::from(scalar_type_name.as_str()),
+                    function_comparison_operators,
+                );
+            }
+        }
+        self.count_comparison_operators = count_scalar_type.and_then(|count_scalar_type| {
+            comparison_operators(&ndc_models::ScalarTypeName::from(
+                count_scalar_type.as_str(),
+            ))
+        });
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -625,6 +907,11 @@ fn mk_ndc_02_capabilities(
                 supports_grouping: aggregates.group_by.as_ref().map(|groupby_capabilities| {
                     DataConnectorGroupingCapabilities {
                         supports_pagination: groupby_capabilities.paginate.is_some(),
+                        supports_filtering: groupby_capabilities.filter.is_some(),
+                        supports_ordering: groupby_capabilities.order.is_some(),
+                        // resolved from the schema of the data connector
+                        aggregate_function_comparison_operators: BTreeMap::new(),
+                        count_comparison_operators: None,
                     }
                 }),
             }
@@ -1,4 +1,5 @@
 use lang_graphql::ast::common as ast;
+use open_dds::permissions::Role;
 
 #[derive(Debug, thiserror::Error)]
 pub enum GraphqlConfigError {
@@ -30,4 +31,6 @@ pub enum GraphqlConfigError {
     InvalidGraphQlName { name: String },
     #[error("multiple graphql types found with the same name: {graphql_type_name:}")]
     ConflictingGraphQlType { graphql_type_name: ast::TypeName },
+    #[error("query limits are defined more than once for role {role:} in GraphqlConfig")]
+    DuplicateRoleQueryLimits { role: Role },
 }
@@ -3,7 +3,7 @@
 mod error;
 mod types;
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::sync::OnceLock;
 
 use lang_graphql::ast::common as ast;
@@ -16,7 +16,7 @@ pub use error::GraphqlConfigError;
 pub use types::{
     AggregateGraphqlConfig, FilterInputGraphqlConfig, FilterInputOperatorNames,
     GlobalGraphqlConfig, GraphqlConfig, GraphqlTypeNames, MultipleOrderByInputObjectFields,
-    OrderByInputGraphqlConfig, QueryGraphqlConfig,
+    OrderByInputGraphqlConfig, QueryGraphqlConfig, QueryLimits, QueryLimitsConfig,
 };
 
 /// Resolve and validate the GraphQL configuration.
@@ -191,6 +191,18 @@ pub fn resolve_graphql_config(
                 .as_ref()
                 .is_some_and(|federation_config| federation_config.enable_root_fields);
 
+            let query_limits = graphql_config_metadata
+                .query_limits
+                .as_ref()
+                .map(resolve_query_limits)
+                .transpose()?;
+
+            let max_batch_size = graphql_config_metadata
+                .batching
+                .as_ref()
+                .map(|batching| batching.max_batch_size)
+                .filter(|max_batch_size| *max_batch_size > 0);
+
             Ok(GraphqlConfig {
                 query: QueryGraphqlConfig {
                     arguments_field_name,
@@ -218,12 +230,41 @@ pub fn resolve_graphql_config(
                     } else {
                         MultipleOrderByInputObjectFields::Allow
                     },
+                    query_limits,
+                    max_batch_size,
                 },
             })
         }
     }
 }
 
+fn resolve_query_limits(
+    query_limits: &graphql_config::QueryLimitsGraphqlConfig,
+) -> Result<QueryLimitsConfig, GraphqlConfigError> {
+    let mut roles = BTreeMap::new();
+    for role_query_limits in &query_limits.roles {
+        if roles
+            .insert(
+                role_query_limits.role.clone(),
+                QueryLimits::from(&role_query_limits.limits),
+            )
+            .is_some()
+        {
+            return Err(GraphqlConfigError::DuplicateRoleQueryLimits {
+                role: role_query_limits.role.clone(),
+            });
+        }
+    }
+    Ok(QueryLimitsConfig {
+        default: query_limits
+            .default
+            .as_ref()
+            .map(QueryLimits::from)
+            .unwrap_or_default(),
+        roles,
+    })
+}
+
 fn fallback_graphql_config() -> &'static graphql_config::GraphqlConfig {
     static CELL: OnceLock<graphql_config::GraphqlConfig> = OnceLock::new();
     CELL.get_or_init(|| {
@@ -273,6 +314,8 @@ fn fallback_graphql_config() -> &'static graphql_config::GraphqlConfig {
             },
             subscription: None,
             apollo_federation: None,
+            query_limits: None,
+            batching: None,
         })
     })
 }
@@ -1,7 +1,8 @@
 use super::error::GraphqlConfigError;
 use lang_graphql::ast::common as ast;
+use open_dds::permissions::Role;
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct GraphqlConfig {
@@ -22,6 +23,42 @@ pub struct GlobalGraphqlConfig {
     pub bypass_relation_comparisons_ndc_capability: bool,
     pub propagate_boolean_expression_deprecation_status: bool,
     pub multiple_order_by_input_object_fields: MultipleOrderByInputObjectFields,
+    pub query_limits: Option<QueryLimitsConfig>,
+    /// The maximum number of operations in a batched request, where `None` disables batching
+    pub max_batch_size: Option<u32>,
+}
+
+/// The limits on GraphQL operations, which may differ per role
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct QueryLimitsConfig {
+    pub default: QueryLimits,
+    pub roles: BTreeMap<Role, QueryLimits>,
+}
+
+impl QueryLimitsConfig {
+    /// The limits applied to the operations run by the given role
+    pub fn for_role(&self, role: &Role) -> &QueryLimits {
+        self.roles.get(role).unwrap_or(&self.default)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct QueryLimits {
+    pub max_depth: Option<u32>,
+    pub max_fields: Option<u32>,
+    pub max_aliases: Option<u32>,
+    pub max_cost: Option<u64>,
+}
+
+impl From<&open_dds::graphql_config::QueryLimits> for QueryLimits {
+    fn from(limits: &open_dds::graphql_config::QueryLimits) -> Self {
+        QueryLimits {
+            max_depth: limits.max_depth,
+            max_fields: limits.max_fields,
+            max_aliases: limits.max_aliases,
+            max_cost: limits.max_cost,
+        }
+    }
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
@@ -9,11 +9,13 @@ mod conflicting_types;
 pub mod data_connector_scalar_types;
 pub mod data_connectors;
 pub mod graphql_config;
+pub mod model_mutations;
 pub mod model_permissions;
 pub mod models;
 pub mod models_graphql;
 pub mod object_relationships;
 pub mod object_types;
+pub mod operation_collections;
 pub mod order_by_expressions;
 pub mod plugins;
 pub mod relationships;
@@ -256,6 +258,7 @@ fn resolve_internal(
         &commands,
         &aggregate_expressions,
         &graphql_config,
+        &metadata_accessor.flags,
     )?;
 
     all_issues.extend(issues.into_iter().map(Warning::from));
@@ -286,6 +289,7 @@ fn resolve_internal(
         &boolean_expression_types,
         &mut track_root_fields,
         &graphql_config,
+        &data_connector_scalars,
         &scalar_types,
         &mut order_by_expressions,
         &mut graphql_types,
@@ -334,6 +338,15 @@ fn resolve_internal(
 
     let plugin_configs = plugins::resolve(&metadata_accessor);
 
+    let operation_collections = operation_collections::resolve(&metadata_accessor)?;
+
+    // Validate the procedures used to insert, update and delete the objects of models
+    let model_mutations = model_mutations::resolve(
+        &metadata_accessor,
+        &models_with_permissions,
+        &commands_with_permissions,
+    )?;
+
     // check for duplicate names across types
     all_issues.extend(conflicting_types::check_conflicting_names_across_types(
         &scalar_types_with_representations,
@@ -355,6 +368,8 @@ fn resolve_internal(
             graphql_config: graphql_config.global,
             roles,
             plugin_configs,
+            operation_collections,
+            model_mutations,
         },
         all_warnings,
     ))
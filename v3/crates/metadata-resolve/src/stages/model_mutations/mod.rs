@@ -0,0 +1,208 @@
+use std::collections::BTreeMap;
+
+use indexmap::IndexMap;
+use open_dds::arguments::ArgumentName;
+use open_dds::commands::{CommandName, DataConnectorCommand};
+use open_dds::models::ModelName;
+use serde::{Deserialize, Serialize};
+
+use crate::stages::{command_permissions, model_permissions};
+use crate::types::subgraph::{Qualified, QualifiedBaseType, QualifiedTypeName};
+
+/// The procedures used to insert, update and delete the objects of a model.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct ModelMutations {
+    pub insert: Option<ModelMutationProcedure>,
+    pub update: Option<ModelMutationProcedure>,
+    pub delete: Option<ModelMutationProcedure>,
+}
+
+/// A procedure command mutating an object of a model. The key argument takes the unique
+/// identifier of the object to update or delete, and the object argument takes the fields of the
+/// object to insert or update. Without an object argument, the fields are passed as the
+/// arguments of the same name.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ModelMutationProcedure {
+    pub command_name: Qualified<CommandName>,
+    pub key_argument: Option<ArgumentName>,
+    pub object_argument: Option<ArgumentName>,
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, derive_more::Display)]
+pub enum ModelMutationKind {
+    #[display("insert")]
+    Insert,
+    #[display("update")]
+    Update,
+    #[display("delete")]
+    Delete,
+}
+
+pub fn resolve(
+    metadata_accessor: &open_dds::accessor::MetadataAccessor,
+    models: &IndexMap<Qualified<ModelName>, model_permissions::ModelWithPermissions>,
+    commands: &IndexMap<Qualified<CommandName>, command_permissions::CommandWithPermissions>,
+) -> Result<BTreeMap<Qualified<ModelName>, ModelMutations>, ModelMutationError> {
+    let mut model_mutations = BTreeMap::new();
+    for open_dds::accessor::QualifiedObject {
+        path: _,
+        subgraph,
+        object: model,
+    } in &metadata_accessor.models
+    {
+        let Some(mutations) = model.mutations() else {
+            continue;
+        };
+        let model_name = Qualified::new(subgraph.clone(), model.name().clone());
+        let Some(model) = models.get(&model_name) else {
+            continue;
+        };
+        let resolve_procedure =
+            |kind: ModelMutationKind,
+             command_name: &CommandName,
+             key_argument: Option<&ArgumentName>,
+             object_argument: Option<&ArgumentName>| {
+                resolve_mutation_procedure(
+                    &model_name,
+                    model,
+                    commands,
+                    kind,
+                    Qualified::new(subgraph.clone(), command_name.clone()),
+                    key_argument,
+                    object_argument,
+                )
+            };
+        let resolved_mutations = ModelMutations {
+            insert: mutations
+                .insert
+                .as_ref()
+                .map(|insert| {
+                    resolve_procedure(
+                        ModelMutationKind::Insert,
+                        &insert.command_name,
+                        None,
+                        insert.object_argument.as_ref(),
+                    )
+                })
+                .transpose()?,
+            update: mutations
+                .update
+                .as_ref()
+                .map(|update| {
+                    resolve_procedure(
+                        ModelMutationKind::Update,
+                        &update.command_name,
+                        Some(&update.key_argument),
+                        update.object_argument.as_ref(),
+                    )
+                })
+                .transpose()?,
+            delete: mutations
+                .delete
+                .as_ref()
+                .map(|delete| {
+                    resolve_procedure(
+                        ModelMutationKind::Delete,
+                        &delete.command_name,
+                        Some(&delete.key_argument),
+                        None,
+                    )
+                })
+                .transpose()?,
+        };
+        model_mutations.insert(model_name, resolved_mutations);
+    }
+    Ok(model_mutations)
+}
+
+fn resolve_mutation_procedure(
+    model_name: &Qualified<ModelName>,
+    model: &model_permissions::ModelWithPermissions,
+    commands: &IndexMap<Qualified<CommandName>, command_permissions::CommandWithPermissions>,
+    kind: ModelMutationKind,
+    command_name: Qualified<CommandName>,
+    key_argument: Option<&ArgumentName>,
+    object_argument: Option<&ArgumentName>,
+) -> Result<ModelMutationProcedure, ModelMutationError> {
+    let command = &commands
+        .get(&command_name)
+        .ok_or_else(|| ModelMutationError::UnknownCommand {
+            model_name: model_name.clone(),
+            kind,
+            command_name: command_name.clone(),
+        })?
+        .command;
+
+    let is_procedure = command
+        .source
+        .as_ref()
+        .is_some_and(|source| matches!(source.source, DataConnectorCommand::Procedure(_)));
+    if !is_procedure {
+        return Err(ModelMutationError::CommandIsNotAProcedure {
+            model_name: model_name.clone(),
+            kind,
+            command_name,
+        });
+    }
+
+    for argument_name in key_argument.iter().chain(object_argument.iter()) {
+        if !command.arguments.contains_key(*argument_name) {
+            return Err(ModelMutationError::UnknownCommandArgument {
+                model_name: model_name.clone(),
+                kind,
+                command_name,
+                argument_name: (*argument_name).clone(),
+            });
+        }
+    }
+
+    let returns_model_type = matches!(
+        &command.output_type.underlying_type,
+        QualifiedBaseType::Named(QualifiedTypeName::Custom(type_name))
+            if *type_name == model.model.data_type
+    );
+    if !returns_model_type {
+        return Err(ModelMutationError::CommandOutputTypeMismatch {
+            model_name: model_name.clone(),
+            kind,
+            command_name,
+            model_type: model.model.data_type.clone(),
+        });
+    }
+
+    Ok(ModelMutationProcedure {
+        command_name,
+        key_argument: key_argument.cloned(),
+        object_argument: object_argument.cloned(),
+    })
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ModelMutationError {
+    #[error("the {kind:} procedure of model {model_name:} refers to the command {command_name:}, which does not exist")]
+    UnknownCommand {
+        model_name: Qualified<ModelName>,
+        kind: ModelMutationKind,
+        command_name: Qualified<CommandName>,
+    },
+    #[error("the {kind:} procedure of model {model_name:} refers to the command {command_name:}, which is not backed by a procedure")]
+    CommandIsNotAProcedure {
+        model_name: Qualified<ModelName>,
+        kind: ModelMutationKind,
+        command_name: Qualified<CommandName>,
+    },
+    #[error("the {kind:} procedure of model {model_name:} refers to the argument {argument_name:}, which is not an argument of the command {command_name:}")]
+    UnknownCommandArgument {
+        model_name: Qualified<ModelName>,
+        kind: ModelMutationKind,
+        command_name: Qualified<CommandName>,
+        argument_name: ArgumentName,
+    },
+    #[error("the {kind:} procedure of model {model_name:} refers to the command {command_name:}, which does not return an object of type {model_type:}")]
+    CommandOutputTypeMismatch {
+        model_name: Qualified<ModelName>,
+        kind: ModelMutationKind,
+        command_name: Qualified<CommandName>,
+        model_type: Qualified<open_dds::types::CustomTypeName>,
+    },
+}
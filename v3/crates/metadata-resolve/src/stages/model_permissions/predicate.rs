@@ -290,9 +290,31 @@ fn resolve_relationship(
         relationship_type,
         target_typename,
         mappings,
+        value_mappings,
         relationship_aggregate: _,
+        groups_field_name: _,
     } = model_relationship_target;
 
+    // The values a relationship binds on its target are not applied in predicates
+    if !value_mappings.is_empty() {
+        return Err(TypePredicateError::UnsupportedFeature {
+            message: format!(
+                "Predicate cannot be built using relationship {} as it maps values to its target",
+                relationship_name.value
+            ),
+        });
+    }
+
+    // Comparisons across relationships can only use top-level fields on either side
+    if model_relationship_target.has_nested_field_mappings() {
+        return Err(TypePredicateError::UnsupportedFeature {
+            message: format!(
+                "Predicate cannot be built using relationship {} as it maps nested fields",
+                relationship_name.value
+            ),
+        });
+    }
+
     let target_model = models.get(model_name).ok_or_else(|| {
         TypePredicateError::UnknownModelUsedInRelationshipTypePredicate {
             type_name: type_name.clone(),
@@ -92,6 +92,8 @@ pub enum ModelsError {
         model_name: Qualified<ModelName>,
         field_name: FieldName,
     },
+    #[error("the response cache of model {model_name:} must have a ttlSeconds greater than 0")]
+    ZeroResponseCacheTtl { model_name: Qualified<ModelName> },
     #[error("the following model is defined more than once: {name:}")]
     DuplicateModelDefinition { name: Qualified<ModelName> },
     #[error("Error in order by expression {order_by_expression_identifier}: {error}")]
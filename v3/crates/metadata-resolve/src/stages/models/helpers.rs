@@ -48,6 +48,7 @@ pub fn get_ndc_column_for_comparison<F: Fn() -> String>(
 
     Ok(NdcColumnForComparison {
         column: field_mapping.column.clone(),
+        field_path: vec![],
         equal_operator: equal_operator.clone(),
     })
 }
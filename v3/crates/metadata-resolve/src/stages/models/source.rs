@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use super::types::{Model, ModelSource, ModelsIssue};
+use super::types::{Model, ModelSource, ModelsIssue, DEFAULT_REMOTE_ORDERING_ROW_LIMIT};
 use jsonpath::JSONPath;
 use open_dds::data_connector::{DataConnectorName, DataConnectorObjectType};
 use open_dds::identifier::SubgraphName;
@@ -44,6 +44,15 @@ pub(crate) fn resolve_model_source(
             model_name: model.name.clone(),
         })?;
     }
+    if model_source
+        .response_cache
+        .as_ref()
+        .is_some_and(|response_cache| response_cache.ttl_seconds == 0)
+    {
+        Err(ModelsError::ZeroResponseCacheTtl {
+            model_name: model.name.clone(),
+        })?;
+    }
     let qualified_data_connector_name = Qualified::new(
         subgraph.clone(),
         model_source.data_connector_name.value.clone(),
@@ -154,6 +163,13 @@ pub(crate) fn resolve_model_source(
         type_mappings,
         argument_mappings,
         data_connector_link_argument_presets,
+        response_cache: model_source
+            .response_cache
+            .as_ref()
+            .map(data_connectors::ResponseCachePolicy::new),
+        remote_ordering_row_limit: model_source
+            .remote_ordering_row_limit
+            .unwrap_or(DEFAULT_REMOTE_ORDERING_ROW_LIMIT),
         source_arguments,
     };
 
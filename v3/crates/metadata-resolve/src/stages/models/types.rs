@@ -37,9 +37,17 @@ pub struct ModelSource {
     pub argument_mappings: BTreeMap<ArgumentName, DataConnectorArgumentName>,
     pub data_connector_link_argument_presets:
         BTreeMap<DataConnectorArgumentName, ArgumentPresetValue>,
+    pub response_cache: Option<data_connectors::ResponseCachePolicy>,
+    /// the maximum number of rows fetched to be sorted by the engine when ordering by fields of
+    /// remote relationships
+    pub remote_ordering_row_limit: u32,
     pub source_arguments: BTreeMap<DataConnectorArgumentName, ndc_models::Type>,
 }
 
+/// The number of rows of a model that can be sorted by the engine when the model source does not
+/// set a limit
+pub const DEFAULT_REMOTE_ORDERING_ROW_LIMIT: u32 = 10_000;
+
 pub struct ModelsOutput {
     pub models: IndexMap<Qualified<ModelName>, Model>,
     pub global_id_enabled_types: BTreeMap<Qualified<CustomTypeName>, Vec<Qualified<ModelName>>>,
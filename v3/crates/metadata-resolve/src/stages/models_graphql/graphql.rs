@@ -1,25 +1,35 @@
 use std::sync::Arc;
 
-use open_dds::aggregates::AggregateExpressionName;
-use open_dds::models::{ModelGraphQlDefinitionV2, ModelName};
+use open_dds::aggregates::{AggregateExpressionName, ExtractionFunctionName};
+use open_dds::data_connector::{DataConnectorName, DataConnectorScalarType};
+use open_dds::models::{
+    CacheScope, ModelGraphQlDefinitionV2, ModelGroupsExtractionGraphQlDefinition, ModelName,
+};
+use open_dds::query::{ComparisonOperator, ExtractionFunction};
 use open_dds::relationships::{ModelRelationshipTarget, RelationshipTarget};
+use open_dds::types::OperatorName;
 
 use super::types::{
-    LimitFieldGraphqlConfig, ModelGraphQlApi, ModelGraphqlApiArgumentsConfig, ModelGraphqlIssue,
-    ModelOrderByExpression, OffsetFieldGraphqlConfig, OrderByExpressionInfo,
-    SelectAggregateGraphQlDefinition, SelectManyGraphQlDefinition, SelectUniqueGraphQlDefinition,
+    GroupKeyExtractionGraphQlDefinition, GroupsFilterGraphQlDefinition,
+    GroupsOrderByGraphQlDefinition, LimitFieldGraphqlConfig, ModelCacheHint, ModelGraphQlApi,
+    ModelGraphqlApiArgumentsConfig, ModelGraphqlIssue, ModelOrderByExpression,
+    OffsetFieldGraphqlConfig, OrderByExpressionInfo, SelectAggregateGraphQlDefinition,
+    SelectGroupsGraphQlDefinition, SelectManyGraphQlDefinition, SelectUniqueGraphQlDefinition,
     SubscriptionGraphQlDefinition, UniqueIdentifierField,
 };
+use crate::helpers::ndc_validation::unwrap_nullable_type;
 use crate::helpers::types::{mk_name, TrackGraphQLRootFields};
 use crate::stages::order_by_expressions::{OrderByExpressionIdentifier, OrderByExpressions};
-use crate::stages::{graphql_config, models, object_types};
+use crate::stages::{data_connector_scalar_types, graphql_config, models, object_types};
 use crate::types::error::Error;
-use crate::types::subgraph::Qualified;
-use crate::Warning;
+use crate::types::subgraph::{
+    mk_qualified_type_name, Qualified, QualifiedBaseType, QualifiedTypeReference,
+};
+use crate::{LogicalOperatorsGraphqlConfig, NdcVersion, Warning};
 use indexmap::IndexMap;
 use lang_graphql::ast::common::{self as ast};
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 pub(crate) fn resolve_model_graphql_api(
     metadata_accessor: &open_dds::accessor::MetadataAccessor,
@@ -31,6 +41,10 @@ pub(crate) fn resolve_model_graphql_api(
     order_by_expression_identifier: Option<&Qualified<OrderByExpressionIdentifier>>,
     order_by_expressions: &OrderByExpressions,
     graphql_config: &graphql_config::GraphqlConfig,
+    data_connector_scalars: &BTreeMap<
+        Qualified<DataConnectorName>,
+        data_connector_scalar_types::DataConnectorScalars,
+    >,
     graphql_types: &mut graphql_config::GraphqlTypeNames,
     issues: &mut Vec<Warning>,
 ) -> Result<ModelGraphQlApi, Error> {
@@ -288,6 +302,187 @@ pub(crate) fn resolve_model_graphql_api(
         _ => None,
     };
 
+    // record groups root field
+    graphql_api.select_groups = match (
+        &model_graphql_definition.groups,
+        aggregate_expression_name,
+        &graphql_config.query.aggregate_config,
+    ) {
+        (None, _, _) => None,
+        (Some(_graphql_groups), None, _) => {
+            // Groups aggregate over their objects, so without an aggregate expression
+            // there is nothing to select from them
+            issues.push(
+                ModelGraphqlIssue::MissingAggregateExpressionForGroups {
+                    model_name: model_name.clone(),
+                }
+                .into(),
+            );
+            None
+        }
+        (Some(_graphql_groups), Some(_aggregate_expression_name), None) => {
+            issues.push(
+                ModelGraphqlIssue::MissingGroupsFilterInputFieldNameInGraphqlConfig {
+                    model_name: model_name.clone(),
+                }
+                .into(),
+            );
+            None
+        }
+        (Some(graphql_groups), Some(aggregate_expression_name), Some(aggregate_config)) => {
+            let groups_root_field = mk_name(graphql_groups.query_root_field.as_str())?;
+            // Let's track and check if the groups field name is already used
+            track_root_fields
+                .track_query_root_field(&groups_root_field)
+                .unwrap_or_else(|error| {
+                    issues.push(
+                        ModelGraphqlIssue::DuplicateRootField {
+                            model_name: model_name.clone(),
+                            error,
+                        }
+                        .into(),
+                    );
+                });
+
+            let group_type_name = ast::TypeName(mk_name(graphql_groups.group_type_name.as_str())?);
+            graphql_types.store(Some(&group_type_name))?;
+            let group_key_type_name =
+                ast::TypeName(mk_name(graphql_groups.group_key_type_name.as_str())?);
+            graphql_types.store(Some(&group_key_type_name))?;
+
+            let extractions = graphql_groups
+                .extractions
+                .iter()
+                .map(|extraction| {
+                    let group_key_field_name = mk_name(extraction.group_key_field_name.as_str())?;
+                    let extraction =
+                        resolve_group_key_extraction(model, extraction, data_connector_scalars)?;
+                    graphql_types.store(Some(&extraction.enum_type_name))?;
+                    Ok((group_key_field_name, extraction))
+                })
+                .collect::<Result<BTreeMap<_, _>, Error>>()?;
+
+            let grouping_capabilities = model.source.as_ref().and_then(|model_source| {
+                model_source
+                    .data_connector
+                    .capabilities
+                    .supports_aggregates
+                    .as_ref()
+                    .and_then(|aggregate_capabilities| {
+                        aggregate_capabilities.supports_grouping.as_ref()
+                    })
+                    .map(|grouping_capabilities| {
+                        (
+                            grouping_capabilities,
+                            model_source
+                                .data_connector
+                                .capabilities
+                                .supported_ndc_version,
+                        )
+                    })
+            });
+
+            let filter = graphql_groups
+                .filter
+                .as_ref()
+                .map(|filter| -> Result<_, Error> {
+                    let count_comparison_operators = grouping_capabilities
+                        .filter(|(grouping_capabilities, _)| {
+                            grouping_capabilities.supports_filtering
+                        })
+                        .and_then(|(grouping_capabilities, ndc_version)| {
+                            grouping_capabilities
+                                .count_comparison_operators
+                                .as_ref()
+                                .map(|operators| count_comparison_operators(operators, ndc_version))
+                        })
+                        .transpose()?
+                        .filter(|operators| !operators.is_empty());
+                    let Some(count_comparison_operators) = count_comparison_operators else {
+                        issues.push(
+                            ModelGraphqlIssue::GroupsFilterNotSupported {
+                                model_name: model_name.clone(),
+                            }
+                            .into(),
+                        );
+                        return Ok(None);
+                    };
+                    let input_type_name = ast::TypeName(mk_name(filter.input_type_name.as_str())?);
+                    graphql_types.store(Some(&input_type_name))?;
+                    let count_comparison_type_name =
+                        ast::TypeName(mk_name(filter.count_comparison_type_name.as_str())?);
+                    graphql_types.store(Some(&count_comparison_type_name))?;
+                    Ok(Some(GroupsFilterGraphQlDefinition {
+                        argument_name: mk_name(filter.argument_name.as_str())?,
+                        input_type_name,
+                        count_comparison_type_name,
+                        logical_operators: graphql_config.query.filter_input_config.as_ref().map(
+                            |filter_input_config| LogicalOperatorsGraphqlConfig {
+                                and_operator_name: filter_input_config.operator_names.and.clone(),
+                                or_operator_name: filter_input_config.operator_names.or.clone(),
+                                not_operator_name: filter_input_config.operator_names.not.clone(),
+                            },
+                        ),
+                        count_comparison_operators,
+                    }))
+                })
+                .transpose()?
+                .flatten();
+
+            let order_by = graphql_groups
+                .order_by
+                .as_ref()
+                .map(|order_by| -> Result<_, Error> {
+                    if graphql_config.global.order_by_input.is_none() {
+                        issues.push(
+                            ModelGraphqlIssue::MissingOrderByInputConfigForGroups {
+                                model_name: model_name.clone(),
+                            }
+                            .into(),
+                        );
+                        return Ok(None);
+                    }
+                    if !grouping_capabilities.is_some_and(|(grouping_capabilities, _)| {
+                        grouping_capabilities.supports_ordering
+                    }) {
+                        issues.push(
+                            ModelGraphqlIssue::GroupsOrderByNotSupported {
+                                model_name: model_name.clone(),
+                            }
+                            .into(),
+                        );
+                        return Ok(None);
+                    }
+                    let input_type_name =
+                        ast::TypeName(mk_name(order_by.input_type_name.as_str())?);
+                    graphql_types.store(Some(&input_type_name))?;
+                    Ok(Some(GroupsOrderByGraphQlDefinition {
+                        argument_name: mk_name(order_by.argument_name.as_str())?,
+                        input_type_name,
+                    }))
+                })
+                .transpose()?
+                .flatten();
+
+            Some(SelectGroupsGraphQlDefinition {
+                query_root_field: groups_root_field,
+                group_type_name,
+                group_key_type_name,
+                group_key_field_name: mk_name(graphql_groups.group_key_field_name.as_str())?,
+                group_aggregate_field_name: mk_name(
+                    graphql_groups.group_aggregate_field_name.as_str(),
+                )?,
+                extractions,
+                filter,
+                order_by,
+                description: graphql_groups.description.clone(),
+                deprecated: graphql_groups.deprecated.clone(),
+                aggregate_expression_name: aggregate_expression_name.clone(),
+                filter_input_field_name: aggregate_config.filter_input_field_name.clone(),
+            })
+        }
+    };
+
     // record limit and offset field names
     graphql_api.limit_field = graphql_config
         .query
@@ -306,6 +501,14 @@ pub(crate) fn resolve_model_graphql_api(
                 field_name: offset_field.clone(),
             });
 
+    graphql_api.cache_hint = model_graphql_definition
+        .cache_hint
+        .as_ref()
+        .map(|cache_hint| ModelCacheHint {
+            max_age: cache_hint.max_age,
+            scope: cache_hint.scope.unwrap_or(CacheScope::Private),
+        });
+
     if model.arguments.is_empty() {
         if model_graphql_definition.arguments_input_type.is_some() {
             issues.push(
@@ -341,6 +544,206 @@ pub(crate) fn resolve_model_graphql_api(
     Ok(graphql_api)
 }
 
+/// Resolve a field of the group key that holds the parts extracted from a field of the model,
+/// which can be any of the parts that the data connector of the model can extract from it
+fn resolve_group_key_extraction(
+    model: &models::Model,
+    extraction: &ModelGroupsExtractionGraphQlDefinition,
+    data_connector_scalars: &BTreeMap<
+        Qualified<DataConnectorName>,
+        data_connector_scalar_types::DataConnectorScalars,
+    >,
+) -> Result<GroupKeyExtractionGraphQlDefinition, Error> {
+    let field_name = &extraction.field_name;
+    if !model.type_fields.contains_key(field_name) {
+        return Err(Error::UnknownFieldInGroupsExtraction {
+            model_name: model.name.clone(),
+            field_name: field_name.clone(),
+        });
+    }
+    let not_supported = || Error::GroupsExtractionNotSupported {
+        model_name: model.name.clone(),
+        field_name: field_name.clone(),
+    };
+
+    let model_source = model.source.as_ref().ok_or_else(not_supported)?;
+    let field_mapping = model_source
+        .type_mappings
+        .get(&model.data_type)
+        .and_then(|type_mapping| {
+            let object_types::TypeMapping::Object { field_mappings, .. } = type_mapping;
+            field_mappings.get(field_name)
+        })
+        .ok_or_else(not_supported)?;
+    let ndc_models::Type::Named {
+        name: scalar_type_name,
+    } = unwrap_nullable_type(&field_mapping.column_type)
+    else {
+        return Err(not_supported());
+    };
+    let scalars = data_connector_scalars
+        .get(&model_source.data_connector.name)
+        .ok_or_else(not_supported)?;
+    let scalar_type = scalars
+        .by_ndc_type
+        .get(&DataConnectorScalarType::from(scalar_type_name.as_str()))
+        .ok_or_else(not_supported)?
+        .scalar_type;
+
+    let mut functions = BTreeMap::new();
+    let mut result_types = BTreeSet::new();
+    for (function_name, definition) in &scalar_type.extraction_functions {
+        let (function, result_type) = match definition {
+            ndc_models::ExtractionFunctionDefinition::Nanosecond { result_type } => {
+                (ExtractionFunction::Nanosecond, result_type.as_str())
+            }
+            ndc_models::ExtractionFunctionDefinition::Microsecond { result_type } => {
+                (ExtractionFunction::Microsecond, result_type.as_str())
+            }
+            ndc_models::ExtractionFunctionDefinition::Second { result_type } => {
+                (ExtractionFunction::Second, result_type.as_str())
+            }
+            ndc_models::ExtractionFunctionDefinition::Minute { result_type } => {
+                (ExtractionFunction::Minute, result_type.as_str())
+            }
+            ndc_models::ExtractionFunctionDefinition::Hour { result_type } => {
+                (ExtractionFunction::Hour, result_type.as_str())
+            }
+            ndc_models::ExtractionFunctionDefinition::Day { result_type } => {
+                (ExtractionFunction::Day, result_type.as_str())
+            }
+            ndc_models::ExtractionFunctionDefinition::Week { result_type } => {
+                (ExtractionFunction::Week, result_type.as_str())
+            }
+            ndc_models::ExtractionFunctionDefinition::Month { result_type } => {
+                (ExtractionFunction::Month, result_type.as_str())
+            }
+            ndc_models::ExtractionFunctionDefinition::Quarter { result_type } => {
+                (ExtractionFunction::Quarter, result_type.as_str())
+            }
+            ndc_models::ExtractionFunctionDefinition::Year { result_type } => {
+                (ExtractionFunction::Year, result_type.as_str())
+            }
+            ndc_models::ExtractionFunctionDefinition::DayOfWeek { result_type } => {
+                (ExtractionFunction::DayOfWeek, result_type.as_str())
+            }
+            ndc_models::ExtractionFunctionDefinition::DayOfYear { result_type } => {
+                (ExtractionFunction::DayOfYear, result_type.as_str())
+            }
+            ndc_models::ExtractionFunctionDefinition::Custom { result_type } => {
+                // only parts of a scalar type can be part of a group key
+                let ndc_models::Type::Named { name } = unwrap_nullable_type(result_type) else {
+                    continue;
+                };
+                (
+                    ExtractionFunction::Custom {
+                        name: ExtractionFunctionName::from(function_name.as_str()),
+                    },
+                    name.as_str(),
+                )
+            }
+        };
+        // duplicate functions are reported when resolving the object types using the scalar types
+        if let Ok(enum_value) = mk_name(&function.to_string()) {
+            functions.entry(enum_value).or_insert(function);
+            result_types.insert(DataConnectorScalarType::from(result_type));
+        }
+    }
+
+    let mut result_types = result_types.into_iter();
+    let result_type = result_types.next().ok_or_else(not_supported)?;
+    if result_types.next().is_some() {
+        return Err(Error::GroupsExtractionResultTypesDiffer {
+            model_name: model.name.clone(),
+            field_name: field_name.clone(),
+        });
+    }
+    let representation = scalars
+        .by_ndc_type
+        .get(&result_type)
+        .and_then(|scalar| scalar.representation.as_ref())
+        .ok_or_else(|| Error::GroupsExtractionResultTypeWithoutRepresentation {
+            model_name: model.name.clone(),
+            field_name: field_name.clone(),
+            result_type: result_type.clone(),
+        })?;
+
+    Ok(GroupKeyExtractionGraphQlDefinition {
+        field_name: field_name.clone(),
+        enum_type_name: ast::TypeName(mk_name(extraction.enum_type_name.as_str())?),
+        functions,
+        // a group may have no value for the field to extract the part from
+        result_type: QualifiedTypeReference {
+            underlying_type: QualifiedBaseType::Named(mk_qualified_type_name(
+                representation,
+                &model.name.subgraph,
+            )),
+            nullable: true,
+        },
+    })
+}
+
+/// The operators that the number of objects in a group can be compared with, by the name of
+/// their field in the GraphQL API
+fn count_comparison_operators(
+    operators: &object_types::ComparisonOperators,
+    ndc_version: NdcVersion,
+) -> Result<BTreeMap<ast::Name, ComparisonOperator>, Error> {
+    let mut count_comparison_operators = BTreeMap::new();
+    let standard_operators = [
+        (
+            "_eq",
+            operators.get_eq_operator(ndc_version),
+            ComparisonOperator::Equals,
+        ),
+        (
+            "_neq",
+            operators.get_eq_operator(ndc_version),
+            ComparisonOperator::NotEquals,
+        ),
+        (
+            "_lt",
+            operators.get_lt_operator(ndc_version),
+            ComparisonOperator::LessThan,
+        ),
+        (
+            "_lte",
+            operators.get_lte_operator(ndc_version),
+            ComparisonOperator::LessThanOrEqual,
+        ),
+        (
+            "_gt",
+            operators.get_gt_operator(ndc_version),
+            ComparisonOperator::GreaterThan,
+        ),
+        (
+            "_gte",
+            operators.get_gte_operator(ndc_version),
+            ComparisonOperator::GreaterThanOrEqual,
+        ),
+    ];
+    let mut standard_operator_names = BTreeSet::new();
+    for (field_name, data_connector_operator_name, operator) in standard_operators {
+        if let Some(data_connector_operator_name) = data_connector_operator_name {
+            standard_operator_names.insert(data_connector_operator_name);
+            count_comparison_operators.insert(mk_name(field_name)?, operator);
+        }
+    }
+    for other_operator in &operators.other_operators {
+        if !standard_operator_names.contains(other_operator) {
+            // operators whose names are not valid GraphQL names cannot be used
+            if let Ok(field_name) = mk_name(other_operator.as_str()) {
+                count_comparison_operators
+                    .entry(field_name)
+                    .or_insert_with(|| {
+                        ComparisonOperator::Custom(OperatorName::from(other_operator.as_str()))
+                    });
+            }
+        }
+    }
+    Ok(count_comparison_operators)
+}
+
 fn is_model_used_in_any_aggregate_relationship(
     metadata_accessor: &open_dds::accessor::MetadataAccessor,
     model_name: &Qualified<ModelName>,
@@ -7,19 +7,25 @@ use crate::Warning;
 use indexmap::IndexMap;
 use std::collections::BTreeMap;
 
-use open_dds::{commands::CommandName, models::ModelName, types::CustomTypeName};
+use open_dds::{
+    commands::CommandName, data_connector::DataConnectorName, models::ModelName,
+    types::CustomTypeName,
+};
 
 use crate::helpers::types::TrackGraphQLRootFields;
 use crate::stages::{
-    boolean_expressions, commands, graphql_config, models, object_relationships, scalar_types,
+    boolean_expressions, commands, data_connector_scalar_types, graphql_config, models,
+    object_relationships, scalar_types,
 };
 use crate::types::error::Error;
 use crate::types::subgraph::Qualified;
 
 pub(crate) use types::ModelWithGraphql;
 pub use types::{
-    ModelGraphQlApi, ModelGraphqlIssue, ModelOrderByExpression, ModelsWithGraphqlOutput,
-    SelectAggregateGraphQlDefinition, SelectManyGraphQlDefinition, SelectUniqueGraphQlDefinition,
+    GroupKeyExtractionGraphQlDefinition, GroupsFilterGraphQlDefinition,
+    GroupsOrderByGraphQlDefinition, ModelCacheHint, ModelGraphQlApi, ModelGraphqlIssue,
+    ModelOrderByExpression, ModelsWithGraphqlOutput, SelectAggregateGraphQlDefinition,
+    SelectGroupsGraphQlDefinition, SelectManyGraphQlDefinition, SelectUniqueGraphQlDefinition,
     SubscriptionGraphQlDefinition, UniqueIdentifierField,
 };
 
@@ -36,6 +42,10 @@ pub fn resolve(
     boolean_expression_types: &boolean_expressions::BooleanExpressionTypes,
     track_root_fields: &mut TrackGraphQLRootFields,
     graphql_config: &graphql_config::GraphqlConfig,
+    data_connector_scalars: &BTreeMap<
+        Qualified<DataConnectorName>,
+        data_connector_scalar_types::DataConnectorScalars,
+    >,
     scalar_types: &BTreeMap<Qualified<CustomTypeName>, scalar_types::ScalarTypeRepresentation>,
     order_by_expressions: &mut order_by_expressions::OrderByExpressions,
     graphql_types: &mut graphql_config::GraphqlTypeNames,
@@ -103,6 +113,7 @@ pub fn resolve(
                 order_by_expression.as_ref(),
                 order_by_expressions,
                 graphql_config,
+                data_connector_scalars,
                 graphql_types,
                 &mut output.issues,
             )?,
@@ -119,5 +130,30 @@ pub fn resolve(
         );
     }
 
+    // a relationship can only expose groups of its target model if that model has
+    // a groups GraphQL API, as the relationship field reuses its types
+    for (type_name, object_type) in object_types {
+        for relationship_field in object_type.relationship_fields.values() {
+            if let object_relationships::RelationshipTarget::Model(model_relationship_target) =
+                &relationship_field.target
+            {
+                if model_relationship_target.groups_field_name.is_some()
+                    && output
+                        .models_with_graphql
+                        .get(&model_relationship_target.model_name)
+                        .is_some_and(|model| model.graphql_api.select_groups.is_none())
+                {
+                    output.issues.push(Warning::from(
+                        ModelGraphqlIssue::MissingGroupsGraphqlApiForRelationshipTarget {
+                            type_name: type_name.clone(),
+                            relationship_name: relationship_field.relationship_name.clone(),
+                            model_name: model_relationship_target.model_name.clone(),
+                        },
+                    ));
+                }
+            }
+        }
+    }
+
     Ok(output)
 }
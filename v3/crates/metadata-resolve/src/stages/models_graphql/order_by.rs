@@ -16,7 +16,8 @@ use std::collections::{BTreeMap, BTreeSet};
 
 use super::order_by_expressions::{
     OrderByExpressionError, OrderByExpressionGraphqlConfig, OrderByExpressionIdentifier,
-    OrderByExpressions, OrderableField, OrderableScalarField, ScalarOrderByExpression,
+    OrderByExpressions, OrderableField, OrderableRelationshipError, OrderableScalarField,
+    ScalarOrderByExpression,
 };
 
 pub fn resolve_order_by_expression(
@@ -187,6 +188,8 @@ pub fn make_order_by_expression(
         // b) the relationship target is a model with a model source
         // c) it's a local rather than remote relationship
         // d) it's an object relationship
+        // e) it doesn't bind any values on its target
+        // f) it doesn't map any nested fields
         if let object_relationships::RelationshipTarget::Model(model_relationship_target) =
             &relationship.target
         {
@@ -195,7 +198,9 @@ pub fn make_order_by_expression(
                 relationship_type,
                 target_typename: _,
                 mappings: _,
+                value_mappings,
                 relationship_aggregate: _,
+                groups_field_name: _,
             } = model_relationship_target.as_ref();
 
             let target_model = models.get(target_model_name).ok_or_else(|| {
@@ -208,17 +213,21 @@ pub fn make_order_by_expression(
             // check for the source model during the runtime
             if let (Some(target_source), Some(model_source)) = (&target_model.source, &model_source)
             {
-                if order_by_expressions::validate_orderable_relationship(
-                    &model.data_type,
-                    relationship_name,
-                    order_by_expressions::OrderableFieldNestedness::NotNested, // we don't support nested fields in legacy OrderByExpressions
-                    &model_source.data_connector,
-                    &target_source.data_connector.name,
-                )
-                .is_ok()
+                if model_source.data_connector.name == target_source.data_connector.name
+                    && order_by_expressions::validate_orderable_relationship(
+                        &model.data_type,
+                        relationship_name,
+                        order_by_expressions::OrderableFieldNestedness::NotNested, // we don't support nested fields in legacy OrderByExpressions
+                        &model_source.data_connector,
+                        &target_source.data_connector.name,
+                    )
+                    .is_ok()
                 {
                     // TODO(naveen): Support Array relationships in order_by when the support for aggregates is implemented
-                    if open_dds::relationships::RelationshipType::Object == *relationship_type {
+                    if open_dds::relationships::RelationshipType::Object == *relationship_type
+                        && value_mappings.is_empty()
+                        && !model_relationship_target.has_nested_field_mappings()
+                    {
                         // If the relationship target model does not have orderByExpressionType do not include
                         // it in the source model order_by input type.
                         orderable_relationships.insert(
@@ -468,8 +477,48 @@ fn validate_orderable_relationships(
                 },
             })?;
 
+        // The values a relationship binds on its target are not applied when ordering by it
+        let has_value_mappings = match &relationship.target {
+            object_relationships::RelationshipTarget::Model(model_relationship_target) => {
+                !model_relationship_target.value_mappings.is_empty()
+            }
+            object_relationships::RelationshipTarget::Command(command_relationship_target) => {
+                !command_relationship_target.value_mappings.is_empty()
+            }
+        };
+        if has_value_mappings {
+            return Err(models::ModelsError::OrderByExpressionError {
+                order_by_expression_identifier: order_by_expression.identifier.clone(),
+                error: OrderByExpressionError::UnsupportedFeature {
+                    message: format!(
+                        "The relationship {relationship_name} maps values to its target, so it cannot be used in order by expressions"
+                    ),
+                },
+            });
+        }
+
+        // Ordering across relationships can only use top-level fields on either side
+        let has_nested_field_mappings = match &relationship.target {
+            object_relationships::RelationshipTarget::Model(model_relationship_target) => {
+                model_relationship_target.has_nested_field_mappings()
+            }
+            object_relationships::RelationshipTarget::Command(command_relationship_target) => {
+                command_relationship_target.has_nested_field_mappings()
+            }
+        };
+        if has_nested_field_mappings {
+            return Err(models::ModelsError::OrderByExpressionError {
+                order_by_expression_identifier: order_by_expression.identifier.clone(),
+                error: OrderByExpressionError::UnsupportedFeature {
+                    message: format!(
+                        "The relationship {relationship_name} maps nested fields, so it cannot be used in order by expressions"
+                    ),
+                },
+            });
+        }
+
         // Get the target data connector name of the relationship, either from the target model or the target command
-        let target_data_connector_name = match &relationship.target {
+        let (target_data_connector_name, is_command_target) = match &relationship.target {
             object_relationships::RelationshipTarget::Model(model_relationship_target) => {
                 // Get the target model
                 let source = models
@@ -482,7 +531,7 @@ fn validate_orderable_relationships(
 
                 // If the model doesn't have a source, we need to raise an issue
                 if let Some(source) = source {
-                    &source.data_connector.name
+                    (&source.data_connector.name, false)
                 } else {
                     issues.push(
                         models::ModelsIssue::OrderableRelationshipTargetModelMustHaveASource {
@@ -508,7 +557,7 @@ fn validate_orderable_relationships(
 
                 // If the command doesn't have a source, we need to raise an issue
                 if let Some(source) = source {
-                    &source.data_connector.name
+                    (&source.data_connector.name, true)
                 } else {
                     issues.push(
                         models::ModelsIssue::OrderableRelationshipTargetCommandMustHaveASource {
@@ -524,14 +573,28 @@ fn validate_orderable_relationships(
             }
         };
 
+        // Only model targets can be sorted by the engine when they are remote
+        let validation_result = if is_command_target
+            && model_source.data_connector.name != *target_data_connector_name
+        {
+            Err(
+                OrderableRelationshipError::RemoteCommandRelationshipsNotSupported {
+                    orderable_type: order_by_expression.ordered_type.clone(),
+                    relationship_name: relationship_name.clone(),
+                },
+            )
+        } else {
+            order_by_expressions::validate_orderable_relationship(
+                &order_by_expression.ordered_type,
+                relationship_name,
+                orderable_field_nestedness,
+                &model_source.data_connector,
+                target_data_connector_name,
+            )
+        };
+
         // Validate the orderable relationship and log an issue if it fails
-        if let Err(error) = order_by_expressions::validate_orderable_relationship(
-            &order_by_expression.ordered_type,
-            relationship_name,
-            orderable_field_nestedness,
-            &model_source.data_connector,
-            target_data_connector_name,
-        ) {
+        if let Err(error) = validation_result {
             issues.push(
                 models::ModelsIssue::OrderableRelationshipError {
                     order_by_expression_identifier: order_by_expression.identifier.clone(),
@@ -4,14 +4,18 @@ use lang_graphql::ast::common::{self as ast};
 use open_dds::{
     aggregates::AggregateExpressionName,
     data_connector::{DataConnectorColumnName, DataConnectorName},
-    models::ModelName,
-    types::{Deprecated, FieldName},
+    models::{CacheScope, ModelName},
+    query::{ComparisonOperator, ExtractionFunction},
+    relationships::RelationshipName,
+    types::{CustomTypeName, Deprecated, FieldName},
 };
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 use crate::stages::{boolean_expressions, models};
 use crate::types::error::ShouldBeAnError;
 use crate::types::subgraph::{Qualified, QualifiedTypeReference};
+use crate::LogicalOperatorsGraphqlConfig;
 use crate::{helpers::types::NdcColumnForComparison, OrderByExpressionIdentifier};
 
 #[derive(Debug)]
@@ -79,6 +83,54 @@ pub struct SelectAggregateGraphQlDefinition {
     pub subscription: Option<SubscriptionGraphQlDefinition>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SelectGroupsGraphQlDefinition {
+    pub query_root_field: ast::Name,
+    pub group_type_name: ast::TypeName,
+    pub group_key_type_name: ast::TypeName,
+    pub group_key_field_name: ast::Name,
+    pub group_aggregate_field_name: ast::Name,
+    /// The fields of the group key that hold a part of a field, by their name
+    pub extractions: BTreeMap<ast::Name, GroupKeyExtractionGraphQlDefinition>,
+    pub filter: Option<GroupsFilterGraphQlDefinition>,
+    pub order_by: Option<GroupsOrderByGraphQlDefinition>,
+    #[serde(default = "serde_ext::ser_default")]
+    #[serde(skip_serializing_if = "serde_ext::is_ser_default")]
+    pub description: Option<String>,
+    #[serde(default = "serde_ext::ser_default")]
+    #[serde(skip_serializing_if = "serde_ext::is_ser_default")]
+    pub deprecated: Option<Deprecated>,
+    pub aggregate_expression_name: Qualified<AggregateExpressionName>,
+    pub filter_input_field_name: ast::Name,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct GroupKeyExtractionGraphQlDefinition {
+    pub field_name: FieldName,
+    pub enum_type_name: ast::TypeName,
+    /// The parts the data connector can extract from the field, by the name of their enum value
+    pub functions: BTreeMap<ast::Name, ExtractionFunction>,
+    /// The type of the extracted parts, which is the same for all of them
+    pub result_type: QualifiedTypeReference,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct GroupsFilterGraphQlDefinition {
+    pub argument_name: ast::Name,
+    pub input_type_name: ast::TypeName,
+    pub count_comparison_type_name: ast::TypeName,
+    /// The names of the logical operators, if the GraphqlConfig defines them
+    pub logical_operators: Option<LogicalOperatorsGraphqlConfig>,
+    /// The operators the number of objects in a group can be compared with, by their field name
+    pub count_comparison_operators: BTreeMap<ast::Name, ComparisonOperator>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct GroupsOrderByGraphQlDefinition {
+    pub argument_name: ast::Name,
+    pub input_type_name: ast::TypeName,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct SubscriptionGraphQlDefinition {
     pub root_field: ast::Name,
@@ -126,10 +178,19 @@ pub struct ModelGraphQlApi {
     pub select_uniques: Vec<SelectUniqueGraphQlDefinition>,
     pub select_many: Option<SelectManyGraphQlDefinition>,
     pub select_aggregate: Option<SelectAggregateGraphQlDefinition>,
+    pub select_groups: Option<SelectGroupsGraphQlDefinition>,
     pub order_by_expression: Option<ModelOrderByExpression>,
     pub limit_field: Option<LimitFieldGraphqlConfig>,
     pub offset_field: Option<OffsetFieldGraphqlConfig>,
     pub filter_input_type_name: Option<ast::TypeName>,
+    pub cache_hint: Option<ModelCacheHint>,
+}
+
+/// A hint to HTTP caches about the responses of GraphQL queries that select from a model
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ModelCacheHint {
+    pub max_age: u32,
+    pub scope: CacheScope,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -137,6 +198,28 @@ pub enum ModelGraphqlIssue {
     #[error("the model {model_name} has defined a selectAggregate graphql API, but it will not appear in the GraphQL API unless query.aggregate.filterInputFieldName is also configured in GraphqlConfig")]
     MissingAggregateFilterInputFieldNameInGraphqlConfig { model_name: Qualified<ModelName> },
 
+    #[error("the model {model_name} has defined a groups graphql API, but it will not appear in the GraphQL API unless the model also has an aggregate expression")]
+    MissingAggregateExpressionForGroups { model_name: Qualified<ModelName> },
+
+    #[error("the model {model_name} has defined a groups graphql API, but it will not appear in the GraphQL API unless query.aggregate.filterInputFieldName is also configured in GraphqlConfig")]
+    MissingGroupsFilterInputFieldNameInGraphqlConfig { model_name: Qualified<ModelName> },
+
+    #[error("the model {model_name} has defined a filter for its groups, but it will not appear in the GraphQL API as its data connector does not support filtering groups")]
+    GroupsFilterNotSupported { model_name: Qualified<ModelName> },
+
+    #[error("the model {model_name} has defined an order_by for its groups, but it will not appear in the GraphQL API as its data connector does not support ordering groups")]
+    GroupsOrderByNotSupported { model_name: Qualified<ModelName> },
+
+    #[error("the model {model_name} has defined an order_by for its groups, but it will not appear in the GraphQL API as the GraphqlConfig does not define the order_by input")]
+    MissingOrderByInputConfigForGroups { model_name: Qualified<ModelName> },
+
+    #[error("the relationship {relationship_name} on type {type_name} has defined a groups field, but it will not appear in the GraphQL API unless its target model {model_name} also defines a groups graphql API")]
+    MissingGroupsGraphqlApiForRelationshipTarget {
+        type_name: Qualified<CustomTypeName>,
+        relationship_name: RelationshipName,
+        model_name: Qualified<ModelName>,
+    },
+
     #[error("the model {model_name} has a duplicate root field in the GraphQL schema: {error:}")]
     DuplicateRootField {
         model_name: Qualified<ModelName>,
@@ -153,6 +236,12 @@ impl ShouldBeAnError for ModelGraphqlIssue {
     fn should_be_an_error(&self, flags: &open_dds::flags::OpenDdFlags) -> bool {
         match self {
             ModelGraphqlIssue::MissingAggregateFilterInputFieldNameInGraphqlConfig { .. }
+            | ModelGraphqlIssue::MissingAggregateExpressionForGroups { .. }
+            | ModelGraphqlIssue::MissingGroupsFilterInputFieldNameInGraphqlConfig { .. }
+            | ModelGraphqlIssue::GroupsFilterNotSupported { .. }
+            | ModelGraphqlIssue::GroupsOrderByNotSupported { .. }
+            | ModelGraphqlIssue::MissingOrderByInputConfigForGroups { .. }
+            | ModelGraphqlIssue::MissingGroupsGraphqlApiForRelationshipTarget { .. }
             | ModelGraphqlIssue::UnnecessaryModelArgumentsGraphQlInputConfiguration { .. }
             | ModelGraphqlIssue::UnnecessaryFilterInputTypeNameGraphqlConfiguration { .. } => false,
             ModelGraphqlIssue::DuplicateRootField { .. } => {
@@ -14,25 +14,31 @@ use open_dds::relationships::{
 };
 use open_dds::types::FieldName;
 use open_dds::{
-    commands::CommandName, data_connector::DataConnectorName, models::ModelName,
+    commands::CommandName,
+    data_connector::{DataConnectorColumnName, DataConnectorName},
+    models::ModelName,
     types::CustomTypeName,
 };
 
-use crate::helpers::types::mk_name;
+use crate::helpers::typecheck;
+use crate::helpers::types::{mk_name, NdcColumnForComparison};
 use crate::stages::{
     aggregates, commands, data_connector_scalar_types, data_connectors, graphql_config, models,
     object_types, relationships, type_permissions,
 };
 use crate::types::error::{Error, RelationshipError};
-use crate::types::subgraph::{Qualified, QualifiedBaseType, QualifiedTypeReference};
+use crate::types::permission::ValueExpression;
+use crate::types::subgraph::{
+    Qualified, QualifiedBaseType, QualifiedTypeName, QualifiedTypeReference,
+};
 
 pub use types::{
     AggregateRelationship, CommandRelationshipTarget, FieldNestedness, ModelRelationshipTarget,
     ObjectRelationshipsIssue, ObjectRelationshipsOutput, ObjectTypeWithRelationships,
-    RelationshipCapabilities, RelationshipCommandMapping, RelationshipExecutionCategory,
-    RelationshipField, RelationshipFieldAccess, RelationshipModelMapping,
-    RelationshipModelMappingFieldTarget, RelationshipModelMappingTarget, RelationshipTarget,
-    RelationshipTargetName,
+    RelationshipCapabilities, RelationshipCommandMapping, RelationshipCommandValueMapping,
+    RelationshipExecutionCategory, RelationshipField, RelationshipFieldAccess,
+    RelationshipModelMapping, RelationshipModelMappingFieldTarget, RelationshipModelMappingTarget,
+    RelationshipModelValueMapping, RelationshipTarget, RelationshipTargetName,
 };
 
 /// resolve relationships
@@ -52,6 +58,7 @@ pub fn resolve(
         aggregates::AggregateExpression,
     >,
     graphql_config: &graphql_config::GraphqlConfig,
+    flags: &open_dds::flags::OpenDdFlags,
 ) -> Result<ObjectRelationshipsOutput, Error> {
     let mut issues = Vec::new();
 
@@ -78,6 +85,7 @@ pub fn resolve(
                             &object_types_with_permissions,
                             graphql_config,
                             &object_type_with_permissions.object_type,
+                            flags,
                             &mut issues,
                         )?;
                         let field_name = resolved_relationship_field.field_name.clone();
@@ -183,47 +191,176 @@ pub fn field_selection_relationship_execution_category(
     }
 }
 
-fn resolve_relationship_source_mapping<'a, 'b>(
+fn resolve_relationship_source_mapping(
     relationship_name: &RelationshipName,
     source_type_name: &Qualified<CustomTypeName>,
-    source_type: &'b object_types::ObjectTypeRepresentation,
-    relationship_mapping: &'a open_dds::relationships::RelationshipMapping,
-) -> Result<(&'a FieldAccess, &'b QualifiedTypeReference), Error> {
-    match &relationship_mapping.source {
-        open_dds::relationships::RelationshipMappingSource::Value(_v) => Err(Error::NotSupported {
-            reason: "Relationship mappings from value expressions are not supported yet."
-                .to_string(),
-        }),
-        open_dds::relationships::RelationshipMappingSource::FieldPath(field_path) => {
-            match &field_path[..] {
-                [] => Err(Error::EmptyFieldPath {
-                    location: "source".to_string(),
-                    type_name: source_type_name.clone(),
-                    relationship_name: relationship_name.clone(),
-                }),
-                [field_access] => {
-                    let Some(field_definition) =
-                        source_type.fields.get(&field_access.field_name.value)
-                    else {
-                        return Err(Error::ObjectRelationshipError {
-                            relationship_error:
-                                RelationshipError::UnknownSourceFieldInRelationshipMapping {
-                                    relationship_name: relationship_name.clone(),
-                                    source_type: source_type_name.clone(),
-                                    field_name: field_access.field_name.value.clone(),
-                                },
-                        });
-                    };
-
-                    Ok((field_access, &field_definition.field_type))
-                }
-                _ => Err(Error::NotSupported {
-                    reason: "Relationships with nested field paths are not supported yet."
-                        .to_string(),
-                }),
+    source_type: &object_types::ObjectTypeRepresentation,
+    source_field_path: &[FieldAccess],
+    object_types: &type_permissions::ObjectTypesWithPermissions,
+) -> Result<(RelationshipFieldAccess, QualifiedTypeReference), Error> {
+    let Some((field_access, nested_field_path)) = source_field_path.split_first() else {
+        return Err(Error::EmptyFieldPath {
+            location: "source".to_string(),
+            type_name: source_type_name.clone(),
+            relationship_name: relationship_name.clone(),
+        });
+    };
+
+    let Some(field_definition) = source_type.fields.get(&field_access.field_name.value) else {
+        return Err(Error::ObjectRelationshipError {
+            relationship_error: RelationshipError::UnknownSourceFieldInRelationshipMapping {
+                relationship_name: relationship_name.clone(),
+                source_type: source_type_name.clone(),
+                field_name: field_access.field_name.value.clone(),
+            },
+        });
+    };
+
+    let nested_field_path = resolve_nested_field_path(
+        relationship_name,
+        source_type_name,
+        source_type_name,
+        &field_access.field_name.value,
+        &field_definition.field_type,
+        nested_field_path,
+        object_types,
+        |field_name| Error::ObjectRelationshipError {
+            relationship_error: RelationshipError::UnknownSourceFieldInRelationshipMapping {
+                relationship_name: relationship_name.clone(),
+                source_type: source_type_name.clone(),
+                field_name: field_name.clone(),
+            },
+        },
+    )?;
+
+    Ok((
+        RelationshipFieldAccess {
+            field_name: field_access.field_name.value.clone(),
+            nested_field_path: nested_field_path
+                .fields
+                .iter()
+                .map(|(_object_type_name, field_name)| field_name.clone())
+                .collect(),
+        },
+        nested_field_path.field_type,
+    ))
+}
+
+/// The fields a mapping descends into below its first field, through nested objects
+struct NestedFieldPath<'a> {
+    /// each field along with the object type that contains it
+    fields: Vec<(&'a Qualified<CustomTypeName>, FieldName)>,
+    /// the type of the last field of the path, which is nullable if any field along the way is
+    field_type: QualifiedTypeReference,
+}
+
+fn resolve_nested_field_path<'a>(
+    relationship_name: &RelationshipName,
+    source_type_name: &Qualified<CustomTypeName>,
+    object_type_name: &Qualified<CustomTypeName>,
+    field_name: &FieldName,
+    field_type: &QualifiedTypeReference,
+    nested_field_path: &[FieldAccess],
+    object_types: &'a type_permissions::ObjectTypesWithPermissions,
+    unknown_field_error: impl Fn(&FieldName) -> Error,
+) -> Result<NestedFieldPath<'a>, Error> {
+    let mut fields = Vec::new();
+    let mut current_object_type_name = object_type_name;
+    let mut current_field_name = field_name.clone();
+    let mut current_field_type = field_type.clone();
+    let mut nullable = field_type.nullable;
+
+    for nested_field in nested_field_path {
+        // Only object fields can be descended into; arrays of objects have no single value
+        // that a relationship could be mapped from or to
+        let object_type_name_and_type = match &current_field_type.underlying_type {
+            QualifiedBaseType::Named(QualifiedTypeName::Custom(object_type_name)) => {
+                object_types.0.get_key_value(object_type_name)
             }
-        }
+            QualifiedBaseType::Named(QualifiedTypeName::Inbuilt(_))
+            | QualifiedBaseType::List(_) => None,
+        };
+        let Some((object_type_name, object_type)) = object_type_name_and_type else {
+            return Err(Error::ObjectRelationshipError {
+                relationship_error: RelationshipError::NestedFieldPathThroughNonObjectField {
+                    source_type: source_type_name.clone(),
+                    relationship_name: relationship_name.clone(),
+                    type_name: current_object_type_name.clone(),
+                    field_name: current_field_name,
+                },
+            });
+        };
+
+        let field_definition = object_type
+            .object_type
+            .fields
+            .get(&nested_field.field_name.value)
+            .ok_or_else(|| unknown_field_error(&nested_field.field_name.value))?;
+
+        current_object_type_name = object_type_name;
+        current_field_name = nested_field.field_name.value.clone();
+        current_field_type = field_definition.field_type.clone();
+        nullable = nullable || current_field_type.nullable;
+        fields.push((object_type_name, current_field_name.clone()));
     }
+
+    Ok(NestedFieldPath {
+        fields,
+        field_type: QualifiedTypeReference {
+            nullable,
+            ..current_field_type
+        },
+    })
+}
+
+/// Resolves a value expression that a relationship mapping binds to a target
+/// field or argument, typechecking it against the type of that target
+fn resolve_relationship_mapping_value(
+    value: &open_dds::permissions::ValueExpression,
+    target_type: &QualifiedTypeReference,
+    relationship: &RelationshipV1,
+    source_type_name: &Qualified<CustomTypeName>,
+    object_types: &type_permissions::ObjectTypesWithPermissions,
+    flags: &open_dds::flags::OpenDdFlags,
+    issues: &mut Vec<ObjectRelationshipsIssue>,
+) -> Result<ValueExpression, Error> {
+    let typecheck_issues = typecheck::typecheck_value_expression(
+        &object_types
+            .iter()
+            .map(|(type_name, object_type)| (type_name, &object_type.object_type))
+            .collect(),
+        target_type,
+        value,
+    )
+    .map_err(|type_error| Error::ObjectRelationshipError {
+        relationship_error: RelationshipError::ValueMappingTypeError {
+            type_name: source_type_name.clone(),
+            relationship_name: relationship.name.clone(),
+            type_error,
+        },
+    })?;
+
+    issues.extend(typecheck_issues.into_iter().map(|typecheck_issue| {
+        ObjectRelationshipsIssue::ValueMappingTypecheckIssue {
+            type_name: source_type_name.clone(),
+            relationship_name: relationship.name.clone(),
+            typecheck_issue,
+        }
+    }));
+
+    Ok(match value {
+        open_dds::permissions::ValueExpression::Literal(literal) => {
+            ValueExpression::Literal(literal.clone())
+        }
+        open_dds::permissions::ValueExpression::SessionVariable(session_variable) => {
+            ValueExpression::SessionVariable(hasura_authn_core::SessionVariableReference {
+                name: session_variable.clone(),
+                passed_as_json: flags.contains(open_dds::flags::Flag::JsonSessionVariables),
+                disallow_unknown_fields: flags
+                    .contains(open_dds::flags::Flag::DisallowUnknownValuesInArguments),
+            })
+        }
+    })
 }
 
 fn resolve_relationship_mappings_model(
@@ -231,27 +368,56 @@ fn resolve_relationship_mappings_model(
     source_type_name: &Qualified<CustomTypeName>,
     source_type: &object_types::ObjectTypeRepresentation,
     target_model: &models::Model,
-) -> Result<Vec<RelationshipModelMapping>, Error> {
+    object_types: &type_permissions::ObjectTypesWithPermissions,
+    flags: &open_dds::flags::OpenDdFlags,
+    issues: &mut Vec<ObjectRelationshipsIssue>,
+) -> Result<
+    (
+        Vec<RelationshipModelMapping>,
+        Vec<RelationshipModelValueMapping>,
+    ),
+    Error,
+> {
     let mut resolved_relationship_mappings = Vec::new();
+    let mut resolved_relationship_value_mappings = Vec::new();
     let mut source_fields_already_mapped = BTreeSet::new();
     let mut target_arguments_already_mapped = BTreeSet::new();
 
     for relationship_mapping in &relationship.mapping {
+        let source_field_path = match &relationship_mapping.source {
+            open_dds::relationships::RelationshipMappingSource::FieldPath(field_path) => field_path,
+            open_dds::relationships::RelationshipMappingSource::Value(value) => {
+                resolved_relationship_value_mappings.push(
+                    resolve_relationship_value_mapping_model(
+                        value,
+                        &relationship_mapping.target,
+                        relationship,
+                        source_type_name,
+                        target_model,
+                        object_types,
+                        flags,
+                        &mut target_arguments_already_mapped,
+                        issues,
+                    )?,
+                );
+                continue;
+            }
+        };
+
         let (resolved_relationship_source_field, source_field_type) =
             resolve_relationship_source_mapping(
                 &relationship.name,
                 source_type_name,
                 source_type,
-                relationship_mapping,
+                source_field_path,
+                object_types,
             )?;
 
-        if !source_fields_already_mapped
-            .insert(&resolved_relationship_source_field.field_name.value)
-        {
+        if source_fields_already_mapped.contains(&resolved_relationship_source_field) {
             return Err(Error::ObjectRelationshipError {
                 relationship_error: RelationshipError::MappingExistsInRelationship {
                     type_name: source_type_name.clone(),
-                    field_name: resolved_relationship_source_field.field_name.value.clone(),
+                    field_name: resolved_relationship_source_field.field_name.clone(),
                     relationship_name: relationship.name.clone(),
                 },
             });
@@ -259,12 +425,15 @@ fn resolve_relationship_mappings_model(
 
         let resolved_relationship_mapping_target = match &relationship_mapping.target {
             open_dds::relationships::RelationshipMappingTarget::ModelField(field_path) => {
-                resolve_relationship_mappings_model_field_target(
-                    field_path,
-                    target_model,
-                    relationship,
-                    source_type_name,
-                )?
+                let (field_target, _target_field_type) =
+                    resolve_relationship_mappings_model_field_target(
+                        field_path,
+                        target_model,
+                        relationship,
+                        source_type_name,
+                        object_types,
+                    )?;
+                RelationshipModelMappingTarget::ModelField(field_target)
             }
             open_dds::relationships::RelationshipMappingTarget::Argument(
                 argument_mapping_target,
@@ -272,24 +441,132 @@ fn resolve_relationship_mappings_model(
                 argument_mapping_target,
                 target_model,
                 relationship,
-                &resolved_relationship_source_field.field_name,
-                source_field_type,
+                resolved_relationship_source_field
+                    .nested_field_path
+                    .last()
+                    .unwrap_or(&resolved_relationship_source_field.field_name),
+                &source_field_type,
                 source_type_name,
                 &mut target_arguments_already_mapped,
             )?,
         };
 
         let resolved_relationship_mapping = RelationshipModelMapping {
-            source_field: RelationshipFieldAccess {
-                field_name: resolved_relationship_source_field.field_name.value.clone(),
-            },
+            source_field: resolved_relationship_source_field.clone(),
             target: resolved_relationship_mapping_target,
         };
+        source_fields_already_mapped.insert(resolved_relationship_source_field);
 
         resolved_relationship_mappings.push(resolved_relationship_mapping);
     }
 
-    Ok(resolved_relationship_mappings)
+    // a target field bound to a value cannot also be joined on, or bound to another value
+    let mut target_fields_already_mapped = resolved_relationship_mappings
+        .iter()
+        .filter_map(|mapping| match &mapping.target {
+            RelationshipModelMappingTarget::ModelField(field_target) => {
+                Some(&field_target.target_field)
+            }
+            RelationshipModelMappingTarget::Argument(_) => None,
+        })
+        .collect::<BTreeSet<_>>();
+    for value_mapping in &resolved_relationship_value_mappings {
+        if let RelationshipModelMappingTarget::ModelField(field_target) = &value_mapping.target {
+            if !target_fields_already_mapped.insert(&field_target.target_field) {
+                return Err(Error::ObjectRelationshipError {
+                    relationship_error:
+                        RelationshipError::ModelFieldValueMappingExistsInRelationship {
+                            field_path: std::iter::once(&field_target.target_field.field_name)
+                                .chain(&field_target.target_field.nested_field_path)
+                                .map(FieldName::as_str)
+                                .collect::<Vec<_>>()
+                                .join("."),
+                            model_name: target_model.name.clone(),
+                            relationship_name: relationship.name.clone(),
+                            type_name: source_type_name.clone(),
+                        },
+                });
+            }
+        }
+    }
+
+    Ok((
+        resolved_relationship_mappings,
+        resolved_relationship_value_mappings,
+    ))
+}
+
+fn resolve_relationship_value_mapping_model<'a>(
+    value: &open_dds::permissions::ValueExpression,
+    relationship_mapping_target: &'a open_dds::relationships::RelationshipMappingTarget,
+    relationship: &RelationshipV1,
+    source_type_name: &Qualified<CustomTypeName>,
+    target_model: &models::Model,
+    object_types: &type_permissions::ObjectTypesWithPermissions,
+    flags: &open_dds::flags::OpenDdFlags,
+    target_arguments_already_mapped: &mut BTreeSet<&'a ArgumentName>,
+    issues: &mut Vec<ObjectRelationshipsIssue>,
+) -> Result<RelationshipModelValueMapping, Error> {
+    let (target, target_type) = match relationship_mapping_target {
+        open_dds::relationships::RelationshipMappingTarget::ModelField(field_path) => {
+            let (field_target, target_type) = resolve_relationship_mappings_model_field_target(
+                field_path,
+                target_model,
+                relationship,
+                source_type_name,
+                object_types,
+            )?;
+            (
+                RelationshipModelMappingTarget::ModelField(field_target),
+                target_type,
+            )
+        }
+        open_dds::relationships::RelationshipMappingTarget::Argument(argument_mapping_target) => {
+            let argument_name = &argument_mapping_target.argument_name;
+            let Some(target_argument) = target_model.arguments.get(argument_name) else {
+                return Err(Error::ObjectRelationshipError {
+                    relationship_error:
+                        RelationshipError::UnknownTargetModelArgumentInRelationshipMapping {
+                            relationship_name: relationship.name.clone(),
+                            source_type: source_type_name.clone(),
+                            model_name: target_model.name.clone(),
+                            argument_name: argument_name.clone(),
+                        },
+                });
+            };
+            if !target_arguments_already_mapped.insert(argument_name) {
+                return Err(Error::ObjectRelationshipError {
+                    relationship_error:
+                        RelationshipError::ModelArgumentMappingExistsInRelationship {
+                            argument_name: argument_name.clone(),
+                            model_name: target_model.name.clone(),
+                            relationship_name: relationship.name.clone(),
+                            type_name: source_type_name.clone(),
+                        },
+                });
+            }
+            (
+                RelationshipModelMappingTarget::Argument(argument_name.clone()),
+                target_argument.argument_type.clone(),
+            )
+        }
+    };
+
+    let source_value = resolve_relationship_mapping_value(
+        value,
+        &target_type,
+        relationship,
+        source_type_name,
+        object_types,
+        flags,
+        issues,
+    )?;
+
+    Ok(RelationshipModelValueMapping {
+        source_value,
+        target,
+        target_type,
+    })
 }
 
 fn resolve_relationship_mappings_model_field_target(
@@ -297,75 +574,177 @@ fn resolve_relationship_mappings_model_field_target(
     target_model: &models::Model,
     relationship: &RelationshipV1,
     source_type_name: &Qualified<CustomTypeName>,
-) -> Result<RelationshipModelMappingTarget, Error> {
-    // Check that the target field path is only one field deep,
-    // since we don't support nested field targets yet
-    let resolved_relationship_target_mapping = match resolved_relationship_target_model_field_path {
-        [t] => t,
-        [] => {
-            return Err(Error::EmptyFieldPath {
-                location: "target".to_string(),
-                type_name: source_type_name.clone(),
-                relationship_name: relationship.name.clone(),
-            })
-        }
-        _ => {
-            return Err(Error::NotSupported {
-                reason: "Relationships with nested field paths are not supported yet.".to_string(),
-            })
-        }
+    object_types: &type_permissions::ObjectTypesWithPermissions,
+) -> Result<(RelationshipModelMappingFieldTarget, QualifiedTypeReference), Error> {
+    let Some((resolved_relationship_target_mapping, nested_field_path)) =
+        resolved_relationship_target_model_field_path.split_first()
+    else {
+        return Err(Error::EmptyFieldPath {
+            location: "target".to_string(),
+            type_name: source_type_name.clone(),
+            relationship_name: relationship.name.clone(),
+        });
+    };
+
+    let unknown_target_field_error = |field_name: &FieldName| Error::ObjectRelationshipError {
+        relationship_error: RelationshipError::UnknownTargetFieldInRelationshipMapping {
+            relationship_name: relationship.name.clone(),
+            source_type: source_type_name.clone(),
+            model_name: target_model.name.clone(),
+            field_name: field_name.clone(),
+        },
     };
 
     // Make sure the target model contains the target field
-    if !target_model
+    let target_field_definition = target_model
         .type_fields
-        .contains_key(&resolved_relationship_target_mapping.field_name.value)
-    {
-        return Err(Error::ObjectRelationshipError {
-            relationship_error: RelationshipError::UnknownTargetFieldInRelationshipMapping {
-                relationship_name: relationship.name.clone(),
-                source_type: source_type_name.clone(),
-                model_name: target_model.name.clone(),
-                field_name: resolved_relationship_target_mapping
-                    .field_name
-                    .value
-                    .clone(),
-            },
-        });
-    }
+        .get(&resolved_relationship_target_mapping.field_name.value)
+        .ok_or_else(|| {
+            unknown_target_field_error(&resolved_relationship_target_mapping.field_name.value)
+        })?;
+
+    // ... as well as the fields of any nested objects the target descends into
+    let nested_field_path = resolve_nested_field_path(
+        &relationship.name,
+        source_type_name,
+        &target_model.data_type,
+        &resolved_relationship_target_mapping.field_name.value,
+        &target_field_definition.field_type,
+        nested_field_path,
+        object_types,
+        unknown_target_field_error,
+    )?;
 
     // Get the NDC column name for the target field
     let target_ndc_column = target_model
         .source
         .as_ref()
         .map(|target_model_source| {
-            models::get_ndc_column_for_comparison(
-                &target_model.name,
-                &target_model.data_type,
+            resolve_relationship_target_ndc_column(
+                relationship,
+                source_type_name,
+                target_model,
                 target_model_source,
-                &resolved_relationship_target_mapping.field_name,
-                || {
-                    format!(
-                        "the mapping for relationship {} on type {}",
-                        relationship.name, source_type_name
-                    )
-                },
+                &resolved_relationship_target_mapping.field_name.value,
+                &nested_field_path,
             )
         })
         .transpose()?;
 
-    let resolved_relationship_mapping_target =
-        RelationshipModelMappingTarget::ModelField(RelationshipModelMappingFieldTarget {
+    Ok((
+        RelationshipModelMappingFieldTarget {
             target_field: RelationshipFieldAccess {
                 field_name: resolved_relationship_target_mapping
                     .field_name
                     .value
                     .clone(),
+                nested_field_path: nested_field_path
+                    .fields
+                    .iter()
+                    .map(|(_object_type_name, field_name)| field_name.clone())
+                    .collect(),
             },
             target_ndc_column,
-        });
+        },
+        nested_field_path.field_type,
+    ))
+}
+
+/// Gets the NDC column that a mapping to a target model field compares against. If the target
+/// field is nested inside object columns, the column is the outermost of those, and the field
+/// path leads from it to the target field.
+fn resolve_relationship_target_ndc_column(
+    relationship: &RelationshipV1,
+    source_type_name: &Qualified<CustomTypeName>,
+    target_model: &models::Model,
+    target_model_source: &models::ModelSource,
+    field_name: &FieldName,
+    nested_field_path: &NestedFieldPath,
+) -> Result<NdcColumnForComparison, Error> {
+    let comparison_location = || {
+        format!(
+            "the mapping for relationship {} on type {}",
+            relationship.name, source_type_name
+        )
+    };
+
+    let Some(((leaf_object_type_name, leaf_field_name), nested_fields)) =
+        nested_field_path.fields.split_last()
+    else {
+        return Ok(models::get_ndc_column_for_comparison(
+            &target_model.name,
+            &target_model.data_type,
+            target_model_source,
+            field_name,
+            comparison_location,
+        )?);
+    };
+
+    // The leaf field determines the equality operator used for the comparison
+    let leaf_column = models::get_ndc_column_for_comparison(
+        &target_model.name,
+        leaf_object_type_name,
+        target_model_source,
+        leaf_field_name,
+        comparison_location,
+    )?;
 
-    Ok(resolved_relationship_mapping_target)
+    // Every other field along the path only needs to be found in the NDC schema
+    let mut columns = std::iter::once((&target_model.data_type, field_name))
+        .chain(
+            nested_fields
+                .iter()
+                .map(|(object_type_name, field_name)| (*object_type_name, field_name)),
+        )
+        .map(|(object_type_name, field_name)| {
+            get_ndc_column_of_field(
+                target_model,
+                target_model_source,
+                object_type_name,
+                field_name,
+                comparison_location,
+            )
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    columns.push(leaf_column.column);
+    let column = columns.remove(0);
+
+    Ok(NdcColumnForComparison {
+        column,
+        field_path: columns,
+        equal_operator: leaf_column.equal_operator,
+    })
+}
+
+fn get_ndc_column_of_field(
+    target_model: &models::Model,
+    target_model_source: &models::ModelSource,
+    object_type_name: &Qualified<CustomTypeName>,
+    field_name: &FieldName,
+    comparison_location: impl Fn() -> String,
+) -> Result<DataConnectorColumnName, Error> {
+    let Some(object_types::TypeMapping::Object { field_mappings, .. }) =
+        target_model_source.type_mappings.get(object_type_name)
+    else {
+        return Err(models::ModelsError::TypeMappingRequired {
+            model_name: target_model.name.clone(),
+            type_name: object_type_name.clone(),
+            data_connector: target_model_source.data_connector.name.clone(),
+        }
+        .into());
+    };
+
+    field_mappings
+        .get(field_name)
+        .map(|field_mapping| field_mapping.column.clone())
+        .ok_or_else(|| {
+            models::ModelsError::NoFieldMappingForComparedField {
+                comparison_location: comparison_location(),
+                field_name: field_name.clone(),
+                model_name: target_model.name.clone(),
+            }
+            .into()
+        })
 }
 
 fn resolve_relationship_mappings_model_argument_target<'a>(
@@ -459,19 +838,22 @@ fn resolve_relationship_mappings_command(
     source_type_name: &Qualified<CustomTypeName>,
     source_type: &object_types::ObjectTypeRepresentation,
     target_command: &commands::Command,
-) -> Result<Vec<RelationshipCommandMapping>, Error> {
+    object_types: &type_permissions::ObjectTypesWithPermissions,
+    flags: &open_dds::flags::OpenDdFlags,
+    issues: &mut Vec<ObjectRelationshipsIssue>,
+) -> Result<
+    (
+        Vec<RelationshipCommandMapping>,
+        Vec<RelationshipCommandValueMapping>,
+    ),
+    Error,
+> {
     let mut resolved_relationship_mappings = Vec::new();
+    let mut resolved_relationship_value_mappings = Vec::new();
     let mut source_fields_already_mapped = BTreeSet::new();
     let mut target_arguments_already_mapped = BTreeSet::new();
 
     for relationship_mapping in &relationship.mapping {
-        let (resolved_relationship_source_mapping, _source_field_type) =
-            resolve_relationship_source_mapping(
-                &relationship.name,
-                source_type_name,
-                source_type,
-                relationship_mapping,
-            )?;
         let target_argument_name = match &relationship_mapping.target {
             open_dds::relationships::RelationshipMappingTarget::Argument(
                 argument_mapping_target,
@@ -485,7 +867,7 @@ fn resolve_relationship_mappings_command(
         };
 
         // Check if the target argument exists in the target command.
-        if !target_command.arguments.contains_key(target_argument_name) {
+        let Some(target_argument) = target_command.arguments.get(target_argument_name) else {
             return Err(Error::ObjectRelationshipError {
                 relationship_error:
                     RelationshipError::UnknownTargetCommandArgumentInRelationshipMapping {
@@ -495,7 +877,7 @@ fn resolve_relationship_mappings_command(
                         argument_name: target_argument_name.clone(),
                     },
             });
-        }
+        };
 
         // Check if the target argument is already mapped to a field in the source type.
         if !target_arguments_already_mapped.insert(target_argument_name) {
@@ -509,37 +891,59 @@ fn resolve_relationship_mappings_command(
             });
         };
 
-        // Check if the source field is already mapped to a target argument
-        let resolved_relationship_mapping = {
-            if source_fields_already_mapped
-                .insert(&resolved_relationship_source_mapping.field_name.value)
-            {
-                Ok(RelationshipCommandMapping {
-                    source_field: RelationshipFieldAccess {
-                        field_name: resolved_relationship_source_mapping
-                            .field_name
-                            .value
-                            .clone(),
-                    },
+        let source_field_path = match &relationship_mapping.source {
+            open_dds::relationships::RelationshipMappingSource::FieldPath(field_path) => field_path,
+            open_dds::relationships::RelationshipMappingSource::Value(value) => {
+                let source_value = resolve_relationship_mapping_value(
+                    value,
+                    &target_argument.argument_type,
+                    relationship,
+                    source_type_name,
+                    object_types,
+                    flags,
+                    issues,
+                )?;
+                resolved_relationship_value_mappings.push(RelationshipCommandValueMapping {
+                    source_value,
                     argument_name: target_argument_name.clone(),
-                })
-            } else {
-                Err(Error::ObjectRelationshipError {
-                    relationship_error: RelationshipError::MappingExistsInRelationship {
-                        type_name: source_type_name.clone(),
-                        field_name: resolved_relationship_source_mapping
-                            .field_name
-                            .value
-                            .clone(),
-                        relationship_name: relationship.name.clone(),
-                    },
-                })
+                    argument_type: target_argument.argument_type.clone(),
+                });
+                continue;
             }
-        }?;
+        };
+
+        let (resolved_relationship_source_field, _source_field_type) =
+            resolve_relationship_source_mapping(
+                &relationship.name,
+                source_type_name,
+                source_type,
+                source_field_path,
+                object_types,
+            )?;
+
+        // Check if the source field is already mapped to a target argument
+        if source_fields_already_mapped.contains(&resolved_relationship_source_field) {
+            return Err(Error::ObjectRelationshipError {
+                relationship_error: RelationshipError::MappingExistsInRelationship {
+                    type_name: source_type_name.clone(),
+                    field_name: resolved_relationship_source_field.field_name.clone(),
+                    relationship_name: relationship.name.clone(),
+                },
+            });
+        }
+
+        let resolved_relationship_mapping = RelationshipCommandMapping {
+            source_field: resolved_relationship_source_field.clone(),
+            argument_name: target_argument_name.clone(),
+        };
+        source_fields_already_mapped.insert(resolved_relationship_source_field);
         resolved_relationship_mappings.push(resolved_relationship_mapping);
     }
 
-    Ok(resolved_relationship_mappings)
+    Ok((
+        resolved_relationship_mappings,
+        resolved_relationship_value_mappings,
+    ))
 }
 
 fn get_relationship_capabilities(
@@ -622,6 +1026,42 @@ fn get_relationship_capabilities(
     }))
 }
 
+/// Relationships that map fields nested inside source or target objects can only be joined
+/// locally if the data connector supports nested relationships. NDC relationships are followed
+/// from a single object, so all the source fields must also be in the same (possibly nested)
+/// object; otherwise the relationship is executed as a remote join.
+fn restrict_capabilities_for_nested_mappings<'a>(
+    capabilities: RelationshipCapabilities,
+    source_fields: impl Iterator<Item = &'a RelationshipFieldAccess>,
+    has_nested_target_mappings: bool,
+) -> RelationshipCapabilities {
+    let source_object_paths = source_fields
+        .map(
+            |source_field| match source_field.nested_field_path.split_last() {
+                None => vec![],
+                Some((_, nested_object_path)) => std::iter::once(&source_field.field_name)
+                    .chain(nested_object_path)
+                    .collect(),
+            },
+        )
+        .collect::<BTreeSet<Vec<&FieldName>>>();
+    let has_nested_source_mappings = source_object_paths.iter().any(|path| !path.is_empty());
+    let supports_relationships =
+        capabilities
+            .supports_relationships
+            .filter(|supports_relationships| {
+                source_object_paths.len() <= 1
+                    && (!(has_nested_source_mappings || has_nested_target_mappings)
+                        || supports_relationships
+                            .supports_nested_relationships
+                            .is_some())
+            });
+    RelationshipCapabilities {
+        supports_relationships,
+        ..capabilities
+    }
+}
+
 fn resolve_aggregate_relationship(
     model_relationship_target: &open_dds::relationships::ModelRelationshipTarget,
     resolved_target_model: &models::Model,
@@ -710,6 +1150,31 @@ fn resolve_aggregate_relationship(
         .transpose()
 }
 
+fn resolve_groups_field_name(
+    model_relationship_target: &open_dds::relationships::ModelRelationshipTarget,
+    relationship: &RelationshipV1,
+    source_type_name: &Qualified<CustomTypeName>,
+) -> Result<Option<ast::Name>, Error> {
+    relationship
+        .graphql
+        .as_ref()
+        .and_then(|g| g.groups_field_name.as_ref())
+        .map(|field_name| {
+            // Ensure the relationship is an array relationship
+            if model_relationship_target.relationship_type != RelationshipType::Array {
+                return Err(
+                    RelationshipError::GroupsAreOnlyAllowedOnArrayRelationships {
+                        type_name: source_type_name.clone(),
+                        relationship_name: relationship.name.clone(),
+                    }
+                    .into(),
+                );
+            }
+            Ok(mk_name(field_name.as_str())?)
+        })
+        .transpose()
+}
+
 fn resolve_model_relationship_fields(
     target_model: &open_dds::relationships::ModelRelationshipTarget,
     models: &IndexMap<Qualified<ModelName>, crate::Model>,
@@ -727,6 +1192,7 @@ fn resolve_model_relationship_fields(
     >,
     object_types: &type_permissions::ObjectTypesWithPermissions,
     graphql_config: &graphql_config::GraphqlConfig,
+    flags: &open_dds::flags::OpenDdFlags,
     issues: &mut Vec<ObjectRelationshipsIssue>,
 ) -> Result<RelationshipField, Error> {
     let qualified_target_model_name = Qualified::new(
@@ -759,13 +1225,30 @@ fn resolve_model_relationship_fields(
         issues,
     )?;
 
-    let mappings = resolve_relationship_mappings_model(
+    let (mappings, value_mappings) = resolve_relationship_mappings_model(
         relationship,
         source_type_name,
         source_type,
         resolved_target_model,
+        object_types,
+        flags,
+        issues,
     )?;
 
+    let target_capabilities = target_capabilities.map(|capabilities| {
+        restrict_capabilities_for_nested_mappings(
+            capabilities,
+            mappings.iter().map(|mapping| &mapping.source_field),
+            mappings.iter().any(|mapping| {
+                matches!(
+                    &mapping.target,
+                    RelationshipModelMappingTarget::ModelField(field_target)
+                        if field_target.target_field.is_nested()
+                )
+            }),
+        )
+    });
+
     let relationship_aggregate = resolve_aggregate_relationship(
         target_model,
         resolved_target_model,
@@ -777,6 +1260,9 @@ fn resolve_model_relationship_fields(
         data_connector_scalars,
     )?;
 
+    let groups_field_name =
+        resolve_groups_field_name(target_model, relationship, source_type_name)?;
+
     let relationship_field = RelationshipField {
         field_name: make_relationship_field_name(&relationship.name)?,
         relationship_name: relationship.name.clone(),
@@ -786,7 +1272,9 @@ fn resolve_model_relationship_fields(
             relationship_type: target_model.relationship_type.clone(),
             target_typename: resolved_target_model.data_type.clone(),
             mappings,
+            value_mappings,
             relationship_aggregate,
+            groups_field_name,
         })),
         target_capabilities,
         description: relationship.description.clone(),
@@ -810,6 +1298,8 @@ fn resolve_command_relationship_field(
     source_type_name: &Qualified<CustomTypeName>,
     relationship: &RelationshipV1,
     source_type: &object_types::ObjectTypeRepresentation,
+    object_types: &type_permissions::ObjectTypesWithPermissions,
+    flags: &open_dds::flags::OpenDdFlags,
     issues: &mut Vec<ObjectRelationshipsIssue>,
 ) -> Result<RelationshipField, Error> {
     let qualified_target_command_name = Qualified::new(
@@ -842,16 +1332,15 @@ fn resolve_command_relationship_field(
         .as_ref()
         .map(|source| &source.data_connector);
 
-    let target = RelationshipTarget::Command(CommandRelationshipTarget {
-        command_name: qualified_target_command_name,
-        target_type: resolved_target_command.output_type.clone(),
-        mappings: resolve_relationship_mappings_command(
-            relationship,
-            source_type_name,
-            source_type,
-            resolved_target_command,
-        )?,
-    });
+    let (mappings, value_mappings) = resolve_relationship_mappings_command(
+        relationship,
+        source_type_name,
+        source_type,
+        resolved_target_command,
+        object_types,
+        flags,
+        issues,
+    )?;
 
     let target_capabilities = get_relationship_capabilities(
         source_type_name,
@@ -864,6 +1353,21 @@ fn resolve_command_relationship_field(
         issues,
     )?;
 
+    let target_capabilities = target_capabilities.map(|capabilities| {
+        restrict_capabilities_for_nested_mappings(
+            capabilities,
+            mappings.iter().map(|mapping| &mapping.source_field),
+            false,
+        )
+    });
+
+    let target = RelationshipTarget::Command(CommandRelationshipTarget {
+        command_name: qualified_target_command_name,
+        target_type: resolved_target_command.output_type.clone(),
+        mappings,
+        value_mappings,
+    });
+
     let field_name = mk_name(relationship.name.as_str())?;
     Ok(RelationshipField {
         field_name,
@@ -917,6 +1421,7 @@ fn resolve_relationship_field(
     object_types: &type_permissions::ObjectTypesWithPermissions,
     graphql_config: &graphql_config::GraphqlConfig,
     source_type: &object_types::ObjectTypeRepresentation,
+    flags: &open_dds::flags::OpenDdFlags,
     issues: &mut Vec<ObjectRelationshipsIssue>,
 ) -> Result<RelationshipField, Error> {
     match &relationship.target {
@@ -932,6 +1437,7 @@ fn resolve_relationship_field(
                 aggregate_expressions,
                 object_types,
                 graphql_config,
+                flags,
                 issues,
             )
         }
@@ -943,6 +1449,8 @@ fn resolve_relationship_field(
                 source_type_name,
                 relationship,
                 source_type,
+                object_types,
+                flags,
                 issues,
             )?;
             Ok(command_relationship_field)
@@ -1,5 +1,7 @@
+use crate::helpers::typecheck::TypecheckIssue;
 use crate::stages::{data_connectors, object_types, type_permissions};
 use crate::types::error::ShouldBeAnError;
+use crate::types::permission::ValueExpression;
 use crate::types::subgraph::{Qualified, QualifiedTypeReference};
 use indexmap::IndexMap;
 use open_dds::aggregates::AggregateExpressionName;
@@ -49,7 +51,32 @@ pub struct ModelRelationshipTarget {
     pub relationship_type: RelationshipType,
     pub target_typename: Qualified<CustomTypeName>,
     pub mappings: Vec<RelationshipModelMapping>,
+    /// mappings that bind target fields or arguments to values rather than to source fields
+    #[serde(default = "serde_ext::ser_default")]
+    #[serde(skip_serializing_if = "serde_ext::is_ser_default")]
+    pub value_mappings: Vec<RelationshipModelValueMapping>,
     pub relationship_aggregate: Option<AggregateRelationship>, // only applicable to array relationships
+    pub groups_field_name: Option<ast::Name>, // only applicable to array relationships
+}
+
+impl ModelRelationshipTarget {
+    /// Whether any of the mappings of the relationship descend into nested object fields
+    pub fn has_nested_field_mappings(&self) -> bool {
+        self.mappings.iter().any(|mapping| {
+            mapping.source_field.is_nested()
+                || matches!(
+                    &mapping.target,
+                    RelationshipModelMappingTarget::ModelField(field_target)
+                        if field_target.target_field.is_nested()
+                )
+        }) || self.value_mappings.iter().any(|mapping| {
+            matches!(
+                &mapping.target,
+                RelationshipModelMappingTarget::ModelField(field_target)
+                    if field_target.target_field.is_nested()
+            )
+        })
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -65,6 +92,19 @@ pub struct CommandRelationshipTarget {
     pub command_name: Qualified<CommandName>,
     pub target_type: QualifiedTypeReference,
     pub mappings: Vec<RelationshipCommandMapping>,
+    /// mappings that bind target arguments to values rather than to source fields
+    #[serde(default = "serde_ext::ser_default")]
+    #[serde(skip_serializing_if = "serde_ext::is_ser_default")]
+    pub value_mappings: Vec<RelationshipCommandValueMapping>,
+}
+
+impl CommandRelationshipTarget {
+    /// Whether any of the mappings of the relationship descend into nested object fields
+    pub fn has_nested_field_mappings(&self) -> bool {
+        self.mappings
+            .iter()
+            .any(|mapping| mapping.source_field.is_nested())
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -80,8 +120,26 @@ pub struct RelationshipModelMapping {
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RelationshipModelValueMapping {
+    pub source_value: ValueExpression,
+    pub target: RelationshipModelMappingTarget,
+    /// The type of the target field or argument, used to typecast session variables
+    pub target_type: QualifiedTypeReference,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct RelationshipFieldAccess {
     pub field_name: FieldName,
+    /// the fields descended into through the nested objects inside `field_name`
+    #[serde(default = "serde_ext::ser_default")]
+    #[serde(skip_serializing_if = "serde_ext::is_ser_default")]
+    pub nested_field_path: Vec<FieldName>,
+}
+
+impl RelationshipFieldAccess {
+    pub fn is_nested(&self) -> bool {
+        !self.nested_field_path.is_empty()
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -103,6 +161,14 @@ pub struct RelationshipCommandMapping {
     pub argument_name: ArgumentName,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RelationshipCommandValueMapping {
+    pub source_value: ValueExpression,
+    pub argument_name: ArgumentName,
+    /// The type of the target argument, used to typecast session variables
+    pub argument_type: QualifiedTypeReference,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct RelationshipField {
     pub field_name: ast::Name,
@@ -170,6 +236,12 @@ pub enum ObjectRelationshipsIssue {
         command_name: Qualified<CommandName>,
         procedure_name: open_dds::commands::ProcedureName,
     },
+    #[error("Typecheck issue in a value mapped by relationship {relationship_name} on type {type_name}: {typecheck_issue}")]
+    ValueMappingTypecheckIssue {
+        type_name: Qualified<CustomTypeName>,
+        relationship_name: RelationshipName,
+        typecheck_issue: TypecheckIssue,
+    },
 }
 
 impl ShouldBeAnError for ObjectRelationshipsIssue {
@@ -181,6 +253,9 @@ impl ShouldBeAnError for ObjectRelationshipsIssue {
             ObjectRelationshipsIssue::ProcedureCommandRelationshipsNotSupported { .. } => {
                 flags.contains(open_dds::flags::Flag::DisallowProcedureCommandRelationships)
             }
+            ObjectRelationshipsIssue::ValueMappingTypecheckIssue {
+                typecheck_issue, ..
+            } => typecheck_issue.should_be_an_error(flags),
         }
     }
 }
@@ -0,0 +1,121 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use hasura_authn_core::Role;
+use open_dds::operation_collections::{CollectionOperation, OperationCollectionName};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::types::subgraph::Qualified;
+
+/// The operations of all the operation collections, keyed by the SHA-256 hash of their query.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct OperationCollections {
+    /// Whether any operation collection is defined. If so, only the operations of the collections
+    /// are allowed to run.
+    pub allowlist_enabled: bool,
+    pub operations: BTreeMap<String, PersistedOperation>,
+    /// The roles allowed to run any operation, including those outside of the collections.
+    pub unrestricted_roles: BTreeSet<Role>,
+}
+
+impl OperationCollections {
+    /// Looks up an operation by the hex-encoded SHA-256 hash of its query.
+    pub fn get_by_hash(&self, query_hash: &str) -> Option<&PersistedOperation> {
+        self.operations.get(&query_hash.to_ascii_lowercase())
+    }
+
+    /// Checks if the given query can be run by the given role.
+    pub fn is_allowed(&self, query: &str, role: &Role) -> bool {
+        !self.allowlist_enabled
+            || self.unrestricted_roles.contains(role)
+            || self
+                .operations
+                .get(&hash_query(query))
+                .is_some_and(|operation| operation.allowed_roles.contains(role))
+    }
+}
+
+/// An operation of an operation collection, along with the roles allowed to run it. An operation
+/// present in multiple collections can be run by the roles of any of them.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PersistedOperation {
+    pub query: String,
+    pub allowed_roles: BTreeSet<Role>,
+}
+
+/// The hex-encoded SHA-256 hash of a query, as used by persisted queries.
+pub fn hash_query(query: &str) -> String {
+    format!("{:x}", Sha256::digest(query.as_bytes()))
+}
+
+pub fn resolve(
+    metadata_accessor: &open_dds::accessor::MetadataAccessor,
+) -> Result<OperationCollections, OperationCollectionError> {
+    let mut collection_names = BTreeSet::new();
+    let mut operations = BTreeMap::<String, PersistedOperation>::new();
+    let mut unrestricted_roles = BTreeSet::new();
+    for open_dds::accessor::QualifiedObject {
+        path: _,
+        subgraph,
+        object: operation_collection,
+    } in &metadata_accessor.operation_collections
+    {
+        let collection_name = Qualified::new(subgraph.clone(), operation_collection.name.clone());
+        if !collection_names.insert(collection_name.clone()) {
+            return Err(
+                OperationCollectionError::DuplicateOperationCollectionDefinition {
+                    name: collection_name,
+                },
+            );
+        }
+        let allowed_roles = operation_collection
+            .permissions
+            .iter()
+            .map(|permission| permission.role.clone())
+            .collect::<BTreeSet<_>>();
+        unrestricted_roles.extend(
+            operation_collection
+                .permissions
+                .iter()
+                .filter(|permission| permission.allow_any_operation)
+                .map(|permission| permission.role.clone()),
+        );
+        for CollectionOperation { name, query } in &operation_collection.operations {
+            if let Err(error) = lang_graphql::parser::Parser::new(query).parse_executable_document()
+            {
+                return Err(OperationCollectionError::InvalidOperationQuery {
+                    collection_name,
+                    operation_name: name.clone(),
+                    error: error.to_string(),
+                });
+            }
+            operations
+                .entry(hash_query(query))
+                .or_insert_with(|| PersistedOperation {
+                    query: query.clone(),
+                    allowed_roles: BTreeSet::new(),
+                })
+                .allowed_roles
+                .extend(allowed_roles.iter().cloned());
+        }
+    }
+    Ok(OperationCollections {
+        allowlist_enabled: !collection_names.is_empty(),
+        operations,
+        unrestricted_roles,
+    })
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum OperationCollectionError {
+    #[error("the operation collection {name:} has been defined more than once")]
+    DuplicateOperationCollectionDefinition {
+        name: Qualified<OperationCollectionName>,
+    },
+    #[error("the query of operation {operation_name:} in operation collection {collection_name:} is not a valid GraphQL document: {error:}")]
+    InvalidOperationQuery {
+        collection_name: Qualified<OperationCollectionName>,
+        operation_name: String,
+        error: String,
+    },
+}
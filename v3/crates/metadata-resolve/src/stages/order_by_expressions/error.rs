@@ -64,8 +64,13 @@ pub enum OrderByExpressionError {
 #[derive(Debug, thiserror::Error)]
 #[allow(clippy::enum_variant_names)]
 pub enum OrderableRelationshipError {
-    #[error("The orderable relationship '{relationship_name}' defined for '{orderable_type}' is a remote relationship and remote relationships are not supported in ordering")]
-    RemoteRelationshipsNotSupported {
+    #[error("The orderable relationship '{relationship_name}' defined for '{orderable_type}' is a remote relationship to a command and remote command relationships are not supported in ordering")]
+    RemoteCommandRelationshipsNotSupported {
+        orderable_type: Qualified<CustomTypeName>,
+        relationship_name: RelationshipName,
+    },
+    #[error("The orderable relationship '{relationship_name}' defined for '{orderable_type}' is a remote relationship on a nested field and remote relationships are only supported in ordering on top-level fields")]
+    NestedRemoteRelationshipsNotSupported {
         orderable_type: Qualified<CustomTypeName>,
         relationship_name: RelationshipName,
     },
@@ -518,13 +518,18 @@ pub fn validate_orderable_relationship(
     source_connector: &data_connectors::DataConnectorLink,
     target_connector_name: &Qualified<DataConnectorName>,
 ) -> Result<(), OrderableRelationshipError> {
+    // Remote relationships are sorted by the engine rather than the source data connector, so
+    // they do not depend on its capabilities, but we only fetch sort keys for top-level fields
     if source_connector.name != *target_connector_name {
-        return Err(
-            OrderableRelationshipError::RemoteRelationshipsNotSupported {
-                orderable_type: orderable_type.clone(),
-                relationship_name: relationship_name.clone(),
-            },
-        );
+        if relationship_field_nestedness == OrderableFieldNestedness::ObjectNested {
+            return Err(
+                OrderableRelationshipError::NestedRemoteRelationshipsNotSupported {
+                    orderable_type: orderable_type.clone(),
+                    relationship_name: relationship_name.clone(),
+                },
+            );
+        }
+        return Ok(());
     }
 
     if let Some(relationship_capabilities) = &source_connector.capabilities.supports_relationships {
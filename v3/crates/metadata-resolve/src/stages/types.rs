@@ -13,8 +13,9 @@ use open_dds::{
 use crate::types::subgraph::Qualified;
 
 use crate::stages::{
-    aggregates, boolean_expressions, command_permissions, graphql_config, model_permissions,
-    object_relationships, order_by_expressions, scalar_type_representations,
+    aggregates, boolean_expressions, command_permissions, graphql_config, model_mutations,
+    model_permissions, object_relationships, operation_collections, order_by_expressions,
+    scalar_type_representations,
 };
 
 use super::plugins::LifecyclePluginConfigs;
@@ -40,5 +41,8 @@ pub struct Metadata {
         BTreeMap<Qualified<AggregateExpressionName>, aggregates::AggregateExpression>,
     pub graphql_config: graphql_config::GlobalGraphqlConfig,
     pub plugin_configs: LifecyclePluginConfigs,
+    pub operation_collections: operation_collections::OperationCollections,
+    #[serde_as(as = "Vec<(_, _)>")]
+    pub model_mutations: BTreeMap<Qualified<ModelName>, model_mutations::ModelMutations>,
     pub roles: BTreeSet<Role>,
 }
@@ -5,8 +5,9 @@ use crate::helpers::{
 use crate::stages::{
     aggregate_boolean_expressions, aggregates::AggregateExpressionError, apollo,
     boolean_expressions, commands, data_connector_scalar_types, data_connectors, graphql_config,
-    model_permissions, models, object_types, order_by_expressions, relationships, relay,
-    scalar_boolean_expressions, scalar_types, type_permissions,
+    model_mutations, model_permissions, models, object_types, operation_collections,
+    order_by_expressions, relationships, relay, scalar_boolean_expressions, scalar_types,
+    type_permissions,
 };
 use crate::types::subgraph::{Qualified, QualifiedTypeReference};
 use error_context::Context;
@@ -15,7 +16,10 @@ use lang_graphql::ast::common as ast;
 use open_dds::{
     arguments::ArgumentName,
     commands::CommandName,
-    data_connector::{DataConnectorColumnName, DataConnectorName, DataConnectorObjectType},
+    data_connector::{
+        DataConnectorColumnName, DataConnectorName, DataConnectorObjectType,
+        DataConnectorScalarType,
+    },
     flags,
     models::ModelName,
     relationships::RelationshipName,
@@ -125,6 +129,27 @@ pub enum Error {
         model_name: Qualified<ModelName>,
         field_name: FieldName,
     },
+    #[error("unknown field {field_name:} in the extractions of the groups defined for model {model_name:}")]
+    UnknownFieldInGroupsExtraction {
+        model_name: Qualified<ModelName>,
+        field_name: FieldName,
+    },
+    #[error("the data connector of model {model_name:} cannot extract any parts of field {field_name:}, which is used in the extractions of its groups")]
+    GroupsExtractionNotSupported {
+        model_name: Qualified<ModelName>,
+        field_name: FieldName,
+    },
+    #[error("the parts that the data connector of model {model_name:} can extract from field {field_name:} have different types, so the field cannot be used in the extractions of its groups")]
+    GroupsExtractionResultTypesDiffer {
+        model_name: Qualified<ModelName>,
+        field_name: FieldName,
+    },
+    #[error("the type {result_type:} of the parts that the data connector of model {model_name:} extracts from field {field_name:} has no type representation")]
+    GroupsExtractionResultTypeWithoutRepresentation {
+        model_name: Qualified<ModelName>,
+        field_name: FieldName,
+        result_type: DataConnectorScalarType,
+    },
     #[error("graphql config must be defined for a filter expression to be used in a {model:}")]
     CannotUseFilterExpressionsWithoutGraphQlConfig {
         model: Qualified<ModelName>,
@@ -314,6 +339,10 @@ pub enum Error {
     #[error("{0}")]
     RelayError(#[from] relay::RelayError),
     #[error("{0}")]
+    OperationCollectionError(#[from] operation_collections::OperationCollectionError),
+    #[error("{0}")]
+    ModelMutationError(#[from] model_mutations::ModelMutationError),
+    #[error("{0}")]
     ModelsError(#[from] models::ModelsError),
     #[error("{0}")]
     CommandsError(#[from] commands::CommandsError),
@@ -428,6 +457,13 @@ pub enum RelationshipError {
         model_name: Qualified<ModelName>,
         field_name: FieldName,
     },
+    #[error("field {field_name} of type {type_name} in a field path for relationship {relationship_name} on type {source_type} is not an object, so the path cannot descend into it.")]
+    NestedFieldPathThroughNonObjectField {
+        source_type: Qualified<CustomTypeName>,
+        relationship_name: RelationshipName,
+        type_name: Qualified<CustomTypeName>,
+        field_name: FieldName,
+    },
     #[error("target argument {argument_name} in argument mapping for relationship {relationship_name} on type {source_type} to model {model_name} is unknown.")]
     UnknownTargetModelArgumentInRelationshipMapping {
         source_type: Qualified<CustomTypeName>,
@@ -455,6 +491,13 @@ pub enum RelationshipError {
         relationship_name: RelationshipName,
         type_name: Qualified<CustomTypeName>,
     },
+    #[error("The target field {field_path} of model {model_name} is mapped to a value in the relationship {relationship_name} on type {type_name}, so it cannot be mapped again")]
+    ModelFieldValueMappingExistsInRelationship {
+        field_path: String,
+        model_name: Qualified<ModelName>,
+        relationship_name: RelationshipName,
+        type_name: Qualified<CustomTypeName>,
+    },
     #[error("The target argument {argument_name} of command {command_name} has been mapped more than once in the relationship {relationship_name} on type {type_name}")]
     CommandArgumentMappingExistsInRelationship {
         argument_name: ArgumentName,
@@ -462,6 +505,12 @@ pub enum RelationshipError {
         relationship_name: RelationshipName,
         type_name: Qualified<CustomTypeName>,
     },
+    #[error("A value mapped in the relationship {relationship_name} on type {type_name} does not match the type of its target: {type_error}")]
+    ValueMappingTypeError {
+        type_name: Qualified<CustomTypeName>,
+        relationship_name: RelationshipName,
+        type_error: TypecheckError,
+    },
     #[error("No mapping for target command argument {argument_name} in the relationship {relationship_name} on type {type_name}")]
     MissingArgumentMappingInRelationship {
         type_name: Qualified<CustomTypeName>,
@@ -485,6 +534,11 @@ pub enum RelationshipError {
         type_name: Qualified<CustomTypeName>,
         relationship_name: RelationshipName,
     },
+    #[error("The relationship {relationship_name} on type {type_name} defines a groups field, but groups can only be used with array relationships, not object relationships")]
+    GroupsAreOnlyAllowedOnArrayRelationships {
+        type_name: Qualified<CustomTypeName>,
+        relationship_name: RelationshipName,
+    },
     #[error("The aggregate defined on the relationship {relationship_name} on type {type_name} has an error: {error}")]
     ModelAggregateExpressionError {
         type_name: Qualified<CustomTypeName>,
@@ -4,8 +4,8 @@ use std::collections::HashSet;
 use crate::identifier::SubgraphName;
 use crate::{
     aggregates, boolean_expression, commands, data_connector, flags, graphql_config, models,
-    order_by_expression, permissions, plugins, relationships, types, Metadata, MetadataWithVersion,
-    OpenDdSubgraphObject, OpenDdSupergraphObject,
+    operation_collections, order_by_expression, permissions, plugins, relationships, types,
+    Metadata, MetadataWithVersion, OpenDdSubgraphObject, OpenDdSupergraphObject,
 };
 
 const GLOBALS_SUBGRAPH: SubgraphName = SubgraphName::new_inline_static("__globals");
@@ -48,6 +48,7 @@ pub struct MetadataAccessor {
     // `graphql_config` is a vector because we want to do some validation depending on the presence of the object
     pub graphql_config: Vec<QualifiedObject<graphql_config::GraphqlConfig>>,
     pub plugins: Vec<QualifiedObject<plugins::LifecyclePluginHookV1>>,
+    pub operation_collections: Vec<QualifiedObject<operation_collections::OperationCollectionV1>>,
 }
 
 fn load_metadata_objects(
@@ -174,6 +175,13 @@ fn load_metadata_objects(
                     plugin.value.upgrade(),
                 ));
             }
+            OpenDdSubgraphObject::OperationCollection(operation_collection) => {
+                accessor.operation_collections.push(QualifiedObject::new(
+                    operation_collection.path,
+                    subgraph,
+                    operation_collection.value.upgrade(),
+                ));
+            }
         }
     }
 }
@@ -252,6 +260,7 @@ impl MetadataAccessor {
             flags: flags.unwrap_or_default(),
             graphql_config: vec![],
             plugins: vec![],
+            operation_collections: vec![],
         }
     }
 }
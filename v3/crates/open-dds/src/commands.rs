@@ -97,6 +97,21 @@ pub struct CommandV1 {
     pub description: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, opendds_derive::OpenDd)]
+#[serde(rename_all = "camelCase")]
+#[opendd(json_schema(title = "ResponseCache"))]
+/// Caching of the responses of a data connector to the queries of a model or command. A cached
+/// response is reused for identical data connector requests, which include the permission
+/// predicates and argument presets applied to the request.
+pub struct ResponseCache {
+    /// The number of seconds for which a response is cached. Must be greater than 0.
+    pub ttl_seconds: u64,
+    /// Whether the role and session variables of the request are part of the cache key, so that
+    /// cached responses are only reused for requests with the same session. Defaults to true.
+    #[opendd(default = "true")]
+    pub include_session_variables: bool,
+}
+
 #[derive(
     Default, Deserialize, Serialize, opendds_derive::OpenDd, Clone, Debug, PartialEq, JsonSchema,
 )]
@@ -130,6 +145,10 @@ pub struct CommandSource {
     /// Mapping from command argument names to data connector function or procedure argument names.
     #[opendd(default)]
     pub argument_mapping: ArgumentMapping,
+
+    /// Caching of the responses of the data connector to this command. Only commands backed by
+    /// a function can be cached. Defaults to no caching.
+    pub response_cache: Option<ResponseCache>,
 }
 
 impl CommandSource {
@@ -6,9 +6,10 @@ use serde::{Deserialize, Serialize};
 mod v1;
 
 pub use v1::{
-    DataConnectorArgumentPreset, DataConnectorArgumentPresetValue, DataConnectorLinkV1,
-    DataConnectorUrlV1 as DataConnectorUrl, HttpHeaders, HttpHeadersPreset, ReadWriteUrls,
-    ResponseHeaders,
+    DataConnectorArgumentPreset, DataConnectorArgumentPresetValue, DataConnectorCircuitBreaker,
+    DataConnectorLinkV1, DataConnectorRequestPolicy, DataConnectorRetryPolicy,
+    DataConnectorUrlV1 as DataConnectorUrl, HttpHeaders, HttpHeadersPreset, ReadReplicaUrls,
+    ReadWriteUrls, ReplicaHealthCheck, ReplicaLoadBalancing, ResponseHeaders,
 };
 
 use crate::{identifier::Identifier, impl_OpenDd_default_for, str_newtype};
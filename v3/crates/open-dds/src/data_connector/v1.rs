@@ -18,7 +18,54 @@ pub struct ReadWriteUrls {
     pub write: EnvironmentValue,
 }
 
-/// A URL to access a data connector. This can be a single URL or a pair of read and write URLs.
+/// A pool of read replicas to access a data connector, used for queries, and a URL used for
+/// mutations. A replica that cannot be connected to is taken out of the pool for
+/// `ejectionCooldownMs`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, opendds_derive::OpenDd)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+#[opendd(json_schema(title = "ReadReplicaUrls"))]
+pub struct ReadReplicaUrls {
+    /// The URLs of the read replicas. At least one URL is required.
+    pub read: Vec<EnvironmentValue>,
+    /// The URL used for mutations.
+    pub write: EnvironmentValue,
+    /// How queries are balanced between the read replicas. Defaults to round robin.
+    pub load_balancing: Option<ReplicaLoadBalancing>,
+    /// The number of milliseconds for which a replica that cannot be connected to is taken out
+    /// of the pool.
+    #[opendd(default = 30000)]
+    pub ejection_cooldown_ms: u64,
+    /// Periodic probing of the health of the read replicas. Defaults to no probing.
+    pub health_check: Option<ReplicaHealthCheck>,
+}
+
+/// How queries are balanced between read replicas.
+#[derive(
+    Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema, opendds_derive::OpenDd,
+)]
+#[serde(rename_all = "camelCase")]
+#[schemars(title = "ReplicaLoadBalancing")]
+pub enum ReplicaLoadBalancing {
+    /// Send queries to each replica in turn.
+    RoundRobin,
+    /// Send queries to the replica with the fewest requests in progress.
+    LeastOutstandingRequests,
+}
+
+/// Periodic probing of the `/health` endpoint of read replicas. A replica whose probe fails is
+/// taken out of the pool until one of its probes succeeds.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, opendds_derive::OpenDd)]
+#[serde(rename_all = "camelCase")]
+#[opendd(json_schema(title = "ReplicaHealthCheck"))]
+pub struct ReplicaHealthCheck {
+    /// The number of milliseconds between two probes of a replica.
+    #[opendd(default = 10000)]
+    pub interval_ms: u64,
+}
+
+/// A URL to access a data connector. This can be a single URL, a pair of read and write URLs,
+/// or a pool of read replicas and a write URL.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, opendds_derive::OpenDd)]
 #[opendd(externally_tagged, json_schema(title = "DataConnectorUrlV1"))]
 #[serde(rename_all = "camelCase")]
@@ -27,6 +74,8 @@ pub enum DataConnectorUrlV1 {
     SingleUrl(EnvironmentValue),
     // #[opendd(json_schema(title = "ReadWriteUrls"))]
     ReadWriteUrls(ReadWriteUrls),
+    // #[opendd(json_schema(title = "ReadReplicaUrls"))]
+    ReadReplicas(ReadReplicaUrls),
 }
 
 #[derive(
@@ -69,6 +118,10 @@ pub struct DataConnectorLinkV1 {
     /// HTTP response headers configuration that is forwarded from a data
     /// connector to the client.
     pub response_headers: Option<ResponseHeaders>,
+    /// Timeouts, retries, circuit breaking, batching and size limits of the requests made to the
+    /// data connector. Defaults to no timeout, no retries, no circuit breaker and no batching
+    /// limit.
+    pub request_policy: Option<DataConnectorRequestPolicy>,
 }
 
 #[derive(Serialize, Clone, Debug, PartialEq, opendds_derive::OpenDd)]
@@ -128,3 +181,59 @@ pub struct ResponseHeaders {
     /// response headers
     pub forward_headers: Vec<String>,
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, opendds_derive::OpenDd)]
+#[serde(rename_all = "camelCase")]
+#[opendd(json_schema(title = "DataConnectorRequestPolicy"))]
+/// Timeouts, retries, circuit breaking, batching and size limits of the requests made to a data
+/// connector.
+pub struct DataConnectorRequestPolicy {
+    /// The number of milliseconds after which a request to the data connector fails.
+    pub timeout_ms: Option<u64>,
+    /// How queries failing because the data connector is unavailable are retried.
+    /// Mutations are never retried.
+    pub retry: Option<DataConnectorRetryPolicy>,
+    /// When to stop sending requests to a data connector that keeps failing.
+    pub circuit_breaker: Option<DataConnectorCircuitBreaker>,
+    /// The maximum number of variable sets sent in a single query request, such as the rows a
+    /// remote join is executed for. Larger sets of variables are split across several requests.
+    /// Defaults to no limit.
+    pub max_variable_sets_per_request: Option<u32>,
+    /// The maximum number of distinct values of the related fields that a query to the data
+    /// connector can be compared with, when it is filtered by a remote relationship. Filters
+    /// matching more values fail. Defaults to no limit.
+    pub max_remote_filter_values: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, opendds_derive::OpenDd)]
+#[serde(rename_all = "camelCase")]
+#[opendd(json_schema(title = "DataConnectorRetryPolicy"))]
+/// How queries failing because the data connector is unavailable are retried. A query is
+/// retried when the request times out or cannot connect, or when the data connector responds
+/// with a 502, 503 or 504 status code.
+pub struct DataConnectorRetryPolicy {
+    /// The maximum number of times a query is retried.
+    pub max_retries: u32,
+    /// The number of milliseconds to wait before the first retry. The wait doubles with every
+    /// retry, and is randomly reduced by up to half to spread out concurrent retries.
+    #[opendd(default = 100)]
+    pub initial_backoff_ms: u64,
+    /// The maximum number of milliseconds to wait between two retries.
+    #[opendd(default = 5000)]
+    pub max_backoff_ms: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, opendds_derive::OpenDd)]
+#[serde(rename_all = "camelCase")]
+#[opendd(json_schema(title = "DataConnectorCircuitBreaker"))]
+/// A circuit breaker for a data connector. After `failureThreshold` consecutive requests fail
+/// because the data connector is unavailable, the circuit opens and requests fail without being
+/// sent. After `resetTimeoutMs`, a single probe request is sent: the circuit closes if it
+/// succeeds, and opens again otherwise.
+pub struct DataConnectorCircuitBreaker {
+    /// The number of consecutive failed requests after which the circuit opens.
+    pub failure_threshold: u32,
+    /// The number of milliseconds for which the circuit stays open before a probe request is sent.
+    #[opendd(default = 30000)]
+    pub reset_timeout_ms: u64,
+}
@@ -5,6 +5,7 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::permissions::Role;
 use crate::types::{GraphQlFieldName, GraphQlTypeName};
 
 #[derive(Serialize, Clone, Debug, PartialEq, opendds_derive::OpenDd)]
@@ -31,6 +32,11 @@ pub struct GraphqlConfigV1 {
     pub mutation: MutationGraphqlConfig,
     pub subscription: Option<SubscriptionGraphqlConfig>,
     pub apollo_federation: Option<GraphqlApolloFederationConfig>,
+    /// Limits on the size and estimated cost of GraphQL operations.
+    pub query_limits: Option<QueryLimitsGraphqlConfig>,
+    /// Configuration for batched GraphQL requests, which send an array of operations. Batched
+    /// requests are rejected when it is omitted.
+    pub batching: Option<BatchingGraphqlConfig>,
 }
 
 /// Configuration for the GraphQL schema of Hasura features for queries.
@@ -204,3 +210,59 @@ pub struct AggregateGraphqlConfig {
     /// The name of the _count_distinct field used for the count distinct aggregate function
     pub count_distinct_field_name: GraphQlFieldName,
 }
+
+/// Limits on the size and estimated cost of GraphQL operations. Operations exceeding them are
+/// rejected before they are executed.
+#[derive(Serialize, Clone, Debug, PartialEq, opendds_derive::OpenDd)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+#[opendd(json_schema(title = "QueryLimitsGraphqlConfig"))]
+pub struct QueryLimitsGraphqlConfig {
+    /// The limits applied to roles that do not have limits of their own.
+    pub default: Option<QueryLimits>,
+    /// The limits applied to specific roles, in place of the default limits.
+    #[opendd(default, json_schema(default_exp = "serde_json::json!([])"))]
+    pub roles: Vec<RoleQueryLimits>,
+}
+
+/// Configuration for batched GraphQL requests, whose body is an array of operations that are
+/// executed together and answered with an array of responses.
+#[derive(Serialize, Clone, Debug, PartialEq, opendds_derive::OpenDd)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+#[opendd(json_schema(title = "BatchingGraphqlConfig"))]
+pub struct BatchingGraphqlConfig {
+    /// The maximum number of operations in a batch. Batches with more operations are rejected, and
+    /// a value of 0 disables batching.
+    pub max_batch_size: u32,
+}
+
+/// The limits of a GraphQL operation. Omitted limits are not enforced.
+#[derive(Serialize, Clone, Debug, PartialEq, opendds_derive::OpenDd)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+#[opendd(json_schema(title = "QueryLimits"))]
+pub struct QueryLimits {
+    /// The maximum nesting depth of selection sets. Root fields are at depth 1.
+    pub max_depth: Option<u32>,
+    /// The maximum number of fields selected in the operation.
+    pub max_fields: Option<u32>,
+    /// The maximum number of aliased fields in the operation.
+    pub max_aliases: Option<u32>,
+    /// The maximum estimated cost of the operation. Every selected field costs 1, multiplied by the
+    /// number of rows that the fields of lists of objects, such as array relationships, are
+    /// estimated to return: their `limit` argument if provided, 100 otherwise.
+    pub max_cost: Option<u64>,
+}
+
+/// The limits of the GraphQL operations run by a role.
+#[derive(Serialize, Clone, Debug, PartialEq, opendds_derive::OpenDd)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+#[opendd(json_schema(title = "RoleQueryLimits"))]
+pub struct RoleQueryLimits {
+    /// The role to which the limits apply.
+    pub role: Role,
+    /// The limits applied to the role.
+    pub limits: QueryLimits,
+}
@@ -16,6 +16,7 @@ pub mod flags;
 pub mod graphql_config;
 pub mod identifier;
 pub mod models;
+pub mod operation_collections;
 pub mod order_by_expression;
 pub mod permissions;
 pub mod plugins;
@@ -125,6 +126,9 @@ pub enum OpenDdSubgraphObject {
 
     // Plugin
     LifecyclePluginHook(Spanned<plugins::LifecyclePluginHook>),
+
+    // Operation collections
+    OperationCollection(Spanned<operation_collections::OperationCollection>),
 }
 
 /// All of the metadata required to run Hasura v3 engine.
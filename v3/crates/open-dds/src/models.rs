@@ -3,8 +3,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     aggregates::AggregateExpressionName,
-    arguments::ArgumentDefinition,
-    commands::ArgumentMapping,
+    arguments::{ArgumentDefinition, ArgumentName},
+    commands::{ArgumentMapping, CommandName, ResponseCache},
     data_connector::{CollectionName, DataConnectorName},
     identifier::Identifier,
     order_by_expression::OrderByExpressionName,
@@ -160,6 +160,13 @@ impl Model {
             Model::V2(v2) => &v2.description,
         }
     }
+
+    pub fn mutations(&self) -> Option<&ModelMutations> {
+        match self {
+            Model::V1(_) => None,
+            Model::V2(v2) => v2.mutations.as_ref(),
+        }
+    }
 }
 
 #[derive(Serialize, Clone, Debug, PartialEq, opendds_derive::OpenDd)]
@@ -220,11 +227,88 @@ pub struct ModelV2 {
     pub aggregate_expression: Option<AggregateExpressionName>,
     /// Configuration for how this model should appear in the GraphQL schema.
     pub graphql: Option<ModelGraphQlDefinitionV2>,
+    /// The procedures used to insert, update and delete the objects of this model, through the
+    /// JSON:API. Not available on `Model` v1.
+    pub mutations: Option<ModelMutations>,
     /// The description of the model.
     /// Gets added to the description of the model in the graphql schema.
     pub description: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, opendds_derive::OpenDd)]
+#[serde(rename_all = "camelCase")]
+#[opendd(json_schema(title = "ModelMutations", example = "ModelMutations::example"))]
+/// The procedure commands used to insert, update and delete the objects of a model, for
+/// instance through the JSON:API. Each procedure must return an object of the type of the model.
+/// Mutations can only be defined on `Model` v2.
+pub struct ModelMutations {
+    /// The procedure inserting an object into the model.
+    pub insert: Option<ModelInsertProcedure>,
+    /// The procedure updating an object of the model.
+    pub update: Option<ModelUpdateProcedure>,
+    /// The procedure deleting an object of the model.
+    pub delete: Option<ModelDeleteProcedure>,
+}
+
+impl ModelMutations {
+    fn example() -> serde_json::Value {
+        serde_json::json!(
+            {
+              "insert": {
+                "commandName": "insert_article",
+                "objectArgument": "object"
+              },
+              "update": {
+                "commandName": "update_article_by_id",
+                "keyArgument": "article_id",
+                "objectArgument": "update_columns"
+              },
+              "delete": {
+                "commandName": "delete_article_by_id",
+                "keyArgument": "article_id"
+              }
+            }
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, opendds_derive::OpenDd)]
+#[serde(rename_all = "camelCase")]
+#[opendd(json_schema(title = "ModelInsertProcedure"))]
+/// A procedure inserting an object into a model.
+pub struct ModelInsertProcedure {
+    /// The name of the procedure command.
+    pub command_name: CommandName,
+    /// The argument of the command taking the object to insert. If omitted, each field of the
+    /// object is passed as the argument of the command with the same name.
+    pub object_argument: Option<ArgumentName>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, opendds_derive::OpenDd)]
+#[serde(rename_all = "camelCase")]
+#[opendd(json_schema(title = "ModelUpdateProcedure"))]
+/// A procedure updating an object of a model.
+pub struct ModelUpdateProcedure {
+    /// The name of the procedure command.
+    pub command_name: CommandName,
+    /// The argument of the command taking the unique identifier of the object to update.
+    pub key_argument: ArgumentName,
+    /// The argument of the command taking the fields of the object to update. If omitted, each
+    /// field is passed as the argument of the command with the same name.
+    pub object_argument: Option<ArgumentName>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, opendds_derive::OpenDd)]
+#[serde(rename_all = "camelCase")]
+#[opendd(json_schema(title = "ModelDeleteProcedure"))]
+/// A procedure deleting an object of a model.
+pub struct ModelDeleteProcedure {
+    /// The name of the procedure command.
+    pub command_name: CommandName,
+    /// The argument of the command taking the unique identifier of the object to delete.
+    pub key_argument: ArgumentName,
+}
+
 #[derive(Serialize, Clone, Debug, PartialEq, opendds_derive::OpenDd)]
 #[serde(rename_all = "camelCase")]
 #[opendd(json_schema(title = "ModelSource", example = "ModelSource::example"))]
@@ -239,6 +323,15 @@ pub struct ModelSource {
     /// Mapping from model argument names to data connector collection argument names.
     #[opendd(default)]
     pub argument_mapping: ArgumentMapping,
+
+    /// Caching of the responses of the data connector to the queries of this model. Defaults to
+    /// no caching.
+    pub response_cache: Option<ResponseCache>,
+
+    /// The maximum number of rows of this model that are fetched to be sorted by the engine when
+    /// ordering by fields of remote relationships. Queries that would fetch more rows fail.
+    /// Defaults to 10000.
+    pub remote_ordering_row_limit: Option<u32>,
 }
 
 impl ModelSource {
@@ -278,6 +371,9 @@ pub struct ModelGraphQlDefinition {
     /// Configures the query root field added to the GraphQL API that can be used to
     /// aggregate over the model
     pub aggregate: Option<ModelAggregateGraphQlDefinition>,
+    /// A hint to HTTP caches about how long the responses of GraphQL queries that select from
+    /// this model can be cached for, when the queries are sent with GET. Defaults to no caching.
+    pub cache_hint: Option<ModelCacheHint>,
 }
 
 impl ModelGraphQlDefinition {
@@ -312,6 +408,8 @@ impl ModelGraphQlDefinition {
             apollo_federation: self.apollo_federation,
             filter_input_type_name: self.filter_input_type_name,
             aggregate: self.aggregate,
+            groups: None,
+            cache_hint: self.cache_hint,
         }
     }
 }
@@ -342,6 +440,12 @@ pub struct ModelGraphQlDefinitionV2 {
     /// Configures the query root field added to the GraphQL API that can be used to
     /// aggregate over the model
     pub aggregate: Option<ModelAggregateGraphQlDefinition>,
+    /// Configures the query root field added to the GraphQL API that can be used to
+    /// group the objects of the model and aggregate over each group
+    pub groups: Option<ModelGroupsGraphQlDefinition>,
+    /// A hint to HTTP caches about how long the responses of GraphQL queries that select from
+    /// this model can be cached for, when the queries are sent with GET. Defaults to no caching.
+    pub cache_hint: Option<ModelCacheHint>,
 }
 
 impl ModelGraphQlDefinitionV2 {
@@ -363,6 +467,30 @@ impl ModelGraphQlDefinitionV2 {
             "aggregate": {
                 "queryRootField": "ArticleAggregate",
                 "description": "Aggregate over Articles"
+            },
+            "groups": {
+                "queryRootField": "ArticleGroups",
+                "groupTypeName": "Article_Group",
+                "groupKeyTypeName": "Article_GroupKey",
+                "groupKeyFieldName": "group_key",
+                "groupAggregateFieldName": "group_aggregate",
+                "extractions": [
+                    {
+                        "fieldName": "published_at",
+                        "groupKeyFieldName": "published_at_part",
+                        "enumTypeName": "Article_PublishedAtPart"
+                    }
+                ],
+                "filter": {
+                    "argumentName": "having",
+                    "inputTypeName": "Article_GroupFilter",
+                    "countComparisonTypeName": "Article_GroupCountComparison"
+                },
+                "orderBy": {
+                    "argumentName": "group_order_by",
+                    "inputTypeName": "Article_GroupOrderBy"
+                },
+                "description": "Group Articles and aggregate over each group"
             }
         })
     }
@@ -422,6 +550,109 @@ pub struct SubscriptionGraphQlDefinition {
     pub polling_interval_ms: u64,
 }
 
+/// The definition of the GraphQL API for grouping the objects of a model.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, opendds_derive::OpenDd)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+#[opendd(json_schema(title = "ModelGroupsGraphQlDefinition"))]
+pub struct ModelGroupsGraphQlDefinition {
+    /// The name of the query root field for this API.
+    pub query_root_field: GraphQlFieldName,
+    /// The name of the GraphQL object type that represents a single group.
+    pub group_type_name: GraphQlTypeName,
+    /// The name of the GraphQL object type that holds the fields a group has been formed by.
+    pub group_key_type_name: GraphQlTypeName,
+    /// The name of the field of a group that holds the fields the group has been formed by.
+    pub group_key_field_name: GraphQlFieldName,
+    /// The name of the field of a group that holds the aggregates computed across its objects.
+    pub group_aggregate_field_name: GraphQlFieldName,
+    /// Fields that groups can be formed by a part of, such as the year of a date. Each adds a
+    /// field to the group key that takes the part to extract as an argument.
+    #[opendd(default, json_schema(default_exp = "serde_json::json!([])"))]
+    pub extractions: Vec<ModelGroupsExtractionGraphQlDefinition>,
+    /// Configures the argument used to filter groups by the aggregates computed across their objects.
+    pub filter: Option<ModelGroupsFilterGraphQlDefinition>,
+    /// Configures the argument used to sort groups.
+    pub order_by: Option<ModelGroupsOrderByGraphQlDefinition>,
+    /// The description of the groups graphql definition of the model.
+    /// Gets added to the description of the groups root field of the model in the graphql schema.
+    pub description: Option<String>,
+    /// Whether this groups query field is deprecated.
+    /// If set, the deprecation status is added to the groups root field's graphql schema.
+    pub deprecated: Option<Deprecated>,
+}
+
+/// The definition of a field of a group key that holds a part of the value of a field, such
+/// as the year of a date.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, opendds_derive::OpenDd)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+#[opendd(json_schema(title = "ModelGroupsExtractionGraphQlDefinition"))]
+pub struct ModelGroupsExtractionGraphQlDefinition {
+    /// The field of the model's object type to extract a part of.
+    pub field_name: FieldName,
+    /// The name of the field of the group key that holds the extracted part.
+    pub group_key_field_name: GraphQlFieldName,
+    /// The name of the GraphQL enum type listing the parts that the data connector can
+    /// extract from the field.
+    pub enum_type_name: GraphQlTypeName,
+}
+
+/// The definition of the GraphQL argument used to filter groups.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, opendds_derive::OpenDd)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+#[opendd(json_schema(title = "ModelGroupsFilterGraphQlDefinition"))]
+pub struct ModelGroupsFilterGraphQlDefinition {
+    /// The name of the argument.
+    pub argument_name: GraphQlFieldName,
+    /// The name of the GraphQL input type of the argument.
+    pub input_type_name: GraphQlTypeName,
+    /// The name of the GraphQL input type used to compare the number of objects in a group.
+    pub count_comparison_type_name: GraphQlTypeName,
+}
+
+/// The definition of the GraphQL argument used to sort groups.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, opendds_derive::OpenDd)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+#[opendd(json_schema(title = "ModelGroupsOrderByGraphQlDefinition"))]
+pub struct ModelGroupsOrderByGraphQlDefinition {
+    /// The name of the argument.
+    pub argument_name: GraphQlFieldName,
+    /// The name of the GraphQL input type of the elements of the argument.
+    pub input_type_name: GraphQlTypeName,
+}
+
+/// A hint to HTTP caches about the responses of GraphQL queries that select from a model. A
+/// response is only cacheable when every model that the query selects from has a cache hint, and
+/// it is cached for the shortest of their `maxAge`s.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, opendds_derive::OpenDd)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+#[opendd(json_schema(title = "ModelCacheHint"))]
+pub struct ModelCacheHint {
+    /// The number of seconds for which a response can be cached.
+    pub max_age: u32,
+    /// Which caches can store a response. Defaults to `private`.
+    pub scope: Option<CacheScope>,
+}
+
+/// Which caches can store the response to a GraphQL query.
+#[derive(
+    Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema, opendds_derive::OpenDd,
+)]
+#[serde(rename_all = "camelCase")]
+#[schemars(title = "CacheScope")]
+pub enum CacheScope {
+    /// Only the client that made the request can store the response.
+    Private,
+    /// Shared caches, such as CDNs, can store the response and serve it to clients sending the
+    /// same credentials and role. Responses to roles with a permission filter on the model are
+    /// always private.
+    Public,
+}
+
 /// A field that can be used to order the objects in a model.
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, opendds_derive::OpenDd)]
 #[serde(rename_all = "camelCase")]
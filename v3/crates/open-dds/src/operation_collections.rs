@@ -0,0 +1,89 @@
+use serde::Serialize;
+
+use crate::{identifier::Identifier, permissions::Role, str_newtype};
+
+str_newtype!(OperationCollectionName over Identifier | doc "The name of an operation collection.");
+
+/// Definition of a collection of GraphQL operations. When operation collections are defined, only
+/// the operations they contain can be run, and only by the roles they allow. Their operations can
+/// also be run as persisted queries, by sending the SHA-256 hash of the query instead of its text.
+#[derive(Serialize, Clone, Debug, PartialEq, opendds_derive::OpenDd)]
+#[serde(tag = "version", content = "definition")]
+#[serde(rename_all = "camelCase")]
+#[opendd(
+    as_versioned_with_definition,
+    json_schema(
+        title = "OperationCollection",
+        example = "OperationCollection::example"
+    )
+)]
+pub enum OperationCollection {
+    V1(OperationCollectionV1),
+}
+
+impl OperationCollection {
+    fn example() -> serde_json::Value {
+        serde_json::json!({
+            "kind": "OperationCollection",
+            "version": "v1",
+            "definition": {
+                "name": "production",
+                "operations": [
+                    {
+                        "name": "GetArticles",
+                        "query": "query GetArticles { articles { id title } }"
+                    }
+                ],
+                "permissions": [
+                    {
+                        "role": "user"
+                    }
+                ]
+            }
+        })
+    }
+
+    pub fn upgrade(self) -> OperationCollectionV1 {
+        match self {
+            OperationCollection::V1(v1) => v1,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, opendds_derive::OpenDd)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[opendd(json_schema(title = "OperationCollectionV1"))]
+/// Definition of a collection of GraphQL operations - version 1.
+pub struct OperationCollectionV1 {
+    /// The name of the operation collection.
+    pub name: OperationCollectionName,
+    /// The GraphQL operations in the collection.
+    pub operations: Vec<CollectionOperation>,
+    /// The roles that are allowed to run the operations in the collection.
+    pub permissions: Vec<OperationCollectionPermission>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, opendds_derive::OpenDd)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[opendd(json_schema(title = "CollectionOperation"))]
+/// A GraphQL operation in an operation collection.
+pub struct CollectionOperation {
+    /// The name of the operation in the collection.
+    pub name: String,
+    /// The GraphQL document of the operation. Requests are matched against the SHA-256 hash of
+    /// this exact text.
+    pub query: String,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, opendds_derive::OpenDd)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[opendd(json_schema(title = "OperationCollectionPermission"))]
+/// Defines a role that is allowed to run the operations in an operation collection.
+pub struct OperationCollectionPermission {
+    /// The role that is allowed to run the operations.
+    pub role: Role,
+    /// Whether the role is also allowed to run operations that are not in any operation
+    /// collection, exempting it from the allowlist.
+    #[opendd(default, json_schema(default_exp = "serde_json::json!(false)"))]
+    pub allow_any_operation: bool,
+}
@@ -79,9 +79,11 @@ pub struct ModelGroupsSelection {
 #[serde(rename_all = "camelCase")]
 pub struct ModelDimensions {
     pub dimensions: IndexMap<Name, Dimension>,
-    // pub filter: Option<BooleanExpression>,
-    // #[serde(default)]
-    // pub order_by: Vec<OrderByElement>,
+    /// Which groups to retrieve, based on the metrics aggregated across their objects.
+    pub filter: Option<GroupBooleanExpression>,
+    /// How to sort the groups.
+    #[serde(default)]
+    pub order_by: Vec<GroupOrderByElement>,
     pub limit: Option<usize>,
     pub offset: Option<usize>,
 }
@@ -97,6 +99,39 @@ pub enum Dimension {
     },
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+/// A boolean expression over the metrics aggregated across the objects of a group.
+pub enum GroupBooleanExpression {
+    And(Vec<GroupBooleanExpression>),
+    Or(Vec<GroupBooleanExpression>),
+    Not(Box<GroupBooleanExpression>),
+    IsNull(Aggregate),
+    Comparison {
+        operand: Aggregate,
+        operator: ComparisonOperator,
+        argument: Box<Value>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+/// A single ordering condition for groups, composed of an ordering key and ordering direction.
+pub struct GroupOrderByElement {
+    pub operand: GroupOrderByOperand,
+    pub direction: OrderByDirection,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+/// The key to sort groups by.
+pub enum GroupOrderByOperand {
+    /// One of the dimensions of the query, by name.
+    Dimension(Name),
+    /// A metric aggregated across the objects of the group.
+    Aggregate(Aggregate),
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 /// An extraction function to evaluate.
@@ -155,7 +190,7 @@ pub enum ObjectSubSelection {
     // FieldGroups(ObjectFieldGroupsSelection),
     Relationship(RelationshipSelection),
     RelationshipAggregate(RelationshipAggregateSelection),
-    // RelationshipGroups(RelationshipGroupsSelection),
+    RelationshipGroups(RelationshipGroupsSelection),
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
@@ -188,6 +223,17 @@ pub struct RelationshipAggregateSelection {
     pub selection: IndexMap<Name, Aggregate>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+/// Selection of metrics aggregated over groups of related values.
+pub struct RelationshipGroupsSelection {
+    #[serde(flatten)]
+    pub target: RelationshipTarget,
+    /// What metrics aggregated across the objects of each group to retrieve.
+    pub selection: IndexMap<Name, Aggregate>,
+    pub dimensions: ModelDimensions,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 /// The model lookup to target in a query.
@@ -607,4 +653,81 @@ mod test {
         )
         .unwrap();
     }
+
+    #[test]
+    fn test_deserialize_model_groups_query() {
+        /*
+        This is the equivalent of
+        SELECT author_id, COUNT(*) AS article_count
+        FROM articles
+        GROUP BY author_id
+        HAVING COUNT(*) > 5
+        ORDER BY COUNT(*) DESC, author_id ASC
+        */
+        serde_json::from_str::<QueryRequest>(
+            r#"{
+                "version": "v1",
+                "queries": {
+                    "prolific_authors": {
+                        "modelGroups": {
+                            "subgraph": "default",
+                            "modelName": "Articles",
+                            "selection": {
+                                "article_count": {
+                                    "function": {
+                                        "count": {}
+                                    }
+                                }
+                            },
+                            "dimensions": {
+                                "dimensions": {
+                                    "author_id": {
+                                        "field": {
+                                            "column": {
+                                                "field": {
+                                                    "fieldName": "author_id"
+                                                }
+                                            }
+                                        }
+                                    }
+                                },
+                                "filter": {
+                                    "comparison": {
+                                        "operand": {
+                                            "function": {
+                                                "count": {}
+                                            }
+                                        },
+                                        "operator": "_gt",
+                                        "argument": {
+                                            "literal": 5
+                                        }
+                                    }
+                                },
+                                "orderBy": [
+                                    {
+                                        "operand": {
+                                            "aggregate": {
+                                                "function": {
+                                                    "count": {}
+                                                }
+                                            }
+                                        },
+                                        "direction": "Desc"
+                                    },
+                                    {
+                                        "operand": {
+                                            "dimension": "author_id"
+                                        },
+                                        "direction": "Asc"
+                                    }
+                                ]
+                            }
+                        }
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+    }
 }
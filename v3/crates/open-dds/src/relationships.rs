@@ -197,6 +197,9 @@ impl RelationshipMapping {
 pub struct RelationshipGraphQlDefinition {
     /// The field name to use for the field that represents an aggregate over the relationship
     pub aggregate_field_name: Option<FieldName>,
+    /// The field name to use for the field that groups the related objects and aggregates
+    /// over each group
+    pub groups_field_name: Option<FieldName>,
 }
 
 #[derive(Serialize, Clone, Debug, PartialEq, Eq, opendds_derive::OpenDd)]
@@ -7,23 +7,29 @@ mod mutation;
 mod query;
 mod relationships;
 mod remote_joins;
+mod remote_order_by;
 use std::sync::Arc;
 
-pub use aggregates::{AggregateFieldSelection, AggregateSelectionSet, Dimension, Grouping};
+pub use aggregates::{
+    AggregateFieldSelection, AggregateSelectionSet, Dimension, GroupExpression,
+    GroupOrderByElement, GroupOrderByTarget, Grouping,
+};
 pub use arguments::{Argument, MutationArgument};
-pub use field::{Field, NestedArray, NestedField, NestedObject};
+pub use field::{Field, NestedArray, NestedField, NestedObject, NESTED_RELATIONSHIP_FIELD_ALIAS};
 pub use filter::ResolvedFilterExpression;
 pub use mutation::MutationExecutionPlan;
 pub use query::{
     AggregateFieldsSelection, FieldsSelection, PredicateQueryTree, PredicateQueryTrees,
-    QueryExecutionPlan, QueryNode, RemotePredicateKey, UniqueNumber,
+    QueryExecutionPlan, QueryNode, RemotePredicateKey, ResponseCache, UniqueNumber,
 };
 pub use relationships::{Relationship, RelationshipArgument};
 pub use remote_joins::{
     mk_argument_target_variable_name, JoinLocations, JoinNode, Location, LocationKind, RemoteJoin,
     RemoteJoinFieldMapping, RemoteJoinObjectFieldMapping, RemoteJoinObjectTargetField,
-    RemoteJoinType, RemoteJoinVariable, RemoteJoinVariableSet, SourceFieldAlias, TargetField,
+    RemoteJoinType, RemoteJoinVariable, RemoteJoinVariableSet, SourceFieldAlias, SourceFieldName,
+    TargetField,
 };
+pub use remote_order_by::{RemoteOrderBy, RemoteOrderByElement, RemoteOrderByTarget};
 
 // these versions of the types are equivalent to the old "Resolved" versions
 
@@ -58,6 +64,9 @@ pub struct QueryExecutionTree {
     pub remote_predicates: PredicateQueryTrees,
     pub query_execution_plan: query::QueryExecutionPlan,
     pub remote_join_executions: remote_joins::JoinLocations,
+    /// Ordering applied by the engine once the rows are fetched, if the query orders by fields of
+    /// remote relationships
+    pub remote_order_by: Option<remote_order_by::RemoteOrderBy>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -88,6 +97,7 @@ pub enum ProcessResponseAs {
         response_config: Option<Arc<metadata_resolve::data_connectors::CommandsResponseConfig>>,
     },
     Aggregates,
+    Groups,
 }
 
 impl ProcessResponseAs {
@@ -96,7 +106,7 @@ impl ProcessResponseAs {
             ProcessResponseAs::Object { is_nullable }
             | ProcessResponseAs::Array { is_nullable }
             | ProcessResponseAs::CommandResponse { is_nullable, .. } => *is_nullable,
-            ProcessResponseAs::Aggregates { .. } => false,
+            ProcessResponseAs::Aggregates { .. } | ProcessResponseAs::Groups => false,
         }
     }
 }
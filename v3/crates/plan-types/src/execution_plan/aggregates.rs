@@ -1,9 +1,9 @@
-use crate::NdcFieldAlias;
+use crate::{NdcFieldAlias, OrderByDirection, RelationshipPathElement, ResolvedFilterExpression};
 use indexmap::IndexMap;
 use nonempty::NonEmpty;
 use open_dds::{
     aggregates::{DataConnectorAggregationFunctionName, DataConnectorExtractionFunctionName},
-    data_connector::DataConnectorColumnName,
+    data_connector::{DataConnectorColumnName, DataConnectorOperatorName},
 };
 use serde::Serialize;
 use std::hash::Hash;
@@ -12,6 +12,10 @@ use std::hash::Hash;
 pub struct Grouping {
     pub aggregates: IndexMap<NdcFieldAlias, AggregateFieldSelection>,
     pub dimensions: IndexMap<NdcFieldAlias, Dimension>,
+    /// Optionally filter the groups
+    pub predicate: Option<GroupExpression>,
+    /// Optionally sort the groups
+    pub order_by: Option<Vec<GroupOrderByElement>>,
     pub limit: Option<u32>,
     pub offset: Option<u32>,
 }
@@ -19,11 +23,52 @@ pub struct Grouping {
 #[derive(Debug, Serialize, PartialEq, Clone, Eq, Hash)]
 pub enum Dimension {
     Column {
+        /// Any local relationships to traverse to reach the column
+        relationship_path: Vec<RelationshipPathElement<ResolvedFilterExpression>>,
         column_path: NonEmpty<DataConnectorColumnName>,
         extraction: Option<DataConnectorExtractionFunctionName>,
     },
 }
 
+/// A predicate on the aggregates of a group
+#[derive(Debug, Serialize, PartialEq, Clone, Eq)]
+pub enum GroupExpression {
+    And {
+        expressions: Vec<GroupExpression>,
+    },
+    Or {
+        expressions: Vec<GroupExpression>,
+    },
+    Not {
+        expression: Box<GroupExpression>,
+    },
+    IsNull {
+        aggregate: AggregateFieldSelection,
+    },
+    BinaryComparison {
+        aggregate: AggregateFieldSelection,
+        operator: DataConnectorOperatorName,
+        value: serde_json::Value,
+    },
+}
+
+#[derive(Debug, Serialize, PartialEq, Clone, Eq)]
+pub struct GroupOrderByElement {
+    pub order_direction: OrderByDirection,
+    pub target: GroupOrderByTarget,
+}
+
+#[derive(Debug, Serialize, PartialEq, Clone, Eq)]
+pub enum GroupOrderByTarget {
+    /// The dimension at the given index in the dimensions of the grouping
+    Dimension {
+        index: usize,
+    },
+    Aggregate {
+        aggregate: AggregateFieldSelection,
+    },
+}
+
 /// IR that represents the selected fields of an output type.
 #[derive(Debug, Serialize, Default, PartialEq, Clone, Eq)]
 pub struct AggregateSelectionSet {
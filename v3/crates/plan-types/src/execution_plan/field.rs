@@ -6,6 +6,10 @@ use std::collections::BTreeMap;
 
 use super::{arguments, query};
 
+/// The alias of the field selected inside each nested object that a relationship with nested
+/// source fields descends into, leading to the relationship itself
+pub const NESTED_RELATIONSHIP_FIELD_ALIAS: &str = "__relationship";
+
 /// Field plan
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Field {
@@ -24,6 +28,9 @@ pub enum Field {
         relationship: NdcRelationshipName,
         /// Values to be provided to any collection arguments
         arguments: BTreeMap<DataConnectorArgumentName, arguments::Argument>,
+        /// The nested object fields to descend into before following the relationship, when its
+        /// source fields are nested inside an object
+        source_field_path: Vec<DataConnectorColumnName>,
     },
 }
 
@@ -1,8 +1,9 @@
 use crate::{LocalFieldComparison, NdcRelationshipName, RemotePredicateKey};
 use open_dds::data_connector::DataConnectorColumnName;
+use serde::Serialize;
 
 /// Filter expression plan to be resolved
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Serialize, Clone, PartialEq, Eq, Hash)]
 pub enum ResolvedFilterExpression {
     And {
         expressions: Vec<ResolvedFilterExpression>,
@@ -5,6 +5,7 @@ use crate::{
     AggregateFieldSelection, NdcRelationshipName, QueryExecutionTree, RelationshipColumnMapping,
     VariableName,
 };
+use hasura_authn_core::Session;
 use indexmap::IndexMap;
 use metadata_resolve::Qualified;
 use open_dds::{
@@ -12,6 +13,7 @@ use open_dds::{
 };
 use std::collections::BTreeMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 #[derive(Debug, Clone, PartialEq)]
 // this represents an execution plan. all predicates only refer to local comparisons.
@@ -29,6 +31,43 @@ pub struct QueryExecutionPlan {
     pub variables: Option<Vec<BTreeMap<VariableName, serde_json::Value>>>,
     /// The data connector used to fetch the data
     pub data_connector: Arc<metadata_resolve::DataConnectorLink>,
+    /// How the response of the data connector is cached, if at all
+    pub response_cache: Option<ResponseCache>,
+}
+
+/// Caching of the response of a data connector to a query
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResponseCache {
+    /// How long the response is cached for
+    pub ttl: Duration,
+    /// The session of the request, if cached responses are only reused within the same session
+    pub session: Option<Session>,
+}
+
+impl ResponseCache {
+    pub fn new(policy: &metadata_resolve::ResponseCachePolicy, session: &Session) -> Self {
+        Self {
+            ttl: policy.ttl,
+            session: policy.include_session_variables.then(|| session.clone()),
+        }
+    }
+
+    /// Restricts the caching to the policy of another model or command whose data is part of
+    /// the response: the shorter of the TTLs applies, and the session is part of the cache key
+    /// if either policy includes it.
+    #[must_use]
+    pub fn restrict(
+        self,
+        policy: &metadata_resolve::ResponseCachePolicy,
+        session: &Session,
+    ) -> Self {
+        Self {
+            ttl: self.ttl.min(policy.ttl),
+            session: self
+                .session
+                .or_else(|| policy.include_session_variables.then(|| session.clone())),
+        }
+    }
 }
 
 /// A tree of queries that are used to execute remote predicates
@@ -64,7 +103,9 @@ impl Default for PredicateQueryTrees {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, derive_more::Display, Ord, Hash, Clone, Copy)]
+#[derive(
+    Debug, PartialEq, Eq, PartialOrd, derive_more::Display, Ord, Hash, Clone, Copy, serde::Serialize,
+)]
 pub struct RemotePredicateKey(pub u64);
 
 // we need to generate unique identifiers for remote predicates
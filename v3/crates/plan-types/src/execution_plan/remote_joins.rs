@@ -133,8 +133,9 @@ pub struct RemoteJoin {
     pub remote_join_type: RemoteJoinType,
 }
 
-/// Name of the source field used in the join mapping
-pub type SourceFieldName = FieldName;
+/// The source field used in the join mapping, along with any fields descended into
+/// through its nested objects
+pub type SourceFieldName = metadata_resolve::RelationshipFieldAccess;
 
 /// Alias of the source field used in the join mapping. This is basically a NDC
 /// field alias (which in the NDC IR is `String`). Change this when modifying
@@ -149,7 +150,11 @@ pub struct RemoteJoinFieldMapping {
     /// include the join mapping field and call it a "phantom field"
     pub source_field_alias: SourceFieldAlias,
 
-    /// The type of the source field
+    /// The NDC fields to descend into through the nested objects of the source field
+    /// to find the value to join on. Empty when the source field is joined on directly.
+    pub source_field_path: Vec<DataConnectorColumnName>,
+
+    /// The type of the value joined on, at the end of `source_field_path`
     pub source_field_type: QualifiedTypeReference,
 
     /// The target NDC field. This could be a model field or an argument name.
@@ -179,9 +184,12 @@ impl TargetField {
         // prefixed so that fields and arguments with the same names do not conflict
         // if they are both used at the same time.
         match self {
-            TargetField::ModelField(_, ndc_column) => {
-                VariableName(format!("$field_{}", ndc_column.column))
-            }
+            TargetField::ModelField(_, ndc_column) => VariableName(
+                std::iter::once(format!("$field_{}", ndc_column.column))
+                    .chain(ndc_column.field_path.iter().map(ToString::to_string))
+                    .collect::<Vec<_>>()
+                    .join("."),
+            ),
             TargetField::Argument(argument_name) => mk_argument_target_variable_name(argument_name),
         }
     }
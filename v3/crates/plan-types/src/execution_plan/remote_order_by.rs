@@ -0,0 +1,69 @@
+//! Ordering that the engine applies to the rows of a query, used when ordering by the fields of
+//! remote relationships, which the data connector of the query cannot order by itself.
+use super::remote_joins::JoinLocations;
+use crate::{NdcFieldAlias, OrderByDirection};
+
+/// The rows of the query are fetched without any limit or offset, the fields of the remote
+/// relationships being ordered by are joined onto each row, and then the rows are sorted and
+/// paginated by the engine. The data connector only orders the rows by the elements that come
+/// before the first remote relationship.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteOrderBy {
+    /// The elements to order by, in order of precedence
+    pub elements: Vec<RemoteOrderByElement>,
+    /// The number of leading elements that the data connector orders the rows by. The engine
+    /// only orders rows that are equal in these elements.
+    pub connector_ordered_elements: usize,
+    /// Remote joins that fetch the fields of the remote relationships being ordered by
+    pub remote_join_executions: JoinLocations,
+    /// Optionally limit to N results, once sorted
+    pub limit: Option<u32>,
+    /// Optionally offset from the Nth result, once sorted
+    pub offset: Option<u32>,
+    /// The maximum number of rows that can be fetched to be sorted. The query fails if there are
+    /// more rows than this.
+    pub row_limit: u32,
+    /// The number of rows fetched from the data connector. This is one more than the row limit,
+    /// so that we can tell when there are too many rows to sort, unless the data connector orders
+    /// the rows by some elements and the page ends before the row limit, in which case it is one
+    /// more than the end of the page, so that we can tell whether the rows that are equal in
+    /// those elements continue past it.
+    pub fetch_limit: u32,
+}
+
+impl RemoteOrderBy {
+    /// Whether fewer rows are fetched than the row limit allows, because the page of rows ends
+    /// before it
+    pub fn fetches_page_only(&self) -> bool {
+        self.fetch_limit <= self.row_limit
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteOrderByElement {
+    pub order_direction: OrderByDirection,
+    pub target: RemoteOrderByTarget,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RemoteOrderByTarget {
+    /// A field selected from each row only to be ordered by. The path starts with the alias of the
+    /// field in the row and continues through the fields of its nested objects.
+    Field { field_path: Vec<NdcFieldAlias> },
+    /// A field of the object that a remote relationship joins each row to. The rows returned by
+    /// the join are found under `join_alias`, and the path is followed within the first of them.
+    RemoteRelationship {
+        join_alias: NdcFieldAlias,
+        field_path: Vec<NdcFieldAlias>,
+    },
+}
+
+impl RemoteOrderByTarget {
+    /// The field of each row that only exists to be ordered by, and is removed once sorted
+    pub fn row_field_alias(&self) -> Option<&NdcFieldAlias> {
+        match self {
+            RemoteOrderByTarget::Field { field_path } => field_path.first(),
+            RemoteOrderByTarget::RemoteRelationship { join_alias, .. } => Some(join_alias),
+        }
+    }
+}
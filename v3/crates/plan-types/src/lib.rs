@@ -11,14 +11,15 @@ mod variable_name;
 pub use execution_plan::{
     mk_argument_target_variable_name, AggregateFieldSelection, AggregateFieldsSelection,
     AggregateSelectionSet, Argument, CommandReturnKind, Dimension, Field, FieldsSelection,
-    Grouping, JoinLocations, JoinNode, Location, LocationKind, MutationArgument,
-    MutationExecutionPlan, MutationExecutionTree, NDCMutationExecution, NDCQueryExecution,
-    NDCSubscriptionExecution, NestedArray, NestedField, NestedObject, PredicateQueryTree,
-    PredicateQueryTrees, ProcessResponseAs, QueryExecutionPlan, QueryExecutionTree, QueryNode,
-    Relationship, RelationshipArgument, RemoteJoin, RemoteJoinFieldMapping,
-    RemoteJoinObjectFieldMapping, RemoteJoinObjectTargetField, RemoteJoinType, RemoteJoinVariable,
-    RemoteJoinVariableSet, RemotePredicateKey, ResolvedFilterExpression, SourceFieldAlias,
-    TargetField, UniqueNumber,
+    GroupExpression, GroupOrderByElement, GroupOrderByTarget, Grouping, JoinLocations, JoinNode,
+    Location, LocationKind, MutationArgument, MutationExecutionPlan, MutationExecutionTree,
+    NDCMutationExecution, NDCQueryExecution, NDCSubscriptionExecution, NestedArray, NestedField,
+    NestedObject, PredicateQueryTree, PredicateQueryTrees, ProcessResponseAs, QueryExecutionPlan,
+    QueryExecutionTree, QueryNode, Relationship, RelationshipArgument, RemoteJoin,
+    RemoteJoinFieldMapping, RemoteJoinObjectFieldMapping, RemoteJoinObjectTargetField,
+    RemoteJoinType, RemoteJoinVariable, RemoteJoinVariableSet, RemoteOrderBy, RemoteOrderByElement,
+    RemoteOrderByTarget, RemotePredicateKey, ResolvedFilterExpression, ResponseCache,
+    SourceFieldAlias, SourceFieldName, TargetField, UniqueNumber, NESTED_RELATIONSHIP_FIELD_ALIAS,
 };
 pub use expression::{
     ComparisonTarget, ComparisonValue, Expression, LocalFieldComparison, RelationshipColumnMapping,
@@ -27,7 +27,7 @@ pub struct LocalModelRelationshipInfo<'s> {
     pub mappings: &'s Vec<metadata_resolve::RelationshipModelMapping>,
 }
 
-#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct RelationshipPathElement<TExpression> {
     pub field_path: Vec<DataConnectorColumnName>,
     pub relationship_name: NdcRelationshipName,
@@ -15,11 +15,14 @@ pub use order_by::to_resolved_order_by_element;
 pub use query::{
     build_relationship_comparison_expression, collect_remote_join_object_type_field_mappings,
     from_command, from_model_aggregate_selection, from_model_group_by, from_model_selection,
-    get_relationship_field_mapping_of_field_name, plan_expression, plan_query_request,
-    process_argument_presets_for_command, process_argument_presets_for_model,
-    process_command_relationship_definition, process_model_predicate,
-    process_model_relationship_definition, query_to_plan, ArgumentPresetExecutionError,
-    CommandPlan, ExecutionPlan, FromCommand, RelationshipFieldMappingError,
-    SingleNodeExecutionPlan, UnresolvedArgument,
+    get_relationship_field_mapping_of_field_name, get_relationship_source_nested_field_path,
+    plan_expression, plan_query_request, plan_response_cache, process_argument_presets_for_command,
+    process_argument_presets_for_model, process_command_relationship_definition,
+    process_command_relationship_definition_with_source_path,
+    process_command_relationship_value_mappings, process_model_predicate,
+    process_model_relationship_definition, process_model_relationship_definition_with_source_path,
+    process_model_relationship_value_mappings, query_to_plan, ArgumentPresetExecutionError,
+    CommandPlan, ExecutionPlan, FromCommand, ModelRelationshipValueConstraints,
+    RelationshipFieldMappingError, SingleNodeExecutionPlan, UnresolvedArgument,
 };
 pub use types::{PermissionError, PlanError};
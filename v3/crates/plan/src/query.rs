@@ -6,6 +6,8 @@ mod model;
 pub mod model_target;
 mod permissions;
 mod relationships;
+mod remote_order_by;
+mod response_cache;
 mod types;
 use crate::types::PlanError;
 pub use arguments::{
@@ -19,9 +21,14 @@ pub use model::{from_model_aggregate_selection, from_model_group_by, from_model_
 pub use permissions::process_model_predicate;
 pub use relationships::{
     collect_remote_join_object_type_field_mappings, get_relationship_field_mapping_of_field_name,
-    process_command_relationship_definition, process_model_relationship_definition,
+    get_relationship_source_nested_field_path, process_command_relationship_definition,
+    process_command_relationship_definition_with_source_path,
+    process_command_relationship_value_mappings, process_model_relationship_definition,
+    process_model_relationship_definition_with_source_path,
+    process_model_relationship_value_mappings, ModelRelationshipValueConstraints,
     RelationshipFieldMappingError,
 };
+pub use response_cache::plan_response_cache;
 
 use hasura_authn_core::Session;
 use metadata_resolve::Metadata;
@@ -130,6 +137,8 @@ where
             Ok(SingleNodeExecutionPlan::Query(execution_tree))
         }
         open_dds::query::Query::ModelGroups(model_groups) => {
+            // usage counts of GraphQL requests are collected when their IR is generated
+            let mut usage_counts = plan_types::UsagesCounts::default();
             let execution_tree = model::from_model_group_by(
                 &model_groups.target,
                 &model_groups.selection,
@@ -138,6 +147,7 @@ where
                 session,
                 request_headers,
                 unique_number,
+                &mut usage_counts,
             )?;
 
             Ok(SingleNodeExecutionPlan::Query(execution_tree))
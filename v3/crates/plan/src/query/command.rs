@@ -214,6 +214,7 @@ pub(crate) fn from_command_selection(
             CommandPlan::Function(QueryExecutionTree {
                 remote_predicates,
                 remote_join_executions,
+                remote_order_by: None,
                 query_execution_plan: QueryExecutionPlan {
                     query_node: QueryNode {
                         fields: Some(plan_types::FieldsSelection {
@@ -234,6 +235,13 @@ pub(crate) fn from_command_selection(
                     collection_relationships: relationships.clone(),
                     variables: None,
                     data_connector: command_source.data_connector.clone(),
+                    response_cache: super::plan_response_cache(
+                        metadata,
+                        session,
+                        &command_source.data_connector,
+                        command_source.response_cache.as_ref(),
+                        &relationships,
+                    ),
                 },
             })
         }
@@ -1,28 +1,31 @@
 use super::{
     relationships::{
+        apply_command_relationship_value_mappings, apply_model_relationship_value_mappings,
         calculate_remote_relationship_fields_for_command_target,
         calculate_remote_relationship_fields_for_model_target,
-        process_command_relationship_definition, process_model_relationship_definition,
-        CommandRemoteRelationshipParts, ModelRemoteRelationshipParts,
+        process_command_relationship_definition_with_source_path,
+        process_model_relationship_definition_with_source_path, CommandRemoteRelationshipParts,
+        ModelRemoteRelationshipParts,
     },
     CommandPlan,
 };
 use crate::metadata_accessor::OutputObjectTypeView;
-use crate::types::{PlanError, RelationshipError};
+use crate::types::{OrderByError, PlanError, RelationshipError};
 use hasura_authn_core::Session;
 use indexmap::IndexMap;
 use metadata_resolve::{
-    FieldNestedness, Metadata, Qualified, QualifiedBaseType, QualifiedTypeReference,
-    RelationshipExecutionCategory, TypeMapping,
+    FieldNestedness, Metadata, ObjectTypeWithRelationships, Qualified, QualifiedBaseType,
+    QualifiedTypeReference, RelationshipExecutionCategory, TypeMapping,
 };
 use open_dds::{
     arguments::ArgumentName,
     commands::DataConnectorCommand,
+    data_connector::DataConnectorColumnName,
     models::ModelName,
     query::{
         Alias, CommandSelection, CommandTarget, ModelSelection, ModelTarget, ObjectFieldSelection,
         ObjectFieldTarget, ObjectSubSelection, RelationshipAggregateSelection,
-        RelationshipSelection, RelationshipTarget, Value,
+        RelationshipGroupsSelection, RelationshipSelection, RelationshipTarget, Value,
     },
     relationships::RelationshipName,
     types::{CustomTypeName, DataConnectorArgumentName, FieldName},
@@ -30,8 +33,8 @@ use open_dds::{
 use plan_types::{
     CommandReturnKind, Field, JoinLocations, JoinNode, Location, LocationKind, NdcFieldAlias,
     NestedArray, NestedField, NestedObject, PredicateQueryTrees, ProcessResponseAs,
-    QueryExecutionPlan, QueryExecutionTree, RemoteJoin, RemoteJoinType, ResolvedFilterExpression,
-    UniqueNumber,
+    QueryExecutionPlan, QueryExecutionTree, RemoteJoin, RemoteJoinType, RemoteOrderBy,
+    ResolvedFilterExpression, UniqueNumber,
 };
 use std::collections::BTreeMap;
 
@@ -119,6 +122,24 @@ pub fn resolve_field_selection(
                     unique_number,
                 )?;
             }
+            ObjectSubSelection::RelationshipGroups(relationship_groups_selection) => {
+                from_relationship_groups_selection(
+                    relationship_groups_selection,
+                    metadata,
+                    session,
+                    request_headers,
+                    object_type,
+                    type_mappings,
+                    data_connector,
+                    relationship_field_nestedness,
+                    NdcFieldAlias::from(field_alias.as_str()),
+                    &mut ndc_fields,
+                    relationships,
+                    remote_join_executions,
+                    remote_predicates,
+                    unique_number,
+                )?;
+            }
         }
     }
     Ok(ndc_fields)
@@ -448,17 +469,32 @@ fn from_model_relationship(
         &target_source.capabilities,
     ) {
         metadata_resolve::RelationshipExecutionCategory::RemoteForEach => {
-            let QueryExecutionTree {
-                query_execution_plan: mut query_execution,
-                remote_join_executions: sub_join_locations,
-                remote_predicates: new_remote_predicates,
-            } = super::model::from_model_selection(
+            let mut query_execution_tree = super::model::from_model_selection(
                 &relationship_target_model_selection,
                 metadata,
                 session,
                 request_headers,
                 unique_number,
             )?;
+            apply_model_relationship_value_mappings(
+                &mut query_execution_tree,
+                relationship_name,
+                object_type.object_type_name,
+                target_model_name,
+                target_model_source,
+                &model_relationship_target.value_mappings,
+                &metadata.object_types,
+                &session.variables,
+                unique_number,
+            )?;
+
+            let QueryExecutionTree {
+                query_execution_plan: mut query_execution,
+                remote_join_executions: sub_join_locations,
+                remote_predicates: new_remote_predicates,
+                remote_order_by,
+            } = query_execution_tree;
+            reject_remote_order_by(relationship_name, remote_order_by.as_ref())?;
 
             let ModelRemoteRelationshipParts {
                 join_mapping,
@@ -521,7 +557,7 @@ fn from_model_relationship(
         }
         metadata_resolve::RelationshipExecutionCategory::Local => {
             // Collect this local relationship
-            let ndc_relationship_name = record_local_model_relationship(
+            let (ndc_relationship_name, source_field_path) = record_local_model_relationship(
                 object_type.object_type_name,
                 relationship_name,
                 target_model_name,
@@ -529,9 +565,29 @@ fn from_model_relationship(
                 source_type_mappings,
                 source_data_connector,
                 model_relationship_target,
+                &metadata.object_types,
                 collect_relationships,
             )?;
 
+            let mut query_execution_tree = super::model::from_model_selection(
+                &relationship_target_model_selection,
+                metadata,
+                session,
+                request_headers,
+                unique_number,
+            )?;
+            apply_model_relationship_value_mappings(
+                &mut query_execution_tree,
+                relationship_name,
+                object_type.object_type_name,
+                target_model_name,
+                target_model_source,
+                &model_relationship_target.value_mappings,
+                &metadata.object_types,
+                &session.variables,
+                unique_number,
+            )?;
+
             let QueryExecutionTree {
                 query_execution_plan:
                     QueryExecutionPlan {
@@ -541,16 +597,13 @@ fn from_model_relationship(
                         collection_relationships: mut ndc_relationships,
                         variables: _,
                         data_connector: _,
+                        response_cache: _,
                     },
                 remote_predicates: new_remote_predicates,
                 remote_join_executions: new_remote_join_executions,
-            } = super::model::from_model_selection(
-                &relationship_target_model_selection,
-                metadata,
-                session,
-                request_headers,
-                unique_number,
-            )?;
+                remote_order_by,
+            } = query_execution_tree;
+            reject_remote_order_by(relationship_name, remote_order_by.as_ref())?;
 
             // Collect relationships from the generated query above
             collect_relationships.append(&mut ndc_relationships);
@@ -575,6 +628,7 @@ fn from_model_relationship(
                     relationship: ndc_relationship_name,
                     arguments: ndc_arguments,
                     query_node: Box::new(query_node),
+                    source_field_path,
                 },
             );
 
@@ -658,7 +712,7 @@ fn from_command_relationship(
         selection: selection.clone(),
     };
 
-    let from_command = super::command::from_command_selection(
+    let mut from_command = super::command::from_command_selection(
         &command_selection,
         metadata,
         session,
@@ -669,6 +723,20 @@ fn from_command_relationship(
         unique_number,
     )?;
 
+    if let CommandPlan::Function(query_execution_tree) = &mut from_command.command_plan {
+        apply_command_relationship_value_mappings(
+            query_execution_tree,
+            relationship_name,
+            object_type.object_type_name,
+            command_name,
+            command_source,
+            &command_relationship_target.value_mappings,
+            &metadata.object_types,
+            &session.variables,
+            unique_number,
+        )?;
+    }
+
     // is it local or remote?
     match metadata_resolve::field_selection_relationship_execution_category(
         relationship_field_nestedness,
@@ -700,6 +768,7 @@ fn from_command_relationship(
                 mut query_execution_plan,
                 remote_predicates: new_remote_predicates,
                 remote_join_executions: new_remote_join_executions,
+                remote_order_by: _,
             } = match from_command.command_plan {
                 CommandPlan::Function(execution_tree) => execution_tree,
                 CommandPlan::Procedure(_ndc_procedure) => {
@@ -763,10 +832,12 @@ fn from_command_relationship(
                 relationship_name,
             );
 
-            collect_relationships.insert(
-                ndc_relationship_name.clone(),
-                process_command_relationship_definition(&local_command_relationship_info)?,
-            );
+            let (source_field_path, relationship) =
+                process_command_relationship_definition_with_source_path(
+                    &local_command_relationship_info,
+                    &metadata.object_types,
+                )?;
+            collect_relationships.insert(ndc_relationship_name.clone(), relationship);
 
             let QueryExecutionTree {
                 query_execution_plan:
@@ -777,9 +848,11 @@ fn from_command_relationship(
                         collection_relationships: mut ndc_relationships,
                         variables: _,
                         data_connector: _,
+                        response_cache: _,
                     },
                 remote_predicates: new_remote_predicates,
                 remote_join_executions: new_remote_join_executions,
+                remote_order_by: _,
             } = match from_command.command_plan {
                 CommandPlan::Function(execution_tree) => execution_tree,
                 CommandPlan::Procedure(_ndc_procedure) => {
@@ -813,6 +886,7 @@ fn from_command_relationship(
                     relationship: ndc_relationship_name,
                     arguments: ndc_arguments,
                     query_node: Box::new(query_node),
+                    source_field_path,
                 },
             );
 
@@ -829,8 +903,15 @@ fn record_local_model_relationship(
     source_type_mappings: &BTreeMap<Qualified<CustomTypeName>, TypeMapping>,
     source_data_connector: &metadata_resolve::DataConnectorLink,
     model_relationship_target: &metadata_resolve::ModelRelationshipTarget,
+    object_types: &BTreeMap<Qualified<CustomTypeName>, ObjectTypeWithRelationships>,
     collect_relationships: &mut BTreeMap<plan_types::NdcRelationshipName, plan_types::Relationship>,
-) -> Result<plan_types::NdcRelationshipName, PlanError> {
+) -> Result<
+    (
+        plan_types::NdcRelationshipName,
+        Vec<DataConnectorColumnName>,
+    ),
+    PlanError,
+> {
     let local_model_relationship_info = plan_types::LocalModelRelationshipInfo {
         relationship_name,
         relationship_type: &model_relationship_target.relationship_type,
@@ -846,11 +927,12 @@ fn record_local_model_relationship(
     let ndc_relationship_name =
         plan_types::NdcRelationshipName::new(object_type_name, relationship_name);
 
-    collect_relationships.insert(
-        ndc_relationship_name.clone(),
-        process_model_relationship_definition(&local_model_relationship_info)?,
-    );
-    Ok(ndc_relationship_name)
+    let (source_field_path, relationship) = process_model_relationship_definition_with_source_path(
+        &local_model_relationship_info,
+        object_types,
+    )?;
+    collect_relationships.insert(ndc_relationship_name.clone(), relationship);
+    Ok((ndc_relationship_name, source_field_path))
 }
 
 pub fn reject_remote_relationship(
@@ -866,6 +948,21 @@ pub fn reject_remote_relationship(
     Ok(())
 }
 
+// The rows of a relationship are fetched for many parent rows at once, so the engine cannot
+// sort them by the fields of further remote relationships
+fn reject_remote_order_by(
+    relationship_name: &RelationshipName,
+    remote_order_by: Option<&RemoteOrderBy>,
+) -> Result<(), PlanError> {
+    if remote_order_by.is_some() {
+        return Err(OrderByError::RemoteRelationshipNotSupported(format!(
+            "The relationship {relationship_name} cannot be ordered by fields of remote relationships"
+        ))
+        .into_plan_error());
+    }
+    Ok(())
+}
+
 /// Resolve a relationship field
 fn from_relationship_aggregate_selection(
     relationship_aggregate_selection: &RelationshipAggregateSelection,
@@ -942,11 +1039,7 @@ fn from_relationship_aggregate_selection(
                 target_capabilities,
             ) {
                 RelationshipExecutionCategory::RemoteForEach => {
-                    let QueryExecutionTree {
-                        query_execution_plan: mut query_execution,
-                        remote_join_executions: sub_join_locations,
-                        remote_predicates: new_remote_predicates,
-                    } = super::model::from_model_aggregate_selection(
+                    let mut query_execution_tree = super::model::from_model_aggregate_selection(
                         &relationship_model_target,
                         selection,
                         metadata,
@@ -955,6 +1048,24 @@ fn from_relationship_aggregate_selection(
                         request_headers,
                         unique_number,
                     )?;
+                    apply_model_relationship_value_mappings(
+                        &mut query_execution_tree,
+                        relationship_name,
+                        object_type.object_type_name,
+                        target_model_name,
+                        target_model_source,
+                        &model_relationship_target.value_mappings,
+                        &metadata.object_types,
+                        &session.variables,
+                        unique_number,
+                    )?;
+
+                    let QueryExecutionTree {
+                        query_execution_plan: mut query_execution,
+                        remote_join_executions: sub_join_locations,
+                        remote_predicates: new_remote_predicates,
+                        remote_order_by: _,
+                    } = query_execution_tree;
 
                     let ModelRemoteRelationshipParts {
                         join_mapping,
@@ -1035,10 +1146,33 @@ fn from_relationship_aggregate_selection(
                     );
 
                     // Record this relationship
-                    collect_relationships.insert(
-                        ndc_relationship_name.clone(),
-                        process_model_relationship_definition(&local_model_relationship_info)?,
-                    );
+                    let (source_field_path, relationship) =
+                        process_model_relationship_definition_with_source_path(
+                            &local_model_relationship_info,
+                            &metadata.object_types,
+                        )?;
+                    collect_relationships.insert(ndc_relationship_name.clone(), relationship);
+
+                    let mut query_execution_tree = super::model::from_model_aggregate_selection(
+                        &relationship_model_target,
+                        selection,
+                        metadata,
+                        session,
+                        relationship_aggregate_expression,
+                        request_headers,
+                        unique_number,
+                    )?;
+                    apply_model_relationship_value_mappings(
+                        &mut query_execution_tree,
+                        relationship_name,
+                        object_type.object_type_name,
+                        target_model_name,
+                        target_model_source,
+                        &model_relationship_target.value_mappings,
+                        &metadata.object_types,
+                        &session.variables,
+                        unique_number,
+                    )?;
 
                     let QueryExecutionTree {
                         query_execution_plan:
@@ -1049,19 +1183,178 @@ fn from_relationship_aggregate_selection(
                                 collection_relationships: mut ndc_relationships,
                                 variables: _,
                                 data_connector: _,
+                                response_cache: _,
                             },
                         remote_join_executions: new_remote_join_executions,
                         remote_predicates: new_remote_predicates,
-                    } = super::model::from_model_aggregate_selection(
+                        remote_order_by: _,
+                    } = query_execution_tree;
+
+                    // Collect relationships from the generated query above
+                    collect_relationships.append(&mut ndc_relationships);
+                    remote_join_executions
+                        .locations
+                        .extend(new_remote_join_executions.locations);
+                    remote_predicates.0.extend(new_remote_predicates.0);
+
+                    ndc_fields.insert(
+                        ndc_field_alias,
+                        Field::Relationship {
+                            relationship: ndc_relationship_name,
+                            arguments: ndc_arguments,
+                            query_node: Box::new(query_node),
+                            source_field_path,
+                        },
+                    );
+
+                    Ok(())
+                }
+            }
+        }
+    }
+}
+
+fn from_relationship_groups_selection(
+    relationship_groups_selection: &RelationshipGroupsSelection,
+    metadata: &Metadata,
+    session: &Session,
+    request_headers: &reqwest::header::HeaderMap,
+    object_type: &OutputObjectTypeView,
+    source_type_mappings: &BTreeMap<Qualified<CustomTypeName>, TypeMapping>,
+    source_data_connector: &metadata_resolve::DataConnectorLink,
+    relationship_field_nestedness: metadata_resolve::FieldNestedness,
+    ndc_field_alias: NdcFieldAlias,
+    ndc_fields: &mut IndexMap<NdcFieldAlias, Field>,
+    collect_relationships: &mut BTreeMap<plan_types::NdcRelationshipName, plan_types::Relationship>,
+    remote_join_executions: &mut JoinLocations,
+    remote_predicates: &mut PredicateQueryTrees,
+    unique_number: &mut UniqueNumber,
+) -> Result<(), PlanError> {
+    let RelationshipGroupsSelection {
+        target,
+        selection,
+        dimensions,
+    } = relationship_groups_selection;
+    let RelationshipTarget {
+        relationship_name,
+        arguments,
+        filter,
+        order_by,
+        limit,
+        offset,
+    } = target;
+    let relationship_field = get_relationship_field(object_type, relationship_name)?;
+    match &relationship_field.target {
+        metadata_resolve::RelationshipTarget::Command(_) => Err(PlanError::Internal(format!(
+            "Command relationships are not supported in groups fields: {relationship_name}"
+        ))),
+        metadata_resolve::RelationshipTarget::Model(model_relationship_target) => {
+            let target_model_name = &model_relationship_target.model_name;
+            let target_model = metadata.models.get(target_model_name).ok_or_else(|| {
+                PlanError::Internal(format!("model {target_model_name} not found in metadata"))
+            })?;
+
+            let target_model_source = target_model.model.source.as_deref().ok_or_else(|| {
+                PlanError::Internal(format!("model {target_model_name} has no source"))
+            })?;
+
+            let target_capabilities =
+                relationship_field
+                    .target_capabilities
+                    .as_ref()
+                    .ok_or_else(|| {
+                        PlanError::Relationship(RelationshipError::Other(format!(
+                    "Relationship capabilities not found for relationship {} in data connector {}",
+                    relationship_name, &target_model_source.data_connector.name,
+                )))
+                    })?;
+
+            let relationship_model_target = ModelTarget {
+                subgraph: target_model_name.subgraph.clone(),
+                model_name: target_model_name.name.clone(),
+                arguments: arguments.clone(),
+                filter: filter.clone(),
+                order_by: order_by.clone(),
+                limit: *limit,
+                offset: *offset,
+            };
+
+            // is it local or remote?
+            match metadata_resolve::field_selection_relationship_execution_category(
+                relationship_field_nestedness,
+                source_data_connector,
+                &target_model_source.data_connector,
+                target_capabilities,
+            ) {
+                RelationshipExecutionCategory::RemoteForEach => {
+                    Err(PlanError::Relationship(RelationshipError::Other(format!(
+                        "Grouping the objects of the remote relationship {relationship_name} is not supported"
+                    ))))
+                }
+                RelationshipExecutionCategory::Local => {
+                    let local_model_relationship_info = plan_types::LocalModelRelationshipInfo {
+                        relationship_name,
+                        relationship_type: &model_relationship_target.relationship_type,
+                        source_type: object_type.object_type_name,
+                        source_data_connector,
+                        source_type_mappings,
+                        target_model_name,
+                        target_source: target_model_source,
+                        target_type: &model_relationship_target.target_typename,
+                        mappings: &model_relationship_target.mappings,
+                    };
+
+                    let ndc_relationship_name = plan_types::NdcRelationshipName::new(
+                        object_type.object_type_name,
+                        relationship_name,
+                    );
+
+                    // Record this relationship
+                    let (source_field_path, relationship) =
+                        process_model_relationship_definition_with_source_path(
+                            &local_model_relationship_info,
+                            &metadata.object_types,
+                        )?;
+                    collect_relationships.insert(ndc_relationship_name.clone(), relationship);
+
+                    let mut query_execution_tree = super::model::from_model_group_by(
                         &relationship_model_target,
                         selection,
+                        dimensions,
                         metadata,
                         session,
-                        relationship_aggregate_expression,
                         request_headers,
                         unique_number,
+                        &mut plan_types::UsagesCounts::default(),
+                    )?;
+                    apply_model_relationship_value_mappings(
+                        &mut query_execution_tree,
+                        relationship_name,
+                        object_type.object_type_name,
+                        target_model_name,
+                        target_model_source,
+                        &model_relationship_target.value_mappings,
+                        &metadata.object_types,
+                        &session.variables,
+                        unique_number,
                     )?;
 
+                    let QueryExecutionTree {
+                        query_execution_plan:
+                            QueryExecutionPlan {
+                                query_node,
+                                collection: _,
+                                arguments: ndc_arguments,
+                                collection_relationships: mut ndc_relationships,
+                                variables: _,
+                                data_connector: _,
+                                response_cache: _,
+                            },
+                        remote_join_executions: new_remote_join_executions,
+                        remote_predicates: new_remote_predicates,
+                        remote_order_by: _,
+                    } = query_execution_tree;
+
                     // Collect relationships from the generated query above
                     collect_relationships.append(&mut ndc_relationships);
                     remote_join_executions
@@ -1075,6 +1368,7 @@ fn from_relationship_aggregate_selection(
                             relationship: ndc_relationship_name,
                             arguments: ndc_arguments,
                             query_node: Box::new(query_node),
+                            source_field_path,
                         },
                     );
 
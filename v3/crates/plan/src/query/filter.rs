@@ -133,6 +133,8 @@ pub fn plan_expression<'a>(
                 collection_relationships,
                 variables: None,
                 data_connector: target_model_source.data_connector.clone(),
+                // remote predicates are only planned from within other queries
+                response_cache: None,
             };
 
             let predicate_query_tree = PredicateQueryTree {
@@ -142,6 +144,7 @@ pub fn plan_expression<'a>(
                     query_execution_plan,
                     remote_predicates: PredicateQueryTrees::new(),
                     remote_join_executions: JoinLocations::new(),
+                    remote_order_by: None,
                 },
                 children: rest_predicate_trees,
             };
@@ -1,9 +1,13 @@
-use super::{field_selection, model_target};
+use super::{field_selection, model_target, remote_order_by};
 
-use crate::types::PlanError;
-use crate::{column::to_resolved_column, OutputObjectTypeView};
+use crate::types::{GroupByError, PlanError};
+use crate::{
+    column::{to_resolved_column, ResolvedColumn},
+    OutputObjectTypeView,
+};
 use indexmap::IndexMap;
 use nonempty::NonEmpty;
+use open_dds::relationships::RelationshipType;
 use open_dds::types::CustomTypeName;
 use open_dds::{
     aggregates::{
@@ -11,18 +15,25 @@ use open_dds::{
     },
     models::ModelName,
 };
+use std::borrow::Cow;
 use std::collections::BTreeMap;
 
 use hasura_authn_core::Session;
-use metadata_resolve::{FieldMapping, Metadata, NdcVersion, Qualified};
+use metadata_resolve::{
+    ComparisonOperators, DataConnectorGroupingCapabilities, FieldMapping, Metadata, NdcVersion,
+    Qualified, RelationshipTarget, TypeMapping,
+};
+use open_dds::data_connector::DataConnectorName;
 use open_dds::query::{
-    Aggregate, AggregationFunction, ExtractionFunction, ModelDimensions, ModelSelection,
-    ModelTarget, Name, ObjectFieldOperand, Operand,
+    Aggregate, AggregationFunction, ComparisonOperator, ExtractionFunction, GroupBooleanExpression,
+    GroupOrderByElement, GroupOrderByOperand, ModelDimensions, ModelSelection, ModelTarget, Name,
+    ObjectFieldOperand, Operand, Value,
 };
 use plan_types::{
-    AggregateFieldSelection, AggregateSelectionSet, FieldsSelection, Grouping, JoinLocations,
-    NdcFieldAlias, PredicateQueryTrees, QueryExecutionPlan, QueryExecutionTree, QueryNode,
-    UniqueNumber,
+    AggregateFieldSelection, AggregateSelectionSet, FieldsSelection, GroupExpression,
+    GroupOrderByTarget, Grouping, JoinLocations, NdcFieldAlias, NdcRelationshipName,
+    PredicateQueryTrees, QueryExecutionPlan, QueryExecutionTree, QueryNode,
+    RelationshipPathElement, ResolvedFilterExpression, UniqueNumber, UsagesCounts,
 };
 
 pub fn from_model_group_by(
@@ -33,6 +44,7 @@ pub fn from_model_group_by(
     session: &Session,
     request_headers: &reqwest::header::HeaderMap,
     unique_number: &mut UniqueNumber,
+    usage_counts: &mut UsagesCounts,
 ) -> Result<QueryExecutionTree, PlanError> {
     let mut remote_predicates = PredicateQueryTrees::new();
 
@@ -61,6 +73,9 @@ pub fn from_model_group_by(
     let ndc_version = data_connector.capabilities.supported_ndc_version;
 
     let mut dimensions: IndexMap<NdcFieldAlias, plan_types::Dimension> = IndexMap::new();
+    let mut dimension_relationships = BTreeMap::new();
+
+    crate::model_tracking::count_model(&qualified_model_name, usage_counts);
 
     for (
         field_alias,
@@ -70,128 +85,113 @@ pub fn from_model_group_by(
         },
     ) in &model_dimensions.dimensions
     {
-        let dimension = match operand {
-            Operand::Field(operand) => {
-                let column = to_resolved_column(
-                    &session.role,
-                    metadata,
-                    &model_source.type_mappings,
-                    &model.model.data_type,
-                    &model_object_type,
-                    operand,
-                )?;
-                let field_mapping: FieldMapping = column.field_mapping;
+        let (relationship_path, column) = to_dimension_column(
+            metadata,
+            session,
+            &model_source.type_mappings,
+            &model.model.data_type,
+            &model_object_type,
+            data_connector,
+            operand,
+            vec![],
+            &mut dimension_relationships,
+            &mut remote_predicates,
+            unique_number,
+            usage_counts,
+        )?;
+        let field_mapping: FieldMapping = column.field_mapping;
+        let extraction = match extraction {
+            None => None,
+            // extraction functions are only required when grouping by a part of a value
+            Some(extraction) => Some({
                 let extraction_functions = field_mapping
                     .extraction_functions
                     .ok_or_else(|| PlanError::Internal("no extraction functions".to_string()))?;
-                let extraction = match extraction {
-                    None => None,
-                    Some(extraction) => Some(match extraction {
-                        ExtractionFunction::Year => {
-                            extraction_functions.year_function.ok_or_else(|| {
-                                PlanError::Internal(
-                                    "year extraction function not found".to_string(),
-                                )
-                            })
-                        }
-                        ExtractionFunction::Month => {
-                            extraction_functions.month_function.ok_or_else(|| {
-                                PlanError::Internal(
-                                    "month extraction function not found".to_string(),
-                                )
-                            })
-                        }
-                        ExtractionFunction::Day => {
-                            extraction_functions.day_function.ok_or_else(|| {
-                                PlanError::Internal("day extraction function not found".to_string())
-                            })
-                        }
-                        ExtractionFunction::Nanosecond => {
-                            extraction_functions.nanosecond_function.ok_or_else(|| {
-                                PlanError::Internal(
-                                    "nanosecond extraction function not found".to_string(),
-                                )
-                            })
-                        }
-                        ExtractionFunction::Microsecond => {
-                            extraction_functions.microsecond_function.ok_or_else(|| {
-                                PlanError::Internal(
-                                    "microsecond extraction function not found".to_string(),
-                                )
-                            })
-                        }
-                        ExtractionFunction::Second => {
-                            extraction_functions.second_function.ok_or_else(|| {
-                                PlanError::Internal(
-                                    "second extraction function not found".to_string(),
-                                )
-                            })
-                        }
-                        ExtractionFunction::Minute => {
-                            extraction_functions.minute_function.ok_or_else(|| {
-                                PlanError::Internal(
-                                    "minute extraction function not found".to_string(),
-                                )
-                            })
-                        }
-                        ExtractionFunction::Hour => {
-                            extraction_functions.hour_function.ok_or_else(|| {
-                                PlanError::Internal(
-                                    "hour extraction function not found".to_string(),
-                                )
-                            })
-                        }
-                        ExtractionFunction::Week => {
-                            extraction_functions.week_function.ok_or_else(|| {
-                                PlanError::Internal(
-                                    "week extraction function not found".to_string(),
-                                )
-                            })
-                        }
-                        ExtractionFunction::Quarter => {
-                            extraction_functions.quarter_function.ok_or_else(|| {
-                                PlanError::Internal(
-                                    "quarter extraction function not found".to_string(),
-                                )
-                            })
-                        }
-                        ExtractionFunction::DayOfWeek => {
-                            extraction_functions.day_of_week_function.ok_or_else(|| {
-                                PlanError::Internal(
-                                    "day_of_week extraction function not found".to_string(),
-                                )
-                            })
-                        }
-                        ExtractionFunction::DayOfYear => {
-                            extraction_functions.day_of_year_function.ok_or_else(|| {
-                                PlanError::Internal(
-                                    "day_of_year extraction function not found".to_string(),
-                                )
-                            })
-                        }
-                        ExtractionFunction::Custom { name } => extraction_functions
-                            .other_functions
-                            .into_iter()
-                            .find(|f| f.as_str() == name.as_str())
-                            .ok_or_else(|| {
-                                PlanError::Internal(format!(
-                                    "unsupported extraction function {name:?}"
-                                ))
-                            }),
-                    }?),
-                };
-                Ok(plan_types::Dimension::Column {
-                    column_path: nonempty::NonEmpty {
-                        head: column.column_name,
-                        tail: column.field_path,
-                    },
-                    extraction,
-                })
-            }
-            _ => Err(PlanError::Internal(format!(
-                "Unsupported dimension in from_model_group_by: {operand:?}"
-            ))),
-        }?;
+                match extraction {
+                    ExtractionFunction::Year => {
+                        extraction_functions.year_function.ok_or_else(|| {
+                            PlanError::Internal("year extraction function not found".to_string())
+                        })
+                    }
+                    ExtractionFunction::Month => {
+                        extraction_functions.month_function.ok_or_else(|| {
+                            PlanError::Internal("month extraction function not found".to_string())
+                        })
+                    }
+                    ExtractionFunction::Day => extraction_functions.day_function.ok_or_else(|| {
+                        PlanError::Internal("day extraction function not found".to_string())
+                    }),
+                    ExtractionFunction::Nanosecond => {
+                        extraction_functions.nanosecond_function.ok_or_else(|| {
+                            PlanError::Internal(
+                                "nanosecond extraction function not found".to_string(),
+                            )
+                        })
+                    }
+                    ExtractionFunction::Microsecond => {
+                        extraction_functions.microsecond_function.ok_or_else(|| {
+                            PlanError::Internal(
+                                "microsecond extraction function not found".to_string(),
+                            )
+                        })
+                    }
+                    ExtractionFunction::Second => {
+                        extraction_functions.second_function.ok_or_else(|| {
+                            PlanError::Internal("second extraction function not found".to_string())
+                        })
+                    }
+                    ExtractionFunction::Minute => {
+                        extraction_functions.minute_function.ok_or_else(|| {
+                            PlanError::Internal("minute extraction function not found".to_string())
+                        })
+                    }
+                    ExtractionFunction::Hour => {
+                        extraction_functions.hour_function.ok_or_else(|| {
+                            PlanError::Internal("hour extraction function not found".to_string())
+                        })
+                    }
+                    ExtractionFunction::Week => {
+                        extraction_functions.week_function.ok_or_else(|| {
+                            PlanError::Internal("week extraction function not found".to_string())
+                        })
+                    }
+                    ExtractionFunction::Quarter => {
+                        extraction_functions.quarter_function.ok_or_else(|| {
+                            PlanError::Internal("quarter extraction function not found".to_string())
+                        })
+                    }
+                    ExtractionFunction::DayOfWeek => {
+                        extraction_functions.day_of_week_function.ok_or_else(|| {
+                            PlanError::Internal(
+                                "day_of_week extraction function not found".to_string(),
+                            )
+                        })
+                    }
+                    ExtractionFunction::DayOfYear => {
+                        extraction_functions.day_of_year_function.ok_or_else(|| {
+                            PlanError::Internal(
+                                "day_of_year extraction function not found".to_string(),
+                            )
+                        })
+                    }
+                    ExtractionFunction::Custom { name } => extraction_functions
+                        .other_functions
+                        .into_iter()
+                        .find(|f| f.as_str() == name.as_str())
+                        .ok_or_else(|| {
+                            PlanError::Internal(format!("unsupported extraction function {name:?}"))
+                        }),
+                }?
+            }),
+        };
+        let dimension = plan_types::Dimension::Column {
+            relationship_path,
+            column_path: nonempty::NonEmpty {
+                head: column.column_name,
+                tail: column.field_path,
+            },
+            extraction,
+        };
 
         dimensions.insert(NdcFieldAlias::from(field_alias.as_str()), dimension);
     }
@@ -208,12 +208,98 @@ pub fn from_model_group_by(
             model_source,
             model.model.aggregate_expression.as_ref(),
             aggregate,
-            field_alias,
             ndc_version,
         )?;
         aggregates.insert(NdcFieldAlias::from(field_alias.as_str()), ndc_aggregate);
     }
 
+    let grouping_capabilities = data_connector
+        .capabilities
+        .supports_aggregates
+        .as_ref()
+        .and_then(|capabilities| capabilities.supports_grouping.as_ref());
+
+    let plan_group_aggregate = |aggregate: &Aggregate| {
+        to_ndc_aggregate(
+            metadata,
+            session,
+            &model.model.name,
+            &model.model.data_type,
+            &model_object_type,
+            model_source,
+            model.model.aggregate_expression.as_ref(),
+            aggregate,
+            ndc_version,
+        )
+    };
+
+    let group_predicate = model_dimensions
+        .filter
+        .as_ref()
+        .map(|filter| {
+            let Some(grouping_capabilities) =
+                grouping_capabilities.filter(|capabilities| capabilities.supports_filtering)
+            else {
+                return Err(GroupByError::FilteringNotSupported {
+                    data_connector_name: data_connector.name.clone(),
+                }
+                .into());
+            };
+            let plan_comparison_operators =
+                |aggregate: &Aggregate, aggregate_selection: &AggregateFieldSelection| {
+                    let operand_column = aggregate
+                        .operand
+                        .as_ref()
+                        .map(|operand| {
+                            let field_operand = extract_field_operand_for_aggregation(operand)?;
+                            to_resolved_column(
+                                &session.role,
+                                metadata,
+                                &model_source.type_mappings,
+                                &model.model.data_type,
+                                &model_object_type,
+                                &field_operand,
+                            )
+                        })
+                        .transpose()?;
+                    group_aggregate_comparison_operators(
+                        grouping_capabilities,
+                        &data_connector.name,
+                        aggregate_selection,
+                        operand_column.as_ref().map(|column| &column.field_mapping),
+                    )
+                    .cloned()
+                };
+            to_group_expression(
+                filter,
+                &data_connector.name,
+                ndc_version,
+                &plan_group_aggregate,
+                &plan_comparison_operators,
+            )
+        })
+        .transpose()?;
+
+    let group_order_by = if model_dimensions.order_by.is_empty() {
+        None
+    } else {
+        if !grouping_capabilities.is_some_and(|capabilities| capabilities.supports_ordering) {
+            return Err(GroupByError::OrderingNotSupported {
+                data_connector_name: data_connector.name.clone(),
+            }
+            .into());
+        }
+        Some(
+            model_dimensions
+                .order_by
+                .iter()
+                .map(|element| {
+                    to_group_order_by_element(element, &dimensions, &plan_group_aggregate)
+                })
+                .collect::<Result<Vec<_>, PlanError>>()?,
+        )
+    };
+
     let query = model_target::model_target_to_ndc_query(
         model_target,
         session,
@@ -245,6 +331,17 @@ pub fn from_model_group_by(
         .transpose()
         .map_err(|_| PlanError::Internal("offset out of range".into()))?;
 
+    // the relationships traversed by dimensions are sent along with those of the query
+    let mut collection_relationships = query.collection_relationships.clone();
+    collection_relationships.extend(dimension_relationships);
+
+    let response_cache = super::plan_response_cache(
+        metadata,
+        session,
+        &model_source.data_connector,
+        model_source.response_cache.as_ref(),
+        &collection_relationships,
+    );
     let query_execution_plan = QueryExecutionPlan {
         query_node: QueryNode {
             fields: None,
@@ -256,21 +353,25 @@ pub fn from_model_group_by(
             group_by: Some(Grouping {
                 aggregates,
                 dimensions,
+                predicate: group_predicate,
+                order_by: group_order_by,
                 limit,
                 offset,
             }),
         },
         collection: query.collection_name.clone(),
         arguments: query.arguments.clone(),
-        collection_relationships: query.collection_relationships.clone(),
+        collection_relationships,
         variables: None,
         data_connector: query.data_connector,
+        response_cache,
     };
 
     Ok(QueryExecutionTree {
         query_execution_plan,
         remote_predicates,
         remote_join_executions: JoinLocations::new(),
+        remote_order_by: None,
     })
 }
 
@@ -327,7 +428,6 @@ pub fn from_model_aggregate_selection(
             model_source,
             aggregate_expression_name,
             aggregate,
-            field_alias,
             ndc_version,
         )?;
 
@@ -376,12 +476,363 @@ pub fn from_model_aggregate_selection(
         collection_relationships: query.collection_relationships.clone(),
         variables: None,
         data_connector: query.data_connector,
+        response_cache: super::plan_response_cache(
+            metadata,
+            session,
+            &model_source.data_connector,
+            model_source.response_cache.as_ref(),
+            &query.collection_relationships,
+        ),
     };
 
     Ok(QueryExecutionTree {
         query_execution_plan,
         remote_predicates,
         remote_join_executions: JoinLocations::new(),
+        remote_order_by: None,
+    })
+}
+
+/// Resolve the column to group by, traversing any local object relationships on the way
+fn to_dimension_column(
+    metadata: &Metadata,
+    session: &Session,
+    type_mappings: &BTreeMap<Qualified<CustomTypeName>, TypeMapping>,
+    type_name: &Qualified<CustomTypeName>,
+    object_type: &OutputObjectTypeView,
+    data_connector: &metadata_resolve::DataConnectorLink,
+    operand: &Operand,
+    mut relationship_path: Vec<RelationshipPathElement<ResolvedFilterExpression>>,
+    collect_relationships: &mut BTreeMap<NdcRelationshipName, plan_types::Relationship>,
+    remote_predicates: &mut PredicateQueryTrees,
+    unique_number: &mut UniqueNumber,
+    usage_counts: &mut UsagesCounts,
+) -> Result<
+    (
+        Vec<RelationshipPathElement<ResolvedFilterExpression>>,
+        ResolvedColumn,
+    ),
+    PlanError,
+> {
+    match operand {
+        Operand::Field(operand) => {
+            let column = to_resolved_column(
+                &session.role,
+                metadata,
+                type_mappings,
+                type_name,
+                object_type,
+                operand,
+            )?;
+            Ok((relationship_path, column))
+        }
+        Operand::Relationship(operand) => {
+            let relationship_name = &operand.target.relationship_name;
+
+            if data_connector.capabilities.supports_relationships.is_none() {
+                return Err(GroupByError::RelationshipDimensionsNotSupported {
+                    data_connector_name: data_connector.name.clone(),
+                }
+                .into());
+            }
+
+            let relationship = object_type
+                .relationship_fields
+                .get(relationship_name)
+                .ok_or_else(|| {
+                    PlanError::Internal(format!(
+                        "can't find relationship {relationship_name} in type: {type_name}"
+                    ))
+                })?;
+
+            let RelationshipTarget::Model(model_relationship_target) = &relationship.target else {
+                return Err(GroupByError::UnsupportedDimension(format!(
+                    "command relationship {relationship_name}"
+                ))
+                .into());
+            };
+
+            if model_relationship_target.relationship_type != RelationshipType::Object {
+                return Err(GroupByError::ArrayRelationshipDimensionNotSupported(
+                    relationship_name.clone(),
+                )
+                .into());
+            }
+
+            let target_model_name = &model_relationship_target.model_name;
+            let target_model = metadata.models.get(target_model_name).ok_or_else(|| {
+                PlanError::Internal(format!("model {target_model_name} not found in metadata"))
+            })?;
+
+            let target_model_source = target_model.model.source.as_deref().ok_or_else(|| {
+                PlanError::Internal(format!("model {target_model_name} has no source"))
+            })?;
+
+            field_selection::reject_remote_relationship(
+                relationship_name,
+                data_connector,
+                &target_model_source.data_connector,
+            )
+            .map_err(|e| GroupByError::RemoteRelationshipNotSupported(e.to_string()))?;
+
+            crate::model_tracking::count_model(target_model_name, usage_counts);
+
+            let target_permission_filter = crate::filter::resolve_model_permission_filter(
+                session,
+                target_model,
+                target_model_source,
+                &metadata.object_types,
+                collect_relationships,
+                remote_predicates,
+                unique_number,
+                usage_counts,
+            )?;
+
+            let local_model_relationship_info = plan_types::LocalModelRelationshipInfo {
+                relationship_name,
+                relationship_type: &model_relationship_target.relationship_type,
+                source_type: type_name,
+                source_data_connector: data_connector,
+                source_type_mappings: type_mappings,
+                target_model_name,
+                target_source: target_model_source,
+                target_type: &model_relationship_target.target_typename,
+                mappings: &model_relationship_target.mappings,
+            };
+
+            let ndc_relationship_name = NdcRelationshipName::new(type_name, relationship_name);
+            let (source_field_path, relationship) =
+                super::process_model_relationship_definition_with_source_path(
+                    &local_model_relationship_info,
+                    &metadata.object_types,
+                )?;
+            collect_relationships.insert(ndc_relationship_name.clone(), relationship);
+
+            relationship_path.push(RelationshipPathElement {
+                field_path: source_field_path,
+                relationship_name: ndc_relationship_name,
+                filter_predicate: target_permission_filter,
+            });
+
+            let nested_operand = operand.nested.as_ref().ok_or_else(|| {
+                GroupByError::UnsupportedDimension(format!(
+                    "relationship {relationship_name} must have a nested field"
+                ))
+            })?;
+
+            let target_type = &model_relationship_target.target_typename;
+            let target_object_type = crate::metadata_accessor::get_output_object_type(
+                metadata,
+                target_type,
+                &session.role,
+            )?;
+
+            to_dimension_column(
+                metadata,
+                session,
+                &target_model_source.type_mappings,
+                target_type,
+                &target_object_type,
+                data_connector,
+                nested_operand,
+                relationship_path,
+                collect_relationships,
+                remote_predicates,
+                unique_number,
+                usage_counts,
+            )
+        }
+        Operand::RelationshipAggregate(operand) => {
+            Err(GroupByError::UnsupportedDimension(format!(
+                "aggregate of relationship {}",
+                operand.target.relationship_name
+            ))
+            .into())
+        }
+    }
+}
+
+/// Convert a filter on the aggregates of groups to the predicate sent to the data connector
+fn to_group_expression(
+    expression: &GroupBooleanExpression,
+    data_connector_name: &Qualified<DataConnectorName>,
+    ndc_version: NdcVersion,
+    plan_aggregate: &impl Fn(&Aggregate) -> Result<AggregateFieldSelection, PlanError>,
+    plan_comparison_operators: &impl Fn(
+        &Aggregate,
+        &AggregateFieldSelection,
+    ) -> Result<ComparisonOperators, PlanError>,
+) -> Result<GroupExpression, PlanError> {
+    let to_group_expressions = |expressions: &Vec<GroupBooleanExpression>| {
+        expressions
+            .iter()
+            .map(|expression| {
+                to_group_expression(
+                    expression,
+                    data_connector_name,
+                    ndc_version,
+                    plan_aggregate,
+                    plan_comparison_operators,
+                )
+            })
+            .collect::<Result<_, _>>()
+    };
+    match expression {
+        GroupBooleanExpression::And(expressions) => Ok(GroupExpression::And {
+            expressions: to_group_expressions(expressions)?,
+        }),
+        GroupBooleanExpression::Or(expressions) => Ok(GroupExpression::Or {
+            expressions: to_group_expressions(expressions)?,
+        }),
+        GroupBooleanExpression::Not(expression) => Ok(GroupExpression::Not {
+            expression: Box::new(to_group_expression(
+                expression,
+                data_connector_name,
+                ndc_version,
+                plan_aggregate,
+                plan_comparison_operators,
+            )?),
+        }),
+        GroupBooleanExpression::IsNull(aggregate) => Ok(GroupExpression::IsNull {
+            aggregate: plan_aggregate(aggregate)?,
+        }),
+        GroupBooleanExpression::Comparison {
+            operand,
+            operator,
+            argument,
+        } => {
+            let Value::Literal(value) = argument.as_ref() else {
+                return Err(GroupByError::NonLiteralComparisonArgument.into());
+            };
+            let aggregate = plan_aggregate(operand)?;
+            // aggregates have no boolean expression types, so operators are resolved using the
+            // scalar type that the data connector declares for the result of the aggregate
+            let comparison_operators = plan_comparison_operators(operand, &aggregate)?;
+            let (operator_name, data_connector_operator_name) = match operator {
+                ComparisonOperator::Equals => {
+                    ("_eq", comparison_operators.get_eq_operator(ndc_version))
+                }
+                ComparisonOperator::NotEquals => {
+                    ("_neq", comparison_operators.get_eq_operator(ndc_version))
+                }
+                ComparisonOperator::LessThan => {
+                    ("_lt", comparison_operators.get_lt_operator(ndc_version))
+                }
+                ComparisonOperator::LessThanOrEqual => {
+                    ("_lte", comparison_operators.get_lte_operator(ndc_version))
+                }
+                ComparisonOperator::GreaterThan => {
+                    ("_gt", comparison_operators.get_gt_operator(ndc_version))
+                }
+                ComparisonOperator::GreaterThanOrEqual => {
+                    ("_gte", comparison_operators.get_gte_operator(ndc_version))
+                }
+                ComparisonOperator::Contains => (
+                    "_contains",
+                    comparison_operators.get_contains_operator(ndc_version),
+                ),
+                ComparisonOperator::ContainsInsensitive => (
+                    "_icontains",
+                    comparison_operators.get_icontains_operator(ndc_version),
+                ),
+                ComparisonOperator::StartsWith => (
+                    "starts_with",
+                    comparison_operators.get_starts_with_operator(ndc_version),
+                ),
+                ComparisonOperator::StartsWithInsensitive => (
+                    "istarts_with",
+                    comparison_operators.get_istarts_with_operator(ndc_version),
+                ),
+                ComparisonOperator::EndsWith => (
+                    "ends_with",
+                    comparison_operators.get_ends_with_operator(ndc_version),
+                ),
+                ComparisonOperator::EndsWithInsensitive => (
+                    "iends_with",
+                    comparison_operators.get_iends_with_operator(ndc_version),
+                ),
+                ComparisonOperator::Custom(operator_name) => (
+                    operator_name.as_str(),
+                    comparison_operators
+                        .other_operators
+                        .iter()
+                        .find(|other_operator| other_operator.as_str() == operator_name.as_str()),
+                ),
+            };
+            let data_connector_operator_name = data_connector_operator_name.ok_or_else(|| {
+                GroupByError::ComparisonOperatorNotSupported {
+                    data_connector_name: data_connector_name.clone(),
+                    operator_name: operator_name.to_string(),
+                }
+            })?;
+            let comparison = GroupExpression::BinaryComparison {
+                aggregate,
+                operator: data_connector_operator_name.clone(),
+                value: value.clone(),
+            };
+            Ok(match operator {
+                ComparisonOperator::NotEquals => GroupExpression::Not {
+                    expression: Box::new(comparison),
+                },
+                _ => comparison,
+            })
+        }
+    }
+}
+
+/// The comparison operators that the data connector declares for the scalar type that an
+/// aggregate of groups results in
+fn group_aggregate_comparison_operators<'a>(
+    grouping_capabilities: &'a DataConnectorGroupingCapabilities,
+    data_connector_name: &Qualified<DataConnectorName>,
+    aggregate: &AggregateFieldSelection,
+    operand_column: Option<&FieldMapping>,
+) -> Result<&'a ComparisonOperators, PlanError> {
+    let comparison_operators = match aggregate {
+        AggregateFieldSelection::Count { .. } | AggregateFieldSelection::CountDistinct { .. } => {
+            grouping_capabilities.count_comparison_operators.as_ref()
+        }
+        AggregateFieldSelection::AggregationFunction { function_name, .. } => operand_column
+            .and_then(|field_mapping| {
+                grouping_capabilities.get_aggregate_function_comparison_operators(
+                    &field_mapping.column_type,
+                    function_name,
+                )
+            }),
+    };
+    comparison_operators.ok_or_else(|| {
+        GroupByError::AggregateNotComparable {
+            data_connector_name: data_connector_name.clone(),
+        }
+        .into()
+    })
+}
+
+/// Convert an ordering of groups to the ordering sent to the data connector, which refers to
+/// dimensions by their position in the grouping
+fn to_group_order_by_element(
+    element: &GroupOrderByElement,
+    dimensions: &IndexMap<NdcFieldAlias, plan_types::Dimension>,
+    plan_aggregate: &impl Fn(&Aggregate) -> Result<AggregateFieldSelection, PlanError>,
+) -> Result<plan_types::GroupOrderByElement, PlanError> {
+    let order_direction = match element.direction {
+        open_dds::models::OrderByDirection::Asc => plan_types::OrderByDirection::Asc,
+        open_dds::models::OrderByDirection::Desc => plan_types::OrderByDirection::Desc,
+    };
+    let target = match &element.operand {
+        GroupOrderByOperand::Dimension(dimension_name) => {
+            let index = dimensions
+                .get_index_of(&NdcFieldAlias::from(dimension_name.as_str()))
+                .ok_or_else(|| GroupByError::DimensionNotFound(dimension_name.to_string()))?;
+            GroupOrderByTarget::Dimension { index }
+        }
+        GroupOrderByOperand::Aggregate(aggregate) => GroupOrderByTarget::Aggregate {
+            aggregate: plan_aggregate(aggregate)?,
+        },
+    };
+    Ok(plan_types::GroupOrderByElement {
+        order_direction,
+        target,
     })
 }
 
@@ -406,7 +857,6 @@ fn to_ndc_aggregate(
     model_source: &metadata_resolve::ModelSource,
     aggregate_expression: Option<&Qualified<AggregateExpressionName>>,
     aggregate: &Aggregate,
-    field_alias: &Name,
     ndc_version: NdcVersion,
 ) -> Result<AggregateFieldSelection, PlanError> {
     let resolved_column = aggregate
@@ -455,8 +905,8 @@ fn to_ndc_aggregate(
                 .aggregate_functions
                 .ok_or_else(|| {
                     PlanError::Internal(format!(
-                        "no aggregate functions defined for field {}",
-                        field_alias.as_str()
+                        "no aggregate functions defined for type: {:?}",
+                        resolved_column.field_mapping.column_type
                     ))
                 })?;
             let data_connector_function_name = match &aggregate.function {
@@ -636,7 +1086,7 @@ pub fn from_model_selection(
 
     let mut relationships = BTreeMap::new();
 
-    let ndc_fields = field_selection::resolve_field_selection(
+    let mut ndc_fields = field_selection::resolve_field_selection(
         metadata,
         session,
         request_headers,
@@ -651,8 +1101,34 @@ pub fn from_model_selection(
         unique_number,
     )?;
 
-    let mut query = model_target::model_target_to_ndc_query(
+    let remote_order_by = remote_order_by::plan_remote_order_by(
         model_target,
+        &model_selection.selection,
+        metadata,
+        session,
+        request_headers,
+        model_source,
+        &model_object_type,
+        &mut ndc_fields,
+        &mut relationships,
+        &mut remote_predicates,
+        unique_number,
+    )?;
+
+    // when the engine orders the rows itself, they are fetched without any pagination, ordered
+    // only by the elements that the data connector can order by
+    let query_model_target = match &remote_order_by {
+        None => Cow::Borrowed(model_target),
+        Some(remote_order_by) => Cow::Owned(ModelTarget {
+            order_by: model_target.order_by[..remote_order_by.connector_ordered_elements].to_vec(),
+            limit: None,
+            offset: None,
+            ..model_target.clone()
+        }),
+    };
+
+    let mut query = model_target::model_target_to_ndc_query(
+        &query_model_target,
         session,
         metadata,
         request_headers,
@@ -663,6 +1139,10 @@ pub fn from_model_selection(
         unique_number,
     )?;
 
+    if let Some(remote_order_by) = &remote_order_by {
+        query.limit = Some(remote_order_by.fetch_limit);
+    }
+
     // collect relationships accummulated in this scope.
     query.collection_relationships.append(&mut relationships);
 
@@ -694,11 +1174,19 @@ pub fn from_model_selection(
         collection_relationships: query.collection_relationships.clone(),
         variables: None,
         data_connector: query.data_connector.clone(),
+        response_cache: super::plan_response_cache(
+            metadata,
+            session,
+            &model_source.data_connector,
+            model_source.response_cache.as_ref(),
+            &query.collection_relationships,
+        ),
     };
 
     Ok(QueryExecutionTree {
         query_execution_plan,
         remote_predicates,
         remote_join_executions,
+        remote_order_by,
     })
 }
@@ -1,10 +1,16 @@
+use super::arguments::{
+    map_field_names_to_ndc_field_names, resolve_arguments, ArgumentPresetExecutionError,
+    UnresolvedArgument,
+};
+use super::filter::plan_expression;
+use super::permissions::make_argument_from_value_expression;
 use crate::metadata_accessor::OutputObjectTypeView;
 use crate::types::{PlanError, RelationshipError};
-use hasura_authn_core::Session;
+use hasura_authn_core::{Session, SessionVariables};
 use indexmap::IndexMap;
 use metadata_resolve::{
-    Metadata, Qualified, QualifiedTypeReference, RelationshipCommandMapping,
-    RelationshipModelMapping, TypeMapping,
+    Metadata, ObjectTypeWithRelationships, Qualified, QualifiedTypeReference,
+    RelationshipCommandMapping, RelationshipModelMapping, TypeMapping,
 };
 use open_dds::{
     arguments::ArgumentName,
@@ -16,14 +22,171 @@ use open_dds::{
     types::{CustomTypeName, DataConnectorArgumentName, FieldName},
 };
 use plan_types::{
-    Argument, ComparisonTarget, ComparisonValue, Field, LocalCommandRelationshipInfo,
-    LocalFieldComparison, LocalModelRelationshipInfo, NdcFieldAlias, Relationship,
-    RemoteJoinFieldMapping, RemoteJoinObjectFieldMapping, RemoteJoinObjectTargetField,
-    ResolvedFilterExpression, SourceFieldAlias, TargetField,
+    Argument, ComparisonTarget, ComparisonValue, Expression, Field, LocalCommandRelationshipInfo,
+    LocalFieldComparison, LocalModelRelationshipInfo, NdcFieldAlias, QueryExecutionTree,
+    Relationship, RemoteJoinFieldMapping, RemoteJoinObjectFieldMapping,
+    RemoteJoinObjectTargetField, ResolvedFilterExpression, SourceFieldAlias, SourceFieldName,
+    TargetField, UniqueNumber,
 };
 use std::collections::BTreeMap;
 use std::collections::VecDeque;
 
+/// Resolves the NDC relationship of a local model relationship, along with the NDC fields of the
+/// nested object that it is followed from. The field path is empty unless the source fields of
+/// the relationship are nested inside an object, in which case the relationship is defined
+/// relative to that object.
+pub fn process_model_relationship_definition_with_source_path(
+    relationship_info: &LocalModelRelationshipInfo,
+    object_types: &BTreeMap<Qualified<CustomTypeName>, ObjectTypeWithRelationships>,
+) -> Result<(Vec<DataConnectorColumnName>, Relationship), PlanError> {
+    let Some((source_field_path, nested_source_type)) = get_relationship_nested_source_object(
+        relationship_info.relationship_name,
+        relationship_info.source_type,
+        relationship_info
+            .mappings
+            .iter()
+            .map(|mapping| &mapping.source_field),
+        relationship_info.source_type_mappings,
+        object_types,
+    )?
+    else {
+        return Ok((
+            vec![],
+            process_model_relationship_definition(relationship_info)?,
+        ));
+    };
+    let mappings = relationship_info
+        .mappings
+        .iter()
+        .map(|mapping| RelationshipModelMapping {
+            source_field: innermost_field_access(&mapping.source_field),
+            target: mapping.target.clone(),
+        })
+        .collect();
+    let relationship = process_model_relationship_definition(&LocalModelRelationshipInfo {
+        source_type: &nested_source_type,
+        mappings: &mappings,
+        ..*relationship_info
+    })?;
+    Ok((source_field_path, relationship))
+}
+
+/// Resolves the NDC relationship of a local command relationship, along with the NDC fields of
+/// the nested object that it is followed from, like
+/// `process_model_relationship_definition_with_source_path`
+pub fn process_command_relationship_definition_with_source_path(
+    relationship_info: &LocalCommandRelationshipInfo,
+    object_types: &BTreeMap<Qualified<CustomTypeName>, ObjectTypeWithRelationships>,
+) -> Result<(Vec<DataConnectorColumnName>, Relationship), PlanError> {
+    let Some((source_field_path, nested_source_type)) = get_relationship_nested_source_object(
+        relationship_info.relationship_name,
+        relationship_info.source_type,
+        relationship_info
+            .mappings
+            .iter()
+            .map(|mapping| &mapping.source_field),
+        relationship_info.source_type_mappings,
+        object_types,
+    )?
+    else {
+        return Ok((
+            vec![],
+            process_command_relationship_definition(relationship_info)?,
+        ));
+    };
+    let mappings = relationship_info
+        .mappings
+        .iter()
+        .map(|mapping| RelationshipCommandMapping {
+            source_field: innermost_field_access(&mapping.source_field),
+            argument_name: mapping.argument_name.clone(),
+        })
+        .collect();
+    let relationship = process_command_relationship_definition(&LocalCommandRelationshipInfo {
+        source_type: &nested_source_type,
+        mappings: &mappings,
+        ..*relationship_info
+    })?;
+    Ok((source_field_path, relationship))
+}
+
+/// Finds the nested object that all the source fields of a relationship are in, returning the
+/// NDC fields leading to it and its type, or `None` if the source fields are top-level fields
+fn get_relationship_nested_source_object<'a>(
+    relationship_name: &RelationshipName,
+    source_type: &Qualified<CustomTypeName>,
+    mut source_fields: impl Iterator<Item = &'a metadata_resolve::RelationshipFieldAccess>,
+    source_type_mappings: &BTreeMap<Qualified<CustomTypeName>, TypeMapping>,
+    object_types: &BTreeMap<Qualified<CustomTypeName>, ObjectTypeWithRelationships>,
+) -> Result<Option<(Vec<DataConnectorColumnName>, Qualified<CustomTypeName>)>, PlanError> {
+    let nested_object_path = |source_field: &'a metadata_resolve::RelationshipFieldAccess| {
+        source_field
+            .nested_field_path
+            .split_last()
+            .map(|(_, nested_object_path)| {
+                std::iter::once(&source_field.field_name)
+                    .chain(nested_object_path)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+    };
+    let Some(first_source_field) = source_fields.next() else {
+        return Ok(None);
+    };
+    let object_path = nested_object_path(first_source_field);
+    if source_fields.any(|source_field| nested_object_path(source_field) != object_path) {
+        return Err(PlanError::Internal(format!(
+            "Relationship {relationship_name} on {source_type} maps source fields from different objects, so it cannot be executed locally"
+        )));
+    }
+    if object_path.is_empty() {
+        return Ok(None);
+    }
+
+    let mut field_path = Vec::new();
+    let mut object_type_name = source_type.clone();
+    for field_name in object_path {
+        let field_mapping = get_relationship_field_mapping_of_field_name(
+            source_type_mappings,
+            &object_type_name,
+            relationship_name,
+            field_name,
+        )
+        .map_err(RelationshipError::RelationshipFieldMappingError)?;
+        let field_type = object_types
+            .get(&object_type_name)
+            .and_then(|object_type| object_type.object_type.fields.get(field_name))
+            .map(|field_definition| &field_definition.field_type.underlying_type);
+        let Some(metadata_resolve::QualifiedBaseType::Named(
+            metadata_resolve::QualifiedTypeName::Custom(nested_object_type_name),
+        )) = field_type
+        else {
+            return Err(RelationshipError::MissingSourceField {
+                relationship_name: relationship_name.clone(),
+                source_field: field_name.clone(),
+            }
+            .into());
+        };
+        field_path.push(field_mapping.column);
+        object_type_name = nested_object_type_name.clone();
+    }
+    Ok(Some((field_path, object_type_name)))
+}
+
+/// The innermost field of a nested field access, as accessed from the object that it is in
+fn innermost_field_access(
+    field_access: &metadata_resolve::RelationshipFieldAccess,
+) -> metadata_resolve::RelationshipFieldAccess {
+    metadata_resolve::RelationshipFieldAccess {
+        field_name: field_access
+            .nested_field_path
+            .last()
+            .unwrap_or(&field_access.field_name)
+            .clone(),
+        nested_field_path: vec![],
+    }
+}
+
 pub fn process_model_relationship_definition(
     relationship_info: &LocalModelRelationshipInfo,
 ) -> Result<Relationship, PlanError> {
@@ -46,6 +209,14 @@ pub fn process_model_relationship_definition(
         target,
     } in mappings
     {
+        // relationships with nested source fields are defined relative to the nested object
+        // that they are followed from
+        if source_field_path.is_nested() {
+            return Err(PlanError::Internal(format!(
+                "Relationship {relationship_name} on {source_type} maps a nested source field, so it must be followed from the nested object"
+            )));
+        }
+
         let source_column = get_relationship_field_mapping_of_field_name(
             source_type_mappings,
             source_type,
@@ -67,8 +238,12 @@ pub fn process_model_relationship_definition(
                     ))
                 })?;
 
+                let target_column_path = std::iter::once(target_column.column.clone())
+                    .chain(target_column.field_path.iter().cloned())
+                    .collect::<Vec<_>>();
+
                 if column_mapping
-                    .insert(source_column.column, target_column.column.clone())
+                    .insert(source_column.column, target_column_path)
                     .is_some()
                 {
                     Err(PlanError::Relationship(
@@ -143,6 +318,14 @@ pub fn process_command_relationship_definition(
         argument_name: target_argument,
     } in mappings
     {
+        // relationships with nested source fields are defined relative to the nested object
+        // that they are followed from
+        if source_field_path.is_nested() {
+            return Err(PlanError::Internal(format!(
+                "Relationship {relationship_name} on {source_type} maps a nested source field, so it must be followed from the nested object"
+            )));
+        }
+
         let source_column = get_relationship_field_mapping_of_field_name(
             source_type_mappings,
             source_type,
@@ -191,8 +374,262 @@ pub fn process_command_relationship_definition(
     Ok(relationship)
 }
 
+/// The arguments and predicate that the value mappings of a model relationship impose on the
+/// target model of the relationship
+pub struct ModelRelationshipValueConstraints<'s> {
+    pub arguments: BTreeMap<DataConnectorArgumentName, UnresolvedArgument<'s>>,
+    pub predicate: Option<Expression<'s>>,
+}
+
+pub fn process_model_relationship_value_mappings<'s>(
+    relationship_name: &RelationshipName,
+    source_type: &Qualified<CustomTypeName>,
+    target_model_name: &Qualified<ModelName>,
+    target_source: &metadata_resolve::ModelSource,
+    value_mappings: &[metadata_resolve::RelationshipModelValueMapping],
+    object_types: &BTreeMap<Qualified<CustomTypeName>, ObjectTypeWithRelationships>,
+    session_variables: &SessionVariables,
+) -> Result<ModelRelationshipValueConstraints<'s>, PlanError> {
+    let mut arguments = BTreeMap::new();
+    let mut predicates = Vec::new();
+    for metadata_resolve::RelationshipModelValueMapping {
+        source_value,
+        target,
+        target_type,
+    } in value_mappings
+    {
+        let value = evaluate_relationship_mapping_value(
+            source_value,
+            target_type,
+            &target_source.type_mappings,
+            object_types,
+            session_variables,
+        )?;
+
+        match target {
+            metadata_resolve::RelationshipModelMappingTarget::ModelField(
+                metadata_resolve::RelationshipModelMappingFieldTarget {
+                    target_field: _,
+                    target_ndc_column,
+                },
+            ) => {
+                let target_column = target_ndc_column.as_ref().ok_or_else(|| {
+                    PlanError::Internal(format!(
+                        "No column mapping for relationship {relationship_name} on {source_type}"
+                    ))
+                })?;
+
+                predicates.push(Expression::LocalField(
+                    LocalFieldComparison::BinaryComparison {
+                        column: ComparisonTarget::Column {
+                            name: target_column.column.clone(),
+                            field_path: target_column.field_path.clone(),
+                        },
+                        operator: target_column.equal_operator.clone(),
+                        value: ComparisonValue::Scalar { value },
+                    },
+                ));
+            }
+            metadata_resolve::RelationshipModelMappingTarget::Argument(argument_name) => {
+                let connector_argument_name = target_source
+                    .argument_mappings
+                    .get(argument_name)
+                    .ok_or_else(|| {
+                        PlanError::Relationship(
+                            RelationshipError::MissingArgumentMappingInModelRelationship {
+                                source_type: source_type.clone(),
+                                relationship_name: relationship_name.clone(),
+                                model_name: target_model_name.clone(),
+                                argument_name: argument_name.clone(),
+                            },
+                        )
+                    })?;
+
+                arguments.insert(
+                    connector_argument_name.clone(),
+                    UnresolvedArgument::Literal { value },
+                );
+            }
+        }
+    }
+
+    Ok(ModelRelationshipValueConstraints {
+        arguments,
+        predicate: Expression::mk_and(predicates).remove_always_true_expression(),
+    })
+}
+
+/// The arguments that the value mappings of a command relationship impose on the target
+/// command of the relationship
+pub fn process_command_relationship_value_mappings<'s>(
+    relationship_name: &RelationshipName,
+    source_type: &Qualified<CustomTypeName>,
+    command_name: &Qualified<CommandName>,
+    command_source: &metadata_resolve::CommandSource,
+    value_mappings: &[metadata_resolve::RelationshipCommandValueMapping],
+    object_types: &BTreeMap<Qualified<CustomTypeName>, ObjectTypeWithRelationships>,
+    session_variables: &SessionVariables,
+) -> Result<BTreeMap<DataConnectorArgumentName, UnresolvedArgument<'s>>, PlanError> {
+    let mut arguments = BTreeMap::new();
+    for metadata_resolve::RelationshipCommandValueMapping {
+        source_value,
+        argument_name,
+        argument_type,
+    } in value_mappings
+    {
+        let value = evaluate_relationship_mapping_value(
+            source_value,
+            argument_type,
+            &command_source.type_mappings,
+            object_types,
+            session_variables,
+        )?;
+
+        let connector_argument_name = command_source
+            .argument_mappings
+            .get(argument_name)
+            .ok_or_else(|| {
+                PlanError::Relationship(
+                    RelationshipError::MissingArgumentMappingInCommandRelationship {
+                        source_type: source_type.clone(),
+                        relationship_name: relationship_name.clone(),
+                        command_name: command_name.clone(),
+                        argument_name: argument_name.clone(),
+                    },
+                )
+            })?;
+
+        arguments.insert(
+            connector_argument_name.clone(),
+            UnresolvedArgument::Literal { value },
+        );
+    }
+    Ok(arguments)
+}
+
+// literals are provided with OpenDD names, while session variables are typecast
+// to the type of the target they are mapped to
+fn evaluate_relationship_mapping_value(
+    source_value: &metadata_resolve::ValueExpression,
+    value_type: &QualifiedTypeReference,
+    type_mappings: &BTreeMap<Qualified<CustomTypeName>, TypeMapping>,
+    object_types: &BTreeMap<Qualified<CustomTypeName>, ObjectTypeWithRelationships>,
+    session_variables: &SessionVariables,
+) -> Result<serde_json::Value, PlanError> {
+    match source_value {
+        metadata_resolve::ValueExpression::Literal(literal) => {
+            let mut value = literal.clone();
+            map_field_names_to_ndc_field_names(
+                &mut value,
+                value_type,
+                type_mappings,
+                object_types,
+                false, // literals have already been typechecked during metadata resolution
+            )
+            .map_err(ArgumentPresetExecutionError::MapFieldNamesError)?;
+            Ok(value)
+        }
+        metadata_resolve::ValueExpression::SessionVariable(_) => {
+            make_argument_from_value_expression(
+                source_value,
+                value_type,
+                session_variables,
+                type_mappings,
+                object_types,
+            )
+        }
+    }
+}
+
+/// Constrains the planned query of the target model of a relationship by the values that the
+/// relationship's mappings bind to the target's arguments and fields
+pub fn apply_model_relationship_value_mappings(
+    query_execution_tree: &mut QueryExecutionTree,
+    relationship_name: &RelationshipName,
+    source_type: &Qualified<CustomTypeName>,
+    target_model_name: &Qualified<ModelName>,
+    target_source: &metadata_resolve::ModelSource,
+    value_mappings: &[metadata_resolve::RelationshipModelValueMapping],
+    object_types: &BTreeMap<Qualified<CustomTypeName>, ObjectTypeWithRelationships>,
+    session_variables: &SessionVariables,
+    unique_number: &mut UniqueNumber,
+) -> Result<(), PlanError> {
+    let ModelRelationshipValueConstraints {
+        arguments,
+        predicate,
+    } = process_model_relationship_value_mappings(
+        relationship_name,
+        source_type,
+        target_model_name,
+        target_source,
+        value_mappings,
+        object_types,
+        session_variables,
+    )?;
+
+    let query_execution_plan = &mut query_execution_tree.query_execution_plan;
+    query_execution_plan.arguments.extend(resolve_arguments(
+        arguments,
+        &mut query_execution_plan.collection_relationships,
+        &mut query_execution_tree.remote_predicates,
+        unique_number,
+    )?);
+
+    if let Some(predicate) = predicate {
+        let predicate = plan_expression(
+            &predicate,
+            &mut query_execution_plan.collection_relationships,
+            &mut query_execution_tree.remote_predicates,
+            unique_number,
+        )?;
+        let query_node = &mut query_execution_plan.query_node;
+        query_node.predicate = Some(match query_node.predicate.take() {
+            Some(existing_predicate) => {
+                ResolvedFilterExpression::mk_and(vec![existing_predicate, predicate])
+            }
+            None => predicate,
+        });
+    }
+
+    Ok(())
+}
+
+/// Binds the arguments of the planned query of the target command of a relationship to the
+/// values that the relationship's mappings bind to them
+pub fn apply_command_relationship_value_mappings(
+    query_execution_tree: &mut QueryExecutionTree,
+    relationship_name: &RelationshipName,
+    source_type: &Qualified<CustomTypeName>,
+    command_name: &Qualified<CommandName>,
+    command_source: &metadata_resolve::CommandSource,
+    value_mappings: &[metadata_resolve::RelationshipCommandValueMapping],
+    object_types: &BTreeMap<Qualified<CustomTypeName>, ObjectTypeWithRelationships>,
+    session_variables: &SessionVariables,
+    unique_number: &mut UniqueNumber,
+) -> Result<(), PlanError> {
+    let arguments = process_command_relationship_value_mappings(
+        relationship_name,
+        source_type,
+        command_name,
+        command_source,
+        value_mappings,
+        object_types,
+        session_variables,
+    )?;
+
+    let query_execution_plan = &mut query_execution_tree.query_execution_plan;
+    query_execution_plan.arguments.extend(resolve_arguments(
+        arguments,
+        &mut query_execution_plan.collection_relationships,
+        &mut query_execution_tree.remote_predicates,
+        unique_number,
+    )?);
+
+    Ok(())
+}
+
 pub struct CommandRemoteRelationshipParts {
-    pub join_mapping: BTreeMap<FieldName, RemoteJoinFieldMapping>,
+    pub join_mapping: BTreeMap<SourceFieldName, RemoteJoinFieldMapping>,
     pub object_type_field_mappings:
         BTreeMap<Qualified<CustomTypeName>, RemoteJoinObjectFieldMapping>,
     pub arguments: IndexMap<DataConnectorArgumentName, Argument>,
@@ -228,8 +665,16 @@ pub fn calculate_remote_relationship_fields_for_command_target(
             })?
             .field_type;
 
-        collect_remote_join_object_type_field_mappings(
+        let (source_field_path, source_field_type) = get_relationship_source_nested_field_path(
+            source_field,
             source_field_type,
+            relationship_name,
+            source_type_mappings,
+            &metadata.object_types,
+        )?;
+
+        collect_remote_join_object_type_field_mappings(
+            &source_field_type,
             &mut object_type_field_mappings,
             relationship_name,
             source_type_mappings,
@@ -277,9 +722,10 @@ pub fn calculate_remote_relationship_fields_for_command_target(
 
         // add join mapping
         join_mapping.insert(
-            source_field.field_name.clone(),
+            source_field.clone(),
             RemoteJoinFieldMapping {
                 source_field_alias: ndc_field_alias.clone(),
+                source_field_path,
                 source_field_type: source_field_type.clone(),
                 target_field: target_argument.clone(),
             },
@@ -294,7 +740,7 @@ pub fn calculate_remote_relationship_fields_for_command_target(
 }
 
 pub struct ModelRemoteRelationshipParts {
-    pub join_mapping: BTreeMap<FieldName, RemoteJoinFieldMapping>,
+    pub join_mapping: BTreeMap<SourceFieldName, RemoteJoinFieldMapping>,
     pub object_type_field_mappings:
         BTreeMap<Qualified<CustomTypeName>, RemoteJoinObjectFieldMapping>,
     pub relationship_join_filter_expressions: VecDeque<ResolvedFilterExpression>,
@@ -332,8 +778,16 @@ pub fn calculate_remote_relationship_fields_for_model_target(
             })?
             .field_type;
 
-        collect_remote_join_object_type_field_mappings(
+        let (source_field_path, source_field_type) = get_relationship_source_nested_field_path(
+            source_field,
             source_field_type,
+            relationship_name,
+            source_type_mappings,
+            &metadata.object_types,
+        )?;
+
+        collect_remote_join_object_type_field_mappings(
+            &source_field_type,
             &mut object_type_field_mappings,
             relationship_name,
             source_type_mappings,
@@ -378,9 +832,10 @@ pub fn calculate_remote_relationship_fields_for_model_target(
                 );
                 let target_value_variable = target_model_field.make_variable_name();
                 join_mapping.insert(
-                    source_field.field_name.clone(),
+                    source_field.clone(),
                     RemoteJoinFieldMapping {
                         source_field_alias: ndc_field_alias.clone(),
+                        source_field_path,
                         source_field_type: source_field_type.clone(),
                         target_field: target_model_field.clone(),
                     },
@@ -390,7 +845,7 @@ pub fn calculate_remote_relationship_fields_for_model_target(
                 let comparison_exp = LocalFieldComparison::BinaryComparison {
                     column: ComparisonTarget::Column {
                         name: target_ndc_column.column.clone(),
-                        field_path: vec![],
+                        field_path: target_ndc_column.field_path.clone(),
                     },
                     operator: target_ndc_column.equal_operator.clone(),
                     value: ComparisonValue::Variable {
@@ -428,9 +883,10 @@ pub fn calculate_remote_relationship_fields_for_model_target(
 
                 // add join mapping
                 join_mapping.insert(
-                    source_field.field_name.clone(),
+                    source_field.clone(),
                     RemoteJoinFieldMapping {
                         source_field_alias: ndc_field_alias.clone(),
+                        source_field_path,
                         source_field_type: source_field_type.clone(),
                         target_field: target_argument.clone(),
                     },
@@ -637,6 +1093,54 @@ pub enum RelationshipFieldMappingError {
     },
 }
 
+/// Resolves the NDC fields that a relationship source field descends into through its nested
+/// objects, along with the type of the field at the end of the path. The value at the end of the
+/// path is null whenever any object along the path is, so its type is nullable if any of theirs is.
+pub fn get_relationship_source_nested_field_path<'a>(
+    source_field: &metadata_resolve::RelationshipFieldAccess,
+    source_field_type: &'a QualifiedTypeReference,
+    relationship_name: &RelationshipName,
+    source_type_mappings: &BTreeMap<Qualified<CustomTypeName>, TypeMapping>,
+    object_types: &'a BTreeMap<Qualified<CustomTypeName>, ObjectTypeWithRelationships>,
+) -> Result<(Vec<DataConnectorColumnName>, QualifiedTypeReference), RelationshipError> {
+    let mut field_path = Vec::new();
+    let mut field_type = source_field_type;
+    let mut nullable = source_field_type.nullable;
+    for field_name in &source_field.nested_field_path {
+        let missing_source_field = || RelationshipError::MissingSourceField {
+            relationship_name: relationship_name.clone(),
+            source_field: field_name.clone(),
+        };
+        let metadata_resolve::QualifiedBaseType::Named(
+            metadata_resolve::QualifiedTypeName::Custom(object_type_name),
+        ) = &field_type.underlying_type
+        else {
+            return Err(missing_source_field());
+        };
+        let field_definition = object_types
+            .get(object_type_name)
+            .and_then(|object_type| object_type.object_type.fields.get(field_name))
+            .ok_or_else(missing_source_field)?;
+        let field_mapping = get_relationship_field_mapping_of_field_name(
+            source_type_mappings,
+            object_type_name,
+            relationship_name,
+            field_name,
+        )
+        .map_err(RelationshipError::RelationshipFieldMappingError)?;
+        field_path.push(field_mapping.column);
+        field_type = &field_definition.field_type;
+        nullable = nullable || field_type.nullable;
+    }
+    Ok((
+        field_path,
+        QualifiedTypeReference {
+            underlying_type: field_type.underlying_type.clone(),
+            nullable,
+        },
+    ))
+}
+
 pub fn get_relationship_field_mapping_of_field_name(
     type_mappings: &BTreeMap<Qualified<CustomTypeName>, metadata_resolve::TypeMapping>,
     type_name: &Qualified<CustomTypeName>,
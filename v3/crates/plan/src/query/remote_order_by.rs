@@ -0,0 +1,412 @@
+//! Planning of ordering by the fields of remote relationships.
+//!
+//! The data connector of a model cannot order its rows by values that live in another data
+//! connector, so instead the rows are fetched ordered only by the elements before the first remote
+//! relationship, the fields of the remote relationships being ordered by are joined onto each row,
+//! and the engine sorts and paginates the rows itself.
+use super::field_selection;
+use super::relationships::{
+    apply_model_relationship_value_mappings, calculate_remote_relationship_fields_for_model_target,
+    ModelRemoteRelationshipParts,
+};
+use crate::metadata_accessor::OutputObjectTypeView;
+use crate::types::{OrderByError, PlanError};
+use hasura_authn_core::Session;
+use indexmap::IndexMap;
+use metadata_resolve::{Metadata, RelationshipTarget};
+use open_dds::{
+    identifier::Identifier,
+    query::{
+        Alias, ModelSelection, ModelTarget, ObjectFieldOperand, ObjectFieldSelection,
+        ObjectSubSelection, Operand, OrderByElement, RelationshipOperand,
+    },
+};
+use plan_types::{
+    Field, JoinLocations, JoinNode, Location, NdcFieldAlias, PredicateQueryTrees,
+    ProcessResponseAs, QueryExecutionTree, RemoteJoin, RemoteJoinType, RemoteOrderBy,
+    RemoteOrderByElement, RemoteOrderByTarget, ResolvedFilterExpression, UniqueNumber,
+};
+use std::collections::BTreeMap;
+
+/// Plans the ordering of a model selection by the engine, if any of its order by elements are
+/// fields of remote relationships. The fields needed to sort the rows are added to `ndc_fields`.
+pub(crate) fn plan_remote_order_by(
+    model_target: &ModelTarget,
+    source_selection: &IndexMap<Alias, ObjectSubSelection>,
+    metadata: &Metadata,
+    session: &Session,
+    request_headers: &reqwest::header::HeaderMap,
+    model_source: &metadata_resolve::ModelSource,
+    model_object_type: &OutputObjectTypeView,
+    ndc_fields: &mut IndexMap<NdcFieldAlias, Field>,
+    relationships: &mut BTreeMap<plan_types::NdcRelationshipName, plan_types::Relationship>,
+    remote_predicates: &mut PredicateQueryTrees,
+    unique_number: &mut UniqueNumber,
+) -> Result<Option<RemoteOrderBy>, PlanError> {
+    let remote_elements = model_target
+        .order_by
+        .iter()
+        .map(|element| {
+            is_remote_relationship_element(metadata, model_object_type, model_source, element)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if !remote_elements.iter().any(|is_remote| *is_remote) {
+        return Ok(None);
+    }
+
+    // the data connector can order the rows by the elements before the first remote relationship
+    let connector_ordered_elements = remote_elements
+        .iter()
+        .take_while(|is_remote| !**is_remote)
+        .count();
+
+    let mut elements = Vec::new();
+    let mut remote_join_executions = JoinLocations::new();
+
+    for (index, (element, is_remote)) in model_target
+        .order_by
+        .iter()
+        .zip(remote_elements)
+        .enumerate()
+    {
+        let order_direction = match element.direction {
+            open_dds::models::OrderByDirection::Asc => plan_types::OrderByDirection::Asc,
+            open_dds::models::OrderByDirection::Desc => plan_types::OrderByDirection::Desc,
+        };
+        let alias = make_order_by_alias(index)?;
+
+        let target = match &element.operand {
+            Operand::Field(field_operand) => {
+                let mut field_path = vec![NdcFieldAlias::from(alias.as_str())];
+                let field_selection = field_operand_to_selection(field_operand, &mut field_path)?;
+                let sort_fields = field_selection::resolve_field_selection(
+                    metadata,
+                    session,
+                    request_headers,
+                    model_object_type,
+                    &model_source.type_mappings,
+                    &model_source.data_connector,
+                    &IndexMap::from_iter([(alias, ObjectSubSelection::Field(field_selection))]),
+                    metadata_resolve::FieldNestedness::NotNested,
+                    relationships,
+                    &mut JoinLocations::new(),
+                    remote_predicates,
+                    unique_number,
+                )?;
+                ndc_fields.extend(sort_fields);
+                RemoteOrderByTarget::Field { field_path }
+            }
+            Operand::Relationship(relationship_operand) if !is_remote => {
+                return Err(OrderByError::RemoteRelationshipNotSupported(format!(
+                    "The local relationship {} cannot be ordered by alongside remote relationships",
+                    relationship_operand.target.relationship_name
+                ))
+                .into_plan_error())
+            }
+            Operand::Relationship(relationship_operand) => {
+                let join_alias = NdcFieldAlias::from(alias.as_str());
+                let (remote_join, sub_join_locations, field_path) =
+                    plan_remote_relationship_sort_key(
+                        relationship_operand,
+                        source_selection,
+                        metadata,
+                        session,
+                        request_headers,
+                        model_source,
+                        model_object_type,
+                        ndc_fields,
+                        remote_predicates,
+                        unique_number,
+                    )?;
+                remote_join_executions.locations.insert(
+                    join_alias.to_string(),
+                    Location {
+                        join_node: JoinNode::Remote(remote_join),
+                        rest: sub_join_locations,
+                    },
+                );
+                RemoteOrderByTarget::RemoteRelationship {
+                    join_alias,
+                    field_path,
+                }
+            }
+            Operand::RelationshipAggregate(relationship_operand) => {
+                return Err(OrderByError::RelationshipAggregateNotSupported(
+                    relationship_operand.target.relationship_name.clone(),
+                )
+                .into_plan_error())
+            }
+        };
+
+        elements.push(RemoteOrderByElement {
+            order_direction,
+            target,
+        });
+    }
+
+    let limit = model_target
+        .limit
+        .map(u32::try_from)
+        .transpose()
+        .map_err(|_| PlanError::Internal("limit out of range".into()))?;
+
+    let offset = model_target
+        .offset
+        .map(u32::try_from)
+        .transpose()
+        .map_err(|_| PlanError::Internal("offset out of range".into()))?;
+
+    let row_limit = model_source.remote_ordering_row_limit;
+
+    // Rows past the end of the page only need fetching to be sorted if they are equal to the last
+    // row of the page in the elements that the data connector orders by, so we fetch one row past
+    // the page to find out. Otherwise every row is needed, and we fetch one more than can be
+    // sorted to find out if there are too many.
+    let page_end = match limit {
+        Some(limit) if connector_ordered_elements > 0 => {
+            Some(offset.unwrap_or(0).saturating_add(limit))
+        }
+        _ => None,
+    };
+    let fetch_limit = page_end
+        .filter(|page_end| *page_end < row_limit)
+        .unwrap_or(row_limit)
+        .saturating_add(1);
+
+    Ok(Some(RemoteOrderBy {
+        elements,
+        connector_ordered_elements,
+        remote_join_executions,
+        limit,
+        offset,
+        row_limit,
+        fetch_limit,
+    }))
+}
+
+// Only relationships at the top level of the ordered type can be remote, as the metadata rejects
+// remote relationships on nested fields
+fn is_remote_relationship_element(
+    metadata: &Metadata,
+    model_object_type: &OutputObjectTypeView,
+    model_source: &metadata_resolve::ModelSource,
+    element: &OrderByElement,
+) -> Result<bool, PlanError> {
+    let Operand::Relationship(relationship_operand) = &element.operand else {
+        return Ok(false);
+    };
+    let relationship_name = &relationship_operand.target.relationship_name;
+    let relationship = model_object_type
+        .relationship_fields
+        .get(relationship_name)
+        .ok_or_else(|| {
+            OrderByError::Internal(format!(
+                "can't find relationship {relationship_name} in type: {}",
+                model_object_type.object_type_name
+            ))
+            .into_plan_error()
+        })?;
+    match &relationship.target {
+        RelationshipTarget::Model(model_relationship_target) => {
+            let target_model_source =
+                get_target_model_source(metadata, &model_relationship_target.model_name)?;
+            Ok(target_model_source.data_connector.name != model_source.data_connector.name)
+        }
+        RelationshipTarget::Command(_) => Ok(false),
+    }
+}
+
+fn get_target_model_source<'a>(
+    metadata: &'a Metadata,
+    target_model_name: &metadata_resolve::Qualified<open_dds::models::ModelName>,
+) -> Result<&'a metadata_resolve::ModelSource, PlanError> {
+    metadata
+        .models
+        .get(target_model_name)
+        .ok_or_else(|| {
+            OrderByError::Internal(format!("model {target_model_name} not found in metadata"))
+                .into_plan_error()
+        })?
+        .model
+        .source
+        .as_deref()
+        .ok_or_else(|| {
+            OrderByError::Internal(format!("model {target_model_name} has no source"))
+                .into_plan_error()
+        })
+}
+
+/// Plans a remote join that fetches the field of a remote relationship to order by, returning the
+/// remote joins nested within it and the path to the field within the object joined onto each row
+fn plan_remote_relationship_sort_key(
+    relationship_operand: &RelationshipOperand,
+    source_selection: &IndexMap<Alias, ObjectSubSelection>,
+    metadata: &Metadata,
+    session: &Session,
+    request_headers: &reqwest::header::HeaderMap,
+    model_source: &metadata_resolve::ModelSource,
+    model_object_type: &OutputObjectTypeView,
+    ndc_fields: &mut IndexMap<NdcFieldAlias, Field>,
+    remote_predicates: &mut PredicateQueryTrees,
+    unique_number: &mut UniqueNumber,
+) -> Result<(RemoteJoin, JoinLocations, Vec<NdcFieldAlias>), PlanError> {
+    let relationship_name = &relationship_operand.target.relationship_name;
+    let model_relationship_target = match model_object_type
+        .relationship_fields
+        .get(relationship_name)
+        .map(|relationship| &relationship.target)
+    {
+        Some(RelationshipTarget::Model(model_relationship_target)) => model_relationship_target,
+        _ => {
+            return Err(OrderByError::Internal(format!(
+                "relationship {relationship_name} is not a model relationship"
+            ))
+            .into_plan_error())
+        }
+    };
+    let target_model_name = &model_relationship_target.model_name;
+    let target_model_source = get_target_model_source(metadata, target_model_name)?;
+
+    // Only fields can be ordered by once the rows have been joined across data connectors
+    let field_operand = match relationship_operand.nested.as_deref() {
+        Some(Operand::Field(field_operand)) => field_operand,
+        Some(_) => {
+            return Err(OrderByError::RemoteRelationshipNotSupported(format!(
+                "Only fields of the remote relationship {relationship_name} can be ordered by"
+            ))
+            .into_plan_error())
+        }
+        None => {
+            return Err(OrderByError::Internal(
+                "Relationship operand must have a nested field".to_string(),
+            )
+            .into_plan_error())
+        }
+    };
+
+    let alias = make_order_by_alias(0)?;
+    let mut field_path = vec![NdcFieldAlias::from(alias.as_str())];
+    let field_selection = field_operand_to_selection(field_operand, &mut field_path)?;
+
+    let target_model_selection = ModelSelection {
+        target: ModelTarget {
+            subgraph: target_model_name.subgraph.clone(),
+            model_name: target_model_name.name.clone(),
+            arguments: relationship_operand.target.arguments.clone(),
+            filter: relationship_operand.target.filter.clone(),
+            order_by: vec![],
+            limit: None,
+            offset: None,
+        },
+        selection: IndexMap::from_iter([(alias, ObjectSubSelection::Field(field_selection))]),
+    };
+
+    let mut query_execution_tree = super::model::from_model_selection(
+        &target_model_selection,
+        metadata,
+        session,
+        request_headers,
+        unique_number,
+    )?;
+    apply_model_relationship_value_mappings(
+        &mut query_execution_tree,
+        relationship_name,
+        model_object_type.object_type_name,
+        target_model_name,
+        target_model_source,
+        &model_relationship_target.value_mappings,
+        &metadata.object_types,
+        &session.variables,
+        unique_number,
+    )?;
+
+    let QueryExecutionTree {
+        query_execution_plan: mut query_execution,
+        remote_join_executions: sub_join_locations,
+        remote_predicates: new_remote_predicates,
+        remote_order_by: _,
+    } = query_execution_tree;
+
+    let ModelRemoteRelationshipParts {
+        join_mapping,
+        object_type_field_mappings,
+        mut relationship_join_filter_expressions,
+        arguments: new_arguments,
+    } = calculate_remote_relationship_fields_for_model_target(
+        session,
+        metadata,
+        model_object_type,
+        relationship_name,
+        target_model_name,
+        &model_relationship_target.mappings,
+        &model_source.type_mappings,
+        &target_model_source.type_mappings,
+        &target_model_source.argument_mappings,
+        source_selection,
+        ndc_fields,
+    )
+    .map_err(PlanError::Relationship)?;
+
+    remote_predicates.0.extend(new_remote_predicates.0);
+
+    // combine the filter of the target model with the comparisons to the join variables
+    if let Some(ref query_filter) = query_execution.query_node.predicate {
+        relationship_join_filter_expressions.push_front(query_filter.clone());
+    }
+    if !relationship_join_filter_expressions.is_empty() {
+        query_execution.query_node.predicate =
+            ResolvedFilterExpression::mk_and(relationship_join_filter_expressions.into())
+                .remove_always_true_expression();
+    }
+
+    query_execution.arguments.extend(new_arguments);
+
+    let remote_join = RemoteJoin {
+        target_ndc_execution: query_execution,
+        target_data_connector: target_model_source.data_connector.clone(),
+        join_mapping,
+        object_type_field_mappings,
+        process_response_as: ProcessResponseAs::Object { is_nullable: true },
+        remote_join_type: RemoteJoinType::ToModel,
+    };
+
+    Ok((remote_join, sub_join_locations, field_path))
+}
+
+/// Selects the field targeted by an order by operand, descending through nested fields. The
+/// aliases of the nested fields are pushed onto `field_path`.
+fn field_operand_to_selection(
+    field_operand: &ObjectFieldOperand,
+    field_path: &mut Vec<NdcFieldAlias>,
+) -> Result<ObjectFieldSelection, PlanError> {
+    let selection = match field_operand.nested.as_deref() {
+        None => None,
+        Some(Operand::Field(nested_field_operand)) => {
+            let nested_field_name = &nested_field_operand.target.field_name;
+            field_path.push(NdcFieldAlias::from(nested_field_name.as_str()));
+            let nested_selection = field_operand_to_selection(nested_field_operand, field_path)?;
+            Some(IndexMap::from_iter([(
+                Alias::from(Identifier::from(nested_field_name.clone())),
+                ObjectSubSelection::Field(nested_selection),
+            )]))
+        }
+        Some(_) => {
+            return Err(OrderByError::RemoteRelationshipNotSupported(format!(
+                "Relationships in the nested field {} cannot be ordered by alongside remote relationships",
+                field_operand.target.field_name
+            ))
+            .into_plan_error())
+        }
+    };
+    Ok(ObjectFieldSelection {
+        target: (*field_operand.target).clone(),
+        selection,
+    })
+}
+
+fn make_order_by_alias(index: usize) -> Result<Alias, PlanError> {
+    Identifier::new(format!("__hasura_order_by__{index}"))
+        .map(Alias::from)
+        .map_err(|e| PlanError::Internal(format!("invalid order by alias: {e}")))
+}
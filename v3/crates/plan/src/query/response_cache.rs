@@ -0,0 +1,69 @@
+//! How the responses of data connectors to the queries of a plan are cached
+
+use std::collections::BTreeMap;
+
+use hasura_authn_core::Session;
+use metadata_resolve::{Metadata, ResponseCachePolicy};
+use open_dds::commands::DataConnectorCommand;
+use open_dds::data_connector::CollectionName;
+use plan_types::{NdcRelationshipName, Relationship, ResponseCache};
+
+/// How the responses to a query of a model or command are cached. The response to a query that
+/// reads other collections through local relationships contains their data too, so it is only
+/// cached if the models and commands of those collections also cache their responses: for the
+/// shortest of their TTLs, and per session if any of them is.
+pub fn plan_response_cache(
+    metadata: &Metadata,
+    session: &Session,
+    data_connector: &metadata_resolve::DataConnectorLink,
+    response_cache: Option<&ResponseCachePolicy>,
+    collection_relationships: &BTreeMap<NdcRelationshipName, Relationship>,
+) -> Option<ResponseCache> {
+    let mut response_cache = ResponseCache::new(response_cache?, session);
+    for relationship in collection_relationships.values() {
+        for policy in collection_response_cache_policies(
+            metadata,
+            data_connector,
+            &relationship.target_collection,
+        )? {
+            response_cache = response_cache.restrict(policy, session);
+        }
+    }
+    Some(response_cache)
+}
+
+// The caching policies of the models and commands backed by the collection of the data
+// connector, or `None` if the collection is not known or any of them does not cache responses
+fn collection_response_cache_policies<'a>(
+    metadata: &'a Metadata,
+    data_connector: &metadata_resolve::DataConnectorLink,
+    collection: &CollectionName,
+) -> Option<Vec<&'a ResponseCachePolicy>> {
+    let model_sources = metadata
+        .models
+        .values()
+        .filter_map(|model| model.model.source.as_deref())
+        .filter(|model_source| {
+            model_source.data_connector.name == data_connector.name
+                && model_source.collection == *collection
+        })
+        .map(|model_source| model_source.response_cache.as_ref());
+    let command_sources = metadata
+        .commands
+        .values()
+        .filter_map(|command| command.command.source.as_deref())
+        .filter(|command_source| {
+            command_source.data_connector.name == data_connector.name
+                && matches!(
+                    &command_source.source,
+                    DataConnectorCommand::Function(function_name)
+                        if function_name.as_str() == collection.as_str()
+                )
+        })
+        .map(|command_source| command_source.response_cache.as_ref());
+
+    let policies = model_sources
+        .chain(command_sources)
+        .collect::<Option<Vec<_>>>()?;
+    (!policies.is_empty()).then_some(policies)
+}
@@ -5,7 +5,7 @@ use metadata_resolve::Qualified;
 use open_dds::{
     arguments::ArgumentName,
     commands::CommandName,
-    data_connector::DataConnectorColumnName,
+    data_connector::{DataConnectorColumnName, DataConnectorName},
     models::ModelName,
     relationships::RelationshipName,
     types::{CustomTypeName, FieldName},
@@ -21,6 +21,8 @@ pub enum PlanError {
     #[error("{0}")]
     OrderBy(#[from] OrderByError),
     #[error("{0}")]
+    GroupBy(#[from] GroupByError),
+    #[error("{0}")]
     ArgumentPresetExecutionError(#[from] ArgumentPresetExecutionError),
     #[error("{0}")]
     InternalError(InternalError),
@@ -38,6 +40,7 @@ impl TraceableError for PlanError {
             Self::Permission(permission_error) => permission_error.visibility(),
             Self::Relationship(relationship_error) => relationship_error.visibility(),
             Self::OrderBy(order_by_error) => order_by_error.visibility(),
+            Self::GroupBy(group_by_error) => group_by_error.visibility(),
             Self::External(_) => ErrorVisibility::User,
             Self::Internal(_) => ErrorVisibility::Internal,
         }
@@ -229,3 +232,44 @@ impl TraceableError for OrderByError {
         }
     }
 }
+
+#[derive(Debug, thiserror::Error)]
+pub enum GroupByError {
+    #[error("Data connector {data_connector_name} does not support filtering groups")]
+    FilteringNotSupported {
+        data_connector_name: Qualified<DataConnectorName>,
+    },
+    #[error("Data connector {data_connector_name} does not support ordering groups")]
+    OrderingNotSupported {
+        data_connector_name: Qualified<DataConnectorName>,
+    },
+    #[error("Data connector {data_connector_name} does not support grouping by fields across relationships")]
+    RelationshipDimensionsNotSupported {
+        data_connector_name: Qualified<DataConnectorName>,
+    },
+    #[error("Relationship {0} is an array relationship, but groups can only be formed across object relationships")]
+    ArrayRelationshipDimensionNotSupported(RelationshipName),
+    #[error("{0}")]
+    RemoteRelationshipNotSupported(String),
+    #[error("Unsupported dimension: {0}")]
+    UnsupportedDimension(String),
+    #[error("Groups can only be ordered by one of their dimensions, but {0} is not a dimension")]
+    DimensionNotFound(String),
+    #[error("Groups can only be filtered by comparing aggregates to literal values")]
+    NonLiteralComparisonArgument,
+    #[error("Data connector {data_connector_name} does not declare the comparison operators of the result of an aggregate that groups are filtered by")]
+    AggregateNotComparable {
+        data_connector_name: Qualified<DataConnectorName>,
+    },
+    #[error("Data connector {data_connector_name} does not support the operator {operator_name} on the result of an aggregate that groups are filtered by")]
+    ComparisonOperatorNotSupported {
+        data_connector_name: Qualified<DataConnectorName>,
+        operator_name: String,
+    },
+}
+
+impl TraceableError for GroupByError {
+    fn visibility(&self) -> ErrorVisibility {
+        ErrorVisibility::User
+    }
+}